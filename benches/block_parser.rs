@@ -0,0 +1,91 @@
+//! Block parser throughput on multi-megabyte inputs.
+//!
+//! Builds a document of at least `PANACHE_PARSE_BENCH_MB` megabytes (default
+//! 4) from block-heavy sections (nested block quotes, lists, definition lists,
+//! hard line breaks) plus `benches/documents/small.qmd`, then reports parse
+//! throughput in MB/s. Track it alongside `cargo bench --bench formatting`
+//! when changing how the parser emits tokens.
+
+use panache::parse;
+use std::env;
+use std::fs;
+use std::hint::black_box;
+use std::time::Instant;
+
+const SECTION: &str = "\
+# Section
+
+> Quoted text with *emphasis* and a [link](https://example.com).
+> > Nested quote line one,  
+> > hard break above.
+>
+> - item in a quote
+>   - nested item
+>     with continuation
+
+1. First
+2. Second
+
+   Loose paragraph in the second item.
+
+   ```r
+   x <- 1
+   ```
+
+Term
+:   Definition with `code`.
+
+    Second paragraph of the definition.
+
+Plain paragraph with trailing spaces for a hard break  
+and a second line.
+
+";
+
+fn build_document(min_bytes: usize) -> String {
+    let small = fs::read_to_string("benches/documents/small.qmd").unwrap_or_default();
+    let mut doc = String::with_capacity(min_bytes + SECTION.len() + small.len());
+    while doc.len() < min_bytes {
+        doc.push_str(SECTION);
+        doc.push_str(&small);
+        doc.push('\n');
+    }
+    doc
+}
+
+fn main() {
+    let megabytes = env::var("PANACHE_PARSE_BENCH_MB")
+        .ok()
+        .and_then(|raw| raw.parse::<usize>().ok())
+        .unwrap_or(4);
+    let iterations = env::var("PANACHE_PARSE_BENCH_ITERATIONS")
+        .ok()
+        .and_then(|raw| raw.parse::<usize>().ok())
+        .unwrap_or(5);
+    let input = build_document(megabytes * 1024 * 1024);
+    let config = panache::Config::default();
+
+    println!("Block parser throughput");
+    println!("=======================\n");
+    println!(
+        "Document size: {} bytes, {} lines",
+        input.len(),
+        input.lines().count()
+    );
+
+    // Warmup
+    black_box(parse(&input, Some(config.clone())));
+
+    let start = Instant::now();
+    for _ in 0..iterations {
+        black_box(parse(black_box(&input), Some(config.clone())));
+    }
+    let elapsed = start.elapsed();
+    let avg = elapsed.as_secs_f64() / iterations as f64;
+    println!("\nParse: {:?} for {} iterations", elapsed, iterations);
+    println!("  Average: {:.2}ms per iteration", avg * 1000.0);
+    println!(
+        "  Throughput: {:.2} MB/s",
+        input.len() as f64 / (1024.0 * 1024.0) / avg
+    );
+}
@@ -0,0 +1,45 @@
+/*
+ * C interface to the panache formatter and linter.
+ *
+ * All strings are NUL-terminated UTF-8. Strings returned by these functions
+ * are owned by the caller and must be released with panache_free(); passing
+ * them to free() is undefined behavior.
+ *
+ * `config_json` is a configuration in JSON with the keys of panache.toml, for
+ * example {"flavor": "quarto", "format": {"line-width": 72}}. NULL or an
+ * empty string selects the defaults; discovered panache.toml files are never
+ * read.
+ *
+ * On failure the functions return NULL and, when `error` is not NULL, store a
+ * message in *error that must also be released with panache_free(). On
+ * success *error is set to NULL.
+ */
+
+#ifndef PANACHE_H
+#define PANACHE_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Format the document `input`. Returns the formatted document. */
+char *panache_format(const char *input, const char *config_json, char **error);
+
+/*
+ * Lint the document `input` with the built-in rules. Returns a JSON array of
+ * diagnostics, each an object with "code", "severity" ("error", "warning", or
+ * "info"), "message", the 1-indexed "line", "column", "end_line", and
+ * "end_column" and byte "start" and "end" of the flagged range, and "fix"
+ * (null, or an object with "message", "safe", and "edits", each edit having
+ * "start", "end", and "replacement").
+ */
+char *panache_lint_json(const char *input, const char *config_json, char **error);
+
+/* Release a string returned by this library. NULL is ignored. */
+void panache_free(char *string);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* PANACHE_H */
@@ -0,0 +1,201 @@
+//! C ABI for embedding panache in editors and tools written in other
+//! languages. The stable interface is `include/panache.h`; see there for
+//! ownership rules.
+
+use std::ffi::{CStr, CString, c_char};
+use std::ptr;
+
+/// Format `input` with the configuration `config_json`.
+///
+/// # Safety
+///
+/// `input` must be a valid NUL-terminated string, `config_json` NULL or a
+/// valid NUL-terminated string, and `error` NULL or valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn panache_format(
+    input: *const c_char,
+    config_json: *const c_char,
+    error: *mut *mut c_char,
+) -> *mut c_char {
+    // SAFETY: forwarded from the caller's contract.
+    unsafe {
+        respond(error, || {
+            let input = read_str(input, "input")?;
+            let config = read_config(config_json)?;
+            panache::try_format(input, Some(config), None).map_err(|err| err.to_string())
+        })
+    }
+}
+
+/// Lint `input` with the built-in rules and return the diagnostics as a JSON
+/// array.
+///
+/// # Safety
+///
+/// As for [`panache_format`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn panache_lint_json(
+    input: *const c_char,
+    config_json: *const c_char,
+    error: *mut *mut c_char,
+) -> *mut c_char {
+    // SAFETY: forwarded from the caller's contract.
+    unsafe {
+        respond(error, || {
+            let input = read_str(input, "input")?;
+            let config = read_config(config_json)?;
+            let tree =
+                panache::try_parse(input, Some(config.clone())).map_err(|err| err.to_string())?;
+            let diagnostics: Vec<serde_json::Value> = panache::linter::lint(&tree, input, &config)
+                .iter()
+                .map(|diagnostic| diagnostic.to_json())
+                .collect();
+            Ok(serde_json::Value::from(diagnostics).to_string())
+        })
+    }
+}
+
+/// Release a string returned by this library.
+///
+/// # Safety
+///
+/// `string` must be NULL or a pointer returned by this library that has not
+/// been freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn panache_free(string: *mut c_char) {
+    if !string.is_null() {
+        // SAFETY: the pointer came from `CString::into_raw` in `into_c_string`.
+        drop(unsafe { CString::from_raw(string) });
+    }
+}
+
+/// Run `f` and hand its result to the caller: the string on success, NULL and
+/// a message in `*error` on failure. Panics are reported as failures rather
+/// than unwinding into foreign code.
+unsafe fn respond(
+    error: *mut *mut c_char,
+    f: impl FnOnce() -> Result<String, String>,
+) -> *mut c_char {
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(f))
+        .unwrap_or_else(|_| Err("internal error".to_string()));
+    let (output, message) = match result {
+        Ok(output) => (into_c_string(output), ptr::null_mut()),
+        Err(message) => (ptr::null_mut(), into_c_string(message)),
+    };
+    if error.is_null() {
+        // SAFETY: `message` is NULL or came from `into_c_string`.
+        unsafe { panache_free(message) };
+    } else {
+        // SAFETY: the caller guarantees `error` is valid for writes.
+        unsafe { *error = message };
+    }
+    output
+}
+
+unsafe fn read_str<'a>(string: *const c_char, name: &str) -> Result<&'a str, String> {
+    if string.is_null() {
+        return Err(format!("{name} is NULL"));
+    }
+    // SAFETY: the caller guarantees a valid NUL-terminated string.
+    unsafe { CStr::from_ptr(string) }
+        .to_str()
+        .map_err(|err| format!("{name} is not valid UTF-8: {err}"))
+}
+
+unsafe fn read_config(config_json: *const c_char) -> Result<panache::Config, String> {
+    if config_json.is_null() {
+        return Ok(panache::Config::default());
+    }
+    // SAFETY: forwarded from the caller's contract.
+    match unsafe { read_str(config_json, "config") }?.trim() {
+        "" => Ok(panache::Config::default()),
+        json => serde_json::from_str(json).map_err(|err| format!("Invalid config: {err}")),
+    }
+}
+
+/// Interior NULs cannot cross the C boundary, so they are replaced.
+fn into_c_string(string: String) -> *mut c_char {
+    CString::new(string)
+        .unwrap_or_else(|err| {
+            let bytes = err.into_vec();
+            CString::new(String::from_utf8_lossy(&bytes).replace('\0', "\u{fffd}"))
+                .expect("NULs were replaced")
+        })
+        .into_raw()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(
+        f: unsafe extern "C" fn(*const c_char, *const c_char, *mut *mut c_char) -> *mut c_char,
+        input: &str,
+        config: Option<&str>,
+    ) -> Result<String, String> {
+        let input = CString::new(input).unwrap();
+        let config = config.map(|config| CString::new(config).unwrap());
+        let config_ptr = config
+            .as_ref()
+            .map_or(ptr::null(), |config| config.as_ptr());
+        let mut error = ptr::null_mut();
+        unsafe {
+            let output = f(input.as_ptr(), config_ptr, &mut error);
+            let take = |string: *mut c_char| {
+                let owned = CStr::from_ptr(string).to_str().unwrap().to_string();
+                panache_free(string);
+                owned
+            };
+            if output.is_null() {
+                Err(take(error))
+            } else {
+                assert!(error.is_null());
+                Ok(take(output))
+            }
+        }
+    }
+
+    #[test]
+    fn formats_with_default_and_json_config() {
+        assert_eq!(
+            call(panache_format, "#  Title\n", None).unwrap(),
+            "# Title\n"
+        );
+        let wrapped = call(
+            panache_format,
+            "one two three four\n",
+            Some(r#"{"format": {"line-width": 10, "wrap": "reflow"}}"#),
+        )
+        .unwrap();
+        assert_eq!(wrapped, "one two\nthree four\n");
+    }
+
+    #[test]
+    fn invalid_config_is_reported_through_error() {
+        let error = call(panache_format, "text\n", Some("{")).unwrap_err();
+        assert!(error.starts_with("Invalid config"), "{error}");
+    }
+
+    #[test]
+    fn lint_returns_a_json_array() {
+        let json = call(panache_lint_json, "# A\n\n### B\n", None).unwrap();
+        let diagnostics: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert!(
+            diagnostics
+                .as_array()
+                .is_some_and(|array| !array.is_empty())
+        );
+    }
+
+    #[test]
+    fn null_arguments_are_errors_and_free_accepts_null() {
+        let mut error = ptr::null_mut();
+        unsafe {
+            assert!(panache_format(ptr::null(), ptr::null(), &mut error).is_null());
+            assert!(!error.is_null());
+            panache_free(error);
+            assert!(panache_format(ptr::null(), ptr::null(), ptr::null_mut()).is_null());
+            panache_free(ptr::null_mut());
+        }
+    }
+}
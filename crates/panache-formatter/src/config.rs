@@ -1,4 +1,6 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::syntax::SyntaxKind;
 
 pub use panache_parser::Dialect;
 pub use panache_parser::Extensions;
@@ -75,6 +77,306 @@ pub enum HorizontalRuleStyle {
     Compact,
 }
 
+/// Delimiter for emphasis (`emphasis-marker`) or strong emphasis
+/// (`strong-marker`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum EmphasisMarker {
+    /// `*emphasis*` and `**strong**`.
+    #[default]
+    Asterisk,
+    /// `_emphasis_` and `__strong__`, except inside words, where only
+    /// asterisks work.
+    Underscore,
+    /// Keep the delimiter each span was written with.
+    Preserve,
+}
+
+/// How backslash escapes in text are written (`escaping`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum Escaping {
+    /// Keep every escape as written.
+    #[default]
+    Preserve,
+    /// Drop escapes on punctuation that reads the same without them.
+    Minimal,
+}
+
+/// Inline construct that reflow may break a line inside (`allow-breaks-in`).
+/// Inline code, math, citations, and URLs are never broken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum BreakableInline {
+    /// The text of a link, `[...]`.
+    LinkText,
+    /// The alt text of an image, `![...]`.
+    ImageAlt,
+    /// Emphasis and strong emphasis.
+    Emphasis,
+    /// Bracketed spans, `[...]{.class}`.
+    Span,
+}
+
+impl BreakableInline {
+    /// Every construct: the default, under which only the atomic inlines
+    /// (code, math, citations, URLs) stay on one line.
+    pub const ALL: [Self; 4] = [Self::LinkText, Self::ImageAlt, Self::Emphasis, Self::Span];
+}
+
+/// Block that always gets a blank line before and after it
+/// (`blank-lines-around`), at the document level and inside fenced divs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum BlankLinesAround {
+    /// ATX and setext headings.
+    Headings,
+    /// Fenced and indented code blocks.
+    Code,
+    /// Bullet, ordered, and task lists.
+    Lists,
+}
+
+impl BlankLinesAround {
+    /// The construct a block of `kind` belongs to, if any.
+    pub fn for_kind(kind: SyntaxKind) -> Option<Self> {
+        match kind {
+            SyntaxKind::HEADING => Some(Self::Headings),
+            SyntaxKind::CODE_BLOCK => Some(Self::Code),
+            SyntaxKind::LIST => Some(Self::Lists),
+            _ => None,
+        }
+    }
+}
+
+/// How inline link and image destinations are written (`link-destinations`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum LinkDestinations {
+    /// Keep destinations as written.
+    #[default]
+    Preserve,
+    /// Wrap destinations with spaces or unbalanced parentheses in `<...>`,
+    /// and drop brackets that are not needed.
+    AngleBrackets,
+    /// Percent-encode spaces and other characters a bare destination cannot
+    /// hold, and drop angle brackets.
+    PercentEncode,
+}
+
+/// How bare URLs in prose are written under `autolink_bare_uris`
+/// (`bare-urls`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum BareUrls {
+    /// Keep bare URLs as written.
+    #[default]
+    Preserve,
+    /// Wrap them in angle brackets: `<https://example.com>`.
+    Autolink,
+    /// Write them as inline links: `[https://example.com](https://example.com)`.
+    Link,
+}
+
+/// Section numbers written in heading text (`headings.numbering`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum HeadingNumbering {
+    /// Leave heading text alone.
+    #[default]
+    Preserve,
+    /// Recompute the numbers of numbered headings from their order and level.
+    Renumber,
+    /// Remove section numbers from heading text.
+    Strip,
+}
+
+/// Heading levels listed in generated tables of contents (`toc`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, rename_all = "kebab-case"))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct TocStyle {
+    /// Shallowest heading level listed.
+    pub min_level: usize,
+    /// Deepest heading level listed.
+    pub max_level: usize,
+}
+
+impl Default for TocStyle {
+    fn default() -> Self {
+        Self {
+            min_level: 2,
+            max_level: 3,
+        }
+    }
+}
+
+/// Number of colons on fenced div fences (`divs.fence-length`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum DivFenceLength {
+    /// Three colons at the top level and two more per level of nesting.
+    #[default]
+    DepthBased,
+    /// Keep the colon count of each opening fence as written.
+    Preserve,
+    /// The same number of colons (at least three) on every fence.
+    Fixed(usize),
+}
+
+/// Character that opens a definition (`definition-lists.marker`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum DefinitionMarker {
+    /// `:   definition`
+    #[default]
+    #[cfg_attr(feature = "serde", serde(rename = ":"))]
+    Colon,
+    /// `~   definition`
+    #[cfg_attr(feature = "serde", serde(rename = "~"))]
+    Tilde,
+}
+
+impl DefinitionMarker {
+    pub fn as_char(self) -> char {
+        match self {
+            Self::Colon => ':',
+            Self::Tilde => '~',
+        }
+    }
+}
+
+/// Layout of definition lists (`definition-lists`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, rename_all = "kebab-case"))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DefinitionListStyle {
+    /// Marker that opens each definition.
+    pub marker: DefinitionMarker,
+    /// Spaces between the marker and the first line of the definition (1--3).
+    /// Continuation lines and further blocks stay indented four columns,
+    /// which Pandoc requires.
+    pub spaces: usize,
+    /// `Some(true)` removes the blank line between a term and its
+    /// definitions, `Some(false)` always writes one, and `None` keeps each
+    /// item as written. Items whose definitions span several blocks are
+    /// always loose.
+    pub compact: Option<bool>,
+}
+
+impl Default for DefinitionListStyle {
+    fn default() -> Self {
+        Self {
+            marker: DefinitionMarker::Colon,
+            spaces: 3,
+            compact: None,
+        }
+    }
+}
+
+impl DefinitionListStyle {
+    /// The marker and the spaces after it, e.g. `":   "`.
+    pub fn prefix(&self) -> String {
+        format!(
+            "{}{}",
+            self.marker.as_char(),
+            " ".repeat(self.spaces.max(1))
+        )
+    }
+}
+
+/// Layout of block quotes (`blockquotes`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, rename_all = "kebab-case"))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct BlockquoteStyle {
+    /// Spaces after each `>` (0 or 1). Lines whose content starts with
+    /// whitespace always get one, since a reader drops the first space after
+    /// the last `>`.
+    pub marker_spacing: usize,
+    /// `true` gives lazy continuation lines explicit `>` markers; `false`
+    /// keeps them lazy. Only `wrap = "preserve"` keeps source lines, so other
+    /// wrap modes always write markers.
+    pub collapse_lazy: bool,
+}
+
+impl Default for BlockquoteStyle {
+    fn default() -> Self {
+        Self {
+            marker_spacing: 1,
+            collapse_lazy: true,
+        }
+    }
+}
+
+impl BlockquoteStyle {
+    /// One quote marker and the spaces after it, e.g. `"> "`.
+    pub fn marker(&self) -> String {
+        format!(">{}", " ".repeat(self.marker_spacing.min(1)))
+    }
+
+    /// Markers for a quote nested `depth` deep, e.g. `"> > "`.
+    pub fn prefix(&self, depth: usize) -> String {
+        self.marker().repeat(depth)
+    }
+}
+
+/// Where a code block's language goes and how its attributes are ordered
+/// (`code-blocks.attribute-style`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum AttributeStyle {
+    /// Language outside the braces: ```` ```python {.numberLines} ````.
+    #[default]
+    Shortcut,
+    /// Language as the first class inside the braces:
+    /// ```` ```{.python .numberLines} ````.
+    Explicit,
+    /// Inside the braces in Pandoc's order: identifier, classes, then
+    /// key-value pairs. A lone language stays in shortcut form.
+    Canonical,
+    /// Info strings as written.
+    Preserve,
+}
+
+/// Info strings of display code blocks (`code-blocks`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, rename_all = "kebab-case"))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct CodeBlockStyle {
+    /// Where the language goes and how attributes are ordered. Only the
+    /// Pandoc dialect has attribute braces; elsewhere `explicit` and
+    /// `canonical` behave like `shortcut`.
+    pub attribute_style: AttributeStyle,
+    /// Lowercase the language (`Python` becomes `python`). Executable chunk
+    /// engines and raw formats are left alone, and so is everything under
+    /// `attribute-style = "preserve"`.
+    pub lowercase_language: bool,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
@@ -85,6 +387,19 @@ pub enum LineEnding {
     Crlf,
 }
 
+/// Whether formatted output ends with a newline (`final-newline`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum FinalNewline {
+    /// End every document with exactly one newline.
+    #[default]
+    Ensure,
+    /// End with one newline only if the input ended with one.
+    Preserve,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
@@ -94,6 +409,8 @@ pub enum BlankLines {
     Preserve,
     /// Collapse multiple consecutive blank lines to a single blank line
     Collapse,
+    /// Keep up to this many consecutive blank lines, collapsing longer runs
+    Max(usize),
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -172,6 +489,15 @@ macro_rules! known_formatter_extensions {
                     _ => false,
                 }
             }
+
+            /// Value of the named formatter extension, or `None` for an
+            /// unknown name.
+            pub fn get_by_name(&self, name: &str) -> Option<bool> {
+                match name.to_ascii_lowercase().as_str() {
+                    $( $kebab => Some(self.$field), )*
+                    _ => None,
+                }
+            }
         }
     };
 }
@@ -193,6 +519,12 @@ pub struct Config {
     pub parser_extensions: ParserExtensions,
     pub formatter_extensions: FormatterExtensions,
     pub line_ending: Option<LineEnding>,
+    /// Whether the output always ends with a newline (default) or only when
+    /// the input did.
+    pub final_newline: FinalNewline,
+    /// Strip trailing spaces and tabs from every line, verbatim blocks
+    /// included, keeping only those that form a hard line break. Default false.
+    pub trim_trailing_whitespace: bool,
     pub line_width: usize,
     pub math_indent: usize,
     pub math_delimiter_style: MathDelimiterStyle,
@@ -201,13 +533,60 @@ pub struct Config {
     /// since Pandoc only recognizes a grid table whose border starts at column
     /// 0. Nested tables honor their container indent instead.
     pub table_indent: usize,
+    /// Width budget for tables: pipe tables that would be wider are written
+    /// without column padding, and captions wrap to it. `None` leaves pipe
+    /// tables aligned and wraps captions at `line_width`.
+    pub table_max_width: Option<usize>,
+    /// Width budget for hashpipe (`#|`) option comments in executable chunks.
+    /// `None` uses `line_width`.
+    pub code_comment_width: Option<usize>,
     pub tab_stops: TabStopMode,
     pub tab_width: usize,
     pub wrap: Option<WrapMode>,
+    /// When reflowing, split words wider than the line and move link
+    /// destinations that cannot fit to reference definitions, so no line
+    /// overflows `line_width` because of a single token. Default false.
+    pub max_width_strict: bool,
     pub blank_lines: BlankLines,
+    /// Blocks that always get a blank line before and after them.
+    pub blank_lines_around: Vec<BlankLinesAround>,
+    /// Exact number of blank lines before each heading. `None` leaves them to
+    /// `blank_lines`.
+    pub blank_lines_before_heading: Option<usize>,
     /// How horizontal rules are rendered: expanded to the line width
     /// (default) or as a compact `---`.
     pub horizontal_rule_style: HorizontalRuleStyle,
+    /// Delimiter for emphasis: `*` (default), `_`, or as written.
+    pub emphasis_marker: EmphasisMarker,
+    /// Delimiter for strong emphasis: `**` (default), `__`, or as written.
+    pub strong_marker: EmphasisMarker,
+    /// Whether superfluous backslash escapes are kept (default) or dropped.
+    pub escaping: Escaping,
+    /// Inline constructs reflow may break a line inside. Anything not listed
+    /// is kept on one line.
+    pub allow_breaks_in: Vec<BreakableInline>,
+    /// Spaces after `>` and whether lazy continuation lines get markers.
+    pub blockquotes: BlockquoteStyle,
+    /// Language placement, attribute order, and language case of code block
+    /// info strings.
+    pub code_blocks: CodeBlockStyle,
+    /// Definition marker, the spaces after it, and compact vs. loose items.
+    pub definition_lists: DefinitionListStyle,
+    /// Colon count of fenced div fences: by nesting depth (default), as
+    /// written, or fixed.
+    pub div_fence_length: DivFenceLength,
+    /// Whether section numbers in heading text (`## 2.3 Methods`) are kept
+    /// (default), recomputed, or removed.
+    pub heading_numbering: HeadingNumbering,
+    /// Heading levels listed between `<!-- panache-toc start -->` and
+    /// `<!-- panache-toc end -->` markers.
+    pub toc: TocStyle,
+    /// Whether link destinations with spaces are kept (default), wrapped in
+    /// angle brackets, or percent-encoded.
+    pub link_destinations: LinkDestinations,
+    /// Whether bare URLs are kept (default) or turned into autolinks or inline
+    /// links. Only applies under the `autolink_bare_uris` extension.
+    pub bare_urls: BareUrls,
     /// Document-language fallback used by sentence wrapping when the document
     /// has no YAML `lang:`. Normalized lowercase code (e.g. `de`, `pt-br`).
     pub lang: Option<String>,
@@ -217,7 +596,7 @@ pub struct Config {
     /// candidate-normalized at resolution time.
     pub no_break_abbreviations: std::collections::BTreeMap<String, Vec<String>>,
     /// Language → Formatter(s) mapping (supports multiple formatters per language)
-    pub formatters: HashMap<String, Vec<FormatterConfig>>,
+    pub formatters: BTreeMap<String, Vec<FormatterConfig>>,
     /// Max parallel external tool invocations (formatters/linters) per document.
     pub external_max_parallel: usize,
     /// Compatibility target for ambiguous Pandoc behavior.
@@ -227,6 +606,10 @@ pub struct Config {
     /// alignment). Default false ⇒ math is emitted verbatim. Unstable surface —
     /// may change without a major release.
     pub experimental_format_math: bool,
+    /// Format each top-level block inside a panic boundary: a block whose
+    /// formatting panics is emitted verbatim (and reported) instead of
+    /// aborting the whole document. Default false.
+    pub fail_safe: bool,
 }
 
 impl Default for Config {
@@ -237,21 +620,41 @@ impl Default for Config {
             parser_extensions: ParserExtensions::for_flavor(flavor),
             formatter_extensions: FormatterExtensions::for_flavor(flavor),
             line_ending: Some(LineEnding::Auto),
+            final_newline: FinalNewline::default(),
+            trim_trailing_whitespace: false,
             line_width: 80,
             math_indent: 2,
             math_delimiter_style: MathDelimiterStyle::default(),
             table_indent: DEFAULT_TABLE_INDENT,
+            table_max_width: None,
+            code_comment_width: None,
             tab_stops: TabStopMode::Normalize,
             tab_width: 4,
             wrap: Some(WrapMode::Reflow),
+            max_width_strict: false,
             blank_lines: BlankLines::Collapse,
+            blank_lines_around: Vec::new(),
+            blank_lines_before_heading: None,
             horizontal_rule_style: HorizontalRuleStyle::default(),
+            emphasis_marker: EmphasisMarker::default(),
+            strong_marker: EmphasisMarker::default(),
+            escaping: Escaping::default(),
+            allow_breaks_in: BreakableInline::ALL.to_vec(),
+            blockquotes: BlockquoteStyle::default(),
+            code_blocks: CodeBlockStyle::default(),
+            definition_lists: DefinitionListStyle::default(),
+            div_fence_length: DivFenceLength::default(),
+            heading_numbering: HeadingNumbering::default(),
+            toc: TocStyle::default(),
+            link_destinations: LinkDestinations::default(),
+            bare_urls: BareUrls::default(),
             lang: None,
             no_break_abbreviations: std::collections::BTreeMap::new(),
-            formatters: HashMap::new(), // Opt-in: empty by default
+            formatters: BTreeMap::new(), // Opt-in: empty by default
             external_max_parallel: default_external_max_parallel(),
             parser: PandocCompat::default(),
             experimental_format_math: false,
+            fail_safe: false,
         }
     }
 }
@@ -262,6 +665,37 @@ impl Config {
         Dialect::for_flavor(self.flavor)
     }
 
+    /// Whether reflow may break a line inside `construct`.
+    pub fn allows_breaks_in(&self, construct: BreakableInline) -> bool {
+        self.allow_breaks_in.contains(&construct)
+    }
+
+    /// Most consecutive blank lines kept between blocks. Never below one, since
+    /// removing the last blank line can change the structure.
+    pub fn max_blank_lines(&self) -> usize {
+        match self.blank_lines {
+            BlankLines::Preserve => usize::MAX,
+            BlankLines::Collapse => 1,
+            BlankLines::Max(lines) => lines.max(1),
+        }
+    }
+
+    /// Whether blocks of `kind` must be set off by blank lines.
+    pub fn wants_blank_lines_around(&self, kind: SyntaxKind) -> bool {
+        BlankLinesAround::for_kind(kind)
+            .is_some_and(|block| self.blank_lines_around.contains(&block))
+    }
+
+    /// Width table captions wrap to.
+    pub fn table_width(&self) -> usize {
+        self.table_max_width.unwrap_or(self.line_width)
+    }
+
+    /// Width hashpipe option lines wrap to.
+    pub fn comment_width(&self) -> usize {
+        self.code_comment_width.unwrap_or(self.line_width)
+    }
+
     pub fn parser_options(&self) -> ParserOptions {
         ParserOptions {
             flavor: self.flavor,
@@ -272,6 +706,7 @@ impl Config {
             // code-block handling) where citation-vs-crossref classification
             // doesn't affect output, so the built-in prefix set suffices here.
             crossref_prefixes: Vec::new(),
+            tab_stop: self.tab_width,
             refdef_labels: None,
         }
     }
@@ -308,11 +743,36 @@ impl ConfigBuilder {
         self
     }
 
+    pub fn max_width_strict(mut self, enabled: bool) -> Self {
+        self.config.max_width_strict = enabled;
+        self
+    }
+
+    pub fn table_max_width(mut self, width: usize) -> Self {
+        self.config.table_max_width = Some(width);
+        self
+    }
+
+    pub fn code_comment_width(mut self, width: usize) -> Self {
+        self.config.code_comment_width = Some(width);
+        self
+    }
+
     pub fn line_ending(mut self, ending: LineEnding) -> Self {
         self.config.line_ending = Some(ending);
         self
     }
 
+    pub fn final_newline(mut self, mode: FinalNewline) -> Self {
+        self.config.final_newline = mode;
+        self
+    }
+
+    pub fn trim_trailing_whitespace(mut self, enabled: bool) -> Self {
+        self.config.trim_trailing_whitespace = enabled;
+        self
+    }
+
     pub fn blank_lines(mut self, mode: BlankLines) -> Self {
         self.config.blank_lines = mode;
         self
@@ -323,6 +783,87 @@ impl ConfigBuilder {
         self
     }
 
+    pub fn emphasis_marker(mut self, marker: EmphasisMarker) -> Self {
+        self.config.emphasis_marker = marker;
+        self
+    }
+
+    pub fn strong_marker(mut self, marker: EmphasisMarker) -> Self {
+        self.config.strong_marker = marker;
+        self
+    }
+
+    pub fn escaping(mut self, escaping: Escaping) -> Self {
+        self.config.escaping = escaping;
+        self
+    }
+
+    pub fn allow_breaks_in(
+        mut self,
+        constructs: impl IntoIterator<Item = BreakableInline>,
+    ) -> Self {
+        self.config.allow_breaks_in = constructs.into_iter().collect();
+        self
+    }
+
+    pub fn blank_lines_around(
+        mut self,
+        blocks: impl IntoIterator<Item = BlankLinesAround>,
+    ) -> Self {
+        self.config.blank_lines_around = blocks.into_iter().collect();
+        self
+    }
+
+    pub fn blank_lines_before_heading(mut self, lines: usize) -> Self {
+        self.config.blank_lines_before_heading = Some(lines);
+        self
+    }
+
+    pub fn blockquotes(mut self, style: BlockquoteStyle) -> Self {
+        self.config.blockquotes = style;
+        self
+    }
+
+    pub fn code_blocks(mut self, style: CodeBlockStyle) -> Self {
+        self.config.code_blocks = style;
+        self
+    }
+
+    pub fn definition_lists(mut self, style: DefinitionListStyle) -> Self {
+        self.config.definition_lists = style;
+        self
+    }
+
+    pub fn div_fence_length(mut self, length: DivFenceLength) -> Self {
+        self.config.div_fence_length = length;
+        self
+    }
+
+    pub fn heading_numbering(mut self, numbering: HeadingNumbering) -> Self {
+        self.config.heading_numbering = numbering;
+        self
+    }
+
+    pub fn toc(mut self, style: TocStyle) -> Self {
+        self.config.toc = style;
+        self
+    }
+
+    pub fn link_destinations(mut self, style: LinkDestinations) -> Self {
+        self.config.link_destinations = style;
+        self
+    }
+
+    pub fn bare_urls(mut self, style: BareUrls) -> Self {
+        self.config.bare_urls = style;
+        self
+    }
+
+    pub fn fail_safe(mut self, enabled: bool) -> Self {
+        self.config.fail_safe = enabled;
+        self
+    }
+
     pub fn build(self) -> Config {
         self.config
     }
@@ -368,9 +909,59 @@ mod schema_tests {
         assert_wire_values::<LineEnding>(&["auto", "lf", "crlf"]);
     }
 
+    #[test]
+    fn final_newline_values_are_lowercase() {
+        assert_wire_values::<FinalNewline>(&["ensure", "preserve"]);
+    }
+
     #[test]
     fn blank_lines_values_are_lowercase() {
-        assert_wire_values::<BlankLines>(&["preserve", "collapse"]);
+        assert_wire_values::<BlankLines>(&["preserve", "collapse", "max"]);
+    }
+
+    #[test]
+    fn emphasis_marker_values_are_lowercase() {
+        assert_wire_values::<EmphasisMarker>(&["asterisk", "underscore", "preserve"]);
+    }
+
+    #[test]
+    fn breakable_inline_values_are_kebab_case() {
+        assert_wire_values::<BreakableInline>(&["link-text", "image-alt", "emphasis", "span"]);
+    }
+
+    #[test]
+    fn attribute_style_values_are_lowercase() {
+        assert_wire_values::<AttributeStyle>(&["shortcut", "explicit", "canonical", "preserve"]);
+    }
+
+    #[test]
+    fn definition_marker_values_are_the_marker_characters() {
+        assert_wire_values::<DefinitionMarker>(&[":", "~"]);
+    }
+
+    #[test]
+    fn div_fence_length_values_are_kebab_case() {
+        assert_wire_values::<DivFenceLength>(&["depth-based", "preserve", "fixed"]);
+    }
+
+    #[test]
+    fn heading_numbering_values_are_lowercase() {
+        assert_wire_values::<HeadingNumbering>(&["preserve", "renumber", "strip"]);
+    }
+
+    #[test]
+    fn link_destinations_values_are_kebab_case() {
+        assert_wire_values::<LinkDestinations>(&["preserve", "angle-brackets", "percent-encode"]);
+    }
+
+    #[test]
+    fn bare_urls_values_are_lowercase() {
+        assert_wire_values::<BareUrls>(&["preserve", "autolink", "link"]);
+    }
+
+    #[test]
+    fn escaping_values_are_lowercase() {
+        assert_wire_values::<Escaping>(&["preserve", "minimal"]);
     }
 
     #[test]
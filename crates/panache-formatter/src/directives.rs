@@ -4,6 +4,7 @@
 //! - `<!-- panache-ignore-start -->` / `<!-- panache-ignore-end -->` - ignore both formatting and linting
 //! - `<!-- panache-ignore-format-start -->` / `<!-- panache-ignore-format-end -->` - ignore formatting only
 //! - `<!-- panache-ignore-lint-start -->` / `<!-- panache-ignore-lint-end -->` - ignore linting only
+//! - `<!-- panache-ignore -->` - preserve the next block exactly as written
 //!
 //! Future extensibility: The syntax is designed to support rule-specific ignores
 //! (e.g., `<!-- panache-ignore-lint heading-hierarchy -->`) though this is not yet implemented.
@@ -28,6 +29,18 @@ pub enum Directive {
     Start(DirectiveKind),
     /// End of an ignore region.
     End(DirectiveKind),
+    /// Skip formatting of the next block only.
+    IgnoreNext,
+}
+
+impl Directive {
+    /// Check if this directive changes how the formatter renders content.
+    pub fn affects_formatting(&self) -> bool {
+        match self {
+            Directive::Start(kind) | Directive::End(kind) => kind.affects_formatting(),
+            Directive::IgnoreNext => true,
+        }
+    }
 }
 
 impl DirectiveKind {
@@ -91,6 +104,7 @@ pub fn parse_directive(comment_text: &str) -> Option<Directive> {
 
     // Parse the directive
     match inner {
+        "panache-ignore" => Some(Directive::IgnoreNext),
         "panache-ignore-start" => Some(Directive::Start(DirectiveKind::IgnoreBoth)),
         "panache-ignore-end" => Some(Directive::End(DirectiveKind::IgnoreBoth)),
         "panache-ignore-format-start" => Some(Directive::Start(DirectiveKind::IgnoreFormat)),
@@ -137,6 +151,8 @@ impl DirectiveTracker {
                 // Mismatch or end without start
                 false
             }
+            // Not a region: the formatter skips the following block itself.
+            Directive::IgnoreNext => true,
         }
     }
 
@@ -228,6 +244,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_directive_ignore_next() {
+        assert_eq!(
+            parse_directive("<!-- panache-ignore -->"),
+            Some(Directive::IgnoreNext)
+        );
+        assert_eq!(
+            parse_directive("<!--panache-ignore-->"),
+            Some(Directive::IgnoreNext)
+        );
+        assert_eq!(parse_directive("<!-- panache-ignore-next -->"), None);
+    }
+
     #[test]
     fn test_parse_directive_ignore_format() {
         assert_eq!(
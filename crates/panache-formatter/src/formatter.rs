@@ -1,23 +1,31 @@
-use crate::config::Config;
-use crate::syntax::{SyntaxNode, YamlFrontmatterRegion};
+use crate::config::{Config, FinalNewline};
+use crate::syntax::{SyntaxKind, SyntaxNode, YamlFrontmatterRegion};
 
 mod blockquotes;
 pub mod code_blocks;
 mod core;
+mod emphasis;
+mod escapes;
 mod fenced_divs;
 mod hashpipe;
 mod headings;
 mod indent_utils;
 mod inline;
 mod inline_layout;
+mod link_dest;
 mod lists;
 mod metadata;
 mod paragraphs;
 mod sentence_wrap;
 mod shortcodes;
 mod smart;
+mod strict_width;
 mod tables;
+mod text_width;
+mod toc;
 mod utils;
+pub mod whitespace;
+mod wrapping;
 // In-tree YAML formatter: `yaml_engine.rs` routes live YAML output
 // through `yaml::format_yaml` (pretty_yaml retired from the formatting
 // path, kept only as the cross-validation reference in
@@ -33,8 +41,64 @@ pub mod math;
 pub use code_blocks::ExternalCodeBlock;
 pub use code_blocks::FormattedCodeMap;
 pub use code_blocks::collect_code_blocks;
+pub use core::BlockFailure;
 pub use core::Formatter;
 pub use indent_utils::continuation_indent_at;
+pub use sentence_wrap::sentence_ends;
+
+/// Node kinds the formatter rewrites into a canonical form. Keep in sync with
+/// the arms of `Formatter::format_node_sync` (front matter is rewritten by the
+/// YAML engine after the tree walk).
+pub const NORMALIZED_KINDS: &[SyntaxKind] = &[
+    SyntaxKind::HEADING,
+    SyntaxKind::HORIZONTAL_RULE,
+    SyntaxKind::REFERENCE_DEFINITION,
+    SyntaxKind::ADMONITION,
+    SyntaxKind::FOOTNOTE_DEFINITION,
+    SyntaxKind::BLOCK_QUOTE,
+    SyntaxKind::PARAGRAPH,
+    SyntaxKind::FIGURE,
+    SyntaxKind::PLAIN,
+    SyntaxKind::LIST,
+    SyntaxKind::LIST_ITEM,
+    SyntaxKind::DEFINITION_LIST,
+    SyntaxKind::LINE_BLOCK,
+    SyntaxKind::SIMPLE_TABLE,
+    SyntaxKind::MULTILINE_TABLE,
+    SyntaxKind::PIPE_TABLE,
+    SyntaxKind::GRID_TABLE,
+    SyntaxKind::FENCED_DIV,
+    SyntaxKind::DISPLAY_MATH,
+    SyntaxKind::CODE_BLOCK,
+    SyntaxKind::YAML_METADATA,
+    SyntaxKind::MYST_DIRECTIVE,
+    SyntaxKind::EMPHASIS,
+    SyntaxKind::STRONG,
+    SyntaxKind::STRIKEOUT,
+    SyntaxKind::SUPERSCRIPT,
+    SyntaxKind::SUBSCRIPT,
+    SyntaxKind::INLINE_MATH,
+];
+
+/// Block kinds the formatter emits exactly as written (apart from container
+/// prefixes and the blank lines around them).
+pub const VERBATIM_KINDS: &[SyntaxKind] = &[
+    SyntaxKind::PANDOC_TITLE_BLOCK,
+    SyntaxKind::MMD_TITLE_BLOCK,
+    SyntaxKind::HTML_BLOCK,
+    SyntaxKind::HTML_BLOCK_RAW,
+    SyntaxKind::HTML_BLOCK_DIV,
+    SyntaxKind::COMMENT,
+    SyntaxKind::LATEX_COMMAND,
+    SyntaxKind::TEX_BLOCK,
+    SyntaxKind::OBSIDIAN_COMMENT,
+    SyntaxKind::MDX_ESM,
+    SyntaxKind::MDX_FLOW,
+    SyntaxKind::MYST_TARGET,
+    SyntaxKind::MYST_COMMENT,
+    SyntaxKind::MYST_BLOCK_BREAK,
+    SyntaxKind::SVELTE_BLOCK,
+];
 
 // Public API functions
 pub fn format_tree(tree: &SyntaxNode, config: &Config, range: Option<(usize, usize)>) -> String {
@@ -98,8 +162,10 @@ pub fn format_tree_with_formatted_code(
 
     log::debug!("Formatting complete: {} bytes output", output.len());
 
-    // Ensure exactly one trailing newline
-    output.trim_end().to_string() + "\n"
+    let final_newline = config.final_newline == FinalNewline::Ensure
+        || range.is_some()
+        || whitespace::ends_with_newline(tree);
+    whitespace::finish_document(&output, config.trim_trailing_whitespace, final_newline)
 }
 
 fn apply_formatted_yaml_at_range(
@@ -1,12 +1,21 @@
-use crate::config::{Config, Flavor};
+use crate::config::{AttributeStyle, CodeBlockStyle, Config, Dialect, Flavor};
+use crate::directives::{
+    Directive, DirectiveTracker, extract_directive_from_node, parse_directive,
+};
 use crate::syntax::{AstNode, SyntaxKind, SyntaxNode};
 use panache_parser::parser::blocks::code_blocks::{CodeBlockType, InfoString};
 use rowan::NodeOrToken;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 use super::hashpipe;
 
-pub type FormattedCodeMap = HashMap<(String, String), String>;
+/// Formatted code keyed by `(language, original code)`. Ordered, so that
+/// nothing that walks it depends on hash iteration order.
+pub type FormattedCodeMap = BTreeMap<(String, String), String>;
+
+/// Class that opts a code block out of external formatting
+/// (```` ```{.python .panache-ignore} ````).
+const IGNORE_CLASS: &str = "panache-ignore";
 
 #[derive(Debug, Clone)]
 pub struct ExternalCodeBlock {
@@ -14,6 +23,8 @@ pub struct ExternalCodeBlock {
     pub original: String,
     pub formatter_input: String,
     pub hashpipe_prefix: Option<String>,
+    /// Byte range of the code block (or MyST directive) in the document.
+    pub range: std::ops::Range<usize>,
 }
 
 /// Format a code block, normalizing fence markers and attributes based on config
@@ -28,15 +39,27 @@ pub(super) fn format_code_block(
         return;
     }
 
-    let (info_node, language, extracted_content) = extract_code_block_parts(node);
+    let (info_node, language, extracted_content) = extract_code_block_parts(node, config.tab_width);
     let mut content = extracted_content;
     let language_key = language.unwrap_or_default();
-
-    if let Some(formatted) = formatted_code.get(&(language_key.clone(), content.clone())) {
-        content = expand_tabs_with_width(formatted, config.tab_width);
-    } else if let Some(raw_content) = extract_raw_code_block_content(node)
-        && let Some(formatted) = formatted_code.get(&(language_key, raw_content))
-    {
+    // An opted-out block keeps its content even when an identical block
+    // elsewhere was formatted.
+    let skip_external = info_node.as_ref().is_some_and(|info_node| {
+        let info = InfoString::parse(&info_node.text().to_string());
+        skips_external_formatting(&info, &language_key, &content)
+    });
+
+    let formatted = if skip_external {
+        None
+    } else {
+        formatted_code
+            .get(&(language_key.clone(), content.clone()))
+            .or_else(|| {
+                let raw_content = extract_raw_code_block_content(node)?;
+                formatted_code.get(&(language_key, raw_content))
+            })
+    };
+    if let Some(formatted) = formatted {
         content = expand_tabs_with_width(formatted, config.tab_width);
     }
 
@@ -97,7 +120,7 @@ pub(super) fn format_code_block(
     }
 
     // Format the info string based on config and block type (traditional inline)
-    let formatted_info = format_info_string(&info_node, &info);
+    let formatted_info = format_info_string(&info_node, &info, config);
 
     log::trace!("formatted_info = '{}'", formatted_info);
 
@@ -156,41 +179,77 @@ fn expand_tabs_with_width(text: &str, tab_width: usize) -> String {
     out
 }
 
-fn strip_indent_columns(indent: &str, columns: usize) -> String {
-    let mut remaining = columns;
+fn strip_indent_columns(indent: &str, columns: usize, tab_width: usize) -> String {
+    let mut cols = 0;
     let mut idx = 0;
     for (i, ch) in indent.char_indices() {
-        if remaining == 0 {
+        if cols >= columns {
             break;
         }
         match ch {
-            ' ' => {
-                remaining = remaining.saturating_sub(1);
-                idx = i + 1;
-            }
-            '\t' => {
-                remaining = remaining.saturating_sub(4);
-                idx = i + 1;
-            }
+            ' ' => cols += 1,
+            '\t' => cols += tab_width - (cols % tab_width),
             _ => break,
         }
+        idx = i + 1;
     }
     indent[idx..].to_string()
 }
 
-fn indent_columns(indent: &str) -> usize {
+fn indent_columns(indent: &str, tab_width: usize) -> usize {
     let mut cols = 0usize;
     for ch in indent.chars() {
         match ch {
             ' ' => cols += 1,
-            '\t' => cols += 4 - (cols % 4),
+            '\t' => cols += tab_width - (cols % tab_width),
             _ => break,
         }
     }
     cols
 }
 
-fn extract_code_block_parts(node: &SyntaxNode) -> (Option<SyntaxNode>, Option<String>, String) {
+/// Columns before a code block that opens on a list marker line (`- ````),
+/// counting every marker on that line. The block's content lines are indented
+/// to this column, but the fence itself carries no indentation of its own.
+fn marker_line_columns(node: &SyntaxNode, tab_width: usize) -> usize {
+    let mut prefixes = Vec::new();
+    let mut child = node.clone();
+    while let Some(parent) = child.parent() {
+        match parent.kind() {
+            SyntaxKind::LIST => {}
+            SyntaxKind::LIST_ITEM => {
+                let prefix: String = parent
+                    .children_with_tokens()
+                    .take_while(|el| el.text_range().start() < child.text_range().start())
+                    .map(|el| el.to_string())
+                    .collect();
+                if prefix.contains('\n') {
+                    break;
+                }
+                prefixes.push(prefix);
+            }
+            _ => break,
+        }
+        child = parent;
+    }
+    let mut cols = 0;
+    for ch in prefixes.iter().rev().flat_map(|prefix| prefix.chars()) {
+        cols += if ch == '\t' {
+            tab_width - (cols % tab_width)
+        } else {
+            1
+        };
+    }
+    cols
+}
+
+/// Info node, language, and content of a code block, with the indentation the
+/// fence (or the indented-code marker) accounts for removed. Tabs in that
+/// indentation span `tab_width` columns, as they did for the parser.
+fn extract_code_block_parts(
+    node: &SyntaxNode,
+    tab_width: usize,
+) -> (Option<SyntaxNode>, Option<String>, String) {
     let mut info_node: Option<SyntaxNode> = None;
     let mut language: Option<String> = None;
     let mut content = String::new();
@@ -208,7 +267,11 @@ fn extract_code_block_parts(node: &SyntaxNode) -> (Option<SyntaxNode>, Option<St
             NodeOrToken::Node(n) => match n.kind() {
                 SyntaxKind::CODE_FENCE_OPEN => {
                     has_fence = true;
-                    fence_indent_cols = indent_columns(&fence_indent);
+                    fence_indent_cols = if fence_indent.is_empty() {
+                        marker_line_columns(node, tab_width)
+                    } else {
+                        indent_columns(&fence_indent, tab_width)
+                    };
                     for child_token in n.children_with_tokens() {
                         if let NodeOrToken::Node(node) = child_token
                             && node.kind() == SyntaxKind::CODE_INFO
@@ -238,6 +301,8 @@ fn extract_code_block_parts(node: &SyntaxNode) -> (Option<SyntaxNode>, Option<St
                                     // Parser may preserve blockquote continuation markers inside
                                     // indented code content for losslessness. These are container
                                     // syntax, not code bytes, so ignore them for formatter output.
+                                    // So is any list indent before the marker.
+                                    line_indent.clear();
                                     saw_blockquote_marker = true;
                                 }
                                 SyntaxKind::WHITESPACE if at_line_start => {
@@ -258,15 +323,23 @@ fn extract_code_block_parts(node: &SyntaxNode) -> (Option<SyntaxNode>, Option<St
                                     if at_line_start && t.text().is_empty() {
                                         continue;
                                     }
+                                    let mut text = t.text();
                                     if at_line_start {
+                                        // The parser can leave an inner list's indent in
+                                        // the text (`  >   x` in `- > - ```…`); it counts
+                                        // toward the fence indent like a WHITESPACE token.
+                                        let code = text.trim_start_matches([' ', '\t']);
+                                        line_indent.push_str(&text[..text.len() - code.len()]);
+                                        text = code;
                                         line_content.push_str(&strip_indent_columns(
                                             &line_indent,
                                             base_indent_cols,
+                                            tab_width,
                                         ));
                                         line_indent.clear();
                                         at_line_start = false;
                                     }
-                                    line_content.push_str(t.text());
+                                    line_content.push_str(text);
                                 }
                                 SyntaxKind::NEWLINE => {
                                     saw_blockquote_marker = false;
@@ -289,6 +362,7 @@ fn extract_code_block_parts(node: &SyntaxNode) -> (Option<SyntaxNode>, Option<St
                                     line_content.push_str(&strip_indent_columns(
                                         &line_indent,
                                         base_indent_cols,
+                                        tab_width,
                                     ));
                                     line_indent.clear();
                                     at_line_start = false;
@@ -480,23 +554,31 @@ pub(super) fn render_executable_info(language: &str, options: &[ChunkOptionRepr]
 }
 
 /// Format the info string based on block type and config preferences
-fn format_info_string(info_node: &SyntaxNode, info: &InfoString) -> String {
+fn format_info_string(info_node: &SyntaxNode, info: &InfoString, config: &Config) -> String {
     log::trace!(
         "format_info_string: block_type={:?}, raw='{}'",
         info.block_type,
         info.raw
     );
+    let style = &config.code_blocks;
+    let display_style = if config.dialect() == Dialect::Pandoc {
+        style.attribute_style
+    } else {
+        match style.attribute_style {
+            AttributeStyle::Preserve => AttributeStyle::Preserve,
+            _ => AttributeStyle::Shortcut,
+        }
+    };
     match &info.block_type {
-        CodeBlockType::Plain => {
-            // No language, just attributes (if any)
-            if info.attributes.is_empty() {
-                String::new()
-            } else {
-                format!("{{{}}}", format_attributes(&info.attributes, false))
-            }
+        CodeBlockType::Plain
+        | CodeBlockType::DisplayShortcut { .. }
+        | CodeBlockType::DisplayExplicit { .. }
+            if display_style == AttributeStyle::Preserve =>
+        {
+            info.raw.trim().to_string()
         }
+        CodeBlockType::Plain => format_display_info(None, info.attributes.clone(), display_style),
         CodeBlockType::DisplayShortcut { language } => {
-            // Display block with shortcut syntax
             if info.attributes.is_empty() {
                 // Preserve the full info string, not just the first word.
                 // Only the first word is the language class, but the rest is
@@ -505,46 +587,36 @@ fn format_info_string(info_node: &SyntaxNode, info: &InfoString) -> String {
                 // that must survive formatting. This bare multi-word form only
                 // reaches the formatter under CommonMark/GFM; the Pandoc
                 // dialect parses it as an inline code span upstream.
-                info.raw.trim().to_string()
+                let raw = info.raw.trim();
+                if raw.contains(char::is_whitespace) {
+                    let rest = &raw[language.len()..];
+                    format!("{}{}", display_language(language, style), rest)
+                } else {
+                    format_display_info(
+                        Some(display_language(language, style)),
+                        Vec::new(),
+                        display_style,
+                    )
+                }
             } else {
-                format!(
-                    "{} {{{}}}",
-                    language,
-                    format_attributes(&info.attributes, false)
+                format_display_info(
+                    Some(display_language(language, style)),
+                    info.attributes.clone(),
+                    display_style,
                 )
             }
         }
         CodeBlockType::DisplayExplicit { classes } => {
-            // Display block with explicit Pandoc syntax
-            // Convert to shortcut form: ```{.python} -> ```python
-            if let Some(first_class) = classes.first() {
-                if info.attributes.is_empty() && classes.len() == 1 {
-                    first_class.clone()
-                } else {
-                    // Mix shortcut + attributes
-                    let mut attrs: Vec<String> =
-                        classes.iter().skip(1).map(|c| format!(".{}", c)).collect();
-                    attrs.extend(info.attributes.iter().map(|(k, v)| {
-                        if let Some(val) = v {
-                            format!("{}=\"{}\"", k, val)
-                        } else {
-                            k.clone()
-                        }
-                    }));
-                    if attrs.is_empty() {
-                        first_class.clone()
-                    } else {
-                        format!("{} {{{}}}", first_class, attrs.join(" "))
-                    }
-                }
-            } else {
-                // No classes, just attributes
-                if info.attributes.is_empty() {
-                    String::new()
-                } else {
-                    format!("{{{}}}", format_attributes(&info.attributes, false))
-                }
-            }
+            // The first class is the language; any further classes come
+            // before the remaining attributes.
+            let mut attrs: Vec<(String, Option<String>)> = classes
+                .iter()
+                .skip(1)
+                .map(|class| (format!(".{class}"), None))
+                .collect();
+            attrs.extend(info.attributes.iter().cloned());
+            let language = classes.first().map(|class| display_language(class, style));
+            format_display_info(language, attrs, display_style)
         }
         CodeBlockType::Executable { language } => {
             // Executable chunk: extract options from CST nodes
@@ -560,6 +632,58 @@ fn format_info_string(info_node: &SyntaxNode, info: &InfoString) -> String {
     }
 }
 
+/// The language of a display block, lowercased if configured.
+fn display_language(language: &str, style: &CodeBlockStyle) -> String {
+    if style.lowercase_language {
+        language.to_lowercase()
+    } else {
+        language.to_string()
+    }
+}
+
+/// Render a display block's info string from its language and its other
+/// attributes (`.class`, `#id`, `key="value"`) in the order written.
+fn format_display_info(
+    language: Option<String>,
+    mut attrs: Vec<(String, Option<String>)>,
+    attribute_style: AttributeStyle,
+) -> String {
+    match attribute_style {
+        AttributeStyle::Shortcut | AttributeStyle::Preserve => match language {
+            Some(language) if attrs.is_empty() => language,
+            Some(language) => format!("{} {{{}}}", language, format_attributes(&attrs, false)),
+            None if attrs.is_empty() => String::new(),
+            None => format!("{{{}}}", format_attributes(&attrs, false)),
+        },
+        AttributeStyle::Explicit => {
+            if let Some(language) = language {
+                attrs.insert(0, (format!(".{language}"), None));
+            }
+            if attrs.is_empty() {
+                String::new()
+            } else {
+                format!("{{{}}}", format_attributes(&attrs, false))
+            }
+        }
+        AttributeStyle::Canonical => {
+            if attrs.is_empty() {
+                return language.unwrap_or_default();
+            }
+            if let Some(language) = language {
+                attrs.insert(0, (format!(".{language}"), None));
+            }
+            // Stable sort: identifier, then classes, then key-value pairs,
+            // each group in the order written.
+            attrs.sort_by_key(|(key, value)| match (key.chars().next(), value) {
+                (Some('#'), None) => 0,
+                (Some('.'), None) => 1,
+                _ => 2,
+            });
+            format!("{{{}}}", format_attributes(&attrs, false))
+        }
+    }
+}
+
 /// Format a code block using Quarto hashpipe style for executable chunks.
 ///
 /// Converts simple inline options to hashpipe format with YAML syntax,
@@ -592,7 +716,7 @@ fn format_code_block_hashpipe(
     let hashpipe_lines = match hashpipe::format_as_hashpipe(
         language,
         &simple,
-        config.line_width,
+        config.comment_width(),
         config.wrap.as_ref(),
     ) {
         Some(lines) => lines,
@@ -772,16 +896,83 @@ pub(crate) fn extract_myst_directive_parts(node: &SyntaxNode) -> Option<(String,
     body.map(|body| (language, body))
 }
 
-/// Collect all code blocks and their info strings from the syntax tree.
+/// Whether a code block opts out of external formatting: a `.panache-ignore`
+/// class, a `panache=FALSE` chunk option, or a `#| panache: false` hashpipe
+/// option.
+pub(crate) fn skips_external_formatting(info: &InfoString, language: &str, content: &str) -> bool {
+    let is_false = |value: &str| {
+        matches!(
+            value
+                .trim()
+                .trim_matches(['"', '\''])
+                .to_ascii_lowercase()
+                .as_str(),
+            "false" | "f" | "no"
+        )
+    };
+
+    if let CodeBlockType::DisplayExplicit { classes } = &info.block_type
+        && classes.iter().any(|class| class == IGNORE_CLASS)
+    {
+        return true;
+    }
+    if info.attributes.iter().any(|(key, value)| match value {
+        None => key.strip_prefix('.') == Some(IGNORE_CLASS),
+        Some(value) => key == "panache" && is_false(value),
+    }) {
+        return true;
+    }
+
+    let Some(prefix) = hashpipe::get_comment_prefix(language) else {
+        return false;
+    };
+    content
+        .lines()
+        .map_while(|line| line.strip_prefix(prefix))
+        .filter_map(|option| option.split_once(':'))
+        .any(|(key, value)| key.trim() == "panache" && is_false(value))
+}
+
 /// Collect all code blocks from the syntax tree for external formatting.
-/// Returns a flat list of (language, content) pairs.
+/// Returns a flat list of (language, content) pairs. Blocks inside a
+/// `panache-ignore` region, after a `<!-- panache-ignore -->` comment, or
+/// opted out per block (see [`skips_external_formatting`]) are left out.
 pub fn collect_code_blocks(
     tree: &SyntaxNode,
     _input: &str,
     config: &Config,
 ) -> Vec<ExternalCodeBlock> {
     let mut result = Vec::new();
+    let mut directives = DirectiveTracker::new();
+    // End of the last directive node, or of the block skipped after
+    // `<!-- panache-ignore -->`; nodes inside it are not visited again.
+    let mut skipped_end = rowan::TextSize::from(0);
+    let mut ignore_next = false;
     for node in tree.descendants() {
+        if node.text_range().start() < skipped_end {
+            continue;
+        }
+        if let Some(directive) = extract_directive_from_node(&node) {
+            directives.process_directive(&directive);
+            // Inline in a paragraph, the comment only skips a following block
+            // when it is the whole paragraph (as when rendering).
+            ignore_next = directive == Directive::IgnoreNext
+                && (node.kind() != SyntaxKind::INLINE_HTML
+                    || node.parent().is_some_and(|parent| {
+                        parse_directive(&parent.text().to_string()) == Some(Directive::IgnoreNext)
+                    }));
+            skipped_end = node.text_range().end();
+            continue;
+        }
+        if ignore_next && node.kind() != SyntaxKind::BLANK_LINE {
+            ignore_next = false;
+            skipped_end = node.text_range().end();
+            continue;
+        }
+        if directives.is_formatting_ignored() {
+            continue;
+        }
+
         if node.kind() == SyntaxKind::MYST_DIRECTIVE {
             if let Some((language, content)) = extract_myst_directive_parts(&node) {
                 if content.is_empty() {
@@ -795,6 +986,7 @@ pub fn collect_code_blocks(
                     original: content.clone(),
                     formatter_input: content,
                     hashpipe_prefix: None,
+                    range: node.text_range().into(),
                 });
             }
             continue;
@@ -804,7 +996,7 @@ pub fn collect_code_blocks(
             continue;
         }
 
-        let (info_node, language, content) = extract_code_block_parts(&node);
+        let (info_node, language, content) = extract_code_block_parts(&node, config.tab_width);
         if content.is_empty() {
             continue;
         }
@@ -814,9 +1006,9 @@ pub fn collect_code_blocks(
             .map(|n| InfoString::parse(&n.text().to_string()))
             .unwrap_or_else(|| InfoString::parse(""));
 
-        let language = language.unwrap_or_else(|| match info.block_type {
+        let language = language.unwrap_or_else(|| match &info.block_type {
             CodeBlockType::DisplayShortcut { language }
-            | CodeBlockType::Executable { language } => language,
+            | CodeBlockType::Executable { language } => language.clone(),
             CodeBlockType::DisplayExplicit { classes } => {
                 classes.first().cloned().unwrap_or_default()
             }
@@ -826,12 +1018,16 @@ pub fn collect_code_blocks(
         if language.is_empty() && !config.formatters.contains_key("") {
             continue;
         }
+        if skips_external_formatting(&info, &language, &content) {
+            continue;
+        }
 
         result.push(ExternalCodeBlock {
             language,
             original: content.clone(),
             formatter_input: content,
             hashpipe_prefix: None,
+            range: node.text_range().into(),
         });
     }
 
@@ -849,7 +1045,7 @@ pub fn collect_code_blocks(
                 continue;
             }
 
-            let (info_node, language, content) = extract_code_block_parts(&node);
+            let (info_node, language, content) = extract_code_block_parts(&node, config.tab_width);
             if content != block.original {
                 continue;
             }
@@ -885,6 +1081,7 @@ pub fn collect_code_blocks(
             original: block.original,
             formatter_input,
             hashpipe_prefix: prefix,
+            range: block.range,
         });
     }
 
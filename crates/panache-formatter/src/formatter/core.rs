@@ -1,20 +1,28 @@
-use crate::config::{Config, HorizontalRuleStyle, WrapMode};
+use crate::config::{Config, DivFenceLength, HorizontalRuleStyle, WrapMode};
 use crate::directives::{DirectiveTracker, extract_directive_from_node};
-use crate::syntax::{BlockQuote, DefinitionItem, DisplayMath, FencedDiv, SyntaxKind, SyntaxNode};
+use crate::syntax::{
+    DefinitionItem, DisplayMath, FencedDiv, SyntaxElement, SyntaxKind, SyntaxNode,
+};
 use panache_parser::parser::blocks::headings::try_parse_atx_heading;
 use panache_parser::parser::blocks::horizontal_rules::try_parse_horizontal_rule;
 use panache_parser::parser::utils::attributes::parse_attribute_content;
-use rowan::NodeOrToken;
 use rowan::ast::AstNode;
+use rowan::{NodeOrToken, TextRange, TextSize};
+use std::collections::HashMap;
+use unicode_width::UnicodeWidthStr;
 
 use super::code_blocks;
 use super::code_blocks::FormattedCodeMap;
+use super::emphasis::emphasis_delimiter;
+use super::escapes::escaped_char_text;
 use super::headings;
 use super::inline;
 use super::inline_layout;
 use super::paragraphs;
 use super::smart::normalize_smart_punctuation;
+use super::strict_width;
 use super::tables;
+use super::toc::{self, TableOfContents};
 use super::utils::{is_block_element, is_structural_block};
 
 pub struct Formatter {
@@ -31,10 +39,52 @@ pub struct Formatter {
     pub(super) directive_tracker: DirectiveTracker,
     /// Depth of ignore region (for preserving content exactly)
     ignore_region_start: Option<usize>,
+    /// Set by `<!-- panache-ignore -->`: emit the next block verbatim.
+    ignore_next_block: bool,
     /// Structured rendering context for nested blockquote containers.
     blockquote_context: Option<BlockquoteContext>,
+    /// Top-level blocks emitted verbatim after their formatting panicked
+    /// (only populated when [`Config::fail_safe`] is set).
+    failures: Vec<BlockFailure>,
+    /// Replacement section numbers for numbered headings, computed over the
+    /// whole document before formatting starts (see `headings.numbering`).
+    section_numbers: HashMap<TextRange, String>,
+    /// The table of contents to write after the `<!-- panache-toc start -->`
+    /// marker, if the document has one.
+    toc: Option<TableOfContents>,
+    /// Set after the table of contents is written: elements ending at or
+    /// before this offset are the old list and its end marker, and are dropped.
+    toc_skip_until: Option<TextSize>,
 }
 
+/// A top-level block whose formatting panicked under [`Config::fail_safe`]. The
+/// block is emitted exactly as written instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockFailure {
+    /// Kind of the block's CST node.
+    pub kind: SyntaxKind,
+    /// Byte range of the block in the formatted input.
+    pub range: std::ops::Range<usize>,
+    /// The panic payload, when it was a string.
+    pub message: String,
+}
+
+/// Formatter state that a panicking block may have left half-updated.
+struct BlockCheckpoint {
+    output_len: usize,
+    consecutive_blank_lines: usize,
+    fenced_div_depth: usize,
+    max_marker_widths: Vec<usize>,
+    directive_tracker: DirectiveTracker,
+    ignore_region_start: Option<usize>,
+    ignore_next_block: bool,
+    blockquote_context: Option<BlockquoteContext>,
+}
+
+/// Buffered output size at which [`Formatter::format_chunks`] starts flushing
+/// between top-level blocks.
+const CHUNK_FLUSH_THRESHOLD: usize = 64 * 1024;
+
 #[derive(Clone, Debug)]
 struct BlockquoteContext {
     in_list_continuation: bool,
@@ -56,14 +106,136 @@ impl Formatter {
             range,
             directive_tracker: DirectiveTracker::new(),
             ignore_region_start: None,
+            ignore_next_block: false,
             blockquote_context: None,
+            failures: Vec::new(),
+            section_numbers: HashMap::new(),
+            toc: None,
+            toc_skip_until: None,
         }
     }
     pub fn format(mut self, node: &SyntaxNode) -> String {
+        #[cfg(feature = "tracing")]
+        let _span = self.span().entered();
+        let referenced = self.reference_long_links(node);
+        let node = referenced.as_ref().unwrap_or(node);
+        self.section_numbers = headings::section_numbers(node, &self.config);
+        self.toc = self.table_of_contents(node);
         self.format_node_sync(node, 0);
         self.output
     }
 
+    /// Like [`Formatter::format`], but also returns the blocks that were
+    /// emitted verbatim because their formatting panicked. Always empty unless
+    /// [`Config::fail_safe`] is set.
+    pub fn format_with_failures(mut self, node: &SyntaxNode) -> (String, Vec<BlockFailure>) {
+        #[cfg(feature = "tracing")]
+        let _span = self.span().entered();
+        let referenced = self.reference_long_links(node);
+        let node = referenced.as_ref().unwrap_or(node);
+        self.section_numbers = headings::section_numbers(node, &self.config);
+        self.toc = self.table_of_contents(node);
+        self.format_node_sync(node, 0);
+        (self.output, self.failures)
+    }
+
+    /// Format `node`, handing the output to `sink` in chunks instead of
+    /// returning one `String`.
+    ///
+    /// Between top-level blocks of a `DOCUMENT`, buffered output past a fixed
+    /// threshold (64 KiB) is flushed up to the start of its last content
+    /// line, so peak memory stays proportional to the largest block rather than
+    /// the whole document. Chunks always end right after a `\n`, and the final
+    /// chunk carries all trailing whitespace, so callers can normalize line
+    /// endings per chunk and trim only the last one. Concatenating the chunks
+    /// yields exactly what [`Formatter::format`] returns. Range formatting is
+    /// not supported here. Returns the blocks recovered under
+    /// [`Config::fail_safe`], as [`Formatter::format_with_failures`] does.
+    pub fn format_chunks<E>(
+        mut self,
+        node: &SyntaxNode,
+        mut sink: impl FnMut(&str) -> Result<(), E>,
+    ) -> Result<Vec<BlockFailure>, E> {
+        #[cfg(feature = "tracing")]
+        let _span = self.span().entered();
+        let referenced = self.reference_long_links(node);
+        let node = referenced.as_ref().unwrap_or(node);
+        self.section_numbers = headings::section_numbers(node, &self.config);
+        self.toc = self.table_of_contents(node);
+        if node.kind() != SyntaxKind::DOCUMENT {
+            self.format_node_sync(node, 0);
+            sink(&self.output)?;
+            return Ok(self.failures);
+        }
+
+        for el in node.children_with_tokens() {
+            self.format_document_element(el, 0);
+            if self.output.len() >= CHUNK_FLUSH_THRESHOLD
+                && let Some(cut) = self.chunk_flush_point()
+            {
+                sink(&self.output[..cut])?;
+                self.output.replace_range(..cut, "");
+            }
+        }
+        sink(&self.output)?;
+        Ok(self.failures)
+    }
+
+    /// The `format` span covering one entry point, for timing and profiling.
+    #[cfg(feature = "tracing")]
+    fn span(&self) -> tracing::Span {
+        tracing::debug_span!(
+            "format",
+            line_width = self.config.line_width,
+            wrap = ?self.config.wrap,
+        )
+    }
+
+    /// Under `max_width_strict`, `node` re-parsed with its over-long inline
+    /// links turned into reference links. Range formatting keeps the links
+    /// inline, since the definitions land outside the range.
+    fn reference_long_links(&self, node: &SyntaxNode) -> Option<SyntaxNode> {
+        if self.range.is_some() {
+            return None;
+        }
+        strict_width::reference_long_links(node, &self.config)
+    }
+
+    /// The table of contents for `node`. Range formatting leaves it alone,
+    /// since the markers may lie outside the range.
+    fn table_of_contents(&self, node: &SyntaxNode) -> Option<TableOfContents> {
+        if self.range.is_some() {
+            return None;
+        }
+        toc::table_of_contents(node, &self.config, &self.section_numbers)
+    }
+
+    /// Write the generated list and the end marker after the start marker,
+    /// and drop the old list.
+    fn write_table_of_contents(&mut self) {
+        let Some(toc) = self.toc.take() else {
+            return;
+        };
+        self.output.push('\n');
+        if !toc.list.is_empty() {
+            self.output.push_str(&toc.list);
+            self.output.push('\n');
+        }
+        self.output.push_str(&toc.end_marker);
+        self.output.push('\n');
+        self.consecutive_blank_lines = 0;
+        self.toc_skip_until = Some(toc.end);
+    }
+
+    /// Byte offset up to which buffered output can be flushed: the start of
+    /// the last line holding non-whitespace content. Everything after it stays
+    /// buffered so trailing-newline checks (`ends_with("\n\n")`,
+    /// `is_empty()`) keep seeing the same text they would without flushing.
+    fn chunk_flush_point(&self) -> Option<usize> {
+        let last_content = self.output.trim_end().len();
+        Some(self.output[..last_content].rfind('\n')? + 1)
+    }
+
     /// Check if a node overlaps with the formatting range
     fn is_in_range(&self, node: &SyntaxNode) -> bool {
         if let Some((range_start, range_end)) = self.range {
@@ -144,7 +316,7 @@ impl Formatter {
 
     // Delegate to headings module
     pub(super) fn format_heading(&self, node: &SyntaxNode) -> String {
-        headings::format_heading(node, &self.config)
+        headings::format_heading(node, &self.config, &self.section_numbers)
     }
 
     fn contains_latex_command(&self, node: &SyntaxNode) -> bool {
@@ -220,6 +392,66 @@ impl Formatter {
             }
     }
 
+    /// Whether `blank-lines-around` asks for a blank line between `node` and
+    /// the block before it that the output does not have yet. Only blocks at
+    /// the document level and in fenced divs there are separated: in a list
+    /// item a blank line would make the list loose, and block quotes manage
+    /// their own blank lines.
+    fn needs_blank_line_before(&self, node: &SyntaxNode) -> bool {
+        if self.config.blank_lines_around.is_empty()
+            || !self.output.ends_with('\n')
+            || self.output.ends_with("\n\n")
+        {
+            return false;
+        }
+        if !is_separable_block(node) {
+            return false;
+        }
+        let Some(prev) = node.prev_sibling() else {
+            return false;
+        };
+        if matches!(
+            prev.kind(),
+            SyntaxKind::BLANK_LINE | SyntaxKind::DIV_FENCE_OPEN
+        ) || matches!(
+            node.kind(),
+            SyntaxKind::BLANK_LINE | SyntaxKind::DIV_FENCE_CLOSE
+        ) {
+            return false;
+        }
+        self.config.wants_blank_lines_around(node.kind())
+            || self.config.wants_blank_lines_around(prev.kind())
+    }
+
+    /// Set the blank lines before a heading to `blank-lines-before-heading`,
+    /// whatever the input had. Headings that open the document or a fenced
+    /// div are left alone.
+    fn pad_blank_lines_before_heading(&mut self, node: &SyntaxNode) {
+        let Some(lines) = self.config.blank_lines_before_heading else {
+            return;
+        };
+        if node.kind() != SyntaxKind::HEADING
+            || !self.output.ends_with('\n')
+            || !is_separable_block(node)
+        {
+            return;
+        }
+        let opens_container = node
+            .siblings(rowan::Direction::Prev)
+            .skip(1)
+            .find(|sibling| sibling.kind() != SyntaxKind::BLANK_LINE)
+            .is_none_or(|prev| prev.kind() == SyntaxKind::DIV_FENCE_OPEN);
+        if opens_container {
+            return;
+        }
+        let content_end = self.output.trim_end_matches('\n').len();
+        if content_end == 0 {
+            return;
+        }
+        self.output.truncate(content_end + 1);
+        self.output.push_str(&"\n".repeat(lines.max(1)));
+    }
+
     fn paragraph_starts_with_atx_heading_candidate(&self, node: &SyntaxNode) -> bool {
         if node.kind() != SyntaxKind::PARAGRAPH {
             return false;
@@ -287,7 +519,7 @@ impl Formatter {
         formatted
     }
 
-    fn strip_leading_columns(line: &str, columns: usize) -> String {
+    fn strip_leading_columns(line: &str, columns: usize, tab_width: usize) -> String {
         let mut cols = 0usize;
         let mut idx = 0usize;
 
@@ -303,7 +535,7 @@ impl Formatter {
                     idx = byte_idx + ch.len_utf8();
                 }
                 '\t' => {
-                    cols += 4 - (cols % 4);
+                    cols += tab_width - (cols % tab_width);
                     idx = byte_idx + ch.len_utf8();
                 }
                 _ => {
@@ -365,8 +597,11 @@ impl Formatter {
 
             self.output.push_str(&continuation_prefix);
             if normalize_content_indent {
-                self.output
-                    .push_str(&Self::strip_leading_columns(line, content_indent_cols));
+                self.output.push_str(&Self::strip_leading_columns(
+                    line,
+                    content_indent_cols,
+                    self.config.tab_width,
+                ));
             } else {
                 self.output.push_str(line);
             }
@@ -375,8 +610,11 @@ impl Formatter {
 
         self.output.push_str(&continuation_prefix);
         if normalize_content_indent {
-            self.output
-                .push_str(&Self::strip_leading_columns(closing, content_indent_cols));
+            self.output.push_str(&Self::strip_leading_columns(
+                closing,
+                content_indent_cols,
+                self.config.tab_width,
+            ));
         } else {
             self.output.push_str(closing);
         }
@@ -468,6 +706,24 @@ impl Formatter {
         }
     }
 
+    /// With `marker-spacing = 0`, give the lines emitted since `start` whose
+    /// content starts with whitespace one space after `content_prefix`: a
+    /// reader drops the first space after the last `>`, so without it the
+    /// content would lose a column of indentation.
+    fn space_indented_quote_lines(&mut self, start: usize, content_prefix: &str) {
+        let quoted = self.output.split_off(start);
+        for line in quoted.split_inclusive('\n') {
+            match line.strip_prefix(content_prefix) {
+                Some(rest) if rest.starts_with([' ', '\t']) => {
+                    self.output.push_str(content_prefix);
+                    self.output.push(' ');
+                    self.output.push_str(rest);
+                }
+                _ => self.output.push_str(line),
+            }
+        }
+    }
+
     fn append_blockquote_prefixed_list_output(
         &mut self,
         list_output: &str,
@@ -475,6 +731,7 @@ impl Formatter {
         content_prefix: &str,
         blank_prefix: &str,
     ) -> bool {
+        let marker = self.config.blockquotes.marker();
         let mut in_list_item_continuation = false;
         for line in list_output.lines() {
             let trimmed_line = line.trim_start();
@@ -482,8 +739,8 @@ impl Formatter {
             if line.is_empty() {
                 self.output.push_str(blank_prefix);
                 in_list_item_continuation = false;
-            } else if line.starts_with("> ") {
-                let rest = line.trim_start_matches("> ");
+            } else if line.starts_with(&marker) {
+                let rest = line.trim_start_matches(marker.as_str());
                 let trimmed_rest = rest.trim_start();
                 if trimmed_rest.is_empty() {
                     self.output.push_str(blank_prefix);
@@ -494,7 +751,7 @@ impl Formatter {
                 let starts_with_marker_after_quote = Self::starts_with_list_marker(trimmed_rest);
                 if starts_with_marker_after_quote {
                     self.output.push_str(base_indent);
-                    self.output.push_str("> ");
+                    self.output.push_str(&marker);
                     self.output.push_str(trimmed_rest);
                     in_list_item_continuation = true;
                 } else {
@@ -503,17 +760,20 @@ impl Formatter {
                     in_list_item_continuation = false;
                 }
             } else if starts_with_list_marker {
+                // The list was formatted at indent 0, so any indentation
+                // left belongs to a nested list.
                 self.output.push_str(content_prefix);
-                self.output.push_str(trimmed_line);
+                self.output.push_str(line);
                 in_list_item_continuation = true;
             } else if in_list_item_continuation && line.starts_with(char::is_whitespace) {
                 if trimmed_line.is_empty() {
                     self.output.push_str(blank_prefix);
                     in_list_item_continuation = false;
                 } else {
+                    // Keep the item's own hanging indent: it is wider than two
+                    // columns under `1.` or `10.` markers.
                     self.output.push_str(content_prefix);
-                    self.output.push_str("  ");
-                    self.output.push_str(trimmed_line);
+                    self.output.push_str(line);
                 }
             } else {
                 self.output.push_str(content_prefix);
@@ -581,7 +841,141 @@ impl Formatter {
         false
     }
 
+    /// Format one direct child (node or token) of a `DOCUMENT`.
+    fn format_document_element(&mut self, el: SyntaxElement, indent: usize) {
+        if let Some(end) = self.toc_skip_until {
+            if el.text_range().end() <= end {
+                return;
+            }
+            self.toc_skip_until = None;
+        }
+        match el {
+            rowan::NodeOrToken::Node(n) => {
+                // When range filtering is active, only process nodes that overlap
+                if self.should_process_top_level_node(&n) {
+                    // A start marker inside an ignore region, or right after
+                    // `<!-- panache-ignore -->`, keeps the list as written.
+                    let writes_toc = self
+                        .toc
+                        .as_ref()
+                        .is_some_and(|toc| toc.start == n.text_range())
+                        && !self.ignore_next_block
+                        && !self.directive_tracker.is_formatting_ignored();
+                    if self.config.fail_safe {
+                        self.format_top_level_block_guarded(&n, indent);
+                    } else {
+                        #[cfg(test)]
+                        tests::maybe_inject_panic(&n);
+                        self.format_node_sync(&n, indent);
+                    }
+                    if writes_toc {
+                        self.write_table_of_contents();
+                    }
+                }
+            }
+            rowan::NodeOrToken::Token(t) => match t.kind() {
+                SyntaxKind::WHITESPACE => {}
+                SyntaxKind::NEWLINE => {}
+                SyntaxKind::BLANK_LINE => {
+                    if !self.output.is_empty() {
+                        self.output.push('\n');
+                    }
+                }
+                SyntaxKind::ESCAPED_CHAR => {
+                    // Token already includes backslash (e.g., "\*")
+                    self.output.push_str(escaped_char_text(&t, &self.config));
+                }
+                SyntaxKind::NONBREAKING_SPACE => {
+                    // Keep Pandoc escaped-space form for idempotency and losslessness.
+                    self.output.push_str(r"\ ");
+                }
+                SyntaxKind::IMAGE_LINK_START
+                | SyntaxKind::LINK_START
+                | SyntaxKind::LATEX_COMMAND => {
+                    self.output.push_str(t.text());
+                }
+                _ => self.output.push_str(t.text()),
+            },
+        }
+    }
+
+    /// Format a top-level block inside a panic boundary. If formatting panics,
+    /// the formatter state is rolled back, the block is emitted verbatim, and a
+    /// [`BlockFailure`] is recorded instead of unwinding through the caller.
+    fn format_top_level_block_guarded(&mut self, node: &SyntaxNode, indent: usize) {
+        let checkpoint = BlockCheckpoint {
+            output_len: self.output.len(),
+            consecutive_blank_lines: self.consecutive_blank_lines,
+            fenced_div_depth: self.fenced_div_depth,
+            max_marker_widths: self.max_marker_widths.clone(),
+            directive_tracker: self.directive_tracker.clone(),
+            ignore_region_start: self.ignore_region_start,
+            ignore_next_block: self.ignore_next_block,
+            blockquote_context: self.blockquote_context.clone(),
+        };
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            #[cfg(test)]
+            tests::maybe_inject_panic(node);
+            self.format_node_sync(node, indent);
+        }));
+        let Err(payload) = result else {
+            return;
+        };
+
+        self.output.truncate(checkpoint.output_len);
+        self.consecutive_blank_lines = checkpoint.consecutive_blank_lines;
+        self.fenced_div_depth = checkpoint.fenced_div_depth;
+        self.max_marker_widths = checkpoint.max_marker_widths;
+        self.directive_tracker = checkpoint.directive_tracker;
+        self.ignore_region_start = checkpoint.ignore_region_start;
+        self.ignore_next_block = checkpoint.ignore_next_block;
+        self.blockquote_context = checkpoint.blockquote_context;
+
+        let text = node.text().to_string();
+        self.output.push_str(&text);
+        if !text.ends_with('\n') {
+            self.output.push('\n');
+        }
+        // The verbatim block is content, so blank lines after it count afresh.
+        self.consecutive_blank_lines = 0;
+
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+        log::debug!(
+            "Formatting {:?} block at bytes {:?} panicked; emitting it unchanged: {}",
+            node.kind(),
+            node.text_range(),
+            message
+        );
+        self.failures.push(BlockFailure {
+            kind: node.kind(),
+            range: node.text_range().start().into()..node.text_range().end().into(),
+            message,
+        });
+    }
+
     pub(super) fn format_node_sync(&mut self, node: &SyntaxNode, indent: usize) {
+        // The block after `<!-- panache-ignore -->` is preserved exactly.
+        if self.ignore_next_block
+            && !matches!(node.kind(), SyntaxKind::DOCUMENT | SyntaxKind::BLANK_LINE)
+            && extract_directive_from_node(node).is_none()
+        {
+            self.ignore_next_block = false;
+            let text = node.text().to_string();
+            self.output.push_str(&text);
+            if !text.ends_with('\n') {
+                self.output.push('\n');
+            }
+            for directive in crate::directives::collect_inline_directives(node) {
+                self.directive_tracker.process_directive(&directive);
+            }
+            return;
+        }
+
         // Check if formatting is ignored - if so, preserve content exactly
         // Exception: Always process DOCUMENT, COMMENT, and HTML_BLOCK / HTML_BLOCK_DIV nodes (may contain directives)
         if self.directive_tracker.is_formatting_ignored()
@@ -612,16 +1006,22 @@ impl Formatter {
         if matches!(node.kind(), SyntaxKind::PARAGRAPH | SyntaxKind::PLAIN) {
             let inline_directives = crate::directives::collect_inline_directives(node);
             if !inline_directives.is_empty() {
-                let affects_formatting = inline_directives.iter().any(|d| match d {
-                    crate::directives::Directive::Start(kind)
-                    | crate::directives::Directive::End(kind) => kind.affects_formatting(),
-                });
+                let affects_formatting = inline_directives
+                    .iter()
+                    .any(crate::directives::Directive::affects_formatting);
                 if affects_formatting {
                     let text = node.text().to_string();
                     self.output.push_str(&text);
                     if !text.ends_with('\n') {
                         self.output.push('\n');
                     }
+                    // A paragraph holding nothing but `<!-- panache-ignore -->`
+                    // is the directive itself; skip the block after it.
+                    if crate::directives::parse_directive(&text)
+                        == Some(crate::directives::Directive::IgnoreNext)
+                    {
+                        self.ignore_next_block = true;
+                    }
                     for directive in inline_directives {
                         self.directive_tracker.process_directive(&directive);
                     }
@@ -638,42 +1038,17 @@ impl Formatter {
             self.consecutive_blank_lines = 0;
         }
 
+        if self.needs_blank_line_before(node) {
+            self.output.push('\n');
+        }
+        self.pad_blank_lines_before_heading(node);
+
         let line_width = self.config.line_width;
 
         match node.kind() {
             SyntaxKind::DOCUMENT => {
                 for el in node.children_with_tokens() {
-                    match el {
-                        rowan::NodeOrToken::Node(n) => {
-                            // When range filtering is active, only process nodes that overlap
-                            if self.should_process_top_level_node(&n) {
-                                self.format_node_sync(&n, indent);
-                            }
-                        }
-                        rowan::NodeOrToken::Token(t) => match t.kind() {
-                            SyntaxKind::WHITESPACE => {}
-                            SyntaxKind::NEWLINE => {}
-                            SyntaxKind::BLANK_LINE => {
-                                if !self.output.is_empty() {
-                                    self.output.push('\n');
-                                }
-                            }
-                            SyntaxKind::ESCAPED_CHAR => {
-                                // Token already includes backslash (e.g., "\*")
-                                self.output.push_str(t.text());
-                            }
-                            SyntaxKind::NONBREAKING_SPACE => {
-                                // Keep Pandoc escaped-space form for idempotency and losslessness.
-                                self.output.push_str(r"\ ");
-                            }
-                            SyntaxKind::IMAGE_LINK_START
-                            | SyntaxKind::LINK_START
-                            | SyntaxKind::LATEX_COMMAND => {
-                                self.output.push_str(t.text());
-                            }
-                            _ => self.output.push_str(t.text()),
-                        },
-                    }
+                    self.format_document_element(el, indent);
                 }
             }
 
@@ -703,8 +1078,11 @@ impl Formatter {
                 // column — emitting it at column 0 would end the list on
                 // reparse and eject the rest of the item.
                 self.output.push_str(&" ".repeat(indent));
-                self.output
-                    .push_str(&headings::format_heading(node, &self.config));
+                self.output.push_str(&headings::format_heading(
+                    node,
+                    &self.config,
+                    &self.section_numbers,
+                ));
                 self.output.push('\n');
 
                 if let Some(next) = node.next_sibling()
@@ -934,7 +1312,7 @@ impl Formatter {
                         // First paragraph - check if it can go on same line
                         if child.kind() == SyntaxKind::PARAGRAPH {
                             // Calculate how much space is available on first line
-                            let marker_len = marker.len();
+                            let marker_len = marker.width();
                             let first_line_space = self
                                 .config
                                 .line_width
@@ -1141,6 +1519,10 @@ impl Formatter {
                     // Process the directive to update tracker state
                     self.directive_tracker.process_directive(&directive);
 
+                    if directive == crate::directives::Directive::IgnoreNext {
+                        self.ignore_next_block = true;
+                    }
+
                     // Track when we enter an ignore region to preserve content
                     if matches!(directive, crate::directives::Directive::Start(_))
                         && self.directive_tracker.is_formatting_ignored()
@@ -1198,6 +1580,10 @@ impl Formatter {
                     // Process the directive to update tracker state
                     self.directive_tracker.process_directive(&directive);
 
+                    if directive == crate::directives::Directive::IgnoreNext {
+                        self.ignore_next_block = true;
+                    }
+
                     // Track when we enter an ignore region to preserve content
                     if matches!(directive, crate::directives::Directive::Start(_))
                         && self.directive_tracker.is_formatting_ignored()
@@ -1242,17 +1628,40 @@ impl Formatter {
                 }
             }
 
+            SyntaxKind::OBSIDIAN_COMMENT | SyntaxKind::MDX_ESM | SyntaxKind::MDX_FLOW => {
+                // Obsidian `%%` comment blocks and MDX statements and JSX
+                // lines are kept verbatim. Container prefix tokens are
+                // dropped; the enclosing blockquote or list re-emits its own
+                // prefix for each line.
+                for token in node.children_with_tokens().filter_map(|c| c.into_token()) {
+                    if matches!(token.kind(), SyntaxKind::TEXT | SyntaxKind::NEWLINE) {
+                        self.output.push_str(token.text());
+                    }
+                }
+                if !self.output.ends_with('\n') {
+                    self.output.push('\n');
+                }
+                self.consecutive_blank_lines = 0;
+            }
+
             SyntaxKind::BLOCK_QUOTE => {
                 log::trace!("Formatting blockquote");
-                // Determine nesting depth by counting ancestor BlockQuote nodes (including self)
-                let depth = BlockQuote::cast(node.clone())
-                    .map(|bq| bq.depth())
-                    .unwrap_or(1);
+                // Determine nesting depth by counting ancestor BlockQuote nodes (including self).
+                // Quotes outside the enclosing list item are left out: they prefix
+                // the whole list when they re-emit it.
+                let depth = node
+                    .ancestors()
+                    .take_while(|ancestor| ancestor.kind() != SyntaxKind::LIST_ITEM)
+                    .filter(|ancestor| ancestor.kind() == SyntaxKind::BLOCK_QUOTE)
+                    .count();
 
                 // Prefixes for quoted content and blank quoted lines
                 let base_indent = " ".repeat(indent);
-                let content_prefix = format!("{}{}", base_indent, "> ".repeat(depth)); // includes trailing space
+                let quote_style = self.config.blockquotes;
+                // The content prefix ends in `marker-spacing` spaces
+                let content_prefix = format!("{}{}", base_indent, quote_style.prefix(depth));
                 let blank_prefix = content_prefix.trim_end().to_string(); // no trailing space
+                let quote_start = self.output.len();
 
                 // Format children (paragraphs, blank lines) with proper > prefix per depth
                 // NOTE: BlockQuoteMarker tokens are in the tree for losslessness, but we ignore
@@ -1302,8 +1711,22 @@ impl Formatter {
                                     }
                                 }
 
-                                for line in lines_text.lines() {
-                                    self.output.push_str(&content_prefix);
+                                // A source line that does not start with `>` is a lazy
+                                // continuation of the paragraph.
+                                let source = child.text().to_string();
+                                let lazy_lines: Vec<bool> = source
+                                    .lines()
+                                    .enumerate()
+                                    .map(|(i, line)| i > 0 && !line.trim_start().starts_with('>'))
+                                    .collect();
+                                for (i, line) in lines_text.lines().enumerate() {
+                                    if !quote_style.collapse_lazy
+                                        && lazy_lines.get(i).copied().unwrap_or(false)
+                                    {
+                                        self.output.push_str(&base_indent);
+                                    } else {
+                                        self.output.push_str(&content_prefix);
+                                    }
                                     self.output.push_str(line);
                                     self.output.push('\n');
                                 }
@@ -1400,7 +1823,7 @@ impl Formatter {
                                         for line in rendered.lines() {
                                             if line.is_empty() {
                                                 self.output.push_str(&blank_prefix);
-                                            } else if line.starts_with("> ") {
+                                            } else if line.starts_with(&quote_style.marker()) {
                                                 self.output.push_str(&base_indent);
                                                 self.output.push_str(line);
                                             } else {
@@ -1510,7 +1933,9 @@ impl Formatter {
                                 ctx.in_list_continuation = false;
                             }
                         }
-                        SyntaxKind::TEX_BLOCK => {
+                        SyntaxKind::TEX_BLOCK
+                        | SyntaxKind::OBSIDIAN_COMMENT
+                        | SyntaxKind::MDX_FLOW => {
                             // Keep raw TeX content verbatim, but preserve blockquote prefixes.
                             let saved_output = self.output.clone();
                             self.output.clear();
@@ -1579,6 +2004,9 @@ impl Formatter {
                         }
                     }
                 }
+                if quote_style.marker_spacing == 0 {
+                    self.space_indented_quote_lines(quote_start, &content_prefix);
+                }
                 self.blockquote_context = saved_blockquote_context;
             }
 
@@ -1786,9 +2214,10 @@ impl Formatter {
                 log::trace!("Formatting Plain block, text length: {}", text.len());
 
                 let wrap_mode = self.config.wrap.clone().unwrap_or(WrapMode::Reflow);
+                let definition_prefix = self.config.definition_lists.prefix();
                 let needs_indent = indent > 0
                     && (self.output.ends_with('\n') || self.output.is_empty())
-                    && !self.output.ends_with(":   ");
+                    && !self.output.ends_with(&definition_prefix);
                 match wrap_mode {
                     WrapMode::Preserve => {
                         if needs_indent {
@@ -1820,14 +2249,14 @@ impl Formatter {
                     }
                     WrapMode::Reflow => {
                         log::trace!("Reflowing Plain block to {} width", line_width);
-                        let in_definition = self.output.ends_with(":   ");
+                        let in_definition = self.output.ends_with(&definition_prefix);
                         let preserve_ambiguous_definition_emphasis =
                             in_definition && text.contains(r"\|*") && text.contains(".*");
                         let lines = if in_definition {
                             if preserve_ambiguous_definition_emphasis {
                                 text.lines().map(ToString::to_string).collect()
                             } else {
-                                let marker_len = ":   ".len();
+                                let marker_len = definition_prefix.len();
                                 let marker_indent = indent.saturating_sub(4);
                                 let first_line_space =
                                     line_width.saturating_sub(marker_indent + marker_len);
@@ -1860,7 +2289,7 @@ impl Formatter {
                         }
                     }
                     WrapMode::Sentence | WrapMode::Semantic => {
-                        let in_definition = self.output.ends_with(":   ");
+                        let in_definition = self.output.ends_with(&definition_prefix);
                         let preserve_ambiguous_definition_emphasis =
                             in_definition && text.contains(r"\|*") && text.contains(".*");
                         let lines = if preserve_ambiguous_definition_emphasis {
@@ -2016,8 +2445,10 @@ impl Formatter {
                     }
                 }
 
-                let is_compact =
-                    is_compact_by_structure && !has_blank_between_term_and_first_definition;
+                let is_compact = match self.config.definition_lists.compact {
+                    Some(compact) => compact && is_compact_by_structure,
+                    None => is_compact_by_structure && !has_blank_between_term_and_first_definition,
+                };
                 let mut saw_term = false;
 
                 for child in node.children() {
@@ -2072,12 +2503,13 @@ impl Formatter {
                 // Definition content is indented 4 spaces from the margin
                 let def_indent = indent + 4;
                 let wrap_mode = self.config.wrap.clone().unwrap_or(WrapMode::Reflow);
+                let definition_prefix = self.config.definition_lists.prefix();
 
                 // Emit base indentation before the marker
                 if indent > 0 {
                     self.output.push_str(&" ".repeat(indent));
                 }
-                self.output.push_str(":   ");
+                self.output.push_str(&definition_prefix);
 
                 // Collect children to determine lazy continuation
                 let children: Vec<_> = node.children_with_tokens().collect();
@@ -2127,7 +2559,7 @@ impl Formatter {
                             }
                         }
                         NodeOrToken::Token(tok) if tok.kind() == SyntaxKind::DEFINITION_MARKER => {
-                            // Skip - we already added `:   `
+                            // Skip - we already added the configured marker
                         }
                         NodeOrToken::Token(tok) if tok.kind() == SyntaxKind::WHITESPACE => {
                             // Skip - we normalize spacing
@@ -2136,7 +2568,7 @@ impl Formatter {
                             // Handle continuation content with proper indentation
                             match n.kind() {
                                 SyntaxKind::CODE_BLOCK => {
-                                    if self.output.ends_with(":   ") {
+                                    if self.output.ends_with(&definition_prefix) {
                                         self.format_container_code_block(
                                             n, "", def_indent, true, true, false,
                                         );
@@ -2191,7 +2623,7 @@ impl Formatter {
                                 SyntaxKind::PARAGRAPH => {
                                     if first_para_idx == Some(i) {
                                         // First paragraph - lazy continuation (inline, wrapped)
-                                        let marker_len = ":   ".len();
+                                        let marker_len = definition_prefix.len();
                                         let first_line_space = self
                                             .config
                                             .line_width
@@ -2248,14 +2680,14 @@ impl Formatter {
                                     let start = self.output.len();
                                     self.format_node_sync(n, def_indent);
 
-                                    if self.output[..start].ends_with(":   ")
+                                    if self.output[..start].ends_with(&definition_prefix)
                                         && self.output[start..].starts_with(&" ".repeat(def_indent))
                                     {
                                         self.output.drain(start..start + def_indent);
                                     }
                                 }
                                 SyntaxKind::BLOCK_QUOTE => {
-                                    if self.output.ends_with(":   ") {
+                                    if self.output.ends_with(&definition_prefix) {
                                         let mut pieces: Vec<String> = Vec::new();
                                         let block_text = n.text().to_string();
                                         for line in block_text.lines() {
@@ -2271,7 +2703,7 @@ impl Formatter {
                                             }
                                         }
 
-                                        self.output.push_str("> ");
+                                        self.output.push_str(&self.config.blockquotes.marker());
                                         self.output.push_str(&pieces.join(" "));
                                         self.output.push('\n');
 
@@ -2510,11 +2942,10 @@ impl Formatter {
                 let in_list_item = node
                     .ancestors()
                     .any(|ancestor| ancestor.kind() == SyntaxKind::LIST_ITEM);
-                let depth_encoded_colons = 3 + (self.fenced_div_depth * 2);
-                let opening_colons = if in_list_item {
-                    source_opening_colons
-                } else {
-                    depth_encoded_colons
+                let opening_colons = match self.config.div_fence_length {
+                    DivFenceLength::DepthBased if !in_list_item => 3 + (self.fenced_div_depth * 2),
+                    DivFenceLength::DepthBased | DivFenceLength::Preserve => source_opening_colons,
+                    DivFenceLength::Fixed(colons) => colons.max(3),
                 };
                 let colons = ":".repeat(opening_colons);
 
@@ -2808,16 +3239,17 @@ impl Formatter {
                 if self.output.is_empty() {
                     return;
                 }
-                // Limit consecutive blank lines to 1
-                if self.consecutive_blank_lines < 1 {
+                // Limit consecutive blank lines per `blank-lines`
+                if self.consecutive_blank_lines < self.config.max_blank_lines() {
                     self.output.push('\n');
                     self.consecutive_blank_lines += 1;
                 }
             }
 
             SyntaxKind::EMPHASIS => {
-                // Normalize emphasis to always use single asterisks
-                self.output.push('*');
+                // Normalize the delimiter to the configured `emphasis-marker`
+                let delimiter = emphasis_delimiter(node, &self.config);
+                self.output.push_str(delimiter);
                 for child in node.children_with_tokens() {
                     match child {
                         rowan::NodeOrToken::Node(n) => self.format_node_sync(&n, indent),
@@ -2828,12 +3260,13 @@ impl Formatter {
                         }
                     }
                 }
-                self.output.push('*');
+                self.output.push_str(delimiter);
             }
 
             SyntaxKind::STRONG => {
-                // Normalize strong emphasis to always use double asterisks
-                self.output.push_str("**");
+                // Normalize the delimiter to the configured `strong-marker`
+                let delimiter = emphasis_delimiter(node, &self.config);
+                self.output.push_str(delimiter);
                 for child in node.children_with_tokens() {
                     match child {
                         rowan::NodeOrToken::Node(n) => self.format_node_sync(&n, indent),
@@ -2844,7 +3277,7 @@ impl Formatter {
                         }
                     }
                 }
-                self.output.push_str("**");
+                self.output.push_str(delimiter);
             }
 
             SyntaxKind::STRIKEOUT => {
@@ -3027,6 +3460,19 @@ impl Formatter {
 /// Render a `MYST_DIRECTIVE_OPTION` node in canonical form: `:name: value`, or
 /// `:name:` when the option has no value. A single space follows the closing
 /// colon so the output re-parses to the same option CST (idempotency).
+/// Whether `node` sits at the document level or in fenced divs there, where
+/// extra blank lines between blocks do not change the structure.
+fn is_separable_block(node: &SyntaxNode) -> bool {
+    node.parent().is_some_and(|parent| {
+        parent.ancestors().all(|ancestor| {
+            matches!(
+                ancestor.kind(),
+                SyntaxKind::DOCUMENT | SyntaxKind::FENCED_DIV
+            )
+        })
+    })
+}
+
 fn format_directive_option(node: &SyntaxNode) -> String {
     let mut name = String::new();
     let mut value = String::new();
@@ -3101,3 +3547,59 @@ pub(super) fn normalize_span_attributes(node: &SyntaxNode) -> String {
     let joined = inner.split_whitespace().collect::<Vec<_>>().join(" ");
     format!("{{{joined}}}")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    thread_local! {
+        static PANIC_ON_KIND: Cell<Option<SyntaxKind>> = const { Cell::new(None) };
+    }
+
+    /// Simulate a formatter bug on top-level blocks of the configured kind.
+    pub(super) fn maybe_inject_panic(node: &SyntaxNode) {
+        if PANIC_ON_KIND.with(Cell::get) == Some(node.kind()) {
+            panic!("injected panic");
+        }
+    }
+
+    fn format_panicking_on(
+        kind: SyntaxKind,
+        input: &str,
+        fail_safe: bool,
+    ) -> (String, Vec<BlockFailure>) {
+        let config = Config {
+            fail_safe,
+            ..Default::default()
+        };
+        let tree = crate::parser::parse(input, Some(config.parser_options()));
+        PANIC_ON_KIND.with(|k| k.set(Some(kind)));
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            Formatter::new(config, FormattedCodeMap::new(), None).format_with_failures(&tree)
+        }));
+        PANIC_ON_KIND.with(|k| k.set(None));
+        result.unwrap_or_else(|payload| std::panic::resume_unwind(payload))
+    }
+
+    #[test]
+    fn fail_safe_emits_panicking_block_verbatim() {
+        let input = "First   paragraph.\n\n#   Heading   #\n\nSecond   paragraph.\n";
+        let (output, failures) = format_panicking_on(SyntaxKind::HEADING, input, true);
+
+        assert_eq!(
+            output,
+            "First paragraph.\n\n#   Heading   #\n\nSecond paragraph.\n"
+        );
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].kind, SyntaxKind::HEADING);
+        assert!(input[failures[0].range.clone()].starts_with("#   Heading   #"));
+        assert_eq!(failures[0].message, "injected panic");
+    }
+
+    #[test]
+    #[should_panic(expected = "injected panic")]
+    fn panics_propagate_without_fail_safe() {
+        format_panicking_on(SyntaxKind::HEADING, "# Heading\n", false);
+    }
+}
@@ -0,0 +1,85 @@
+//! Delimiters for emphasis and strong emphasis (`emphasis-marker` and
+//! `strong-marker`).
+//!
+//! Asterisks work everywhere, underscores do not: an underscore next to a
+//! letter or digit cannot open or close emphasis (`snake_case`), and runs of
+//! the same delimiter around directly nested spans (`___x___`) are resolved
+//! differently by Pandoc and CommonMark. In those positions the span keeps
+//! asterisks, even under `underscore`.
+
+use crate::config::{Config, EmphasisMarker};
+use crate::syntax::{SyntaxKind, SyntaxNode};
+use rowan::NodeOrToken;
+
+/// Opening (and closing) delimiter for an `EMPHASIS` or `STRONG` node.
+pub(super) fn emphasis_delimiter(node: &SyntaxNode, config: &Config) -> &'static str {
+    let strong = node.kind() == SyntaxKind::STRONG;
+    let style = if strong {
+        config.strong_marker
+    } else {
+        config.emphasis_marker
+    };
+    let underscore = match style {
+        EmphasisMarker::Asterisk => false,
+        EmphasisMarker::Underscore => underscore_is_safe(node, config),
+        EmphasisMarker::Preserve => written_with_underscore(node),
+    };
+    match (strong, underscore) {
+        (false, false) => "*",
+        (false, true) => "_",
+        (true, false) => "**",
+        (true, true) => "__",
+    }
+}
+
+fn is_marker(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::EMPHASIS_MARKER | SyntaxKind::STRONG_MARKER
+    )
+}
+
+fn is_emphasis(kind: SyntaxKind) -> bool {
+    matches!(kind, SyntaxKind::EMPHASIS | SyntaxKind::STRONG)
+}
+
+fn written_with_underscore(node: &SyntaxNode) -> bool {
+    node.children_with_tokens()
+        .find(|child| is_marker(child.kind()))
+        .and_then(|marker| marker.into_token())
+        .is_some_and(|marker| marker.text().starts_with('_'))
+}
+
+/// Whether `_` delimiters around `node` parse back to the same span.
+fn underscore_is_safe(node: &SyntaxNode, config: &Config) -> bool {
+    let blocks_underscore = |c: char| c.is_alphanumeric() || c == '_';
+
+    let before = match node.prev_sibling_or_token() {
+        Some(NodeOrToken::Token(token)) if is_marker(token.kind()) => return false,
+        Some(NodeOrToken::Token(token)) => token.text().chars().next_back(),
+        Some(NodeOrToken::Node(prev)) => prev.text().to_string().chars().next_back(),
+        None => None,
+    };
+    let after = match node.next_sibling_or_token() {
+        Some(NodeOrToken::Token(token)) if is_marker(token.kind()) => return false,
+        Some(NodeOrToken::Token(token)) => token.text().chars().next(),
+        Some(NodeOrToken::Node(next)) => next.text().to_string().chars().next(),
+        None => None,
+    };
+    if before.is_some_and(blocks_underscore) || after.is_some_and(blocks_underscore) {
+        return false;
+    }
+
+    // A span nested flush against this one's delimiters must not use
+    // underscores too. (Under `underscore` it never does: its neighbour is
+    // this span's marker.)
+    let content: Vec<_> = node
+        .children_with_tokens()
+        .filter(|child| !is_marker(child.kind()))
+        .collect();
+    ![content.first(), content.last()]
+        .into_iter()
+        .flatten()
+        .filter_map(|child| child.as_node().filter(|n| is_emphasis(n.kind())).cloned())
+        .any(|child| emphasis_delimiter(&child, config).starts_with('_'))
+}
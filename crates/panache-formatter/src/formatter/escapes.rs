@@ -0,0 +1,69 @@
+//! Backslash escapes under `escaping = "minimal"`.
+//!
+//! An escaped character (`\,`, `\.`) is written without its backslash when
+//! the bare character reads back as the same literal text wherever it sits.
+//! Only punctuation that never starts inline syntax qualifies, and `.`, `)`,
+//! `(`, `!` and `&` keep their escape next to the characters that would turn
+//! them into a list marker, link, image or entity. Characters that can open a
+//! block at the start of a line (`#`, `-`, `+`, `>`, `:`, ...) always keep it,
+//! since wrapping may move them there.
+
+use crate::config::{Config, Escaping};
+use crate::syntax::SyntaxToken;
+
+/// Text to emit for an `ESCAPED_CHAR` token.
+pub(super) fn escaped_char_text<'a>(token: &'a SyntaxToken, config: &Config) -> &'a str {
+    let text = token.text();
+    if config.escaping == Escaping::Preserve {
+        return text;
+    }
+    let Some(escaped) = text.strip_prefix('\\') else {
+        return text;
+    };
+    let mut chars = escaped.chars();
+    let (Some(c), None) = (chars.next(), chars.next()) else {
+        return text;
+    };
+    if escape_is_needed(token, c, config) {
+        text
+    } else {
+        escaped
+    }
+}
+
+fn escape_is_needed(token: &SyntaxToken, c: char, config: &Config) -> bool {
+    let before = token
+        .prev_token()
+        .and_then(|prev| prev.text().chars().next_back());
+    let after = token
+        .next_token()
+        .and_then(|next| next.text().chars().next());
+    let smart = config.formatter_extensions.smart;
+    match c {
+        ',' | ';' | '?' | '/' => false,
+        // `1.`/`1)` is an ordered list marker; `...` is an ellipsis under `smart`.
+        '.' => {
+            before.is_some_and(|b| b.is_ascii_digit())
+                || (smart && (before == Some('.') || after == Some('.')))
+        }
+        ')' => before.is_some_and(|b| b.is_ascii_digit()),
+        // `[text](url)` is a link, `![` an image, `&amp;` an entity.
+        '(' => before == Some(']'),
+        '!' => after == Some('['),
+        '&' => token
+            .next_token()
+            .is_some_and(|next| starts_entity_body(next.text())),
+        // Escaped quotes stay straight under `smart`.
+        '\'' | '"' => smart,
+        _ => true,
+    }
+}
+
+/// Whether `text` starts with the `name;` or `#123;` that follows the `&` of
+/// an entity.
+fn starts_entity_body(text: &str) -> bool {
+    let body_len = text
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '#'))
+        .unwrap_or(text.len());
+    body_len > 0 && text[body_len..].starts_with(';')
+}
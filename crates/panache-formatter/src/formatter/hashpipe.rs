@@ -11,8 +11,11 @@ use crate::syntax::{
 use crate::yaml_engine;
 use panache_parser::parser::utils::chunk_options::ChunkOptionValue;
 use panache_parser::parser::utils::chunk_options::hashpipe_comment_prefix;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
 use super::code_blocks::ChunkOptionRepr;
+use super::text_width::prefix_len_within_width;
 
 /// The physical-line count of a code block's hashpipe preamble
 /// (`HASHPIPE_YAML_CONTENT`), or `None` when the block has no preamble. Used to
@@ -620,14 +623,6 @@ pub fn format_hashpipe_option_with_wrap(
     value: &str,
     line_width: usize,
 ) -> Vec<String> {
-    fn floor_char_boundary(s: &str, max: usize) -> usize {
-        let mut idx = max.min(s.len());
-        while idx > 0 && !s.is_char_boundary(idx) {
-            idx -= 1;
-        }
-        idx
-    }
-
     if let Some((first, rest)) = value.split_once('\n')
         && is_yaml_block_scalar_indicator(first)
     {
@@ -647,14 +642,14 @@ pub fn format_hashpipe_option_with_wrap(
 
     let first_line = format!("{} {}: {}", prefix, key, value);
 
-    // Check if wrapping is needed
-    if first_line.len() <= line_width {
+    // Check if wrapping is needed (in display columns)
+    if first_line.width() <= line_width {
         return vec![first_line];
     }
 
     // Calculate available space
     let first_prefix = format!("{} {}: ", prefix, key);
-    let available_first = line_width.saturating_sub(first_prefix.len());
+    let available_first = line_width.saturating_sub(first_prefix.width());
 
     // If even the prefix is too long, return as-is (don't break mid-word)
     if available_first < 10 {
@@ -662,7 +657,7 @@ pub fn format_hashpipe_option_with_wrap(
     }
 
     let continuation_prefix = format!("{}   ", prefix); // 3 spaces after prefix
-    let available_continuation = line_width.saturating_sub(continuation_prefix.len());
+    let available_continuation = line_width.saturating_sub(continuation_prefix.width());
 
     let mut lines = Vec::new();
     let mut remaining = value;
@@ -676,14 +671,14 @@ pub fn format_hashpipe_option_with_wrap(
         };
 
         // Find word boundary at or before available length
-        let break_point = if remaining.len() <= available {
+        let break_point = if remaining.width() <= available {
             remaining.len()
         } else {
             // Find last space before or at available
-            let upper = floor_char_boundary(remaining, available);
+            let upper = prefix_len_within_width(remaining, available);
             if upper == 0 {
                 remaining
-                    .char_indices()
+                    .grapheme_indices(true)
                     .nth(1)
                     .map(|(i, _)| i)
                     .unwrap_or(remaining.len())
@@ -963,6 +958,25 @@ mod tests {
         assert!(lines[1].starts_with("#|   "));
     }
 
+    #[test]
+    fn test_format_hashpipe_option_wrap_measures_display_width() {
+        // Each CJK character takes two columns: 30 of them overflow 40.
+        let value = "日本語のキャプション".repeat(3);
+        let lines = format_hashpipe_option_with_wrap("#|", "fig-cap", &value, 40);
+
+        assert!(lines.len() > 1, "Should wrap into multiple lines");
+        assert!(lines.iter().all(|line| line.width() <= 40), "{lines:?}");
+        assert_eq!(
+            lines
+                .iter()
+                .map(|line| line
+                    .trim_start_matches("#| fig-cap: ")
+                    .trim_start_matches("#|   "))
+                .collect::<String>(),
+            value
+        );
+    }
+
     #[test]
     fn test_format_hashpipe_option_block_scalar() {
         let value = "|\n   A caption\n   spanning lines";
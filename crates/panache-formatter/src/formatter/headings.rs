@@ -1,10 +1,104 @@
-use rowan::NodeOrToken;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use rowan::ast::AstNode;
+use rowan::{NodeOrToken, TextRange};
 
 use super::core::normalize_attribute_text;
 use super::inline::format_inline_node;
 use super::smart::normalize_smart_punctuation;
-use crate::config::Config;
-use crate::syntax::{SyntaxKind, SyntaxNode};
+use crate::config::{Config, HeadingNumbering};
+use crate::syntax::{Heading, SyntaxKind, SyntaxNode};
+
+/// Section numbers written into heading text (`## 2.3 Methods`), keyed by
+/// heading range, under `headings.numbering`. Each value replaces the
+/// heading's number: the recomputed number under `renumber`, an empty string
+/// under `strip`. Empty under `preserve`.
+///
+/// Only headings that already start with a number take part, and headings
+/// marked `{-}` or `{.unnumbered}` are left alone. Numbers follow heading
+/// levels, counted from the shallowest numbered level, so a section moved or
+/// promoted gets the number of its new place.
+pub(super) fn section_numbers(root: &SyntaxNode, config: &Config) -> HashMap<TextRange, String> {
+    let mut numbers = HashMap::new();
+    if config.heading_numbering == HeadingNumbering::Preserve {
+        return numbers;
+    }
+
+    let numbered: Vec<(Heading, bool)> = root
+        .descendants()
+        .filter_map(Heading::cast)
+        .filter(|heading| !is_unnumbered(heading.syntax()))
+        .filter_map(|heading| {
+            let text = heading.text();
+            let (_, trailing_dot, _) = split_section_number(text.trim_start())?;
+            Some((heading, trailing_dot))
+        })
+        .collect();
+    let Some(top_level) = numbered.iter().map(|(heading, _)| heading.level()).min() else {
+        return numbers;
+    };
+
+    let mut counters = [0usize; 6];
+    for (heading, trailing_dot) in numbered {
+        if config.heading_numbering == HeadingNumbering::Strip {
+            numbers.insert(heading.text_range(), String::new());
+            continue;
+        }
+        let depth = heading.level().saturating_sub(top_level).min(5);
+        counters[depth] += 1;
+        counters[depth + 1..].fill(0);
+        let mut number = counters[..=depth]
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(".");
+        if trailing_dot {
+            number.push('.');
+        }
+        numbers.insert(heading.text_range(), number);
+    }
+    numbers
+}
+
+/// Split `2.3 Methods` or `2.3. Methods` into the number, whether it ends in
+/// a dot, and the title. The title must be non-empty.
+fn split_section_number(text: &str) -> Option<(&str, bool, &str)> {
+    let (number, title) = text.split_once([' ', '\t'])?;
+    let title = title.trim_start();
+    let (digits, trailing_dot) = match number.strip_suffix('.') {
+        Some(digits) => (digits, true),
+        None => (number, false),
+    };
+    let is_number = !digits.is_empty()
+        && digits
+            .split('.')
+            .all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()));
+    (is_number && !title.is_empty()).then_some((digits, trailing_dot, title))
+}
+
+fn is_unnumbered(heading: &SyntaxNode) -> bool {
+    heading
+        .children()
+        .filter(|child| child.kind() == SyntaxKind::ATTRIBUTE)
+        .any(|attribute| {
+            let text = attribute.text().to_string();
+            text.trim_start_matches('{')
+                .trim_end_matches('}')
+                .split_whitespace()
+                .any(|part| part == "-" || part == ".unnumbered")
+        })
+}
+
+/// `content` with its section number replaced by `number` (or removed when
+/// `number` is empty).
+pub(super) fn replace_section_number<'a>(content: &'a str, number: &str) -> Cow<'a, str> {
+    match split_section_number(content) {
+        Some((_, _, title)) if number.is_empty() => Cow::Borrowed(title),
+        Some((_, _, title)) => Cow::Owned(format!("{number} {title}")),
+        None => Cow::Borrowed(content),
+    }
+}
 
 /// Render a single heading line (`### content {attrs}`), formatting inline
 /// elements and normalizing attributes. Surrounding blank lines and the
@@ -15,7 +109,11 @@ use crate::syntax::{SyntaxKind, SyntaxNode};
 /// inline content (code spans, links, emphasis) and attributes are reformatted
 /// identically regardless of where the heading appears. The `#` prefix means
 /// the rendered line can never collide with a thematic break.
-pub(super) fn format_heading(node: &SyntaxNode, config: &Config) -> String {
+pub(super) fn format_heading(
+    node: &SyntaxNode,
+    config: &Config,
+    section_numbers: &HashMap<TextRange, String>,
+) -> String {
     let mut level = 1;
     let mut attributes = String::new();
     let mut content = String::new();
@@ -69,11 +167,15 @@ pub(super) fn format_heading(node: &SyntaxNode, config: &Config) -> String {
     let content = content
         .trim_end_matches(|c: char| c == '#' || c.is_whitespace())
         .trim_start();
+    let content = match section_numbers.get(&node.text_range()) {
+        Some(number) => replace_section_number(content, number),
+        None => Cow::Borrowed(content),
+    };
 
     let mut out = "#".repeat(level);
     if !content.is_empty() {
         out.push(' ');
-        out.push_str(content);
+        out.push_str(&content);
     }
     if !attributes.is_empty() {
         out.push(' ');
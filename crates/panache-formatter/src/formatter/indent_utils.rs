@@ -4,6 +4,7 @@
 //! including marker alignment, spacing, and checkbox handling.
 
 use crate::syntax::{SyntaxKind, SyntaxNode};
+use unicode_width::UnicodeWidthStr;
 
 /// Column a lazy continuation line should start at to stay aligned with the
 /// list item containing `offset`.
@@ -133,7 +134,7 @@ pub(super) fn calculate_list_item_indent(
 
     // Calculate marker padding (for right-alignment)
     let marker_padding = if is_alignable && max_marker_width > 0 {
-        max_marker_width.saturating_sub(marker.len())
+        max_marker_width.saturating_sub(marker.width())
     } else {
         0
     };
@@ -158,7 +159,7 @@ pub(super) fn calculate_list_item_indent(
 
     ListItemIndent {
         marker_padding,
-        marker_width: marker.len(),
+        marker_width: marker.width(),
         spaces_after,
         checkbox_width,
         four_space_rule,
@@ -1,5 +1,7 @@
-use crate::config::{Config, MathDelimiterStyle};
+use crate::config::{BareUrls, Config, LinkDestinations, MathDelimiterStyle};
 use crate::formatter::core::{normalize_attribute_text, normalize_span_attributes};
+use crate::formatter::emphasis::emphasis_delimiter;
+use crate::formatter::link_dest::{rewrite_link_dest, rewrite_url};
 use crate::formatter::math::{self, MathContext, MathFormatOptions};
 use crate::formatter::shortcodes::format_shortcode;
 use crate::formatter::smart::normalize_smart_punctuation;
@@ -112,9 +114,55 @@ fn collapse_internal_newlines(text: &str) -> std::borrow::Cow<'_, str> {
     std::borrow::Cow::Owned(out)
 }
 
+/// Whether `node` is a bare URI (an `AUTO_LINK` with no `<`/`>` markers) that
+/// `bare-urls` may rewrite. Bare URIs inside link text or image alt text stay
+/// as written: turning them into links would nest one link in another.
+fn is_rewritable_bare_url(node: &SyntaxNode) -> bool {
+    !node
+        .children_with_tokens()
+        .any(|child| child.kind() == SyntaxKind::AUTO_LINK_MARKER)
+        && !node
+            .ancestors()
+            .any(|a| matches!(a.kind(), SyntaxKind::LINK_TEXT | SyntaxKind::IMAGE_ALT))
+}
+
+/// A bare URL written as an autolink or an inline link.
+fn format_bare_url(url: &str, mode: BareUrls) -> String {
+    match mode {
+        BareUrls::Preserve => url.to_string(),
+        BareUrls::Autolink => format!("<{url}>"),
+        BareUrls::Link => {
+            // `_` stays bare: the formatter writes `\_` in link text as `_`, so
+            // escaping it here would not survive a second pass.
+            let mut text = String::with_capacity(url.len());
+            for c in url.chars() {
+                if matches!(c, '\\' | '[' | ']' | '*' | '`') {
+                    text.push('\\');
+                }
+                text.push(c);
+            }
+            format!(
+                "[{text}]({})",
+                rewrite_url(url, LinkDestinations::AngleBrackets)
+            )
+        }
+    }
+}
+
 /// Format an inline node to normalized string (e.g., emphasis with asterisks)
 pub(super) fn format_inline_node(node: &SyntaxNode, config: &Config) -> String {
     match node.kind() {
+        SyntaxKind::AUTO_LINK
+            if config.bare_urls != BareUrls::Preserve && is_rewritable_bare_url(node) =>
+        {
+            let url: String = node
+                .children_with_tokens()
+                .filter_map(|child| child.into_token())
+                .filter(|tok| tok.kind() == SyntaxKind::TEXT)
+                .map(|tok| tok.text().to_string())
+                .collect();
+            format_bare_url(&url, config.bare_urls)
+        }
         SyntaxKind::AUTO_LINK => {
             let mut result = String::new();
             let mut skip_marker_whitespace = false;
@@ -323,7 +371,8 @@ pub(super) fn format_inline_node(node: &SyntaxNode, config: &Config) -> String {
             }
             // Trim leading and trailing whitespace from emphasis content
             let content = content.trim();
-            format!("*{}*", content)
+            let delimiter = emphasis_delimiter(node, config);
+            format!("{delimiter}{content}{delimiter}")
         }
         SyntaxKind::STRONG => {
             let mut content = String::new();
@@ -356,7 +405,8 @@ pub(super) fn format_inline_node(node: &SyntaxNode, config: &Config) -> String {
             }
             // Trim leading and trailing whitespace from strong emphasis content
             let content = content.trim();
-            format!("**{}**", content)
+            let delimiter = emphasis_delimiter(node, config);
+            format!("{delimiter}{content}{delimiter}")
         }
         SyntaxKind::INLINE_HTML_SPAN => {
             // Inline `<span ...>...</span>` lift (Pandoc dialect). The open
@@ -716,6 +766,26 @@ pub(super) fn format_inline_node(node: &SyntaxNode, config: &Config) -> String {
             format!("^[{}]", normalized)
         }
         SyntaxKind::CITATION | SyntaxKind::CROSSREF => format_citation_like(node, config),
+        SyntaxKind::LINK | SyntaxKind::IMAGE_LINK
+            if config.link_destinations != LinkDestinations::Preserve =>
+        {
+            // Links kept on one line are otherwise emitted as written; only
+            // the destination is rewritten.
+            let mut result = String::new();
+            for child in node.children_with_tokens() {
+                match child {
+                    NodeOrToken::Node(n) if n.kind() == SyntaxKind::LINK_DEST => {
+                        result.push_str(&rewrite_link_dest(
+                            &n.text().to_string(),
+                            config.link_destinations,
+                        ));
+                    }
+                    NodeOrToken::Node(n) => result.push_str(&n.text().to_string()),
+                    NodeOrToken::Token(t) => result.push_str(t.text()),
+                }
+            }
+            result
+        }
         _ => {
             // For other inline nodes, just return their text
             node.text().to_string()
@@ -1,9 +1,13 @@
-use crate::config::{Config, Dialect};
+use crate::config::{BreakableInline, Config, Dialect, LinkDestinations};
+use crate::formatter::emphasis::emphasis_delimiter;
+use crate::formatter::escapes::escaped_char_text;
+use crate::formatter::link_dest;
 use crate::formatter::sentence_wrap::{
     ResolvedProfile, SentenceBoundaryClass, SentenceLanguage, SentenceSegment,
     is_sentence_boundary_segment, resolve_profile,
 };
 use crate::formatter::smart::normalize_smart_punctuation;
+use crate::formatter::wrapping::{can_break_between, cjk_break_segments};
 use crate::syntax::{SyntaxKind, SyntaxNode};
 use rowan::NodeOrToken;
 use std::borrow::Cow;
@@ -154,7 +158,17 @@ fn ends_with_ascii_whitespace(text: &str) -> bool {
         .is_some_and(|c| c.is_ascii_whitespace())
 }
 
-fn append_normalized_link_dest(dest: &str, out: &mut String) {
+fn append_normalized_link_dest(dest: &str, out: &mut String, config: &Config) {
+    if config.link_destinations != LinkDestinations::Preserve {
+        let (url, title) = link_dest::split_link_dest(dest);
+        out.push_str(&link_dest::rewrite_url(url, config.link_destinations));
+        if let Some(title) = title {
+            out.push(' ');
+            append_normalized_link_title(title, out);
+        }
+        return;
+    }
+
     let dest_trimmed = dest.trim();
     let mut split_at = None;
     for (i, ch) in dest_trimmed.char_indices() {
@@ -178,6 +192,10 @@ fn append_normalized_link_dest(dest: &str, out: &mut String) {
 
     out.push_str(url);
     out.push(' ');
+    append_normalized_link_title(title, out);
+}
+
+fn append_normalized_link_title(title: &str, out: &mut String) {
     if title.starts_with('\'') && title.ends_with('\'') && title.len() >= 2 {
         out.push('"');
         out.push_str(&title[1..title.len() - 1]);
@@ -212,6 +230,12 @@ fn normalize_inline_for_sentence<'a>(text: &'a str) -> Cow<'a, str> {
     }
 }
 
+/// Push an inline construct the wrapper must not break inside: its soft
+/// breaks become spaces within a single piece.
+fn push_atomic_inline(sink: &mut TraversalBuilder<'_>, formatted: &str) {
+    sink.push_piece(normalize_inline_for_sentence(formatted).as_ref());
+}
+
 fn should_merge_initialism_year(left: &str, left_ws_after: bool, right: &str) -> bool {
     left_ws_after && is_initialism_with_periods(left) && is_year_like(right)
 }
@@ -248,6 +272,9 @@ pub(super) struct NodeWrapOptions<'a> {
     /// to the breaks `mode` produces. Set by the `Semantic` wrap mode, which
     /// layers sembr break-preservation on top of the sentence-break path.
     pub preserve_newlines: bool,
+    /// Split a plain word wider than the line across lines instead of letting
+    /// it overflow (`max_width_strict`). Reflow only.
+    pub split_long_words: bool,
 }
 
 impl<'a> NodeWrapOptions<'a> {
@@ -261,6 +288,7 @@ impl<'a> NodeWrapOptions<'a> {
             avoid_blockquote_line_start: false,
             avoid_heading_line_start: false,
             preserve_newlines: false,
+            split_long_words: false,
         }
     }
 
@@ -277,6 +305,7 @@ impl<'a> NodeWrapOptions<'a> {
             avoid_blockquote_line_start: true,
             avoid_heading_line_start: true,
             preserve_newlines: false,
+            split_long_words: false,
         }
     }
 
@@ -304,6 +333,7 @@ impl WrapStrategy {
             Self::ParagraphReflow => NodeWrapOptions {
                 avoid_unsafe_line_start: avoid_unsafe_in_paragraph_reflow,
                 avoid_blockquote_line_start: avoid_blockquote_start,
+                split_long_words: config.max_width_strict,
                 ..NodeWrapOptions::reflow(widths)
             },
             Self::ParagraphSentence => NodeWrapOptions::sentence(),
@@ -312,6 +342,7 @@ impl WrapStrategy {
                 strip_standalone_blockquote_markers: in_blockquote,
                 avoid_unsafe_line_start: true,
                 avoid_blockquote_line_start: avoid_blockquote_start,
+                split_long_words: config.max_width_strict,
                 ..NodeWrapOptions::reflow(widths)
             },
             // `sentence()` already guards every block-start token, so list items
@@ -373,6 +404,17 @@ fn is_definition_marker_piece(piece: &str) -> bool {
     piece == ":"
 }
 
+// A footnote reference glued to a colon (`[^note]:`) reads as a footnote
+// definition at a line start, and footnote definitions interrupt paragraphs in
+// every flavor, so a break before it would split the paragraph on the next pass.
+fn is_footnote_definition_marker_piece(piece: &str) -> bool {
+    let Some(rest) = piece.strip_prefix("[^") else {
+        return false;
+    };
+    rest.find(']')
+        .is_some_and(|close| close > 0 && rest[close + 1..].starts_with(':'))
+}
+
 fn is_bullet_list_marker_piece(piece: &str) -> bool {
     matches!(piece, "+" | "-" | "*")
 }
@@ -472,6 +514,7 @@ struct StreamingCoreSink<'a> {
     avoid_unsafe_line_start: bool,
     avoid_blockquote_line_start: bool,
     avoid_heading_line_start: bool,
+    split_long_words: bool,
 }
 
 impl<'a> StreamingCoreSink<'a> {
@@ -485,6 +528,7 @@ impl<'a> StreamingCoreSink<'a> {
         avoid_unsafe_line_start: bool,
         avoid_blockquote_line_start: bool,
         avoid_heading_line_start: bool,
+        split_long_words: bool,
     ) -> Self {
         Self {
             default_line_width: line_widths.last().copied().unwrap_or(0),
@@ -502,15 +546,17 @@ impl<'a> StreamingCoreSink<'a> {
             avoid_unsafe_line_start,
             avoid_blockquote_line_start,
             avoid_heading_line_start,
+            split_long_words,
         }
     }
 
     /// Whether `text` at the start of a line would open a block-level construct
-    /// (list item, blockquote, heading, rule, or definition) and split the
-    /// paragraph. Each category is gated by its `avoid_*` flag; `:` is always
-    /// unsafe.
+    /// (list item, blockquote, heading, rule, definition, or footnote
+    /// definition) and split the paragraph. Each category is gated by its
+    /// `avoid_*` flag; `:` and `[^note]:` are always unsafe.
     fn piece_would_start_unsafe_line(&self, text: &str) -> bool {
         is_definition_marker_piece(text)
+            || is_footnote_definition_marker_piece(text)
             || (self.avoid_blockquote_line_start && is_unsafe_block_line_start_piece(text))
             || (self.avoid_unsafe_line_start && is_unsafe_list_line_start_piece(text))
             || (self.avoid_heading_line_start
@@ -525,11 +571,14 @@ impl<'a> StreamingCoreSink<'a> {
     ) {
         let piece_width = UnicodeWidthStr::width(segment.text.as_str());
         if !self.sentence_mode {
-            let width_limit = self
-                .line_widths
-                .get(self.out.len())
-                .copied()
-                .unwrap_or(self.default_line_width);
+            let width_limit = self.width_limit();
+            if self.split_long_words
+                && piece_width > width_limit
+                && is_splittable_word(&segment.text)
+            {
+                self.consume_long_word(segment);
+                return;
+            }
             let spacer_width = usize::from(self.line_has_piece && self.prev_ws_after);
             let would_start_line_with_unsafe_piece =
                 self.prev_ws_after && self.piece_would_start_unsafe_line(segment.text.as_str());
@@ -566,6 +615,62 @@ impl<'a> StreamingCoreSink<'a> {
         }
     }
 
+    fn width_limit(&self) -> usize {
+        self.line_widths
+            .get(self.out.len())
+            .copied()
+            .unwrap_or(self.default_line_width)
+    }
+
+    /// Lay out a word wider than the line in chunks: the first fills what is
+    /// left of the current line, the rest take whole lines. A chunk never
+    /// starts a line with anything but a letter or digit, so no break can open
+    /// a block; a word with no such split point overflows as before.
+    fn consume_long_word(&mut self, segment: SentenceSegment) {
+        let mut rest = segment.text.as_str();
+        loop {
+            let spacer_width = usize::from(self.line_has_piece && self.prev_ws_after);
+            let room = self
+                .width_limit()
+                .saturating_sub(self.line_width + spacer_width);
+            let split = if UnicodeWidthStr::width(rest) <= room {
+                None
+            } else {
+                long_word_split_point(rest, room)
+                    .filter(|&idx| !self.piece_would_start_unsafe_line(&rest[idx..]))
+            };
+            let Some(idx) = split else {
+                if self.line_has_piece && UnicodeWidthStr::width(rest) > room {
+                    // No split fits after the current content; retry on a
+                    // fresh line.
+                    self.out.push(std::mem::take(&mut self.line));
+                    self.line_width = 0;
+                    self.line_has_piece = false;
+                    self.prev_ws_after = false;
+                    continue;
+                }
+                if spacer_width == 1 {
+                    self.line.push(' ');
+                    self.line_width += 1;
+                }
+                self.line.push_str(rest);
+                self.line_width += UnicodeWidthStr::width(rest);
+                self.line_has_piece = true;
+                self.prev_ws_after = segment.has_whitespace_after;
+                return;
+            };
+            if spacer_width == 1 {
+                self.line.push(' ');
+            }
+            self.line.push_str(&rest[..idx]);
+            self.out.push(std::mem::take(&mut self.line));
+            self.line_width = 0;
+            self.line_has_piece = false;
+            self.prev_ws_after = false;
+            rest = &rest[idx..];
+        }
+    }
+
     fn emit_piece(&mut self, piece: String, ws_after: bool) {
         self.emit_piece_with_boundary(piece, ws_after, SentenceBoundaryClass::Normal);
     }
@@ -630,6 +735,34 @@ impl<'a> StreamingCoreSink<'a> {
     }
 }
 
+/// Whether `piece` is a plain word that can be split across lines without
+/// changing its markup: letters, digits, and punctuation that carries no inline
+/// syntax. URLs stay whole, since splitting one would break the autolink.
+fn is_splittable_word(piece: &str) -> bool {
+    !piece.contains("://")
+        && !piece.starts_with("www.")
+        && piece.chars().all(|c| {
+            c.is_alphanumeric() || matches!(c, '-' | '.' | ',' | ';' | '/' | '?' | '!' | '%' | '\'')
+        })
+}
+
+/// Byte offset of the last split point in `word` whose head fits in `room`
+/// columns and whose tail starts with a letter or digit.
+fn long_word_split_point(word: &str, room: usize) -> Option<usize> {
+    let mut width = 0;
+    let mut best = None;
+    for (idx, c) in word.char_indices() {
+        if idx > 0 && c.is_alphanumeric() {
+            best = Some(idx);
+        }
+        width += UnicodeWidthChar::width(c).unwrap_or(0);
+        if width > room {
+            break;
+        }
+    }
+    best
+}
+
 pub(super) fn wrap_text_first_fit(text: &str, line_width: usize) -> Vec<String> {
     let words: Vec<&str> = text.split_ascii_whitespace().collect();
     let line_widths = [line_width];
@@ -642,6 +775,7 @@ pub(super) fn wrap_text_first_fit(text: &str, line_width: usize) -> Vec<String>
         false,
         false,
         false,
+        false,
     );
     for (idx, word) in words.iter().enumerate() {
         let ws_after = idx + 1 < words.len();
@@ -683,7 +817,7 @@ fn append_link_closing(node: &SyntaxNode, out: &mut String, config: &Config) {
                     if past_link_text {
                         if link_child.kind() == SyntaxKind::LINK_DEST {
                             let raw = link_child.text().to_string();
-                            append_normalized_link_dest(&raw, out);
+                            append_normalized_link_dest(&raw, out, config);
                         } else {
                             let _ = write!(out, "{}", link_child.text());
                         }
@@ -743,7 +877,7 @@ fn append_image_closing(node: &SyntaxNode, out: &mut String, config: &Config) {
                     if past_image_alt {
                         if img_child.kind() == SyntaxKind::LINK_DEST {
                             let raw = img_child.text().to_string();
-                            append_normalized_link_dest(&raw, out);
+                            append_normalized_link_dest(&raw, out, config);
                         } else {
                             let _ = write!(out, "{}", img_child.text());
                         }
@@ -804,6 +938,7 @@ impl<'a> TraversalBuilder<'a> {
         avoid_blockquote_line_start: bool,
         avoid_heading_line_start: bool,
         preserve_newlines: bool,
+        split_long_words: bool,
     ) -> Self {
         Self {
             sink: StreamingCoreSink::new(
@@ -815,6 +950,7 @@ impl<'a> TraversalBuilder<'a> {
                 avoid_unsafe_line_start,
                 avoid_blockquote_line_start,
                 avoid_heading_line_start,
+                split_long_words,
             ),
             current_piece: None,
             current_piece_boundary_class: SentenceBoundaryClass::Normal,
@@ -830,6 +966,24 @@ impl<'a> TraversalBuilder<'a> {
         self.push_piece_with_boundary(text, SentenceBoundaryClass::Normal);
     }
 
+    /// Push a word the line may also break inside, between two CJK
+    /// characters (see [`cjk_break_segments`]). The segments are separate
+    /// pieces joined without a space. Sentence mode never breaks on width, so
+    /// the word stays whole there.
+    fn push_cjk_breakable_piece(&mut self, word: &str) {
+        if self.sink.sentence_mode {
+            self.push_piece(word);
+            return;
+        }
+        let mut segments = cjk_break_segments(word).into_iter().peekable();
+        while let Some(segment) = segments.next() {
+            self.push_piece(segment);
+            if segments.peek().is_some() {
+                self.flush_current(false);
+            }
+        }
+    }
+
     fn push_piece_with_boundary(&mut self, text: &str, boundary_class: SentenceBoundaryClass) {
         if let Some(prev) = self.pending_soft_break_ea_prev.take()
             && let Some(next) = text.chars().next()
@@ -837,6 +991,11 @@ impl<'a> TraversalBuilder<'a> {
             && UnicodeWidthChar::width(next) == Some(2)
         {
             self.pending_space = false;
+            // Keep the text on either side as separate pieces where a CJK
+            // break may go, so reflowing wrapped text rewraps it the same way.
+            if !self.sink.sentence_mode && can_break_between(prev, next) {
+                self.flush_current(false);
+            }
         }
         if self.pending_space {
             self.flush_current(true);
@@ -1018,7 +1177,7 @@ fn process_node_recursive(
                     if in_link_text && t.text() == r"\_" {
                         sink.push_piece("_");
                     } else {
-                        sink.push_piece(t.text());
+                        sink.push_piece(escaped_char_text(&t, config));
                     }
                 }
                 SyntaxKind::NONBREAKING_SPACE => {
@@ -1062,7 +1221,13 @@ fn process_node_recursive(
                                 || config.parser_extensions.line_blocks
                                 || config.parser_extensions.grid_tables,
                         );
-                        sink.push_piece(&processed_word);
+                        // A break between two wide characters only reads back
+                        // as nothing under `east_asian_line_breaks`.
+                        if config.formatter_extensions.east_asian_line_breaks {
+                            sink.push_cjk_breakable_piece(&processed_word);
+                        } else {
+                            sink.push_piece(&processed_word);
+                        }
                         saw_word = true;
                     }
                     if saw_word && ends_with_ascii_whitespace(&text) {
@@ -1127,13 +1292,21 @@ fn process_node_recursive(
                     atomic_links,
                     in_inline_footnote,
                 ),
+                SyntaxKind::EMPHASIS | SyntaxKind::STRONG
+                    if !config.allows_breaks_in(BreakableInline::Emphasis)
+                        && !node_starts_with_whitespace(&n) =>
+                {
+                    skip_marker_whitespace = false;
+                    push_atomic_inline(sink, &format_inline_fn(&n));
+                }
                 SyntaxKind::EMPHASIS => {
                     skip_marker_whitespace = false;
                     if node_starts_with_whitespace(&n) {
                         sink.set_pending_space(true);
                         sink.set_skip_next_leading_whitespace(true);
                     }
-                    sink.push_piece("*");
+                    let delimiter = emphasis_delimiter(&n, config);
+                    sink.push_piece(delimiter);
                     process_node_recursive(
                         config,
                         &n,
@@ -1146,7 +1319,7 @@ fn process_node_recursive(
                     sink.set_skip_next_leading_whitespace(false);
                     let had_pending_space = sink.pending_space();
                     sink.set_pending_space(false);
-                    sink.push_piece("*");
+                    sink.push_piece(delimiter);
                     sink.set_pending_space(had_pending_space);
                 }
                 SyntaxKind::STRONG => {
@@ -1155,7 +1328,8 @@ fn process_node_recursive(
                         sink.set_pending_space(true);
                         sink.set_skip_next_leading_whitespace(true);
                     }
-                    sink.push_piece("**");
+                    let delimiter = emphasis_delimiter(&n, config);
+                    sink.push_piece(delimiter);
                     process_node_recursive(
                         config,
                         &n,
@@ -1168,15 +1342,13 @@ fn process_node_recursive(
                     sink.set_skip_next_leading_whitespace(false);
                     let had_pending_space = sink.pending_space();
                     sink.set_pending_space(false);
-                    sink.push_piece("**");
+                    sink.push_piece(delimiter);
                     sink.set_pending_space(had_pending_space);
                 }
                 SyntaxKind::LINK => {
                     skip_marker_whitespace = false;
-                    if atomic_links {
-                        let formatted = format_inline_fn(&n);
-                        let text = normalize_inline_for_sentence(&formatted);
-                        sink.push_piece(text.as_ref());
+                    if atomic_links || !config.allows_breaks_in(BreakableInline::LinkText) {
+                        push_atomic_inline(sink, &format_inline_fn(&n));
                     } else {
                         sink.push_piece("[");
                         for child in n.children_with_tokens() {
@@ -1201,10 +1373,8 @@ fn process_node_recursive(
                 }
                 SyntaxKind::IMAGE_LINK => {
                     skip_marker_whitespace = false;
-                    if atomic_links {
-                        let formatted = format_inline_fn(&n);
-                        let text = normalize_inline_for_sentence(&formatted);
-                        sink.push_piece(text.as_ref());
+                    if atomic_links || !config.allows_breaks_in(BreakableInline::ImageAlt) {
+                        push_atomic_inline(sink, &format_inline_fn(&n));
                     } else {
                         sink.push_piece("![");
                         for child in n.children_with_tokens() {
@@ -1238,6 +1408,10 @@ fn process_node_recursive(
                     skip_marker_whitespace = false;
                     sink.push_piece(&n.text().to_string());
                 }
+                SyntaxKind::BRACKETED_SPAN if !config.allows_breaks_in(BreakableInline::Span) => {
+                    skip_marker_whitespace = false;
+                    push_atomic_inline(sink, &format_inline_fn(&n));
+                }
                 SyntaxKind::BRACKETED_SPAN => {
                     skip_marker_whitespace = false;
                     sink.push_piece("[");
@@ -1340,7 +1514,12 @@ fn process_node_recursive(
                     let text = format_inline_fn(&n);
                     sink.push_piece(&text);
                 }
+                // Inline math, footnote references, and other atomic nodes.
+                // Resetting the marker flag keeps a soft break right after an
+                // atom on a `>`-prefixed line from being swallowed, which would
+                // glue the atom to the next word.
                 _ => {
+                    skip_marker_whitespace = false;
                     let text = format_inline_fn(&n);
                     sink.push_piece(&text);
                 }
@@ -1465,6 +1644,7 @@ pub(super) fn wrapped_lines_for_node(
         options.avoid_blockquote_line_start,
         options.avoid_heading_line_start,
         options.preserve_newlines,
+        options.split_long_words,
     );
     process_node_recursive(
         config,
@@ -1578,8 +1758,8 @@ mod tests {
         is_decimal_ordered_list_marker_piece, is_definition_marker_piece,
         is_example_list_marker_piece, is_fancy_alpha_marker_piece,
         is_fancy_paren_alpha_or_roman_marker_piece, is_fancy_paren_decimal_marker_piece,
-        is_fancy_roman_marker_piece, is_setext_or_thematic_marker_piece,
-        is_unsafe_list_line_start_piece, wrap_text_first_fit,
+        is_fancy_roman_marker_piece, is_footnote_definition_marker_piece,
+        is_setext_or_thematic_marker_piece, is_unsafe_list_line_start_piece, wrap_text_first_fit,
     };
 
     #[test]
@@ -1622,6 +1802,23 @@ mod tests {
         assert!(!is_decimal_ordered_list_marker_piece("2024.05"));
     }
 
+    #[test]
+    fn wrap_text_first_fit_keeps_footnote_reference_before_colon_inline() {
+        let lines = wrap_text_first_fit("alpha beta [^note]: gamma", 10);
+        assert_eq!(lines, vec!["alpha beta [^note]:", "gamma"]);
+    }
+
+    #[test]
+    fn footnote_definition_marker_rule_requires_label_and_colon() {
+        assert!(is_footnote_definition_marker_piece("[^1]:"));
+        assert!(is_footnote_definition_marker_piece("[^note]:"));
+        assert!(is_footnote_definition_marker_piece("[^note]:,"));
+        assert!(!is_footnote_definition_marker_piece("[^note]"));
+        assert!(!is_footnote_definition_marker_piece("[^note]."));
+        assert!(!is_footnote_definition_marker_piece("[^]:"));
+        assert!(!is_footnote_definition_marker_piece("[@doe]:"));
+    }
+
     #[test]
     fn atx_heading_marker_rule_matches_hash_runs() {
         assert!(is_atx_heading_marker_piece("#"));
@@ -0,0 +1,193 @@
+//! Link and image destinations under `link-destinations`.
+//!
+//! A bare destination ends at the first space and must have balanced
+//! parentheses, so `[text](my file.pdf)` only works in dialects that accept
+//! spaces there, and stops working the moment a wrapper or another tool
+//! treats the space as a break. `angle-brackets` writes such destinations as
+//! `<my file.pdf>`, `percent-encode` as `my%20file.pdf`; both drop angle
+//! brackets around destinations that do not need them.
+
+use std::borrow::Cow;
+use std::fmt::Write;
+
+use crate::config::LinkDestinations;
+
+/// Split the text between a link's parentheses into the destination (angle
+/// brackets included) and the title, if any.
+pub(super) fn split_link_dest(text: &str) -> (&str, Option<&str>) {
+    let text = text.trim();
+    if text.starts_with('<')
+        && let Some(end) = closing_angle(text)
+    {
+        let rest = text[end + 1..].trim_start();
+        return (&text[..=end], (!rest.is_empty()).then_some(rest));
+    }
+    for (idx, c) in text.char_indices() {
+        if c.is_whitespace() {
+            let rest = text[idx..].trim_start();
+            if is_title(rest) {
+                return (text[..idx].trim_end(), Some(rest));
+            }
+        }
+    }
+    (text, None)
+}
+
+/// The text between a link's parentheses with its destination rewritten for
+/// `mode` and the title kept as written.
+pub(super) fn rewrite_link_dest(text: &str, mode: LinkDestinations) -> String {
+    let (url, title) = split_link_dest(text);
+    let url = rewrite_url(url, mode);
+    match title {
+        Some(title) => format!("{url} {title}"),
+        None => url.into_owned(),
+    }
+}
+
+/// `url` rewritten for `mode`.
+pub(super) fn rewrite_url(url: &str, mode: LinkDestinations) -> Cow<'_, str> {
+    let (inner, bracketed) = match url.strip_prefix('<').and_then(|u| u.strip_suffix('>')) {
+        Some(inner) => (inner, true),
+        None => (url, false),
+    };
+    // `<>` is the only way to write an empty destination before a title.
+    if inner.is_empty() {
+        return Cow::Borrowed(url);
+    }
+    match mode {
+        LinkDestinations::Preserve => Cow::Borrowed(url),
+        LinkDestinations::AngleBrackets if !needs_brackets(inner) => Cow::Borrowed(inner),
+        LinkDestinations::AngleBrackets if bracketed => Cow::Borrowed(url),
+        LinkDestinations::AngleBrackets => Cow::Owned(format!(
+            "<{}>",
+            inner.replace('<', "\\<").replace('>', "\\>")
+        )),
+        LinkDestinations::PercentEncode => percent_encode(inner),
+    }
+}
+
+/// Byte offset of the `>` closing the `<` at the start of `text`.
+fn closing_angle(text: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (idx, c) in text.char_indices().skip(1) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '<' | '\n' => return None,
+            '>' => return Some(idx),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn is_title(text: &str) -> bool {
+    let mut chars = text.chars();
+    let (Some(open), Some(close)) = (chars.next(), chars.next_back()) else {
+        return false;
+    };
+    matches!((open, close), ('"', '"') | ('\'', '\'') | ('(', ')'))
+}
+
+fn is_illegal(c: char) -> bool {
+    c.is_whitespace() || c.is_control() || c == '<' || c == '>'
+}
+
+/// Whether unescaped parentheses in `url` fail to balance.
+fn has_unbalanced_parens(url: &str) -> bool {
+    let mut depth = 0usize;
+    let mut escaped = false;
+    for c in url.chars() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '(' => depth += 1,
+            ')' if depth == 0 => return true,
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+    depth != 0
+}
+
+fn needs_brackets(url: &str) -> bool {
+    url.chars().any(is_illegal) || has_unbalanced_parens(url)
+}
+
+fn percent_encode(url: &str) -> Cow<'_, str> {
+    let encode_parens = has_unbalanced_parens(url);
+    let must_encode = |c: char| is_illegal(c) || (encode_parens && matches!(c, '(' | ')'));
+    if !url.chars().any(must_encode) {
+        return Cow::Borrowed(url);
+    }
+
+    let mut out = String::with_capacity(url.len() + 8);
+    let push_encoded = |out: &mut String, c: char| {
+        let mut buf = [0; 4];
+        for byte in c.encode_utf8(&mut buf).bytes() {
+            let _ = write!(out, "%{byte:02X}");
+        }
+    };
+    let mut chars = url.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            // An escaped character that needs encoding loses its backslash.
+            '\\' => match chars.next() {
+                Some(next) if must_encode(next) => push_encoded(&mut out, next),
+                Some(next) => {
+                    out.push('\\');
+                    out.push(next);
+                }
+                None => out.push('\\'),
+            },
+            _ if must_encode(c) => push_encoded(&mut out, c),
+            _ => out.push(c),
+        }
+    }
+    Cow::Owned(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_destination_from_title() {
+        assert_eq!(split_link_dest("url"), ("url", None));
+        assert_eq!(
+            split_link_dest("url \"A title\""),
+            ("url", Some("\"A title\""))
+        );
+        assert_eq!(
+            split_link_dest("my file.pdf 'T'"),
+            ("my file.pdf", Some("'T'"))
+        );
+        assert_eq!(
+            split_link_dest("<my file.pdf> (T)"),
+            ("<my file.pdf>", Some("(T)"))
+        );
+    }
+
+    #[test]
+    fn angle_brackets_wrap_only_when_needed() {
+        let mode = LinkDestinations::AngleBrackets;
+        assert_eq!(rewrite_url("my file.pdf", mode), "<my file.pdf>");
+        assert_eq!(rewrite_url("<my file.pdf>", mode), "<my file.pdf>");
+        assert_eq!(
+            rewrite_url("<https://example.com>", mode),
+            "https://example.com"
+        );
+        assert_eq!(rewrite_url("a(b", mode), "<a(b>");
+        assert_eq!(rewrite_url("<>", mode), "<>");
+    }
+
+    #[test]
+    fn percent_encode_replaces_illegal_characters() {
+        let mode = LinkDestinations::PercentEncode;
+        assert_eq!(rewrite_url("my file.pdf", mode), "my%20file.pdf");
+        assert_eq!(rewrite_url("<a b>", mode), "a%20b");
+        assert_eq!(rewrite_url("<a\\>b>", mode), "a%3Eb");
+        assert_eq!(rewrite_url("wiki/Foo_(bar)", mode), "wiki/Foo_(bar)");
+        assert_eq!(rewrite_url("<a(b>", mode), "a%28b");
+    }
+}
@@ -4,6 +4,7 @@ use crate::formatter::inline_layout::{self, WrapStrategy};
 use crate::formatter::tables;
 use crate::syntax::{AstNode, BlockQuote, FencedDiv, SyntaxKind, SyntaxNode};
 use rowan::NodeOrToken;
+use unicode_width::UnicodeWidthStr;
 
 use super::Formatter;
 
@@ -188,7 +189,7 @@ impl Formatter {
         markers
             .iter()
             .filter(|m| is_alignable_marker(m))
-            .map(|m| m.len())
+            .map(|m| m.width())
             .max()
             .unwrap_or(0)
     }
@@ -457,27 +458,14 @@ impl Formatter {
                     self.output.push('\n');
                 }
                 continue;
-            } else if child.kind() == SyntaxKind::PARAGRAPH {
-                if Self::has_continuation_eligible_predecessor(&child) {
-                    // Paragraphs that are siblings of ListItems are continuation content.
-                    self.format_list_continuation_paragraph(&child, last_item_content_indent);
-                } else {
-                    self.format_node_sync(&child, indent);
-                }
-            } else if child.kind() == SyntaxKind::CODE_BLOCK {
-                if Self::has_continuation_eligible_predecessor(&child) {
-                    // Code blocks that are siblings of ListItems are also continuation content.
-                    self.format_indented_code_block(&child, last_item_content_indent);
-                } else {
-                    self.format_node_sync(&child, indent);
-                }
-            } else if child.kind() == SyntaxKind::LIST {
-                if Self::has_continuation_eligible_predecessor(&child) {
-                    // Nested lists emitted as siblings of ListItems should stay continuation content.
-                    self.format_node_sync(&child, last_item_content_indent);
-                } else {
-                    self.format_node_sync(&child, indent);
-                }
+            } else if matches!(
+                child.kind(),
+                SyntaxKind::PARAGRAPH | SyntaxKind::CODE_BLOCK | SyntaxKind::LIST
+            ) && Self::has_continuation_eligible_predecessor(&child)
+            {
+                // Paragraphs, code blocks, and nested lists that are siblings of
+                // ListItems are continuation content of the item before them.
+                self.format_list_item_block(&child, last_item_content_indent);
             } else {
                 self.format_node_sync(&child, indent);
             }
@@ -623,25 +611,38 @@ impl Formatter {
                 self.output.push('\n');
             }
 
-            for child in node.children() {
-                if &child == leading_heading || child.kind() == SyntaxKind::BLANK_LINE {
-                    continue;
-                }
+            for child in Self::children_after(node, leading_heading) {
+                self.format_list_item_block(&child, hanging);
+            }
+            return;
+        }
 
-                match child.kind() {
-                    SyntaxKind::PLAIN | SyntaxKind::PARAGRAPH => {
-                        self.format_list_continuation_paragraph(&child, hanging);
-                    }
-                    SyntaxKind::LIST => {
-                        self.format_node_sync(&child, hanging);
-                    }
-                    SyntaxKind::CODE_BLOCK => {
-                        self.format_indented_code_block(&child, hanging);
-                    }
-                    _ => {
-                        self.format_node_sync(&child, hanging);
-                    }
-                }
+        // A fenced code block opening on the marker line (`- ````): put the
+        // opening fence there too, and the rest of the block at the hanging
+        // indent.
+        if let Some(leading_code) = first_non_blank_child.as_ref()
+            && leading_code.kind() == SyntaxKind::CODE_BLOCK
+            && leading_code
+                .children()
+                .any(|child| child.kind() == SyntaxKind::CODE_FENCE_OPEN)
+            && checkbox.is_none()
+        {
+            self.output.push_str(&" ".repeat(total_indent));
+            self.output
+                .push_str(&" ".repeat(list_indent.marker_padding));
+            self.output.push_str(&marker);
+            self.output.push_str(&" ".repeat(list_indent.spaces_after));
+            let code_start = self.output.len();
+            self.format_list_item_block(leading_code, hanging);
+            let first_line_indent = self.output[code_start..]
+                .bytes()
+                .take_while(|b| *b == b' ')
+                .count();
+            self.output
+                .replace_range(code_start..code_start + first_line_indent, "");
+
+            for child in Self::children_after(node, leading_code) {
+                self.format_list_item_block(&child, hanging);
             }
             return;
         }
@@ -688,11 +689,8 @@ impl Formatter {
                 }
             }
 
-            for child in node.children() {
-                if &child == leading_bq || child.kind() == SyntaxKind::BLANK_LINE {
-                    continue;
-                }
-                self.format_node_sync(&child, hanging);
+            for child in Self::children_after(node, leading_bq) {
+                self.format_list_item_block(&child, hanging);
             }
             return;
         }
@@ -745,11 +743,8 @@ impl Formatter {
 
             // Emit any trailing children (blank lines, continuation paragraphs,
             // further nested blocks) at hanging indent.
-            for child in node.children() {
-                if &child == leading_list || child.kind() == SyntaxKind::BLANK_LINE {
-                    continue;
-                }
-                self.format_node_sync(&child, hanging);
+            for child in Self::children_after(node, leading_list) {
+                self.format_list_item_block(&child, hanging);
             }
             return;
         }
@@ -804,10 +799,7 @@ impl Formatter {
             .map(|content| {
                 crate::directives::collect_inline_directives(content)
                     .iter()
-                    .any(|d| match d {
-                        crate::directives::Directive::Start(kind)
-                        | crate::directives::Directive::End(kind) => kind.affects_formatting(),
-                    })
+                    .any(crate::directives::Directive::affects_formatting)
             })
             .unwrap_or(false);
 
@@ -1021,13 +1013,12 @@ impl Formatter {
                     let in_ignore_region = self.directive_tracker.is_formatting_ignored();
 
                     if !is_content_node || in_ignore_region {
-                        let content_indent = list_indent.hanging_indent(total_indent);
                         // If in ignore region, just call format_node_sync which preserves content
                         // The indent parameter isn't used when in ignore mode, so we don't add it
                         if in_ignore_region {
                             self.format_node_sync(&child, 0);
                         } else {
-                            self.format_list_continuation_paragraph(&child, content_indent);
+                            self.format_list_item_block(&child, hanging);
                         }
                     }
                 }
@@ -1046,13 +1037,11 @@ impl Formatter {
                         self.output.push('\n');
                     } else {
                         // Normal nested list: indent on next line
-                        self.format_node_sync(&child, list_indent.hanging_indent(total_indent));
+                        self.format_list_item_block(&child, hanging);
                     }
                 }
                 SyntaxKind::CODE_BLOCK => {
-                    // Code blocks in list items need indentation
-                    let content_indent = list_indent.hanging_indent(total_indent);
-                    self.format_indented_code_block(&child, content_indent);
+                    self.format_list_item_block(&child, hanging);
                 }
                 SyntaxKind::BLOCK_QUOTE => {
                     let follows_primary_content = child
@@ -1087,8 +1076,7 @@ impl Formatter {
                         }
                         self.output.push('\n');
                     } else {
-                        let content_indent = list_indent.hanging_indent(total_indent);
-                        self.format_node_sync(&child, content_indent);
+                        self.format_list_item_block(&child, hanging);
                     }
                 }
                 SyntaxKind::HORIZONTAL_RULE => {
@@ -1136,15 +1124,11 @@ impl Formatter {
                         self.output.push_str(hr_text.trim());
                         self.output.push('\n');
                     } else {
-                        let content_indent = list_indent.hanging_indent(total_indent);
-                        self.format_node_sync(&child, content_indent);
+                        self.format_list_item_block(&child, hanging);
                     }
                 }
                 SyntaxKind::BLANK_LINE => {
-                    // Normalize consecutive blank lines within list-item continuation content.
-                    if !self.output.ends_with("\n\n") {
-                        self.output.push('\n');
-                    }
+                    self.format_list_item_block(&child, hanging);
                 }
                 SyntaxKind::HTML_BLOCK
                 | SyntaxKind::HTML_BLOCK_RAW
@@ -1192,8 +1176,7 @@ impl Formatter {
                         self.output.push_str(trimmed);
                         self.output.push('\n');
                     } else {
-                        let content_indent = list_indent.hanging_indent(total_indent);
-                        self.format_node_sync(&child, content_indent);
+                        self.format_list_item_block(&child, hanging);
                     }
                 }
                 SyntaxKind::PIPE_TABLE | SyntaxKind::GRID_TABLE => {
@@ -1242,7 +1225,7 @@ impl Formatter {
                     // in those cases the splice would misalign, so fall back.
                     let prefix_width = total_indent
                         + list_indent.marker_padding
-                        + marker.len()
+                        + marker.width()
                         + list_indent.spaces_after;
                     if no_content_emitted
                         && is_first_real_child
@@ -1268,15 +1251,48 @@ impl Formatter {
                         self.output.push_str(&prefix);
                         self.output.push_str(&table_str[content_indent..]);
                     } else {
-                        self.format_node_sync(&child, content_indent);
+                        self.format_list_item_block(&child, hanging);
                     }
                 }
                 _ => {
-                    // Other block elements - format with proper indentation
-                    let content_indent = list_indent.hanging_indent(total_indent);
-                    self.format_node_sync(&child, content_indent);
+                    self.format_list_item_block(&child, hanging);
                 }
             }
         }
     }
+
+    /// Format a block nested in a list item at the item's hanging indent.
+    ///
+    /// Every layout of the item's first line (plain text, a heading, a block
+    /// quote, or a nested list on the marker line) hands its remaining
+    /// children here, so continuation paragraphs, code blocks, block quotes,
+    /// and tables are laid out by their own formatters at the same column.
+    fn format_list_item_block(&mut self, child: &SyntaxNode, hanging: usize) {
+        match child.kind() {
+            SyntaxKind::PLAIN | SyntaxKind::PARAGRAPH => {
+                if !Self::is_marker_only_blockquote_continuation(child) {
+                    self.format_list_continuation_paragraph(child, hanging);
+                }
+            }
+            // The generic code block path ignores `indent`; this one strips the
+            // source's list indent and applies `hanging` instead.
+            SyntaxKind::CODE_BLOCK => self.format_indented_code_block(child, hanging),
+            SyntaxKind::BLANK_LINE => {
+                // Normalize consecutive blank lines within list-item continuation content.
+                if !self.output.ends_with("\n\n") {
+                    self.output.push('\n');
+                }
+            }
+            _ => self.format_node_sync(child, hanging),
+        }
+    }
+
+    /// Children of `item` after `leading`, the block already placed on the
+    /// marker line.
+    fn children_after(item: &SyntaxNode, leading: &SyntaxNode) -> Vec<SyntaxNode> {
+        item.children()
+            .skip_while(|child| child != leading)
+            .skip(1)
+            .collect()
+    }
 }
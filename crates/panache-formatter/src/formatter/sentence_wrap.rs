@@ -466,6 +466,31 @@ pub(super) fn profile_from<'a>(
     }
 }
 
+/// Whether each of `words` ends a sentence under the rules of `wrap =
+/// "sentence"`, for document language `lang` (falling back to `config.lang`).
+/// For callers that wrap text outside a CST, such as the Pandoc filter.
+pub fn sentence_ends(words: &[&str], lang: Option<&str>, config: &Config) -> Vec<bool> {
+    let lang = lang
+        .or(config.lang.as_deref())
+        .map(|lang| lang.to_lowercase());
+    let extra_no_break = merge_no_break_list(config, lang.as_deref());
+    let profile = profile_from(lang.as_deref(), &extra_no_break);
+    words
+        .iter()
+        .enumerate()
+        .map(|(idx, word)| {
+            let next_word = words.get(idx + 1).copied();
+            is_sentence_boundary_text(
+                word,
+                next_word,
+                next_word.is_some(),
+                next_word.is_none(),
+                profile,
+            )
+        })
+        .collect()
+}
+
 /// Resolve the built-in profile plus any user-configured no-break abbreviations
 /// for `node`'s document language. `scratch` owns the normalized user entries
 /// for the lifetime of the returned profile. Built once per node-wrap; this
@@ -496,6 +521,15 @@ mod tests {
         ResolvedProfile::builtin_only(SentenceLanguage::English)
     }
 
+    #[test]
+    fn sentence_ends_marks_each_boundary_word() {
+        let words = ["See", "e.g.", "this.", "Next", "one?", "Yes."];
+        assert_eq!(
+            sentence_ends(&words, None, &Config::default()),
+            [false, false, true, false, true, true]
+        );
+    }
+
     #[test]
     fn abbreviation_periods_are_not_sentence_boundaries() {
         assert!(!is_sentence_boundary_text(
@@ -0,0 +1,140 @@
+//! Reference links under `max_width_strict`.
+//!
+//! Reflow never breaks between the last word of a link's text and its closing
+//! parenthesis, so `[text](https://example.com/a/very/long/path)` overflows
+//! the line whatever the paragraph around it does. Strict mode rewrites such
+//! links as numbered reference links (`[text][1]`) before formatting and
+//! collects their destinations as definitions at the end of the document,
+//! where nothing else has to share their line.
+
+use std::fmt::Write;
+
+use rowan::TextRange;
+use unicode_width::UnicodeWidthStr;
+
+use crate::config::{Config, WrapMode};
+use crate::parser;
+use crate::syntax::{SyntaxKind, SyntaxNode};
+
+/// `root` re-parsed with its over-long inline links and images turned into
+/// reference links, or `None` when strict mode is off or nothing needs it.
+///
+/// Links whose destination spans lines, or that carry attributes, are left
+/// alone, as are documents with `panache-ignore` directives, whose ignored
+/// regions must come out exactly as written.
+pub(super) fn reference_long_links(root: &SyntaxNode, config: &Config) -> Option<SyntaxNode> {
+    if !config.max_width_strict
+        || !config.parser_extensions.reference_links
+        || root.kind() != SyntaxKind::DOCUMENT
+        || !matches!(config.wrap, None | Some(WrapMode::Reflow))
+    {
+        return None;
+    }
+    let input = root.text().to_string();
+    if input.contains("panache-ignore") {
+        return None;
+    }
+
+    let mut links: Vec<(TextRange, String)> = root
+        .descendants()
+        .filter_map(|node| long_link(&node, &input, config))
+        .collect();
+    if links.is_empty() {
+        return None;
+    }
+    // An image inside a link's text closes before the link does.
+    links.sort_by_key(|(range, _)| range.start());
+
+    let mut output = String::with_capacity(input.len());
+    let mut definitions = String::new();
+    let mut copied = 0;
+    let mut label = 0;
+    for (range, dest) in &links {
+        label = next_free_label(&input, label);
+        output.push_str(&input[copied..usize::from(range.start())]);
+        let _ = write!(output, "][{label}]");
+        let _ = writeln!(definitions, "[{label}]: {dest}");
+        copied = usize::from(range.end());
+    }
+    output.push_str(&input[copied..]);
+    if !output.is_empty() && !output.ends_with('\n') {
+        output.push('\n');
+    }
+    if !output.ends_with("\n\n") {
+        output.push('\n');
+    }
+    output.push_str(&definitions);
+
+    // A document ending in an unclosed container could swallow the
+    // definitions; keep the links inline rather than break them.
+    let tree = parser::parse(&output, Some(config.parser_options()));
+    let definition_count = |node: &SyntaxNode| count_kind(node, SyntaxKind::REFERENCE_DEFINITION);
+    (definition_count(&tree) == definition_count(root) + links.len()).then_some(tree)
+}
+
+/// The `](destination)` range of an inline link or image whose last unbreakable
+/// piece cannot fit in the line width, with its destination (and title).
+fn long_link(node: &SyntaxNode, input: &str, config: &Config) -> Option<(TextRange, String)> {
+    let (text_end, dest_end) = match node.kind() {
+        SyntaxKind::LINK => (SyntaxKind::LINK_TEXT_END, SyntaxKind::LINK_DEST_END),
+        SyntaxKind::IMAGE_LINK => (SyntaxKind::IMAGE_ALT_END, SyntaxKind::IMAGE_DEST_END),
+        _ => return None,
+    };
+    if node
+        .children()
+        .any(|child| matches!(child.kind(), SyntaxKind::LINK_REF | SyntaxKind::ATTRIBUTE))
+    {
+        return None;
+    }
+    let dest = node
+        .children()
+        .find(|child| child.kind() == SyntaxKind::LINK_DEST)?
+        .text()
+        .to_string();
+    let dest = dest.trim();
+    if dest.is_empty() {
+        return None;
+    }
+
+    let mut tokens = node
+        .children_with_tokens()
+        .filter_map(|element| element.into_token());
+    let start = tokens
+        .find(|token| token.kind() == text_end)?
+        .text_range()
+        .start();
+    let end = tokens
+        .find(|token| token.kind() == dest_end)?
+        .text_range()
+        .end();
+    let range = TextRange::new(start, end);
+    let closing = &input[range];
+    if closing.contains('\n') {
+        return None;
+    }
+
+    let block = node
+        .ancestors()
+        .find(|ancestor| matches!(ancestor.kind(), SyntaxKind::PARAGRAPH | SyntaxKind::PLAIN))?;
+    let block_start = usize::from(block.text_range().start());
+    let line_start = input[..block_start].rfind('\n').map_or(0, |idx| idx + 1);
+    let indent = UnicodeWidthStr::width(&input[line_start..block_start]);
+    let text = &input[usize::from(node.text_range().start())..usize::from(start)];
+    let last_word = text.rsplit(char::is_whitespace).next().unwrap_or(text);
+    let width = indent + UnicodeWidthStr::width(last_word) + UnicodeWidthStr::width(closing);
+    (width > config.line_width).then(|| (range, dest.to_string()))
+}
+
+/// The smallest number above `after` that `input` never uses as `[n]`, so the
+/// new definition cannot capture an existing reference or bracketed text.
+fn next_free_label(input: &str, after: usize) -> usize {
+    (after + 1..)
+        .find(|n| !input.contains(&format!("[{n}]")))
+        .expect("some label is free")
+}
+
+fn count_kind(root: &SyntaxNode, kind: SyntaxKind) -> usize {
+    root.descendants()
+        .filter(|node| node.kind() == kind)
+        .count()
+}
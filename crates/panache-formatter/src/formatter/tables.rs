@@ -1,7 +1,10 @@
 use crate::config::{Config, WrapMode};
+use crate::formatter::escapes::escaped_char_text;
 use crate::formatter::inline::format_inline_node;
 use crate::formatter::inline_layout::wrap_text_first_fit;
 use crate::formatter::sentence_wrap::{ResolvedProfile, resolve_profile, split_sentence_text};
+use crate::formatter::smart::normalize_smart_punctuation;
+use crate::formatter::text_width::slice_columns;
 use crate::syntax::{SyntaxKind, SyntaxNode, SyntaxToken};
 use panache_parser::analyze_grid;
 use rowan::NodeOrToken;
@@ -340,7 +343,10 @@ fn format_table_caption_with_language(
     }
 
     let wrap_mode = config.wrap.clone().unwrap_or(WrapMode::Reflow);
-    let available_width = config.line_width.saturating_sub(TABLE_BLOCK_INDENT).max(1);
+    let available_width = config
+        .table_width()
+        .saturating_sub(TABLE_BLOCK_INDENT)
+        .max(1);
 
     match wrap_mode {
         WrapMode::Preserve => format!(": {body}"),
@@ -396,7 +402,7 @@ fn format_table_caption(caption_text: &str, config: &Config, node: &SyntaxNode)
     format_table_caption_with_language(caption_text, config, profile)
 }
 
-fn extract_table_caption_content(caption_node: &SyntaxNode) -> String {
+fn extract_table_caption_content(caption_node: &SyntaxNode, config: &Config) -> String {
     let mut caption_body = String::new();
     // Captions inside a blockquote carry BLOCK_QUOTE_MARKER tokens for
     // losslessness; the blockquote formatter re-adds the prefix dynamically, so
@@ -424,8 +430,10 @@ fn extract_table_caption_content(caption_node: &SyntaxNode) -> String {
                 caption_body.push_str(token.text());
             }
             rowan::NodeOrToken::Node(node) => {
+                // Normalize inline markup the same way paragraphs do, as the
+                // cells already are.
                 skip_next_whitespace = false;
-                caption_body.push_str(&node.text().to_string());
+                caption_body.push_str(&format_inline_node(&node, config));
             }
         }
     }
@@ -454,14 +462,7 @@ fn format_cell_content(node: &SyntaxNode, config: &Config) -> String {
 
     for child in node.children_with_tokens() {
         match child {
-            NodeOrToken::Token(token) => {
-                if token.kind() == SyntaxKind::TEXT
-                    || token.kind() == SyntaxKind::NEWLINE
-                    || token.kind() == SyntaxKind::ESCAPED_CHAR
-                {
-                    result.push_str(token.text());
-                }
-            }
+            NodeOrToken::Token(token) => push_cell_token(&mut result, &token, config),
             NodeOrToken::Node(node) => {
                 // Handle inline elements (emphasis, code, links, etc.)
                 result.push_str(&format_inline_node(&node, config));
@@ -472,6 +473,94 @@ fn format_cell_content(node: &SyntaxNode, config: &Config) -> String {
     result
 }
 
+/// Format a pipe table cell like [`format_cell_content`], escaping as `\|` any
+/// pipe the formatted inline content introduces so it cannot split the cell.
+/// Code, math, and raw spans are copied as formatted: the parser already read
+/// their pipes as content, not cell boundaries.
+fn format_pipe_cell_content(node: &SyntaxNode, config: &Config) -> String {
+    let mut result = String::new();
+
+    for child in node.children_with_tokens() {
+        match child {
+            NodeOrToken::Token(token) => push_cell_token(&mut result, &token, config),
+            NodeOrToken::Node(node) => {
+                let formatted = format_inline_node(&node, config);
+                if matches!(
+                    node.kind(),
+                    SyntaxKind::INLINE_CODE
+                        | SyntaxKind::INLINE_EXEC
+                        | SyntaxKind::RAW_INLINE
+                        | SyntaxKind::INLINE_MATH
+                        | SyntaxKind::DISPLAY_MATH
+                ) {
+                    result.push_str(&formatted);
+                } else {
+                    result.push_str(&escape_cell_pipes(&formatted));
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// Append a cell's own token with the same normalization paragraph text gets:
+/// smart punctuation and `escaping`.
+fn push_cell_token(result: &mut String, token: &SyntaxToken, config: &Config) {
+    match token.kind() {
+        SyntaxKind::TEXT => result.push_str(
+            normalize_smart_punctuation(
+                token.text(),
+                config.formatter_extensions.smart,
+                config.formatter_extensions.smart_quotes,
+            )
+            .as_ref(),
+        ),
+        SyntaxKind::NEWLINE => result.push_str(token.text()),
+        SyntaxKind::ESCAPED_CHAR => result.push_str(escaped_char_text(token, config)),
+        SyntaxKind::NONBREAKING_SPACE => result.push_str("\\ "),
+        _ => {}
+    }
+}
+
+/// Escape unescaped `|` outside code spans.
+fn escape_cell_pipes(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        match c {
+            '\\' => {
+                // Keep an existing escape together with the character it escapes.
+                let len = rest[1..]
+                    .chars()
+                    .next()
+                    .map_or(1, |next| 1 + next.len_utf8());
+                result.push_str(&rest[..len]);
+                rest = &rest[len..];
+            }
+            '`' => {
+                let run = rest.len() - rest.trim_start_matches('`').len();
+                let fence = &rest[..run];
+                let span_len = rest[run..]
+                    .match_indices(fence)
+                    .find(|(idx, _)| !rest[run + idx + run..].starts_with('`'))
+                    .map_or(run, |(idx, _)| run + idx + run);
+                result.push_str(&rest[..span_len]);
+                rest = &rest[span_len..];
+            }
+            '|' => {
+                result.push_str("\\|");
+                rest = &rest[1..];
+            }
+            _ => {
+                result.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    result
+}
+
 /// Extract cell contents from TABLE_CELL nodes if present, otherwise fall back to text splitting
 fn extract_row_cells(row_node: &SyntaxNode, config: &Config) -> Vec<String> {
     let mut cells = Vec::new();
@@ -584,7 +673,7 @@ fn extract_pipe_table_data(node: &SyntaxNode, config: &Config) -> TableData {
     for child in node.children() {
         match child.kind() {
             SyntaxKind::TABLE_CAPTION => {
-                let caption_text = extract_table_caption_content(&child);
+                let caption_text = extract_table_caption_content(&child, config);
                 if caption.is_none() {
                     caption = Some(caption_text);
                 }
@@ -598,7 +687,11 @@ fn extract_pipe_table_data(node: &SyntaxNode, config: &Config) -> TableData {
                 // `\|` stays inside its cell. Re-rendering the row and splitting
                 // on `|` (as `split_row` does) is escape-blind: it re-tokenizes
                 // the `\|` as a delimiter and invents a phantom column.
-                let cells = extract_row_cells(&child, config);
+                let cells: Vec<String> = child
+                    .children()
+                    .filter(|cell| cell.kind() == SyntaxKind::TABLE_CELL)
+                    .map(|cell| format_pipe_cell_content(&cell, config))
+                    .collect();
                 let cells = if cells.is_empty() {
                     split_row(&format_cell_content(&child, config))
                 } else {
@@ -671,7 +764,26 @@ pub fn format_pipe_table(node: &SyntaxNode, config: &Config, indent: usize) -> S
         return node.text().to_string();
     }
 
-    let widths = calculate_column_widths(&table_data.rows);
+    // A top-level pipe table self-indents by the configured `table-indent`
+    // (default 2). Nested tables always honor the container indent threaded in.
+    let block_indent = if indent == 0 {
+        config.table_indent
+    } else {
+        indent
+    };
+
+    // An aligned table wider than `table-max-width` is written compactly, with
+    // unpadded cells and minimal separators. Pandoc wraps the cells of a pipe
+    // table with any line wider than its columns setting, so padding past the
+    // budget would change how the table renders.
+    let mut widths = calculate_column_widths(&table_data.rows);
+    let aligned_width = block_indent + widths.iter().map(|width| width + 3).sum::<usize>() + 1;
+    if config
+        .table_max_width
+        .is_some_and(|max_width| aligned_width > max_width)
+    {
+        widths.iter_mut().for_each(|width| *width = 0);
+    }
 
     // Format rows
     for (row_idx, row) in table_data.rows.iter().enumerate() {
@@ -727,7 +839,8 @@ pub fn format_pipe_table(node: &SyntaxNode, config: &Config, indent: usize) -> S
         if row_idx == 0 {
             output.push('|');
 
-            for (col_idx, width) in widths.iter().enumerate() {
+            for (col_idx, &width) in widths.iter().enumerate() {
+                let width = width.max(3);
                 let alignment = table_data
                     .alignments
                     .get(col_idx)
@@ -758,13 +871,6 @@ pub fn format_pipe_table(node: &SyntaxNode, config: &Config, indent: usize) -> S
         output.push_str(&formatted_caption);
         output.push('\n');
     }
-    // A top-level pipe table self-indents by the configured `table-indent`
-    // (default 2). Nested tables always honor the container indent threaded in.
-    let block_indent = if indent == 0 {
-        config.table_indent
-    } else {
-        indent
-    };
     indent_table_block(&output, block_indent)
 }
 
@@ -1152,7 +1258,7 @@ fn extract_grid_table_data(node: &SyntaxNode, config: &Config) -> GridTableData
     for child in node.children() {
         match child.kind() {
             SyntaxKind::TABLE_CAPTION => {
-                let caption_text = extract_table_caption_content(&child);
+                let caption_text = extract_table_caption_content(&child, config);
                 if caption.is_none() {
                     caption = Some(caption_text);
                 }
@@ -1630,9 +1736,9 @@ pub fn format_grid_table(node: &SyntaxNode, config: &Config, indent: usize) -> S
 /// Column information for simple tables (extracted from separator line)
 #[derive(Debug, Clone)]
 struct SimpleColumn {
-    /// Start position (byte index) in the line
+    /// Start position (display column) in the line
     start: usize,
-    /// End position (byte index) in the line
+    /// End position (display column) in the line
     end: usize,
     /// Column alignment
     alignment: Alignment,
@@ -1641,9 +1747,10 @@ struct SimpleColumn {
 /// Extract column positions from a simple table separator line.
 /// Returns column boundaries and default alignments.
 fn extract_simple_table_columns(separator: &SyntaxNode) -> Vec<SimpleColumn> {
-    // One column per dash run, byte offsets relative to the node start (= line
+    // One column per dash run, offsets relative to the node start (= line
     // start, leading whitespace/prefix included) so they line up with the
-    // header line the alignment pass indexes into. End is exclusive.
+    // header line the alignment pass indexes into. End is exclusive. The
+    // separator is ASCII, so its byte offsets are display columns.
     let node_start = u32::from(separator.text_range().start());
     separator_marker_tokens(separator)
         .filter(|t| t.kind() == SyntaxKind::TABLE_SEP_DASHES)
@@ -1669,31 +1776,17 @@ fn determine_simple_alignments(
 ) {
     if let Some(header) = header_line {
         for col in columns.iter_mut() {
-            if col.end > header.len() {
-                col.alignment = Alignment::Default;
-                continue;
-            }
-
-            // Extract header text for this column
-            let header_text = if col.end <= header.len() {
-                header[col.start..col.end].trim()
-            } else if col.start < header.len() {
-                header[col.start..].trim()
-            } else {
-                ""
-            };
-
-            if header_text.is_empty() {
+            // Dash runs are measured in display columns, so the header is too.
+            let header_in_col = slice_columns(header, col.start, col.end);
+            if col.end > header.width() || header_in_col.trim().is_empty() {
                 col.alignment = Alignment::Default;
                 continue;
             }
 
             // Find where the header text starts and ends within the column
-            let header_in_col = &header[col.start..col.end.min(header.len())];
-            let text_start = header_in_col.len() - header_in_col.trim_start().len();
+            let text_start = header_in_col.width() - header_in_col.trim_start().width();
             // text_end is the position AFTER the last non-whitespace character
-            let trimmed_text = header_in_col.trim();
-            let text_end = text_start + trimmed_text.len();
+            let text_end = text_start + header_in_col.trim().width();
 
             // Column width is separator length
             let col_width = col.end - col.start;
@@ -1728,15 +1821,8 @@ fn split_simple_table_row(row_text: &str, columns: &[SimpleColumn]) -> Vec<Strin
         // A column spans to the start of the next column (the gap belongs to
         // the left column); the last column runs to end-of-line. Ending at the
         // dash-run end instead would truncate cell text wider than its dashes.
-        let end = columns
-            .get(i + 1)
-            .map_or(row.len(), |next| next.start.min(row.len()));
-        let cell_text = if col.start < end {
-            row[col.start..end].trim()
-        } else {
-            ""
-        };
-        cells.push(cell_text.to_string());
+        let end = columns.get(i + 1).map_or(usize::MAX, |next| next.start);
+        cells.push(slice_columns(row, col.start, end).trim().to_string());
     }
 
     cells
@@ -1758,7 +1844,7 @@ fn extract_simple_table_data(node: &SyntaxNode, config: &Config) -> TableData {
     for child in node.children() {
         match child.kind() {
             SyntaxKind::TABLE_CAPTION => {
-                let caption_text = extract_table_caption_content(&child);
+                let caption_text = extract_table_caption_content(&child, config);
                 if caption.is_none() {
                     caption = Some(caption_text);
                 }
@@ -1964,9 +2050,9 @@ pub fn format_simple_table(node: &SyntaxNode, config: &Config) -> String {
 }
 
 /// Extract column information from a multiline table separator. One
-/// `(start, end)` per dash run, byte offsets relative to the node start
-/// (leading whitespace preserved, as the old line-relative offsets were),
-/// end exclusive.
+/// `(start, end)` per dash run, offsets relative to the node start (leading
+/// whitespace preserved, as the old line-relative offsets were), end
+/// exclusive. The separator is ASCII, so these are also display columns.
 fn extract_multiline_columns(separator: &SyntaxNode) -> Vec<(usize, usize)> {
     let node_start = u32::from(separator.text_range().start());
     separator_marker_tokens(separator)
@@ -2031,17 +2117,13 @@ fn determine_multiline_alignment(header_text: &str, col_start: usize, col_end: u
         .unwrap_or("");
 
     // Extract text within this column using original line (not normalized)
-    let header_in_col = if col_end <= first_line.len() {
-        &first_line[col_start..col_end]
-    } else if col_start < first_line.len() {
-        &first_line[col_start..]
-    } else {
+    if col_start >= first_line.width() {
         return Alignment::Default;
-    };
+    }
+    let header_in_col = slice_columns(first_line, col_start, col_end);
 
-    let text_start = header_in_col.len() - header_in_col.trim_start().len();
-    let trimmed_text = header_in_col.trim();
-    let text_end = text_start + trimmed_text.len();
+    let text_start = header_in_col.width() - header_in_col.trim_start().width();
+    let text_end = text_start + header_in_col.trim().width();
 
     let col_width = col_end - col_start;
     let flush_left = text_start == 0;
@@ -2055,6 +2137,50 @@ fn determine_multiline_alignment(header_text: &str, col_start: usize, col_end: u
     }
 }
 
+/// Render line `line_idx` of a multiline table row, placing each cell's text
+/// at its display column. Wide characters take two columns, so cells are laid
+/// out by width rather than on a char grid; a cell that overflows into the
+/// next column pushes that column's text right instead of being overwritten.
+fn render_multiline_line(
+    row: &[Vec<String>],
+    line_idx: usize,
+    positions: &[(usize, usize)],
+    alignments: &[Alignment],
+) -> String {
+    let mut line = String::new();
+    let mut width = 0;
+    for (col_idx, cell_lines) in row.iter().enumerate() {
+        let Some(&(col_start, col_end)) = positions.get(col_idx) else {
+            continue;
+        };
+        let cell_text = cell_lines.get(line_idx).map_or("", |s| s.trim_end());
+        if cell_text.is_empty() {
+            continue;
+        }
+        let alignment = alignments
+            .get(col_idx)
+            .copied()
+            .unwrap_or(Alignment::Default);
+
+        let total_padding = (col_end - col_start).saturating_sub(cell_text.width());
+        let text_start_in_col = match alignment {
+            Alignment::Left | Alignment::Default => 0,
+            Alignment::Right => total_padding,
+            Alignment::Center => total_padding / 2,
+        };
+
+        let target = col_start + text_start_in_col;
+        if width < target {
+            line.push_str(&" ".repeat(target - width));
+            width = target;
+        }
+        line.push_str(cell_text);
+        width += cell_text.width();
+    }
+    line.truncate(line.trim_end().len());
+    line
+}
+
 /// Represents a multiline table with cells that can span multiple lines
 struct MultilineTableData {
     /// Rows of cells, where each cell is a vector of lines
@@ -2076,13 +2202,7 @@ fn extract_multiline_cells(text: &str, column_positions: &[(usize, usize)]) -> V
     for line in lines {
         // Keep line as-is without normalization - column positions should work on original text
         for (col_idx, &(col_start, col_end)) in column_positions.iter().enumerate() {
-            let cell_line = if col_end <= line.len() {
-                &line[col_start..col_end]
-            } else if col_start < line.len() {
-                &line[col_start..]
-            } else {
-                ""
-            };
+            let cell_line = slice_columns(line, col_start, col_end);
             // Trim the cell line to normalize spacing - this ensures idempotency
             // We trim both leading and trailing whitespace because alignment will be
             // recalculated based on column positions
@@ -2135,7 +2255,7 @@ fn extract_multiline_table_data(node: &SyntaxNode, config: &Config) -> Multiline
     for child in node.children() {
         match child.kind() {
             SyntaxKind::TABLE_CAPTION => {
-                let caption_text = extract_table_caption_content(&child);
+                let caption_text = extract_table_caption_content(&child, config);
                 if caption.is_none() {
                     caption = Some(caption_text);
                 }
@@ -2341,39 +2461,12 @@ pub fn format_multiline_table(node: &SyntaxNode, config: &Config) -> String {
 
         // Emit each line of the header
         for line_idx in 0..max_lines {
-            let mut line_chars: Vec<char> = vec![' '; last_col_end];
-
-            for (col_idx, cell_lines) in header_row.iter().enumerate() {
-                if let Some(&(col_start, col_end)) = positions.get(col_idx) {
-                    let cell_text = cell_lines.get(line_idx).map(|s| s.as_str()).unwrap_or("");
-                    let alignment = table_data
-                        .alignments
-                        .get(col_idx)
-                        .copied()
-                        .unwrap_or(Alignment::Default);
-
-                    let col_width = col_end - col_start;
-                    let cell_width = cell_text.trim_end().width();
-                    let total_padding = col_width.saturating_sub(cell_width);
-
-                    // Calculate text start position based on alignment
-                    let text_start_in_col = match alignment {
-                        Alignment::Left | Alignment::Default => 0,
-                        Alignment::Right => total_padding,
-                        Alignment::Center => total_padding / 2,
-                    };
-
-                    // Place characters
-                    for (i, ch) in cell_text.trim_end().chars().enumerate() {
-                        let target_pos = col_start + text_start_in_col + i;
-                        if target_pos < line_chars.len() {
-                            line_chars[target_pos] = ch;
-                        }
-                    }
-                }
-            }
-
-            output.push_str(line_chars.iter().collect::<String>().trim_end());
+            output.push_str(&render_multiline_line(
+                header_row,
+                line_idx,
+                &positions,
+                &table_data.alignments,
+            ));
             output.push('\n');
         }
 
@@ -2396,39 +2489,12 @@ pub fn format_multiline_table(node: &SyntaxNode, config: &Config) -> String {
 
         // Emit each line of the row
         for line_idx in 0..max_lines {
-            let mut line_chars: Vec<char> = vec![' '; last_col_end];
-
-            for (col_idx, cell_lines) in row.iter().enumerate() {
-                if let Some(&(col_start, col_end)) = positions.get(col_idx) {
-                    let cell_text = cell_lines.get(line_idx).map(|s| s.as_str()).unwrap_or("");
-                    let alignment = table_data
-                        .alignments
-                        .get(col_idx)
-                        .copied()
-                        .unwrap_or(Alignment::Default);
-
-                    let col_width = col_end - col_start;
-                    let cell_width = cell_text.trim_end().width();
-                    let total_padding = col_width.saturating_sub(cell_width);
-
-                    // Calculate text start position based on alignment
-                    let text_start_in_col = match alignment {
-                        Alignment::Left | Alignment::Default => 0,
-                        Alignment::Right => total_padding,
-                        Alignment::Center => total_padding / 2,
-                    };
-
-                    // Place characters
-                    for (i, ch) in cell_text.trim_end().chars().enumerate() {
-                        let target_pos = col_start + text_start_in_col + i;
-                        if target_pos < line_chars.len() {
-                            line_chars[target_pos] = ch;
-                        }
-                    }
-                }
-            }
-
-            output.push_str(line_chars.iter().collect::<String>().trim_end());
+            output.push_str(&render_multiline_line(
+                row,
+                line_idx,
+                &positions,
+                &table_data.alignments,
+            ));
             output.push('\n');
         }
 
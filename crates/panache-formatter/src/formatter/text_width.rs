@@ -0,0 +1,78 @@
+//! Display-column arithmetic on text.
+//!
+//! Layout works in terminal columns, not bytes or chars: CJK characters and
+//! most emoji take two columns, combining marks none. Positions are resolved
+//! on grapheme clusters so a cut never separates a base character from its
+//! combining marks or splits an emoji sequence, and never lands inside a
+//! multi-byte character.
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Byte offset of display column `column` in `text`: the start of the first
+/// grapheme that begins at or after it, or `text.len()`. A wide grapheme that
+/// straddles `column` stays on the left.
+pub(super) fn byte_offset_at_column(text: &str, column: usize) -> usize {
+    let mut width = 0;
+    for (idx, grapheme) in text.grapheme_indices(true) {
+        if width >= column {
+            return idx;
+        }
+        width += grapheme.width();
+    }
+    text.len()
+}
+
+/// The part of `text` between display columns `start` and `end` (exclusive),
+/// cut at grapheme boundaries as in [`byte_offset_at_column`].
+pub(super) fn slice_columns(text: &str, start: usize, end: usize) -> &str {
+    let from = byte_offset_at_column(text, start);
+    let to = byte_offset_at_column(text, end).max(from);
+    &text[from..to]
+}
+
+/// Byte length of the longest grapheme-aligned prefix of `text` that fits in
+/// `max_width` columns.
+pub(super) fn prefix_len_within_width(text: &str, max_width: usize) -> usize {
+    let mut width = 0;
+    for (idx, grapheme) in text.grapheme_indices(true) {
+        width += grapheme.width();
+        if width > max_width {
+            return idx;
+        }
+    }
+    text.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn columns_count_wide_characters_twice() {
+        let text = "日本語 text";
+        assert_eq!(byte_offset_at_column(text, 2), "日".len());
+        assert_eq!(slice_columns(text, 0, 6), "日本語");
+        assert_eq!(slice_columns(text, 7, usize::MAX), "text");
+    }
+
+    #[test]
+    fn wide_character_straddling_a_boundary_stays_left() {
+        assert_eq!(slice_columns("a日b", 0, 2), "a日");
+        assert_eq!(slice_columns("a日b", 2, 4), "b");
+    }
+
+    #[test]
+    fn combining_marks_stay_with_their_base() {
+        let text = "e\u{301}x";
+        assert_eq!(slice_columns(text, 0, 1), "e\u{301}");
+        assert_eq!(prefix_len_within_width(text, 1), "e\u{301}".len());
+    }
+
+    #[test]
+    fn prefix_stops_before_overflowing_grapheme() {
+        assert_eq!(prefix_len_within_width("ab日c", 3), 2);
+        assert_eq!(prefix_len_within_width("ab日c", 4), "ab日".len());
+        assert_eq!(prefix_len_within_width("ab", 10), 2);
+    }
+}
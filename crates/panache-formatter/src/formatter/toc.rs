@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+
+use rowan::ast::AstNode;
+use rowan::{TextRange, TextSize};
+
+use super::headings::replace_section_number;
+use crate::config::Config;
+use crate::syntax::{Heading, SyntaxKind, SyntaxNode};
+use crate::utils::implicit_heading_ids;
+use panache_parser::parser::utils::attributes::parse_attribute_content;
+
+/// A table of contents to write between a top-level
+/// `<!-- panache-toc start -->` comment and the next
+/// `<!-- panache-toc end -->`, replacing whatever was there.
+#[derive(Debug, Clone)]
+pub(super) struct TableOfContents {
+    /// Range of the start marker block.
+    pub(super) start: TextRange,
+    /// End of the end marker block: everything up to here is regenerated.
+    pub(super) end: TextSize,
+    /// The end marker as written.
+    pub(super) end_marker: String,
+    /// Bullet list linking the headings, one `\n`-terminated line each.
+    pub(super) list: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Marker {
+    Start,
+    End,
+}
+
+/// The table of contents for `root`, if it has a start marker followed by an
+/// end marker. Only top-level markers count, and only the first pair.
+///
+/// Headings between `toc.min_level` and `toc.max_level` are listed in
+/// document order, nested by level, and linked to their explicit `{#id}` or
+/// their automatic identifier. Headings marked `{.unlisted}` and headings
+/// inside block quotes or list items are left out, as Pandoc does. Section
+/// numbers rewritten under `headings.numbering` appear as rewritten, so the
+/// list is stable across runs.
+pub(super) fn table_of_contents(
+    root: &SyntaxNode,
+    config: &Config,
+    section_numbers: &HashMap<TextRange, String>,
+) -> Option<TableOfContents> {
+    let mut blocks = root.children();
+    let start = blocks.find(|node| marker(node) == Some(Marker::Start))?;
+    let end = blocks.find(|node| marker(node) == Some(Marker::End))?;
+
+    let implicit_ids: HashMap<TextRange, String> =
+        implicit_heading_ids(root, &config.formatter_extensions)
+            .into_iter()
+            .map(|entry| (entry.heading.text_range(), entry.id))
+            .collect();
+
+    let levels = config.toc.min_level..=config.toc.max_level;
+    let mut list = String::new();
+    let mut open_levels: Vec<usize> = Vec::new();
+    for heading in root.descendants().filter_map(Heading::cast) {
+        let level = heading.level();
+        if !levels.contains(&level) || !is_listed(heading.syntax()) {
+            continue;
+        }
+        let Some(id) = explicit_id(heading.syntax())
+            .or_else(|| implicit_ids.get(&heading.text_range()).cloned())
+        else {
+            continue;
+        };
+        let text = heading.text();
+        let text = text
+            .trim_end_matches(|c: char| c == '#' || c.is_whitespace())
+            .trim_start();
+        let text = match section_numbers.get(&heading.text_range()) {
+            Some(number) => replace_section_number(text, number),
+            None => text.into(),
+        };
+        if text.is_empty() {
+            continue;
+        }
+
+        while open_levels.last().is_some_and(|&open| open >= level) {
+            open_levels.pop();
+        }
+        list.push_str(&"  ".repeat(open_levels.len()));
+        list.push_str(&format!("- [{text}](#{id})\n"));
+        open_levels.push(level);
+    }
+
+    Some(TableOfContents {
+        start: start.text_range(),
+        end: end.text_range().end(),
+        end_marker: end.text().to_string().trim_end().to_string(),
+        list,
+    })
+}
+
+/// Whether `node` is a block holding nothing but a TOC marker comment.
+fn marker(node: &SyntaxNode) -> Option<Marker> {
+    if !matches!(
+        node.kind(),
+        SyntaxKind::HTML_BLOCK
+            | SyntaxKind::HTML_BLOCK_RAW
+            | SyntaxKind::COMMENT
+            | SyntaxKind::PARAGRAPH
+            | SyntaxKind::PLAIN
+    ) {
+        return None;
+    }
+    let text = node.text().to_string();
+    let inner = text
+        .trim()
+        .strip_prefix("<!--")?
+        .strip_suffix("-->")?
+        .split_whitespace()
+        .collect::<Vec<_>>();
+    match inner.as_slice() {
+        ["panache-toc", "start"] => Some(Marker::Start),
+        ["panache-toc", "end"] => Some(Marker::End),
+        _ => None,
+    }
+}
+
+fn heading_attributes(heading: &SyntaxNode) -> impl Iterator<Item = String> {
+    heading
+        .children()
+        .filter(|child| child.kind() == SyntaxKind::ATTRIBUTE)
+        .map(|attribute| {
+            let text = attribute.text().to_string();
+            text.trim()
+                .trim_start_matches('{')
+                .trim_end_matches('}')
+                .to_string()
+        })
+}
+
+fn explicit_id(heading: &SyntaxNode) -> Option<String> {
+    heading_attributes(heading)
+        .filter_map(|content| parse_attribute_content(&content)?.identifier)
+        .next()
+}
+
+fn is_listed(heading: &SyntaxNode) -> bool {
+    let unlisted = heading_attributes(heading)
+        .any(|content| content.split_whitespace().any(|part| part == ".unlisted"));
+    let nested = heading.ancestors().any(|ancestor| {
+        matches!(
+            ancestor.kind(),
+            SyntaxKind::BLOCK_QUOTE | SyntaxKind::LIST_ITEM
+        )
+    });
+    !unlisted && !nested
+}
@@ -0,0 +1,128 @@
+//! Document-wide whitespace policies: `trim-trailing-whitespace` and
+//! `final-newline`.
+//!
+//! Like line endings, both apply to the finished output, so they reach
+//! verbatim content (code blocks, raw blocks, ignored regions) too. Trailing
+//! whitespace is only kept where removing it could change how the document
+//! reads: two or more spaces before a line that continues the text, which
+//! Markdown treats as a hard line break, and whitespace after a backslash,
+//! which would otherwise become a backslash hard line break.
+
+use std::ops::Range;
+
+use crate::syntax::SyntaxNode;
+
+/// Byte ranges of the trailing whitespace in `text` that can be removed.
+/// `followed_by_content` says whether a non-blank line comes after `text`,
+/// for callers that see the document in pieces.
+pub fn removable_trailing_whitespace(text: &str, followed_by_content: bool) -> Vec<Range<usize>> {
+    let mut lines = Vec::new();
+    let mut offset = 0;
+    for raw in text.split_inclusive('\n') {
+        lines.push((offset, raw));
+        offset += raw.len();
+    }
+
+    let mut ranges = Vec::new();
+    for (idx, &(offset, raw)) in lines.iter().enumerate() {
+        let terminated = raw.ends_with('\n');
+        let line = raw.strip_suffix('\n').unwrap_or(raw);
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        let content = line.trim_end_matches([' ', '\t']);
+        if content.len() == line.len() {
+            continue;
+        }
+        let continues = match lines.get(idx + 1) {
+            Some((_, next)) => !next.trim().is_empty(),
+            None => terminated && followed_by_content,
+        };
+        if continues && is_significant(content, &line[content.len()..]) {
+            continue;
+        }
+        ranges.push(offset + content.len()..offset + line.len());
+    }
+    ranges
+}
+
+/// `text` without its removable trailing whitespace.
+pub fn trim_trailing_whitespace(text: &str, followed_by_content: bool) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    for range in removable_trailing_whitespace(text, followed_by_content) {
+        out.push_str(&text[last..range.start]);
+        last = range.end;
+    }
+    out.push_str(&text[last..]);
+    out
+}
+
+/// Whether the source of `tree` ends with a newline, which decides the final
+/// newline under `final-newline = "preserve"`.
+pub fn ends_with_newline(tree: &SyntaxNode) -> bool {
+    tree.last_token()
+        .is_some_and(|token| token.text().ends_with('\n'))
+}
+
+/// The formatted document with trailing blank lines dropped, trailing
+/// whitespace trimmed when `trim` is set, and a single final newline when
+/// `final_newline` is set.
+pub fn finish_document(output: &str, trim: bool, final_newline: bool) -> String {
+    let body = output.trim_end();
+    let mut out = if trim {
+        trim_trailing_whitespace(body, false)
+    } else {
+        body.to_string()
+    };
+    if final_newline {
+        out.push('\n');
+    }
+    out
+}
+
+/// Whether trailing whitespace after `content` can end in a line break that
+/// renders.
+fn is_significant(content: &str, trailing: &str) -> bool {
+    if content.is_empty() {
+        return false;
+    }
+    let hard_break = trailing.len() >= 2 && trailing.bytes().all(|b| b == b' ');
+    let backslashes = content.len() - content.trim_end_matches('\\').len();
+    hard_break || backslashes % 2 == 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trims_spaces_and_tabs() {
+        assert_eq!(
+            trim_trailing_whitespace("a \nb\t\n   \n", false),
+            "a\nb\n\n"
+        );
+        assert_eq!(trim_trailing_whitespace("a \r\nb\r\n", false), "a\r\nb\r\n");
+    }
+
+    #[test]
+    fn keeps_hard_line_breaks() {
+        assert_eq!(trim_trailing_whitespace("a  \nb\n", false), "a  \nb\n");
+        // Nothing follows, so the spaces cannot break a line.
+        assert_eq!(trim_trailing_whitespace("a  \n\nb\n", false), "a\n\nb\n");
+        assert_eq!(trim_trailing_whitespace("a  \n", false), "a\n");
+        assert_eq!(trim_trailing_whitespace("a  \n", true), "a  \n");
+    }
+
+    #[test]
+    fn keeps_whitespace_after_a_backslash() {
+        assert_eq!(trim_trailing_whitespace("a\\ \nb\n", false), "a\\ \nb\n");
+        assert_eq!(trim_trailing_whitespace("a\\\\ \nb\n", false), "a\\\\\nb\n");
+    }
+
+    #[test]
+    fn finish_document_controls_the_final_newline() {
+        assert_eq!(finish_document("a\n\n\n", false, true), "a\n");
+        assert_eq!(finish_document("a\n\n\n", false, false), "a");
+        assert_eq!(finish_document("a  \n", true, true), "a\n");
+        assert_eq!(finish_document("", false, false), "");
+    }
+}
@@ -0,0 +1,143 @@
+//! Line-break opportunities inside words.
+//!
+//! Reflow breaks lines at spaces, but Chinese and Japanese text has none, so a
+//! whole run of ideographs and kana reaches the wrapper as one word. Under the
+//! `east_asian_line_breaks` extension a line break between two wide characters
+//! vanishes when the document is read back, which makes every such position a
+//! safe place to wrap. Kinsoku rules still apply: a line never starts with
+//! closing punctuation or a small kana, and never ends with opening
+//! punctuation. Hangul is left to the space-based wrapper, since Korean
+//! separates words with spaces.
+
+use unicode_width::UnicodeWidthChar;
+
+/// Split `word` at the positions where a CJK line break may go. Returns the
+/// word unchanged (as a single segment) when it has none.
+pub(super) fn cjk_break_segments(word: &str) -> Vec<&str> {
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let mut prev: Option<char> = None;
+    for (idx, c) in word.char_indices() {
+        if let Some(p) = prev
+            && can_break_between(p, c)
+        {
+            segments.push(&word[start..idx]);
+            start = idx;
+        }
+        prev = Some(c);
+    }
+    segments.push(&word[start..]);
+    segments
+}
+
+/// Whether a CJK line break may go between `before` and `after`.
+pub(super) fn can_break_between(before: char, after: char) -> bool {
+    is_breakable_wide(before)
+        && is_breakable_wide(after)
+        && !is_no_break_after(before)
+        && !is_no_break_before(after)
+}
+
+fn is_breakable_wide(c: char) -> bool {
+    UnicodeWidthChar::width(c) == Some(2) && !is_hangul(c)
+}
+
+fn is_hangul(c: char) -> bool {
+    matches!(
+        c,
+        '\u{1100}'..='\u{11FF}' | '\u{3130}'..='\u{318F}' | '\u{AC00}'..='\u{D7AF}'
+    )
+}
+
+/// Opening brackets and quotes: the text they open follows on the same line.
+fn is_no_break_after(c: char) -> bool {
+    matches!(
+        c,
+        '（' | '「' | '『' | '【' | '〈' | '《' | '〔' | '［' | '｛' | '〘' | '〖'
+    )
+}
+
+/// Closing punctuation, iteration marks, the prolonged sound mark and small
+/// kana, which must not start a line.
+fn is_no_break_before(c: char) -> bool {
+    matches!(
+        c,
+        '、' | '。'
+            | '，'
+            | '．'
+            | '：'
+            | '；'
+            | '！'
+            | '？'
+            | '）'
+            | '」'
+            | '』'
+            | '】'
+            | '〉'
+            | '》'
+            | '〕'
+            | '］'
+            | '｝'
+            | '〙'
+            | '〗'
+            | '・'
+            | 'ー'
+            | '々'
+            | 'ゝ'
+            | 'ゞ'
+            | 'ヽ'
+            | 'ヾ'
+            | 'ぁ'
+            | 'ぃ'
+            | 'ぅ'
+            | 'ぇ'
+            | 'ぉ'
+            | 'っ'
+            | 'ゃ'
+            | 'ゅ'
+            | 'ょ'
+            | 'ゎ'
+            | 'ゕ'
+            | 'ゖ'
+            | 'ァ'
+            | 'ィ'
+            | 'ゥ'
+            | 'ェ'
+            | 'ォ'
+            | 'ッ'
+            | 'ャ'
+            | 'ュ'
+            | 'ョ'
+            | 'ヮ'
+            | 'ヵ'
+            | 'ヶ'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_between_ideographs() {
+        assert_eq!(cjk_break_segments("日本語"), ["日", "本", "語"]);
+    }
+
+    #[test]
+    fn keeps_latin_and_hangul_words_whole() {
+        assert_eq!(cjk_break_segments("word"), ["word"]);
+        assert_eq!(cjk_break_segments("한국어"), ["한국어"]);
+    }
+
+    #[test]
+    fn breaks_only_between_two_wide_characters() {
+        assert_eq!(cjk_break_segments("漢字abc漢字"), ["漢", "字abc漢", "字"]);
+    }
+
+    #[test]
+    fn applies_kinsoku_rules() {
+        assert_eq!(cjk_break_segments("です。次"), ["で", "す。", "次"]);
+        assert_eq!(cjk_break_segments("「引用」"), ["「引", "用」"]);
+        assert_eq!(cjk_break_segments("ちょっと"), ["ちょっ", "と"]);
+    }
+}
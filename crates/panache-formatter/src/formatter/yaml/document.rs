@@ -43,6 +43,7 @@
 use panache_parser::SyntaxNode;
 use panache_parser::syntax::{SyntaxKind, SyntaxToken};
 use rowan::{TextSize, TokenAtOffset};
+use unicode_width::UnicodeWidthStr;
 
 use super::options::{WrapMode, YamlFormatOptions};
 use crate::formatter::sentence_wrap::{self, ResolvedProfile};
@@ -636,8 +637,8 @@ fn apply_flow_wrap(buf: String, opts: &YamlFormatOptions) -> String {
         let end = usize::from(container.text_range().end());
         let col = column_of_offset(&out, start);
         let single = canonical_single_line_flow(&container);
-        let tail_chars = out[end..].split('\n').next().unwrap_or("").chars().count();
-        let line_len = col + single.chars().count() + tail_chars;
+        let tail_width = out[end..].split('\n').next().unwrap_or("").width();
+        let line_len = col + single.width() + tail_width;
         if line_len <= opts.line_width {
             continue;
         }
@@ -664,7 +665,7 @@ fn has_flow_ancestor(node: &SyntaxNode) -> bool {
 fn column_of_offset(text: &str, offset: usize) -> usize {
     let prefix = &text[..offset];
     let last_nl = prefix.rfind('\n').map(|p| p + 1).unwrap_or(0);
-    prefix[last_nl..].chars().count()
+    prefix[last_nl..].width()
 }
 
 fn canonical_single_line_flow(node: &SyntaxNode) -> String {
@@ -905,7 +906,7 @@ fn apply_plain_scalar_wrap(buf: String, opts: &YamlFormatOptions) -> String {
             continue;
         }
         let line_start = buf[..scalar_start].rfind('\n').map(|p| p + 1).unwrap_or(0);
-        let scalar_col = buf[line_start..scalar_start].chars().count();
+        let scalar_col = buf[line_start..scalar_start].width();
         let indent = depth * 2;
         let wrapped = match opts.wrap {
             WrapMode::Reflow => {
@@ -913,7 +914,7 @@ fn apply_plain_scalar_wrap(buf: String, opts: &YamlFormatOptions) -> String {
                     .find('\n')
                     .map(|p| scalar_end + p)
                     .unwrap_or(buf.len());
-                if buf[line_start..line_end].chars().count() <= opts.line_width {
+                if buf[line_start..line_end].width() <= opts.line_width {
                     continue;
                 }
                 wrap_plain_scalar_text(&text, scalar_col, indent, opts.line_width)
@@ -1085,8 +1086,8 @@ fn wrap_plain_scalar_text(text: &str, start_col: usize, indent: usize, width: us
     let mut out = String::new();
     let mut col = start_col;
     for (i, (sep, chunk)) in units.iter().enumerate() {
-        let sep_len = sep.chars().count();
-        let chunk_len = chunk.chars().count();
+        let sep_len = sep.width();
+        let chunk_len = chunk.width();
         if i != 0 && col + sep_len + chunk_len > width {
             out.push('\n');
             out.push_str(&indent_str);
@@ -6,15 +6,31 @@ pub mod syntax;
 pub mod utils;
 pub mod yaml_engine;
 
+pub use config::AttributeStyle;
+pub use config::BareUrls;
 pub use config::BlankLines;
+pub use config::BlankLinesAround;
+pub use config::BlockquoteStyle;
+pub use config::BreakableInline;
+pub use config::CodeBlockStyle;
 pub use config::Config;
 pub use config::ConfigBuilder;
+pub use config::DefinitionListStyle;
+pub use config::DefinitionMarker;
+pub use config::DivFenceLength;
+pub use config::EmphasisMarker;
+pub use config::Escaping;
+pub use config::FinalNewline;
+pub use config::HeadingNumbering;
 pub use config::HorizontalRuleStyle;
 pub use config::LineEnding;
+pub use config::LinkDestinations;
 pub use config::MathDelimiterStyle;
 pub use config::ParserOptions;
 pub use config::TabStopMode;
+pub use config::TocStyle;
 pub use config::WrapMode;
+pub use formatter::BlockFailure;
 pub use formatter::ExternalCodeBlock;
 pub use formatter::FormattedCodeMap;
 pub use formatter::collect_code_blocks;
@@ -59,6 +75,25 @@ pub fn format(input: &str, config: Option<Config>, range: Option<(usize, usize)>
     apply_line_ending(&out, target_line_ending)
 }
 
+/// Format an inline fragment (a title, caption, or table cell) without block
+/// structure. See [`parser::parse_inline`]. Surrounding whitespace is trimmed
+/// and the result has no trailing newline.
+pub fn format_inline(snippet: &str, config: &Config) -> String {
+    let snippet = snippet.trim();
+    if snippet.is_empty() {
+        return String::new();
+    }
+    let target_line_ending = match config.line_ending {
+        Some(LineEnding::Lf) => "\n",
+        Some(LineEnding::Crlf) => "\r\n",
+        Some(LineEnding::Auto) | None => detect_line_ending(snippet),
+    };
+
+    let tree = parser::parse_inline(snippet, Some(config.parser_options()));
+    let out = formatter::format_tree(&tree, config, None);
+    apply_line_ending(out.trim_end(), target_line_ending)
+}
+
 pub fn format_with_defaults(input: &str) -> String {
     format(input, None, None)
 }
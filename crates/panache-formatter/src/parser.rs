@@ -9,3 +9,7 @@ use crate::syntax::SyntaxNode;
 pub fn parse(input: &str, config: Option<ParserOptions>) -> SyntaxNode {
     panache_parser::parser::parse(input, config)
 }
+
+pub fn parse_inline(snippet: &str, config: Option<ParserOptions>) -> SyntaxNode {
+    panache_parser::parser::parse_inline(snippet, config)
+}
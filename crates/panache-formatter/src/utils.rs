@@ -1,5 +1,6 @@
 use crate::config::FormatterExtensions;
 use crate::syntax::{AstNode, Heading, SyntaxKind, SyntaxNode};
+use panache_parser::slug::{SlugStyle, Slugger};
 use rowan::NodeOrToken;
 use std::collections::HashMap;
 
@@ -215,31 +216,24 @@ pub fn implicit_heading_ids(
     extensions: &FormatterExtensions,
 ) -> Vec<ImplicitHeadingId> {
     let mut out = Vec::new();
-    let mut seen: HashMap<String, usize> = HashMap::new();
+    let mut slugger = Slugger::new(if extensions.gfm_auto_identifiers {
+        SlugStyle::Gfm
+    } else {
+        SlugStyle::Pandoc
+    });
 
     for heading in tree.descendants().filter_map(Heading::cast) {
         let raw_text = heading
             .content()
             .map(|content| content.text())
             .unwrap_or_default();
-        let normalized = normalize_label(&raw_text);
-        if normalized.is_empty() {
+        if raw_text.trim().is_empty() {
             continue;
         }
 
-        let base = heading_slugify(&normalized, extensions);
-        if base.is_empty() {
+        let Some(id) = slugger.slug(&raw_text) else {
             continue;
-        }
-
-        let count = seen.entry(base.clone()).or_insert(0);
-        let id = if *count == 0 {
-            base
-        } else {
-            format!("{}-{}", base, *count)
         };
-        *count += 1;
-
         out.push(ImplicitHeadingId {
             id,
             heading: heading.syntax().clone(),
@@ -249,73 +243,6 @@ pub fn implicit_heading_ids(
     out
 }
 
-/// Generate an auto identifier from heading text based on extension settings.
-pub fn heading_slugify(text: &str, extensions: &FormatterExtensions) -> String {
-    if extensions.gfm_auto_identifiers {
-        gfm_slugify(text)
-    } else {
-        pandoc_slugify(text)
-    }
-}
-
-/// Generate a GitHub-style auto identifier from heading text.
-pub fn gfm_slugify(text: &str) -> String {
-    let mut out = String::new();
-    let mut prev_dash = false;
-
-    for ch in text.chars() {
-        if ch.is_whitespace() {
-            if !out.is_empty() && !prev_dash {
-                out.push('-');
-                prev_dash = true;
-            }
-            continue;
-        }
-
-        for lc in ch.to_lowercase() {
-            if lc.is_alphanumeric() || lc == '_' || lc == '-' {
-                out.push(lc);
-                prev_dash = lc == '-';
-            }
-        }
-    }
-
-    while out.ends_with('-') {
-        out.pop();
-    }
-
-    out
-}
-
-/// Generate a Pandoc-style auto identifier from heading text.
-pub fn pandoc_slugify(text: &str) -> String {
-    let mut out = String::new();
-    let mut prev_dash = false;
-
-    for ch in text.chars() {
-        if ch.is_whitespace() {
-            if !out.is_empty() && !prev_dash {
-                out.push('-');
-                prev_dash = true;
-            }
-            continue;
-        }
-
-        for lc in ch.to_lowercase() {
-            if lc.is_alphanumeric() || lc == '_' || lc == '-' || lc == '.' {
-                out.push(lc);
-                prev_dash = lc == '-';
-            }
-        }
-    }
-
-    while out.ends_with('-') {
-        out.pop();
-    }
-
-    out
-}
-
 #[cfg(test)]
 mod tests {
     use super::{crossref_resolution_labels, implicit_heading_ids};
@@ -356,4 +283,14 @@ mod tests {
             .collect::<Vec<_>>();
         assert_eq!(ids, vec!["3-applications"]);
     }
+
+    #[test]
+    fn implicit_heading_ids_drop_leading_numbers_like_pandoc() {
+        let tree = crate::parser::parse("# 2.1 Methods\n\n# 42\n\n# Methods\n", None);
+        let ids = implicit_heading_ids(&tree, &FormatterExtensions::default())
+            .into_iter()
+            .map(|entry| entry.id)
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["methods", "section", "methods-1"]);
+    }
 }
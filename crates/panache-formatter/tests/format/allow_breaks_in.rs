@@ -0,0 +1,44 @@
+use panache_formatter::{BreakableInline, Config, ConfigBuilder, format};
+
+fn narrow(allow: impl IntoIterator<Item = BreakableInline>) -> Config {
+    ConfigBuilder::default()
+        .line_width(20)
+        .allow_breaks_in(allow)
+        .build()
+}
+
+#[test]
+fn link_text_breaks_by_default() {
+    let input = "Some text with [a long link text here](https://e.com) and more.\n";
+    let config = ConfigBuilder::default().line_width(20).build();
+    let output = format(input, Some(config), None);
+    assert!(output.contains("[a\n"), "{output}");
+}
+
+#[test]
+fn link_kept_on_one_line_when_not_allowed() {
+    let input = "Some text with [a long link text here](https://e.com) and more.\n";
+    let config = narrow([BreakableInline::Emphasis, BreakableInline::Span]);
+    let output = format(input, Some(config.clone()), None);
+    similar_asserts::assert_eq!(
+        output,
+        "Some text with\n[a long link text here](https://e.com)\nand more.\n"
+    );
+    similar_asserts::assert_eq!(format(&output, Some(config), None), output);
+}
+
+#[test]
+fn emphasis_kept_on_one_line_when_not_allowed() {
+    let input = "Words *emphasized phrase that is long* end.\n";
+    let config = narrow([]);
+    let output = format(input, Some(config.clone()), None);
+    similar_asserts::assert_eq!(output, "Words\n*emphasized phrase that is long*\nend.\n");
+    similar_asserts::assert_eq!(format(&output, Some(config), None), output);
+}
+
+#[test]
+fn soft_breaks_inside_kept_constructs_become_spaces() {
+    let input = "Words **strong\nphrase** end.\n";
+    let output = format(input, Some(narrow([])), None);
+    similar_asserts::assert_eq!(output, "Words\n**strong phrase**\nend.\n");
+}
@@ -1,4 +1,4 @@
-use panache_formatter::{Config, format};
+use panache_formatter::{BareUrls, Config, format};
 
 fn format_with_bare_uris(input: &str) -> String {
     let mut config = Config::default();
@@ -6,6 +6,15 @@ fn format_with_bare_uris(input: &str) -> String {
     format(input, Some(config), None)
 }
 
+fn format_bare_urls_as(input: &str, bare_urls: BareUrls) -> String {
+    let mut config = Config {
+        bare_urls,
+        ..Default::default()
+    };
+    config.parser_extensions.autolink_bare_uris = true;
+    format(input, Some(config), None)
+}
+
 // A bare URI carries no markers in the source, so it must round-trip to itself:
 // the formatter emits it losslessly, never as a fabricated `[url](url)` link.
 #[test]
@@ -77,3 +86,49 @@ fn real_bare_url_still_autolinks() {
     // Idempotent: re-formatting the bare URI is a no-op.
     similar_asserts::assert_eq!(format_with_bare_uris(&output), output);
 }
+
+#[test]
+fn bare_urls_autolink_wraps_in_angle_brackets() {
+    let input = "See https://example.com/path, or (http://google.com).\n";
+    let output = format_bare_urls_as(input, BareUrls::Autolink);
+    similar_asserts::assert_eq!(
+        output,
+        "See <https://example.com/path>, or (<http://google.com>).\n"
+    );
+    similar_asserts::assert_eq!(format_bare_urls_as(&output, BareUrls::Autolink), output);
+}
+
+#[test]
+fn bare_urls_link_writes_inline_links() {
+    let input = "See https://example.com/a_b for details.\n";
+    let output = format_bare_urls_as(input, BareUrls::Link);
+    similar_asserts::assert_eq!(
+        output,
+        "See [https://example.com/a_b](https://example.com/a_b) for details.\n"
+    );
+    similar_asserts::assert_eq!(format_bare_urls_as(&output, BareUrls::Link), output);
+}
+
+#[test]
+fn bare_urls_leaves_angle_bracket_autolinks_alone() {
+    let input = "Visit <https://example.com> today.\n";
+    let output = format_bare_urls_as(input, BareUrls::Link);
+    similar_asserts::assert_eq!(output, input);
+}
+
+#[test]
+fn bare_urls_does_not_nest_links() {
+    let input = "[see https://example.com](https://example.com)\n";
+    let output = format_bare_urls_as(input, BareUrls::Autolink);
+    similar_asserts::assert_eq!(output, input);
+}
+
+#[test]
+fn bare_urls_needs_the_extension() {
+    let config = Config {
+        bare_urls: BareUrls::Autolink,
+        ..Default::default()
+    };
+    let input = "See https://example.com today.\n";
+    similar_asserts::assert_eq!(format(input, Some(config), None), input);
+}
@@ -0,0 +1,40 @@
+use panache_formatter::{BlankLines, Config, format};
+
+fn config(blank_lines: BlankLines, before_heading: Option<usize>) -> Config {
+    Config {
+        blank_lines,
+        blank_lines_before_heading: before_heading,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn collapse_keeps_one_blank_line() {
+    let output = format(
+        "A\n\n\n\nB\n",
+        Some(config(BlankLines::Collapse, None)),
+        None,
+    );
+    similar_asserts::assert_eq!(output, "A\n\nB\n");
+}
+
+#[test]
+fn max_keeps_up_to_n_blank_lines() {
+    let input = "A\n\n\n\n\nB\n\n\nC\n\nD\n";
+    let config = config(BlankLines::Max(2), None);
+    let output = format(input, Some(config.clone()), None);
+    similar_asserts::assert_eq!(output, "A\n\n\nB\n\n\nC\n\nD\n");
+    similar_asserts::assert_eq!(format(&output, Some(config), None), output);
+}
+
+#[test]
+fn blank_lines_before_heading_are_set_exactly() {
+    let input = "# Title\n\nIntro.\n\n## One\n\nText.\n\n\n\n\n## Two\n";
+    let config = config(BlankLines::Collapse, Some(2));
+    let output = format(input, Some(config.clone()), None);
+    similar_asserts::assert_eq!(
+        output,
+        "# Title\n\nIntro.\n\n\n## One\n\nText.\n\n\n## Two\n"
+    );
+    similar_asserts::assert_eq!(format(&output, Some(config), None), output);
+}
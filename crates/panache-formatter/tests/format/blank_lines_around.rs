@@ -0,0 +1,38 @@
+use panache_formatter::config::{Extensions, Flavor};
+use panache_formatter::{BlankLinesAround, Config, format};
+
+fn gfm_config(blocks: impl IntoIterator<Item = BlankLinesAround>) -> Config {
+    let flavor = Flavor::Gfm;
+    Config {
+        flavor,
+        parser_extensions: Extensions::for_flavor(flavor),
+        blank_lines_around: blocks.into_iter().collect(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn code_blocks_are_separated_from_following_text() {
+    let input = "Text.\n```\nx <- 1\n```\nMore text.\n";
+    let config = gfm_config([BlankLinesAround::Code]);
+    let output = format(input, Some(config.clone()), None);
+    similar_asserts::assert_eq!(output, "Text.\n\n```\nx <- 1\n```\n\nMore text.\n");
+    similar_asserts::assert_eq!(format(&output, Some(config), None), output);
+}
+
+#[test]
+fn lists_are_separated_from_surrounding_blocks() {
+    let input = "Text.\n- a\n- b\n```\ncode\n```\n";
+    let config = gfm_config([BlankLinesAround::Lists]);
+    let output = format(input, Some(config.clone()), None);
+    similar_asserts::assert_eq!(output, "Text.\n\n- a\n- b\n\n```\ncode\n```\n");
+    similar_asserts::assert_eq!(format(&output, Some(config), None), output);
+}
+
+#[test]
+fn blocks_inside_list_items_stay_tight() {
+    let input = "- item\n  ```\n  code\n  ```\n- next\n";
+    let config = gfm_config([BlankLinesAround::Code, BlankLinesAround::Lists]);
+    let output = format(input, Some(config), None);
+    similar_asserts::assert_eq!(output, format(input, Some(gfm_config([])), None));
+}
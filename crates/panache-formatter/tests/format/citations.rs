@@ -25,3 +25,28 @@ fn collapses_newline_in_crossref() {
     let output = format(input, None, None);
     similar_asserts::assert_eq!(output, "as in [-@friedman2010; -@tay2023] above.\n");
 }
+
+#[test]
+fn footnote_reference_before_colon_never_starts_a_line() {
+    // A break before `[^a]:` would turn the rest of the paragraph into a
+    // footnote definition on the next pass, so the reference stays on the
+    // overflowing line.
+    let input = "aaaa aaaa aaaa aaaa aaaa aaaa aaaa aaaa aaaa aaaa aaaa aaaa aaaa aaaa aaaa bb [^a]: text follows.\n\n[^a]: The note.\n";
+    let output = format(input, None, None);
+    similar_asserts::assert_eq!(
+        output,
+        "aaaa aaaa aaaa aaaa aaaa aaaa aaaa aaaa aaaa aaaa aaaa aaaa aaaa aaaa aaaa bb [^a]:\ntext follows.\n\n[^a]: The note.\n"
+    );
+    similar_asserts::assert_eq!(format(&output, None, None), output);
+}
+
+#[test]
+fn citation_ending_exactly_at_line_width_stays_on_line() {
+    let input = "word word word word word word word word word word word word word [@doe, p. 333]. Next words.\n";
+    let output = format(input, None, None);
+    similar_asserts::assert_eq!(
+        output,
+        "word word word word word word word word word word word word word [@doe, p. 333].\nNext words.\n"
+    );
+    similar_asserts::assert_eq!(format(&output, None, None), output);
+}
@@ -0,0 +1,80 @@
+use panache_formatter::config::{Extensions, Flavor};
+use panache_formatter::{AttributeStyle, CodeBlockStyle, Config, format};
+
+fn style_config(attribute_style: AttributeStyle, lowercase_language: bool) -> Config {
+    Config {
+        code_blocks: CodeBlockStyle {
+            attribute_style,
+            lowercase_language,
+        },
+        ..Default::default()
+    }
+}
+
+const COMPLEX: &str = "``` {.python #lst-x .numberLines startFrom=\"5\"}\nx = 1\n```\n";
+
+#[test]
+fn shortcut_moves_the_language_out_of_the_braces() {
+    let output = format(COMPLEX, None, None);
+    assert_eq!(
+        output,
+        "```python {.numberLines #lst-x startFrom=\"5\"}\nx = 1\n```\n"
+    );
+}
+
+#[test]
+fn canonical_orders_identifier_classes_then_pairs() {
+    let config = style_config(AttributeStyle::Canonical, false);
+    let output = format(COMPLEX, Some(config.clone()), None);
+    assert_eq!(
+        output,
+        "```{#lst-x .python .numberLines startFrom=\"5\"}\nx = 1\n```\n"
+    );
+    assert_eq!(format(&output, Some(config.clone()), None), output);
+
+    let lone = format("``` {.python}\nx = 1\n```\n", Some(config), None);
+    assert_eq!(lone, "```python\nx = 1\n```\n");
+}
+
+#[test]
+fn explicit_puts_the_language_first_inside_the_braces() {
+    let config = style_config(AttributeStyle::Explicit, false);
+    let output = format("```python {#lst-x}\nx = 1\n```\n", Some(config), None);
+    assert_eq!(output, "```{.python #lst-x}\nx = 1\n```\n");
+}
+
+#[test]
+fn preserve_keeps_info_strings_as_written() {
+    let config = style_config(AttributeStyle::Preserve, true);
+    let output = format(COMPLEX, Some(config), None);
+    assert_eq!(
+        output,
+        "```{.python #lst-x .numberLines startFrom=\"5\"}\nx = 1\n```\n"
+    );
+}
+
+#[test]
+fn lowercase_language_applies_to_display_blocks_only() {
+    let config = style_config(AttributeStyle::Shortcut, true);
+    let output = format(
+        "``` {.Python .numberLines}\nx = 1\n```\n",
+        Some(config.clone()),
+        None,
+    );
+    assert_eq!(output, "```python {.numberLines}\nx = 1\n```\n");
+
+    let config = Config {
+        flavor: Flavor::Quarto,
+        parser_extensions: Extensions::for_flavor(Flavor::Quarto),
+        ..config
+    };
+    let chunk = format("```{R}\nx <- 1\n```\n", Some(config), None);
+    assert!(chunk.starts_with("```{R}"), "{chunk}");
+}
+
+#[test]
+fn attribute_values_with_quotes_survive() {
+    let input = "``` {.python title=\"a \\\"b\\\"\"}\nx = 1\n```\n";
+    let output = format(input, None, None);
+    assert_eq!(output, "```python {title=\"a \\\"b\\\"\"}\nx = 1\n```\n");
+}
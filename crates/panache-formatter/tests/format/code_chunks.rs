@@ -245,6 +245,26 @@ fn hashpipe_yaml_wrap_accounts_for_comment_prefix_width() {
     }
 }
 
+#[test]
+fn hashpipe_wrap_uses_code_comment_width() {
+    let input = "```{r}\n#| fig-cap: A caption long enough to wrap once the comment width is narrower than the line width.\n```\n";
+    let config = Config {
+        line_width: 120,
+        code_comment_width: Some(50),
+        ..quarto_config()
+    };
+    let output = format(input, Some(config), None);
+
+    let hashpipe: Vec<&str> = output
+        .lines()
+        .filter(|line| line.starts_with("#|"))
+        .collect();
+    assert!(hashpipe.len() > 1, "expected wrapped option:\n{output}");
+    for line in hashpipe {
+        assert!(line.len() <= 50, "hashpipe line exceeded width: {line}");
+    }
+}
+
 #[test]
 fn hashpipe_fig_cap_list_value_is_idempotent() {
     let input = "```{r}\n#| fig-cap:\n#|   - A\n#|   - B\n```\n";
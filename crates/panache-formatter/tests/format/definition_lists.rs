@@ -1,6 +1,6 @@
-use panache_formatter::Config;
 use panache_formatter::config::{PandocCompat, WrapMode};
 use panache_formatter::format;
+use panache_formatter::{Config, ConfigBuilder, DefinitionListStyle, DefinitionMarker};
 
 #[test]
 fn definition_list_wrapped_continuation_is_idempotent() {
@@ -115,3 +115,76 @@ fn definition_item_with_code_block_formats_as_loose() {
     let output = format(input, None, None);
     assert_eq!(output, expected);
 }
+
+fn definition_style(marker: DefinitionMarker, spaces: usize, compact: Option<bool>) -> Config {
+    ConfigBuilder::default()
+        .definition_lists(DefinitionListStyle {
+            marker,
+            spaces,
+            compact,
+        })
+        .build()
+}
+
+#[test]
+fn definition_marker_and_spacing_are_configurable() {
+    let input = "Term\n:   Definition\n\n    Second paragraph.\n";
+    let cfg = definition_style(DefinitionMarker::Tilde, 1, None);
+
+    let output1 = format(input, Some(cfg.clone()), None);
+    let output2 = format(&output1, Some(cfg), None);
+
+    similar_asserts::assert_eq!(output1, "Term\n\n~ Definition\n\n    Second paragraph.\n");
+    similar_asserts::assert_eq!(output1, output2, "Formatting should be idempotent");
+}
+
+#[test]
+fn definition_wraps_after_a_narrow_marker() {
+    let input = "Term\n:   one two three four five six\n";
+    let cfg = Config {
+        line_width: 20,
+        ..definition_style(DefinitionMarker::Colon, 1, None)
+    };
+
+    let output1 = format(input, Some(cfg.clone()), None);
+    let output2 = format(&output1, Some(cfg), None);
+
+    similar_asserts::assert_eq!(output1, "Term\n: one two three four\n    five six\n");
+    similar_asserts::assert_eq!(output1, output2, "Formatting should be idempotent");
+}
+
+#[test]
+fn compact_true_removes_blank_line_between_term_and_definition() {
+    let input = "Term 1\n\n:   Definition 1\n\nTerm 2\n:   Definition 2\n";
+    let cfg = definition_style(DefinitionMarker::Colon, 3, Some(true));
+
+    let output = format(input, Some(cfg), None);
+
+    similar_asserts::assert_eq!(
+        output,
+        "Term 1\n:   Definition 1\n\nTerm 2\n:   Definition 2\n"
+    );
+}
+
+#[test]
+fn compact_false_adds_blank_line_between_term_and_definition() {
+    let input = "Term 1\n\n:   Definition 1\n\nTerm 2\n:   Definition 2\n";
+    let cfg = definition_style(DefinitionMarker::Colon, 3, Some(false));
+
+    let output = format(input, Some(cfg), None);
+
+    similar_asserts::assert_eq!(
+        output,
+        "Term 1\n\n:   Definition 1\n\nTerm 2\n\n:   Definition 2\n"
+    );
+}
+
+#[test]
+fn compact_true_keeps_multi_block_definitions_loose() {
+    let input = "Term\n\n:   First paragraph.\n\n    Second paragraph.\n";
+    let cfg = definition_style(DefinitionMarker::Colon, 3, Some(true));
+
+    let output = format(input, Some(cfg), None);
+
+    similar_asserts::assert_eq!(output, input);
+}
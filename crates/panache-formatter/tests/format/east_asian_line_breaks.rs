@@ -62,3 +62,36 @@ fn suppresses_inside_link_text() {
     let out2 = format(&out, Some(cfg_with_ext(true)), None);
     assert_eq!(out, out2);
 }
+
+fn narrow_cfg_with_ext(enabled: bool, line_width: usize) -> Config {
+    Config {
+        line_width,
+        ..cfg_with_ext(enabled)
+    }
+}
+
+#[test]
+fn reflow_breaks_cjk_runs_between_characters() {
+    let input = "日本語の文章を折り返します。\n";
+    let cfg = narrow_cfg_with_ext(true, 10);
+    let out = format(input, Some(cfg.clone()), None);
+    assert_eq!(out, "日本語の文\n章を折り返\nします。\n");
+    let out2 = format(&out, Some(cfg), None);
+    assert_eq!(out, out2);
+}
+
+#[test]
+fn reflow_keeps_closing_punctuation_off_line_starts() {
+    let input = "あいうえ。かきく\n";
+    let out = format(input, Some(narrow_cfg_with_ext(true, 8)), None);
+    assert_eq!(out, "あいう\nえ。かき\nく\n");
+}
+
+#[test]
+fn extension_off_keeps_cjk_runs_whole() {
+    // Without the extension a line break reads back as a space, so a run of
+    // wide characters is never split.
+    let input = "日本語の文章を折り返します。\n";
+    let out = format(input, Some(narrow_cfg_with_ext(false, 10)), None);
+    assert_eq!(out, input);
+}
@@ -1,4 +1,11 @@
-use panache_formatter::format;
+use panache_formatter::{Config, ConfigBuilder, EmphasisMarker, format};
+
+fn markers(emphasis: EmphasisMarker, strong: EmphasisMarker) -> Config {
+    ConfigBuilder::default()
+        .emphasis_marker(emphasis)
+        .strong_marker(strong)
+        .build()
+}
 
 // Emphasis that OPENS with a code span must keep the space between the code
 // span and the following word. Regression: `**`reg_schema` treats**` lost the
@@ -30,3 +37,39 @@ fn strong_with_code_span_in_middle_is_unchanged() {
     similar_asserts::assert_eq!(output, "**text `code` word**\n");
     similar_asserts::assert_eq!(format(&output, None, None), output);
 }
+
+#[test]
+fn underscore_markers() {
+    let config = markers(EmphasisMarker::Underscore, EmphasisMarker::Underscore);
+    let input = "Some *emphasis* and **strong** text.\n";
+    let output = format(input, Some(config.clone()), None);
+    similar_asserts::assert_eq!(output, "Some _emphasis_ and __strong__ text.\n");
+    similar_asserts::assert_eq!(format(&output, Some(config), None), output);
+}
+
+// Underscores cannot delimit emphasis inside a word, and a span nested flush
+// inside another must not repeat its delimiter (`___x_ y__`); both keep
+// asterisks.
+#[test]
+fn underscore_markers_fall_back_to_asterisks() {
+    let config = markers(EmphasisMarker::Underscore, EmphasisMarker::Underscore);
+    let input = "un*frigging*believable and **_x_ y**\n";
+    let output = format(input, Some(config.clone()), None);
+    similar_asserts::assert_eq!(output, "un*frigging*believable and __*x* y__\n");
+    similar_asserts::assert_eq!(format(&output, Some(config), None), output);
+}
+
+#[test]
+fn preserve_markers_keep_each_span() {
+    let config = markers(EmphasisMarker::Preserve, EmphasisMarker::Preserve);
+    let input = "*one* _two_ **three** __four__\n";
+    let output = format(input, Some(config), None);
+    similar_asserts::assert_eq!(output, input);
+}
+
+#[test]
+fn emphasis_and_strong_markers_are_independent() {
+    let config = markers(EmphasisMarker::Underscore, EmphasisMarker::Asterisk);
+    let output = format("*one* __two__\n", Some(config), None);
+    similar_asserts::assert_eq!(output, "_one_ **two**\n");
+}
@@ -0,0 +1,50 @@
+use panache_formatter::{Config, ConfigBuilder, Escaping, format};
+
+fn minimal() -> Config {
+    ConfigBuilder::default().escaping(Escaping::Minimal).build()
+}
+
+#[test]
+fn preserve_keeps_superfluous_escapes() {
+    let input = "Hello\\, world\\? Yes\\.\n";
+    let output = format(input, None, None);
+    similar_asserts::assert_eq!(output, input);
+}
+
+#[test]
+fn minimal_drops_superfluous_escapes() {
+    let input = "Hello\\, world\\? Yes\\; a\\/b\\.\n";
+    let output = format(input, Some(minimal()), None);
+    similar_asserts::assert_eq!(output, "Hello, world? Yes; a/b.\n");
+    similar_asserts::assert_eq!(format(&output, Some(minimal()), None), output);
+}
+
+#[test]
+fn minimal_keeps_escapes_that_carry_meaning() {
+    let input = "\\*not emphasis\\* and \\`code\\` and \\[link\\]\n";
+    let output = format(input, Some(minimal()), None);
+    similar_asserts::assert_eq!(output, input);
+}
+
+// `2020\.` at the start of a paragraph would otherwise become an ordered list.
+#[test]
+fn minimal_keeps_escaped_period_after_digits() {
+    let input = "2020\\. A good year.\n";
+    let output = format(input, Some(minimal()), None);
+    similar_asserts::assert_eq!(output, input);
+}
+
+#[test]
+fn minimal_keeps_escapes_that_prevent_links_and_entities() {
+    let input = "\\[a\\]\\(b) and \\&amp; but A\\&B\n";
+    let output = format(input, Some(minimal()), None);
+    similar_asserts::assert_eq!(output, "\\[a\\]\\(b) and \\&amp; but A&B\n");
+}
+
+// Under `smart`, `\'` and `\"` keep quotes straight.
+#[test]
+fn minimal_keeps_escaped_quotes_under_smart() {
+    let input = "It\\'s \\\"quoted\\\".\n";
+    let output = format(input, Some(minimal()), None);
+    similar_asserts::assert_eq!(output, input);
+}
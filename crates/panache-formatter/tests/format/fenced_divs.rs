@@ -1,4 +1,4 @@
-use panache_formatter::format;
+use panache_formatter::{ConfigBuilder, DivFenceLength, format};
 
 #[test]
 fn fenced_div_strips_leading_and_trailing_blank_lines_in_body() {
@@ -123,3 +123,67 @@ A line that mentions colons: just a continuation line here.
     let output = format(input, None, None);
     assert_eq!(output, expected);
 }
+
+const NESTED_THREE_COLON_DIVS: &str = "\
+::: outer
+Outer text.
+
+::: inner
+Inner text.
+:::
+:::
+";
+
+#[test]
+fn fenced_div_fence_length_is_depth_based_by_default() {
+    let expected = "\
+::: outer
+Outer text.
+
+::::: inner
+Inner text.
+:::::
+:::
+";
+    let output = format(NESTED_THREE_COLON_DIVS, None, None);
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn fenced_div_fence_length_preserve_keeps_source_colons() {
+    let config = ConfigBuilder::default()
+        .div_fence_length(DivFenceLength::Preserve)
+        .build();
+    let output = format(NESTED_THREE_COLON_DIVS, Some(config.clone()), None);
+    assert_eq!(output, NESTED_THREE_COLON_DIVS);
+
+    let input = "\
+:::: outer
+::::: inner
+Inner text.
+:::::
+::::
+";
+    let output = format(input, Some(config), None);
+    assert_eq!(output, input);
+}
+
+#[test]
+fn fenced_div_fence_length_fixed_uses_one_length() {
+    let config = ConfigBuilder::default()
+        .div_fence_length(DivFenceLength::Fixed(4))
+        .build();
+    let expected = "\
+:::: outer
+Outer text.
+
+:::: inner
+Inner text.
+::::
+::::
+";
+    let output1 = format(NESTED_THREE_COLON_DIVS, Some(config.clone()), None);
+    let output2 = format(&output1, Some(config), None);
+    assert_eq!(output1, expected);
+    assert_eq!(output2, expected, "Formatting should be idempotent");
+}
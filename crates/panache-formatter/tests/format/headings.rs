@@ -1,4 +1,4 @@
-use panache_formatter::format;
+use panache_formatter::{Config, ConfigBuilder, HeadingNumbering, format};
 
 #[test]
 fn atx_trailing_hashes_are_removed() {
@@ -99,3 +99,65 @@ fn blockquote_horizontal_rule_respects_available_width() {
     let out = format(input, Some(cfg), None);
     assert_eq!(out, expected);
 }
+
+fn numbering(mode: HeadingNumbering) -> Config {
+    ConfigBuilder::default().heading_numbering(mode).build()
+}
+
+#[test]
+fn heading_numbers_are_preserved_by_default() {
+    let input = "## 3 Methods\n\n## 1 Introduction\n";
+    assert_eq!(format(input, None, None), input);
+}
+
+#[test]
+fn renumber_recomputes_section_numbers_from_order_and_level() {
+    let input = "\
+# Report
+
+## 2 Methods
+
+### 2.2 Data
+
+### 2.1 Models
+
+## 1. Results {#results}
+
+## References {-}
+";
+    let expected = "\
+# Report
+
+## 1 Methods
+
+### 1.1 Data
+
+### 1.2 Models
+
+## 2. Results {#results}
+
+## References {-}
+";
+    let cfg = numbering(HeadingNumbering::Renumber);
+    let out = format(input, Some(cfg.clone()), None);
+    assert_eq!(out, expected);
+    assert_eq!(format(&out, Some(cfg), None), expected);
+}
+
+#[test]
+fn strip_removes_section_numbers() {
+    let input = "## 1 Introduction\n\n### 1.1. Scope\n\n## Summary\n";
+    let expected = "## Introduction\n\n### Scope\n\n## Summary\n";
+    let cfg = numbering(HeadingNumbering::Strip);
+    assert_eq!(format(input, Some(cfg), None), expected);
+}
+
+#[test]
+fn unnumbered_headings_keep_their_text() {
+    let input = "## 1 Introduction\n\n## 1 Appendix {.unnumbered}\n";
+    let cfg = numbering(HeadingNumbering::Strip);
+    assert_eq!(
+        format(input, Some(cfg), None),
+        "## Introduction\n\n## 1 Appendix {.unnumbered}\n"
+    );
+}
@@ -174,3 +174,45 @@ def   foo():
     assert!(output.contains("def   foo():"));
     assert!(output.contains("    return   42"));
 }
+
+#[test]
+fn test_ignore_next_block() {
+    let input = r#"<!-- panache-ignore -->
+
+| a |  b |
+|---|----|
+| 1 |  2 |
+
+| c |  d |
+|---|----|
+| 3 |  4 |
+"#;
+
+    let output = format_with_defaults(input);
+
+    // The table after the comment is kept byte-for-byte
+    assert!(output.contains("<!-- panache-ignore -->\n\n| a |  b |\n|---|----|\n| 1 |  2 |\n"));
+    // Only the next block: the second table is normalized
+    assert!(!output.contains("| c |  d |"));
+}
+
+#[test]
+fn test_ignore_next_paragraph_directly_after_comment() {
+    let input =
+        "<!-- panache-ignore -->\nThis    has    weird     spacing\n\nThis    is    normal.\n";
+
+    let output = format_with_defaults(input);
+
+    assert!(output.contains("This    has    weird     spacing"));
+    assert!(output.contains("This is normal."));
+}
+
+#[test]
+fn test_ignore_next_list() {
+    let input = "<!-- panache-ignore -->\n\n*   one\n*   two\n\nAfter    the    list.\n";
+
+    let output = format_with_defaults(input);
+
+    assert!(output.contains("*   one\n*   two\n"));
+    assert!(output.contains("After the list."));
+}
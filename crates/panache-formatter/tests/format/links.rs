@@ -1,4 +1,4 @@
-use panache_formatter::format;
+use panache_formatter::{Config, ConfigBuilder, LinkDestinations, format};
 
 #[test]
 fn markdown_link_no_break() {
@@ -121,3 +121,45 @@ fn literal_brackets_with_failed_emphasis_round_trip_under_single_backslash_math(
         "literal `[` must not be escaped to `\\[` under tex_math_single_backslash, got: {output1:?}"
     );
 }
+
+fn link_destinations(style: LinkDestinations) -> Config {
+    ConfigBuilder::default().link_destinations(style).build()
+}
+
+#[test]
+fn link_destinations_are_preserved_by_default() {
+    let input = "See [the report](<my report.pdf>) and [home](<https://example.com>).\n";
+    assert_eq!(format(input, None, None), input);
+}
+
+#[test]
+fn angle_brackets_wrap_destinations_with_spaces() {
+    let cfg = link_destinations(LinkDestinations::AngleBrackets);
+    let input = "See [the report](<my report.pdf> \"PDF\") and [home](<https://example.com>).\n";
+    let expected = "See [the report](<my report.pdf> \"PDF\") and [home](https://example.com).\n";
+    let output = format(input, Some(cfg.clone()), None);
+    assert_eq!(output, expected);
+    assert_eq!(format(&output, Some(cfg), None), expected);
+}
+
+#[test]
+fn percent_encode_rewrites_destinations_with_spaces() {
+    let cfg = link_destinations(LinkDestinations::PercentEncode);
+    let input = "See [the report](<my report.pdf>) and ![a chart](<figures/chart 1.png>).\n";
+    let expected = "See [the report](my%20report.pdf) and ![a chart](figures/chart%201.png).\n";
+    let output = format(input, Some(cfg.clone()), None);
+    assert_eq!(output, expected);
+    assert_eq!(format(&output, Some(cfg), None), expected);
+}
+
+#[test]
+fn link_destination_with_spaces_survives_reflow() {
+    let cfg = ConfigBuilder::default()
+        .line_width(30)
+        .link_destinations(LinkDestinations::AngleBrackets)
+        .build();
+    let input = "Read the [quarterly report](<reports/q1 summary.pdf>) before the meeting.\n";
+    let output = format(input, Some(cfg.clone()), None);
+    assert!(output.contains("(<reports/q1 summary.pdf>)"), "{output}");
+    assert_eq!(format(&output, Some(cfg), None), output);
+}
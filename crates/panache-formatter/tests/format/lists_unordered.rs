@@ -299,3 +299,22 @@ fn content_visible_div_with_ordered_item_does_not_gain_leading_blank_line() {
     let output2 = format(&output1, None, None);
     assert_eq!(output1, output2, "Formatting should be idempotent");
 }
+
+#[test]
+fn code_block_after_leading_blockquote_keeps_hanging_indent() {
+    let input = "- > quote\n\n  ```\n  code\n  ```\n";
+    let output = format(input, None, None);
+    assert!(output.contains("\n\n  ```\n  code\n  ```\n"), "{output}");
+    similar_asserts::assert_eq!(format(&output, None, None), output);
+}
+
+#[test]
+fn blocks_after_leading_heading_keep_blank_lines_between_them() {
+    let input = "- # Heading\n\n  Paragraph.\n\n  ```\n  code\n  ```\n";
+    let output = format(input, None, None);
+    assert!(
+        output.contains("  Paragraph.\n\n  ```\n  code\n  ```\n"),
+        "{output}"
+    );
+    similar_asserts::assert_eq!(format(&output, None, None), output);
+}
@@ -1,12 +1,17 @@
 mod admonitions;
+mod allow_breaks_in;
 mod bare_uris;
+mod blank_lines;
+mod blank_lines_around;
 mod bullet_standardization;
 mod citations;
+mod code_block_attributes;
 mod code_chunks;
 mod comments;
 mod definition_lists;
 mod east_asian_line_breaks;
 mod emphasis;
+mod escaping;
 mod fenced_divs;
 mod frontmatter;
 mod header_attributes;
@@ -22,6 +27,7 @@ mod line_blocks;
 mod links;
 mod lists_unordered;
 mod math;
+mod max_width_strict;
 mod nbsp;
 mod no_break_abbreviations;
 mod paragraphs;
@@ -36,7 +42,10 @@ mod strikeout;
 mod subscript;
 mod superscript;
 mod tables;
+mod tabs;
 mod tex;
+mod toc;
+mod whitespace;
 mod yaml_double_to_folded;
 mod yaml_folded_wrap;
 mod yaml_frontmatter;
@@ -541,3 +541,24 @@ $$t(\mathbf{y}) = \sum_{i=1}^N x_i y_i = \mathbf{X}^T \mathbf{y} \quad \text{and
     let output2 = format(&output1, Some(config), None);
     assert_eq!(output1, output2, "Formatting should be idempotent");
 }
+
+#[test]
+fn inline_math_ending_exactly_at_line_width_stays_on_line() {
+    let input = "word word word word word word word word word word word word word $a + b = cdef$, then more.\n";
+    let output = format(input, None, None);
+    similar_asserts::assert_eq!(
+        output,
+        "word word word word word word word word word word word word word $a + b = cdef$,\nthen more.\n"
+    );
+    similar_asserts::assert_eq!(format(&output, None, None), output);
+}
+
+#[test]
+fn inline_math_at_end_of_blockquote_line_keeps_following_space() {
+    // The soft break after `$x$` must still separate it from `after.` when the
+    // math sits directly after a `>` marker with no space.
+    let input = "> Text before\n>$x$\n> after.\n";
+    let output = format(input, None, None);
+    similar_asserts::assert_eq!(output, "> Text before $x$ after.\n");
+    similar_asserts::assert_eq!(format(&output, None, None), output);
+}
@@ -0,0 +1,54 @@
+use panache_formatter::{Config, format};
+
+const URL: &str = "https://example.com/a/very/long/path/that/does/not/fit";
+
+fn config(strict: bool) -> Config {
+    Config {
+        line_width: 20,
+        max_width_strict: strict,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn strict_splits_words_wider_than_the_line() {
+    let input = "Words: abcdefghijklmnopqrstuvwxyz0123456789 end.\n";
+    let output = format(input, Some(config(true)), None);
+    similar_asserts::assert_eq!(
+        output,
+        "Words: abcdefghijklm\nnopqrstuvwxyz0123456\n789 end.\n"
+    );
+    similar_asserts::assert_eq!(format(&output, Some(config(true)), None), output);
+
+    let loose = format(input, Some(config(false)), None);
+    similar_asserts::assert_eq!(
+        loose,
+        "Words:\nabcdefghijklmnopqrstuvwxyz0123456789\nend.\n"
+    );
+}
+
+#[test]
+fn strict_moves_long_link_destinations_to_references() {
+    let input = format!("See [docs]({URL}) here.\n");
+    let output = format(&input, Some(config(true)), None);
+    similar_asserts::assert_eq!(output, format!("See [docs][1] here.\n\n[1]: {URL}\n"));
+    similar_asserts::assert_eq!(format(&output, Some(config(true)), None), output);
+
+    let loose = format(&input, Some(config(false)), None);
+    assert!(loose.contains(&format!("[docs]({URL})")), "{loose}");
+}
+
+#[test]
+fn strict_reference_labels_skip_bracketed_numbers() {
+    let input = format!("Note [1] and ![a plot]({URL}/plot.png).\n");
+    let config = Config {
+        line_width: 40,
+        ..config(true)
+    };
+    let output = format(&input, Some(config), None);
+    assert!(output.contains("![a plot][2]"), "{output}");
+    assert!(
+        output.ends_with(&format!("\n\n[2]: {URL}/plot.png\n")),
+        "{output}"
+    );
+}
@@ -1,5 +1,15 @@
-use panache_formatter::config::{Extensions, Flavor};
-use panache_formatter::{Config, format};
+use panache_formatter::config::{Extensions, Flavor, WrapMode};
+use panache_formatter::{BlockquoteStyle, Config, format};
+
+fn quote_config(marker_spacing: usize, collapse_lazy: bool) -> Config {
+    Config {
+        blockquotes: BlockquoteStyle {
+            marker_spacing,
+            collapse_lazy,
+        },
+        ..Default::default()
+    }
+}
 
 #[test]
 fn quote_single_line() {
@@ -39,7 +49,7 @@ fn quote_with_fenced_code_block() {
 
     // Use config with empty formatters to avoid external formatter invocation
     let config = panache_formatter::Config {
-        formatters: std::collections::HashMap::new(),
+        formatters: std::collections::BTreeMap::new(),
         ..Default::default()
     };
 
@@ -293,3 +303,46 @@ fn blockquote_list_continuation_not_preserved_before_paragraph() {
         "Blank suffix should not be preserved when next significant block is not code.\nOutput:\n{output}"
     );
 }
+
+#[test]
+fn blockquote_marker_spacing_zero() {
+    let input = "> A quote.\n>\n> > Nested.\n> >\n> > Again.\n";
+    let config = quote_config(0, true);
+    let output = format(input, Some(config.clone()), None);
+    similar_asserts::assert_eq!(output, ">A quote.\n>\n>>Nested.\n>>\n>>Again.\n");
+    similar_asserts::assert_eq!(format(&output, Some(config), None), output);
+}
+
+#[test]
+fn blockquote_marker_spacing_zero_keeps_indented_content() {
+    // The first space after `>` is part of the marker, so indented content
+    // needs one more to keep its indentation.
+    let input = "> Text:\n>\n> ```\n>   indented\n> ```\n>\n> - item\n>\n>   continued\n";
+    let spaced = format(input, None, None);
+    let unspaced = format(input, Some(quote_config(0, true)), None);
+    similar_asserts::assert_eq!(unspaced.replace("> ", ">"), spaced.replace("> ", ">"));
+    assert!(
+        unspaced.contains("\n>   indented\n"),
+        "indented code must keep its indentation.\nOutput:\n{unspaced}"
+    );
+    assert!(
+        unspaced.contains("\n>   continued\n"),
+        "list continuation must stay inside the item.\nOutput:\n{unspaced}"
+    );
+}
+
+#[test]
+fn blockquote_lazy_lines_kept_with_collapse_lazy_false() {
+    let input = "> This is a block quote. This\nparagraph has two lines.\n";
+    let mut config = quote_config(1, false);
+    config.wrap = Some(WrapMode::Preserve);
+    let output = format(input, Some(config.clone()), None);
+    similar_asserts::assert_eq!(output, input);
+
+    config.blockquotes.collapse_lazy = true;
+    let output = format(input, Some(config), None);
+    similar_asserts::assert_eq!(
+        output,
+        "> This is a block quote. This\n> paragraph has two lines.\n"
+    );
+}
@@ -1,5 +1,6 @@
 use panache_formatter::config::WrapMode;
 use panache_formatter::{Config, ConfigBuilder, format};
+use unicode_width::UnicodeWidthStr;
 
 #[test]
 fn test_basic_pipe_table() {
@@ -116,6 +117,22 @@ fn test_pipe_table_caption_reflow_wraps() {
     assert!(result.contains("\n    when reflow mode is enabled for formatting."));
 }
 
+#[test]
+fn test_pipe_table_caption_uses_table_max_width() {
+    let input = "| A | B |\n|---|---|\n| C | D |\n\n: A long caption that should stay on one line because tables get a wider budget.";
+    let config = Config {
+        wrap: Some(WrapMode::Reflow),
+        line_width: 40,
+        table_max_width: Some(100),
+        ..Default::default()
+    };
+
+    let result = format(input, Some(config), None);
+    assert!(result.contains(
+        "  : A long caption that should stay on one line because tables get a wider budget.\n"
+    ));
+}
+
 #[test]
 fn test_pipe_table_caption_sentence_wraps() {
     let input =
@@ -442,6 +459,47 @@ fn test_multiline_table_with_wide_chars_stays_idempotent() {
     assert_eq!(first, second);
 }
 
+/// Display column at which `needle` starts on the line containing it.
+fn display_column_of(output: &str, needle: &str) -> usize {
+    let line = output
+        .lines()
+        .find(|line| line.contains(needle))
+        .unwrap_or_else(|| panic!("{needle:?} missing from:\n{output}"));
+    line[..line.find(needle).unwrap()].width()
+}
+
+// Columns are display columns: a CJK character takes two of them. Placing
+// cells on a char grid shifted every column after wide text one step left.
+#[test]
+fn test_multiline_table_aligns_columns_after_wide_chars() {
+    let input = "-------------------\n名前       説明\n---------- --------\nりんご     赤い\n\nバナナ     黄色い\n-------------------\n";
+    let result = format(input, None, None);
+
+    let column = display_column_of(&result, "説明");
+    assert_eq!(display_column_of(&result, "赤い"), column, "{result}");
+    assert_eq!(display_column_of(&result, "黄色い"), column, "{result}");
+    assert_eq!(format(&result, None, None), result);
+}
+
+// The header's column boundaries fall inside multi-byte characters; slicing
+// on byte offsets panicked.
+#[test]
+fn test_simple_table_with_wide_header_chars() {
+    let input = "名前の列 値\n-------- ----\nりんご   1\n";
+    let result = format(input, None, None);
+
+    assert!(
+        result.contains("名前の列") && result.contains("りんご"),
+        "{result}"
+    );
+    assert_eq!(
+        display_column_of(&result, "値"),
+        display_column_of(&result, "1"),
+        "{result}"
+    );
+    assert_eq!(format(&result, None, None), result);
+}
+
 // Output geometry is recomputed from content (pandoc-style: dash run =
 // max-content-width + 2), so oversized source separators are normalized away.
 #[test]
@@ -486,6 +544,29 @@ fn test_pipe_table_indent_three() {
     assert_eq!(result, expected);
 }
 
+// A pipe table whose aligned form would exceed `table-max-width` is written
+// without column padding; one that fits stays aligned.
+#[test]
+fn test_pipe_table_wider_than_table_max_width_is_compact() {
+    let input = "| Name | Description |\n|:--|--:|\n| x | a fairly long description |\n";
+    let expected = "| Name | Description |\n| :-- | --: |\n| x | a fairly long description |\n";
+
+    let config = ConfigBuilder::default()
+        .table_indent(0)
+        .table_max_width(30)
+        .build();
+    let result = format(input, Some(config.clone()), None);
+    assert_eq!(result, expected);
+    assert_eq!(format(&result, Some(config), None), result);
+
+    let config = ConfigBuilder::default()
+        .table_indent(0)
+        .table_max_width(40)
+        .build();
+    let result = format(input, Some(config), None);
+    assert!(result.starts_with("| Name | Description               |\n"));
+}
+
 // `table-indent` governs simple tables too, not just pipe tables.
 #[test]
 fn test_simple_table_honors_table_indent() {
@@ -626,3 +707,18 @@ fn test_simple_table_dash_run_is_content_width_plus_two() {
     let sep = result.lines().nth(1).unwrap();
     assert_eq!(sep, "--------------- -------", "got:\n{result}");
 }
+
+#[test]
+fn pipe_table_cells_get_inline_normalization() {
+    let config = ConfigBuilder::default()
+        .emphasis_marker(panache_formatter::EmphasisMarker::Underscore)
+        .escaping(panache_formatter::Escaping::Minimal)
+        .build();
+    let input = "| *a* | b\\, c |\n|---|---|\n| x \\| y | `code` |\n";
+    let output = format(input, Some(config.clone()), None);
+    assert!(output.contains("| _a_ "), "{output}");
+    assert!(output.contains("| b, c "), "{output}");
+    assert!(output.contains("| x \\| y "), "{output}");
+    assert!(output.contains("| `code` "), "{output}");
+    assert_eq!(format(&output, Some(config), None), output);
+}
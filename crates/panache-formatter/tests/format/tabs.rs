@@ -0,0 +1,19 @@
+use panache_formatter::{ConfigBuilder, format};
+
+#[test]
+fn tab_width_decides_whether_a_tab_indents_code() {
+    let cfg = ConfigBuilder::default().tab_width(2).build();
+    let input = "Text\n\n\tnot code\n";
+    similar_asserts::assert_eq!(format(input, Some(cfg), None), "Text\n\nnot code\n");
+}
+
+#[test]
+fn tab_width_decides_list_continuation() {
+    let input = "1. item\n\n\tcontinued\n";
+    similar_asserts::assert_eq!(format(input, None, None), "1. item\n\n   continued\n");
+
+    let cfg = ConfigBuilder::default().tab_width(2).build();
+    let output = format(input, Some(cfg.clone()), None);
+    similar_asserts::assert_eq!(output, "1. item\n\ncontinued\n");
+    similar_asserts::assert_eq!(format(&output, Some(cfg), None), output);
+}
@@ -0,0 +1,121 @@
+use panache_formatter::{ConfigBuilder, HeadingNumbering, TocStyle, format};
+
+#[test]
+fn toc_is_filled_between_markers() {
+    let input = "\
+# Project
+
+<!-- panache-toc start -->
+<!-- panache-toc end -->
+
+## Getting Started
+
+### Install {#install}
+
+#### From source
+
+## Usage
+
+## Usage
+";
+    let expected = "\
+# Project
+
+<!-- panache-toc start -->
+
+- [Getting Started](#getting-started)
+  - [Install](#install)
+- [Usage](#usage)
+- [Usage](#usage-1)
+
+<!-- panache-toc end -->
+
+## Getting Started
+
+### Install {#install}
+
+#### From source
+
+## Usage
+
+## Usage
+";
+    let out = format(input, None, None);
+    assert_eq!(out, expected);
+    assert_eq!(format(&out, None, None), expected);
+}
+
+#[test]
+fn stale_toc_is_replaced() {
+    let input = "\
+<!-- panache-toc start -->
+
+- [Old section](#old-section)
+- [Intro](#intro)
+
+<!-- panache-toc end -->
+
+# Intro
+
+## Details {.unlisted}
+
+# Outro
+";
+    let expected = "\
+<!-- panache-toc start -->
+
+- [Intro](#intro)
+- [Outro](#outro)
+
+<!-- panache-toc end -->
+
+# Intro
+
+## Details {.unlisted}
+
+# Outro
+";
+    let cfg = ConfigBuilder::default()
+        .toc(TocStyle {
+            min_level: 1,
+            max_level: 2,
+        })
+        .build();
+    assert_eq!(format(input, Some(cfg), None), expected);
+}
+
+#[test]
+fn toc_follows_renumbered_headings() {
+    let input = "\
+<!-- panache-toc start -->
+<!-- panache-toc end -->
+
+## 2 Methods
+
+## 1 Results
+";
+    let expected = "\
+<!-- panache-toc start -->
+
+- [1 Methods](#methods)
+- [2 Results](#results)
+
+<!-- panache-toc end -->
+
+## 1 Methods
+
+## 2 Results
+";
+    let cfg = ConfigBuilder::default()
+        .heading_numbering(HeadingNumbering::Renumber)
+        .build();
+    let out = format(input, Some(cfg.clone()), None);
+    assert_eq!(out, expected);
+    assert_eq!(format(&out, Some(cfg), None), expected);
+}
+
+#[test]
+fn unterminated_toc_is_left_alone() {
+    let input = "<!-- panache-toc start -->\n\n- [Intro](#intro)\n\n## Intro\n";
+    assert_eq!(format(input, None, None), input);
+}
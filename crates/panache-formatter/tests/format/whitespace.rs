@@ -0,0 +1,45 @@
+use panache_formatter::{ConfigBuilder, FinalNewline, format};
+
+#[test]
+fn code_blocks_keep_trailing_whitespace_by_default() {
+    let input = "```\ncode \nmore   \n```\n";
+    similar_asserts::assert_eq!(format(input, None, None), input);
+}
+
+#[test]
+fn trim_trailing_whitespace_reaches_code_blocks() {
+    let cfg = ConfigBuilder::default()
+        .trim_trailing_whitespace(true)
+        .build();
+    let input = "```\ncode \nmore\t\n   \n```\n";
+    let output = format(input, Some(cfg.clone()), None);
+    similar_asserts::assert_eq!(output, "```\ncode\nmore\n\n```\n");
+    similar_asserts::assert_eq!(format(&output, Some(cfg), None), output);
+}
+
+#[test]
+fn trim_trailing_whitespace_keeps_hard_line_breaks() {
+    let mut cfg = ConfigBuilder::default()
+        .trim_trailing_whitespace(true)
+        .build();
+    // Keep the two-space marker instead of rewriting it as a backslash.
+    cfg.formatter_extensions.escaped_line_breaks = false;
+    let input = "First line  \nsecond line.\n";
+    similar_asserts::assert_eq!(format(input, Some(cfg), None), input);
+}
+
+#[test]
+fn final_newline_ensure_adds_one() {
+    similar_asserts::assert_eq!(format("Text", None, None), "Text\n");
+    similar_asserts::assert_eq!(format("Text\n\n\n", None, None), "Text\n");
+}
+
+#[test]
+fn final_newline_preserve_follows_the_input() {
+    let cfg = ConfigBuilder::default()
+        .final_newline(FinalNewline::Preserve)
+        .build();
+    similar_asserts::assert_eq!(format("Text", Some(cfg.clone()), None), "Text");
+    similar_asserts::assert_eq!(format("Text\n\n\n", Some(cfg.clone()), None), "Text\n");
+    similar_asserts::assert_eq!(format("Text\r\n", Some(cfg), None), "Text\r\n");
+}
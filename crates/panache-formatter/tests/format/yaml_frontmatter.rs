@@ -1,10 +1,10 @@
 use panache_formatter::config::WrapMode;
 use panache_formatter::{Config, format};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 #[test]
 fn test_yaml_frontmatter_ignores_external_yaml_formatter() {
-    let mut formatters = HashMap::new();
+    let mut formatters = BTreeMap::new();
     formatters.insert(
         "yaml".to_string(),
         vec![panache_formatter::config::FormatterConfig {
@@ -17,16 +17,20 @@
 //! # Main entry points
 //!
 //! - [`parse`]: Parse input text into a [`SyntaxNode`].
+//! - [`parse_inline`]: Parse a title/caption-style fragment as inlines only.
 //! - [`to_pandoc_ast`]: Project a [`SyntaxNode`] into pandoc-native AST text.
+//! - [`check_lossless`]: Parse and verify the CST reproduces the input exactly.
 //! - [`ParserOptions`]: Parser configuration and extension toggles.
 //! - [`syntax`]: Typed syntax wrappers and syntax kinds.
 //! - [`parser`]: Lower-level parser modules and incremental helpers.
 //!
 pub mod grid_layout;
+pub mod lossless;
 mod options;
 pub mod pandoc_ast;
 pub mod parser;
 pub mod range_utils;
+pub mod slug;
 pub mod syntax;
 
 /// Re-export of the [`entities`] crate (HTML5 named-entity table). Downstream
@@ -35,6 +39,7 @@ pub mod syntax;
 pub use entities;
 
 pub use grid_layout::{GridCellRect, GridLayout, analyze_grid};
+pub use lossless::{LosslessMismatch, check_lossless, find_lossless_mismatch};
 pub use options::Dialect;
 pub use options::Extensions;
 pub use options::Flavor;
@@ -43,5 +48,7 @@ pub use options::ParserOptions;
 pub use pandoc_ast::{to_pandoc_ast, to_pandoc_json};
 pub use parser::inlines::refdef_map::{RefdefMap, collect_refdef_labels};
 pub use parser::parse;
+pub use parser::parse_inline;
+pub use parser::parse_with_diagnostics;
 pub use parser::parse_with_refdefs;
 pub use syntax::SyntaxNode;
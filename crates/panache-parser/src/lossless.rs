@@ -0,0 +1,172 @@
+//! Losslessness checking: the concatenated text of a CST must reproduce its
+//! input byte for byte.
+//!
+//! [`check_lossless`] is the entry point for fuzz targets and property tests;
+//! [`find_lossless_mismatch`] compares an already-parsed tree.
+
+use std::fmt;
+
+use rowan::TextSize;
+
+use crate::options::ParserOptions;
+use crate::syntax::{SyntaxKind, SyntaxNode};
+
+/// Number of characters of context captured on each side of a mismatch.
+const CONTEXT_CHARS: usize = 24;
+
+/// Where and how a CST's text first diverges from its input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LosslessMismatch {
+    /// Byte offset of the first differing character (a char boundary in both
+    /// texts).
+    pub offset: usize,
+    /// 1-indexed line of `offset` in the input.
+    pub line: usize,
+    /// 1-indexed column of `offset` in the input, counted in characters.
+    pub column: usize,
+    /// Input text starting at `offset` (truncated).
+    pub expected: String,
+    /// Tree text starting at `offset` (truncated).
+    pub actual: String,
+    /// Byte length of the input.
+    pub input_len: usize,
+    /// Byte length of the tree text.
+    pub tree_len: usize,
+    /// Node kinds from the root down to the token covering `offset` in the
+    /// tree, when the tree extends that far.
+    pub node_path: Vec<SyntaxKind>,
+}
+
+impl fmt::Display for LosslessMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "CST text diverges from input at {}:{} (byte {}): expected {:?}, found {:?}",
+            self.line, self.column, self.offset, self.expected, self.actual
+        )?;
+        if self.input_len != self.tree_len {
+            write!(
+                f,
+                " (input is {} bytes, tree text is {} bytes)",
+                self.input_len, self.tree_len
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for LosslessMismatch {}
+
+/// Parse `input` and verify the tree reproduces it exactly.
+///
+/// Returns the tree on success so callers can run further checks on it.
+///
+/// ```rust
+/// let tree = panache_parser::check_lossless("# Title\n\nText\n", None).unwrap();
+/// assert_eq!(tree.text().to_string(), "# Title\n\nText\n");
+/// ```
+pub fn check_lossless(
+    input: &str,
+    options: Option<ParserOptions>,
+) -> Result<SyntaxNode, LosslessMismatch> {
+    let tree = crate::parser::parse(input, options);
+    match find_lossless_mismatch(input, &tree) {
+        Some(mismatch) => Err(mismatch),
+        None => Ok(tree),
+    }
+}
+
+/// Compare `tree`'s text against `input`, returning the first divergence.
+pub fn find_lossless_mismatch(input: &str, tree: &SyntaxNode) -> Option<LosslessMismatch> {
+    let tree_text = tree.text().to_string();
+    if tree_text == input {
+        return None;
+    }
+
+    let mut offset = input
+        .bytes()
+        .zip(tree_text.bytes())
+        .position(|(a, b)| a != b)
+        .unwrap_or_else(|| input.len().min(tree_text.len()));
+    while !input.is_char_boundary(offset) || !tree_text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+
+    let before = &input[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let column = input[line_start..offset].chars().count() + 1;
+
+    let node_path = if offset < tree_text.len() {
+        tree.token_at_offset(TextSize::from(offset as u32))
+            .right_biased()
+            .map(|token| {
+                let mut kinds: Vec<SyntaxKind> =
+                    token.parent_ancestors().map(|node| node.kind()).collect();
+                kinds.reverse();
+                kinds.push(token.kind());
+                kinds
+            })
+            .unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+
+    Some(LosslessMismatch {
+        offset,
+        line,
+        column,
+        expected: input[offset..].chars().take(CONTEXT_CHARS).collect(),
+        actual: tree_text[offset..].chars().take(CONTEXT_CHARS).collect(),
+        input_len: input.len(),
+        tree_len: tree_text.len(),
+        node_path,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matching_input_is_lossless() {
+        let input = "---\ntitle: x\n---\n\n# Heading\n\n- a\n- b\n\n```r\n1\n```\n";
+        assert!(check_lossless(input, None).is_ok());
+    }
+
+    #[test]
+    fn reports_first_divergence_with_location() {
+        let tree = crate::parser::parse("# Title\n\nSome text here\n", None);
+        let mismatch = find_lossless_mismatch("# Title\n\nSome test here\n", &tree).unwrap();
+
+        assert_eq!(mismatch.offset, 16);
+        assert_eq!((mismatch.line, mismatch.column), (3, 8));
+        assert!(mismatch.expected.starts_with("st here"));
+        assert!(mismatch.actual.starts_with("xt here"));
+        assert_eq!(mismatch.node_path.first(), Some(&SyntaxKind::DOCUMENT));
+        assert!(mismatch.node_path.contains(&SyntaxKind::PARAGRAPH));
+    }
+
+    #[test]
+    fn reports_truncated_tree_text() {
+        let tree = crate::parser::parse("abc\n", None);
+        let mismatch = find_lossless_mismatch("abc\ndef\n", &tree).unwrap();
+
+        assert_eq!(mismatch.offset, 4);
+        assert_eq!((mismatch.line, mismatch.column), (2, 1));
+        assert_eq!(mismatch.expected, "def\n");
+        assert_eq!(mismatch.actual, "");
+        assert!(mismatch.node_path.is_empty());
+    }
+
+    #[test]
+    fn offset_backs_up_to_char_boundary() {
+        // "é" (C3 A9) vs "è" (C3 A8) share their first byte.
+        let tree = crate::parser::parse("caf\u{e8}\n", None);
+        let mismatch = find_lossless_mismatch("caf\u{e9}\n", &tree).unwrap();
+
+        assert_eq!(mismatch.offset, 3);
+        assert_eq!(mismatch.column, 4);
+        assert_eq!(mismatch.expected, "\u{e9}\n");
+    }
+}
@@ -29,6 +29,9 @@ pub enum Flavor {
     /// MyST (CommonMark + Sphinx/MyST directives, roles, and targets)
     #[cfg_attr(feature = "serde", serde(rename = "myst"))]
     Myst,
+    /// Obsidian (GFM + wikilinks, embeds, callouts, and `%%` comments)
+    #[cfg_attr(feature = "serde", serde(rename = "obsidian"))]
+    Obsidian,
 }
 
 /// Pandoc/Markdown extensions configuration.
@@ -240,6 +243,12 @@ pub struct Extensions {
     /// Parsed as opaque, lossless spans (content preserved verbatim).
     pub svelte_template: bool,
 
+    // ===== MDX extensions =====
+    /// [NON-DEFAULT] MDX tolerance: JSX tags (`<Tabs>`, `<Badge />`), `{expr}`
+    /// braces, and top-level `import`/`export` statements are kept verbatim
+    /// instead of being parsed as HTML or Markdown.
+    pub mdx: bool,
+
     // ===== MyST-specific extensions =====
     /// [NON-DEFAULT] MyST directives: ```` ```{name} ```` (and, with
     /// `myst_colon_fence`, `:::{name}`) blocks carrying `:key: value` options
@@ -260,6 +269,14 @@ pub struct Extensions {
     /// optionally carrying trailing cell metadata. `myst-parser` loads these via
     /// `myst_block_plugin`, so they are on by default for `Flavor::Myst`.
     pub myst_block_breaks: bool,
+
+    // ===== Obsidian-specific extensions =====
+    /// [NON-DEFAULT] Obsidian callouts: blockquote alerts of any type, in any
+    /// case, with an optional fold marker and title (`> [!tip]- Read me`).
+    pub obsidian_callouts: bool,
+    /// [NON-DEFAULT] Obsidian comments: `%%hidden%%` inline, or a block that
+    /// opens with `%%` and runs to the next `%%`. Kept verbatim.
+    pub obsidian_comments: bool,
 }
 
 impl Default for Extensions {
@@ -337,6 +354,7 @@ impl Extensions {
             subscript: false,
             superscript: false,
             svelte_template: false,
+            mdx: false,
             myst_directives: false,
             myst_roles: false,
             myst_targets: false,
@@ -344,6 +362,8 @@ impl Extensions {
             myst_comments: false,
             myst_colon_fence: false,
             myst_block_breaks: false,
+            obsidian_callouts: false,
+            obsidian_comments: false,
             table_captions: false,
             task_lists: false,
             tex_math_dollars: false,
@@ -368,6 +388,7 @@ impl Extensions {
             Flavor::MultiMarkdown => Self::multimarkdown_defaults(),
             Flavor::Mdsvex => Self::mdsvex_defaults(),
             Flavor::Myst => Self::myst_defaults(),
+            Flavor::Obsidian => Self::obsidian_defaults(),
         }
     }
 
@@ -491,6 +512,9 @@ impl Extensions {
             // mdsvex (opt-in, mdsvex flavor only)
             svelte_template: false,
 
+            // MDX (opt-in for every flavor)
+            mdx: false,
+
             // MyST (opt-in, myst flavor only)
             myst_directives: false,
             myst_roles: false,
@@ -499,6 +523,10 @@ impl Extensions {
             myst_comments: false,
             myst_colon_fence: false,
             myst_block_breaks: false,
+
+            // Obsidian (opt-in, obsidian flavor only)
+            obsidian_callouts: false,
+            obsidian_comments: false,
         }
     }
 
@@ -680,6 +708,26 @@ impl Extensions {
         ext
     }
 
+    fn obsidian_defaults() -> Self {
+        // Obsidian renders notes with a GFM-based parser, so start from the GFM
+        // set and add the vault syntax on top: `[[page|alias]]` wikilinks and
+        // `![[file]]` embeds, `==highlights==`, inline footnotes, callouts of
+        // any type, and `%%` comments. Emoji shortcodes and GFM's `` $`...`$ ``
+        // math are not rendered by Obsidian, so they stay off.
+        let mut ext = Self::gfm_defaults();
+
+        ext.emoji = false;
+        ext.tex_math_gfm = false;
+
+        ext.inline_footnotes = true;
+        ext.mark = true;
+        ext.wikilinks_title_after_pipe = true;
+        ext.obsidian_callouts = true;
+        ext.obsidian_comments = true;
+
+        ext
+    }
+
     /// Merge user-specified extension overrides with flavor defaults.
     ///
     /// This is used to support partial extension overrides in config files.
@@ -741,6 +789,14 @@ macro_rules! known_extensions {
                     _ => false,
                 }
             }
+
+            /// Value of the named extension, or `None` for an unknown name.
+            pub fn get_by_name(&self, name: &str) -> Option<bool> {
+                match name {
+                    $( $kebab => Some(self.$field), )*
+                    _ => None,
+                }
+            }
         }
     };
 }
@@ -818,10 +874,13 @@ known_extensions! {
     "quarto-shortcodes" => quarto_shortcodes,
     "bookdown-references" => bookdown_references,
     "bookdown-equation-references" => bookdown_equation_references,
+    // Short name for the `[[Target|Display]]` form used by Obsidian and wikis.
+    "wikilinks" => wikilinks_title_after_pipe,
     "wikilinks-title-after-pipe" => wikilinks_title_after_pipe,
     "wikilinks-title-before-pipe" => wikilinks_title_before_pipe,
     "spaced-reference-links" => spaced_reference_links,
     "svelte-template" => svelte_template,
+    "mdx" => mdx,
     "myst-directives" => myst_directives,
     "myst-roles" => myst_roles,
     "myst-targets" => myst_targets,
@@ -829,6 +888,8 @@ known_extensions! {
     "myst-comments" => myst_comments,
     "myst-colon-fence" => myst_colon_fence,
     "myst-block-breaks" => myst_block_breaks,
+    "obsidian-callouts" => obsidian_callouts,
+    "obsidian-comments" => obsidian_comments,
 }
 
 #[cfg(test)]
@@ -878,6 +939,7 @@ mod tests {
             Flavor::MultiMarkdown,
             Flavor::Mdsvex,
             Flavor::Myst,
+            Flavor::Obsidian,
         ] {
             assert!(
                 !Extensions::for_flavor(flavor).four_space_rule,
@@ -896,6 +958,7 @@ mod tests {
             Flavor::CommonMark,
             Flavor::MultiMarkdown,
             Flavor::Myst,
+            Flavor::Obsidian,
         ] {
             assert!(
                 !Extensions::for_flavor(flavor).svelte_template,
@@ -904,6 +967,26 @@ mod tests {
         }
     }
 
+    #[test]
+    fn mdx_is_opt_in_for_every_flavor() {
+        for flavor in [
+            Flavor::Pandoc,
+            Flavor::Quarto,
+            Flavor::RMarkdown,
+            Flavor::Gfm,
+            Flavor::CommonMark,
+            Flavor::MultiMarkdown,
+            Flavor::Mdsvex,
+            Flavor::Myst,
+            Flavor::Obsidian,
+        ] {
+            assert!(
+                !Extensions::for_flavor(flavor).mdx,
+                "mdx should be off by default for {flavor:?}"
+            );
+        }
+    }
+
     #[test]
     fn myst_uses_commonmark_dialect() {
         assert_eq!(Dialect::for_flavor(Flavor::Myst), Dialect::CommonMark);
@@ -960,6 +1043,7 @@ mod tests {
             Flavor::CommonMark,
             Flavor::MultiMarkdown,
             Flavor::Mdsvex,
+            Flavor::Obsidian,
         ] {
             let ext = Extensions::for_flavor(flavor);
             assert!(
@@ -1004,6 +1088,44 @@ mod tests {
         assert!(!ext.inline_code_attributes);
     }
 
+    #[test]
+    fn obsidian_defaults_enable_vault_syntax() {
+        let ext = Extensions::for_flavor(Flavor::Obsidian);
+
+        assert_eq!(Dialect::for_flavor(Flavor::Obsidian), Dialect::CommonMark);
+        assert!(ext.obsidian_callouts);
+        assert!(ext.obsidian_comments);
+        assert!(ext.wikilinks_title_after_pipe);
+        assert!(!ext.wikilinks_title_before_pipe);
+        assert!(ext.mark);
+        assert!(ext.inline_footnotes);
+
+        // GFM base, minus what Obsidian does not render.
+        assert!(ext.pipe_tables);
+        assert!(ext.task_lists);
+        assert!(ext.tex_math_dollars);
+        assert!(!ext.tex_math_gfm);
+        assert!(!ext.emoji);
+
+        for flavor in [Flavor::Pandoc, Flavor::Gfm, Flavor::Myst] {
+            let ext = Extensions::for_flavor(flavor);
+            assert!(
+                !ext.obsidian_callouts && !ext.obsidian_comments,
+                "Obsidian constructs should be off by default for {flavor:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn wikilinks_is_short_for_title_after_pipe() {
+        let mut overrides = HashMap::new();
+        overrides.insert("wikilinks".to_string(), true);
+        let ext = Extensions::merge_with_flavor(overrides, Flavor::Gfm);
+        assert!(ext.wikilinks_title_after_pipe);
+        assert!(!ext.wikilinks_title_before_pipe);
+        assert_eq!(ext.get_by_name("wikilinks"), Some(true));
+    }
+
     #[test]
     fn merge_with_flavor_accepts_four_space_rule_override() {
         let mut overrides = HashMap::new();
@@ -1076,7 +1198,9 @@ impl Dialect {
     /// Default dialect for a given user-facing flavor.
     pub fn for_flavor(flavor: Flavor) -> Self {
         match flavor {
-            Flavor::CommonMark | Flavor::Gfm | Flavor::Mdsvex | Flavor::Myst => Dialect::CommonMark,
+            Flavor::CommonMark | Flavor::Gfm | Flavor::Mdsvex | Flavor::Myst | Flavor::Obsidian => {
+                Dialect::CommonMark
+            }
             Flavor::Pandoc | Flavor::Quarto | Flavor::RMarkdown | Flavor::MultiMarkdown => {
                 Dialect::Pandoc
             }
@@ -1084,6 +1208,9 @@ impl Dialect {
     }
 }
 
+/// Default for [`ParserOptions::tab_stop`].
+pub const DEFAULT_TAB_STOP: usize = 4;
+
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serde", serde(default, rename_all = "kebab-case"))]
@@ -1102,6 +1229,12 @@ pub struct ParserOptions {
     /// those references misclassified as citations.
     #[cfg_attr(feature = "serde", serde(default, alias = "crossref_prefixes"))]
     pub crossref_prefixes: Vec<String>,
+    /// Columns between tab stops when measuring indentation, like Pandoc's
+    /// `--tab-stop`. Decides how far a tab indents a list item, a
+    /// continuation line, or an indented code block. Defaults to 4, the
+    /// value CommonMark fixes.
+    #[cfg_attr(feature = "serde", serde(alias = "tab_stop"))]
+    pub tab_stop: usize,
     /// Document-level reference link label set, populated by the
     /// top-level `parse()` function when running CommonMark dialect and
     /// consulted by inline parsing's bracket resolution pass. `None`
@@ -1125,6 +1258,7 @@ impl Default for ParserOptions {
             extensions: Extensions::for_flavor(flavor),
             pandoc_compat: PandocCompat::default(),
             crossref_prefixes: Vec::new(),
+            tab_stop: DEFAULT_TAB_STOP,
             refdef_labels: None,
         }
     }
@@ -1158,7 +1292,8 @@ impl schemars::JsonSchema for Flavor {
                 "commonmark",
                 "multimarkdown",
                 "mdsvex",
-                "myst"
+                "myst",
+                "obsidian"
             ]
         })
     }
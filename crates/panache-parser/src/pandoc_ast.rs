@@ -19,6 +19,7 @@ use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
 
 use crate::SyntaxNode;
+use crate::slug;
 use crate::syntax::{SyntaxKind, SyntaxToken};
 use rowan::NodeOrToken;
 use serde_json::{Value, json};
@@ -379,7 +380,7 @@ fn heading_id_with_explicitness(node: &SyntaxNode) -> (String, bool) {
     if !parsed.id.is_empty() {
         return (parsed.id, true);
     }
-    (pandoc_slugify(&inlines_to_plaintext(&inlines)), false)
+    (slug::pandoc(&inlines_to_plaintext(&inlines)), false)
 }
 
 fn parse_footnote_def(node: &SyntaxNode) -> Option<(String, Vec<Block>)> {
@@ -543,7 +544,7 @@ fn lookup_ref(label: &str) -> Option<(String, String)> {
 }
 
 fn lookup_heading_id(label: &str) -> Option<String> {
-    let id = pandoc_slugify(&unescape_label(label));
+    let id = slug::pandoc(&unescape_label(label));
     if id.is_empty() {
         return None;
     }
@@ -5254,32 +5255,6 @@ fn inlines_to_plaintext(inlines: &[Inline]) -> String {
     s
 }
 
-fn pandoc_slugify(text: &str) -> String {
-    // Mirror crates/panache-formatter::utils::pandoc_slugify so the parser-side
-    // projector doesn't need to depend on the formatter crate.
-    let mut out = String::new();
-    let mut prev_dash = false;
-    for ch in text.chars() {
-        if ch.is_whitespace() {
-            if !out.is_empty() && !prev_dash {
-                out.push('-');
-                prev_dash = true;
-            }
-            continue;
-        }
-        for lc in ch.to_lowercase() {
-            if lc.is_alphanumeric() || lc == '_' || lc == '-' || lc == '.' {
-                out.push(lc);
-                prev_dash = lc == '-';
-            }
-        }
-    }
-    while out.ends_with('-') {
-        out.pop();
-    }
-    out
-}
-
 impl Attr {
     fn with_id(id: String) -> Self {
         Self {
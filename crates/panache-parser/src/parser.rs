@@ -14,6 +14,7 @@ pub mod diagnostics;
 pub mod inlines;
 pub mod math;
 pub mod utils;
+pub mod warnings;
 pub mod yaml;
 
 mod block_dispatcher;
@@ -22,6 +23,7 @@ mod core;
 // Re-export main parser
 pub use core::Parser;
 pub use diagnostics::{Diagnostics, SyntaxError, SyntaxErrorSource};
+pub use warnings::{ParseWarning, ParseWarningKind, collect_warnings};
 
 /// Parses a Quarto document string into a syntax tree.
 ///
@@ -65,6 +67,19 @@ pub fn parse_with_errors(
     Parser::new(input, &config).parse_with_errors()
 }
 
+/// Like [`parse`], but also returns warnings for the places where the parser
+/// quietly recovered from malformed structure, such as an unclosed code fence
+/// or fenced div. See [`warnings`].
+pub fn parse_with_diagnostics(
+    input: &str,
+    config: Option<ParserOptions>,
+) -> (SyntaxNode, Vec<ParseWarning>) {
+    let mut config = config.unwrap_or_default();
+    populate_refdef_labels(input, &mut config);
+    let (tree, _, warnings) = Parser::new(input, &config).parse_with_diagnostics();
+    (tree, warnings)
+}
+
 /// Parse with a caller-supplied refdef set.
 ///
 /// Skips the [`collect_refdef_labels`] scan that [`parse`] performs.
@@ -90,9 +105,54 @@ pub fn parse_with_refdefs_and_errors(
     options: Option<ParserOptions>,
     refdefs: RefdefMap,
 ) -> (SyntaxNode, Vec<SyntaxError>) {
+    let (tree, errors, _) = parse_with_refdefs_and_diagnostics(input, options, refdefs);
+    (tree, errors)
+}
+
+/// Like [`parse_with_refdefs_and_errors`], but also returns the recovery
+/// warnings of [`parse_with_diagnostics`].
+pub fn parse_with_refdefs_and_diagnostics(
+    input: &str,
+    options: Option<ParserOptions>,
+    refdefs: RefdefMap,
+) -> (SyntaxNode, Vec<SyntaxError>, Vec<ParseWarning>) {
     let mut options = options.unwrap_or_default();
     options.refdef_labels = Some(refdefs);
-    Parser::new(input, &options).parse_with_errors()
+    Parser::new(input, &options).parse_with_diagnostics()
+}
+
+/// Parses a fragment as inline content only, with no block structure.
+///
+/// The result is a `DOCUMENT` holding a single `PARAGRAPH` whose children are
+/// the fragment's inlines, so text that would open a block in a document
+/// (`# `, `- `, `> `, `1. `) stays literal. Intended for titles, captions, and
+/// table cells that hosts store outside a document. The tree is lossless.
+///
+/// Reference links resolve against `options.refdef_labels` when set; a
+/// fragment can't define references itself, so otherwise none resolve.
+///
+/// # Examples
+///
+/// ```rust
+/// use panache_parser::parser::parse_inline;
+/// use panache_parser::syntax::SyntaxKind;
+///
+/// let tree = parse_inline("# Not a heading", None);
+/// let paragraph = tree.first_child().unwrap();
+/// assert_eq!(paragraph.kind(), SyntaxKind::PARAGRAPH);
+/// assert_eq!(tree.text().to_string(), "# Not a heading");
+/// ```
+pub fn parse_inline(snippet: &str, options: Option<ParserOptions>) -> SyntaxNode {
+    let mut options = options.unwrap_or_default();
+    options.refdef_labels.get_or_insert_with(RefdefMap::default);
+
+    let mut builder = rowan::GreenNodeBuilder::new();
+    builder.start_node(SyntaxKind::DOCUMENT.into());
+    builder.start_node(SyntaxKind::PARAGRAPH.into());
+    utils::inline_emission::emit_inlines(&mut builder, snippet, &options, false);
+    builder.finish_node();
+    builder.finish_node();
+    SyntaxNode::new_root(builder.finish())
 }
 
 /// Pre-compute the document-level reference link label set.
@@ -760,4 +820,28 @@ mod tests {
             "frontmatter delimiter edits should stay in conservative mode"
         );
     }
+
+    #[test]
+    fn parse_inline_keeps_block_markers_literal() {
+        for snippet in [
+            "# Title",
+            "- item",
+            "> quote",
+            "1. First",
+            "Line one\n- two",
+        ] {
+            let tree = parse_inline(snippet, None);
+            assert_eq!(tree.text().to_string(), snippet);
+            let children: Vec<SyntaxKind> = tree.children().map(|node| node.kind()).collect();
+            assert_eq!(children, [SyntaxKind::PARAGRAPH], "{snippet:?}");
+        }
+    }
+
+    #[test]
+    fn parse_inline_parses_inline_constructs() {
+        let tree = parse_inline("*Emphasis* with [a link](https://example.com)", None);
+        let kinds: Vec<SyntaxKind> = tree.descendants().map(|node| node.kind()).collect();
+        assert!(kinds.contains(&SyntaxKind::EMPHASIS));
+        assert!(kinds.contains(&SyntaxKind::LINK));
+    }
 }
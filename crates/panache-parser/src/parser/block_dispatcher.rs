@@ -14,6 +14,7 @@ use rowan::GreenNodeBuilder;
 use std::any::Any;
 
 use super::diagnostics::Diagnostics;
+use super::warnings::unclosed_fence_as_text;
 
 use super::blocks::admonitions::{AdmonitionOpen, try_parse_admonition_open};
 use super::blocks::blockquotes::{
@@ -71,6 +72,8 @@ use super::blocks::tables::{
     try_parse_pipe_table, try_parse_simple_table,
 };
 use super::inlines::links::{LinkScanContext, try_parse_inline_image};
+use super::inlines::mdx::{is_mdx_esm_line, try_parse_jsx_tag, try_parse_mdx_expression};
+use super::inlines::obsidian_comments::COMMENT_MARKER;
 use super::inlines::svelte::{SvelteKind, emit_svelte_template, try_parse_svelte_template};
 use super::utils::attributes::{emit_div_info_node, parse_html_tag_attributes};
 use super::utils::container_stack::{byte_index_at_column, leading_indent};
@@ -1687,6 +1690,9 @@ impl BlockParser for FencedCodeBlockParser {
     ) -> Option<(BlockDetectionResult, Option<Box<dyn Any>>)> {
         let content = lines.first();
         let line_pos = lines.pos();
+        // In `- > …` the list indent comes before the quote markers on
+        // continuation lines, so it is stripped first there.
+        let list_outside_bq = ctx.blockquote_depth > 0 && !bq_outer_of_list(lines.prefix());
         let lines = lines.raw();
         // Calculate content to check - may need to strip list indentation
         let content_to_check = if let Some(list_info) = ctx.list_indent_info {
@@ -1747,20 +1753,26 @@ impl BlockParser for FencedCodeBlockParser {
                     .map(|list_info| list_info.content_col)
                     .unwrap_or(0);
             for raw_line in lines.iter().skip(line_pos + 1) {
+                let raw_line = if list_outside_bq {
+                    strip_list_indent(raw_line, container_content_col)
+                } else {
+                    raw_line
+                };
                 let (line_bq_depth, inner) = count_blockquote_markers(raw_line);
                 if line_bq_depth < ctx.blockquote_depth {
                     break;
                 }
-                let candidate = if container_content_col > 0 && !inner.is_empty() {
-                    let idx = byte_index_at_column(inner, container_content_col);
-                    if idx <= inner.len() {
-                        &inner[idx..]
+                let candidate =
+                    if container_content_col > 0 && !list_outside_bq && !inner.is_empty() {
+                        let idx = byte_index_at_column(inner, container_content_col);
+                        if idx <= inner.len() {
+                            &inner[idx..]
+                        } else {
+                            inner
+                        }
                     } else {
                         inner
-                    }
-                } else {
-                    inner
-                };
+                    };
                 if is_closing_fence(candidate, &fence) {
                     found = true;
                     break;
@@ -1774,6 +1786,15 @@ impl BlockParser for FencedCodeBlockParser {
         // Pandoc dialect: bare fences without a closer fall through to a paragraph.
         let common_mark_dialect = ctx.config.dialect == crate::options::Dialect::CommonMark;
         if !has_matching_closer && !common_mark_dialect {
+            // The fallback leaves no trace in the tree, so record it here.
+            // Like the YAML errors, the host offset is the pointer offset of
+            // the (container-stripped) fence from line 0.
+            let fence_line = strip_newline(content_to_check).0.trim_start();
+            let start = fence_line.as_ptr() as usize - lines[0].as_ptr() as usize;
+            ctx.diags.warn(unclosed_fence_as_text(rowan::TextRange::at(
+                (start as u32).into(),
+                (fence_line.len() as u32).into(),
+            )));
             return None;
         }
 
@@ -3042,6 +3063,277 @@ impl BlockParser for MystCommentParser {
     }
 }
 
+/// Parser for Obsidian `%%` comment blocks: a line opening with `%%`, through
+/// the line that closes the comment. The lines are emitted verbatim under an
+/// `OBSIDIAN_COMMENT` node, so commented-out markdown is never reformatted.
+pub(crate) struct ObsidianCommentParser;
+
+impl ObsidianCommentParser {
+    /// Number of lines in the comment opening on the dispatch line, or `None`
+    /// when that line does not open a block comment. A closed `%%...%%`
+    /// followed by more text is an inline comment, and a comment that is never
+    /// closed is left to the paragraph parser.
+    fn line_count(lines: &StrippedLines<'_, '_>) -> Option<usize> {
+        let (first, _) = strip_newline(lines.first());
+        let indent_len = first.bytes().take_while(|&b| b == b' ').count();
+        if indent_len > 3 {
+            return None;
+        }
+        let rest = first[indent_len..].strip_prefix(COMMENT_MARKER)?;
+        if let Some(close) = rest.find(COMMENT_MARKER) {
+            return rest[close + COMMENT_MARKER.len()..]
+                .trim()
+                .is_empty()
+                .then_some(1);
+        }
+
+        let raw = lines.raw();
+        let start = lines.pos();
+        let prefix = lines.prefix();
+        let bq_depth = prefix.bq_depth();
+        let list_content_col = prefix.list_content_col();
+        let bq_outer = bq_outer_of_list(prefix);
+        for (i, &raw_line) in raw.iter().enumerate().skip(start + 1) {
+            // Leaving the enclosing blockquote ends the search unclosed.
+            let probe = if bq_outer {
+                raw_line
+            } else {
+                strip_list_indent(raw_line, list_content_col)
+            };
+            if count_blockquote_markers(probe).0 < bq_depth {
+                return None;
+            }
+            let (line, _) = strip_newline(lines.strip_at(i));
+            if let Some(close) = line.find(COMMENT_MARKER) {
+                return line[close + COMMENT_MARKER.len()..]
+                    .trim()
+                    .is_empty()
+                    .then_some(i + 1 - start);
+            }
+        }
+        None
+    }
+}
+
+impl BlockParser for ObsidianCommentParser {
+    fn detect_prepared(
+        &self,
+        ctx: &BlockContext,
+        lines: &StrippedLines<'_, '_>,
+    ) -> Option<(BlockDetectionResult, Option<Box<dyn Any>>)> {
+        if !ctx.config.extensions.obsidian_comments {
+            return None;
+        }
+        // Inside a paragraph, `%%` stays an inline comment.
+        let count = Self::line_count(lines)?;
+        Some((BlockDetectionResult::Yes, Some(Box::new(count))))
+    }
+
+    fn parse_prepared(
+        &self,
+        _ctx: &BlockContext,
+        builder: &mut GreenNodeBuilder<'static>,
+        lines: &StrippedLines<'_, '_>,
+        payload: Option<&dyn Any>,
+    ) -> usize {
+        use crate::syntax::SyntaxKind;
+
+        let count = payload
+            .and_then(|p| p.downcast_ref::<usize>())
+            .copied()
+            .or_else(|| Self::line_count(lines))
+            .expect("comment block should exist");
+
+        emit_verbatim_block(builder, lines, SyntaxKind::OBSIDIAN_COMMENT, count);
+        count
+    }
+
+    fn name(&self) -> &'static str {
+        "obsidian_comment"
+    }
+}
+
+/// Emit the `count` lines starting at the dispatch line as a `kind` node whose
+/// content is kept verbatim as TEXT/NEWLINE tokens, after each line's
+/// container prefix.
+fn emit_verbatim_block(
+    builder: &mut GreenNodeBuilder<'static>,
+    lines: &StrippedLines<'_, '_>,
+    kind: crate::syntax::SyntaxKind,
+    count: usize,
+) {
+    use crate::syntax::SyntaxKind;
+
+    builder.start_node(kind.into());
+    for i in lines.pos()..lines.pos() + count {
+        let tail = lines.emit_or_dispatch_tail(builder, i);
+        let (text, newline) = strip_newline(tail);
+        if !text.is_empty() {
+            builder.token(SyntaxKind::TEXT.into(), text);
+        }
+        if !newline.is_empty() {
+            builder.token(SyntaxKind::NEWLINE.into(), newline);
+        }
+    }
+    builder.finish_node();
+}
+
+/// Parser for MDX `import` / `export` statements.
+///
+/// ESM is only valid at the top level of an MDX document, so the statement
+/// must start in column 0 outside every container. It runs to the next blank
+/// line and is kept verbatim as an [`SyntaxKind::MDX_ESM`] block.
+///
+/// [`SyntaxKind::MDX_ESM`]: crate::syntax::SyntaxKind::MDX_ESM
+pub(crate) struct MdxEsmParser;
+
+impl MdxEsmParser {
+    /// Number of lines from the dispatch line up to the next blank line.
+    fn line_count(lines: &StrippedLines<'_, '_>) -> usize {
+        let raw = lines.raw();
+        let start = lines.pos();
+        (start + 1..raw.len())
+            .find(|&i| raw[i].trim().is_empty())
+            .unwrap_or(raw.len())
+            - start
+    }
+}
+
+impl BlockParser for MdxEsmParser {
+    fn detect_prepared(
+        &self,
+        ctx: &BlockContext,
+        lines: &StrippedLines<'_, '_>,
+    ) -> Option<(BlockDetectionResult, Option<Box<dyn Any>>)> {
+        if !ctx.config.extensions.mdx
+            || ctx.blockquote_depth > 0
+            || ctx.in_list
+            || ctx.in_fenced_div
+            || ctx.content_indent != 0
+            || !is_mdx_esm_line(lines.first())
+        {
+            return None;
+        }
+        let count = Self::line_count(lines);
+        Some((BlockDetectionResult::Yes, Some(Box::new(count))))
+    }
+
+    fn parse_prepared(
+        &self,
+        _ctx: &BlockContext,
+        builder: &mut GreenNodeBuilder<'static>,
+        lines: &StrippedLines<'_, '_>,
+        payload: Option<&dyn Any>,
+    ) -> usize {
+        let count = payload
+            .and_then(|p| p.downcast_ref::<usize>())
+            .copied()
+            .unwrap_or_else(|| Self::line_count(lines));
+        emit_verbatim_block(builder, lines, crate::syntax::SyntaxKind::MDX_ESM, count);
+        count
+    }
+
+    fn name(&self) -> &'static str {
+        "mdx_esm"
+    }
+}
+
+/// Parser for MDX flow: lines holding only JSX tags and `{expressions}`, such
+/// as `<Tabs groupId="os">` or `<TabItem value="linux">`.
+///
+/// A tag's attributes may continue over several lines, so the run of tags is
+/// scanned across the block's lines (up to a blank line or the end of the
+/// enclosing blockquote). The block ends on the line of the last tag, which
+/// must hold nothing else; Markdown between an opening and closing tag is
+/// parsed normally. Kept verbatim as an [`SyntaxKind::MDX_FLOW`] block.
+///
+/// [`SyntaxKind::MDX_FLOW`]: crate::syntax::SyntaxKind::MDX_FLOW
+pub(crate) struct MdxFlowParser;
+
+impl MdxFlowParser {
+    /// Number of lines of the flow block opening on the dispatch line, or
+    /// `None` when that line does not open one.
+    fn line_count(lines: &StrippedLines<'_, '_>) -> Option<usize> {
+        let (first, _) = strip_newline(lines.first());
+        let indent_len = first.bytes().take_while(|&b| b == b' ').count();
+        if indent_len > 3 || !matches!(first.as_bytes().get(indent_len), Some(b'<' | b'{')) {
+            return None;
+        }
+
+        let raw = lines.raw();
+        let start = lines.pos();
+        let prefix = lines.prefix();
+        let bq_depth = prefix.bq_depth();
+        let list_content_col = prefix.list_content_col();
+        let bq_outer = bq_outer_of_list(prefix);
+        let mut text = lines.first().to_string();
+        let mut line_ends = vec![text.len()];
+        for (i, &raw_line) in raw.iter().enumerate().skip(start + 1) {
+            let probe = if bq_outer {
+                raw_line
+            } else {
+                strip_list_indent(raw_line, list_content_col)
+            };
+            let line = lines.strip_at(i);
+            if count_blockquote_markers(probe).0 < bq_depth || line.trim().is_empty() {
+                break;
+            }
+            text.push_str(line);
+            line_ends.push(text.len());
+        }
+
+        let mut pos = indent_len;
+        loop {
+            pos += match text.as_bytes()[pos] {
+                b'<' => try_parse_jsx_tag(&text[pos..])?,
+                b'{' => try_parse_mdx_expression(&text[pos..])?,
+                _ => return None,
+            };
+            let line = line_ends.iter().position(|&end| pos <= end)?;
+            let rest = &text[pos..line_ends[line]];
+            if rest.trim().is_empty() {
+                return Some(line + 1);
+            }
+            pos = line_ends[line] - rest.trim_start_matches([' ', '\t']).len();
+        }
+    }
+}
+
+impl BlockParser for MdxFlowParser {
+    fn detect_prepared(
+        &self,
+        ctx: &BlockContext,
+        lines: &StrippedLines<'_, '_>,
+    ) -> Option<(BlockDetectionResult, Option<Box<dyn Any>>)> {
+        if !ctx.config.extensions.mdx {
+            return None;
+        }
+        // Inside a paragraph, JSX stays inline.
+        let count = Self::line_count(lines)?;
+        Some((BlockDetectionResult::Yes, Some(Box::new(count))))
+    }
+
+    fn parse_prepared(
+        &self,
+        _ctx: &BlockContext,
+        builder: &mut GreenNodeBuilder<'static>,
+        lines: &StrippedLines<'_, '_>,
+        payload: Option<&dyn Any>,
+    ) -> usize {
+        let count = payload
+            .and_then(|p| p.downcast_ref::<usize>())
+            .copied()
+            .or_else(|| Self::line_count(lines))
+            .expect("MDX flow block should exist");
+        emit_verbatim_block(builder, lines, crate::syntax::SyntaxKind::MDX_FLOW, count);
+        count
+    }
+
+    fn name(&self) -> &'static str {
+        "mdx_flow"
+    }
+}
+
 /// A standalone Svelte span line, detected for [`SvelteBlockParser`].
 struct SvelteBlockInfo {
     /// Leading-space count (≤3) before the opening `{`.
@@ -3703,11 +3995,18 @@ impl BlockParserRegistry {
             // (6b) MyST target lines `(label)=` and `%` comments (leaf blocks).
             Box::new(MystTargetParser),
             Box::new(MystCommentParser),
+            // (6c) Obsidian `%%` comment blocks — before setext headings so a
+            // commented-out `---` underline is not read as a heading.
+            Box::new(ObsidianCommentParser),
             // (7) Setext headings (part of Pandoc's "header" parser)
             // Must come before ATX to properly handle `---` disambiguation
             Box::new(SetextHeadingParser),
             // (7) ATX headings (part of Pandoc's "header" parser)
             Box::new(AtxHeadingParser),
+            // (8b) MDX `import`/`export` statements and JSX flow — before HTML
+            // blocks, since every tag is JSX in MDX. Gated on `mdx`.
+            Box::new(MdxEsmParser),
+            Box::new(MdxFlowParser),
             // (9) HTML blocks
             Box::new(HtmlBlockParser),
             // (9b) Standalone Svelte spans (mdsvex) - opaque line-level blocks,
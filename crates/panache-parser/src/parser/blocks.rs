@@ -63,8 +63,12 @@ pub mod tests {
     pub mod lists;
     #[path = "losslessness.rs"]
     pub mod losslessness;
+    #[path = "mdx.rs"]
+    pub mod mdx;
     #[path = "metadata_guards.rs"]
     pub mod metadata_guards;
+    #[path = "obsidian.rs"]
+    pub mod obsidian;
 }
 #[path = "blocks/latex_envs.rs"]
 pub mod latex_envs;
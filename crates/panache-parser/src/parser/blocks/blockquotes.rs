@@ -6,6 +6,7 @@ use crate::syntax::SyntaxKind;
 use rowan::GreenNodeBuilder;
 
 use crate::parser::utils::container_stack::{Container, ContainerStack};
+use crate::parser::utils::helpers::spaces;
 
 pub(crate) use crate::parser::utils::marker_utils::{
     count_blockquote_markers, try_parse_blockquote_marker,
@@ -72,7 +73,7 @@ pub(in crate::parser) fn emit_one_blockquote_marker(
     has_trailing_space: bool,
 ) {
     if leading_spaces > 0 {
-        builder.token(SyntaxKind::WHITESPACE.into(), &" ".repeat(leading_spaces));
+        builder.token(SyntaxKind::WHITESPACE.into(), &spaces(leading_spaces));
     }
     builder.token(SyntaxKind::BLOCK_QUOTE_MARKER.into(), ">");
     if has_trailing_space {
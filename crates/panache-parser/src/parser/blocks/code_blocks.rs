@@ -582,7 +582,9 @@ fn prepare_fence_open_line<'a>(
     // WHITESPACE emissions are coalesced into one token for
     // byte-range-equivalent CST stability.
     if let Some(first_line) = first_line_override {
-        if bq_depth > 0 && source_line != first_line {
+        // On a list marker line the bq markers are already emitted, ahead of
+        // the list marker.
+        if bq_depth > 0 && source_line != first_line && !list_marker_consumed_on_line_0 {
             let stripped = strip_n_blockquote_markers(source_line, bq_depth);
             let prefix_len = source_line.len().saturating_sub(stripped.len());
             if prefix_len > 0 {
@@ -641,22 +643,22 @@ fn prepare_fence_open_line<'a>(
         }
     };
 
-    let do_strip_bq =
-        |builder: &mut GreenNodeBuilder<'static>, s: &mut &'a str, pending: &mut Option<usize>| {
-            if bq_depth == 0 {
-                return;
-            }
-            let current_offset = source_line.len() - s.len();
-            flush_ws(builder, pending, current_offset);
-            *s = strip_n_blockquote_markers(s, bq_depth);
-        };
+    let do_strip_bq = |s: &mut &'a str, pending: &mut Option<usize>| {
+        if bq_depth == 0 {
+            return;
+        }
+        // A list indent ahead of the bq markers (`  > ````) is emitted
+        // upstream along with them.
+        *pending = None;
+        *s = strip_n_blockquote_markers(s, bq_depth);
+    };
 
     if bq_outer {
-        do_strip_bq(builder, &mut s, &mut pending_ws_start);
+        do_strip_bq(&mut s, &mut pending_ws_start);
         do_strip_list(&mut s, &mut pending_ws_start);
     } else {
         do_strip_list(&mut s, &mut pending_ws_start);
-        do_strip_bq(builder, &mut s, &mut pending_ws_start);
+        do_strip_bq(&mut s, &mut pending_ws_start);
     }
 
     // content_indent (footnote/definition) — always emit as WHITESPACE.
@@ -1124,6 +1126,12 @@ pub(crate) fn parse_fenced_code_block(
     let list_marker_consumed_on_line_0 = prefix.list_marker_consumed_on_line_0;
     let bq_outer = bq_outer_of_list(prefix);
     let content_indent = prefix.content_indent();
+    // On a marker line inside `> - > …` the whole prefix is upstream-emitted,
+    // and only the op walk finds where it ends.
+    let first_line_override = first_line_override.or_else(|| {
+        (list_marker_consumed_on_line_0 && prefix.interleaves_bq_around_list())
+            .then(|| window.dispatch_tail())
+    });
 
     // Start code block
     builder.start_node(SyntaxKind::CODE_BLOCK.into());
@@ -277,6 +277,21 @@ impl ContainerPrefix {
             .sum()
     }
 
+    /// True iff blockquote markers sit both outside and inside the
+    /// innermost list item (`> - > …`). The scalar accessors above cannot
+    /// describe that order; only the op walk strips such a line correctly.
+    pub fn interleaves_bq_around_list(&self) -> bool {
+        let ops = self.ops();
+        let Some(list_idx) = ops
+            .iter()
+            .rposition(|op| matches!(op, StripOp::ListAdvance(_)))
+        else {
+            return false;
+        };
+        let is_bq = |op: &StripOp| matches!(op, StripOp::BlockQuoteMarker);
+        ops[..list_idx].iter().any(is_bq) && ops[list_idx + 1..].iter().any(is_bq)
+    }
+
     /// Build a `ContainerPrefix` directly from a sequence of strip ops.
     /// Intended for tests; production code should use
     /// [`Self::from_stack`] or [`Self::from_ctx`].
@@ -406,8 +421,9 @@ fn apply_op(line: &str, op: StripOp) -> &str {
 /// Mirrors the strip done by `parse_inner_content` in `core.rs` for
 /// footnote/definition base-indent: when the line's leading indent
 /// reaches `content_indent`, strip exactly `content_indent` columns;
-/// otherwise (lazy continuation) strip whatever leading whitespace
-/// exists.
+/// otherwise (lazy continuation) strip whatever leading spaces and tabs
+/// exist. Other Unicode whitespace (NBSP, ideographic space) is content,
+/// and the line ending is never part of the prefix.
 pub(crate) fn strip_content_indent(line: &str, content_indent: usize) -> (&str, Option<&str>) {
     if content_indent == 0 {
         return (line, None);
@@ -417,7 +433,7 @@ pub(crate) fn strip_content_indent(line: &str, content_indent: usize) -> (&str,
         let idx = byte_index_at_column(line, content_indent);
         (&line[idx..], Some(&line[..idx]))
     } else {
-        let trimmed_start = line.trim_start();
+        let trimmed_start = line.trim_start_matches([' ', '\t']);
         let ws_len = line.len() - trimmed_start.len();
         if ws_len > 0 {
             (trimmed_start, Some(&line[..ws_len]))
@@ -1174,6 +1190,16 @@ mod tests {
         assert_eq!(emit, Some("  "));
     }
 
+    #[test]
+    fn strip_content_indent_lazy_keeps_unicode_space_and_line_ending() {
+        let p = ContainerPrefix::from_ops(&[StripOp::ContentIndent(4)], false);
+        let (stripped, emit) = p.strip_line_0_with_indent_emit(" \u{a0}nbsp");
+        assert_eq!(stripped, "\u{a0}nbsp");
+        assert_eq!(emit, Some(" "));
+        assert_eq!(p.strip("\u{3000}ideographic"), "\u{3000}ideographic");
+        assert_eq!(p.strip("  \n"), "\n");
+    }
+
     #[test]
     fn strip_content_indent_with_list_marker_consumed() {
         // List-marker line with content_indent set (footnote in a list
@@ -3,7 +3,7 @@ use crate::syntax::SyntaxKind;
 use rowan::GreenNodeBuilder;
 
 use crate::parser::utils::container_stack::leading_indent;
-use crate::parser::utils::helpers::strip_newline;
+use crate::parser::utils::helpers::{spaces, strip_newline};
 use crate::parser::utils::inline_emission;
 
 /// Tries to parse a definition list marker (`:` or `~`)
@@ -82,9 +82,12 @@ pub(crate) fn emit_definition_marker(
     indent_cols: usize,
 ) {
     if indent_cols > 0 {
-        builder.token(SyntaxKind::WHITESPACE.into(), &" ".repeat(indent_cols));
+        builder.token(SyntaxKind::WHITESPACE.into(), &spaces(indent_cols));
     }
-    builder.token(SyntaxKind::DEFINITION_MARKER.into(), &marker.to_string());
+    builder.token(
+        SyntaxKind::DEFINITION_MARKER.into(),
+        marker.encode_utf8(&mut [0; 4]),
+    );
 }
 
 // Helper functions for definition list management in Parser
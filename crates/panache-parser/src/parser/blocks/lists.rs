@@ -3,7 +3,7 @@ use crate::syntax::SyntaxKind;
 use rowan::GreenNodeBuilder;
 
 use crate::parser::utils::container_stack::{
-    Container, ContainerStack, leading_indent, leading_indent_from,
+    Container, ContainerStack, leading_indent, leading_indent_from, tab_stop,
 };
 use crate::parser::utils::helpers::{strip_newline, trim_end_newlines};
 use crate::parser::utils::list_item_buffer::ListItemBuffer;
@@ -217,7 +217,8 @@ fn marker_spaces_after(after_marker: &str, marker_end_col: usize) -> (usize, usi
         let bytes = match after_marker.as_bytes().first() {
             Some(b' ') => 1,
             Some(b'\t') => {
-                let span = 4 - (marker_end_col % 4);
+                let tab_stop = tab_stop();
+                let span = tab_stop - (marker_end_col % tab_stop);
                 if span == 1 { 1 } else { 0 }
             }
             _ => 0,
@@ -1368,6 +1369,42 @@ pub(in crate::parser) fn in_list(containers: &ContainerStack) -> bool {
         .any(|c| matches!(c, Container::List { .. }))
 }
 
+/// Whether a `marker` at `indent_cols`, on a line with `bq_depth` quote
+/// markers, starts a sibling item of a list that sits outside the deeper open
+/// quotes (`- > a` then `- b`). Such a line ends the quotes rather than
+/// continuing their paragraph lazily.
+pub(in crate::parser) fn starts_item_outside_blockquote(
+    containers: &ContainerStack,
+    marker: &ListMarker,
+    indent_cols: usize,
+    bq_depth: usize,
+    dialect: crate::Dialect,
+) -> bool {
+    let mut depth = 0;
+    for (i, c) in containers.stack.iter().enumerate() {
+        match c {
+            Container::BlockQuote { .. } => {
+                depth += 1;
+                if depth > bq_depth {
+                    return false;
+                }
+            }
+            Container::List {
+                marker: list_marker,
+                ..
+            } if depth == bq_depth && markers_match(marker, list_marker, dialect) => {
+                if let Some(Container::ListItem { content_col, .. }) = containers.stack.get(i + 1)
+                    && indent_cols < *content_col
+                {
+                    return true;
+                }
+            }
+            _ => {}
+        }
+    }
+    false
+}
+
 /// Check if we're in a list inside a blockquote.
 pub(in crate::parser) fn in_blockquote_list(containers: &ContainerStack) -> bool {
     let mut seen_blockquote = false;
@@ -1627,7 +1664,10 @@ pub(in crate::parser) fn add_list_item_with_nested_empty_list(
 
     // Add empty list item to the nested list
     builder.start_node(SyntaxKind::LIST_ITEM.into());
-    builder.token(SyntaxKind::LIST_MARKER.into(), &nested_marker.to_string());
+    builder.token(
+        SyntaxKind::LIST_MARKER.into(),
+        nested_marker.encode_utf8(&mut [0; 4]),
+    );
 
     // Extract and emit the newline from original content (lossless)
     let content_start = item.indent_bytes + item.marker_len + item.spaces_after_bytes;
@@ -1750,10 +1790,10 @@ fn finish_list_item_with_optional_nested(
             };
             let (inner_content_col, inner_text_to_buffer) =
                 emit_list_item(builder, &inner_item, config);
-            // Recursive call is for nested same-line markers (`- - foo`);
-            // the inner content doesn't begin with `>` so no BqDispatch can
-            // propagate up. Discard the result.
-            let _ = finish_list_item_with_optional_nested(
+            // Recursive call is for nested same-line markers (`- - foo`).
+            // The innermost item may open a blockquote (`- - > foo`), whose
+            // BqDispatch the caller must still run.
+            return finish_list_item_with_optional_nested(
                 containers,
                 builder,
                 inner_content_col,
@@ -1761,7 +1801,6 @@ fn finish_list_item_with_optional_nested(
                 inner_match.virtual_marker_space,
                 config,
             );
-            return ListItemFinish::Done;
         }
     }
 
@@ -1779,8 +1818,8 @@ fn finish_list_item_with_optional_nested(
     {
         let bytes = text_to_buffer.as_bytes();
         let has_trailing_space = bytes.get(1).copied() == Some(b' ');
-        let content_offset = if has_trailing_space { 2 } else { 1 };
-        let remaining = &text_to_buffer[content_offset..];
+        let marker_len = if has_trailing_space { 2 } else { 1 };
+        let mut remaining = &text_to_buffer[marker_len..];
 
         // Push outer ListItem with empty buffer; the inner BLOCK_QUOTE
         // counts as real content so `marker_only` is false.
@@ -1799,6 +1838,19 @@ fn finish_list_item_with_optional_nested(
         }
         containers.push(Container::BlockQuote {});
 
+        // Further markers on the line open nested quotes (`- > > foo`).
+        while remaining.starts_with('>') {
+            let has_trailing_space = remaining.as_bytes().get(1).copied() == Some(b' ');
+            let marker_len = if has_trailing_space { 2 } else { 1 };
+            builder.start_node(SyntaxKind::BLOCK_QUOTE.into());
+            builder.token(SyntaxKind::BLOCK_QUOTE_MARKER.into(), ">");
+            if has_trailing_space {
+                builder.token(SyntaxKind::WHITESPACE.into(), " ");
+            }
+            containers.push(Container::BlockQuote {});
+            remaining = &remaining[marker_len..];
+        }
+
         let trimmed = trim_end_newlines(remaining);
 
         // If the BlockQuote content begins with another list marker
@@ -1815,7 +1867,9 @@ fn finish_list_item_with_optional_nested(
             let inner_content_start = inner_match.marker_len + inner_match.spaces_after_bytes;
             let after_inner = trim_end_newlines(remaining.get(inner_content_start..).unwrap_or(""));
             if !after_inner.is_empty() {
-                let bq_content_col = content_col + content_offset;
+                // Columns inside the quote count from its content, as they
+                // do for the quote's later lines once the `>` is stripped.
+                let bq_content_col = 0;
                 builder.start_node(SyntaxKind::LIST.into());
                 containers.push(Container::List {
                     marker: inner_match.marker.clone(),
@@ -1833,9 +1887,9 @@ fn finish_list_item_with_optional_nested(
                 };
                 let (inner_content_col, inner_text_to_buffer) =
                     emit_list_item(builder, &inner_item, config);
-                // Same as above: inner content doesn't start with `>` so no
-                // BqDispatch can propagate.
-                let _ = finish_list_item_with_optional_nested(
+                // Same as above: a blockquote opened by the inner item
+                // (`- > - > foo`) still needs its BqDispatch.
+                return finish_list_item_with_optional_nested(
                     containers,
                     builder,
                     inner_content_col,
@@ -1843,7 +1897,6 @@ fn finish_list_item_with_optional_nested(
                     inner_match.virtual_marker_space,
                     config,
                 );
-                return ListItemFinish::Done;
             }
         }
 
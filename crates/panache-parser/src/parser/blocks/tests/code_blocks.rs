@@ -758,6 +758,23 @@ fn indented_code_with_tab() {
     assert_eq!(find_all(&tree, SyntaxKind::CODE_BLOCK).len(), 1);
 }
 
+#[test]
+fn tab_stop_decides_whether_a_tab_indents_code() {
+    let input = "
+\tnot code at tab stop 2";
+    let config = ParserOptions {
+        tab_stop: 2,
+        ..Default::default()
+    };
+    let tree = parse_blocks_with_config(input, &config);
+    assert_eq!(find_all(&tree, SyntaxKind::CODE_BLOCK).len(), 0);
+    assert_eq!(find_all(&tree, SyntaxKind::PARAGRAPH).len(), 1);
+
+    // The setting ends with the parse.
+    let tree = parse_blocks(input);
+    assert_eq!(find_all(&tree, SyntaxKind::CODE_BLOCK).len(), 1);
+}
+
 #[test]
 fn indented_code_with_list_marker() {
     let input = "
@@ -39,6 +39,16 @@ pub fn parse_blocks_gfm(input: &str) -> SyntaxNode {
     Parser::new(input, &config).parse()
 }
 
+pub fn parse_blocks_obsidian(input: &str) -> SyntaxNode {
+    let config = ParserOptions {
+        flavor: Flavor::Obsidian,
+        dialect: Dialect::for_flavor(Flavor::Obsidian),
+        extensions: Extensions::for_flavor(Flavor::Obsidian),
+        ..Default::default()
+    };
+    Parser::new(input, &config).parse()
+}
+
 pub fn find_first(node: &SyntaxNode, kind: SyntaxKind) -> Option<SyntaxNode> {
     node.descendants().find(|n| n.kind() == kind)
 }
@@ -0,0 +1,94 @@
+use super::helpers::{find_all, find_first, parse_blocks_gfm, parse_blocks_with_config};
+use crate::options::{Dialect, Extensions, Flavor, ParserOptions};
+use crate::syntax::{SyntaxKind, SyntaxNode};
+
+fn parse_blocks_mdx(input: &str) -> SyntaxNode {
+    let config = ParserOptions {
+        flavor: Flavor::Gfm,
+        dialect: Dialect::for_flavor(Flavor::Gfm),
+        extensions: Extensions {
+            mdx: true,
+            ..Extensions::for_flavor(Flavor::Gfm)
+        },
+        ..Default::default()
+    };
+    parse_blocks_with_config(input, &config)
+}
+
+#[test]
+fn esm_statements_run_to_the_next_blank_line() {
+    let input = "import Tabs from '@theme/Tabs';\nimport TabItem from '@theme/TabItem';\n\nexport const meta = {\n  title: 'Install',\n};\n\nText.\n";
+    let tree = parse_blocks_mdx(input);
+
+    let esm = find_all(&tree, SyntaxKind::MDX_ESM);
+    assert_eq!(esm.len(), 2);
+    assert_eq!(
+        esm[1].text().to_string(),
+        "export const meta = {\n  title: 'Install',\n};\n"
+    );
+    assert_eq!(find_all(&tree, SyntaxKind::PARAGRAPH).len(), 1);
+    assert_eq!(tree.text().to_string(), input);
+}
+
+#[test]
+fn esm_is_top_level_only() {
+    let tree = parse_blocks_mdx("> import x from 'y';\n");
+    assert!(find_first(&tree, SyntaxKind::MDX_ESM).is_none());
+}
+
+#[test]
+fn jsx_flow_keeps_multiline_attributes_and_parses_children() {
+    let input = "<Tabs\n  groupId=\"os\"\n  values={[{ label: 'Linux', value: 'linux' }]}>\n  <TabItem value=\"linux\">\n\nRun **this**.\n\n  </TabItem>\n</Tabs>\n";
+    let tree = parse_blocks_mdx(input);
+
+    let flow: Vec<String> = find_all(&tree, SyntaxKind::MDX_FLOW)
+        .iter()
+        .map(|node| node.text().to_string())
+        .collect();
+    assert_eq!(
+        flow,
+        [
+            "<Tabs\n  groupId=\"os\"\n  values={[{ label: 'Linux', value: 'linux' }]}>\n",
+            "  <TabItem value=\"linux\">\n",
+            "  </TabItem>\n",
+            "</Tabs>\n",
+        ]
+    );
+    assert!(find_first(&tree, SyntaxKind::STRONG).is_some());
+    assert!(find_first(&tree, SyntaxKind::HTML_BLOCK).is_none());
+    assert_eq!(tree.text().to_string(), input);
+}
+
+#[test]
+fn expression_line_is_a_flow_block() {
+    let input = "{props.items.map((item) => <li>{item}</li>)}\n- a list\n";
+    let tree = parse_blocks_mdx(input);
+
+    assert!(find_first(&tree, SyntaxKind::MDX_FLOW).is_some());
+    assert!(find_first(&tree, SyntaxKind::LIST).is_some());
+}
+
+#[test]
+fn inline_jsx_and_expressions_are_opaque() {
+    let input = "A <Badge text=\"*new*\" /> and {props.a_b_c * 2_0} here.\n";
+    let tree = parse_blocks_mdx(input);
+
+    assert_eq!(find_all(&tree, SyntaxKind::MDX_JSX).len(), 1);
+    assert_eq!(
+        find_first(&tree, SyntaxKind::MDX_EXPRESSION)
+            .unwrap()
+            .text()
+            .to_string(),
+        "{props.a_b_c * 2_0}"
+    );
+    assert!(find_first(&tree, SyntaxKind::EMPHASIS).is_none());
+    assert_eq!(tree.text().to_string(), input);
+}
+
+#[test]
+fn mdx_constructs_are_inert_without_the_extension() {
+    let tree = parse_blocks_gfm("import x from 'y';\n\n<Badge text={a b} />\n");
+    assert!(find_first(&tree, SyntaxKind::MDX_ESM).is_none());
+    assert!(find_first(&tree, SyntaxKind::MDX_FLOW).is_none());
+    assert!(find_first(&tree, SyntaxKind::MDX_JSX).is_none());
+}
@@ -0,0 +1,69 @@
+use super::helpers::{find_all, find_first, parse_blocks_gfm, parse_blocks_obsidian};
+use crate::syntax::SyntaxKind;
+
+#[test]
+fn callout_header_keeps_type_fold_marker_and_title() {
+    let input = "> [!tip]- Read me first\n> Body text.\n";
+    let tree = parse_blocks_obsidian(input);
+
+    let alert = find_first(&tree, SyntaxKind::ALERT).expect("callout should parse as an alert");
+    let marker = alert
+        .children_with_tokens()
+        .filter_map(|el| el.into_token())
+        .find(|token| token.kind() == SyntaxKind::ALERT_MARKER)
+        .expect("alert marker");
+    assert_eq!(marker.text(), "[!tip]- Read me first");
+    assert_eq!(find_all(&alert, SyntaxKind::PARAGRAPH).len(), 1);
+    assert_eq!(tree.text().to_string(), input);
+}
+
+#[test]
+fn callout_types_are_not_limited_to_github_alerts() {
+    let input = "> [!faq]\n> Question?\n";
+    assert!(find_first(&parse_blocks_obsidian(input), SyntaxKind::ALERT).is_some());
+    assert!(find_first(&parse_blocks_gfm(input), SyntaxKind::ALERT).is_none());
+}
+
+#[test]
+fn block_comment_keeps_markdown_inside_verbatim() {
+    let input = "Intro.\n\n%%\n- not a list\n\n| not | a table |\n%%\n\nOutro.\n";
+    let tree = parse_blocks_obsidian(input);
+
+    let comment =
+        find_first(&tree, SyntaxKind::OBSIDIAN_COMMENT).expect("comment block should parse");
+    assert_eq!(
+        comment.text().to_string(),
+        "%%\n- not a list\n\n| not | a table |\n%%\n"
+    );
+    assert!(find_first(&tree, SyntaxKind::LIST).is_none());
+    assert_eq!(find_all(&tree, SyntaxKind::PARAGRAPH).len(), 2);
+    assert_eq!(tree.text().to_string(), input);
+}
+
+#[test]
+fn block_comment_inside_blockquote_is_lossless() {
+    let input = "> %% hidden\n> still hidden %%\n> Shown.\n";
+    let tree = parse_blocks_obsidian(input);
+
+    assert!(find_first(&tree, SyntaxKind::OBSIDIAN_COMMENT).is_some());
+    assert_eq!(tree.text().to_string(), input);
+}
+
+#[test]
+fn inline_comment_is_opaque() {
+    let input = "Some *text %%with a* note%% here.\n";
+    let tree = parse_blocks_obsidian(input);
+
+    let comment = find_first(&tree, SyntaxKind::OBSIDIAN_COMMENT).expect("inline comment");
+    assert_eq!(comment.text().to_string(), "%%with a* note%%");
+    assert!(find_first(&tree, SyntaxKind::EMPHASIS).is_none());
+}
+
+#[test]
+fn unclosed_comment_is_plain_text() {
+    let input = "%% never closed\n\nText.\n";
+    let tree = parse_blocks_obsidian(input);
+
+    assert!(find_first(&tree, SyntaxKind::OBSIDIAN_COMMENT).is_none());
+    assert_eq!(find_all(&tree, SyntaxKind::PARAGRAPH).len(), 2);
+}
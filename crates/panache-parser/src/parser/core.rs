@@ -30,10 +30,13 @@ use super::utils::helpers::{
 use super::utils::inline_emission;
 use super::utils::marker_utils;
 use super::utils::text_buffer;
+use super::warnings::{ParseWarning, merge_warnings};
 
 use super::blocks::blockquotes::strip_n_blockquote_markers;
 use super::utils::continuation::ContinuationPolicy;
-use container_stack::{Container, ContainerStack, byte_index_at_column, leading_indent};
+use container_stack::{
+    Container, ContainerStack, TabStopScope, byte_index_at_column, leading_indent,
+};
 use definition_lists::{emit_definition_marker, emit_term};
 use line_blocks::{parse_line_block, try_parse_line_block_start};
 use lists::{
@@ -51,6 +54,27 @@ const GITHUB_ALERT_MARKERS: [&str; 5] = [
     "[!NOTE]",
 ];
 
+/// Whether `line` is an Obsidian callout header: `[!type]` with any type name
+/// in any case, then an optional `+`/`-` fold marker and an optional title
+/// (`[!tip]- Read me`).
+fn is_obsidian_callout_header(line: &str) -> bool {
+    let Some((kind, rest)) = line
+        .strip_prefix("[!")
+        .and_then(|rest| rest.split_once(']'))
+    else {
+        return false;
+    };
+    if kind.is_empty()
+        || !kind
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+    {
+        return false;
+    }
+    let rest = rest.strip_prefix(['+', '-']).unwrap_or(rest);
+    rest.is_empty() || rest.starts_with(char::is_whitespace)
+}
+
 /// Outcome of dispatching a line through `parse_line` / `parse_inner_content`
 /// and friends. The outer loop in `parse_document_stack` is the only authority
 /// that commits `self.pos`; dispatch helpers describe what they consumed
@@ -125,11 +149,23 @@ impl<'a> Parser<'a> {
 
     /// Parse, returning the CST plus any embedded-sublanguage syntax errors
     /// (host-ranged) collected during the single pass.
-    pub fn parse_with_errors(mut self) -> (SyntaxNode, Vec<SyntaxError>) {
+    pub fn parse_with_errors(self) -> (SyntaxNode, Vec<SyntaxError>) {
+        let (node, errors, _) = self.parse_with_diagnostics();
+        (node, errors)
+    }
+
+    /// Like [`Parser::parse_with_errors`], but also returns the recovery
+    /// warnings: those recorded while parsing plus those the tree itself
+    /// records (see [`super::warnings::collect_warnings`]), in document order.
+    pub fn parse_with_diagnostics(mut self) -> (SyntaxNode, Vec<SyntaxError>, Vec<ParseWarning>) {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("block_parse", lines = self.lines.len()).entered();
+        let _tab_stop = TabStopScope::enter(self.config.tab_stop);
         self.parse_document_stack();
         let node = SyntaxNode::new_root(self.builder.finish());
         let errors = self.diagnostics.take();
-        (node, errors)
+        let warnings = merge_warnings(self.diagnostics.take_warnings(), &node);
+        (node, errors, warnings)
     }
 
     /// Close enclosing list items (and their containing list) whose
@@ -392,12 +428,19 @@ impl<'a> Parser<'a> {
         )
     }
 
-    fn alert_marker_from_content(content: &str) -> Option<&'static str> {
+    fn alert_marker_from_content<'c>(&self, content: &'c str) -> Option<&'c str> {
         let (without_newline, _) = strip_newline(content);
         let trimmed = without_newline.trim();
+        if self.config.extensions.obsidian_callouts && is_obsidian_callout_header(trimmed) {
+            return Some(trimmed);
+        }
+        if !self.config.extensions.alerts {
+            return None;
+        }
         GITHUB_ALERT_MARKERS
             .into_iter()
-            .find(|marker| *marker == trimmed)
+            .any(|marker| marker == trimmed)
+            .then_some(trimmed)
     }
 
     /// Emit buffered list item content if we're in a ListItem and it has content.
@@ -2768,10 +2811,13 @@ impl<'a> Parser<'a> {
                     self.containers.push(Container::BlockQuote {});
                 }
             } else {
-                // First, emit markers for existing blockquote levels (before opening new ones)
+                // First, emit markers for existing blockquote levels (before opening new ones).
+                // They go straight out rather than into a list-item buffer, which would
+                // emit them after the new blockquote.
                 for level in 0..current_bq_depth {
                     if let Some(info) = marker_info.get(level) {
-                        self.emit_or_buffer_blockquote_marker(
+                        blockquotes::emit_one_blockquote_marker(
+                            &mut self.builder,
                             info.leading_spaces,
                             info.has_trailing_space,
                         );
@@ -2858,10 +2904,30 @@ impl<'a> Parser<'a> {
                 let interrupts_via_div_close = self.config.extensions.fenced_divs
                     && self.in_fenced_div()
                     && fenced_divs::is_div_closing_fence(line);
+                // A marker for a list outside the deeper quotes starts that
+                // list's next item (`- > a` then `- b`), in both dialects.
+                let interrupts_via_list_item = try_parse_list_marker(
+                    inner_content,
+                    self.config,
+                    lists::open_list_hint_at_indent(
+                        &self.containers,
+                        leading_indent(inner_content).0,
+                    ),
+                )
+                .is_some_and(|marker_match| {
+                    lists::starts_item_outside_blockquote(
+                        &self.containers,
+                        &marker_match.marker,
+                        leading_indent(inner_content).0,
+                        bq_depth,
+                        self.config.dialect,
+                    )
+                });
                 if !interrupts_via_hr
                     && !interrupts_via_fence
                     && !interrupts_via_heading
                     && !interrupts_via_div_close
+                    && !interrupts_via_list_item
                 {
                     if bq_depth > 0 {
                         // Buffer the explicit `>` markers we have into the
@@ -2989,12 +3055,23 @@ impl<'a> Parser<'a> {
                     )
                 {
                     let (indent_cols, indent_bytes) = leading_indent(line);
-                    if let Some(level) = lists::find_matching_list_level(
+                    // A list outside the quote claims its own markers
+                    // (`- > - a` then `- b`).
+                    let outer_item = lists::starts_item_outside_blockquote(
                         &self.containers,
                         &marker_match.marker,
                         indent_cols,
+                        0,
                         self.config.dialect,
-                    ) {
+                    );
+                    if !outer_item
+                        && let Some(level) = lists::find_matching_list_level(
+                            &self.containers,
+                            &marker_match.marker,
+                            indent_cols,
+                            self.config.dialect,
+                        )
+                    {
                         // Continue the list inside the blockquote
                         // Close containers to the target level, emitting buffers properly
                         self.close_containers_to(level + 1);
@@ -3116,7 +3193,10 @@ impl<'a> Parser<'a> {
             ) {
                 // Don't steal lines whose leading whitespace inside the BQ
                 // would push the marker into the previous inner LIST_ITEM's
-                // content area — those are nested lists, not siblings.
+                // content area — those are nested lists, not siblings. A
+                // marker left of the content of the item holding a nested
+                // LIST belongs to an outer LIST instead (`> - a` /
+                // `>   - b` / `> - c`).
                 let inner_content_threshold =
                     marker_match.marker_len + marker_match.spaces_after_cols;
                 let is_sibling_candidate = inner_indent_cols_raw < inner_content_threshold;
@@ -3136,7 +3216,12 @@ impl<'a> Parser<'a> {
                                     .iter()
                                     .filter(|x| matches!(x, Container::BlockQuote { .. }))
                                     .count()
-                                    == bq_depth =>
+                                    == bq_depth
+                                    && !matches!(
+                                        i.checked_sub(1).and_then(|p| self.containers.stack.get(p)),
+                                        Some(Container::ListItem { content_col, .. })
+                                            if inner_indent_cols_raw < *content_col
+                                    ) =>
                             {
                                 Some(i)
                             }
@@ -3529,11 +3614,11 @@ impl<'a> Parser<'a> {
         let content_indent = self.content_container_indent_to_strip();
         let (stripped_content, indent_to_emit) = strip_content_indent(content, content_indent);
 
-        if self.config.extensions.alerts
+        if (self.config.extensions.alerts || self.config.extensions.obsidian_callouts)
             && self.current_blockquote_depth() > 0
             && !self.in_active_alert()
             && !self.is_paragraph_open()
-            && let Some(marker) = Self::alert_marker_from_content(stripped_content)
+            && let Some(marker) = self.alert_marker_from_content(stripped_content)
         {
             let (_, newline_str) = strip_newline(stripped_content);
             self.builder.start_node(SyntaxKind::ALERT.into());
@@ -3854,7 +3939,9 @@ impl<'a> Parser<'a> {
                 )
                 .is_some();
 
-            let prev_line_blank = is_blank_line(prev_line);
+            // A bare `>` line is blank inside the quote (`> - a`, `>`, `>   ~~~`).
+            let prev_line_blank =
+                is_blank_line(prev_line) || (prev_bq_depth > 0 && is_blank_line(prev_inner));
             prev_line_blank
                 || prev_is_fenced_div_open
                 || matches!(self.containers.last(), Some(Container::BlockQuote { .. }))
@@ -11,12 +11,17 @@
 //! The CST is unchanged — invalid YAML still becomes opaque tokens. This channel
 //! is purely the *diagnostic* the parser already computed, surfaced instead of
 //! thrown away. It is empty for pure Markdown.
+//!
+//! The same sink also collects [`ParseWarning`]s for recoveries that leave no
+//! trace in the tree, such as an unclosed code fence read as paragraph text.
 
 use std::cell::RefCell;
 use std::rc::Rc;
 
 use rowan::TextRange;
 
+use super::warnings::ParseWarning;
+
 /// Which sublanguage validation produced a [`SyntaxError`]. Lets downstream
 /// consumers (the linter) map to the right diagnostic code without the parser
 /// knowing linter codes.
@@ -45,6 +50,7 @@ pub struct SyntaxError {
 #[derive(Debug, Clone, Default)]
 pub struct Diagnostics {
     errors: Rc<RefCell<Vec<SyntaxError>>>,
+    warnings: Rc<RefCell<Vec<ParseWarning>>>,
 }
 
 impl Diagnostics {
@@ -61,4 +67,15 @@ impl Diagnostics {
     pub fn take(&self) -> Vec<SyntaxError> {
         std::mem::take(&mut self.errors.borrow_mut())
     }
+
+    /// Record a recovery warning. Block detection may probe the same line
+    /// more than once, so duplicates are dropped when the warnings are taken.
+    pub fn warn(&self, warning: ParseWarning) {
+        self.warnings.borrow_mut().push(warning);
+    }
+
+    /// Drain the recorded warnings.
+    pub fn take_warnings(&self) -> Vec<ParseWarning> {
+        std::mem::take(&mut self.warnings.borrow_mut())
+    }
 }
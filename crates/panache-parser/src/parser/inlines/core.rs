@@ -54,9 +54,11 @@ use super::math::{
     try_parse_math_environment, try_parse_single_backslash_display_math,
     try_parse_single_backslash_inline_math,
 };
+use super::mdx::{emit_mdx_inline, try_parse_mdx_inline};
 use super::myst_roles::{emit_role, try_parse_role};
 use super::myst_substitutions::{emit_substitution, try_parse_substitution};
 use super::native_spans::{emit_native_span, try_parse_native_span};
+use super::obsidian_comments::{emit_obsidian_comment, try_parse_obsidian_comment};
 use super::raw_inline::is_raw_inline;
 use super::shortcodes::{emit_shortcode, try_parse_shortcode};
 use super::strikeout::{emit_strikeout, try_parse_strikeout};
@@ -238,8 +240,8 @@ fn structural_byte_mask(config: &ParserOptions) -> [bool; 256] {
         mask[b'!' as usize] = true;
     }
 
-    // `<` covers autolinks, raw HTML, and Pandoc native spans.
-    if exts.autolinks || exts.raw_html || exts.native_spans {
+    // `<` covers autolinks, raw HTML, Pandoc native spans, and MDX JSX.
+    if exts.autolinks || exts.raw_html || exts.native_spans || exts.mdx {
         mask[b'<' as usize] = true;
     }
 
@@ -275,6 +277,9 @@ fn structural_byte_mask(config: &ParserOptions) -> [bool; 256] {
     if exts.mark {
         mask[b'=' as usize] = true;
     }
+    if exts.obsidian_comments {
+        mask[b'%' as usize] = true;
+    }
     if exts.emoji {
         mask[b':' as usize] = true;
     }
@@ -527,6 +532,36 @@ fn parse_inline_range_impl(
                         continue;
                     }
                 }
+                ConstructDispo::ObsidianComment { end: dispo_end } => {
+                    if dispo_end <= end
+                        && let Some(len) = try_parse_obsidian_comment(&text[pos..])
+                        && pos + len == dispo_end
+                    {
+                        if pos > text_start {
+                            builder.token(SyntaxKind::TEXT.into(), &text[text_start..pos]);
+                        }
+                        log::trace!("IR: matched Obsidian comment at pos {}", pos);
+                        emit_obsidian_comment(builder, &text[pos..pos + len]);
+                        pos += len;
+                        text_start = pos;
+                        continue;
+                    }
+                }
+                ConstructDispo::Mdx { end: dispo_end } => {
+                    if dispo_end <= end
+                        && let Some((len, kind)) = try_parse_mdx_inline(&text[pos..])
+                        && pos + len == dispo_end
+                    {
+                        if pos > text_start {
+                            builder.token(SyntaxKind::TEXT.into(), &text[text_start..pos]);
+                        }
+                        log::trace!("IR: matched MDX span at pos {}: {:?}", pos, kind);
+                        emit_mdx_inline(builder, kind, &text[pos..pos + len]);
+                        pos += len;
+                        text_start = pos;
+                        continue;
+                    }
+                }
             }
         }
 
@@ -1595,10 +1630,11 @@ fn parse_inline_range_impl(
                 if !text_content.is_empty() {
                     builder.token(SyntaxKind::TEXT.into(), text_content);
                 }
-                let spaces = " ".repeat(trailing_spaces);
+                // The trailing spaces and line ending are contiguous in the
+                // source, so the token is a slice of it.
                 builder.token(
                     SyntaxKind::HARD_LINE_BREAK.into(),
-                    &format!("{}\r\n", spaces),
+                    &text[pos - trailing_spaces..pos + 2],
                 );
                 pos += 2;
                 text_start = pos;
@@ -1637,8 +1673,10 @@ fn parse_inline_range_impl(
                 if !text_content.is_empty() {
                     builder.token(SyntaxKind::TEXT.into(), text_content);
                 }
-                let spaces = " ".repeat(trailing_spaces);
-                builder.token(SyntaxKind::HARD_LINE_BREAK.into(), &format!("{}\n", spaces));
+                builder.token(
+                    SyntaxKind::HARD_LINE_BREAK.into(),
+                    &text[pos - trailing_spaces..pos + 1],
+                );
                 pos += 1;
                 text_start = pos;
                 continue;
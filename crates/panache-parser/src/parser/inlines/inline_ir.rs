@@ -114,7 +114,9 @@ use super::math::{
     try_parse_double_backslash_inline_math, try_parse_gfm_inline_math, try_parse_inline_math,
     try_parse_single_backslash_display_math, try_parse_single_backslash_inline_math,
 };
+use super::mdx::try_parse_mdx_inline;
 use super::native_spans::try_parse_native_span;
+use super::obsidian_comments::try_parse_obsidian_comment;
 
 /// One event in the inline IR.
 ///
@@ -269,6 +271,15 @@ pub enum ConstructKind {
     /// the IR's `ConstructPlan`; the `is_image` variant is recovered by
     /// peeking the leading byte of the source range.
     WikiLink,
+    /// Obsidian comment `%%hidden%%`. Recognised in `build_ir` when
+    /// `obsidian_comments` is enabled, so the comment body is opaque to
+    /// emphasis and bracket resolution.
+    ObsidianComment,
+    /// MDX JSX tag `<Tabs>` or expression `{props.x}`. Recognised in
+    /// `build_ir` when `mdx` is enabled, so attribute values and
+    /// JavaScript are opaque to emphasis and bracket resolution. The
+    /// emission walk recovers the node kind from the leading byte.
+    Mdx,
 }
 
 /// One matched fragment within a [`IrEvent::DelimRun`].
@@ -540,6 +551,21 @@ pub(super) fn build_ir_into(
                 text_run_start = pos;
                 continue;
             }
+            // MDX: every tag is JSX, so it wins over raw HTML.
+            if exts.mdx
+                && let Some((len, _)) = try_parse_mdx_inline(&text[pos..])
+                && pos + len <= end
+            {
+                flush_text!();
+                events.push(IrEvent::Construct {
+                    start: pos,
+                    end: pos + len,
+                    kind: ConstructKind::Mdx,
+                });
+                pos += len;
+                text_run_start = pos;
+                continue;
+            }
             if exts.raw_html
                 && let Some(len) = try_parse_inline_html(&text[pos..], config.dialect)
                 && pos + len <= end
@@ -695,6 +721,41 @@ pub(super) fn build_ir_into(
             continue;
         }
 
+        // Obsidian comment `%%hidden%%`. Opaque, so delimiter runs and
+        // brackets inside the comment never pair with ones outside it.
+        if b == b'%'
+            && exts.obsidian_comments
+            && let Some(len) = try_parse_obsidian_comment(&text[pos..])
+            && pos + len <= end
+        {
+            flush_text!();
+            events.push(IrEvent::Construct {
+                start: pos,
+                end: pos + len,
+                kind: ConstructKind::ObsidianComment,
+            });
+            pos += len;
+            text_run_start = pos;
+            continue;
+        }
+
+        // MDX expression `{props.x}`. Opaque like the JSX tags above.
+        if b == b'{'
+            && exts.mdx
+            && let Some((len, _)) = try_parse_mdx_inline(&text[pos..])
+            && pos + len <= end
+        {
+            flush_text!();
+            events.push(IrEvent::Construct {
+                start: pos,
+                end: pos + len,
+                kind: ConstructKind::Mdx,
+            });
+            pos += len;
+            text_run_start = pos;
+            continue;
+        }
+
         // `![` opens an image bracket. Recognised whenever any
         // image-producing extension is on — `inline_images` for the
         // `![alt](url)` form, or `reference_links` for the
@@ -902,6 +963,16 @@ fn build_ir_byte_mask(config: &ParserOptions) -> [bool; 256] {
         mask[b'$' as usize] = true;
     }
 
+    if exts.obsidian_comments {
+        mask[b'%' as usize] = true;
+    }
+
+    // `<` and `{` open MDX JSX tags and expressions.
+    if exts.mdx {
+        mask[b'<' as usize] = true;
+        mask[b'{' as usize] = true;
+    }
+
     mask
 }
 
@@ -2466,6 +2537,10 @@ pub enum ConstructDispo {
     /// emit via `emit_wikilink` after re-locating the pipe within the
     /// source range.
     WikiLink { end: usize },
+    /// `%%hidden%%` — emit verbatim via `emit_obsidian_comment`.
+    ObsidianComment { end: usize },
+    /// `<Tabs>` / `{props.x}` — emit verbatim via `emit_mdx_inline`.
+    Mdx { end: usize },
 }
 
 /// A byte-keyed view of the IR's standalone Pandoc constructs that the
@@ -2520,6 +2595,12 @@ pub fn build_construct_plan(events: &[IrEvent]) -> ConstructPlan {
                 ConstructKind::WikiLink => {
                     by_pos.insert(*start, ConstructDispo::WikiLink { end: *end });
                 }
+                ConstructKind::ObsidianComment => {
+                    by_pos.insert(*start, ConstructDispo::ObsidianComment { end: *end });
+                }
+                ConstructKind::Mdx => {
+                    by_pos.insert(*start, ConstructDispo::Mdx { end: *end });
+                }
                 _ => {}
             }
         }
@@ -2918,6 +2999,7 @@ mod tests {
             extensions: crate::options::Extensions::for_flavor(flavor),
             pandoc_compat: crate::options::PandocCompat::default(),
             crossref_prefixes: Vec::new(),
+            tab_stop: crate::options::DEFAULT_TAB_STOP,
             refdef_labels: None,
         }
     }
@@ -3136,6 +3218,7 @@ mod tests {
             extensions: crate::options::Extensions::for_flavor(flavor),
             pandoc_compat: crate::options::PandocCompat::default(),
             crossref_prefixes: Vec::new(),
+            tab_stop: crate::options::DEFAULT_TAB_STOP,
             refdef_labels: None,
         }
     }
@@ -0,0 +1,200 @@
+//! MDX tolerance: JSX tags and `{expression}` braces.
+//!
+//! MDX documents (Docusaurus, Astro, ...) mix JSX components into Markdown.
+//! Panache does not interpret JSX; it only needs to find where a tag or an
+//! expression ends so the bytes can be kept verbatim instead of being read as
+//! emphasis, links, or raw HTML. The recognizers here are shared by the inline
+//! parser (single-line spans) and the block parser (whole-line JSX, which may
+//! spread its attributes over several lines).
+//!
+//! Known limitations (both fall back to plain text, so they stay lossless):
+//! - JSX element values in attributes (`icon=<Icon />`) are not recognized.
+//! - Regex literals containing an unbalanced brace can end an expression early.
+
+use super::sink::InlineSink;
+use crate::syntax::SyntaxKind;
+
+/// Try to parse a JSX tag at the start of `text`: an opening, closing, or
+/// self-closing tag (`<Tabs>`, `</Tabs>`, `<Badge text="new" />`) or a
+/// fragment (`<>`, `</>`). Returns the byte length of the tag.
+///
+/// Attributes may hold quoted strings and `{expression}` values, and may span
+/// lines; callers that must stay on one line check the span themselves.
+pub(crate) fn try_parse_jsx_tag(text: &str) -> Option<usize> {
+    let bytes = text.as_bytes();
+    if bytes.first() != Some(&b'<') {
+        return None;
+    }
+    let mut pos = 1;
+    let closing = bytes.get(pos) == Some(&b'/');
+    if closing {
+        pos += 1;
+    }
+
+    // Fragments: `<>` and `</>`.
+    if bytes.get(pos) == Some(&b'>') {
+        return Some(pos + 1);
+    }
+
+    pos += jsx_name_len(&text[pos..])?;
+
+    loop {
+        pos += skip_whitespace(&text[pos..]);
+        match bytes.get(pos)? {
+            b'>' => return Some(pos + 1),
+            b'/' if !closing && bytes.get(pos + 1) == Some(&b'>') => return Some(pos + 2),
+            _ if closing => return None,
+            // Spread attribute: `{...props}`.
+            b'{' => pos += try_parse_mdx_expression(&text[pos..])?,
+            _ => {
+                pos += jsx_name_len(&text[pos..])?;
+                let after_name = pos + skip_whitespace(&text[pos..]);
+                if bytes.get(after_name) != Some(&b'=') {
+                    continue;
+                }
+                pos = after_name + 1;
+                pos += skip_whitespace(&text[pos..]);
+                pos += match bytes.get(pos)? {
+                    b'{' => try_parse_mdx_expression(&text[pos..])?,
+                    quote @ (b'"' | b'\'') => text[pos + 1..].find(*quote as char)? + 2,
+                    _ => return None,
+                };
+            }
+        }
+    }
+}
+
+/// Try to parse a balanced `{expression}` at the start of `text`. Returns the
+/// byte length including both braces.
+///
+/// String and template literals are skipped, so a brace inside quotes does not
+/// end the expression. `{{<` is left to the Quarto shortcode probe.
+pub(crate) fn try_parse_mdx_expression(text: &str) -> Option<usize> {
+    let bytes = text.as_bytes();
+    if bytes.first() != Some(&b'{') || text.starts_with("{{<") {
+        return None;
+    }
+
+    let mut depth = 0usize;
+    let mut pos = 0;
+    while pos < bytes.len() {
+        match bytes[pos] {
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(pos + 1);
+                }
+            }
+            quote @ (b'"' | b'\'' | b'`') => {
+                pos += 1;
+                while pos < bytes.len() && bytes[pos] != quote {
+                    if bytes[pos] == b'\\' {
+                        pos += 1;
+                    }
+                    pos += 1;
+                }
+            }
+            _ => {}
+        }
+        pos += 1;
+    }
+    None
+}
+
+/// Try to parse an inline MDX span (a JSX tag or an expression) that stays on
+/// one line. Returns the byte length and the node kind to emit.
+pub(crate) fn try_parse_mdx_inline(text: &str) -> Option<(usize, SyntaxKind)> {
+    let (len, kind) = match text.as_bytes().first()? {
+        b'<' => (try_parse_jsx_tag(text)?, SyntaxKind::MDX_JSX),
+        b'{' => (try_parse_mdx_expression(text)?, SyntaxKind::MDX_EXPRESSION),
+        _ => return None,
+    };
+    (!text[..len].contains(['\n', '\r'])).then_some((len, kind))
+}
+
+/// Emit an inline MDX span as an opaque node holding its verbatim text.
+pub(crate) fn emit_mdx_inline(builder: &mut impl InlineSink, kind: SyntaxKind, text: &str) {
+    builder.start_node(kind.into());
+    builder.token(SyntaxKind::TEXT.into(), text);
+    builder.finish_node();
+}
+
+/// Whether `line` opens an ESM statement (`import ...` / `export ...`).
+pub(crate) fn is_mdx_esm_line(line: &str) -> bool {
+    ["import", "export"].iter().any(|keyword| {
+        line.strip_prefix(keyword)
+            .and_then(|rest| rest.chars().next())
+            .is_some_and(|c| c.is_whitespace() || c == '{' || c == '*')
+    })
+}
+
+/// Length of a JSX element or attribute name: an identifier that may contain
+/// `-`, with `.` member access or a `:` namespace.
+fn jsx_name_len(text: &str) -> Option<usize> {
+    let first = text.chars().next()?;
+    if !(first.is_ascii_alphabetic() || first == '_' || first == '$') {
+        return None;
+    }
+    Some(
+        text.find(|c: char| {
+            !(c.is_ascii_alphanumeric() || matches!(c, '_' | '$' | '-' | '.' | ':'))
+        })
+        .unwrap_or(text.len()),
+    )
+}
+
+fn skip_whitespace(text: &str) -> usize {
+    text.len() - text.trim_start().len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_jsx_tags() {
+        assert_eq!(try_parse_jsx_tag("<Tabs>rest"), Some(6));
+        assert_eq!(try_parse_jsx_tag("</Tabs>"), Some(7));
+        assert_eq!(try_parse_jsx_tag("<Badge />"), Some(9));
+        assert_eq!(try_parse_jsx_tag("<>"), Some(2));
+        assert_eq!(try_parse_jsx_tag("</>"), Some(3));
+        assert_eq!(try_parse_jsx_tag("<Foo.Bar />"), Some(11));
+        let tag = r#"<TabItem value="a b" label={`x ${y}`} {...rest} default>"#;
+        assert_eq!(try_parse_jsx_tag(tag), Some(tag.len()));
+    }
+
+    #[test]
+    fn jsx_attributes_may_span_lines() {
+        let tag = "<Tabs\n  groupId=\"os\"\n  values={[{ label: 'Linux' }]}\n>";
+        assert_eq!(try_parse_jsx_tag(tag), Some(tag.len()));
+        assert!(try_parse_mdx_inline(tag).is_none());
+    }
+
+    #[test]
+    fn rejects_non_jsx_angle_brackets() {
+        assert!(try_parse_jsx_tag("<https://example.com>").is_none());
+        assert!(try_parse_jsx_tag("<me@example.com>").is_none());
+        assert!(try_parse_jsx_tag("<!-- comment -->").is_none());
+        assert!(try_parse_jsx_tag("< 3").is_none());
+        assert!(try_parse_jsx_tag("<Tabs").is_none());
+    }
+
+    #[test]
+    fn parses_expressions() {
+        assert_eq!(try_parse_mdx_expression("{props.x} rest"), Some(9));
+        assert_eq!(try_parse_mdx_expression("{ {a: 1} }"), Some(10));
+        assert_eq!(try_parse_mdx_expression(r#"{"}"}"#), Some(5));
+        assert!(try_parse_mdx_expression("{open").is_none());
+        assert!(try_parse_mdx_expression("{{< meta x >}}").is_none());
+    }
+
+    #[test]
+    fn detects_esm_lines() {
+        assert!(is_mdx_esm_line("import Tabs from '@theme/Tabs';"));
+        assert!(is_mdx_esm_line("export const meta = {};"));
+        assert!(is_mdx_esm_line("import {Foo} from './foo';"));
+        assert!(!is_mdx_esm_line("important things"));
+        assert!(!is_mdx_esm_line("exports are listed below"));
+    }
+}
@@ -0,0 +1,50 @@
+//! Obsidian comment parsing (`%%hidden%%`).
+//!
+//! Obsidian hides everything between a pair of `%%` markers from the rendered
+//! note. Inline comments are matched here, on a single line; a comment that
+//! spans lines is a block (see `ObsidianCommentParser`). Either way the bytes
+//! are kept verbatim so the formatter never reflows or escapes their content.
+
+use super::sink::InlineSink;
+use crate::syntax::SyntaxKind;
+
+/// The `%%` marker that opens and closes a comment.
+pub(crate) const COMMENT_MARKER: &str = "%%";
+
+/// Try to parse an inline comment at the start of `text`. Returns the total
+/// length, markers included. The closing `%%` must be on the same line.
+pub(crate) fn try_parse_obsidian_comment(text: &str) -> Option<usize> {
+    let rest = text.strip_prefix(COMMENT_MARKER)?;
+    let line = &rest[..rest.find(['\n', '\r']).unwrap_or(rest.len())];
+    let close = line.find(COMMENT_MARKER)?;
+    Some(2 * COMMENT_MARKER.len() + close)
+}
+
+/// Emit an inline comment as an opaque `OBSIDIAN_COMMENT` node.
+pub(crate) fn emit_obsidian_comment(builder: &mut impl InlineSink, text: &str) {
+    builder.start_node(SyntaxKind::OBSIDIAN_COMMENT.into());
+    builder.token(SyntaxKind::TEXT.into(), text);
+    builder.finish_node();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_inline_comment() {
+        assert_eq!(try_parse_obsidian_comment("%%todo%% rest"), Some(8));
+        assert_eq!(try_parse_obsidian_comment("%%%%"), Some(4));
+        assert_eq!(
+            try_parse_obsidian_comment("%% a *b* [c] %%"),
+            Some("%% a *b* [c] %%".len())
+        );
+    }
+
+    #[test]
+    fn rejects_unclosed_and_multiline_comments() {
+        assert!(try_parse_obsidian_comment("%%open").is_none());
+        assert!(try_parse_obsidian_comment("%%open\nclose%%").is_none());
+        assert!(try_parse_obsidian_comment("% not a comment %").is_none());
+    }
+}
@@ -13,6 +13,7 @@
 //! whose offset coincides with a node boundary is emitted *outside* the node
 //! (before `start_node`) so it never nests inside e.g. an `EMPHASIS_MARKER`.
 
+use crate::parser::utils::helpers::spaces;
 use crate::syntax::SyntaxKind;
 use rowan::GreenNodeBuilder;
 
@@ -81,7 +82,7 @@ impl<'a, 'b> MarkerInjectingSink<'a, 'b> {
         {
             if leading_spaces > 0 {
                 self.inner
-                    .token(SyntaxKind::WHITESPACE.into(), &" ".repeat(leading_spaces));
+                    .token(SyntaxKind::WHITESPACE.into(), &spaces(leading_spaces));
             }
             self.inner.token(SyntaxKind::BLOCK_QUOTE_MARKER.into(), ">");
             if has_trailing_space {
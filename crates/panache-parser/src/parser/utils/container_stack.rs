@@ -1,7 +1,9 @@
 use super::list_item_buffer::ListItemBuffer;
 use super::text_buffer::{ParagraphBuffer, TextBuffer};
+use crate::options::DEFAULT_TAB_STOP;
 use crate::parser::blocks::lists::ListMarker;
 use rowan::Checkpoint;
+use std::cell::Cell;
 
 #[derive(Debug, Clone)]
 pub(crate) enum Container {
@@ -80,7 +82,37 @@ pub(crate) struct ContainerStack {
     pub(crate) stack: Vec<Container>,
 }
 
-const TAB_STOP: usize = 4;
+thread_local! {
+    /// Tab stop of the parse running on this thread, set from
+    /// [`ParserOptions::tab_stop`](crate::options::ParserOptions::tab_stop) by
+    /// [`TabStopScope`].
+    static TAB_STOP: Cell<usize> = const { Cell::new(DEFAULT_TAB_STOP) };
+}
+
+/// Columns between tab stops for the parse running on this thread.
+pub(crate) fn tab_stop() -> usize {
+    TAB_STOP.with(Cell::get)
+}
+
+/// Makes `tab_stop` the thread's tab stop until dropped, then restores the
+/// previous one, so a parse nested inside another keeps its own setting.
+pub(crate) struct TabStopScope {
+    previous: usize,
+}
+
+impl TabStopScope {
+    pub(crate) fn enter(tab_stop: usize) -> Self {
+        Self {
+            previous: TAB_STOP.replace(tab_stop.max(1)),
+        }
+    }
+}
+
+impl Drop for TabStopScope {
+    fn drop(&mut self) {
+        TAB_STOP.set(self.previous);
+    }
+}
 
 impl ContainerStack {
     pub(crate) fn new() -> Self {
@@ -100,7 +132,8 @@ impl ContainerStack {
     }
 }
 
-/// Expand tabs to columns (tab stop = 4) and return (cols, byte_offset).
+/// Expand tabs to columns (at the parse's [`tab_stop`]) and return
+/// (cols, byte_offset).
 pub(crate) fn leading_indent(line: &str) -> (usize, usize) {
     leading_indent_from(line, 0)
 }
@@ -111,6 +144,7 @@ pub(crate) fn leading_indent(line: &str) -> (usize, usize) {
 /// list marker, where the marker itself occupies columns
 /// `[indent_cols, indent_cols + marker_len)`).
 pub(crate) fn leading_indent_from(line: &str, start_col: usize) -> (usize, usize) {
+    let tab_stop = tab_stop();
     let mut cols = 0usize;
     let mut bytes = 0usize;
     for b in line.bytes() {
@@ -121,7 +155,7 @@ pub(crate) fn leading_indent_from(line: &str, start_col: usize) -> (usize, usize
             }
             b'\t' => {
                 let absolute = start_col + cols;
-                cols += TAB_STOP - (absolute % TAB_STOP);
+                cols += tab_stop - (absolute % tab_stop);
                 bytes += 1;
             }
             _ => break,
@@ -130,8 +164,10 @@ pub(crate) fn leading_indent_from(line: &str, start_col: usize) -> (usize, usize
     (cols, bytes)
 }
 
-/// Return byte index at a given column (tabs = 4).
+/// Return byte index at a given column (tabs expand to the parse's
+/// [`tab_stop`]).
 pub(crate) fn byte_index_at_column(line: &str, target_col: usize) -> usize {
+    let tab_stop = tab_stop();
     let mut col = 0usize;
     let mut idx = 0usize;
     for (i, b) in line.bytes().enumerate() {
@@ -144,7 +180,7 @@ pub(crate) fn byte_index_at_column(line: &str, target_col: usize) -> usize {
                 idx = i + 1;
             }
             b'\t' => {
-                col += TAB_STOP - (col % TAB_STOP);
+                col += tab_stop - (col % tab_stop);
                 idx = i + 1;
             }
             _ => break,
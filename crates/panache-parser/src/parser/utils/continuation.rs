@@ -167,7 +167,14 @@ impl<'a, 'cfg> ContinuationPolicy<'a, 'cfg> {
                     }
 
                     let effective_indent = raw_indent_cols.saturating_sub(content_indent_so_far);
-                    let continues_list = if let Some(ref marker_match) = next_marker {
+                    // A marker behind new `>` markers (`- a`, blank, `> - b`)
+                    // starts a list inside the new quote, not another item
+                    // of this one; the LIST_ITEM arm below still keeps the
+                    // list when the quote is indented into the item.
+                    let marker_in_new_quote = next_bq_depth > current_bq_depth;
+                    let continues_list = if let Some(ref marker_match) = next_marker
+                        && !marker_in_new_quote
+                    {
                         // Ordered markers can be right-aligned across items
                         // (e.g. `i.`, `ii.`, `iii.`), so they need a symmetric
                         // drift tolerance. Bullets are directional: a marker
@@ -178,7 +185,19 @@ impl<'a, 'cfg> ContinuationPolicy<'a, 'cfg> {
                         // it, breaking idempotency for nested-list outputs.
                         let indent_in_range = match marker {
                             lists::ListMarker::Ordered(_) => {
-                                effective_indent.abs_diff(*base_indent_cols) <= 3
+                                // The drift stops at the enclosing item: a marker
+                                // left of its content column (`- 1. a`, blank,
+                                // `1. b`) is outside that item altogether.
+                                let outside_parent_item = i > 0
+                                    && matches!(
+                                        containers.stack[i - 1],
+                                        crate::parser::utils::container_stack::Container::ListItem {
+                                            content_col,
+                                            ..
+                                        } if effective_indent < content_col
+                                    );
+                                !outside_parent_item
+                                    && effective_indent.abs_diff(*base_indent_cols) <= 3
                             }
                             lists::ListMarker::Bullet(_) => {
                                 // A bullet marker at indent ≥ 4 cannot continue
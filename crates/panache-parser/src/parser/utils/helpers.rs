@@ -1,8 +1,22 @@
 //! Shared utilities for block parsing.
 
+use std::borrow::Cow;
+
 use crate::syntax::SyntaxKind;
 use rowan::GreenNodeBuilder;
 
+/// Enough spaces for any indent the parser emits in practice.
+const SPACES: &str = "                                                                ";
+
+/// `n` spaces, borrowed rather than allocated for the short indents emitted
+/// on nearly every container line.
+pub(crate) fn spaces(n: usize) -> Cow<'static, str> {
+    match SPACES.get(..n) {
+        Some(spaces) => Cow::Borrowed(spaces),
+        None => Cow::Owned(" ".repeat(n)),
+    }
+}
+
 /// Helper to emit a line's text and newline tokens separately.
 /// Lines from split_lines_inclusive contain trailing newlines (LF or CRLF) that must be separated.
 pub(crate) fn emit_line_tokens(builder: &mut GreenNodeBuilder<'static>, line: &str) {
@@ -189,10 +203,13 @@ pub(crate) fn split_lines_inclusive(input: &str) -> Vec<&str> {
         return vec![];
     }
 
-    let mut lines = Vec::new();
-    let mut start = 0;
     let bytes = input.as_bytes();
     let len = bytes.len();
+    // One pass to size the vector saves the doubling reallocations on
+    // multi-megabyte inputs.
+    let line_count = bytes.iter().filter(|&&b| b == b'\n').count() + 1;
+    let mut lines = Vec::with_capacity(line_count);
+    let mut start = 0;
 
     let mut i = 0;
     while i < len {
@@ -223,6 +240,14 @@ pub(crate) fn split_lines_inclusive(input: &str) -> Vec<&str> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn spaces_borrow_short_runs_and_allocate_long_ones() {
+        assert_eq!(spaces(0), "");
+        assert!(matches!(spaces(4), Cow::Borrowed("    ")));
+        assert!(matches!(spaces(SPACES.len()), Cow::Borrowed(_)));
+        assert_eq!(spaces(SPACES.len() + 3), " ".repeat(SPACES.len() + 3));
+    }
+
     #[test]
     fn test_strip_leading_spaces_n() {
         assert_eq!(strip_leading_spaces_n("   text", 3), "text");
@@ -44,6 +44,8 @@ pub fn emit_inlines(
         &text[..text.len().min(40)],
         text.len()
     );
+    #[cfg(feature = "tracing")]
+    let _span = tracing::trace_span!("inline_parse", bytes = text.len()).entered();
 
     // Call the recursive inline parser
     core::parse_inline_text_recursive(builder, text, config, suppress_footnote_refs);
@@ -0,0 +1,192 @@
+//! Parser recovery warnings.
+//!
+//! Every input parses, so malformed structure is never an error. Some
+//! recoveries are visible in the tree: an unclosed fenced div or HTML comment
+//! runs to the end of the document, and under CommonMark an unclosed code
+//! fence swallows the rest of its container as code. Those are read back from
+//! the CST by [`collect_warnings`]. Others leave no trace: under Pandoc an
+//! unclosed code fence is not a fence at all and becomes paragraph text, so
+//! the parser records that warning while parsing, in its
+//! [`Diagnostics`](super::Diagnostics) sink.
+
+use rowan::{TextRange, TextSize};
+
+use crate::syntax::{SyntaxKind, SyntaxNode};
+
+/// What the parser recovered from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseWarningKind {
+    /// A code fence with no closing fence.
+    UnclosedCodeFence,
+    /// A fenced div with no closing `:::` line.
+    UnclosedFencedDiv,
+    /// An HTML comment block with no `-->`.
+    UnclosedHtmlComment,
+}
+
+impl ParseWarningKind {
+    /// Stable kebab-case name, e.g. for diagnostic codes.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::UnclosedCodeFence => "unclosed-code-fence",
+            Self::UnclosedFencedDiv => "unclosed-fenced-div",
+            Self::UnclosedHtmlComment => "unclosed-html-comment",
+        }
+    }
+}
+
+/// A place where the parser accepted malformed structure.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseWarning {
+    pub kind: ParseWarningKind,
+    /// The opening marker's range (the fence, `:::` line, or `<!--` line).
+    pub range: TextRange,
+    pub message: String,
+}
+
+/// Recovery warnings that `tree` records, in document order. Warnings the
+/// parser records while parsing (see the [module docs](self)) are only
+/// available from [`parse_with_diagnostics`](super::parse_with_diagnostics).
+pub fn collect_warnings(tree: &SyntaxNode) -> Vec<ParseWarning> {
+    let mut warnings = Vec::new();
+    for node in tree.descendants() {
+        let warning = match node.kind() {
+            SyntaxKind::CODE_BLOCK => unclosed(
+                &node,
+                SyntaxKind::CODE_FENCE_OPEN,
+                SyntaxKind::CODE_FENCE_CLOSE,
+                ParseWarningKind::UnclosedCodeFence,
+                "code fence is never closed, so the rest of its container is code",
+            ),
+            SyntaxKind::FENCED_DIV => unclosed(
+                &node,
+                SyntaxKind::DIV_FENCE_OPEN,
+                SyntaxKind::DIV_FENCE_CLOSE,
+                ParseWarningKind::UnclosedFencedDiv,
+                "fenced div is never closed, so it runs to the end of its container",
+            ),
+            SyntaxKind::HTML_BLOCK | SyntaxKind::HTML_BLOCK_RAW => unclosed_comment(&node),
+            _ => None,
+        };
+        warnings.extend(warning);
+    }
+    warnings
+}
+
+/// `recorded` plus the warnings `tree` records, in document order and without
+/// duplicates.
+pub(crate) fn merge_warnings(
+    mut recorded: Vec<ParseWarning>,
+    tree: &SyntaxNode,
+) -> Vec<ParseWarning> {
+    recorded.extend(collect_warnings(tree));
+    recorded.sort_by_key(|warning| (warning.range.start(), warning.range.end()));
+    recorded.dedup();
+    recorded
+}
+
+/// The warning for a code fence on `line` (without its line ending) that is
+/// never closed and so is read as paragraph text.
+pub(crate) fn unclosed_fence_as_text(line: TextRange) -> ParseWarning {
+    ParseWarning {
+        kind: ParseWarningKind::UnclosedCodeFence,
+        range: line,
+        message: "code fence is never closed, so it is read as paragraph text".to_string(),
+    }
+}
+
+fn unclosed(
+    node: &SyntaxNode,
+    open: SyntaxKind,
+    close: SyntaxKind,
+    kind: ParseWarningKind,
+    message: &str,
+) -> Option<ParseWarning> {
+    let open = node.children().find(|child| child.kind() == open)?;
+    if node.children().any(|child| child.kind() == close) {
+        return None;
+    }
+    Some(ParseWarning {
+        kind,
+        range: trim_newline(&open),
+        message: message.to_string(),
+    })
+}
+
+fn unclosed_comment(node: &SyntaxNode) -> Option<ParseWarning> {
+    let text = node.text().to_string();
+    let start = text.len() - text.trim_start().len();
+    if !text[start..].starts_with("<!--") || text[start..].contains("-->") {
+        return None;
+    }
+    let line_len = text[start..].find('\n').unwrap_or(text.len() - start);
+    let offset = node.text_range().start() + TextSize::from(start as u32);
+    Some(ParseWarning {
+        kind: ParseWarningKind::UnclosedHtmlComment,
+        range: TextRange::at(offset, TextSize::from(line_len as u32)),
+        message: "HTML comment is never closed, so it runs to the end of the document".to_string(),
+    })
+}
+
+/// `node`'s range without its trailing line ending.
+fn trim_newline(node: &SyntaxNode) -> TextRange {
+    let text = node.text().to_string();
+    let trimmed = text.trim_end_matches(['\n', '\r']);
+    TextRange::at(
+        node.text_range().start(),
+        TextSize::from(trimmed.len() as u32),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_with_diagnostics;
+
+    fn kinds(input: &str) -> Vec<ParseWarningKind> {
+        parse_with_diagnostics(input, None)
+            .1
+            .into_iter()
+            .map(|warning| warning.kind)
+            .collect()
+    }
+
+    #[test]
+    fn well_formed_documents_have_no_warnings() {
+        let input = "```r\nx\n```\n\n::: note\ntext\n:::\n\n<!-- ok -->\n";
+        assert!(kinds(input).is_empty());
+    }
+
+    #[test]
+    fn unclosed_code_fence() {
+        let (_, warnings) = parse_with_diagnostics("Text.\n\n```python\nx = 1\n", None);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, ParseWarningKind::UnclosedCodeFence);
+        assert_eq!(u32::from(warnings[0].range.start()), 7);
+        assert_eq!(u32::from(warnings[0].range.len()), 9);
+    }
+
+    #[test]
+    fn unclosed_code_fence_in_a_container_points_past_the_prefix() {
+        let (_, warnings) = parse_with_diagnostics("> Quote.\n>\n> ```r\n> x\n", None);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(u32::from(warnings[0].range.start()), 13);
+        assert_eq!(u32::from(warnings[0].range.len()), 4);
+    }
+
+    #[test]
+    fn unclosed_fenced_div() {
+        assert_eq!(
+            kinds("::: note\n\ntext\n"),
+            vec![ParseWarningKind::UnclosedFencedDiv]
+        );
+    }
+
+    #[test]
+    fn unclosed_html_comment() {
+        assert_eq!(
+            kinds("<!-- draft\n\ntext\n"),
+            vec![ParseWarningKind::UnclosedHtmlComment]
+        );
+    }
+}
@@ -166,7 +166,7 @@ impl YamlValidationContext {
 ///   pandoc (libyaml), so both must accept.
 /// - RMarkdown: `rmarkdown::yaml_front_matter` (R `yaml`) reads it, then the doc
 ///   renders through pandoc (libyaml), so both must accept.
-/// - GFM/CommonMark/MultiMarkdown/Mdsvex/MyST: no asserted YAML metadata
+/// - GFM/CommonMark/MultiMarkdown/Mdsvex/MyST/Obsidian: no asserted YAML metadata
 ///   consumer — lenient. (MyST frontmatter is real YAML read by PyYAML, but the
 ///   parse-first MyST flavor stays lenient until its loader is modeled.)
 ///
@@ -180,7 +180,8 @@ fn frontmatter_consumers(flavor: Flavor) -> ConsumerSet {
         | Flavor::CommonMark
         | Flavor::MultiMarkdown
         | Flavor::Mdsvex
-        | Flavor::Myst => ConsumerSet::empty(),
+        | Flavor::Myst
+        | Flavor::Obsidian => ConsumerSet::empty(),
     }
 }
 
@@ -196,7 +197,8 @@ fn hashpipe_consumers(flavor: Flavor) -> ConsumerSet {
         | Flavor::CommonMark
         | Flavor::MultiMarkdown
         | Flavor::Mdsvex
-        | Flavor::Myst => ConsumerSet::empty(),
+        | Flavor::Myst
+        | Flavor::Obsidian => ConsumerSet::empty(),
     }
 }
 
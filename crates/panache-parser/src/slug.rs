@@ -0,0 +1,162 @@
+//! Heading anchor slugs.
+//!
+//! Pandoc and GitHub derive the id of a heading without an explicit `{#id}`
+//! from its text, each in its own way, and both number repeats (`intro`,
+//! `intro-1`, ...). Everything in panache that needs those ids (anchor
+//! validation, link checking, `#section` navigation in the editor, generated
+//! tables of contents) goes through this module, so they agree with each other
+//! and with the renderer.
+//! Which algorithm applies follows the `gfm_auto_identifiers` extension, on by
+//! default for the `gfm` flavor.
+
+use std::collections::HashSet;
+
+use crate::Extensions;
+
+/// An identifier algorithm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SlugStyle {
+    /// Pandoc's `auto_identifiers`.
+    #[default]
+    Pandoc,
+    /// GitHub's, as in Pandoc's `gfm_auto_identifiers`.
+    Gfm,
+}
+
+impl SlugStyle {
+    /// The style a document with these extensions is rendered with.
+    pub fn for_extensions(extensions: &Extensions) -> Self {
+        if extensions.gfm_auto_identifiers {
+            Self::Gfm
+        } else {
+            Self::Pandoc
+        }
+    }
+
+    /// The identifier for heading `text`, before numbering repeats.
+    pub fn slugify(self, text: &str) -> String {
+        match self {
+            Self::Pandoc => pandoc(text),
+            Self::Gfm => gfm(text),
+        }
+    }
+}
+
+/// Pandoc's identifier for `text`: lowercased, punctuation other than `_`,
+/// `-`, and `.` removed, whitespace runs replaced by a hyphen, and everything
+/// before the first letter dropped. Empty when no letter is left; Pandoc then
+/// uses `section`, which [`Slugger`] applies.
+pub fn pandoc(text: &str) -> String {
+    let filtered: String = text
+        .chars()
+        .flat_map(char::to_lowercase)
+        .filter(|ch| ch.is_whitespace() || ch.is_alphanumeric() || matches!(ch, '_' | '-' | '.'))
+        .collect();
+    let joined = filtered.split_whitespace().collect::<Vec<_>>().join("-");
+    joined
+        .trim_start_matches(|ch: char| !ch.is_alphabetic())
+        .to_string()
+}
+
+/// GitHub's identifier for `text`: lowercased, punctuation other than `_`
+/// and `-` removed, and each whitespace character replaced by a hyphen.
+pub fn gfm(text: &str) -> String {
+    text.trim()
+        .chars()
+        .flat_map(char::to_lowercase)
+        .filter_map(|ch| {
+            if ch.is_whitespace() {
+                Some('-')
+            } else if ch.is_alphanumeric() || matches!(ch, '_' | '-') {
+                Some(ch)
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Hands out unique identifiers for the headings of one document, in order.
+#[derive(Debug, Clone, Default)]
+pub struct Slugger {
+    style: SlugStyle,
+    used: HashSet<String>,
+}
+
+impl Slugger {
+    pub fn new(style: SlugStyle) -> Self {
+        Self {
+            style,
+            used: HashSet::new(),
+        }
+    }
+
+    /// Mark `id` as taken, as by a heading's explicit `{#id}`.
+    pub fn reserve(&mut self, id: &str) {
+        self.used.insert(id.to_string());
+    }
+
+    /// The identifier for the next heading with `text`: its slug, numbered
+    /// `-1`, `-2`, ... when an earlier heading took it. `None` when the
+    /// heading gets no identifier (a GitHub heading without letters or
+    /// digits).
+    pub fn slug(&mut self, text: &str) -> Option<String> {
+        let mut base = self.style.slugify(text);
+        if base.is_empty() {
+            match self.style {
+                SlugStyle::Pandoc => base = "section".to_string(),
+                SlugStyle::Gfm => return None,
+            }
+        }
+        let mut id = base.clone();
+        let mut n = 0;
+        while self.used.contains(&id) {
+            n += 1;
+            id = format!("{base}-{n}");
+        }
+        self.used.insert(id.clone());
+        Some(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pandoc_slugs_match_pandoc() {
+        assert_eq!(
+            pandoc("Heading identifiers in HTML"),
+            "heading-identifiers-in-html"
+        );
+        assert_eq!(pandoc("Maître d'hôtel"), "maître-dhôtel");
+        assert_eq!(pandoc("*Dogs*?--in *my* house?"), "dogs--in-my-house");
+        assert_eq!(pandoc("[HTML], [S5], or [RTF]?"), "html-s5-or-rtf");
+        assert_eq!(pandoc("3. Applications"), "applications");
+        assert_eq!(pandoc("33"), "");
+        assert_eq!(pandoc("Version 2.0 notes"), "version-2.0-notes");
+    }
+
+    #[test]
+    fn gfm_slugs_match_github() {
+        assert_eq!(gfm("3. Applications"), "3-applications");
+        assert_eq!(gfm("Foo - Bar"), "foo---bar");
+        assert_eq!(gfm("Version 2.0 notes"), "version-20-notes");
+        assert_eq!(gfm("snake_case & more"), "snake_case--more");
+    }
+
+    #[test]
+    fn slugger_numbers_repeats_and_skips_reserved_ids() {
+        let mut slugger = Slugger::new(SlugStyle::Pandoc);
+        slugger.reserve("intro-1");
+        assert_eq!(slugger.slug("Intro").as_deref(), Some("intro"));
+        assert_eq!(slugger.slug("Intro").as_deref(), Some("intro-2"));
+        assert_eq!(slugger.slug("42").as_deref(), Some("section"));
+        assert_eq!(slugger.slug("!!").as_deref(), Some("section-1"));
+
+        let mut slugger = Slugger::new(SlugStyle::Gfm);
+        assert_eq!(slugger.slug("Intro").as_deref(), Some("intro"));
+        assert_eq!(slugger.slug("Intro").as_deref(), Some("intro-1"));
+        assert_eq!(slugger.slug("!!"), None);
+    }
+}
@@ -32,7 +32,7 @@ pub enum SyntaxKind {
     YAML_DOCUMENT_END,   // YAML document end marker (...)
 
     BLOCK_QUOTE_MARKER, // >
-    ALERT_MARKER,       // [!NOTE], [!TIP], etc.
+    ALERT_MARKER,       // [!NOTE], [!TIP], or an Obsidian callout header
     IMAGE_LINK_START,   // ![
     LIST_MARKER,        // - + *
     TASK_CHECKBOX,      // [ ] or [x] or [X]
@@ -375,6 +375,15 @@ pub enum SyntaxKind {
     MYST_SUBSTITUTION,       // an inline `{{ name }}` substitution
     MYST_SUBSTITUTION_NAME,  // the substitution key between `{{` and `}}`
 
+    // Obsidian
+    OBSIDIAN_COMMENT, // a `%%...%%` comment, inline or as a block
+
+    // MDX. Opaque, lossless spans: JSX and JavaScript are kept verbatim.
+    MDX_ESM,        // top-level `import` / `export` statements
+    MDX_FLOW,       // JSX tags or `{expressions}` occupying whole lines
+    MDX_JSX,        // an inline JSX tag: <Badge />, <Tabs>, </Tabs>
+    MDX_EXPRESSION, // an inline `{expression}`
+
     EMOJI, // :alias:
 
     // Bracket-shape pattern that did not resolve as a link/image.
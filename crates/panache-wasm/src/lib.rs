@@ -1,7 +1,11 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
 use wasm_bindgen::prelude::*;
 
 use panache_formatter::config::{
-    BlankLines, Flavor, HorizontalRuleStyle, LineEnding, MathDelimiterStyle, TabStopMode, WrapMode,
+    BareUrls, BlankLines, EmphasisMarker, Escaping, FinalNewline, Flavor, HorizontalRuleStyle,
+    LineEnding, LinkDestinations, MathDelimiterStyle, TabStopMode, WrapMode,
 };
 
 fn parse_flavor(value: &str) -> Option<Flavor> {
@@ -13,6 +17,8 @@ fn parse_flavor(value: &str) -> Option<Flavor> {
         "commonmark" | "common-mark" => Some(Flavor::CommonMark),
         "multimarkdown" | "multi-markdown" => Some(Flavor::MultiMarkdown),
         "mdsvex" => Some(Flavor::Mdsvex),
+        "myst" => Some(Flavor::Myst),
+        "obsidian" => Some(Flavor::Obsidian),
         _ => None,
     }
 }
@@ -31,7 +37,11 @@ fn parse_blank_lines(value: &str) -> Option<BlankLines> {
     match value.to_ascii_lowercase().as_str() {
         "preserve" => Some(BlankLines::Preserve),
         "collapse" => Some(BlankLines::Collapse),
-        _ => None,
+        // `max=2` stands in for the TOML `{ max = 2 }`.
+        other => other
+            .strip_prefix("max=")
+            .and_then(|lines| lines.trim().parse().ok())
+            .map(BlankLines::Max),
     }
 }
 
@@ -61,6 +71,49 @@ fn parse_horizontal_rule_style(value: &str) -> Option<HorizontalRuleStyle> {
     }
 }
 
+fn parse_emphasis_marker(value: &str) -> Option<EmphasisMarker> {
+    match value.to_ascii_lowercase().as_str() {
+        "asterisk" => Some(EmphasisMarker::Asterisk),
+        "underscore" => Some(EmphasisMarker::Underscore),
+        "preserve" => Some(EmphasisMarker::Preserve),
+        _ => None,
+    }
+}
+
+fn parse_escaping(value: &str) -> Option<Escaping> {
+    match value.to_ascii_lowercase().as_str() {
+        "preserve" => Some(Escaping::Preserve),
+        "minimal" => Some(Escaping::Minimal),
+        _ => None,
+    }
+}
+
+fn parse_link_destinations(value: &str) -> Option<LinkDestinations> {
+    match value.to_ascii_lowercase().as_str() {
+        "preserve" => Some(LinkDestinations::Preserve),
+        "angle-brackets" => Some(LinkDestinations::AngleBrackets),
+        "percent-encode" => Some(LinkDestinations::PercentEncode),
+        _ => None,
+    }
+}
+
+fn parse_bare_urls(value: &str) -> Option<BareUrls> {
+    match value.to_ascii_lowercase().as_str() {
+        "preserve" => Some(BareUrls::Preserve),
+        "autolink" => Some(BareUrls::Autolink),
+        "link" => Some(BareUrls::Link),
+        _ => None,
+    }
+}
+
+fn parse_final_newline(value: &str) -> Option<FinalNewline> {
+    match value.to_ascii_lowercase().as_str() {
+        "ensure" => Some(FinalNewline::Ensure),
+        "preserve" => Some(FinalNewline::Preserve),
+        _ => None,
+    }
+}
+
 fn parse_tab_stops(value: &str) -> Option<TabStopMode> {
     match value.to_ascii_lowercase().as_str() {
         "normalize" => Some(TabStopMode::Normalize),
@@ -91,6 +144,13 @@ pub fn format_qmd_with_options(
     tab_width: Option<usize>,
     math_indent: Option<usize>,
     horizontal_rule_style: Option<String>,
+    emphasis_marker: Option<String>,
+    strong_marker: Option<String>,
+    escaping: Option<String>,
+    link_destinations: Option<String>,
+    bare_urls: Option<String>,
+    final_newline: Option<String>,
+    trim_trailing_whitespace: Option<bool>,
 ) -> Result<String, JsValue> {
     let mut cfg = panache_formatter::Config::default();
 
@@ -160,6 +220,46 @@ pub fn format_qmd_with_options(
             })?;
     }
 
+    if let Some(emphasis_marker) = emphasis_marker {
+        cfg.emphasis_marker = parse_emphasis_marker(&emphasis_marker).ok_or_else(|| {
+            JsValue::from_str(&format!("Unsupported emphasis marker: {emphasis_marker}"))
+        })?;
+    }
+
+    if let Some(strong_marker) = strong_marker {
+        cfg.strong_marker = parse_emphasis_marker(&strong_marker).ok_or_else(|| {
+            JsValue::from_str(&format!("Unsupported strong marker: {strong_marker}"))
+        })?;
+    }
+
+    if let Some(escaping) = escaping {
+        cfg.escaping = parse_escaping(&escaping)
+            .ok_or_else(|| JsValue::from_str(&format!("Unsupported escaping: {escaping}")))?;
+    }
+
+    if let Some(link_destinations) = link_destinations {
+        cfg.link_destinations = parse_link_destinations(&link_destinations).ok_or_else(|| {
+            JsValue::from_str(&format!(
+                "Unsupported link destinations: {link_destinations}"
+            ))
+        })?;
+    }
+
+    if let Some(bare_urls) = bare_urls {
+        cfg.bare_urls = parse_bare_urls(&bare_urls)
+            .ok_or_else(|| JsValue::from_str(&format!("Unsupported bare URLs: {bare_urls}")))?;
+    }
+
+    if let Some(final_newline) = final_newline {
+        cfg.final_newline = parse_final_newline(&final_newline).ok_or_else(|| {
+            JsValue::from_str(&format!("Unsupported final newline: {final_newline}"))
+        })?;
+    }
+
+    if let Some(trim) = trim_trailing_whitespace {
+        cfg.trim_trailing_whitespace = trim;
+    }
+
     Ok(panache_formatter::format(input, Some(cfg), None))
 }
 
@@ -170,3 +270,156 @@ pub fn tokenize_debug(input: &str) -> String {
     let tree = panache_parser::parse(input, None);
     format!("{tree:#?}")
 }
+
+#[wasm_bindgen(typescript_custom_section)]
+const TS_TYPES: &'static str = r#"
+/**
+ * Configuration accepted by `format_with_config`, `lint`, and `parse_json`,
+ * as a JSON string. Keys and values are those of `panache.toml`, e.g.
+ * `{"flavor": "quarto", "format": {"line-width": 72, "wrap": "sentence"}}`.
+ */
+export type PanacheConfig = Record<string, unknown>;
+
+/**
+ * Formats the code of one code cell, registered with `set_formatter`. Gets the
+ * cell's code and language and returns the formatted code, or `null` or
+ * `undefined` to leave the cell unchanged. Must be synchronous; a thrown
+ * exception also leaves the cell unchanged.
+ */
+export type CodeFormatter = (code: string, language: string) => string | null | undefined;
+
+/** A lint diagnostic, as returned (JSON-encoded, in an array) by `lint`. */
+export interface LintDiagnostic {
+  code: string;
+  severity: "error" | "warning" | "info";
+  message: string;
+  /** 1-indexed line of the start of the range. */
+  line: number;
+  /** 1-indexed column of the start of the range. */
+  column: number;
+  /** 1-indexed line of the end of the range. */
+  end_line: number;
+  /** 1-indexed column of the end of the range (just past its last character). */
+  end_column: number;
+  /** Byte offsets of the flagged range. */
+  start: number;
+  end: number;
+  fix: LintFix | null;
+}
+
+export interface LintFix {
+  message: string;
+  safe: boolean;
+  edits: { start: number; end: number; replacement: string }[];
+}
+
+/** A node or token of the syntax tree, as returned (JSON-encoded) by `parse_json`. */
+export type CstElement = CstNode | CstToken;
+
+/** Zero-based line and column, in UTF-8 bytes and in UTF-16 code units. */
+export interface CstPoint {
+  line: number;
+  column: number;
+  column_utf16: number;
+}
+
+interface CstElementBase {
+  /** Pre-order index of the element; the root is 0. */
+  id: number;
+  kind: string;
+  /** Byte offsets. */
+  range: { start: number; end: number };
+  position: { start: CstPoint; end: CstPoint };
+}
+
+export interface CstNode extends CstElementBase {
+  children: CstElement[];
+}
+
+export interface CstToken extends CstElementBase {
+  text: string;
+}
+"#;
+
+fn parse_config(config_json: Option<String>) -> Result<panache::Config, JsValue> {
+    match config_json.as_deref().map(str::trim) {
+        None | Some("") => Ok(panache::Config::default()),
+        Some(json) => serde_json::from_str(json)
+            .map_err(|err| JsValue::from_str(&format!("Invalid config: {err}"))),
+    }
+}
+
+/// Format `input` with a full configuration given as JSON with the keys of
+/// `panache.toml` (see `PanacheConfig`). Code cells are formatted with the
+/// functions registered through `set_formatter`.
+#[wasm_bindgen]
+pub fn format_with_config(input: &str, config_json: Option<String>) -> Result<String, JsValue> {
+    let config = parse_config(config_json)?;
+    panache::try_format(input, Some(config), None)
+        .map_err(|err| JsValue::from_str(&err.to_string()))
+}
+
+/// Run the built-in lint rules over `input` and return the diagnostics as a
+/// JSON array of `LintDiagnostic`.
+#[wasm_bindgen]
+pub fn lint(input: &str, config_json: Option<String>) -> Result<String, JsValue> {
+    let config = parse_config(config_json)?;
+    let tree = panache::try_parse(input, Some(config.clone()))
+        .map_err(|err| JsValue::from_str(&err.to_string()))?;
+    let diagnostics: Vec<serde_json::Value> = panache::linter::lint(&tree, input, &config)
+        .iter()
+        .map(|diagnostic| diagnostic.to_json())
+        .collect();
+    Ok(serde_json::Value::from(diagnostics).to_string())
+}
+
+/// Parse `input` and return its syntax tree as JSON (see `CstElement`), in the
+/// shape written by `panache parse --json`.
+#[wasm_bindgen]
+pub fn parse_json(input: &str, config_json: Option<String>) -> Result<String, JsValue> {
+    let config = parse_config(config_json)?;
+    let tree = panache::try_parse(input, Some(config))
+        .map_err(|err| JsValue::from_str(&err.to_string()))?;
+    serde_json::to_string(&panache::syntax::cst_to_json(&tree))
+        .map_err(|err| JsValue::from_str(&err.to_string()))
+}
+
+thread_local! {
+    static FORMATTERS: RefCell<HashMap<String, js_sys::Function>> = RefCell::new(HashMap::new());
+}
+
+/// Register `formatter` (see `CodeFormatter`) for code cells of `language`,
+/// replacing any earlier one. `format_with_config` then calls it for those
+/// cells in place of the external commands configured under `[formatters]`,
+/// which cannot run in the browser.
+#[wasm_bindgen]
+pub fn set_formatter(language: &str, formatter: js_sys::Function) {
+    FORMATTERS.with(|formatters| {
+        formatters
+            .borrow_mut()
+            .insert(language.to_ascii_lowercase(), formatter)
+    });
+    panache::formatter::set_code_formatter(Some(Box::new(call_formatter)));
+}
+
+/// Remove every formatter registered with `set_formatter`.
+#[wasm_bindgen]
+pub fn clear_formatters() {
+    FORMATTERS.with(|formatters| formatters.borrow_mut().clear());
+    panache::formatter::set_code_formatter(None);
+}
+
+fn call_formatter(language: &str, code: &str) -> Option<String> {
+    FORMATTERS.with(|formatters| {
+        let formatters = formatters.borrow();
+        let formatter = formatters.get(&language.to_ascii_lowercase())?;
+        formatter
+            .call2(
+                &JsValue::NULL,
+                &JsValue::from_str(code),
+                &JsValue::from_str(language),
+            )
+            .ok()?
+            .as_string()
+    })
+}
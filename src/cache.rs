@@ -5,7 +5,7 @@ use std::path::{Path, PathBuf};
 
 use wincode::{SchemaRead, SchemaWrite};
 
-const CACHE_SCHEMA_VERSION: u32 = 2;
+const CACHE_SCHEMA_VERSION: u32 = 4;
 const CACHE_FILE_NAME: &str = "cli-cache-v1.bin";
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -55,6 +55,8 @@ pub enum CachedDiagnosticNoteKind {
 pub struct CachedLocation {
     pub line: usize,
     pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
     pub start: u32,
     pub end: u32,
 }
@@ -90,6 +92,7 @@ struct PersistentCache {
     schema_version: u32,
     lint: HashMap<String, CachedLintEntry>,
     format: HashMap<String, CachedFormatEntry>,
+    urls: HashMap<String, CachedUrlEntry>,
 }
 
 impl Default for PersistentCache {
@@ -98,6 +101,7 @@ impl Default for PersistentCache {
             schema_version: CACHE_SCHEMA_VERSION,
             lint: HashMap::new(),
             format: HashMap::new(),
+            urls: HashMap::new(),
         }
     }
 }
@@ -121,6 +125,15 @@ struct CachedFormatEntry {
     output: String,
 }
 
+/// An external URL that loaded, for `check-links --online`. Failures are not
+/// stored, so a fixed link is picked up on the next run.
+#[derive(Debug, Clone, SchemaWrite, SchemaRead)]
+struct CachedUrlEntry {
+    status: u16,
+    /// Seconds since the Unix epoch.
+    checked_at: u64,
+}
+
 pub struct FormatStoreArgs {
     pub file_fingerprint: String,
     pub config_fingerprint: String,
@@ -312,6 +325,30 @@ impl CliCache {
         );
         self.dirty = true;
     }
+
+    /// The status `url` answered with within the last `max_age`.
+    pub fn get_url(&self, url: &str, max_age: std::time::Duration) -> Option<u16> {
+        let entry = self.state.urls.get(url)?;
+        (unix_now().saturating_sub(entry.checked_at) <= max_age.as_secs()).then_some(entry.status)
+    }
+
+    pub fn put_url(&mut self, url: &str, status: u16) {
+        self.state.urls.insert(
+            url.to_string(),
+            CachedUrlEntry {
+                status,
+                checked_at: unix_now(),
+            },
+        );
+        self.dirty = true;
+    }
+}
+
+fn unix_now() -> u64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |dur| dur.as_secs())
 }
 
 fn mode_to_str(mode: FormatCacheMode) -> &'static str {
@@ -464,6 +501,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn url_entry_expires_after_max_age() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        let cfg = cache_enabled_config(tmp.path());
+        let mut cache = CliCache::open(&cfg, None, tmp.path())
+            .expect("open cache")
+            .expect("cache enabled");
+
+        cache.put_url("https://example.com", 200);
+        cache
+            .state
+            .urls
+            .get_mut("https://example.com")
+            .unwrap()
+            .checked_at -= 120;
+
+        let minute = std::time::Duration::from_secs(60);
+        let hour = std::time::Duration::from_secs(3600);
+        assert_eq!(cache.get_url("https://example.com", hour), Some(200));
+        assert_eq!(cache.get_url("https://example.com", minute), None);
+        assert_eq!(cache.get_url("https://example.org", hour), None);
+    }
+
     #[test]
     fn default_cache_dir_uses_global_base_with_workspace_namespace() {
         let tmp = tempfile::tempdir().expect("tempdir");
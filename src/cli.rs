@@ -1,5 +1,5 @@
-use clap::builder::Styles;
 use clap::builder::styling::{AnsiColor, Effects};
+use clap::builder::{PossibleValue, StringValueParser, Styles, TypedValueParser};
 use clap::{Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
 
@@ -62,7 +62,7 @@ pub struct Cli {
     #[arg(
         long,
         global = true,
-        value_enum,
+        value_parser = FlavorValueParser,
         value_name = "FLAVOR",
         help_heading = "Global options"
     )]
@@ -73,9 +73,43 @@ pub struct Cli {
         table, and the flavor inferred from the file extension. Extension overrides from \
         [extensions] in panache.toml still merge on top of the flavor's defaults. Useful \
         when the file extension is unknown (e.g. a .txt file containing markdown) or when \
-        you want to force a one-off interpretation."
+        you want to force a one-off interpretation. Pandoc-style extension modifiers may be \
+        appended, as in `--flavor markdown+emoji-fancy_lists`; `markdown` and \
+        `markdown_mmd` are accepted as Pandoc's names for pandoc and multimarkdown."
+    )]
+    pub flavor: Option<String>,
+
+    /// Enable a syntax extension for this invocation
+    #[arg(
+        long,
+        global = true,
+        value_name = "NAME",
+        help_heading = "Global options"
+    )]
+    #[arg(help = "Enable an extension (overrides config; may be repeated)")]
+    #[arg(
+        long_help = "Enable the extension NAME for this invocation, on top of the flavor's \
+        defaults and the [extensions] table in panache.toml. May be repeated. Names are the \
+        kebab-case keys of [extensions]; underscores are accepted too, so `fancy_lists` and \
+        `fancy-lists` are the same extension."
     )]
-    pub flavor: Option<CliFlavor>,
+    pub enable_extension: Vec<String>,
+
+    /// Disable a syntax extension for this invocation
+    #[arg(
+        long,
+        global = true,
+        value_name = "NAME",
+        help_heading = "Global options"
+    )]
+    #[arg(help = "Disable an extension (overrides config; may be repeated)")]
+    #[arg(
+        long_help = "Disable the extension NAME for this invocation, on top of the flavor's \
+        defaults and the [extensions] table in panache.toml. May be repeated, and wins over \
+        --enable-extension for the same name. Names are the kebab-case keys of [extensions]; \
+        underscores are accepted too."
+    )]
+    pub disable_extension: Vec<String>,
 
     /// Control when colored output is used
     #[arg(
@@ -113,9 +147,10 @@ pub struct Cli {
     #[arg(short = 'v', long, global = true, help_heading = "Global options")]
     #[arg(help = "Print additional informational output where supported")]
     #[arg(
-        long_help = "Print additional informational output where supported. Currently used by \
-        `panache clean` to include a summary of cache size and file count alongside the \
-        \"Removed cache directory\" message. Conflicts with --quiet."
+        long_help = "Print additional informational output where supported. `panache format` \
+        warns on stderr about malformed structure the parser recovered from, such as an \
+        unclosed code fence or fenced div, and `panache clean` includes a summary of cache size \
+        and file count alongside the \"Removed cache directory\" message. Conflicts with --quiet."
     )]
     pub verbose: bool,
 
@@ -137,6 +172,47 @@ pub struct Cli {
     )]
     pub no_cache: bool,
 
+    /// Don't run external commands from untrusted project configs
+    #[arg(
+        long,
+        global = true,
+        value_name = "BOOL",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true",
+        env = "PANACHE_SAFE",
+        help_heading = "Global options"
+    )]
+    #[arg(help = "Skip external formatters/linters from project configs until trusted")]
+    #[arg(
+        long_help = "Skip the external formatters and linters configured by a discovered project \
+        config (panache.toml or .panache.toml) until it has been approved with `panache trust`. \
+        All other settings from the config still apply. Approvals are stored per config file and \
+        invalidated when its external commands change. Off by default for the CLI and on by \
+        default for `panache lsp`; pass --safe=false to disable it there. Can also be set with \
+        PANACHE_SAFE."
+    )]
+    pub safe: Option<bool>,
+
+    /// Limits for external formatters, as key=value
+    #[arg(
+        long,
+        global = true,
+        value_name = "KEY=VALUE",
+        value_delimiter = ',',
+        env = "PANACHE_SANDBOX",
+        help_heading = "Global options"
+    )]
+    #[arg(help = "Set an [external-sandbox] limit for external formatters (repeatable)")]
+    #[arg(
+        long_help = "Set an [external-sandbox] limit for spawned external formatters, e.g. \
+        --sandbox max-memory-mb=1024 or --sandbox restrict-commands=true,allowed-commands=air. \
+        Overrides the sandbox from the user config or --config; a discovered project config \
+        cannot set the sandbox. Repeat allowed-commands to allow several commands. Can also be \
+        set with PANACHE_SANDBOX."
+    )]
+    pub sandbox: Vec<String>,
+
     /// Path to cache directory override
     #[arg(
         long,
@@ -177,8 +253,11 @@ pub enum Commands {
     /// Format a Quarto, Pandoc, or Markdown document
     #[command(
         long_about = "Format a Quarto, Pandoc, or R Markdown document according to Panache's \
-        formatting rules. By default, formats files in place. Use --check to verify formatting \
-        without making changes. Stdin input always outputs to stdout."
+        formatting rules. By default, formats files in place, writing each through a temporary \
+        file that is renamed over the original so an interrupted run never truncates a document. \
+        Use --check to verify formatting without making changes, or --stdout to print the result \
+        instead. Stdin input always outputs to stdout. A file that cannot be read or formatted is \
+        reported and skipped while the others are still processed, and the run exits with code 2."
     )]
     Format {
         /// Input file(s) (stdin if not provided, or pass `-`)
@@ -189,7 +268,8 @@ pub enum Commands {
             Supports .qmd, .md, .Rmd/.Rmarkdown, and other Markdown-based formats. When file paths are \
             provided, the files are formatted in place by default. Stdin input always outputs \
             to stdout. Supports glob patterns (e.g., *.md) and directories (e.g., . or docs/). \
-            Directories are traversed recursively, respecting .gitignore files. \
+            Directories are traversed recursively, respecting .gitignore and .panacheignore \
+            files. \
             `-` cannot be combined with other paths."
         )]
         files: Vec<PathBuf>,
@@ -200,10 +280,128 @@ pub enum Commands {
         #[arg(
             long_help = "Check if the file is already formatted according to Panache's rules \
             without making any changes. If the file is not formatted, displays a diff and exits \
-            with code 1. If formatted, exits with code 0. Useful for CI/CD pipelines."
+            with code 1. If formatted, exits with code 0. Errors (unreadable files, invalid config) \
+            exit with code 2. Useful for CI/CD pipelines."
         )]
         check: bool,
 
+        /// How `--check` reports differences
+        #[arg(
+            long,
+            value_enum,
+            value_name = "FORMAT",
+            default_value = "inline",
+            requires = "check"
+        )]
+        #[arg(help = "Diff style for --check: inline, unified, or json")]
+        #[arg(long_help = "How --check reports formatting differences.\
+            \n\n`inline` (the default) prints rustfmt-style hunks headed by `Diff in FILE:LINE:`. \
+            `unified` prints a standard `diff -u` patch with `a/` and `b/` path prefixes, which \
+            can be applied with `patch -p1` or `git apply`. `json` prints a single JSON document \
+            listing the changed hunks of every file, each tagged with a `kind` (`content`, \
+            `line-endings`, `bom`, or `line-endings-and-bom`), and suppresses the other status \
+            messages.\
+            \n\nInline and unified diffs are colored on terminals; colors are dropped when \
+            output is piped, with --no-color or --color never, or when NO_COLOR is set.")]
+        diff_format: DiffFormat,
+
+        /// Print formatted output instead of overwriting files
+        #[arg(long, conflicts_with = "check")]
+        #[arg(help = "Print formatted output to stdout instead of overwriting files")]
+        #[arg(
+            long_help = "Write the formatted document to stdout instead of overwriting the \
+            input file, even when paths are given. With several files, their outputs are \
+            printed one after another in input order. Status messages are suppressed."
+        )]
+        stdout: bool,
+
+        /// Print the edits formatting would make instead of applying them
+        #[arg(
+            long,
+            value_enum,
+            value_name = "FORMAT",
+            conflicts_with_all = ["check", "stdout", "backup", "list_different", "restage"]
+        )]
+        #[arg(help = "Print the text edits formatting would make (json) without writing files")]
+        #[arg(
+            long_help = "Print the minimal text edits that turn each file into its formatted \
+            version instead of writing them, as a single JSON document. Each changed file \
+            lists its edits in document order, and each edit has `start` and `end` positions \
+            (1-indexed `line` and `column`, in characters, plus the byte `offset`) and the \
+            replacement `new_text`. Unchanged files are omitted. These are the same edits the \
+            language server sends for `textDocument/formatting`."
+        )]
+        edits: Option<EditsFormat>,
+
+        /// Keep a copy of each file before overwriting it
+        #[arg(long, value_name = "EXT", conflicts_with_all = ["check", "stdout"])]
+        #[arg(help = "Save the original of each reformatted file as <FILE>.<EXT>")]
+        #[arg(
+            long_help = "Before overwriting a file that formatting changes, copy the original \
+            next to it with EXT appended to its name (e.g. `--backup orig` keeps \
+            `doc.qmd.orig`). A leading dot in EXT is optional. Unchanged files are not \
+            backed up."
+        )]
+        backup: Option<String>,
+
+        /// List files that would be reformatted, without diffs
+        #[arg(short = 'l', long, conflicts_with_all = ["stdout", "backup"])]
+        #[arg(help = "Print the paths of files that are not formatted (implies --check)")]
+        #[arg(
+            long_help = "Print the path of every file whose formatting differs, one per line, \
+            instead of a diff. Like prettier's `-l`, this implies --check: no files are written, \
+            and the exit code is 1 when any file is listed."
+        )]
+        list_different: bool,
+
+        /// Summarize which files a check would change
+        #[arg(long)]
+        #[arg(help = "With --check, end with a list of changed files and totals")]
+        #[arg(
+            long_help = "With --check or --list-different, finish with a `Would reformat: FILE` \
+            line for each file that needs formatting followed by the totals (e.g. `2 files \
+            would be reformatted, 5 files already formatted`). Ignored with \
+            `--diff-format json`, which already lists the files.\
+            \n\nExit codes for --check are 0 when everything is formatted, 1 when some file \
+            needs formatting, and 2 on an error such as an unreadable file or invalid config."
+        )]
+        summary: bool,
+
+        /// Treat line-ending-only differences as formatted
+        #[arg(long)]
+        #[arg(help = "Don't count files that differ only in line endings as changed")]
+        #[arg(
+            long_help = "Treat a file whose formatted output differs from it only in line \
+            endings (for example CRLF normalized to LF by `line-ending = \"lf\"`, or a file \
+            with mixed endings) as already formatted: --check does not report it and format \
+            does not rewrite it.\
+            \n\nWithout this flag, --check reports such files as `line endings only` instead \
+            of printing a diff whose lines look identical. Useful while migrating a \
+            mixed-platform repository."
+        )]
+        ignore_line_endings: bool,
+
+        /// Line ending to write
+        #[arg(long, value_enum, value_name = "ENDING")]
+        #[arg(help = "Line ending to write: auto, lf, or crlf (overrides config)")]
+        #[arg(
+            long_help = "Line ending to write, overriding `line-ending` in panache.toml for \
+            this invocation. `lf` writes `\\n` and `crlf` writes `\\r\\n` throughout; `auto` \
+            (the config default) uses the first line ending found in each input."
+        )]
+        line_ending: Option<CliLineEnding>,
+
+        /// Treat BOM-only differences as formatted
+        #[arg(long)]
+        #[arg(help = "Don't count files that differ only by a byte order mark as changed")]
+        #[arg(
+            long_help = "Treat a file whose only difference from the formatted output is a \
+            leading UTF-8 byte order mark, which formatting drops, as already formatted: \
+            --check does not report it and format does not rewrite it.\
+            \n\nWithout this flag, --check reports such files as `byte order mark only`."
+        )]
+        ignore_bom: bool,
+
         /// Format only a specific line range (1-indexed, inclusive)
         #[arg(long, value_name = "START:END")]
         #[arg(help = "Format only lines START:END (e.g., --range 5:10) [Experimental]")]
@@ -216,6 +414,21 @@ pub enum Commands {
         )]
         range: Option<String>,
 
+        /// Format Markdown embedded in source-code comments or docstrings
+        #[arg(long, value_enum, value_name = "KIND", conflicts_with = "range")]
+        #[arg(help = "Format Markdown in roxygen comments or Python docstrings")]
+        #[arg(
+            long_help = "Treat the inputs as source code and format only the Markdown embedded \
+            in it, leaving the code untouched. `roxygen` formats the description and prose \
+            tags (`@details`, `@return`, `@section`, ...) of `#'` roxygen2 blocks in R files; \
+            other tags such as `@param` and `@examples` are kept as written. `docstring` \
+            formats multi-line module, function, and class docstrings in Python files. \
+            \n\nEach region is wrapped to the line width minus its comment prefix or \
+            indentation. Directory walks select `*.R` or `*.py` files unless --include is \
+            given, and the Markdown is parsed as CommonMark unless --flavor is given."
+        )]
+        embedded: Option<CliEmbedded>,
+
         /// Enforce exclude patterns even for explicitly provided files
         #[arg(long)]
         #[arg(help = "Apply exclude patterns to explicitly provided files")]
@@ -231,6 +444,143 @@ pub enum Commands {
         )]
         force_exclude: bool,
 
+        /// Format the files staged in git
+        #[arg(long, conflicts_with_all = ["files", "range"])]
+        #[arg(help = "Format only the files staged in git")]
+        #[arg(
+            long_help = "Format the added, copied, modified, and renamed files in the git index \
+            (`git diff --cached --name-only`) instead of taking paths as arguments. The files \
+            are filtered like a directory walk: only those matching `include` and not matching \
+            `exclude` are formatted, so a pre-commit hook can run `panache format --staged` \
+            without listing files. Nothing is formatted, and the exit code is 0, when no \
+            matching file is staged.\
+            \n\nThe working-tree copy of each file is formatted. Combine with --restage to add \
+            the results back to the index."
+        )]
+        staged: bool,
+
+        /// Stage files again after formatting them
+        #[arg(long, requires = "staged", conflicts_with_all = ["check", "stdout", "list_different"])]
+        #[arg(help = "With --staged, `git add` the files that were reformatted")]
+        #[arg(
+            long_help = "With --staged, run `git add` on every file that formatting changed so \
+            the commit picks up the formatted version. A file that also had unstaged changes \
+            is not restaged, since `git add` would stage those changes too; Panache warns \
+            about it and leaves the file for you to add."
+        )]
+        restage: bool,
+
+        /// Format only the lines changed in git
+        #[arg(long, conflicts_with_all = ["files", "range", "staged", "embedded"])]
+        #[arg(help = "Format only the blocks changed since the last commit (or --since)")]
+        #[arg(
+            long_help = "Format only the blocks touching lines that differ from HEAD, as \
+            reported by `git diff -U0 HEAD` (staged and unstaged changes alike), and leave the \
+            rest of each file as written, so adopting panache in an existing repository does \
+            not reformat code nobody touched. Use --since to compare against another revision. Each hunk is expanded to complete blocks, so editing \
+            one line of a list formats the whole list. The changed files are filtered like a \
+            directory walk, and untracked files are not included. Nothing is formatted, and \
+            the exit code is 0, when no matching file has changes."
+        )]
+        changed: bool,
+
+        /// With --changed, compare against REF instead of HEAD
+        #[arg(long, value_name = "REF", requires = "changed")]
+        #[arg(help = "With --changed, format lines changed since REF instead of HEAD")]
+        #[arg(
+            long_help = "With --changed, format the lines that differ between REF and the \
+            working tree, so everything touched on a branch is covered, committed or not. \
+            REF is any git revision, e.g. `main` or `HEAD~3`; use \
+            `--since $(git merge-base main HEAD)` to ignore changes made on `main` since the \
+            branch was created."
+        )]
+        since: Option<String>,
+
+        /// Read paths to format from a file
+        #[arg(long, value_name = "PATH", conflicts_with_all = ["staged", "changed"])]
+        #[arg(help = "Read newline-separated paths from PATH (use `-` for stdin)")]
+        #[arg(
+            long_help = "Read the paths to format from PATH, one per line, in addition to any \
+            given as arguments. `--files-from -` reads them from stdin, so `git ls-files` or \
+            `find` output can be piped in without running into argument length limits. Blank \
+            lines are skipped. Nothing is formatted, and the exit code is 0, when the list is \
+            empty."
+        )]
+        files_from: Option<PathBuf>,
+
+        /// Only traverse files matching this glob
+        #[arg(long, value_name = "GLOB")]
+        #[arg(help = "Only traverse files matching GLOB (may be repeated)")]
+        #[arg(
+            long_help = "When traversing directories, only process files matching GLOB. May be \
+            repeated. Replaces the `include` and `extend-include` patterns from your \
+            configuration for this run, so `--include 'docs/**/*.qmd'` narrows a run to the \
+            Quarto files under docs/. \
+            \n\nPatterns use the same gitignore-style syntax as the config keys and are \
+            matched relative to the config file's directory. Explicitly named files are not \
+            filtered."
+        )]
+        include: Vec<String>,
+
+        /// Skip files matching this glob
+        #[arg(long, value_name = "GLOB")]
+        #[arg(help = "Skip files matching GLOB (may be repeated)")]
+        #[arg(
+            long_help = "When traversing directories, skip files matching GLOB. May be repeated. \
+            The patterns are added to the configured `exclude` and `extend-exclude` patterns, \
+            so the defaults (`node_modules/`, `target/`, ...) still apply. \
+            \n\nPatterns use the same gitignore-style syntax as the config keys, for example \
+            `--exclude vendor/`. Explicitly named files are only filtered with --force-exclude."
+        )]
+        exclude: Vec<String>,
+
+        /// Leave blocks unformatted instead of aborting when formatting panics
+        #[arg(long)]
+        #[arg(help = "Leave a block unformatted (with a warning) if formatting it crashes")]
+        #[arg(
+            long_help = "Format each top-level block inside a panic boundary. If formatting \
+            a block hits an internal error, that block is written out exactly as it was and a \
+            warning names its line, instead of aborting the whole file or directory run. A \
+            document that fails to parse is left unchanged with a warning. Useful for large \
+            batch runs; please report the warnings as bugs."
+        )]
+        fail_safe: bool,
+
+        /// Never let a single word or link overflow the line width
+        #[arg(long)]
+        #[arg(help = "Split long words and move long link destinations to references")]
+        #[arg(
+            long_help = "Enable `max-width-strict` for this invocation. When reflowing, a word \
+            wider than the line is split across lines (the break renders as a space), and an \
+            inline link whose destination cannot fit becomes a numbered reference link with its \
+            definition at the end of the document. URLs, code spans, and reference definitions \
+            are never split and may still overflow."
+        )]
+        max_width_strict: bool,
+
+        /// Print the time spent in each formatting phase per file
+        #[arg(long)]
+        #[arg(help = "Print a per-file summary of time spent in each formatting phase")]
+        #[arg(
+            long_help = "After formatting each file, print to stderr how long it spent in \
+            block parsing (with the inline parsing inside it), external code formatters, and \
+            formatting. Cache hits report no time. Use it to find which phase makes a slow \
+            file slow; for finer detail, the same phases are `tracing` spans."
+        )]
+        timings: bool,
+
+        /// Write a machine-readable report of the run
+        #[arg(long, value_name = "PATH")]
+        #[arg(help = "Write a per-file JSON or Markdown report of the run to PATH")]
+        #[arg(
+            long_help = "Write a report of the run to PATH for CI dashboards: Markdown when \
+            PATH ends in `.md` or `.markdown`, JSON otherwise. It lists every file with its \
+            status (`formatted` when formatting changes it, or with --check would; \
+            `unchanged`; or `error`), the number of diff hunks, and the time spent on it, \
+            followed by totals."
+        )]
+        report: Option<PathBuf>,
+
         /// Override individual config options (repeatable)
         #[arg(short = 'o', long = "option", value_name = "KEY=VALUE")]
         #[arg(
@@ -239,7 +589,7 @@ pub enum Commands {
         #[arg(
             long_help = "Override an individual config option for this invocation, using the \
             kebab-case key names from panache.toml. May be repeated. Takes precedence over \
-            both panache.toml and the built-in defaults. \
+            panache.toml, a document's `panache` frontmatter key, and the built-in defaults. \
             \n\nSupported keys: \
             `line-width` (positive integer, measured in Unicode display columns, where \
             East Asian wide characters count as 2: `x宮` is 3 columns, not 2 characters \
@@ -289,8 +639,10 @@ pub enum Commands {
         #[arg(
             long_help = "Write the parsed CST to the given JSON file in addition to \
             printing the selected --to format to stdout. The JSON output is always \
-            CST-shaped regardless of --to; it includes node kinds, text ranges, and \
-            token text."
+            CST-shaped regardless of --to; it includes node kinds, token text, and \
+            for every node and token a stable pre-order `id`, its byte `range`, and a \
+            zero-based line/column `position` with both UTF-8 (`column`) and UTF-16 \
+            (`column_utf16`) columns."
         )]
         json: Option<PathBuf>,
     },
@@ -331,7 +683,7 @@ For editor configuration examples, see: https://github.com/jolars/panache#editor
             the single argument `-` is given, reads from stdin. \
             Supports .qmd, .md, .Rmd/.Rmarkdown, and other Markdown-based formats. Supports glob patterns \
             (e.g., *.md) and directories (e.g., . or docs/). Directories are traversed recursively, \
-            respecting .gitignore files. `-` cannot be combined with other paths."
+            respecting .gitignore and .panacheignore files. `-` cannot be combined with other paths."
         )]
         files: Vec<PathBuf>,
 
@@ -382,6 +734,91 @@ For editor configuration examples, see: https://github.com/jolars/panache#editor
             configuration."
         )]
         force_exclude: bool,
+
+        /// Only traverse files matching this glob
+        #[arg(long, value_name = "GLOB")]
+        #[arg(help = "Only traverse files matching GLOB (may be repeated)")]
+        #[arg(
+            long_help = "When traversing directories, only process files matching GLOB. May be \
+            repeated. Replaces the `include` and `extend-include` patterns from your \
+            configuration for this run, so `--include 'docs/**/*.qmd'` narrows a run to the \
+            Quarto files under docs/. \
+            \n\nPatterns use the same gitignore-style syntax as the config keys and are \
+            matched relative to the config file's directory. Explicitly named files are not \
+            filtered."
+        )]
+        include: Vec<String>,
+
+        /// Skip files matching this glob
+        #[arg(long, value_name = "GLOB")]
+        #[arg(help = "Skip files matching GLOB (may be repeated)")]
+        #[arg(
+            long_help = "When traversing directories, skip files matching GLOB. May be repeated. \
+            The patterns are added to the configured `exclude` and `extend-exclude` patterns, \
+            so the defaults (`node_modules/`, `target/`, ...) still apply. \
+            \n\nPatterns use the same gitignore-style syntax as the config keys, for example \
+            `--exclude vendor/`. Explicitly named files are only filtered with --force-exclude."
+        )]
+        exclude: Vec<String>,
+    },
+    /// Check that links in documents resolve
+    #[command(
+        long_about = "Check the links in documents: relative file paths must exist, and \
+        `#fragment` destinations must name a heading or anchor in the target document (or, for \
+        `#fragment` on its own, in the linking one). With --online, external http(s) URLs are \
+        requested as well and must answer with a status below 400. Broken links are reported \
+        as `broken-link` and `broken-url` diagnostics, and the command exits with code 1 when \
+        any are found, for use as a CI docs check."
+    )]
+    #[command(after_help = "Example: `panache check-links --online docs/`")]
+    CheckLinks {
+        /// Input file(s) or directories (stdin if not provided, or pass `-`)
+        #[arg(help = "Input file path(s) or directories (use `-` for stdin)")]
+        #[arg(
+            long_help = "Path(s) to the input file(s) or directories to check. If not provided, \
+            or if the single argument `-` is given, reads from stdin and resolves relative links \
+            against --stdin-filename (or the current directory). Directories are traversed \
+            recursively, respecting .gitignore and .panacheignore files and the configured \
+            exclude patterns."
+        )]
+        files: Vec<PathBuf>,
+
+        /// Also request external URLs
+        #[arg(long)]
+        #[arg(help = "Also check external http(s) URLs over the network")]
+        #[arg(
+            long_help = "Request every external http(s) URL once (duplicates across documents are \
+            checked once), trying HEAD and falling back to GET for servers that refuse it. \
+            Redirects are followed; a final status of 400 or above, or no answer within --timeout, \
+            is reported as `broken-url`. URLs that loaded are remembered in the cache for a day, \
+            unless --no-cache is given."
+        )]
+        online: bool,
+
+        /// Number of URLs requested at once
+        #[arg(long, value_name = "N", default_value_t = 8, requires = "online")]
+        #[arg(help = "Number of URLs requested at once with --online")]
+        concurrency: usize,
+
+        /// Seconds to wait for each URL
+        #[arg(
+            long,
+            value_name = "SECONDS",
+            default_value_t = 10,
+            requires = "online"
+        )]
+        #[arg(help = "Seconds to wait for each URL with --online")]
+        timeout: u64,
+
+        /// Diagnostic rendering format
+        #[arg(long, value_enum, default_value = "human")]
+        #[arg(help = "Diagnostic rendering format")]
+        message_format: MessageFormat,
+
+        /// Emit JSON output for machine-readable tooling
+        #[arg(long, conflicts_with = "message_format")]
+        #[arg(help = "Print a JSON array with the diagnostics of each file")]
+        json: bool,
     },
     /// Delete cache data
     #[command(long_about = "Delete Panache's on-disk cache data.")]
@@ -402,6 +839,196 @@ For editor configuration examples, see: https://github.com/jolars/panache#editor
         )]
         dry_run: bool,
     },
+    /// Approve a project config's external formatters and linters
+    #[command(
+        long_about = "Approve the external formatters and linters configured by a project \
+        config so they run in safe mode. The config is resolved the same way `panache format` \
+        resolves it for PATH. Approval is tied to the configured commands: editing them \
+        requires running `panache trust` again."
+    )]
+    #[command(after_help = "\
+Approvals are stored in trusted-configs.json under $XDG_STATE_HOME/panache \
+(~/.local/state/panache by default, or $PANACHE_STATE_HOME/panache when set).")]
+    Trust {
+        /// File or directory whose config should be trusted
+        #[arg(
+            help = "File or directory whose config should be trusted (default: current directory)"
+        )]
+        path: Option<PathBuf>,
+
+        /// Withdraw a previous approval
+        #[arg(long)]
+        #[arg(help = "Withdraw a previous approval instead of granting one")]
+        revoke: bool,
+    },
+    /// Move a document and update links pointing to it
+    #[command(
+        long_about = "Rename or move a document and rewrite the relative links, images, \
+        include/embed shortcodes, frontmatter paths, and `_quarto.yml` entries that point to \
+        it across the workspace. Relative links inside the moved document are rebased onto its \
+        new location."
+    )]
+    #[command(after_help = "\
+The workspace is the Quarto or bookdown project containing OLD, or the current directory \
+otherwise. This is the same rewrite the language server performs when an editor renames a file.")]
+    Mv {
+        /// Existing document path
+        #[arg(help = "Path of the document to move")]
+        old: PathBuf,
+
+        /// Destination path
+        #[arg(help = "Destination path for the document")]
+        new: PathBuf,
+
+        /// Show the planned edits without changing anything
+        #[arg(long)]
+        #[arg(help = "Show the planned rename and link edits without changing anything")]
+        #[arg(
+            long_help = "Print the rename and every link rewrite that would be applied, as \
+            `path:line:column: old -> new`, but do not modify anything on disk."
+        )]
+        dry_run: bool,
+    },
+    /// Extract code blocks of one language from documents
+    #[command(
+        long_about = "Extract the fenced code blocks of one language into a single stream on \
+        stdout or into one file per block, so external tools can run over code embedded in a \
+        document. Blocks match through the same language aliases as formatters and linters, \
+        so `--lang python` also picks up `py` and `{python}` fences. Chunk labels are kept as \
+        `# ---- label ----` comments above each block."
+    )]
+    #[command(after_help = "\
+Example: `panache extract --lang r analysis.qmd > analysis.R`")]
+    Extract {
+        /// Input file(s) (stdin if not provided, or pass `-`)
+        #[arg(help = "Input file path(s) (use `-` for stdin)")]
+        files: Vec<PathBuf>,
+
+        /// Language of the blocks to extract
+        #[arg(long, value_name = "LANG")]
+        #[arg(help = "Language of the code blocks to extract (e.g. r, python)")]
+        lang: String,
+
+        /// Write each block to its own file in DIR
+        #[arg(long, value_name = "DIR")]
+        #[arg(help = "Write each block to its own file in DIR instead of stdout")]
+        #[arg(
+            long_help = "Write each block to its own file in DIR (created if missing) \
+            instead of printing them to stdout. Files are named after the document and the \
+            block's position, with an extension for the language, e.g. `analysis-1.r`, \
+            `analysis-2.r`."
+        )]
+        out_dir: Option<PathBuf>,
+
+        /// Write a JSON source map to PATH
+        #[arg(long, value_name = "PATH")]
+        #[arg(help = "Write a JSON source map from output lines to document lines")]
+        #[arg(
+            long_help = "Write a JSON array to PATH with one entry per extracted block: the \
+            `output` file (`-` for stdout) and the `output_line` its code starts on, the \
+            `source` document and its `source_line`, the number of `lines`, and the `label`. \
+            Use it to map diagnostics from tools run over the extracted code back to the \
+            document."
+        )]
+        source_map: Option<PathBuf>,
+    },
+    /// Count words, headings, code cells, and links in documents
+    #[command(
+        long_about = "Print document metrics: words of prose, headings by level, code cells by \
+        language, links, images, footnotes, and the longest line. Words exclude code, math, \
+        raw HTML and TeX, frontmatter, comments, and link destinations. With several files, \
+        the table ends with their totals."
+    )]
+    #[command(after_help = "\
+Example: `panache stats --output-format json chapters/*.qmd`")]
+    Stats {
+        /// Input file(s) (stdin if not provided, or pass `-`)
+        #[arg(help = "Input file path(s) (use `-` for stdin)")]
+        files: Vec<PathBuf>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "table")]
+        #[arg(help = "Output format: a table, or a JSON array with one object per file")]
+        output_format: StatsFormat,
+    },
+    /// Render documents in another format
+    #[command(
+        long_about = "Render documents in another format and print them to stdout. `--to plain` \
+        strips all markup and leaves readable text for search indexing and word counting: \
+        links read `text (url)`, images their alt text, tables are laid out in aligned \
+        columns, and frontmatter, comments, raw HTML and TeX, and shortcodes are dropped. \
+        With several files, their outputs are separated by a blank line."
+    )]
+    #[command(after_help = "\
+Example: `panache render --to plain README.md | wc -w`")]
+    Render {
+        /// Input file(s) (stdin if not provided, or pass `-`)
+        #[arg(help = "Input file path(s) (use `-` for stdin)")]
+        files: Vec<PathBuf>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "plain", value_name = "FORMAT")]
+        #[arg(help = "Output format")]
+        to: RenderFormat,
+    },
+    /// Sort sections of documents
+    #[command(
+        long_about = "Rearrange the sections of documents. `--sort-headings-under` sorts the \
+        subsections directly under a heading alphabetically, each keeping the blocks and \
+        deeper subsections below it, which keeps FAQs, glossaries, and changelogs in order. \
+        Files are rewritten in place; standard input is written to standard output."
+    )]
+    #[command(after_help = "\
+Example: `panache organize --sort-headings-under \"## FAQ\" README.md`
+
+Headings default to the `sort-headings-under` list in the `[organize]` config table.")]
+    Organize {
+        /// Input file(s) (stdin if not provided, or pass `-`)
+        #[arg(help = "Input file path(s) (use `-` for stdin)")]
+        files: Vec<PathBuf>,
+
+        /// Heading whose subsections are sorted
+        #[arg(long = "sort-headings-under", value_name = "HEADING")]
+        #[arg(help = "Sort the subsections under HEADING alphabetically (repeatable)")]
+        #[arg(
+            long_help = "Sort the subsections directly under HEADING alphabetically by title, \
+            ignoring case. Write `## FAQ` to match only a level-2 heading, or `FAQ` to match \
+            one of any level. Text between HEADING and its first subsection stays in place. \
+            Only top-level headings are considered. Overrides `[organize] \
+            sort-headings-under`."
+        )]
+        sort_headings_under: Vec<String>,
+
+        /// Check whether files are organized without changing them
+        #[arg(long)]
+        #[arg(help = "Exit with code 1 if any file would change, without writing it")]
+        check: bool,
+    },
+    /// Re-wrap the text of a Pandoc JSON document, as a Pandoc filter
+    #[command(
+        long_about = "Read a Pandoc JSON document on stdin and write it to stdout with the line \
+        breaks in its paragraphs rewritten for the configured `wrap` mode and `line-width`, so \
+        panache can take part in a Pandoc pipeline. Only `Space` and `SoftBreak` nodes change; \
+        every other node, including ones panache does not know, passes through untouched. \
+        Run the final writer with `--wrap=preserve` to keep the breaks."
+    )]
+    #[command(after_help = "\
+Example: `pandoc -t json in.md | panache filter | pandoc -f json -t markdown --wrap=preserve`")]
+    Filter {
+        /// Output format passed by `pandoc --filter` (ignored)
+        #[arg(help = "Target format, as passed by `pandoc --filter` (ignored)")]
+        target_format: Option<String>,
+    },
+    /// Show, check, or create a config file
+    #[command(
+        long_about = "Inspect Panache's configuration. `config show` prints the settings a \
+        file would be formatted with, and where each one comes from; `config validate` checks \
+        a config file for errors; `config init` writes a commented starter `.panache.toml`."
+    )]
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
     /// Debug utilities for parser/formatter diagnostics
     #[command(
         long_about = "Debugging utilities for parse/format workflows. These commands are intended \
@@ -413,8 +1040,85 @@ For editor configuration examples, see: https://github.com/jolars/panache#editor
     },
 }
 
+#[derive(Subcommand)]
+pub enum ConfigCommands {
+    /// Print the resolved configuration with the source of each value
+    #[command(
+        long_about = "Print the configuration Panache resolves for FILE as TOML, after config \
+        discovery, `extend` chains, flavor detection, --flavor, and the document's `panache` \
+        frontmatter key. Each value is followed by a comment naming where it came from: a \
+        config file, the file's extension or [flavor-overrides], a command-line flag, the \
+        frontmatter, or the built-in default."
+    )]
+    Show {
+        /// Document to resolve the configuration for
+        #[arg(help = "Document to resolve the configuration for (default: current directory)")]
+        #[arg(
+            long_help = "Document to resolve the configuration for. Config discovery starts \
+            from its directory, its extension selects the flavor, and its frontmatter is \
+            applied. Without FILE, the configuration for the current directory is shown."
+        )]
+        file: Option<PathBuf>,
+    },
+    /// Check a config file for errors
+    #[command(
+        long_about = "Check a config file, and every file it extends, for syntax errors, \
+        unknown keys, and invalid values. Each problem is reported with its line and column \
+        and, for a misspelled key, the closest valid one. Exits with status 1 if the config \
+        is invalid."
+    )]
+    Validate {
+        /// Config file to check
+        #[arg(help = "Config file to check (default: the discovered config)")]
+        #[arg(
+            long_help = "Config file to check. Without CONFIG, the file given by --config is \
+            checked, or else the config Panache would discover from the current directory."
+        )]
+        config: Option<PathBuf>,
+    },
+    /// Write a commented default `.panache.toml`
+    #[command(
+        long_about = "Write a `.panache.toml` in the current directory listing the common \
+        options with their defaults, commented out, so the file changes nothing until you \
+        edit it."
+    )]
+    Init {
+        /// Overwrite an existing `.panache.toml`
+        #[arg(long)]
+        #[arg(help = "Overwrite an existing .panache.toml")]
+        force: bool,
+    },
+}
+
 #[derive(Subcommand)]
 pub enum DebugCommands {
+    /// Parse a document and check the CST for losslessness
+    #[command(name = "parse")]
+    #[command(
+        long_about = "Parse a document with the same config resolution as `panache parse`. \
+        With --check-lossless, verify that the CST text reproduces the input byte for byte \
+        and report where it first diverges; otherwise print the CST."
+    )]
+    Parse {
+        /// Input file (stdin if not provided, or pass `-`)
+        #[arg(help = "Input file path (use `-` for stdin)")]
+        file: Option<PathBuf>,
+
+        /// Verify the CST text exactly reconstructs the input
+        #[arg(long)]
+        #[arg(help = "Verify the CST text exactly reconstructs the input (exit 1 if not)")]
+        #[arg(
+            long_help = "Verify that concatenating the CST's text reproduces the input exactly. \
+            On a mismatch, print the line, column, and byte offset of the first divergence, \
+            the expected and actual text there, and the CST node path covering it, then exit \
+            with code 1."
+        )]
+        check_lossless: bool,
+
+        /// Emit JSON output for machine-readable tooling
+        #[arg(long, requires = "check_lossless")]
+        json: bool,
+    },
     /// Run parser+formatter checks and emit diagnostics
     #[command(name = "format")]
     Format {
@@ -458,6 +1162,59 @@ pub enum DebugCommands {
         )]
         force_exclude: bool,
     },
+    /// Run regression checks over a corpus of documents
+    #[command(name = "corpus")]
+    #[command(
+        long_about = "Run parser and formatter regression checks over every supported file in \
+        a directory or git repository, in parallel. A git URL is shallow-cloned into \
+        <OUT_DIR>/source first. Per-file logs, Markdown reports, and pass artifacts for \
+        failing files are written to <OUT_DIR>/logs, alongside a summary.json and a \
+        failures.tsv index. External formatters are never run. Exits with code 1 if any \
+        check fails."
+    )]
+    Corpus {
+        /// Directory or git URL to scan
+        #[arg(value_name = "SOURCE")]
+        #[arg(help = "Directory or git URL to scan")]
+        source: String,
+
+        /// Which checks to run
+        #[arg(
+            long,
+            value_enum,
+            value_delimiter = ',',
+            default_value = "idempotency,losslessness,panic"
+        )]
+        #[arg(help = "Comma-separated checks to run")]
+        checks: Vec<CorpusCheck>,
+
+        /// Directory where results are written
+        #[arg(long, value_name = "DIR", default_value = "panache-corpus")]
+        #[arg(help = "Directory for the clone, logs, reports, and summary")]
+        out_dir: PathBuf,
+
+        /// Dump intermediate check artifacts even when checks pass
+        #[arg(long)]
+        #[arg(help = "Write input/parse/format pass artifacts for every file")]
+        dump_passes: bool,
+
+        /// Emit JSON output for machine-readable tooling
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print the enabled extensions and how the formatter treats each construct
+    #[command(name = "capabilities")]
+    #[command(
+        long_about = "Print, as JSON, the panache version, the resolved flavor, every parser \
+        and formatter extension with whether it is enabled, and the syntax node kinds the \
+        formatter normalizes versus preserves verbatim. Config is resolved as for \
+        `panache format`, starting from FILE's directory (or --stdin-filename) when given."
+    )]
+    Capabilities {
+        /// Resolve config as if formatting this file
+        #[arg(help = "Resolve config as if formatting this file")]
+        file: Option<PathBuf>,
+    },
 }
 
 #[derive(Clone, Copy, Debug, ValueEnum)]
@@ -467,6 +1224,16 @@ pub enum DebugChecks {
     All,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum CorpusCheck {
+    /// Formatting twice gives the same output as formatting once
+    Idempotency,
+    /// The CST reproduces the input byte for byte
+    Losslessness,
+    /// Parsing and formatting do not panic
+    Panic,
+}
+
 #[derive(Clone, Copy, Debug, ValueEnum)]
 pub enum ParseOutput {
     Cst,
@@ -474,6 +1241,36 @@ pub enum ParseOutput {
     PandocJson,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum DiffFormat {
+    /// rustfmt-style hunks headed by `Diff in FILE:LINE:`
+    Inline,
+    /// A `diff -u` patch with `a/` and `b/` path prefixes
+    Unified,
+    /// One JSON document listing the changed hunks of every file
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum EditsFormat {
+    /// One JSON document listing the edits for every changed file
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum StatsFormat {
+    /// One block of counts per file, then totals
+    Table,
+    /// A JSON array with one object per file
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum RenderFormat {
+    /// Text without markup
+    Plain,
+}
+
 #[derive(Clone, Copy, Debug, ValueEnum)]
 pub enum ColorMode {
     Auto,
@@ -483,8 +1280,58 @@ pub enum ColorMode {
 
 #[derive(Clone, Copy, Debug, ValueEnum)]
 pub enum MessageFormat {
+    /// Source snippets with annotations, one block per diagnostic
     Human,
+    /// One `file:line:column: severity[code]: message` line per diagnostic
     Short,
+    /// Diagnostics grouped under each file, in aligned columns
+    Grouped,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum CliLineEnding {
+    /// The first line ending found in the input
+    Auto,
+    /// `\n`
+    Lf,
+    /// `\r\n`
+    Crlf,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum CliEmbedded {
+    /// roxygen2 `#'` comments in R files
+    Roxygen,
+    /// Python docstrings
+    Docstring,
+}
+
+/// Value parser for `--flavor`. The value is passed through as a string, since
+/// it may carry `+ext`/`-ext` modifiers that Panache validates itself, while
+/// the flavor names are still listed as possible values in help and
+/// completions.
+#[derive(Clone)]
+pub struct FlavorValueParser;
+
+impl TypedValueParser for FlavorValueParser {
+    type Value = String;
+
+    fn parse_ref(
+        &self,
+        cmd: &clap::Command,
+        arg: Option<&clap::Arg>,
+        value: &std::ffi::OsStr,
+    ) -> Result<String, clap::Error> {
+        StringValueParser::new().parse_ref(cmd, arg, value)
+    }
+
+    fn possible_values(&self) -> Option<Box<dyn Iterator<Item = PossibleValue> + '_>> {
+        Some(Box::new(
+            CliFlavor::value_variants()
+                .iter()
+                .filter_map(ValueEnum::to_possible_value),
+        ))
+    }
 }
 
 #[derive(Clone, Copy, Debug, ValueEnum)]
@@ -510,4 +1357,7 @@ pub enum CliFlavor {
     /// MyST (CommonMark + Sphinx/MyST directives, roles, and targets)
     #[value(name = "myst")]
     Myst,
+    /// Obsidian (GFM + wikilinks, embeds, callouts, and `%%` comments)
+    #[value(name = "obsidian")]
+    Obsidian,
 }
@@ -1,12 +1,15 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::env;
 use std::fmt;
 use std::fs;
 use std::io;
 use std::ops::Range;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 mod formatter_presets;
+mod overrides;
+mod sandbox;
 mod types;
 
 pub use formatter_presets::FormatterPresetMetadata;
@@ -15,23 +18,58 @@ pub use formatter_presets::formatter_preset_names;
 pub use formatter_presets::formatter_preset_supported_languages;
 pub use formatter_presets::formatter_presets_for_language;
 pub use formatter_presets::get_formatter_preset;
+pub use overrides::apply_format_option;
+pub(crate) use overrides::frontmatter_config;
+pub use overrides::frontmatter_options;
+pub use overrides::frontmatter_warnings;
+pub use overrides::set_command_line_options;
+pub use overrides::unknown_frontmatter_option;
 pub use panache_formatter::config::FormatterExtensions;
 pub use panache_parser::Extensions;
 pub use panache_parser::Flavor;
 pub use panache_parser::PandocCompat;
 pub use panache_parser::ParserOptions;
+pub use sandbox::{apply_sandbox_option, apply_sandbox_overrides, set_sandbox_overrides};
+pub use types::AttributeStyle;
+pub use types::BareUrls;
 pub use types::BlankLines;
+pub use types::BlankLinesAround;
+pub use types::BlockquoteStyle;
+pub use types::BreakableInline;
+pub use types::CodeBlockStyle;
 pub use types::Config;
 pub use types::ConfigBuilder;
+pub use types::DefinitionListStyle;
+pub use types::DefinitionMarker;
+pub use types::DivFenceLength;
+pub use types::DivStyle;
+pub use types::EmphasisMarker;
+pub use types::Escaping;
+pub use types::ExperimentalConfig;
+pub use types::ExternalSandboxConfig;
+pub use types::FinalNewline;
 pub use types::FormatterConfig;
 pub use types::FormatterDefinition;
 pub use types::FormatterValue;
+pub use types::HeadingCase;
+pub use types::HeadingCaseOptions;
+pub use types::HeadingHierarchyOptions;
+pub use types::HeadingLengthOptions;
+pub use types::HeadingNumbering;
+pub use types::HeadingPunctuationOptions;
+pub use types::HeadingStyle;
 pub use types::HorizontalRuleStyle;
 pub use types::LineEnding;
+pub use types::LinkDestinations;
 pub use types::LintConfig;
 pub use types::MathDelimiterStyle;
 pub use types::NoBreakAbbreviations;
+pub use types::OrganizeConfig;
+pub use types::SpellChecker;
+pub use types::SpellingOptions;
 pub use types::TabStopMode;
+pub use types::TocStyle;
+pub use types::UndefinedReferencesOptions;
 pub use types::WrapMode;
 
 // Globset forms (the engine `GlobMatcher` is built on): `**/<dir>/**` excludes
@@ -57,10 +95,7 @@ pub const DEFAULT_EXCLUDE_PATTERNS: &[&str] = &[
     "**/.tox/**",
     "**/.venv/**",
     "**/.vscode/**",
-    "**/_book/**",
     "**/_build/**",
-    "**/_freeze/**",
-    "**/_site/**",
     "**/build/**",
     "**/dist/**",
     "**/node_modules/**",
@@ -70,6 +105,36 @@ pub const DEFAULT_EXCLUDE_PATTERNS: &[&str] = &[
     "**/LICENSE.md",
 ];
 
+/// Output directories written by Quarto, bookdown, and similar renderers.
+/// Excluded in addition to `exclude`/`extend-exclude` unless
+/// `exclude-generated = false`, so `panache format .` at a project root never
+/// churns rendered artifacts.
+pub const GENERATED_EXCLUDE_PATTERNS: &[&str] = &[
+    "**/.quarto/**",
+    "**/_book/**",
+    "**/_bookdown_files/**",
+    "**/_freeze/**",
+    "**/_manuscript/**",
+    "**/_site/**",
+];
+
+/// The exclude globs in effect for `cfg`: the `exclude` base (or
+/// [`DEFAULT_EXCLUDE_PATTERNS`]), then `extend-exclude`, then
+/// [`GENERATED_EXCLUDE_PATTERNS`] unless `exclude-generated` is off.
+pub fn effective_exclude_patterns(cfg: &Config) -> Vec<String> {
+    let mut patterns = cfg.exclude.clone().unwrap_or_else(|| {
+        DEFAULT_EXCLUDE_PATTERNS
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    });
+    patterns.extend(cfg.extend_exclude.iter().cloned());
+    if cfg.exclude_generated {
+        patterns.extend(GENERATED_EXCLUDE_PATTERNS.iter().map(|s| s.to_string()));
+    }
+    patterns
+}
+
 pub const DEFAULT_INCLUDE_PATTERNS: &[&str] = &[
     "**/*.md",
     "**/*.qmd",
@@ -83,10 +148,11 @@ pub const DEFAULT_INCLUDE_PATTERNS: &[&str] = &[
     // `.svelte.md` is already covered by the `**/*.md` glob above; only the bare
     // `.svx` extension needs its own pattern.
     "**/*.svx",
+    "**/*.mdx",
 ];
 
 const CANDIDATE_NAMES: &[&str] = &[".panache.toml", "panache.toml"];
-const MARKDOWN_FAMILY_EXTENSIONS: &[&str] = &["md", "markdown", "mdown", "mkd"];
+const MARKDOWN_FAMILY_EXTENSIONS: &[&str] = &["md", "markdown", "mdown", "mkd", "mdx"];
 
 fn check_deprecated_blank_lines(s: &str, path: &Path) {
     let Ok(toml_value) = toml::from_str::<toml::Value>(s) else {
@@ -96,31 +162,17 @@ fn check_deprecated_blank_lines(s: &str, path: &Path) {
         return;
     };
 
-    fn has_blank_lines(table: &toml::map::Map<String, toml::Value>) -> bool {
-        table.contains_key("blank-lines") || table.contains_key("blank_lines")
-    }
-
-    let top_level = has_blank_lines(root);
-    let format_nested = root
-        .get("format")
-        .and_then(|v| v.as_table())
-        .is_some_and(has_blank_lines);
-
-    // `[style] blank-lines` is deliberately not covered: the whole `[style]`
-    // section was removed in 3.0, so the config errors out with a migration
-    // hint before a "no-op" warning could be anything but misleading.
-    if top_level || format_nested {
+    // Only the top-level key is deprecated; `[format] blank-lines` is the
+    // supported spelling. `[style] blank-lines` is not covered: the whole
+    // `[style]` section was removed in 3.0, so the config errors out with a
+    // migration hint first.
+    if root.contains_key("blank-lines") || root.contains_key("blank_lines") {
         eprintln!(
-            "Warning: Deprecated `blank-lines` setting found in {}:",
+            "Warning: Deprecated top-level `blank-lines` setting found in {}.",
             path.display()
         );
-        if format_nested {
-            eprintln!("  - [format] blank-lines");
-        }
-        if top_level {
-            eprintln!("  - blank-lines (top-level)");
-        }
-        eprintln!("  This option is now a no-op and will be removed in a future release.");
+        eprintln!("  Use `[format] blank-lines` instead; the top-level key is ignored");
+        eprintln!("  when a `[format]` section is present.");
     }
 }
 
@@ -182,29 +234,35 @@ fn parse_config_str(s: &str, path: &Path) -> io::Result<Config> {
 /// existing `io::Result` callers.
 fn parse_config_detailed(s: &str, path: &Path) -> Result<Config, ConfigError> {
     check_deprecated_blank_lines(s, path);
+    validate_extension_names(s, path)?;
 
-    if let Err(msg) = validate_extension_names(s) {
-        return Err(ConfigError {
-            path: path.to_path_buf(),
-            span: None,
-            message: msg,
-        });
+    if let Ok(mut table) = toml::from_str::<toml::Table>(s)
+        && expand_flavor_spec(&mut table)
+            .map_err(|message| located_error(s, path, "flavor", message))?
+    {
+        return finalize_merged_table(&table, path);
     }
 
-    toml::from_str(s).map_err(|e| {
-        let mut message = e.to_string();
-        if let Some(hint) = removed_surface_hint(&message) {
-            message.push('\n');
-            message.push_str(hint);
-        }
-        ConfigError {
-            path: path.to_path_buf(),
-            span: e.span(),
-            message,
-        }
+    toml::from_str(s).map_err(|e| ConfigError {
+        path: path.to_path_buf(),
+        span: e.span(),
+        message: with_hint(e.to_string()),
     })
 }
 
+/// Append a migration hint or a "did you mean" suggestion to a deserialization
+/// error, when one applies.
+fn with_hint(mut message: String) -> String {
+    let hint = removed_surface_hint(&message)
+        .map(str::to_string)
+        .or_else(|| unknown_field_suggestion(&message));
+    if let Some(hint) = hint {
+        message.push('\n');
+        message.push_str(&hint);
+    }
+    message
+}
+
 /// Migration hint for config surface removed in 3.0, matched against the
 /// `unknown field` text of `toml`'s error. Configs from older releases (e.g.
 /// a `[style]` section) otherwise fail with a bare serde error that gives no
@@ -238,7 +296,8 @@ fn removed_surface_hint(message: &str) -> Option<&'static str> {
         ),
         (
             "code-blocks",
-            "hint: the `code-blocks` table was a no-op and was removed in 3.0; delete it",
+            "hint: the top-level `code-blocks` table was removed in 3.0; \
+             use `[format.code-blocks]`",
         ),
     ];
     HINTS
@@ -247,6 +306,17 @@ fn removed_surface_hint(message: &str) -> Option<&'static str> {
         .map(|(_, hint)| *hint)
 }
 
+/// Suggest the closest valid key for serde's unknown-field error, which lists
+/// every key the table accepts after `expected`.
+fn unknown_field_suggestion(message: &str) -> Option<String> {
+    const PREFIX: &str = "unknown field `";
+    let rest = &message[message.find(PREFIX)? + PREFIX.len()..];
+    let (field, rest) = rest.split_once('`')?;
+    let expected = rest.strip_prefix(", expected ")?.lines().next()?;
+    let candidates: Vec<&str> = expected.split('`').skip(1).step_by(2).collect();
+    closest_match(field, &candidates).map(|key| format!("hint: did you mean `{key}`?"))
+}
+
 /// True if `name` is a known extension at either the parser or formatter
 /// layer (since users write both under a single `[extensions]` table).
 fn is_known_extension_name(name: &str) -> bool {
@@ -280,6 +350,7 @@ const KNOWN_FLAVOR_KEYS: &[&str] = &[
     "multi-markdown",
     "mdsvex",
     "myst",
+    "obsidian",
 ];
 
 /// Suggest the closest valid name from `candidates` for an unknown `input`
@@ -313,8 +384,8 @@ fn closest_match<'a>(input: &str, candidates: &[&'a str]) -> Option<&'a str> {
 
 /// Walk the raw TOML `[extensions]` table and reject unknown extension
 /// names (both at the top level and inside per-flavor subtables) and unknown
-/// per-flavor subtable keys. Returns the user-facing error text on failure.
-fn validate_extension_names(s: &str) -> Result<(), String> {
+/// per-flavor subtable keys. The error points at the offending key in `s`.
+fn validate_extension_names(s: &str, path: &Path) -> Result<(), ConfigError> {
     let Ok(value) = toml::from_str::<toml::Value>(s) else {
         // Real TOML parse error — serde will surface it.
         return Ok(());
@@ -334,16 +405,19 @@ fn validate_extension_names(s: &str) -> Result<(), String> {
         match val {
             toml::Value::Boolean(_) => {
                 if !is_known_extension_name(key) {
-                    return Err(unknown_extension_error(key, &known_exts, None));
+                    let message = unknown_extension_error(key, &known_exts, None);
+                    return Err(located_error(s, path, key, message));
                 }
             }
             toml::Value::Table(flavor_table) => {
                 if parse_flavor_key(key).is_none() {
-                    return Err(unknown_flavor_subtable_error(key));
+                    let message = unknown_flavor_subtable_error(key);
+                    return Err(located_error(s, path, key, message));
                 }
                 for sub_key in flavor_table.keys() {
                     if !is_known_extension_name(sub_key) {
-                        return Err(unknown_extension_error(sub_key, &known_exts, Some(key)));
+                        let message = unknown_extension_error(sub_key, &known_exts, Some(key));
+                        return Err(located_error(s, path, sub_key, message));
                     }
                 }
             }
@@ -357,6 +431,20 @@ fn validate_extension_names(s: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Reject `name` unless it is a known `[extensions]` name, suggesting the
+/// closest one. For extension names given outside a config file, such as on
+/// the command line.
+pub fn check_extension_name(name: &str) -> Result<(), String> {
+    if is_known_extension_name(name) {
+        return Ok(());
+    }
+    let mut msg = format!("unknown extension `{name}`");
+    if let Some(suggestion) = closest_match(name, &all_known_extension_names()) {
+        msg.push_str(&format!("; did you mean `{suggestion}`?"));
+    }
+    Err(msg)
+}
+
 fn unknown_extension_error(name: &str, known: &[&str], in_flavor: Option<&str>) -> String {
     let mut msg = match in_flavor {
         Some(f) => format!("unknown extension `{name}` in [extensions.{f}]"),
@@ -368,6 +456,64 @@ fn unknown_extension_error(name: &str, known: &[&str], in_flavor: Option<&str>)
     msg
 }
 
+/// A [`ConfigError`] for `key`, found by walking the raw table of `source`.
+///
+/// A raw `toml::Value` carries no spans, so the key is looked up in the text
+/// and the message gets the same source excerpt `toml` renders for its own
+/// errors. Falls back to the bare message if the key cannot be found.
+fn located_error(source: &str, path: &Path, key: &str, message: String) -> ConfigError {
+    let span = key_span(source, key);
+    let message = match &span {
+        Some(span) => located_message(source, span, &message),
+        None => message,
+    };
+    ConfigError {
+        path: path.to_path_buf(),
+        span,
+        message,
+    }
+}
+
+/// Byte span of the first place `key` is written as a TOML key: bare or
+/// quoted, at the start of a line or after the `.` of a dotted key or table
+/// header, and followed by `=`, `.`, or `]`.
+fn key_span(source: &str, key: &str) -> Option<Range<usize>> {
+    source
+        .match_indices(key)
+        .map(|(start, _)| start..start + key.len())
+        .find(|span| {
+            let before = source[..span.start].trim_end_matches('"');
+            let line = &before[before.rfind('\n').map_or(0, |i| i + 1)..];
+            let after = source[span.end..]
+                .trim_start_matches('"')
+                .trim_start_matches([' ', '\t']);
+            (line.trim().is_empty() || before.ends_with(['.', '[']))
+                && after.starts_with(['=', '.', ']'])
+        })
+}
+
+/// `message` under an excerpt of the line containing `span`, laid out like
+/// `toml`'s parse errors.
+fn located_message(source: &str, span: &Range<usize>, message: &str) -> String {
+    let line_start = source[..span.start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = source[span.start..]
+        .find('\n')
+        .map_or(source.len(), |i| span.start + i);
+    let line_number = source[..span.start].matches('\n').count() + 1;
+    let column = source[line_start..span.start].chars().count() + 1;
+    let gutter = " ".repeat(line_number.to_string().len());
+    let line = source[line_start..line_end].trim_end_matches('\r');
+    let marker = "^".repeat(source[span.clone()].chars().count().max(1));
+    format!(
+        "TOML parse error at line {line_number}, column {column}\n\
+         {gutter} |\n\
+         {line_number} | {line}\n\
+         {gutter} | {indent}{marker}\n\
+         {message}\n",
+        indent = " ".repeat(column - 1),
+    )
+}
+
 fn unknown_flavor_subtable_error(name: &str) -> String {
     let mut msg = format!("unknown flavor subtable [extensions.{name}]");
     if let Some(suggestion) = closest_match(name, KNOWN_FLAVOR_KEYS) {
@@ -386,9 +532,11 @@ fn unknown_flavor_subtable_error(name: &str) -> String {
 /// the original string, preserving byte-accurate error spans. Only configs that
 /// actually declare `extend` pay for the raw-table merge (which loses spans,
 /// since a merged table has no single source file to point into).
-fn read_config_with_chain(
-    path: &Path,
-) -> Result<(Config, Option<toml::Value>, Vec<PathBuf>), ConfigError> {
+/// Read the config at `path` with its `extend` chain. Also returns the raw
+/// merged TOML table, for the keys the resolved [`Config`] cannot tell apart
+/// from their defaults (whether `flavor` was set, the unresolved
+/// `[extensions]`).
+fn read_config_with_chain(path: &Path) -> Result<(Config, toml::Table, Vec<PathBuf>), ConfigError> {
     log::debug!("Reading config from: {}", path.display());
     let s = fs::read_to_string(path).map_err(|e| ConfigError {
         path: path.to_path_buf(),
@@ -401,21 +549,22 @@ fn read_config_with_chain(
 
     if !has_extend {
         let config = parse_config_detailed(&s, path)?;
-        let extensions = table.and_then(|t| t.get("extensions").cloned());
+        let mut table = table.unwrap_or_default();
+        // Already validated by `parse_config_detailed`.
+        let _ = expand_flavor_spec(&mut table);
         log::debug!("Loaded config from: {}", path.display());
-        return Ok((config, extensions, vec![canonical(path)]));
+        return Ok((config, table, vec![canonical(path)]));
     }
 
     let mut chain = Vec::new();
     let merged = load_merged_toml(path, &mut chain)?;
     let config = finalize_merged_table(&merged, path)?;
-    let extensions = merged.get("extensions").cloned();
     log::debug!(
         "Loaded config from: {} (extends {} file(s))",
         path.display(),
         chain.len().saturating_sub(1)
     );
-    Ok((config, extensions, chain))
+    Ok((config, merged, chain))
 }
 
 /// Canonicalize for stable identity comparisons, falling back to the path as
@@ -456,19 +605,16 @@ fn load_merged_toml(path: &Path, chain: &mut Vec<PathBuf>) -> Result<toml::Table
 
     // Per-file deprecation/validation checks so warnings carry this file's path.
     check_deprecated_blank_lines(&s, path);
-    if let Err(msg) = validate_extension_names(&s) {
-        return Err(ConfigError {
-            path: path.to_path_buf(),
-            span: None,
-            message: msg,
-        });
-    }
+    validate_extension_names(&s, path)?;
 
     let mut table = toml::from_str::<toml::Table>(&s).map_err(|e| ConfigError {
         path: path.to_path_buf(),
         span: e.span(),
         message: e.to_string(),
     })?;
+    // Expand per file, so a flavor string's extensions stay scoped to the
+    // flavor of the file that declares it.
+    expand_flavor_spec(&mut table).map_err(|message| located_error(&s, path, "flavor", message))?;
 
     if let Some(extend_val) = table.get("extend") {
         let extend_str = extend_val.as_str().ok_or_else(|| ConfigError {
@@ -549,10 +695,23 @@ fn finalize_merged_table(table: &toml::Table, leaf: &Path) -> Result<Config, Con
         .map_err(|e| ConfigError {
             path: leaf.to_path_buf(),
             span: None,
-            message: e.to_string(),
+            message: with_hint(e.to_string()),
         })
 }
 
+/// Check the config file at `path` and every file it `extend`s, without
+/// resolving a flavor. Returns the canonical paths of the checked files (leaf
+/// first), or the first problem found.
+pub fn validate(path: &Path) -> Result<Vec<PathBuf>, ConfigError> {
+    read_config_with_chain(path).map(|(_, _, chain)| chain)
+}
+
+/// The config file [`load`] would discover from `start_dir` without an
+/// explicit path: the nearest project config, else the global user config.
+pub fn discover(start_dir: &Path) -> Option<PathBuf> {
+    find_in_tree(start_dir, project_boundary(start_dir).as_deref()).or_else(xdg_config_path)
+}
+
 /// Walk up from `start_dir` looking for a `panache.toml` / `.panache.toml`.
 ///
 /// `boundary`, when set, caps the walk: the boundary directory itself is
@@ -680,36 +839,105 @@ pub fn load_with_chain(
     flavor_override: Option<Flavor>,
 ) -> io::Result<(Config, ConfigSource, Vec<PathBuf>)> {
     let boundary = project_boundary(start_dir);
-    let (mut cfg, source, extensions, chain) = if let Some(path) = explicit {
-        let (cfg, ext, chain) = read_config_with_chain(path).map_err(io::Error::from)?;
-        (cfg, ConfigSource::Explicit(path.to_path_buf()), ext, chain)
+    let (mut cfg, source, raw, chain) = if let Some(path) = explicit {
+        let (cfg, raw, chain) = read_config_with_chain(path).map_err(io::Error::from)?;
+        (cfg, ConfigSource::Explicit(path.to_path_buf()), raw, chain)
     } else if let Some(p) = find_in_tree(start_dir, boundary.as_deref()) {
         // A discovered config that fails to parse is fatal: it is the config
         // that *would* apply, so silently falling through to the global/default
         // config (the old `&& let Ok(cfg)` behavior) let a typo'd project
         // `panache.toml` be ignored by both the CLI and the LSP.
-        let (cfg, ext, chain) = read_config_with_chain(&p).map_err(io::Error::from)?;
-        (cfg, ConfigSource::Discovered(p), ext, chain)
+        let (cfg, raw, chain) = read_config_with_chain(&p).map_err(io::Error::from)?;
+        (cfg, ConfigSource::Discovered(p), raw, chain)
     } else if let Some(p) = xdg_config_path()
-        && let Ok((cfg, ext, chain)) = read_config_with_chain(&p)
+        && let Ok((cfg, raw, chain)) = read_config_with_chain(&p)
     {
-        (cfg, ConfigSource::Global(p), ext, chain)
+        (cfg, ConfigSource::Global(p), raw, chain)
     } else {
         log::debug!("No config file found, using defaults");
-        (Config::default(), ConfigSource::None, None, Vec::new())
+        (
+            Config::default(),
+            ConfigSource::None,
+            toml::Table::new(),
+            Vec::new(),
+        )
     };
+    let extensions = raw.get("extensions").cloned();
 
     let anchor = source.project_anchor();
-    let resolved_flavor =
-        flavor_override.or_else(|| detect_flavor(input_file, anchor.as_deref(), &cfg));
+    // A Quarto or bookdown project decides the flavor of Markdown files when no
+    // config file sets one, and its `from:` modifiers add to the extensions.
+    let project = input_file
+        .filter(|_| flavor_override.is_none())
+        .and_then(crate::project::Project::discover);
+    let resolved_flavor = flavor_override.or_else(|| match &project {
+        Some(project) if !raw.contains_key("flavor") => {
+            let project_cfg = Config {
+                flavor: project.flavor(),
+                ..cfg.clone()
+            };
+            detect_flavor(input_file, anchor.as_deref(), &project_cfg)
+        }
+        _ => detect_flavor(input_file, anchor.as_deref(), &cfg),
+    });
 
     if let Some(flavor) = resolved_flavor {
+        let extensions = match &project {
+            Some(project) => {
+                with_project_extensions(extensions, flavor, &project.extension_modifiers())
+            }
+            None => extensions,
+        };
         apply_flavor(&mut cfg, flavor, extensions.as_ref());
     }
+    if let Some(input_file) = input_file {
+        apply_path_extensions(&mut cfg, input_file);
+    }
+
+    cfg.external_sandbox = sandbox::sandbox_policy(
+        std::mem::take(&mut cfg.external_sandbox),
+        &source,
+        raw.contains_key("external-sandbox"),
+    );
 
     Ok((cfg, source, chain))
 }
 
+/// Add a project's `from:` extension modifiers to the raw `[extensions]`
+/// value as `[extensions.<flavor>]` entries. Any extension the config already
+/// sets, globally or for `flavor`, keeps its configured value.
+fn with_project_extensions(
+    extensions: Option<toml::Value>,
+    flavor: Flavor,
+    modifiers: &[(String, bool)],
+) -> Option<toml::Value> {
+    if modifiers.is_empty() {
+        return extensions;
+    }
+    let mut table = match extensions {
+        Some(toml::Value::Table(table)) => table,
+        // A wrong-shaped `[extensions]` is reported when it is resolved.
+        Some(other) => return Some(other),
+        None => toml::Table::new(),
+    };
+    let key = flavor_key(flavor);
+    for (name, enabled) in modifiers {
+        if table.get(name).is_some_and(toml::Value::is_bool) {
+            continue;
+        }
+        if let Some(scoped) = table
+            .entry(key.clone())
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+            .as_table_mut()
+        {
+            scoped
+                .entry(name.clone())
+                .or_insert(toml::Value::Boolean(*enabled));
+        }
+    }
+    Some(toml::Value::Table(table))
+}
+
 /// Re-resolve flavor-dependent extensions from the already-merged raw
 /// `[extensions]` value. Passing the merged value (rather than re-reading the
 /// config file) keeps `extend`ed base extensions in play and avoids a second
@@ -720,6 +948,140 @@ fn apply_flavor(cfg: &mut Config, flavor: Flavor, extensions: Option<&toml::Valu
     cfg.formatter_extensions = resolve_formatter_extensions_for_flavor(extensions, flavor);
 }
 
+/// Pandoc reader names accepted as the base of a flavor string, besides
+/// Panache's own flavor names.
+const PANDOC_READER_FLAVORS: &[(&str, Flavor)] = &[
+    ("markdown", Flavor::Pandoc),
+    ("markdown-mmd", Flavor::MultiMarkdown),
+];
+
+/// A flavor written Pandoc-style, with extension modifiers appended to its
+/// name: `markdown+emoji-fancy_lists` is Pandoc's Markdown with `emoji`
+/// enabled and `fancy-lists` disabled.
+///
+/// The base is a Panache flavor name or a Pandoc reader name (`markdown`,
+/// `markdown_mmd`). Extension names may use Pandoc's underscores; they are
+/// stored in the kebab-case form of `[extensions]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlavorSpec {
+    pub flavor: Flavor,
+    /// `(name, enabled)` for each `+name` / `-name` modifier, in order.
+    pub extensions: Vec<(String, bool)>,
+}
+
+impl FromStr for FlavorSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        let s = s.trim();
+        // Flavor names may themselves contain `-` (`r-markdown`), so take the
+        // longest prefix ending at a modifier sign that names a flavor.
+        let (flavor, mut rest) = s
+            .match_indices(['+', '-'])
+            .map(|(i, _)| i)
+            .chain([s.len()])
+            .rev()
+            .find_map(|i| Some((flavor_from_name(&s[..i])?, &s[i..])))
+            .ok_or_else(|| unknown_flavor_error(s))?;
+
+        let mut extensions = Vec::new();
+        while let Some(sign) = rest.chars().next() {
+            let body = &rest[1..];
+            let end = body.find(['+', '-']).unwrap_or(body.len());
+            let name = body[..end].replace('_', "-");
+            if name.is_empty() {
+                return Err(format!(
+                    "missing extension name after `{sign}` in flavor `{s}`"
+                ));
+            }
+            check_extension_name(&name).map_err(|err| {
+                format!(
+                    "{err} in flavor `{s}` (extension names in a flavor use underscores, \
+                     e.g. `fancy_lists`)"
+                )
+            })?;
+            extensions.push((name, sign == '+'));
+            rest = &body[end..];
+        }
+
+        Ok(Self { flavor, extensions })
+    }
+}
+
+fn flavor_from_name(name: &str) -> Option<Flavor> {
+    parse_flavor_key(name).or_else(|| {
+        let name = name.replace('_', "-").to_lowercase();
+        PANDOC_READER_FLAVORS
+            .iter()
+            .find(|(alias, _)| *alias == name)
+            .map(|(_, flavor)| *flavor)
+    })
+}
+
+fn unknown_flavor_error(s: &str) -> String {
+    let base = &s[..s.find('+').unwrap_or(s.len())];
+    let mut msg = format!("unknown flavor `{base}`");
+    let known: Vec<&str> = KNOWN_FLAVOR_KEYS
+        .iter()
+        .copied()
+        .chain(["markdown", "markdown_mmd"])
+        .collect();
+    if let Some(suggestion) = closest_match(base, &known) {
+        msg.push_str(&format!("; did you mean `{suggestion}`?"));
+    }
+    msg
+}
+
+/// The `[extensions.<flavor>]` key for `flavor`.
+fn flavor_key(flavor: Flavor) -> String {
+    toml::Value::try_from(flavor)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+/// Rewrite a Pandoc-style `flavor = "markdown+emoji"` in a raw config table
+/// into the plain base flavor plus `[extensions.<flavor>]` entries, so the
+/// rest of the loader only sees flavor names. Entries already present in
+/// `[extensions.<flavor>]` win over the flavor string.
+///
+/// Returns whether the table changed; plain flavor names are left as is.
+pub fn expand_flavor_spec(table: &mut toml::Table) -> Result<bool, String> {
+    let Some(value) = table.get("flavor").and_then(toml::Value::as_str) else {
+        return Ok(false);
+    };
+    if toml::Value::String(value.to_string())
+        .try_into::<Flavor>()
+        .is_ok()
+    {
+        return Ok(false);
+    }
+
+    let spec: FlavorSpec = value.parse()?;
+    let key = flavor_key(spec.flavor);
+    table.insert("flavor".to_string(), toml::Value::String(key.clone()));
+    if spec.extensions.is_empty() {
+        return Ok(true);
+    }
+
+    // A wrong-shaped `[extensions]` is reported when extensions are resolved.
+    let extensions = table
+        .entry("extensions")
+        .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+    let Some(scoped) = extensions.as_table_mut().and_then(|extensions| {
+        extensions
+            .entry(key)
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+            .as_table_mut()
+    }) else {
+        return Ok(true);
+    };
+    for (name, enabled) in spec.extensions {
+        scoped.entry(name).or_insert(toml::Value::Boolean(enabled));
+    }
+    Ok(true)
+}
+
 fn parse_flavor_key(s: &str) -> Option<Flavor> {
     match s.replace('_', "-").to_lowercase().as_str() {
         "pandoc" => Some(Flavor::Pandoc),
@@ -730,6 +1092,7 @@ fn parse_flavor_key(s: &str) -> Option<Flavor> {
         "multimarkdown" | "multi-markdown" => Some(Flavor::MultiMarkdown),
         "mdsvex" => Some(Flavor::Mdsvex),
         "myst" => Some(Flavor::Myst),
+        "obsidian" => Some(Flavor::Obsidian),
         _ => None,
     }
 }
@@ -850,6 +1213,18 @@ fn resolve_formatter_extensions_for_flavor(
     FormatterExtensions::merge_with_flavor(global_overrides, flavor)
 }
 
+/// Turn on the extensions a document's file extension implies: `.mdx` files
+/// are parsed with `mdx`, whatever their flavor.
+pub fn apply_path_extensions(cfg: &mut Config, input_file: &Path) {
+    if input_file
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("mdx"))
+    {
+        cfg.extensions.mdx = true;
+    }
+}
+
 /// Extension-based flavor detection for fallback callers that don't run the
 /// full config walk: the LSP no-config default (`default_config_for_uri`) and
 /// the CLI `--isolated` path. Both previously hand-rolled a reduced match that
@@ -910,7 +1285,7 @@ fn is_quarto_manifest_filename(path: &Path) -> bool {
 fn detect_flavor_override(
     input_path: &Path,
     base_dir: Option<&Path>,
-    overrides: &HashMap<String, Flavor>,
+    overrides: &BTreeMap<String, Flavor>,
 ) -> Option<Flavor> {
     if overrides.is_empty() {
         return None;
@@ -938,6 +1313,7 @@ fn detect_flavor_override(
             continue;
         }
 
+        // Equally specific patterns resolve to the first in sorted order.
         let score = pattern_specificity(pattern);
         if best.is_none_or(|(best_score, _)| score > best_score) {
             best = Some((score, *flavor));
@@ -1104,6 +1480,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn mdx_files_enable_the_mdx_extension() {
+        let mut cfg = Config::default();
+        apply_path_extensions(&mut cfg, Path::new("doc.md"));
+        assert!(!cfg.extensions.mdx);
+        apply_path_extensions(&mut cfg, Path::new("docs/intro.MDX"));
+        assert!(cfg.extensions.mdx);
+        assert_eq!(
+            detect_flavor(Some(Path::new("doc.mdx")), None, &cfg),
+            Some(cfg.flavor)
+        );
+    }
+
     #[test]
     fn detect_flavor_maps_compound_svelte_md_extension() {
         let cfg = Config::default();
@@ -1211,6 +1600,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn quarto_project_sets_markdown_flavor_and_from_extensions() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        let root = tmp.path();
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        std::fs::write(
+            root.join("_quarto.yml"),
+            "project:\n  type: website\nfrom: markdown+emoji-fancy_lists\n",
+        )
+        .unwrap();
+        let md = root.join("notes.md");
+        std::fs::write(&md, "").unwrap();
+
+        let (cfg, _) = load(None, root, Some(&md), None).expect("load");
+        assert_eq!(cfg.flavor, Flavor::Quarto);
+        assert!(cfg.extensions.emoji);
+        assert!(!cfg.extensions.fancy_lists);
+
+        // A flavor or extension set in the config file wins over the project.
+        std::fs::write(
+            root.join("panache.toml"),
+            "flavor = \"gfm\"\n\n[extensions]\nemoji = false\n",
+        )
+        .unwrap();
+        let (cfg, _) = load(None, root, Some(&md), None).expect("load");
+        assert_eq!(cfg.flavor, Flavor::Gfm);
+        assert!(!cfg.extensions.emoji);
+    }
+
     #[test]
     fn flavor_override_still_merges_extensions_overrides() {
         let tmp = tempfile::tempdir().expect("tempdir");
@@ -1593,13 +2011,23 @@ mod tests {
     }
 
     #[test]
-    fn deprecated_blank_lines_still_parses() {
-        // Soft-removed: setting it must not error so existing user TOMLs keep
-        // working. The warning is emitted via stderr (not asserted here).
-        let toml = "[format]\nblank-lines = \"preserve\"\n";
-        let cfg = parse_config_str(toml, Path::new("panache.toml"))
-            .expect("config with deprecated blank-lines must still parse");
-        assert_eq!(cfg.line_width, 80, "unrelated defaults preserved");
+    fn format_blank_lines_parses_all_policies() {
+        for (value, expected) in [
+            ("\"preserve\"", BlankLines::Preserve),
+            ("\"collapse\"", BlankLines::Collapse),
+            ("{ max = 2 }", BlankLines::Max(2)),
+        ] {
+            let toml = format!("[format]\nblank-lines = {value}\n");
+            let cfg = parse_config_str(&toml, Path::new("panache.toml")).expect("parse");
+            assert_eq!(cfg.blank_lines, expected);
+            assert_eq!(cfg.line_width, 80, "unrelated defaults preserved");
+        }
+        let cfg = parse_config_str(
+            "[format]\nblank-lines-before-heading = 2\n",
+            Path::new("panache.toml"),
+        )
+        .expect("parse");
+        assert_eq!(cfg.blank_lines_before_heading, Some(2));
     }
 
     #[test]
@@ -1623,6 +2051,208 @@ mod tests {
         assert_eq!(cfg.horizontal_rule_style, HorizontalRuleStyle::LineWidth);
     }
 
+    #[test]
+    fn emphasis_markers_parse_and_default_to_asterisk() {
+        let cfg = parse_config_str(
+            "[format]\nemphasis-marker = \"underscore\"\nstrong-marker = \"preserve\"\n",
+            Path::new("panache.toml"),
+        )
+        .expect("[format] emphasis markers must parse");
+        assert_eq!(cfg.emphasis_marker, EmphasisMarker::Underscore);
+        assert_eq!(cfg.strong_marker, EmphasisMarker::Preserve);
+
+        let cfg = parse_config_str("[format]\n", Path::new("panache.toml"))
+            .expect("empty [format] section must parse");
+        assert_eq!(cfg.emphasis_marker, EmphasisMarker::Asterisk);
+        assert_eq!(cfg.strong_marker, EmphasisMarker::Asterisk);
+    }
+
+    #[test]
+    fn allow_breaks_in_parses_and_defaults_to_all() {
+        let cfg = parse_config_str(
+            "[format]\nallow-breaks-in = [\"emphasis\", \"span\"]\n",
+            Path::new("panache.toml"),
+        )
+        .expect("[format] allow-breaks-in must parse");
+        assert_eq!(
+            cfg.allow_breaks_in,
+            vec![BreakableInline::Emphasis, BreakableInline::Span]
+        );
+
+        let cfg = parse_config_str("[format]\n", Path::new("panache.toml"))
+            .expect("empty [format] section must parse");
+        assert_eq!(cfg.allow_breaks_in, BreakableInline::ALL.to_vec());
+
+        let err = parse_config_str(
+            "[format]\nallow-breaks-in = [\"code\"]\n",
+            Path::new("panache.toml"),
+        );
+        assert!(err.is_err(), "unknown construct must be rejected");
+    }
+
+    #[test]
+    fn blockquotes_parse_and_default_to_one_space_with_explicit_markers() {
+        let cfg = parse_config_str(
+            "[format.blockquotes]\nmarker-spacing = 0\ncollapse-lazy = false\n",
+            Path::new("panache.toml"),
+        )
+        .expect("[format.blockquotes] must parse");
+        assert_eq!(
+            cfg.blockquotes,
+            BlockquoteStyle {
+                marker_spacing: 0,
+                collapse_lazy: false,
+            }
+        );
+
+        let cfg = parse_config_str("[format]\n", Path::new("panache.toml"))
+            .expect("empty [format] section must parse");
+        assert_eq!(cfg.blockquotes, BlockquoteStyle::default());
+
+        for bad in ["marker-spacing = 2", "spacing = 1"] {
+            let toml = format!("[format.blockquotes]\n{bad}\n");
+            assert!(
+                parse_config_str(&toml, Path::new("panache.toml")).is_err(),
+                "{bad} must be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn definition_lists_parse_and_default_to_colon_with_three_spaces() {
+        let cfg = parse_config_str(
+            "[format.definition-lists]\nmarker = \"~\"\nspaces = 1\ncompact = true\n",
+            Path::new("panache.toml"),
+        )
+        .expect("[format.definition-lists] must parse");
+        assert_eq!(
+            cfg.definition_lists,
+            DefinitionListStyle {
+                marker: DefinitionMarker::Tilde,
+                spaces: 1,
+                compact: Some(true),
+            }
+        );
+
+        let cfg = parse_config_str("[format]\n", Path::new("panache.toml"))
+            .expect("empty [format] section must parse");
+        assert_eq!(cfg.definition_lists, DefinitionListStyle::default());
+
+        for bad in ["spaces = 0", "spaces = 4", "marker = \"-\"", "indent = 2"] {
+            let toml = format!("[format.definition-lists]\n{bad}\n");
+            assert!(
+                parse_config_str(&toml, Path::new("panache.toml")).is_err(),
+                "`{bad}` must be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn div_fence_length_parses_and_defaults_to_depth_based() {
+        let cfg = parse_config_str(
+            "[format.divs]\nfence-length = \"preserve\"\n",
+            Path::new("panache.toml"),
+        )
+        .expect("[format.divs] fence-length must parse");
+        assert_eq!(cfg.divs.fence_length, DivFenceLength::Preserve);
+
+        let cfg = parse_config_str(
+            "[format.divs]\nfence-length = { fixed = 4 }\n",
+            Path::new("panache.toml"),
+        )
+        .expect("fixed fence-length must parse");
+        assert_eq!(cfg.divs.fence_length, DivFenceLength::Fixed(4));
+
+        let cfg = parse_config_str("[format]\n", Path::new("panache.toml"))
+            .expect("empty [format] section must parse");
+        assert_eq!(cfg.divs.fence_length, DivFenceLength::DepthBased);
+
+        let err = parse_config_str(
+            "[format.divs]\nfence-length = \"longest\"\n",
+            Path::new("panache.toml"),
+        );
+        assert!(err.is_err(), "unknown fence-length must be rejected");
+    }
+
+    #[test]
+    fn heading_numbering_parses_and_defaults_to_preserve() {
+        let cfg = parse_config_str(
+            "[format.headings]\nnumbering = \"renumber\"\n",
+            Path::new("panache.toml"),
+        )
+        .expect("[format.headings] numbering must parse");
+        assert_eq!(cfg.headings.numbering, HeadingNumbering::Renumber);
+
+        let cfg = parse_config_str("[format]\n", Path::new("panache.toml"))
+            .expect("empty [format] section must parse");
+        assert_eq!(cfg.headings.numbering, HeadingNumbering::Preserve);
+    }
+
+    #[test]
+    fn toc_levels_parse_and_reject_out_of_range() {
+        let cfg = parse_config_str(
+            "[format.toc]\nmin-level = 1\nmax-level = 4\n",
+            Path::new("panache.toml"),
+        )
+        .expect("[format.toc] levels must parse");
+        assert_eq!(
+            cfg.toc,
+            TocStyle {
+                min_level: 1,
+                max_level: 4
+            }
+        );
+
+        let cfg = parse_config_str("[format]\n", Path::new("panache.toml"))
+            .expect("empty [format] section must parse");
+        assert_eq!(cfg.toc, TocStyle::default());
+
+        let err = parse_config_str("[format.toc]\nmax-level = 7\n", Path::new("panache.toml"));
+        assert!(err.is_err(), "heading level 7 must be rejected");
+    }
+
+    #[test]
+    fn link_destinations_parses_and_defaults_to_preserve() {
+        let cfg = parse_config_str(
+            "[format]\nlink-destinations = \"percent-encode\"\n",
+            Path::new("panache.toml"),
+        )
+        .expect("[format] link-destinations must parse");
+        assert_eq!(cfg.link_destinations, LinkDestinations::PercentEncode);
+
+        let cfg = parse_config_str("[format]\n", Path::new("panache.toml"))
+            .expect("empty [format] section must parse");
+        assert_eq!(cfg.link_destinations, LinkDestinations::Preserve);
+    }
+
+    #[test]
+    fn bare_urls_parses_and_defaults_to_preserve() {
+        let cfg = parse_config_str(
+            "[format]\nbare-urls = \"autolink\"\n",
+            Path::new("panache.toml"),
+        )
+        .expect("[format] bare-urls must parse");
+        assert_eq!(cfg.bare_urls, BareUrls::Autolink);
+
+        let cfg = parse_config_str("[format]\n", Path::new("panache.toml"))
+            .expect("empty [format] section must parse");
+        assert_eq!(cfg.bare_urls, BareUrls::Preserve);
+    }
+
+    #[test]
+    fn escaping_parses_and_defaults_to_preserve() {
+        let cfg = parse_config_str(
+            "[format]\nescaping = \"minimal\"\n",
+            Path::new("panache.toml"),
+        )
+        .expect("[format] escaping must parse");
+        assert_eq!(cfg.escaping, Escaping::Minimal);
+
+        let cfg = parse_config_str("[format]\n", Path::new("panache.toml"))
+            .expect("empty [format] section must parse");
+        assert_eq!(cfg.escaping, Escaping::Preserve);
+    }
+
     #[test]
     fn horizontal_rule_style_rejects_unknown_value() {
         let toml = "[format]\nhorizontal-rule-style = \"full\"\n";
@@ -1787,6 +2417,19 @@ mod tests {
         assert_eq!(cfg.line_ending, Some(LineEnding::Lf));
     }
 
+    #[test]
+    fn final_newline_and_trailing_whitespace_parse_and_default() {
+        let toml = "[format]\nfinal-newline = \"preserve\"\ntrim-trailing-whitespace = true\n";
+        let cfg = parse_config_str(toml, Path::new("panache.toml"))
+            .expect("[format] final-newline and trim-trailing-whitespace must parse");
+        assert_eq!(cfg.final_newline, FinalNewline::Preserve);
+        assert!(cfg.trim_trailing_whitespace);
+
+        let cfg = parse_config_str("", Path::new("panache.toml")).expect("empty config parses");
+        assert_eq!(cfg.final_newline, FinalNewline::Ensure);
+        assert!(!cfg.trim_trailing_whitespace);
+    }
+
     #[test]
     fn deprecated_top_level_line_width_still_applies() {
         // Back-compat: top-level `line-width` (no `[format]` key) is honored.
@@ -1828,6 +2471,24 @@ mod tests {
         assert_eq!(cfg.line_ending, Some(LineEnding::Auto));
     }
 
+    #[test]
+    fn convert_tabs_sets_tab_stops_and_tab_width_must_be_positive() {
+        let toml = "[format]\ntab-stops = \"normalize\"\nconvert-tabs = false\ntab-width = 2\n";
+        let cfg = parse_config_str(toml, Path::new("panache.toml"))
+            .expect("[format] convert-tabs must parse");
+        assert_eq!(cfg.tab_stops, TabStopMode::Preserve);
+        assert_eq!(cfg.tab_width, 2);
+        assert_eq!(cfg.parser_options().tab_stop, 2);
+
+        let cfg = parse_config_str("", Path::new("panache.toml")).expect("empty config parses");
+        assert_eq!(cfg.tab_stops, TabStopMode::Normalize);
+
+        assert!(
+            parse_config_str("[format]\ntab-width = 0\n", Path::new("panache.toml")).is_err(),
+            "tab-width = 0 must be rejected"
+        );
+    }
+
     #[test]
     fn table_indent_parses_from_format_section() {
         let toml = "[format]\ntable-indent = 0\n";
@@ -1857,18 +2518,38 @@ mod tests {
 
     #[test]
     fn removed_code_blocks_table_now_errors() {
-        // `[code-blocks]` was a no-op for several releases; in 3.0 it is
-        // rejected under `deny_unknown_fields`.
+        // The top-level `[code-blocks]` table was a no-op for several
+        // releases; in 3.0 it is rejected under `deny_unknown_fields`.
         let toml = "flavor = \"pandoc\"\n[code-blocks]\nattribute-style = \"explicit\"\n";
         parse_config_str(toml, Path::new("panache.toml"))
             .expect_err("removed [code-blocks] table must error");
     }
 
     #[test]
-    fn removed_format_code_blocks_subtable_now_errors() {
-        let toml = "[format.code-blocks]\nattribute-style = \"explicit\"\n";
-        parse_config_str(toml, Path::new("panache.toml"))
-            .expect_err("removed [format.code-blocks] subtable must error");
+    fn format_code_blocks_parse_and_default_to_shortcut() {
+        let toml =
+            "[format.code-blocks]\nattribute-style = \"canonical\"\nlowercase-language = true\n";
+        let cfg = parse_config_str(toml, Path::new("panache.toml"))
+            .expect("[format.code-blocks] must parse");
+        assert_eq!(
+            cfg.code_blocks,
+            CodeBlockStyle {
+                attribute_style: AttributeStyle::Canonical,
+                lowercase_language: true,
+            }
+        );
+
+        let cfg = parse_config_str("[format]\n", Path::new("panache.toml"))
+            .expect("empty [format] section must parse");
+        assert_eq!(cfg.code_blocks, CodeBlockStyle::default());
+
+        for bad in ["attribute-style = \"braces\"", "language-case = \"lower\""] {
+            let toml = format!("[format.code-blocks]\n{bad}\n");
+            assert!(
+                parse_config_str(&toml, Path::new("panache.toml")).is_err(),
+                "{bad} must be rejected"
+            );
+        }
     }
 
     #[test]
@@ -1928,6 +2609,140 @@ mod tests {
         assert!(!cfg.lint.is_rule_enabled("heading-hierarchy"));
     }
 
+    #[test]
+    fn lint_rule_table_controls_enabled_and_fix() {
+        let toml = "[lint.rules.heading-hierarchy]\nfix = false\n\n[lint.rules.Link_Text_Is_Url]\nenabled = false\n";
+        let cfg = parse_config_str(toml, Path::new("panache.toml"))
+            .expect("[lint.rules.<rule>] table must parse");
+        assert!(cfg.lint.is_rule_enabled("heading-hierarchy"));
+        assert!(!cfg.lint.is_rule_fix_enabled("heading-hierarchy"));
+        assert!(!cfg.lint.is_rule_enabled("link-text-is-url"));
+        assert!(cfg.lint.is_rule_fix_enabled("link-text-is-url"));
+    }
+
+    #[test]
+    fn lint_rule_table_rejects_unknown_keys() {
+        let toml = "[lint.rules.heading-hierarchy]\nautofix = false\n";
+        let err = parse_config_str(toml, Path::new("panache.toml"))
+            .expect_err("unknown rule table key must error");
+        assert!(
+            err.to_string().contains("expected `enabled` or `fix`"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn lint_heading_hierarchy_options_parse() {
+        let toml = "[lint.rules.heading-hierarchy]\nfix = false\nallow-multiple-h1 = false\nstart-level = 2\nignore-in-divs = true\n";
+        let cfg = parse_config_str(toml, Path::new("panache.toml"))
+            .expect("heading-hierarchy options must parse");
+        assert!(cfg.lint.is_rule_enabled("heading-hierarchy"));
+        assert!(!cfg.lint.is_rule_fix_enabled("heading-hierarchy"));
+        assert_eq!(
+            cfg.lint.heading_hierarchy,
+            HeadingHierarchyOptions {
+                allow_multiple_h1: false,
+                start_level: Some(2),
+                ignore_in_divs: true,
+            }
+        );
+
+        let toml = "[lint.rules.heading-hierarchy]\nstart-level = 7\n";
+        let err = parse_config_str(toml, Path::new("panache.toml"))
+            .expect_err("out-of-range start-level must error");
+        assert!(err.to_string().contains("from 1 to 6"), "{err}");
+    }
+
+    #[test]
+    fn lint_heading_length_options_parse() {
+        let toml = "[lint.rules.heading-length]\nenabled = true\nmax-length = 60\n";
+        let cfg = parse_config_str(toml, Path::new("panache.toml"))
+            .expect("heading-length options must parse");
+        assert!(cfg.lint.is_rule_explicitly_enabled("heading-length"));
+        assert_eq!(
+            cfg.lint.heading_length,
+            HeadingLengthOptions {
+                max_length: Some(60),
+            }
+        );
+
+        let toml = "[lint.rules.heading-length]\nmax-length = 0\n";
+        let err = parse_config_str(toml, Path::new("panache.toml"))
+            .expect_err("zero max-length must error");
+        assert!(err.to_string().contains("positive integer"), "{err}");
+    }
+
+    #[test]
+    fn lint_heading_style_options_parse() {
+        let toml = "[lint.rules.heading-case]\nstyle = \"title\"\nwords = [\"macOS\"]\n\n\
+                    [lint.rules.heading-punctuation]\npunctuation = \".:\"\n";
+        let cfg = parse_config_str(toml, Path::new("panache.toml"))
+            .expect("heading style options must parse");
+        assert_eq!(
+            cfg.lint.heading_case,
+            HeadingCaseOptions {
+                style: HeadingCase::Title,
+                words: vec!["macOS".to_string()],
+            }
+        );
+        assert_eq!(cfg.lint.heading_punctuation.punctuation, ".:");
+
+        let toml = "[lint.rules.heading-case]\nstyle = \"upper\"\n";
+        let err = parse_config_str(toml, Path::new("panache.toml"))
+            .expect_err("unknown heading case must error");
+        assert!(err.to_string().contains("sentence"), "{err}");
+    }
+
+    #[test]
+    fn lint_spelling_options_parse() {
+        let toml = "[lint.rules.spelling]\nenabled = true\nchecker = \"aspell\"\n\
+                    language = \"en_GB\"\nwords = [\"panache\", \"Quarto\"]\n";
+        let cfg =
+            parse_config_str(toml, Path::new("panache.toml")).expect("spelling options must parse");
+        assert!(cfg.lint.is_rule_explicitly_enabled("spelling"));
+        assert_eq!(
+            cfg.lint.spelling,
+            SpellingOptions {
+                checker: SpellChecker::Aspell,
+                language: "en_GB".to_string(),
+                words: vec!["panache".to_string(), "Quarto".to_string()],
+            }
+        );
+
+        let toml = "[lint.rules.spelling]\nchecker = \"ispell\"\n";
+        let err = parse_config_str(toml, Path::new("panache.toml"))
+            .expect_err("unknown checker must error");
+        assert!(err.to_string().contains("hunspell"), "{err}");
+    }
+
+    #[test]
+    fn lint_undefined_references_options_parse() {
+        assert!(Config::default().lint.undefined_references.project);
+        let toml = "[lint.rules.undefined-references]\nproject = false\n";
+        let cfg = parse_config_str(toml, Path::new("panache.toml"))
+            .expect("undefined-references options must parse");
+        assert!(cfg.lint.is_rule_enabled("undefined-references"));
+        assert_eq!(
+            cfg.lint.undefined_references,
+            UndefinedReferencesOptions { project: false }
+        );
+
+        let toml = "[lint.rules.undefined-references]\nproject = \"yes\"\n";
+        let err = parse_config_str(toml, Path::new("panache.toml"))
+            .expect_err("non-boolean project must error");
+        assert!(err.to_string().contains("true or false"), "{err}");
+    }
+
+    #[test]
+    fn per_construct_widths_parse_from_format_section() {
+        let toml = "[format]\nline-width = 80\ntable-max-width = 100\ncode-comment-width = 72\n";
+        let cfg = parse_config_str(toml, Path::new("panache.toml"))
+            .expect("per-construct widths must parse");
+        assert_eq!(cfg.table_max_width, Some(100));
+        assert_eq!(cfg.code_comment_width, Some(72));
+        assert_eq!(Config::default().table_max_width, None);
+    }
+
     #[test]
     fn kebab_case_formatter_prepend_args_applies() {
         // The canonical `prepend-args` spelling prepends to the preset args.
@@ -1938,6 +2753,30 @@ mod tests {
         assert_eq!(air.args.first().map(String::as_str), Some("--extra"));
     }
 
+    #[test]
+    fn formatter_timeout_ms_overrides_preset() {
+        let toml = "[formatters]\nr = \"air\"\npython = \"my-fmt\"\n\n[formatters.air]\ntimeout-ms = 5000\n\n[formatters.my-fmt]\ncmd = \"my-fmt\"\n";
+        let cfg = parse_config_str(toml, Path::new("panache.toml")).expect("timeout-ms must parse");
+        assert_eq!(cfg.formatters["r"][0].timeout_ms, Some(5000));
+        assert_eq!(cfg.formatters["r"][0].cmd, "air");
+        assert_eq!(cfg.formatters["python"][0].timeout_ms, None);
+    }
+
+    #[test]
+    fn languages_table_aliases_fence_labels() {
+        let toml = "[languages]\nPy3 = \"python\"\nrmd = \"R\"\n";
+        let cfg = parse_config_str(toml, Path::new("panache.toml")).expect("languages must parse");
+        assert_eq!(cfg.language_key("py3"), "python");
+        assert_eq!(cfg.language_key("RMD"), "r");
+        // Built-in aliases and case folding apply without any configuration.
+        assert_eq!(cfg.language_key("py"), "python");
+        assert_eq!(cfg.language_key("R"), "r");
+        assert_eq!(cfg.language_key(".yml"), "yaml");
+        assert_eq!(cfg.language_key("sh"), "bash");
+        assert_eq!(cfg.language_key("js"), "javascript");
+        assert_eq!(cfg.language_key("lua"), "lua");
+    }
+
     #[test]
     fn snake_case_formatter_field_is_not_honored() {
         // The removed `prepend_args` snake_case alias no longer parses, so the
@@ -1971,6 +2810,17 @@ mod tests {
         assert!(cfg.experimental.format_math, "opt-in must enable the gate");
     }
 
+    #[test]
+    fn organize_sort_headings_under_parses() {
+        let toml = "[organize]\nsort-headings-under = [\"## FAQ\", \"Glossary\"]\n";
+        let cfg = parse_config_str(toml, Path::new("panache.toml"))
+            .expect("[organize] sort-headings-under must parse");
+        assert_eq!(cfg.organize.sort_headings_under, vec!["## FAQ", "Glossary"]);
+
+        let cfg = parse_config_str("", Path::new("panache.toml")).expect("empty config must parse");
+        assert!(cfg.organize.sort_headings_under.is_empty());
+    }
+
     #[test]
     fn unknown_key_inside_experimental_section_is_rejected() {
         let toml = "[experimental]\nformat-maths = true\n";
@@ -2022,6 +2872,126 @@ mod tests {
         );
     }
 
+    #[test]
+    fn unknown_key_suggests_closest_valid_key() {
+        let err = parse_config_str("lin-width = 100\n", Path::new("panache.toml"))
+            .expect_err("typo'd key must error");
+        assert!(
+            err.to_string().contains("did you mean `line-width`?"),
+            "error must suggest the closest key: {err}"
+        );
+
+        let err = parse_config_str("[format]\nwrapp = \"reflow\"\n", Path::new("panache.toml"))
+            .expect_err("typo'd [format] key must error");
+        assert!(
+            err.to_string().contains("did you mean `wrap`?"),
+            "error must suggest the closest [format] key: {err}"
+        );
+    }
+
+    #[test]
+    fn unknown_extension_error_points_at_the_key() {
+        let toml = "[extensions]\nemoji = true\nquato-crossrefs = true\n";
+        let err = parse_config_detailed(toml, Path::new("panache.toml"))
+            .expect_err("typo'd extension must error");
+        let span = err.span.clone().expect("extension errors carry a span");
+        assert_eq!(&toml[span], "quato-crossrefs");
+        assert!(
+            err.message.contains("line 3, column 1"),
+            "error must name the location: {}",
+            err.message
+        );
+
+        let toml = "[extensions.qarto]\nfenced-divs = true\n";
+        let err = parse_config_detailed(toml, Path::new("panache.toml"))
+            .expect_err("typo'd flavor subtable must error");
+        assert_eq!(&toml[err.span.expect("span")], "qarto");
+    }
+
+    #[test]
+    fn flavor_spec_parses_pandoc_modifiers() {
+        let spec: FlavorSpec = "markdown+emoji-fancy_lists".parse().unwrap();
+        assert_eq!(spec.flavor, Flavor::Pandoc);
+        assert_eq!(
+            spec.extensions,
+            vec![
+                ("emoji".to_string(), true),
+                ("fancy-lists".to_string(), false)
+            ]
+        );
+
+        let spec: FlavorSpec = "r-markdown-footnotes".parse().unwrap();
+        assert_eq!(spec.flavor, Flavor::RMarkdown);
+        assert_eq!(spec.extensions, vec![("footnotes".to_string(), false)]);
+
+        let spec: FlavorSpec = "gfm".parse().unwrap();
+        assert_eq!(spec.flavor, Flavor::Gfm);
+        assert!(spec.extensions.is_empty());
+    }
+
+    #[test]
+    fn flavor_spec_rejects_unknown_names() {
+        let err = "markdwn+emoji".parse::<FlavorSpec>().unwrap_err();
+        assert!(err.contains("did you mean `markdown`?"), "{err}");
+
+        let err = "markdown+emojj".parse::<FlavorSpec>().unwrap_err();
+        assert!(err.contains("unknown extension `emojj`"), "{err}");
+
+        let err = "markdown+".parse::<FlavorSpec>().unwrap_err();
+        assert!(err.contains("missing extension name"), "{err}");
+    }
+
+    #[test]
+    fn config_flavor_string_applies_extension_modifiers() {
+        let cfg = parse_config_str(
+            "flavor = \"markdown+emoji-fancy_lists\"\n",
+            Path::new("panache.toml"),
+        )
+        .expect("flavor string must parse");
+        assert_eq!(cfg.flavor, Flavor::Pandoc);
+        assert!(cfg.extensions.emoji);
+        assert!(!cfg.extensions.fancy_lists);
+
+        // An explicit `[extensions.<flavor>]` entry wins over the modifier.
+        let cfg = parse_config_str(
+            "flavor = \"markdown+emoji\"\n\n[extensions.pandoc]\nemoji = false\n",
+            Path::new("panache.toml"),
+        )
+        .expect("flavor string must parse");
+        assert!(!cfg.extensions.emoji);
+    }
+
+    #[test]
+    fn config_flavor_string_error_points_at_flavor() {
+        let toml = "line-width = 80\nflavor = \"markdown+emojj\"\n";
+        let err = parse_config_detailed(toml, Path::new("panache.toml"))
+            .expect_err("unknown modifier must error");
+        assert_eq!(&toml[err.span.expect("span")], "flavor");
+        assert!(err.message.contains("unknown extension `emojj`"));
+    }
+
+    #[test]
+    fn builder_flavor_rederives_extensions() {
+        let cfg = ConfigBuilder::default()
+            .extension("emoji", true)
+            .flavor(Flavor::Gfm)
+            .wrap(WrapMode::Preserve)
+            .build();
+        assert_eq!(cfg.flavor, Flavor::Gfm);
+        assert_eq!(cfg.extensions, Extensions::for_flavor(Flavor::Gfm));
+        assert_eq!(
+            cfg.formatter_extensions,
+            FormatterExtensions::for_flavor(Flavor::Gfm)
+        );
+        assert_eq!(cfg.wrap, Some(WrapMode::Preserve));
+
+        let cfg = ConfigBuilder::default()
+            .flavor(Flavor::Gfm)
+            .extension("emoji", false)
+            .build();
+        assert!(!cfg.extensions.emoji);
+    }
+
     #[test]
     fn known_extension_under_flavor_subtable_still_parses() {
         let toml = "[extensions.pandoc]\nfenced-divs = false\n";
@@ -2174,6 +3144,16 @@ mod tests {
         assert!(m.is_match("tests/testthat/_snaps/x.md"));
         assert!(!m.is_match("docs/intro.qmd"));
 
+        let generated: Vec<String> = GENERATED_EXCLUDE_PATTERNS
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let g = GlobMatcher::build(&generated).expect("generated excludes build");
+        assert!(g.is_match("_freeze/post/index/execute-results/html.json"));
+        assert!(g.is_match("site/.quarto/xref/index.md"));
+        assert!(g.is_match("_site/index.md"));
+        assert!(!g.is_match("docs/intro.qmd"));
+
         let includes: Vec<String> = DEFAULT_INCLUDE_PATTERNS
             .iter()
             .map(|s| s.to_string())
@@ -17,6 +17,7 @@ impl FormatterPresetMetadata {
             cmd: self.cmd.to_string(),
             args: self.args.iter().map(ToString::to_string).collect(),
             stdin: self.stdin,
+            timeout_ms: None,
         }
     }
 }
@@ -249,6 +250,27 @@ const PRESETS: &[FormatterPresetMetadata] = &[
         stdin: true,
         supported_languages: &["cue"],
     },
+    FormatterPresetMetadata {
+        name: "deno",
+        url: "https://docs.deno.com/runtime/reference/cli/fmt/",
+        description: "Deno's built-in formatter for JavaScript, TypeScript, JSON, and CSS.",
+        cmd: "deno",
+        args: &["fmt", "--ext", "{ext}", "-"],
+        stdin: true,
+        supported_languages: &[
+            "javascript",
+            "js",
+            "typescript",
+            "ts",
+            "jsx",
+            "tsx",
+            "json",
+            "jsonc",
+            "css",
+            "scss",
+            "less",
+        ],
+    },
     FormatterPresetMetadata {
         name: "dfmt",
         url: "https://github.com/dlang-community/dfmt",
@@ -375,6 +397,15 @@ const PRESETS: &[FormatterPresetMetadata] = &[
         stdin: true,
         supported_languages: &["kotlin", "kt", "kts"],
     },
+    FormatterPresetMetadata {
+        name: "latexindent",
+        url: "https://github.com/cmhughes/latexindent.pl",
+        description: "Indentation and formatting for LaTeX source.",
+        cmd: "latexindent",
+        args: &["-"],
+        stdin: true,
+        supported_languages: &["tex", "latex"],
+    },
     FormatterPresetMetadata {
         name: "leptosfmt",
         url: "https://github.com/bram209/leptosfmt",
@@ -541,6 +572,15 @@ const PRESETS: &[FormatterPresetMetadata] = &[
         stdin: true,
         supported_languages: &["sh", "bash", "zsh", "ksh", "shell"],
     },
+    FormatterPresetMetadata {
+        name: "sqlfluff",
+        url: "https://sqlfluff.com/",
+        description: "Dialect-flexible SQL linter and formatter (ANSI dialect by default).",
+        cmd: "sqlfluff",
+        args: &["format", "--dialect", "ansi", "-"],
+        stdin: true,
+        supported_languages: &["sql"],
+    },
     FormatterPresetMetadata {
         name: "sqlfmt",
         url: "https://sqlfmt.com",
@@ -697,6 +737,7 @@ pub fn formatter_preset_names() -> &'static [&'static str] {
         "cmake-format",
         "csharpier",
         "cue-fmt",
+        "deno",
         "dfmt",
         "efmt",
         "fatou",
@@ -711,6 +752,7 @@ pub fn formatter_preset_names() -> &'static [&'static str] {
         "isort",
         "jsonnetfmt",
         "ktfmt",
+        "latexindent",
         "leptosfmt",
         "mdformat",
         "mix",
@@ -727,6 +769,7 @@ pub fn formatter_preset_names() -> &'static [&'static str] {
         "runic",
         "rustfmt",
         "shfmt",
+        "sqlfluff",
         "sqlfmt",
         "styler",
         "stylua",
@@ -877,6 +920,22 @@ mod tests {
         }
     }
 
+    #[test]
+    fn deno_sqlfluff_and_latexindent_cover_their_languages() {
+        for (preset, language) in [
+            ("deno", "typescript"),
+            ("deno", "json"),
+            ("sqlfluff", "sql"),
+            ("latexindent", "latex"),
+        ] {
+            let presets = formatter_presets_for_language(language);
+            assert!(
+                presets.iter().any(|meta| meta.name == preset),
+                "Expected {preset} preset to support {language}"
+            );
+        }
+    }
+
     #[test]
     fn preset_names_list_matches_metadata() {
         let metadata_names: HashSet<&str> = PRESETS.iter().map(|preset| preset.name).collect();
@@ -0,0 +1,238 @@
+//! Per-invocation and per-document formatting overrides.
+//!
+//! The same small set of keys can be overridden from the command line
+//! (`panache format -o line-width=100`) and from a document's frontmatter:
+//!
+//! ```yaml
+//! ---
+//! title: Notes
+//! panache:
+//!   line-width: 100
+//!   wrap: preserve
+//! ---
+//! ```
+//!
+//! Only formatting options are accepted. Flavor and extensions change how the
+//! document is parsed, so they cannot come from the document itself. A key
+//! given on the command line wins over the same key in the frontmatter.
+
+use std::sync::OnceLock;
+
+use super::{Config, WrapMode};
+use crate::syntax::{SyntaxNode, YamlNode, collect_frontmatter_region, parse_yaml_document};
+
+/// Frontmatter key holding per-document overrides.
+const FRONTMATTER_KEY: &str = "panache";
+
+/// The keys [`apply_format_option`] handles.
+const FORMAT_OPTION_KEYS: &[&str] = &["line-width", "wrap", "table-indent"];
+
+static COMMAND_LINE_KEYS: OnceLock<Vec<String>> = OnceLock::new();
+
+/// Set the process-wide list of option keys given with `--option`, which the
+/// frontmatter cannot override. The first caller wins.
+pub fn set_command_line_options(keys: Vec<String>) {
+    let _ = COMMAND_LINE_KEYS.set(keys);
+}
+
+fn set_on_command_line(key: &str) -> bool {
+    COMMAND_LINE_KEYS
+        .get()
+        .is_some_and(|keys| keys.iter().any(|set| set == key))
+}
+
+/// Apply one `key = value` formatting override to `cfg`.
+///
+/// Returns `Ok(false)` for a key this function does not handle, so callers can
+/// layer their own keys on top.
+pub fn apply_format_option(cfg: &mut Config, key: &str, value: &str) -> Result<bool, String> {
+    match key {
+        "line-width" => {
+            let n: usize = value.parse().map_err(|_| {
+                format!("invalid value for `line-width`: `{value}` (expected positive integer)")
+            })?;
+            if n == 0 {
+                return Err("invalid value for `line-width`: 0 (expected positive integer)".into());
+            }
+            cfg.line_width = n;
+        }
+        "wrap" => {
+            let mode = match value {
+                "reflow" => WrapMode::Reflow,
+                "sentence" => WrapMode::Sentence,
+                "semantic" => WrapMode::Semantic,
+                "preserve" => WrapMode::Preserve,
+                other => {
+                    return Err(format!(
+                        "invalid value for `wrap`: `{other}` (expected one of: reflow, sentence, semantic, preserve)"
+                    ));
+                }
+            };
+            cfg.wrap = Some(mode);
+        }
+        "table-indent" => {
+            let indent: usize = value.parse().map_err(|_| {
+                format!(
+                    "invalid value for `table-indent`: `{value}` (expected an integer 0, 1, 2, or 3)"
+                )
+            })?;
+            if indent > 3 {
+                return Err(format!(
+                    "invalid value for `table-indent`: `{indent}` (expected 0, 1, 2, or 3)"
+                ));
+            }
+            cfg.table_indent = indent;
+        }
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
+
+/// The raw `key: value` pairs under `panache:` in the document's frontmatter,
+/// in document order. Nested values are skipped.
+pub fn frontmatter_options(tree: &SyntaxNode) -> Vec<(String, String)> {
+    let Some(options) = collect_frontmatter_region(tree)
+        .and_then(|frontmatter| parse_yaml_document(&frontmatter.content))
+        .and_then(|document| document.block_map())
+        .and_then(|map| map.value_of(FRONTMATTER_KEY))
+    else {
+        return Vec::new();
+    };
+
+    match options.as_node() {
+        Some(YamlNode::BlockMap(map)) => map
+            .entries()
+            .filter_map(|entry| Some((entry.key_text()?, entry.value()?.as_scalar()?.value())))
+            .collect(),
+        Some(YamlNode::FlowMap(map)) => map
+            .entries()
+            .filter_map(|entry| Some((entry.key_text()?, entry.value()?.as_scalar()?.value())))
+            .collect(),
+        _ => {
+            log::warn!("Ignoring frontmatter `{FRONTMATTER_KEY}`: expected a mapping of options");
+            Vec::new()
+        }
+    }
+}
+
+/// Why the frontmatter option `key` is not supported, suggesting the closest
+/// supported key for a likely typo.
+pub fn unknown_frontmatter_option(key: &str) -> String {
+    if matches!(key, "flavor" | "extensions") {
+        return format!(
+            "ignoring frontmatter option `{FRONTMATTER_KEY}.{key}`: it changes how the \
+             document is parsed, so set it in panache.toml or on the command line"
+        );
+    }
+    let mut msg = format!("ignoring unknown frontmatter option `{FRONTMATTER_KEY}.{key}`");
+    match super::closest_match(key, FORMAT_OPTION_KEYS) {
+        Some(suggestion) => msg.push_str(&format!("; did you mean `{suggestion}`?")),
+        None => msg.push_str(&format!(" (supported: {})", FORMAT_OPTION_KEYS.join(", "))),
+    }
+    msg
+}
+
+/// `config` with the `panache:` overrides from the document's frontmatter
+/// applied, or `None` when the document sets none.
+///
+/// Unknown keys and invalid values are logged and skipped rather than failing
+/// the format; [`frontmatter_warnings`] describes them for the user.
+pub(crate) fn frontmatter_config(tree: &SyntaxNode, config: &Config) -> Option<Config> {
+    let entries = frontmatter_options(tree);
+    if entries.is_empty() {
+        return None;
+    }
+
+    let mut config = config.clone();
+    for (key, value) in entries {
+        if set_on_command_line(key.trim()) {
+            log::debug!("Frontmatter option `{FRONTMATTER_KEY}.{key}` is overridden by --option");
+            continue;
+        }
+        if let Some(warning) = apply_frontmatter_option(&mut config, key.trim(), value.trim()) {
+            log::warn!("{warning}");
+        }
+    }
+    Some(config)
+}
+
+/// A warning for every `panache:` frontmatter option of the document that is
+/// skipped because its key is unknown or its value is invalid.
+pub fn frontmatter_warnings(tree: &SyntaxNode, config: &Config) -> Vec<String> {
+    let mut config = config.clone();
+    frontmatter_options(tree)
+        .into_iter()
+        .filter(|(key, _)| !set_on_command_line(key.trim()))
+        .filter_map(|(key, value)| apply_frontmatter_option(&mut config, key.trim(), value.trim()))
+        .collect()
+}
+
+/// Apply one frontmatter option to `config`, returning why it was skipped.
+fn apply_frontmatter_option(config: &mut Config, key: &str, value: &str) -> Option<String> {
+    match apply_format_option(config, key, value) {
+        Ok(true) => None,
+        Ok(false) => Some(unknown_frontmatter_option(key)),
+        Err(err) => Some(format!(
+            "ignoring frontmatter option `{FRONTMATTER_KEY}.{key}`: {err}"
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolve(input: &str) -> Option<Config> {
+        let config = Config::default();
+        let tree = crate::parser::parse(input, Some(config.clone()));
+        frontmatter_config(&tree, &config)
+    }
+
+    #[test]
+    fn reads_block_mapping() {
+        let config = resolve(
+            "---\ntitle: Notes\npanache:\n  line-width: 100\n  wrap: preserve\n---\n\nText\n",
+        )
+        .expect("overrides");
+        assert_eq!(config.line_width, 100);
+        assert_eq!(config.wrap, Some(WrapMode::Preserve));
+    }
+
+    #[test]
+    fn reads_flow_mapping() {
+        let config = resolve("---\npanache: { line-width: 60, table-indent: 2 }\n---\n\nText\n")
+            .expect("overrides");
+        assert_eq!(config.line_width, 60);
+        assert_eq!(config.table_indent, 2);
+    }
+
+    #[test]
+    fn skips_invalid_and_unknown_options() {
+        let config =
+            resolve("---\npanache:\n  line-width: wide\n  flavor: gfm\n  wrap: sentence\n---\n")
+                .expect("overrides");
+        assert_eq!(config.line_width, Config::default().line_width);
+        assert_eq!(config.flavor, Config::default().flavor);
+        assert_eq!(config.wrap, Some(WrapMode::Sentence));
+    }
+
+    #[test]
+    fn warns_about_skipped_options() {
+        let input = "---\npanache:\n  line-widht: 100\n  flavor: gfm\n  wrap: tight\n  \
+                     colour: red\n---\n";
+        let config = Config::default();
+        let tree = crate::parser::parse(input, Some(config.clone()));
+        let warnings = frontmatter_warnings(&tree, &config);
+        assert_eq!(warnings.len(), 4, "{warnings:?}");
+        assert!(warnings[0].ends_with("`panache.line-widht`; did you mean `line-width`?"));
+        assert!(warnings[1].contains("`panache.flavor`: it changes how the document is parsed"));
+        assert!(warnings[2].contains("invalid value for `wrap`: `tight`"));
+        assert!(warnings[3].ends_with("(supported: line-width, wrap, table-indent)"));
+    }
+
+    #[test]
+    fn no_overrides_without_panache_key() {
+        assert!(resolve("---\ntitle: Notes\n---\n\nText\n").is_none());
+        assert!(resolve("Text\n").is_none());
+    }
+}
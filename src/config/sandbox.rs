@@ -0,0 +1,129 @@
+//! Where the `[external-sandbox]` policy comes from.
+//!
+//! The sandbox exists to limit formatters configured by a repository's own
+//! `panache.toml`, so that file must not be able to loosen it. The policy is
+//! therefore read only from sources the user controls: the user config
+//! (`~/.config/panache/config.toml`), an explicit `--config`, and the
+//! `--sandbox key=value` flag or its `PANACHE_SANDBOX` environment variable.
+//! An `[external-sandbox]` table in a discovered project config is ignored.
+
+use std::sync::OnceLock;
+
+use super::{ConfigSource, ExternalSandboxConfig};
+
+static SANDBOX_OVERRIDES: OnceLock<Vec<(String, String)>> = OnceLock::new();
+
+/// Set the process-wide `--sandbox` overrides, after checking each one. The
+/// first caller wins.
+pub fn set_sandbox_overrides(overrides: Vec<(String, String)>) -> Result<(), String> {
+    let mut probe = ExternalSandboxConfig::default();
+    for (key, value) in &overrides {
+        apply_sandbox_option(&mut probe, key, value)?;
+    }
+    let _ = SANDBOX_OVERRIDES.set(overrides);
+    Ok(())
+}
+
+/// Apply the `--sandbox` overrides set with [`set_sandbox_overrides`] to
+/// `sandbox`.
+pub fn apply_sandbox_overrides(sandbox: &mut ExternalSandboxConfig) {
+    for (key, value) in SANDBOX_OVERRIDES.get().into_iter().flatten() {
+        // Checked in `set_sandbox_overrides`.
+        let _ = apply_sandbox_option(sandbox, key, value);
+    }
+}
+
+/// Apply one `key=value` sandbox setting. `allowed-commands` adds one command
+/// per use.
+pub fn apply_sandbox_option(
+    sandbox: &mut ExternalSandboxConfig,
+    key: &str,
+    value: &str,
+) -> Result<(), String> {
+    let key = key.trim();
+    let value = value.trim();
+    let invalid =
+        |expected: &str| format!("invalid value for sandbox `{key}`: `{value}` ({expected})");
+    match key {
+        "max-memory-mb" => {
+            sandbox.max_memory_mb =
+                Some(value.parse().map_err(|_| invalid("expected an integer"))?);
+        }
+        "max-output-bytes" => {
+            sandbox.max_output_bytes =
+                Some(value.parse().map_err(|_| invalid("expected an integer"))?);
+        }
+        "scrub-env" => {
+            sandbox.scrub_env = value
+                .parse()
+                .map_err(|_| invalid("expected true or false"))?;
+        }
+        "restrict-commands" => {
+            sandbox.restrict_commands = value
+                .parse()
+                .map_err(|_| invalid("expected true or false"))?;
+        }
+        "allowed-commands" => sandbox.allowed_commands.push(value.to_string()),
+        other => {
+            return Err(format!(
+                "unknown sandbox option `{other}` (supported: max-memory-mb, max-output-bytes, \
+                 scrub-env, restrict-commands, allowed-commands)"
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// The sandbox policy for a config loaded from `source`, whose own
+/// `[external-sandbox]` table is `loaded`. `sets_sandbox` says whether the
+/// config files actually contain that table.
+pub(super) fn sandbox_policy(
+    loaded: ExternalSandboxConfig,
+    source: &ConfigSource,
+    sets_sandbox: bool,
+) -> ExternalSandboxConfig {
+    let mut sandbox = match source {
+        ConfigSource::Explicit(_) | ConfigSource::Global(_) => loaded,
+        ConfigSource::Discovered(path) => {
+            if sets_sandbox {
+                log::warn!(
+                    "Ignoring `[external-sandbox]` in {}: the sandbox can only be set in the \
+                     user config, with --sandbox, or with PANACHE_SANDBOX",
+                    path.display()
+                );
+            }
+            user_sandbox()
+        }
+        ConfigSource::None => ExternalSandboxConfig::default(),
+    };
+    apply_sandbox_overrides(&mut sandbox);
+    sandbox
+}
+
+/// The `[external-sandbox]` table of the user config, if there is one.
+fn user_sandbox() -> ExternalSandboxConfig {
+    super::xdg_config_path()
+        .and_then(|path| super::read_config_with_chain(&path).ok())
+        .map(|(config, _, _)| config.external_sandbox)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn options_parse_and_reject_unknown_keys() {
+        let mut sandbox = ExternalSandboxConfig::default();
+        apply_sandbox_option(&mut sandbox, "max-memory-mb", "512").unwrap();
+        apply_sandbox_option(&mut sandbox, "restrict-commands", "true").unwrap();
+        apply_sandbox_option(&mut sandbox, "allowed-commands", "air").unwrap();
+        apply_sandbox_option(&mut sandbox, "allowed-commands", "ruff").unwrap();
+        assert_eq!(sandbox.max_memory_mb, Some(512));
+        assert!(sandbox.restrict_commands);
+        assert_eq!(sandbox.allowed_commands, ["air", "ruff"]);
+
+        assert!(apply_sandbox_option(&mut sandbox, "scrub-env", "maybe").is_err());
+        assert!(apply_sandbox_option(&mut sandbox, "max-memory", "1").is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use schemars::JsonSchema;
 use serde::{Deserialize, Deserializer, Serialize};
@@ -19,6 +19,8 @@ pub struct FormatterConfig {
     pub args: Vec<String>,
     /// Whether the formatter reads from stdin (true) or requires a file path (false)
     pub stdin: bool,
+    /// Per-invocation timeout in milliseconds (None = the default of 30 seconds)
+    pub timeout_ms: Option<u64>,
 }
 
 /// NEW: Language → Formatter mapping value (single formatter or chain)
@@ -64,6 +66,8 @@ pub struct FormatterDefinition {
     pub append_args: Option<Vec<String>>,
     /// Whether the formatter reads from stdin (None = inherit from preset if name matches)
     pub stdin: Option<bool>,
+    /// Per-invocation timeout in milliseconds (None = inherit from preset, or 30 seconds)
+    pub timeout_ms: Option<u64>,
 }
 
 /// Internal struct for deserializing FormatterConfig with preset support.
@@ -78,6 +82,9 @@ struct RawFormatterConfig {
     args: Option<Vec<String>>,
     /// Whether the formatter reads from stdin
     stdin: bool,
+    /// Per-invocation timeout in milliseconds
+    #[serde(rename = "timeout-ms")]
+    timeout_ms: Option<u64>,
 }
 
 impl Default for RawFormatterConfig {
@@ -87,6 +94,7 @@ impl Default for RawFormatterConfig {
             cmd: None,
             args: None,
             stdin: true,
+            timeout_ms: None,
         }
     }
 }
@@ -119,6 +127,7 @@ impl<'de> Deserialize<'de> for FormatterConfig {
                 cmd: preset.cmd,
                 args: preset.args,
                 stdin: preset.stdin,
+                timeout_ms: raw.timeout_ms,
             })
         } else if let Some(cmd) = raw.cmd {
             // Custom configuration
@@ -126,6 +135,7 @@ impl<'de> Deserialize<'de> for FormatterConfig {
                 cmd,
                 args: raw.args.unwrap_or_default(),
                 stdin: raw.stdin,
+                timeout_ms: raw.timeout_ms,
             })
         } else {
             // No preset and no cmd - return empty config
@@ -134,6 +144,7 @@ impl<'de> Deserialize<'de> for FormatterConfig {
                 cmd: String::new(),
                 args: raw.args.unwrap_or_default(),
                 stdin: raw.stdin,
+                timeout_ms: raw.timeout_ms,
             })
         }
     }
@@ -145,6 +156,7 @@ impl Default for FormatterConfig {
             cmd: String::new(),
             args: Vec::new(),
             stdin: true,
+            timeout_ms: None,
         }
     }
 }
@@ -201,7 +213,7 @@ pub fn default_formatters() -> HashMap<String, FormatterConfig> {
 }
 
 /// Style for formatting math delimiters
-#[derive(Debug, Clone, Copy, Deserialize, JsonSchema, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Default)]
 #[serde(rename_all = "kebab-case")]
 pub enum MathDelimiterStyle {
     /// Preserve original delimiter style (\(...\) stays \(...\), $...$ stays $...$)
@@ -214,7 +226,7 @@ pub enum MathDelimiterStyle {
 }
 
 /// Tab stop handling for formatter output.
-#[derive(Debug, Clone, Copy, Deserialize, JsonSchema, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Default)]
 #[serde(rename_all = "kebab-case")]
 pub enum TabStopMode {
     /// Normalize tabs to spaces (4-column tab stop).
@@ -244,6 +256,174 @@ where
     Ok(value)
 }
 
+fn deserialize_definition_spaces<'de, D>(deserializer: D) -> Result<usize, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = usize::deserialize(deserializer)?;
+    if !(1..=3).contains(&value) {
+        return Err(serde::de::Error::custom(format!(
+            "definition-lists.spaces must be 1, 2, or 3 (got {value})"
+        )));
+    }
+    Ok(value)
+}
+
+fn deserialize_toc_level<'de, D>(deserializer: D) -> Result<usize, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = usize::deserialize(deserializer)?;
+    if !(1..=6).contains(&value) {
+        return Err(serde::de::Error::custom(format!(
+            "toc levels must be between 1 and 6 (got {value})"
+        )));
+    }
+    Ok(value)
+}
+
+fn deserialize_tab_width<'de, D>(deserializer: D) -> Result<usize, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = usize::deserialize(deserializer)?;
+    if value == 0 {
+        return Err(serde::de::Error::custom("tab-width must be at least 1"));
+    }
+    Ok(value)
+}
+
+fn deserialize_marker_spacing<'de, D>(deserializer: D) -> Result<usize, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = usize::deserialize(deserializer)?;
+    if value > 1 {
+        return Err(serde::de::Error::custom(format!(
+            "blockquotes.marker-spacing must be 0 or 1 (got {value})"
+        )));
+    }
+    Ok(value)
+}
+
+/// Block quote layout (`[format.blockquotes]`).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct BlockquoteStyle {
+    /// Spaces after each `>` (0 or 1)
+    #[serde(deserialize_with = "deserialize_marker_spacing")]
+    #[schemars(range(min = 0, max = 1))]
+    pub marker_spacing: usize,
+    /// `true` writes `>` on lazy continuation lines; `false` keeps them lazy
+    /// under `wrap = "preserve"`
+    pub collapse_lazy: bool,
+}
+
+impl Default for BlockquoteStyle {
+    fn default() -> Self {
+        Self {
+            marker_spacing: 1,
+            collapse_lazy: true,
+        }
+    }
+}
+
+/// Where a code block's language goes and how its attributes are ordered.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AttributeStyle {
+    /// Language outside the braces: `python {.numberLines}`
+    #[default]
+    Shortcut,
+    /// Language as the first class inside the braces: `{.python .numberLines}`
+    Explicit,
+    /// Inside the braces in Pandoc's order: identifier, classes, key-value
+    /// pairs
+    Canonical,
+    /// Info strings as written
+    Preserve,
+}
+
+/// Code block info strings (`[format.code-blocks]`).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Default)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct CodeBlockStyle {
+    /// Where the language goes and how attributes are ordered: `shortcut`,
+    /// `explicit`, `canonical`, or `preserve`
+    pub attribute_style: AttributeStyle,
+    /// Lowercase the language of display code blocks (`Python` to `python`)
+    pub lowercase_language: bool,
+}
+
+/// Definition list layout (`[format.definition-lists]`).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct DefinitionListStyle {
+    /// Marker that opens each definition: `:` or `~`
+    pub marker: DefinitionMarker,
+    /// Spaces between the marker and the definition text (1--3). Continuation
+    /// lines stay indented four columns.
+    #[serde(deserialize_with = "deserialize_definition_spaces")]
+    #[schemars(range(min = 1, max = 3))]
+    pub spaces: usize,
+    /// `true` drops the blank line between a term and its definitions, `false`
+    /// always writes one. Unset keeps each item as written.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compact: Option<bool>,
+}
+
+impl Default for DefinitionListStyle {
+    fn default() -> Self {
+        Self {
+            marker: DefinitionMarker::Colon,
+            spaces: 3,
+            compact: None,
+        }
+    }
+}
+
+/// Heading text (`[format.headings]`).
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct HeadingStyle {
+    /// Section numbers written in heading text (`## 2.3 Methods`): `preserve`,
+    /// `renumber` from heading order and level, or `strip`
+    pub numbering: HeadingNumbering,
+}
+
+/// Tables of contents between `<!-- panache-toc start -->` and
+/// `<!-- panache-toc end -->` (`[format.toc]`).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct TocStyle {
+    /// Shallowest heading level listed (1-6)
+    #[serde(deserialize_with = "deserialize_toc_level")]
+    #[schemars(range(min = 1, max = 6))]
+    pub min_level: usize,
+    /// Deepest heading level listed (1-6)
+    #[serde(deserialize_with = "deserialize_toc_level")]
+    #[schemars(range(min = 1, max = 6))]
+    pub max_level: usize,
+}
+
+impl Default for TocStyle {
+    fn default() -> Self {
+        Self {
+            min_level: 2,
+            max_level: 3,
+        }
+    }
+}
+
+/// Fenced div layout (`[format.divs]`).
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct DivStyle {
+    /// Colons on each fence: `"depth-based"` (3, plus 2 per nesting level),
+    /// `"preserve"` (as written), or `{ fixed = n }`
+    pub fence_length: DivFenceLength,
+}
+
 /// User-supplied no-break abbreviations for sentence wrapping.
 ///
 /// Accepts either a flat list applied to every document, or a table keyed by
@@ -304,10 +484,26 @@ pub struct StyleConfig {
     /// Line ending style. `None` falls back to the deprecated top-level
     /// `line-ending`, then `auto`.
     pub line_ending: Option<LineEnding>,
+    /// Whether output always ends with a newline (`ensure`) or only when the
+    /// input did (`preserve`)
+    pub final_newline: FinalNewline,
+    /// Strip trailing whitespace from every line, code blocks included,
+    /// except spaces that form a hard line break
+    pub trim_trailing_whitespace: bool,
     /// Text wrapping mode
     pub wrap: Option<WrapMode>,
+    /// Never overflow `line-width` when reflowing: split words that are too
+    /// long for a line and move long link destinations to reference
+    /// definitions
+    pub max_width_strict: bool,
     /// Blank line handling between blocks
     pub blank_lines: BlankLines,
+    /// Blocks that always get a blank line before and after them: `headings`,
+    /// `code`, `lists` (default: none)
+    pub blank_lines_around: Vec<BlankLinesAround>,
+    /// Exact number of blank lines before each heading (at least 1). Unset
+    /// leaves them to `blank-lines`.
+    pub blank_lines_before_heading: Option<usize>,
     /// Math delimiter style preference
     pub math_delimiter_style: MathDelimiterStyle,
     /// Math indentation (spaces)
@@ -317,12 +513,57 @@ pub struct StyleConfig {
     #[serde(deserialize_with = "deserialize_table_indent")]
     #[schemars(range(min = 0, max = 3))]
     pub table_indent: usize,
+    /// Width budget for tables: pipe tables that would be wider are written
+    /// without column padding, and captions wrap to it. `None` leaves pipe
+    /// tables aligned and wraps captions at `line-width`.
+    pub table_max_width: Option<usize>,
+    /// Width budget for hashpipe (`#|`) option comments in executable chunks.
+    /// `None` uses `line-width`.
+    pub code_comment_width: Option<usize>,
     /// Tab stop handling (normalize or preserve)
     pub tab_stops: TabStopMode,
-    /// Tab width for expanding tabs when normalizing
+    /// Shorthand for `tab-stops`: `true` is `normalize`, `false` is
+    /// `preserve`. Wins over `tab-stops` when both are set.
+    pub convert_tabs: Option<bool>,
+    /// Columns between tab stops, used both to measure tab indentation when
+    /// parsing and to expand tabs when normalizing
+    #[serde(deserialize_with = "deserialize_tab_width")]
+    #[schemars(range(min = 1))]
     pub tab_width: usize,
     /// Horizontal rule rendering: expanded to the line width or compact `---`
     pub horizontal_rule_style: HorizontalRuleStyle,
+    /// Emphasis delimiter: `asterisk` (`*text*`), `underscore` (`_text_`), or
+    /// `preserve`
+    pub emphasis_marker: EmphasisMarker,
+    /// Strong emphasis delimiter: `asterisk` (`**text**`), `underscore`
+    /// (`__text__`), or `preserve`
+    pub strong_marker: EmphasisMarker,
+    /// Backslash escapes: `preserve` as written, or `minimal` to drop the ones
+    /// that change nothing
+    pub escaping: Escaping,
+    /// Inline link and image destinations: `preserve` as written,
+    /// `angle-brackets` around ones with spaces, or `percent-encode` them
+    pub link_destinations: LinkDestinations,
+    /// Bare URLs under `autolink-bare-uris`: `preserve` as written, or turn
+    /// them into an `autolink` (`<url>`) or an inline `link` (`[url](url)`)
+    pub bare_urls: BareUrls,
+    /// Inline constructs reflow may break a line inside: `link-text`,
+    /// `image-alt`, `emphasis`, `span` (default: all). Inline code, math,
+    /// citations, and URLs are never broken.
+    pub allow_breaks_in: Vec<BreakableInline>,
+    /// Block quote marker spacing and lazy continuation lines
+    pub blockquotes: BlockquoteStyle,
+    /// Code block language placement, attribute order, and language case
+    pub code_blocks: CodeBlockStyle,
+    /// Definition list marker (`:` or `~`), spaces after it, and whether
+    /// items are compact or loose
+    pub definition_lists: DefinitionListStyle,
+    /// Fenced div fence length
+    pub divs: DivStyle,
+    /// Section numbers in heading text
+    pub headings: HeadingStyle,
+    /// Heading levels listed in tables of contents
+    pub toc: TocStyle,
     /// Use panache-native greedy wrapping instead of textwrap.
     pub built_in_greedy_wrap: bool,
     /// Extra abbreviations whose trailing period must not end a sentence (used
@@ -338,14 +579,34 @@ impl Default for StyleConfig {
         Self {
             line_width: None,
             line_ending: None,
+            final_newline: FinalNewline::Ensure,
+            trim_trailing_whitespace: false,
             wrap: Some(WrapMode::Reflow),
+            max_width_strict: false,
             blank_lines: BlankLines::Collapse,
+            blank_lines_around: Vec::new(),
+            blank_lines_before_heading: None,
             math_delimiter_style: MathDelimiterStyle::default(),
             math_indent: 2,
             table_indent: DEFAULT_TABLE_INDENT,
+            table_max_width: None,
+            code_comment_width: None,
             tab_stops: TabStopMode::Normalize,
+            convert_tabs: None,
             tab_width: 4,
             horizontal_rule_style: HorizontalRuleStyle::LineWidth,
+            emphasis_marker: EmphasisMarker::Asterisk,
+            strong_marker: EmphasisMarker::Asterisk,
+            escaping: Escaping::Preserve,
+            link_destinations: LinkDestinations::Preserve,
+            bare_urls: BareUrls::Preserve,
+            allow_breaks_in: BreakableInline::ALL.to_vec(),
+            blockquotes: BlockquoteStyle::default(),
+            code_blocks: CodeBlockStyle::default(),
+            definition_lists: DefinitionListStyle::default(),
+            divs: DivStyle::default(),
+            headings: HeadingStyle::default(),
+            toc: TocStyle::default(),
             built_in_greedy_wrap: true,
             no_break_abbreviations: None,
             lang: None,
@@ -373,12 +634,55 @@ pub struct ExperimentalConfig {
     pub format_math: bool,
 }
 
+/// Section rewrites applied by `panache organize` (`[organize]`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct OrganizeConfig {
+    /// Headings whose subsections are sorted alphabetically, as `"## FAQ"`
+    /// (level 2 only) or `"FAQ"` (any level)
+    pub sort_headings_under: Vec<String>,
+}
+
+/// Limits for spawned external formatter processes (`[external-sandbox]`).
+///
+/// Meant for teams running panache on repositories whose `panache.toml` they
+/// do not fully control: a repo-provided config can otherwise point
+/// `[formatters]` at any command. Every limit is off by default. The table is
+/// only read from the user config or an explicit `--config`; see
+/// [`set_sandbox_overrides`](crate::config::set_sandbox_overrides) for the
+/// command-line limits.
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema, PartialEq)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ExternalSandboxConfig {
+    /// Cap each formatter's virtual memory, in megabytes. Applied with
+    /// `ulimit -v` through `sh`, so it only takes effect on Unix.
+    pub max_memory_mb: Option<u64>,
+    /// Discard formatter output larger than this many bytes and leave the code
+    /// block unchanged.
+    pub max_output_bytes: Option<usize>,
+    /// Spawn formatters with a scrubbed environment: only `PATH`, `HOME`,
+    /// locale, and temp-directory variables are passed through, so proxy
+    /// settings and credentials never reach the formatter.
+    pub scrub_env: bool,
+    /// Refuse to run formatter commands that are neither a built-in preset
+    /// invocation (same command and args) nor listed in `allowed-commands`.
+    pub restrict_commands: bool,
+    /// Additional commands permitted when `restrict-commands` is enabled.
+    /// Matched exactly against the configured `cmd`.
+    pub allowed_commands: Vec<String>,
+}
+
 /// Linter configuration.
-/// Preferred shape is `[lint.rules] rule-name = true/false`.
-/// Legacy `[lint] rule-name = true/false` is still supported (deprecated).
+/// Rules are toggled with `[lint.rules] rule-name = true/false`, or configured
+/// as a table (`[lint.rules.rule-name] enabled = true, fix = false`) to keep a
+/// rule's diagnostics while opting it out of auto-fixes.
 #[derive(Debug, Clone, Serialize, PartialEq, Default)]
 pub struct LintConfig {
     pub rules: HashMap<String, bool>,
+    /// Per-rule auto-fix toggles from `[lint.rules.<rule>] fix = ...`. Rules
+    /// absent here keep their fixes.
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub fixes: HashMap<String, bool>,
     /// Resolved Quarto compatibility target for the `quarto-schema` rule.
     ///
     /// This is populated from `[compat] quarto` during config finalization, not
@@ -386,6 +690,398 @@ pub struct LintConfig {
     /// only consumer. See [`CompatConfig::quarto`].
     #[serde(rename = "quarto-version", skip_serializing_if = "Option::is_none")]
     pub quarto_version: Option<String>,
+    /// Options for the `heading-hierarchy` rule, from the extra keys of
+    /// `[lint.rules.heading-hierarchy]`.
+    #[serde(
+        rename = "heading-hierarchy",
+        skip_serializing_if = "HeadingHierarchyOptions::is_default"
+    )]
+    pub heading_hierarchy: HeadingHierarchyOptions,
+    /// Options for the `heading-case` rule, from the extra keys of
+    /// `[lint.rules.heading-case]`.
+    #[serde(
+        rename = "heading-case",
+        skip_serializing_if = "HeadingCaseOptions::is_default"
+    )]
+    pub heading_case: HeadingCaseOptions,
+    /// Options for the `heading-length` rule, from the extra keys of
+    /// `[lint.rules.heading-length]`.
+    #[serde(
+        rename = "heading-length",
+        skip_serializing_if = "HeadingLengthOptions::is_default"
+    )]
+    pub heading_length: HeadingLengthOptions,
+    /// Options for the `heading-punctuation` rule, from the extra keys of
+    /// `[lint.rules.heading-punctuation]`.
+    #[serde(
+        rename = "heading-punctuation",
+        skip_serializing_if = "HeadingPunctuationOptions::is_default"
+    )]
+    pub heading_punctuation: HeadingPunctuationOptions,
+    /// Options for the `spelling` rule, from the extra keys of
+    /// `[lint.rules.spelling]`.
+    #[serde(
+        rename = "spelling",
+        skip_serializing_if = "SpellingOptions::is_default"
+    )]
+    pub spelling: SpellingOptions,
+    /// Options for the `undefined-references` rule, from the extra keys of
+    /// `[lint.rules.undefined-references]`.
+    #[serde(
+        rename = "undefined-references",
+        skip_serializing_if = "UndefinedReferencesOptions::is_default"
+    )]
+    pub undefined_references: UndefinedReferencesOptions,
+}
+
+/// Options for the `heading-hierarchy` lint rule:
+///
+/// ```toml
+/// [lint.rules.heading-hierarchy]
+/// allow-multiple-h1 = false
+/// start-level = 2
+/// ignore-in-divs = true
+/// ```
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct HeadingHierarchyOptions {
+    /// Allow more than one level-1 heading (default: true).
+    pub allow_multiple_h1: bool,
+    /// Level the document's headings start at (1-6). When set, the first
+    /// heading may not be deeper than this and no heading may be shallower.
+    pub start_level: Option<usize>,
+    /// Skip headings inside fenced divs, including Quarto callouts
+    /// (default: false).
+    pub ignore_in_divs: bool,
+}
+
+impl Default for HeadingHierarchyOptions {
+    fn default() -> Self {
+        Self {
+            allow_multiple_h1: true,
+            start_level: None,
+            ignore_in_divs: false,
+        }
+    }
+}
+
+impl HeadingHierarchyOptions {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Apply one option key from `[lint.rules.heading-hierarchy]`. Returns
+    /// `Ok(false)` for keys that are not heading-hierarchy options.
+    fn set(&mut self, key: &str, value: &toml::Value) -> Result<bool, String> {
+        match key {
+            "allow-multiple-h1" | "ignore-in-divs" => {
+                let flag = value.as_bool().ok_or_else(|| {
+                    format!("[lint.rules.heading-hierarchy] {key} must be true or false")
+                })?;
+                if key == "allow-multiple-h1" {
+                    self.allow_multiple_h1 = flag;
+                } else {
+                    self.ignore_in_divs = flag;
+                }
+            }
+            "start-level" => {
+                let level = value
+                    .as_integer()
+                    .filter(|level| (1..=6).contains(level))
+                    .ok_or_else(|| {
+                        "[lint.rules.heading-hierarchy] start-level must be an integer from 1 to 6"
+                            .to_string()
+                    })?;
+                self.start_level = Some(level as usize);
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+/// Options for the opt-in `heading-length` lint rule:
+///
+/// ```toml
+/// [lint.rules.heading-length]
+/// enabled = true
+/// max-length = 60
+/// ```
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct HeadingLengthOptions {
+    /// Longest allowed heading text, in display columns. `None` uses
+    /// `[format] line-width`, since the formatter never wraps headings.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<usize>,
+}
+
+impl HeadingLengthOptions {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Apply one option key from `[lint.rules.heading-length]`. Returns
+    /// `Ok(false)` for keys that are not heading-length options.
+    fn set(&mut self, key: &str, value: &toml::Value) -> Result<bool, String> {
+        if key != "max-length" {
+            return Ok(false);
+        }
+        let length = value
+            .as_integer()
+            .filter(|length| *length >= 1)
+            .ok_or_else(|| {
+                "[lint.rules.heading-length] max-length must be a positive integer".to_string()
+            })?;
+        self.max_length = Some(length as usize);
+        Ok(true)
+    }
+}
+
+/// Capitalization style enforced by the `heading-case` lint rule.
+#[derive(Debug, Clone, Copy, Default, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HeadingCase {
+    /// Only the first word (and proper nouns) capitalized.
+    #[default]
+    Sentence,
+    /// Every word capitalized except short articles, conjunctions, and
+    /// prepositions inside the heading.
+    Title,
+}
+
+/// Options for the opt-in `heading-case` lint rule:
+///
+/// ```toml
+/// [lint.rules.heading-case]
+/// enabled = true
+/// style = "title"
+/// words = ["Quarto", "macOS"]
+/// ```
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct HeadingCaseOptions {
+    /// Case style headings must follow (default: sentence).
+    pub style: HeadingCase,
+    /// Words kept exactly as written here, such as proper nouns.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub words: Vec<String>,
+}
+
+impl HeadingCaseOptions {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Apply one option key from `[lint.rules.heading-case]`. Returns
+    /// `Ok(false)` for keys that are not heading-case options.
+    fn set(&mut self, key: &str, value: &toml::Value) -> Result<bool, String> {
+        match key {
+            "style" => {
+                self.style = match value.as_str() {
+                    Some("sentence") => HeadingCase::Sentence,
+                    Some("title") => HeadingCase::Title,
+                    _ => {
+                        return Err(
+                            "[lint.rules.heading-case] style must be \"sentence\" or \"title\""
+                                .to_string(),
+                        );
+                    }
+                };
+            }
+            "words" => {
+                self.words = string_array(value).ok_or_else(|| {
+                    "[lint.rules.heading-case] words must be an array of strings".to_string()
+                })?;
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+/// Options for the opt-in `heading-punctuation` lint rule:
+///
+/// ```toml
+/// [lint.rules.heading-punctuation]
+/// enabled = true
+/// punctuation = ".,;:"
+/// ```
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct HeadingPunctuationOptions {
+    /// Characters a heading may not end with (default: `.,;:!` and their
+    /// full-width forms).
+    pub punctuation: String,
+}
+
+impl Default for HeadingPunctuationOptions {
+    fn default() -> Self {
+        Self {
+            punctuation: ".,;:!。，；：！".to_string(),
+        }
+    }
+}
+
+impl HeadingPunctuationOptions {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Apply one option key from `[lint.rules.heading-punctuation]`. Returns
+    /// `Ok(false)` for keys that are not heading-punctuation options.
+    fn set(&mut self, key: &str, value: &toml::Value) -> Result<bool, String> {
+        if key != "punctuation" {
+            return Ok(false);
+        }
+        self.punctuation = value
+            .as_str()
+            .ok_or_else(|| {
+                "[lint.rules.heading-punctuation] punctuation must be a string".to_string()
+            })?
+            .to_string();
+        Ok(true)
+    }
+}
+
+/// Spell checker run by the `spelling` lint rule.
+#[derive(Debug, Clone, Copy, Default, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SpellChecker {
+    #[default]
+    Hunspell,
+    Aspell,
+}
+
+impl SpellChecker {
+    /// The checker's executable.
+    pub fn command(self) -> &'static str {
+        match self {
+            Self::Hunspell => "hunspell",
+            Self::Aspell => "aspell",
+        }
+    }
+}
+
+/// Options for the opt-in `spelling` lint rule:
+///
+/// ```toml
+/// [lint.rules.spelling]
+/// enabled = true
+/// checker = "hunspell"
+/// language = "en_GB"
+/// words = ["panache", "Quarto"]
+/// ```
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct SpellingOptions {
+    /// External spell checker to run (default: hunspell).
+    pub checker: SpellChecker,
+    /// Dictionary passed to the checker (default: `en_US`).
+    pub language: String,
+    /// Project words the checker should accept, matched case-insensitively.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub words: Vec<String>,
+}
+
+impl Default for SpellingOptions {
+    fn default() -> Self {
+        Self {
+            checker: SpellChecker::default(),
+            language: "en_US".to_string(),
+            words: Vec::new(),
+        }
+    }
+}
+
+impl SpellingOptions {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Apply one option key from `[lint.rules.spelling]`. Returns `Ok(false)`
+    /// for keys that are not spelling options.
+    fn set(&mut self, key: &str, value: &toml::Value) -> Result<bool, String> {
+        match key {
+            "checker" => {
+                self.checker = match value.as_str() {
+                    Some("hunspell") => SpellChecker::Hunspell,
+                    Some("aspell") => SpellChecker::Aspell,
+                    _ => {
+                        return Err(
+                            "[lint.rules.spelling] checker must be \"hunspell\" or \"aspell\""
+                                .to_string(),
+                        );
+                    }
+                };
+            }
+            "language" => {
+                self.language = value
+                    .as_str()
+                    .filter(|language| !language.trim().is_empty())
+                    .ok_or_else(|| {
+                        "[lint.rules.spelling] language must be a dictionary name".to_string()
+                    })?
+                    .trim()
+                    .to_string();
+            }
+            "words" => {
+                self.words = string_array(value).ok_or_else(|| {
+                    "[lint.rules.spelling] words must be an array of strings".to_string()
+                })?;
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+/// `value` as a list of strings, or `None` if it is anything else.
+fn string_array(value: &toml::Value) -> Option<Vec<String>> {
+    value
+        .as_array()?
+        .iter()
+        .map(|item| item.as_str().map(str::to_string))
+        .collect()
+}
+
+/// Options for the `undefined-references` lint rule:
+///
+/// ```toml
+/// [lint.rules.undefined-references]
+/// project = false
+/// ```
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct UndefinedReferencesOptions {
+    /// Resolve cross-references against the other documents of the enclosing
+    /// Quarto or bookdown project, not just the document itself (default:
+    /// true).
+    pub project: bool,
+}
+
+impl Default for UndefinedReferencesOptions {
+    fn default() -> Self {
+        Self { project: true }
+    }
+}
+
+impl UndefinedReferencesOptions {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Apply one option key from `[lint.rules.undefined-references]`. Returns
+    /// `Ok(false)` for keys that are not undefined-references options.
+    fn set(&mut self, key: &str, value: &toml::Value) -> Result<bool, String> {
+        if key != "project" {
+            return Ok(false);
+        }
+        self.project = value.as_bool().ok_or_else(|| {
+            "[lint.rules.undefined-references] project must be true or false".to_string()
+        })?;
+        Ok(true)
+    }
 }
 
 impl LintConfig {
@@ -399,6 +1095,11 @@ impl LintConfig {
             .into_iter()
             .map(|(name, enabled)| (Self::normalize_rule_name(&name), enabled))
             .collect();
+        self.fixes = self
+            .fixes
+            .into_iter()
+            .map(|(name, fix)| (Self::normalize_rule_name(&name), fix))
+            .collect();
         self
     }
 
@@ -414,6 +1115,14 @@ impl LintConfig {
         let normalized = Self::normalize_rule_name(rule_name);
         self.rules.get(&normalized).copied().unwrap_or(false)
     }
+
+    /// Whether `--fix` (and editor fix actions) may apply the rule's fixes.
+    /// Defaults to `true`; `[lint.rules.<rule>] fix = false` keeps the
+    /// diagnostic but drops its fix.
+    pub fn is_rule_fix_enabled(&self, rule_name: &str) -> bool {
+        let normalized = Self::normalize_rule_name(rule_name);
+        self.fixes.get(&normalized).copied().unwrap_or(true)
+    }
 }
 
 impl JsonSchema for LintConfig {
@@ -433,9 +1142,183 @@ impl JsonSchema for LintConfig {
             "properties": {
                 "rules": {
                     "type": "object",
-                    "description": "Map of lint rule names to enabled/disabled. \
-                                    Preferred over the legacy flat `[lint]` shape.",
-                    "additionalProperties": { "type": "boolean" },
+                    "description": "Map of lint rule names to enabled/disabled, or to a \
+                                    table with `enabled` and `fix` toggles. Preferred \
+                                    over the legacy flat `[lint]` shape.",
+                    "properties": {
+                        "heading-hierarchy": {
+                            "anyOf": [
+                                { "type": "boolean" },
+                                {
+                                    "type": "object",
+                                    "properties": {
+                                        "enabled": { "type": "boolean", "default": true },
+                                        "fix": { "type": "boolean", "default": true },
+                                        "allow-multiple-h1": {
+                                            "type": "boolean",
+                                            "description": "Allow more than one level-1 heading.",
+                                            "default": true,
+                                        },
+                                        "start-level": {
+                                            "type": "integer",
+                                            "minimum": 1,
+                                            "maximum": 6,
+                                            "description": "Level the document's headings \
+                                                            start at. The first heading may \
+                                                            not be deeper and no heading may \
+                                                            be shallower.",
+                                        },
+                                        "ignore-in-divs": {
+                                            "type": "boolean",
+                                            "description": "Skip headings inside fenced divs \
+                                                            and Quarto callouts.",
+                                            "default": false,
+                                        },
+                                    },
+                                    "additionalProperties": false,
+                                },
+                            ],
+                        },
+                        "heading-length": {
+                            "anyOf": [
+                                { "type": "boolean" },
+                                {
+                                    "type": "object",
+                                    "properties": {
+                                        "enabled": { "type": "boolean", "default": false },
+                                        "fix": { "type": "boolean", "default": true },
+                                        "max-length": {
+                                            "type": "integer",
+                                            "minimum": 1,
+                                            "description": "Longest allowed heading text, in \
+                                                            display columns. Defaults to \
+                                                            `[format] line-width`.",
+                                        },
+                                    },
+                                    "additionalProperties": false,
+                                },
+                            ],
+                        },
+                        "heading-case": {
+                            "anyOf": [
+                                { "type": "boolean" },
+                                {
+                                    "type": "object",
+                                    "properties": {
+                                        "enabled": { "type": "boolean", "default": false },
+                                        "fix": { "type": "boolean", "default": true },
+                                        "style": {
+                                            "type": "string",
+                                            "enum": ["sentence", "title"],
+                                            "description": "Case style headings must follow.",
+                                            "default": "sentence",
+                                        },
+                                        "words": {
+                                            "type": "array",
+                                            "items": { "type": "string" },
+                                            "description": "Words kept exactly as written, \
+                                                            such as proper nouns.",
+                                        },
+                                    },
+                                    "additionalProperties": false,
+                                },
+                            ],
+                        },
+                        "heading-punctuation": {
+                            "anyOf": [
+                                { "type": "boolean" },
+                                {
+                                    "type": "object",
+                                    "properties": {
+                                        "enabled": { "type": "boolean", "default": false },
+                                        "fix": { "type": "boolean", "default": true },
+                                        "punctuation": {
+                                            "type": "string",
+                                            "description": "Characters a heading may not end \
+                                                            with.",
+                                            "default": ".,;:!。，；：！",
+                                        },
+                                    },
+                                    "additionalProperties": false,
+                                },
+                            ],
+                        },
+                        "spelling": {
+                            "anyOf": [
+                                { "type": "boolean" },
+                                {
+                                    "type": "object",
+                                    "properties": {
+                                        "enabled": { "type": "boolean", "default": false },
+                                        "fix": { "type": "boolean", "default": true },
+                                        "checker": {
+                                            "type": "string",
+                                            "enum": ["hunspell", "aspell"],
+                                            "description": "External spell checker to run.",
+                                            "default": "hunspell",
+                                        },
+                                        "language": {
+                                            "type": "string",
+                                            "description": "Dictionary passed to the spell \
+                                                            checker.",
+                                            "default": "en_US",
+                                        },
+                                        "words": {
+                                            "type": "array",
+                                            "items": { "type": "string" },
+                                            "description": "Project words to accept, matched \
+                                                            case-insensitively.",
+                                        },
+                                    },
+                                    "additionalProperties": false,
+                                },
+                            ],
+                        },
+                        "undefined-references": {
+                            "anyOf": [
+                                { "type": "boolean" },
+                                {
+                                    "type": "object",
+                                    "properties": {
+                                        "enabled": { "type": "boolean", "default": true },
+                                        "fix": { "type": "boolean", "default": true },
+                                        "project": {
+                                            "type": "boolean",
+                                            "description": "Resolve cross-references against \
+                                                            the other documents of the \
+                                                            enclosing Quarto or bookdown \
+                                                            project.",
+                                            "default": true,
+                                        },
+                                    },
+                                    "additionalProperties": false,
+                                },
+                            ],
+                        },
+                    },
+                    "additionalProperties": {
+                        "anyOf": [
+                            { "type": "boolean" },
+                            {
+                                "type": "object",
+                                "properties": {
+                                    "enabled": {
+                                        "type": "boolean",
+                                        "description": "Whether the rule runs.",
+                                        "default": true,
+                                    },
+                                    "fix": {
+                                        "type": "boolean",
+                                        "description": "Whether the rule's auto-fixes are \
+                                                        applied by `--fix` and editor fix \
+                                                        actions.",
+                                        "default": true,
+                                    },
+                                },
+                                "additionalProperties": false,
+                            },
+                        ],
+                    },
                 },
             },
             "additionalProperties": { "type": "boolean" },
@@ -450,6 +1333,13 @@ impl<'de> Deserialize<'de> for LintConfig {
     {
         let value = toml::Value::deserialize(deserializer)?;
         let mut rules = HashMap::new();
+        let mut fixes = HashMap::new();
+        let mut heading_hierarchy = HeadingHierarchyOptions::default();
+        let mut heading_case = HeadingCaseOptions::default();
+        let mut heading_length = HeadingLengthOptions::default();
+        let mut heading_punctuation = HeadingPunctuationOptions::default();
+        let mut spelling = SpellingOptions::default();
+        let mut undefined_references = UndefinedReferencesOptions::default();
 
         let mut table = value
             .as_table()
@@ -472,14 +1362,53 @@ impl<'de> Deserialize<'de> for LintConfig {
             let rules_table = rules_value
                 .as_table()
                 .ok_or_else(|| serde::de::Error::custom("[lint.rules] must be a table"))?;
-            for (name, enabled) in rules_table {
-                let enabled = enabled.as_bool().ok_or_else(|| {
-                    serde::de::Error::custom(format!(
-                        "[lint.rules] entry '{}' must be true or false",
+            for (name, entry) in rules_table {
+                if let Some(enabled) = entry.as_bool() {
+                    rules.insert(name.clone(), enabled);
+                    continue;
+                }
+                let Some(entry_table) = entry.as_table() else {
+                    return Err(serde::de::Error::custom(format!(
+                        "[lint.rules] entry '{}' must be true, false, or a table",
                         name
-                    ))
-                })?;
-                rules.insert(name.clone(), enabled);
+                    )));
+                };
+                let rule = LintConfig::normalize_rule_name(name);
+                for (key, value) in entry_table {
+                    let is_option = match rule.as_str() {
+                        "heading-hierarchy" => heading_hierarchy.set(key, value),
+                        "heading-case" => heading_case.set(key, value),
+                        "heading-length" => heading_length.set(key, value),
+                        "heading-punctuation" => heading_punctuation.set(key, value),
+                        "spelling" => spelling.set(key, value),
+                        "undefined-references" => undefined_references.set(key, value),
+                        _ => Ok(false),
+                    }
+                    .map_err(serde::de::Error::custom)?;
+                    if is_option {
+                        continue;
+                    }
+                    let flag = value.as_bool().ok_or_else(|| {
+                        serde::de::Error::custom(format!(
+                            "[lint.rules.{}] {} must be true or false",
+                            name, key
+                        ))
+                    })?;
+                    match key.as_str() {
+                        "enabled" => {
+                            rules.insert(name.clone(), flag);
+                        }
+                        "fix" => {
+                            fixes.insert(name.clone(), flag);
+                        }
+                        _ => {
+                            return Err(serde::de::Error::custom(format!(
+                                "Unsupported [lint.rules.{}] key '{}'; expected `enabled` or `fix`",
+                                name, key
+                            )));
+                        }
+                    }
+                }
             }
         }
 
@@ -493,8 +1422,15 @@ impl<'de> Deserialize<'de> for LintConfig {
 
         Ok(Self {
             rules,
+            fixes,
             // Populated later from `[compat] quarto` during finalization.
             quarto_version: None,
+            heading_hierarchy,
+            heading_case,
+            heading_length,
+            heading_punctuation,
+            spelling,
+            undefined_references,
         }
         .normalize())
     }
@@ -540,7 +1476,10 @@ struct RawConfig {
     #[serde(default)]
     #[allow(dead_code)]
     extend: Option<String>,
+    /// Markdown flavor, optionally with Pandoc-style extension modifiers
+    /// (`markdown+emoji-fancy_lists`).
     #[serde(default)]
+    #[schemars(schema_with = "schema_helpers::flavor_spec_schema")]
     flavor: Flavor,
     #[serde(default)]
     #[schemars(schema_with = "schema_helpers::extensions_schema")]
@@ -566,9 +1505,9 @@ struct RawConfig {
     #[serde(rename = "format")]
     format_section: Option<StyleConfig>,
 
-    /// DEPRECATED no-op: `blank-lines` is retained only so older configs keep
-    /// parsing and `check_deprecated_blank_lines` can warn. When no `[format]`
-    /// section is present this top-level value still feeds `blank_lines`.
+    /// DEPRECATED alias for `[format] blank-lines`, retained so older configs
+    /// keep parsing and `check_deprecated_blank_lines` can warn. It only feeds
+    /// `blank_lines` when no `[format]` section is present.
     #[serde(default = "default_blank_lines")]
     blank_lines: BlankLines,
     // Language → Formatter(s) mapping (parsed manually as a raw Value).
@@ -580,8 +1519,16 @@ struct RawConfig {
     #[serde(default)]
     external_max_parallel: Option<usize>,
 
+    /// Resource limits and command allowlist for external formatters.
     #[serde(default)]
-    linters: HashMap<String, String>,
+    external_sandbox: Option<ExternalSandboxConfig>,
+
+    #[serde(default)]
+    linters: BTreeMap<String, String>,
+    /// Code fence language aliases: fence label → language name used to look
+    /// up `formatters` and `linters` (e.g. `py3 = "python"`).
+    #[serde(default)]
+    languages: BTreeMap<String, String>,
     #[serde(default)]
     lint: Option<LintConfig>,
     #[serde(default)]
@@ -594,17 +1541,26 @@ struct RawConfig {
     exclude: Option<Vec<String>>,
     #[serde(default)]
     extend_exclude: Vec<String>,
+    /// Skip generated output directories (Quarto's `.quarto/`, `_freeze/`,
+    /// `_site/`, bookdown's `_book/`, ...) when traversing directories
+    /// (default: true). Applies on top of `exclude`.
+    #[serde(default)]
+    exclude_generated: Option<bool>,
     #[serde(default)]
     include: Option<Vec<String>>,
     #[serde(default)]
     extend_include: Vec<String>,
     #[serde(default)]
-    flavor_overrides: HashMap<String, Flavor>,
+    flavor_overrides: BTreeMap<String, Flavor>,
 
     /// Opt-in experimental features (`[experimental]`). Unstable surface.
     #[serde(default)]
     experimental: Option<ExperimentalConfig>,
 
+    /// Section rewrites applied by `panache organize` (`[organize]`).
+    #[serde(default)]
+    organize: Option<OrganizeConfig>,
+
     /// Extra cross-reference key prefixes for crossref-injecting extensions
     /// (e.g. pseudocode's `@algo-`). Keys with these prefixes parse as
     /// cross-references rather than citations.
@@ -691,6 +1647,9 @@ fn resolve_formatter_name(
                 if let Some(stdin) = definition.stdin {
                     base_config.stdin = stdin;
                 }
+                if definition.timeout_ms.is_some() {
+                    base_config.timeout_ms = definition.timeout_ms;
+                }
 
                 // Apply prepend_args and append_args modifiers
                 apply_arg_modifiers(&mut base_config.args, definition);
@@ -708,6 +1667,7 @@ fn resolve_formatter_name(
                     cmd: cmd.clone(),
                     args,
                     stdin: definition.stdin.unwrap_or(true),
+                    timeout_ms: definition.timeout_ms,
                 })
             }
             // Case 3: No preset, no cmd - error
@@ -829,18 +1789,46 @@ impl RawConfig {
                 self.flavor,
             ),
             line_ending,
+            final_newline: style.final_newline,
+            trim_trailing_whitespace: style.trim_trailing_whitespace,
             flavor: self.flavor,
             line_width,
             wrap: style.wrap,
+            max_width_strict: style.max_width_strict,
             blank_lines,
+            blank_lines_around: style.blank_lines_around,
+            blank_lines_before_heading: style.blank_lines_before_heading,
             horizontal_rule_style: style.horizontal_rule_style,
+            emphasis_marker: style.emphasis_marker,
+            strong_marker: style.strong_marker,
+            escaping: style.escaping,
+            link_destinations: style.link_destinations,
+            bare_urls: style.bare_urls,
+            allow_breaks_in: style.allow_breaks_in,
+            blockquotes: style.blockquotes,
+            code_blocks: style.code_blocks,
+            definition_lists: style.definition_lists,
+            divs: style.divs,
+            headings: style.headings,
+            toc: style.toc,
             math_delimiter_style: style.math_delimiter_style,
             math_indent: style.math_indent,
             table_indent: style.table_indent,
-            tab_stops: style.tab_stops,
+            table_max_width: style.table_max_width,
+            code_comment_width: style.code_comment_width,
+            tab_stops: match style.convert_tabs {
+                Some(true) => TabStopMode::Normalize,
+                Some(false) => TabStopMode::Preserve,
+                None => style.tab_stops,
+            },
             tab_width: style.tab_width,
             formatters: resolve_formatters(self.formatters),
             linters: self.linters,
+            languages: self
+                .languages
+                .into_iter()
+                .map(|(alias, language)| (normalize_language_label(&alias), language))
+                .collect(),
             lint: {
                 let mut lint = self.lint.unwrap_or_default().normalize();
                 lint.quarto_version = compat.quarto;
@@ -851,16 +1839,20 @@ impl RawConfig {
             external_max_parallel: self
                 .external_max_parallel
                 .unwrap_or_else(default_external_max_parallel),
+            external_sandbox: self.external_sandbox.unwrap_or_default(),
             parser: resolved_pandoc_compat,
             built_in_greedy_wrap: style.built_in_greedy_wrap,
             no_break_abbreviations: style.no_break_abbreviations,
             lang: style.lang,
             exclude: self.exclude,
             extend_exclude: self.extend_exclude,
+            exclude_generated: self.exclude_generated.unwrap_or(true),
             include: self.include,
             extend_include: self.extend_include,
             flavor_overrides: self.flavor_overrides,
             experimental: self.experimental.unwrap_or_default(),
+            organize: self.organize.unwrap_or_default(),
+            fail_safe: false,
             crossref_prefixes: self.crossref_prefixes,
         }
     }
@@ -873,14 +1865,14 @@ impl RawConfig {
 /// `[formatters.<lang>]` config format was removed in 3.0.
 fn resolve_formatters(
     raw_formatters: Option<toml::Value>,
-) -> HashMap<String, Vec<FormatterConfig>> {
+) -> BTreeMap<String, Vec<FormatterConfig>> {
     let Some(value) = raw_formatters else {
-        return HashMap::new();
+        return BTreeMap::new();
     };
 
     let toml::Value::Table(table) = value else {
         eprintln!("Warning: Invalid formatters configuration - expected table");
-        return HashMap::new();
+        return BTreeMap::new();
     };
 
     resolve_formatter_table(table)
@@ -890,8 +1882,8 @@ fn resolve_formatters(
 /// `[formatters.air]` / `[formatters.isort]` definitions.
 fn resolve_formatter_table(
     table: toml::map::Map<String, toml::Value>,
-) -> HashMap<String, Vec<FormatterConfig>> {
-    let mut mappings = HashMap::new();
+) -> BTreeMap<String, Vec<FormatterConfig>> {
+    let mut mappings = BTreeMap::new();
     let mut definitions = HashMap::new();
 
     // First pass: separate mappings from definitions
@@ -931,7 +1923,7 @@ fn resolve_formatter_table(
     }
 
     // Second pass: resolve mappings using definitions
-    let mut resolved = HashMap::new();
+    let mut resolved = BTreeMap::new();
     for (lang, value) in mappings {
         match resolve_language_formatters(&lang, &value, &definitions) {
             Ok(configs) if !configs.is_empty() => {
@@ -954,22 +1946,66 @@ pub struct Config {
     pub extensions: Extensions,
     pub formatter_extensions: FormatterExtensions,
     pub line_ending: Option<LineEnding>,
+    /// Whether output always ends with a newline or only when the input did.
+    pub final_newline: FinalNewline,
+    /// Whether trailing whitespace is stripped from every line.
+    pub trim_trailing_whitespace: bool,
     pub line_width: usize,
     pub math_indent: usize,
     pub math_delimiter_style: MathDelimiterStyle,
     /// Indentation (columns) for top-level pipe, simple, and multiline tables.
     pub table_indent: usize,
+    /// Width budget for tables; `None` leaves pipe tables aligned.
+    pub table_max_width: Option<usize>,
+    /// Width budget for hashpipe option comments; `None` uses `line_width`.
+    pub code_comment_width: Option<usize>,
     pub tab_stops: TabStopMode,
     pub tab_width: usize,
     pub wrap: Option<WrapMode>,
+    /// Split long words and reference long links instead of overflowing.
+    pub max_width_strict: bool,
     pub blank_lines: BlankLines,
+    /// Blocks that always get a blank line before and after them.
+    pub blank_lines_around: Vec<BlankLinesAround>,
+    /// Exact number of blank lines before each heading.
+    pub blank_lines_before_heading: Option<usize>,
     /// Horizontal rule rendering: expanded to the line width or compact `---`.
     pub horizontal_rule_style: HorizontalRuleStyle,
+    /// Delimiter for emphasis (`*`, `_`, or as written).
+    pub emphasis_marker: EmphasisMarker,
+    /// Delimiter for strong emphasis (`**`, `__`, or as written).
+    pub strong_marker: EmphasisMarker,
+    /// Whether superfluous backslash escapes are kept or dropped.
+    pub escaping: Escaping,
+    /// How inline link and image destinations are written.
+    pub link_destinations: LinkDestinations,
+    /// Whether bare URLs are kept or turned into autolinks or inline links.
+    pub bare_urls: BareUrls,
+    /// Inline constructs reflow may break a line inside.
+    pub allow_breaks_in: Vec<BreakableInline>,
+    /// Block quote marker spacing and lazy continuation lines.
+    pub blockquotes: BlockquoteStyle,
+    /// Code block language placement, attribute order, and language case.
+    pub code_blocks: CodeBlockStyle,
+    /// Definition list marker, spacing, and compact vs. loose items.
+    pub definition_lists: DefinitionListStyle,
+    /// Fenced div fence length.
+    pub divs: DivStyle,
+    /// Section numbers in heading text.
+    pub headings: HeadingStyle,
+    /// Heading levels listed in tables of contents.
+    pub toc: TocStyle,
     /// Language → Formatter(s) mapping (supports multiple formatters per language)
-    pub formatters: HashMap<String, Vec<FormatterConfig>>,
-    pub linters: HashMap<String, String>,
+    pub formatters: BTreeMap<String, Vec<FormatterConfig>>,
+    pub linters: BTreeMap<String, String>,
+    /// Code fence language aliases (`[languages]`): lowercased fence label →
+    /// language name, applied before the built-in aliases in
+    /// [`Config::language_key`].
+    pub languages: BTreeMap<String, String>,
     /// Max parallel external tool invocations (formatters/linters) per document.
     pub external_max_parallel: usize,
+    /// Resource limits and command allowlist for external formatters.
+    pub external_sandbox: ExternalSandboxConfig,
     /// Compatibility target for ambiguous Pandoc behavior.
     pub parser: PandocCompat,
     /// Extra cross-reference key prefixes (top-level `crossref-prefixes`) for
@@ -989,11 +2025,19 @@ pub struct Config {
     pub lang: Option<String>,
     pub exclude: Option<Vec<String>>,
     pub extend_exclude: Vec<String>,
+    /// Whether [`crate::config::GENERATED_EXCLUDE_PATTERNS`] are excluded.
+    pub exclude_generated: bool,
     pub include: Option<Vec<String>>,
     pub extend_include: Vec<String>,
-    pub flavor_overrides: HashMap<String, Flavor>,
+    pub flavor_overrides: BTreeMap<String, Flavor>,
     /// Opt-in experimental features (`[experimental]`). Unstable surface.
     pub experimental: ExperimentalConfig,
+    /// Section rewrites applied by `panache organize` (`[organize]`).
+    pub organize: OrganizeConfig,
+    /// Emit a top-level block verbatim (with a warning) when formatting it
+    /// panics, instead of aborting. Runtime-only: set by `--fail-safe` or
+    /// [`ConfigBuilder::fail_safe`], never read from `panache.toml`.
+    pub fail_safe: bool,
 }
 
 impl<'de> Deserialize<'de> for Config {
@@ -1027,18 +2071,39 @@ impl Default for Config {
             extensions: Extensions::for_flavor(flavor),
             formatter_extensions: FormatterExtensions::for_flavor(flavor),
             line_ending: Some(LineEnding::Auto),
+            final_newline: FinalNewline::Ensure,
+            trim_trailing_whitespace: false,
             line_width: 80,
             math_indent: 2,
             math_delimiter_style: MathDelimiterStyle::default(),
             table_indent: DEFAULT_TABLE_INDENT,
+            table_max_width: None,
+            code_comment_width: None,
             tab_stops: TabStopMode::Normalize,
             tab_width: 4,
             wrap: Some(WrapMode::Reflow),
+            max_width_strict: false,
             blank_lines: BlankLines::Collapse,
+            blank_lines_around: Vec::new(),
+            blank_lines_before_heading: None,
             horizontal_rule_style: HorizontalRuleStyle::LineWidth,
-            formatters: HashMap::new(), // Opt-in: empty by default
-            linters: HashMap::new(),    // Opt-in: empty by default
+            emphasis_marker: EmphasisMarker::Asterisk,
+            strong_marker: EmphasisMarker::Asterisk,
+            escaping: Escaping::Preserve,
+            link_destinations: LinkDestinations::Preserve,
+            bare_urls: BareUrls::Preserve,
+            allow_breaks_in: BreakableInline::ALL.to_vec(),
+            blockquotes: BlockquoteStyle::default(),
+            code_blocks: CodeBlockStyle::default(),
+            definition_lists: DefinitionListStyle::default(),
+            divs: DivStyle::default(),
+            headings: HeadingStyle::default(),
+            toc: TocStyle::default(),
+            formatters: BTreeMap::new(), // Opt-in: empty by default
+            linters: BTreeMap::new(),    // Opt-in: empty by default
+            languages: BTreeMap::new(),
             external_max_parallel: default_external_max_parallel(),
+            external_sandbox: ExternalSandboxConfig::default(),
             parser: PandocCompat::default(),
             crossref_prefixes: Vec::new(),
             lint: LintConfig::default(),
@@ -1049,10 +2114,13 @@ impl Default for Config {
             lang: None,
             exclude: None,
             extend_exclude: Vec::new(),
+            exclude_generated: true,
             include: None,
             extend_include: Vec::new(),
-            flavor_overrides: HashMap::new(),
+            flavor_overrides: BTreeMap::new(),
             experimental: ExperimentalConfig::default(),
+            organize: OrganizeConfig::default(),
+            fail_safe: false,
         }
     }
 }
@@ -1065,22 +2133,145 @@ impl Config {
             extensions: self.extensions.clone(),
             pandoc_compat: self.parser,
             crossref_prefixes: self.crossref_prefixes.clone(),
+            tab_stop: self.tab_width,
             refdef_labels: None,
         }
     }
+
+    /// The language a code fence label stands for, used to match code blocks
+    /// against `formatters` and `linters` keys. The label is lowercased (a
+    /// leading `.` dropped), mapped through `[languages]`, then through the
+    /// built-in aliases (`py` → `python`, `sh` → `bash`, `yml` → `yaml`, ...).
+    pub fn language_key(&self, label: &str) -> String {
+        let label = normalize_language_label(label);
+        let label = match self.languages.get(&label) {
+            Some(language) => normalize_language_label(language),
+            None => label,
+        };
+        match builtin_language_alias(&label) {
+            Some(language) => language.to_string(),
+            None => label,
+        }
+    }
+}
+
+fn normalize_language_label(label: &str) -> String {
+    label.trim().trim_start_matches('.').to_ascii_lowercase()
 }
 
+/// Built-in code fence aliases, applied after the user's `[languages]` table.
+fn builtin_language_alias(label: &str) -> Option<&'static str> {
+    Some(match label {
+        "py" | "python3" => "python",
+        "js" | "node" => "javascript",
+        "ts" => "typescript",
+        "sh" | "shell" => "bash",
+        "yml" => "yaml",
+        "rs" => "rust",
+        "golang" => "go",
+        "jl" => "julia",
+        "rb" => "ruby",
+        "md" => "markdown",
+        _ => return None,
+    })
+}
+
+/// Builds a [`Config`] in code, starting from the defaults.
+///
+/// There is a setter for every public option. Extensions depend on the flavor,
+/// so set the flavor first: [`ConfigBuilder::flavor`] resets both extension
+/// sets to that flavor's defaults.
+///
+/// ```rust
+/// use panache::config::{Flavor, WrapMode};
+///
+/// let cfg = panache::ConfigBuilder::default()
+///     .flavor(Flavor::Quarto)
+///     .extension("emoji", false)
+///     .wrap(WrapMode::Sentence)
+///     .line_width(100)
+///     .build();
+/// assert!(!cfg.extensions.emoji);
+/// ```
 #[derive(Default, Clone)]
 pub struct ConfigBuilder {
     config: Config,
 }
 
 impl ConfigBuilder {
+    /// Set the flavor and reset the parser and formatter extensions to its
+    /// defaults.
+    pub fn flavor(mut self, flavor: Flavor) -> Self {
+        self.config.flavor = flavor;
+        self.config.extensions = Extensions::for_flavor(flavor);
+        self.config.formatter_extensions = FormatterExtensions::for_flavor(flavor);
+        self
+    }
+
+    pub fn extensions(mut self, extensions: Extensions) -> Self {
+        self.config.extensions = extensions;
+        self
+    }
+
+    pub fn formatter_extensions(mut self, extensions: FormatterExtensions) -> Self {
+        self.config.formatter_extensions = extensions;
+        self
+    }
+
+    /// Turn one extension on or off by its `[extensions]` name, in both the
+    /// parser and formatter extension sets. Unknown names are ignored.
+    pub fn extension(mut self, name: &str, enabled: bool) -> Self {
+        let overrides = HashMap::from([(name.to_string(), enabled)]);
+        self.config.extensions.apply_overrides(overrides.clone());
+        self.config.formatter_extensions.apply_overrides(overrides);
+        self
+    }
+
+    pub fn line_ending(mut self, ending: LineEnding) -> Self {
+        self.config.line_ending = Some(ending);
+        self
+    }
+
+    pub fn final_newline(mut self, mode: FinalNewline) -> Self {
+        self.config.final_newline = mode;
+        self
+    }
+
+    pub fn trim_trailing_whitespace(mut self, enabled: bool) -> Self {
+        self.config.trim_trailing_whitespace = enabled;
+        self
+    }
+
+    pub fn line_width(mut self, width: usize) -> Self {
+        self.config.line_width = width;
+        self
+    }
+
     pub fn math_indent(mut self, indent: usize) -> Self {
         self.config.math_indent = indent;
         self
     }
 
+    pub fn math_delimiter_style(mut self, style: MathDelimiterStyle) -> Self {
+        self.config.math_delimiter_style = style;
+        self
+    }
+
+    pub fn table_indent(mut self, indent: usize) -> Self {
+        self.config.table_indent = indent;
+        self
+    }
+
+    pub fn table_max_width(mut self, width: usize) -> Self {
+        self.config.table_max_width = Some(width);
+        self
+    }
+
+    pub fn code_comment_width(mut self, width: usize) -> Self {
+        self.config.code_comment_width = Some(width);
+        self
+    }
+
     pub fn tab_stops(mut self, mode: TabStopMode) -> Self {
         self.config.tab_stops = mode;
         self
@@ -1091,13 +2282,31 @@ impl ConfigBuilder {
         self
     }
 
-    pub fn line_width(mut self, width: usize) -> Self {
-        self.config.line_width = width;
+    pub fn wrap(mut self, mode: WrapMode) -> Self {
+        self.config.wrap = Some(mode);
         self
     }
 
-    pub fn line_ending(mut self, ending: LineEnding) -> Self {
-        self.config.line_ending = Some(ending);
+    pub fn max_width_strict(mut self, enabled: bool) -> Self {
+        self.config.max_width_strict = enabled;
+        self
+    }
+
+    pub fn blank_lines(mut self, mode: BlankLines) -> Self {
+        self.config.blank_lines = mode;
+        self
+    }
+
+    pub fn blank_lines_around(
+        mut self,
+        blocks: impl IntoIterator<Item = BlankLinesAround>,
+    ) -> Self {
+        self.config.blank_lines_around = blocks.into_iter().collect();
+        self
+    }
+
+    pub fn blank_lines_before_heading(mut self, lines: usize) -> Self {
+        self.config.blank_lines_before_heading = Some(lines);
         self
     }
 
@@ -1106,8 +2315,194 @@ impl ConfigBuilder {
         self
     }
 
-    pub fn blank_lines(mut self, mode: BlankLines) -> Self {
-        self.config.blank_lines = mode;
+    pub fn emphasis_marker(mut self, marker: EmphasisMarker) -> Self {
+        self.config.emphasis_marker = marker;
+        self
+    }
+
+    pub fn strong_marker(mut self, marker: EmphasisMarker) -> Self {
+        self.config.strong_marker = marker;
+        self
+    }
+
+    pub fn escaping(mut self, escaping: Escaping) -> Self {
+        self.config.escaping = escaping;
+        self
+    }
+
+    pub fn link_destinations(mut self, style: LinkDestinations) -> Self {
+        self.config.link_destinations = style;
+        self
+    }
+
+    pub fn bare_urls(mut self, style: BareUrls) -> Self {
+        self.config.bare_urls = style;
+        self
+    }
+
+    pub fn allow_breaks_in(
+        mut self,
+        constructs: impl IntoIterator<Item = BreakableInline>,
+    ) -> Self {
+        self.config.allow_breaks_in = constructs.into_iter().collect();
+        self
+    }
+
+    pub fn blockquotes(mut self, style: BlockquoteStyle) -> Self {
+        self.config.blockquotes = style;
+        self
+    }
+
+    pub fn code_blocks(mut self, style: CodeBlockStyle) -> Self {
+        self.config.code_blocks = style;
+        self
+    }
+
+    pub fn definition_lists(mut self, style: DefinitionListStyle) -> Self {
+        self.config.definition_lists = style;
+        self
+    }
+
+    pub fn divs(mut self, style: DivStyle) -> Self {
+        self.config.divs = style;
+        self
+    }
+
+    pub fn headings(mut self, style: HeadingStyle) -> Self {
+        self.config.headings = style;
+        self
+    }
+
+    pub fn toc(mut self, style: TocStyle) -> Self {
+        self.config.toc = style;
+        self
+    }
+
+    /// Format code blocks in `language` with `formatters`, run in order.
+    /// Replaces any formatters already set for the language.
+    pub fn formatter(
+        mut self,
+        language: impl Into<String>,
+        formatters: Vec<FormatterConfig>,
+    ) -> Self {
+        self.config.formatters.insert(language.into(), formatters);
+        self
+    }
+
+    /// Lint code blocks in `language` with the external linter `linter`.
+    pub fn linter(mut self, language: impl Into<String>, linter: impl Into<String>) -> Self {
+        self.config.linters.insert(language.into(), linter.into());
+        self
+    }
+
+    /// Treat code fences labeled `alias` as `language` when looking up
+    /// formatters and linters.
+    pub fn language_alias(mut self, alias: impl Into<String>, language: impl Into<String>) -> Self {
+        let alias = normalize_language_label(&alias.into());
+        self.config.languages.insert(alias, language.into());
+        self
+    }
+
+    pub fn external_max_parallel(mut self, max: usize) -> Self {
+        self.config.external_max_parallel = max;
+        self
+    }
+
+    pub fn external_sandbox(mut self, sandbox: ExternalSandboxConfig) -> Self {
+        self.config.external_sandbox = sandbox;
+        self
+    }
+
+    pub fn pandoc_compat(mut self, compat: PandocCompat) -> Self {
+        self.config.parser = compat;
+        self
+    }
+
+    pub fn crossref_prefixes(mut self, prefixes: Vec<String>) -> Self {
+        self.config.crossref_prefixes = prefixes;
+        self
+    }
+
+    pub fn lint(mut self, lint: LintConfig) -> Self {
+        self.config.lint = lint;
+        self
+    }
+
+    /// Turn one lint rule on or off, like `[lint.rules] <rule> = <enabled>`.
+    pub fn lint_rule(mut self, rule: impl Into<String>, enabled: bool) -> Self {
+        self.config.lint.rules.insert(rule.into(), enabled);
+        self
+    }
+
+    pub fn cache_dir(mut self, dir: impl Into<String>) -> Self {
+        self.config.cache_dir = Some(dir.into());
+        self
+    }
+
+    pub fn cache(mut self, enabled: bool) -> Self {
+        self.config.cache = enabled;
+        self
+    }
+
+    pub fn built_in_greedy_wrap(mut self, enabled: bool) -> Self {
+        self.config.built_in_greedy_wrap = enabled;
+        self
+    }
+
+    pub fn no_break_abbreviations(mut self, abbreviations: NoBreakAbbreviations) -> Self {
+        self.config.no_break_abbreviations = Some(abbreviations);
+        self
+    }
+
+    pub fn lang(mut self, lang: impl Into<String>) -> Self {
+        self.config.lang = Some(lang.into());
+        self
+    }
+
+    pub fn exclude(mut self, patterns: Vec<String>) -> Self {
+        self.config.exclude = Some(patterns);
+        self
+    }
+
+    pub fn extend_exclude(mut self, patterns: Vec<String>) -> Self {
+        self.config.extend_exclude = patterns;
+        self
+    }
+
+    pub fn exclude_generated(mut self, enabled: bool) -> Self {
+        self.config.exclude_generated = enabled;
+        self
+    }
+
+    pub fn include(mut self, patterns: Vec<String>) -> Self {
+        self.config.include = Some(patterns);
+        self
+    }
+
+    pub fn extend_include(mut self, patterns: Vec<String>) -> Self {
+        self.config.extend_include = patterns;
+        self
+    }
+
+    /// Use `flavor` for files matching the glob `pattern`, like
+    /// `[flavor-overrides]`.
+    pub fn flavor_override(mut self, pattern: impl Into<String>, flavor: Flavor) -> Self {
+        self.config.flavor_overrides.insert(pattern.into(), flavor);
+        self
+    }
+
+    pub fn experimental(mut self, experimental: ExperimentalConfig) -> Self {
+        self.config.experimental = experimental;
+        self
+    }
+
+    pub fn organize(mut self, organize: OrganizeConfig) -> Self {
+        self.config.organize = organize;
+        self
+    }
+
+    pub fn fail_safe(mut self, enabled: bool) -> Self {
+        self.config.fail_safe = enabled;
         self
     }
 
@@ -1116,7 +2511,7 @@ impl ConfigBuilder {
     }
 }
 
-#[derive(Debug, Clone, Deserialize, JsonSchema, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub enum WrapMode {
     Preserve,
@@ -1125,7 +2520,7 @@ pub enum WrapMode {
     Semantic,
 }
 
-#[derive(Debug, Clone, Deserialize, JsonSchema, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub enum HorizontalRuleStyle {
     /// Expand horizontal rules to the configured line width (Pandoc-style)
@@ -1134,7 +2529,111 @@ pub enum HorizontalRuleStyle {
     Compact,
 }
 
-#[derive(Debug, Clone, Deserialize, JsonSchema, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum EmphasisMarker {
+    /// Asterisks: `*emphasis*`, `**strong**`
+    Asterisk,
+    /// Underscores: `_emphasis_`, `__strong__` (asterisks inside words)
+    Underscore,
+    /// Keep the delimiter each span was written with
+    Preserve,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Escaping {
+    /// Keep backslash escapes as written
+    Preserve,
+    /// Drop escapes on punctuation that reads the same without them
+    Minimal,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum LinkDestinations {
+    /// Keep link destinations as written
+    Preserve,
+    /// Wrap destinations that need it in `<...>`, drop redundant brackets
+    AngleBrackets,
+    /// Percent-encode spaces and other illegal characters, drop brackets
+    PercentEncode,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum BareUrls {
+    /// Keep bare URLs as written
+    Preserve,
+    /// Wrap them in angle brackets: `<url>`
+    Autolink,
+    /// Write them as inline links: `[url](url)`
+    Link,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum BreakableInline {
+    /// Link text, `[...]`
+    LinkText,
+    /// Image alt text, `![...]`
+    ImageAlt,
+    /// Emphasis and strong emphasis
+    Emphasis,
+    /// Bracketed spans, `[...]{.class}`
+    Span,
+}
+
+impl BreakableInline {
+    pub const ALL: [Self; 4] = [Self::LinkText, Self::ImageAlt, Self::Emphasis, Self::Span];
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum BlankLinesAround {
+    /// ATX and setext headings
+    Headings,
+    /// Fenced and indented code blocks
+    Code,
+    /// Bullet, ordered, and task lists
+    Lists,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum HeadingNumbering {
+    /// Leave heading text alone
+    #[default]
+    Preserve,
+    /// Recompute section numbers from heading order and level
+    Renumber,
+    /// Remove section numbers from heading text
+    Strip,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum DivFenceLength {
+    /// Three colons at the top level, two more per level of nesting
+    #[default]
+    DepthBased,
+    /// Keep each opening fence's colon count as written
+    Preserve,
+    /// The same number of colons (at least three) on every fence
+    Fixed(usize),
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub enum DefinitionMarker {
+    /// `:   definition`
+    #[serde(rename = ":")]
+    Colon,
+    /// `~   definition`
+    #[serde(rename = "~")]
+    Tilde,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub enum LineEnding {
     Auto,
@@ -1142,11 +2641,22 @@ pub enum LineEnding {
     Crlf,
 }
 
-#[derive(Debug, Clone, Deserialize, JsonSchema, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum FinalNewline {
+    /// End every document with exactly one newline
+    Ensure,
+    /// End with a newline only if the input did
+    Preserve,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub enum BlankLines {
     /// Preserve original blank lines (any number)
     Preserve,
     /// Collapse multiple consecutive blank lines to a single blank line
     Collapse,
+    /// Keep up to this many consecutive blank lines, collapsing longer runs
+    Max(usize),
 }
@@ -14,7 +14,7 @@ use std::collections::HashMap;
 use schemars::{JsonSchema, Schema, SchemaGenerator};
 
 use panache_formatter::config::FormatterExtensions;
-use panache_parser::Extensions;
+use panache_parser::{Extensions, Flavor};
 
 use super::FormatterDefinition;
 
@@ -64,6 +64,26 @@ pub fn extensions_schema(_generator: &mut SchemaGenerator) -> Schema {
     })
 }
 
+/// Schema for the top-level `flavor`: a flavor name, or a Pandoc-style flavor
+/// string with extension modifiers (`markdown+emoji-fancy_lists`).
+pub fn flavor_spec_schema(generator: &mut SchemaGenerator) -> Schema {
+    let flavor = generator.subschema_for::<Flavor>();
+    schemars::json_schema!({
+        "anyOf": [
+            flavor,
+            {
+                "type": "string",
+                "description": "A flavor or Pandoc reader name followed by `+extension` \
+                                or `-extension` modifiers, as in Pandoc's `--from` \
+                                (`markdown+emoji-fancy_lists`).",
+                "pattern": "^(pandoc|quarto|r[-_]?markdown|gfm|common[-_]?mark\
+                            |multi[-_]?markdown|mdsvex|myst|obsidian|markdown([-_]mmd)?)\
+                            ([+-][A-Za-z0-9_]+)*$"
+            }
+        ]
+    })
+}
+
 pub fn formatters_schema(generator: &mut SchemaGenerator) -> Schema {
     <HashMap<String, FormatterEntry> as JsonSchema>::json_schema(generator)
 }
@@ -96,6 +116,7 @@ fn known_flavor_keys_json() -> Vec<serde_json::Value> {
         "multi-markdown",
         "mdsvex",
         "myst",
+        "obsidian",
     ]
     .into_iter()
     .map(serde_json::Value::from)
@@ -0,0 +1,443 @@
+//! `panache config`: show the resolved configuration and scaffold a new one.
+//!
+//! `config show` prints the settings that apply to a document as TOML, each
+//! value followed by a comment naming its source. Sources are checked from the
+//! most to the least specific: the document's `panache` frontmatter key,
+//! `--flavor`, the config files of the `extend` chain (most derived first),
+//! flavor detection from the document's path, and finally the built-in default.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use panache::Config;
+use panache::config::{ConfigSource, Extensions, Flavor, FormatterExtensions};
+use serde::Serialize;
+
+/// File written by `config init`.
+pub(crate) const INIT_FILENAME: &str = ".panache.toml";
+
+/// Starter config written by `config init`. Every option line is commented out
+/// at its default value, so the file changes nothing until it is edited.
+pub(crate) const INIT_TEMPLATE: &str = r#"# Panache configuration: https://panache.bz/guide/configuration
+#
+# Every option below is commented out and shows its default value, so this
+# file changes nothing until you uncomment and edit a line.
+
+# Markdown flavor: pandoc, quarto, rmarkdown, gfm, commonmark, multimarkdown,
+# mdsvex, myst, or obsidian. Files ending in .qmd, .Rmd, and .svx use their own flavor.
+# flavor = "pandoc"
+
+# Extra glob patterns to skip when walking directories.
+# extend-exclude = []
+
+[format]
+# Maximum line width, in display columns.
+# line-width = 80
+
+# Paragraph wrapping: reflow, sentence, semantic, or preserve.
+# wrap = "reflow"
+
+# When reflowing, split words too long for a line and move long link
+# destinations to reference definitions instead of overflowing.
+# max-width-strict = false
+
+# Line endings of the output: auto (match the input), lf, or crlf.
+# line-ending = "auto"
+
+# Final newline: ensure one, or preserve the input's.
+# final-newline = "ensure"
+
+# Strip trailing whitespace everywhere, code blocks included (hard line breaks
+# are kept).
+# trim-trailing-whitespace = false
+
+# Runs of blank lines between blocks: collapse, preserve, or { max = 2 }.
+# blank-lines = "collapse"
+
+# Exact number of blank lines before each heading, set with
+# blank-lines-before-heading (unset, it follows blank-lines).
+
+# Blocks that always get a blank line before and after: headings, code, lists.
+# blank-lines-around = []
+
+# Math delimiters: preserve, dollars, or backslash.
+# math-delimiter-style = "preserve"
+
+# Indentation (0-3 columns) of pipe, simple, and multiline tables.
+# table-indent = 2
+
+# Horizontal rules: line-width or compact.
+# horizontal-rule-style = "line-width"
+
+# Emphasis and strong emphasis delimiters: asterisk, underscore, or preserve.
+# emphasis-marker = "asterisk"
+# strong-marker = "asterisk"
+
+# Backslash escapes: preserve, or minimal to drop unneeded ones.
+# escaping = "preserve"
+
+# Link destinations with spaces: preserve, angle-brackets, or percent-encode.
+# link-destinations = "preserve"
+
+# Bare URLs (with autolink-bare-uris): preserve, autolink (<url>), or link
+# ([url](url)).
+# bare-urls = "preserve"
+
+# Inline constructs reflow may break inside (code, math, citations, and URLs
+# are never broken).
+# allow-breaks-in = ["link-text", "image-alt", "emphasis", "span"]
+
+# Block quotes: spaces after each ">" (0 or 1), and collapse-lazy = false to
+# keep lazy continuation lines without ">" (when wrap is "preserve").
+# blockquotes = { marker-spacing = 1, collapse-lazy = true }
+
+# Code block info strings: attribute-style "shortcut" (python {.numberLines}),
+# "explicit" ({.python .numberLines}), "canonical" (Pandoc's attribute order),
+# or "preserve"; set lowercase-language to true to turn `Python` into `python`.
+# code-blocks = { attribute-style = "shortcut", lowercase-language = false }
+
+# Definition lists: marker (":" or "~"), spaces after it (1-3), and compact
+# (true or false) to force tight or loose items (unset keeps them).
+# definition-lists = { marker = ":", spaces = 3 }
+
+# Colons on fenced div fences: "depth-based", "preserve", or { fixed = 3 }.
+# divs = { fence-length = "depth-based" }
+
+# Section numbers in heading text (`## 2.3 Methods`): preserve, renumber, or
+# strip.
+# headings = { numbering = "preserve" }
+
+# Heading levels listed between <!-- panache-toc start --> and
+# <!-- panache-toc end --> markers.
+# toc = { min-level = 2, max-level = 3 }
+
+[extensions]
+# Toggle syntax extensions on top of the flavor's defaults, for example
+# `emoji = false`.
+
+[lint.rules]
+# Turn individual lint rules on or off, for example
+# `heading-hierarchy = false`.
+"#;
+
+/// Where the values of a resolved config came from.
+pub(crate) struct Origins {
+    /// Config files that contributed, most derived first, with their tables.
+    files: Vec<(PathBuf, toml::Table)>,
+    /// Whether `--flavor` was given.
+    cli_flavor: bool,
+    /// Extensions set on the command line, by `--flavor` modifiers or
+    /// `--enable-extension` / `--disable-extension`.
+    cli_extensions: HashMap<String, bool>,
+    /// The document the config was resolved for.
+    document: Option<PathBuf>,
+    /// Keys applied from the document's `panache` frontmatter.
+    frontmatter: Vec<String>,
+}
+
+impl Origins {
+    /// Read the config files in `chain` (most derived first).
+    pub(crate) fn new(
+        chain: &[PathBuf],
+        cli_flavor: bool,
+        cli_extensions: HashMap<String, bool>,
+        document: Option<&Path>,
+    ) -> io::Result<Self> {
+        let mut files = Vec::new();
+        for path in chain {
+            let text = fs::read_to_string(path)?;
+            let mut table = toml::from_str::<toml::Table>(&text).map_err(io::Error::other)?;
+            // Attribute a flavor string's `+ext` modifiers to this file.
+            panache::config::expand_flavor_spec(&mut table).map_err(io::Error::other)?;
+            files.push((path.clone(), table));
+        }
+        Ok(Self {
+            files,
+            cli_flavor,
+            cli_extensions,
+            document: document.map(Path::to_path_buf),
+            frontmatter: Vec::new(),
+        })
+    }
+
+    /// Apply the `panache` frontmatter options of `input` to `cfg`, recording
+    /// which keys took effect. Unusable entries are reported on stderr.
+    pub(crate) fn apply_frontmatter(&mut self, cfg: &mut Config, input: &str) {
+        let tree = panache::parse(input, Some(cfg.clone()));
+        for (key, value) in panache::config::frontmatter_options(&tree) {
+            let key = key.trim().to_string();
+            match panache::config::apply_format_option(cfg, &key, value.trim()) {
+                Ok(true) => self.frontmatter.push(key),
+                Ok(false) => eprintln!(
+                    "Warning: {}",
+                    panache::config::unknown_frontmatter_option(&key)
+                ),
+                Err(err) => eprintln!("Warning: ignoring frontmatter option `{key}`: {err}"),
+            }
+        }
+    }
+
+    /// The most derived config file setting `path`, with its value.
+    fn lookup(&self, path: &[&str]) -> Option<(&Path, &toml::Value)> {
+        self.files.iter().find_map(|(file, table)| {
+            let (last, parents) = path.split_last()?;
+            let mut table = table;
+            for key in parents {
+                table = table.get(*key)?.as_table()?;
+            }
+            table.get(*last).map(|value| (file.as_path(), value))
+        })
+    }
+
+    /// Source of a `[format]` option, which may also be set by the
+    /// frontmatter or, for a few keys, by a deprecated top-level alias.
+    fn format_origin(&self, key: &str) -> String {
+        if self.frontmatter.iter().any(|applied| applied == key) {
+            return "frontmatter".to_string();
+        }
+        self.lookup(&["format", key])
+            .or_else(|| self.lookup(&[key]))
+            .map_or_else(|| "default".to_string(), |(file, _)| file_origin(file))
+    }
+
+    fn origin(&self, path: &[&str]) -> String {
+        self.lookup(path)
+            .map_or_else(|| "default".to_string(), |(file, _)| file_origin(file))
+    }
+
+    fn flavor_origin(&self, flavor: Flavor) -> String {
+        if self.cli_flavor {
+            return "--flavor".to_string();
+        }
+        let configured = self.lookup(&["flavor"]).and_then(|(file, value)| {
+            let flavor = value.clone().try_into::<Flavor>().ok()?;
+            Some((file, flavor))
+        });
+        let base = configured.map_or(Flavor::default(), |(_, flavor)| flavor);
+        match (&self.document, configured) {
+            (Some(document), _) if flavor != base => {
+                format!("detected from {}", document.display())
+            }
+            (_, Some((file, _))) => file_origin(file),
+            _ => "default".to_string(),
+        }
+    }
+
+    fn extension_origin(&self, name: &str, flavor: Flavor) -> String {
+        if self.cli_extensions.contains_key(name) {
+            return "command line".to_string();
+        }
+        let flavor_key = toml::Value::try_from(flavor)
+            .ok()
+            .and_then(|value| value.as_str().map(str::to_string))
+            .unwrap_or_default();
+        self.lookup(&["extensions", &flavor_key, name])
+            .or_else(|| self.lookup(&["extensions", name]))
+            .map_or_else(
+                || format!("{flavor_key} default"),
+                |(file, _)| file_origin(file),
+            )
+    }
+}
+
+fn file_origin(file: &Path) -> String {
+    file.display().to_string()
+}
+
+/// `value` as an inline TOML value, or `None` when it has no TOML form (an
+/// unset option).
+fn toml_value<T: Serialize>(value: &T) -> Option<String> {
+    toml::Value::try_from(value)
+        .ok()
+        .map(|value| value.to_string())
+}
+
+fn push_entry(out: &mut String, key: &str, value: Option<String>, origin: &str) {
+    match value {
+        Some(value) => {
+            let _ = writeln!(out, "{key} = {value}  # {origin}");
+        }
+        None => {
+            let _ = writeln!(out, "# {key} is unset  # {origin}");
+        }
+    }
+}
+
+/// Render `cfg` as TOML, each value followed by a comment naming its source.
+pub(crate) fn render_resolved(cfg: &Config, source: &ConfigSource, origins: &Origins) -> String {
+    let mut out = String::new();
+    match source.path() {
+        Some(path) => {
+            let _ = writeln!(out, "# Config file: {}", path.display());
+        }
+        None => out.push_str("# Config file: none (built-in defaults)\n"),
+    }
+    if let Some(document) = &origins.document {
+        let _ = writeln!(out, "# Resolved for: {}", document.display());
+    }
+    out.push('\n');
+
+    push_entry(
+        &mut out,
+        "flavor",
+        toml_value(&cfg.flavor),
+        &origins.flavor_origin(cfg.flavor),
+    );
+    push_entry(
+        &mut out,
+        "cache",
+        toml_value(&cfg.cache),
+        &origins.origin(&["cache"]),
+    );
+    push_entry(
+        &mut out,
+        "exclude-generated",
+        toml_value(&cfg.exclude_generated),
+        &origins.origin(&["exclude-generated"]),
+    );
+
+    out.push_str("\n[format]\n");
+    let format_entries = [
+        ("line-width", toml_value(&cfg.line_width)),
+        ("line-ending", toml_value(&cfg.line_ending)),
+        ("final-newline", toml_value(&cfg.final_newline)),
+        (
+            "trim-trailing-whitespace",
+            toml_value(&cfg.trim_trailing_whitespace),
+        ),
+        ("wrap", toml_value(&cfg.wrap)),
+        ("max-width-strict", toml_value(&cfg.max_width_strict)),
+        ("blank-lines", toml_value(&cfg.blank_lines)),
+        ("blank-lines-around", toml_value(&cfg.blank_lines_around)),
+        (
+            "blank-lines-before-heading",
+            toml_value(&cfg.blank_lines_before_heading),
+        ),
+        (
+            "math-delimiter-style",
+            toml_value(&cfg.math_delimiter_style),
+        ),
+        ("math-indent", toml_value(&cfg.math_indent)),
+        ("table-indent", toml_value(&cfg.table_indent)),
+        ("table-max-width", toml_value(&cfg.table_max_width)),
+        ("code-comment-width", toml_value(&cfg.code_comment_width)),
+        ("tab-stops", toml_value(&cfg.tab_stops)),
+        ("tab-width", toml_value(&cfg.tab_width)),
+        (
+            "horizontal-rule-style",
+            toml_value(&cfg.horizontal_rule_style),
+        ),
+        ("emphasis-marker", toml_value(&cfg.emphasis_marker)),
+        ("strong-marker", toml_value(&cfg.strong_marker)),
+        ("escaping", toml_value(&cfg.escaping)),
+        ("link-destinations", toml_value(&cfg.link_destinations)),
+        ("bare-urls", toml_value(&cfg.bare_urls)),
+        ("allow-breaks-in", toml_value(&cfg.allow_breaks_in)),
+        ("blockquotes", toml_value(&cfg.blockquotes)),
+        ("code-blocks", toml_value(&cfg.code_blocks)),
+        ("definition-lists", toml_value(&cfg.definition_lists)),
+        ("divs", toml_value(&cfg.divs)),
+        ("headings", toml_value(&cfg.headings)),
+        ("toc", toml_value(&cfg.toc)),
+        ("lang", toml_value(&cfg.lang)),
+    ];
+    for (key, value) in format_entries {
+        push_entry(&mut out, key, value, &origins.format_origin(key));
+    }
+
+    out.push_str("\n[extensions]\n");
+    let mut names: Vec<&str> = Extensions::KNOWN_NAMES
+        .iter()
+        .chain(FormatterExtensions::KNOWN_NAMES)
+        .copied()
+        .collect();
+    names.sort_unstable();
+    names.dedup();
+    for name in names {
+        let value = cfg
+            .extensions
+            .get_by_name(name)
+            .or_else(|| cfg.formatter_extensions.get_by_name(name));
+        push_entry(
+            &mut out,
+            name,
+            value.and_then(|value| toml_value(&value)),
+            &origins.extension_origin(name, cfg.flavor),
+        );
+    }
+
+    if !cfg.lint.rules.is_empty() {
+        out.push_str("\n[lint.rules]\n");
+        let mut rules: Vec<(&String, &bool)> = cfg.lint.rules.iter().collect();
+        rules.sort();
+        for (name, enabled) in rules {
+            push_entry(
+                &mut out,
+                name,
+                toml_value(enabled),
+                &origins.origin(&["lint", "rules", name]),
+            );
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The template with every commented-out `key = value` line enabled.
+    fn uncommented_template() -> String {
+        INIT_TEMPLATE
+            .lines()
+            .map(|line| match line.strip_prefix("# ") {
+                Some(rest)
+                    if rest.starts_with(|c: char| c.is_ascii_lowercase())
+                        && rest.contains(" = ") =>
+                {
+                    rest
+                }
+                _ => line,
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn init_template_values_are_the_defaults() {
+        let template: Config = toml::from_str(INIT_TEMPLATE).expect("template parses");
+        let uncommented: Config =
+            toml::from_str(&uncommented_template()).expect("uncommented template parses");
+        assert_eq!(template, uncommented);
+        assert_eq!(template, Config::default());
+    }
+
+    #[test]
+    fn render_names_file_and_default_origins() {
+        let file = PathBuf::from("/project/panache.toml");
+        let table: toml::Table = toml::from_str("[format]\nline-width = 100\n").unwrap();
+        let origins = Origins {
+            files: vec![(file, table)],
+            cli_flavor: false,
+            cli_extensions: HashMap::new(),
+            document: None,
+            frontmatter: vec!["wrap".to_string()],
+        };
+        let cfg = Config {
+            line_width: 100,
+            ..Default::default()
+        };
+        let source = ConfigSource::Discovered(PathBuf::from("/project/panache.toml"));
+        let rendered = render_resolved(&cfg, &source, &origins);
+
+        assert!(rendered.starts_with("# Config file: /project/panache.toml\n"));
+        assert!(rendered.contains("flavor = \"pandoc\"  # default\n"));
+        assert!(rendered.contains("line-width = 100  # /project/panache.toml\n"));
+        assert!(rendered.contains("wrap = \"reflow\"  # frontmatter\n"));
+        assert!(rendered.contains("# table-max-width is unset  # default\n"));
+        assert!(rendered.contains("emoji = "));
+    }
+}
@@ -0,0 +1,421 @@
+//! `panache debug corpus`: run the debug checks over a whole repository.
+//!
+//! This is the CLI counterpart of the weekly repository scan: it resolves a
+//! directory (or shallow-clones a git URL), runs the selected checks over every
+//! supported file on a worker pool, and writes one log, report, and pass
+//! directory per failing file under `<OUT_DIR>/logs`, indexed by
+//! `summary.json` and `failures.tsv`.
+
+use std::cell::RefCell;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use rayon::prelude::*;
+use serde_json::json;
+
+use crate::cli::{CorpusCheck, DebugChecks};
+use crate::report::build_debug_failure_report;
+use crate::{
+    DebugFailure, DebugRunArtifacts, build_pool, effective_parallelism, expand_paths,
+    load_config_for_cli, remove_dir_if_exists, run_debug_checks_for_content,
+    sanitize_path_for_filename, write_debug_artifacts,
+};
+use panache::config::Flavor;
+
+pub(crate) struct CorpusOptions<'a> {
+    pub source: &'a str,
+    pub checks: &'a [CorpusCheck],
+    pub out_dir: &'a Path,
+    pub dump_passes: bool,
+    pub json: bool,
+    pub config: Option<&'a Path>,
+    pub isolated: bool,
+    pub flavor: Option<Flavor>,
+    pub jobs: usize,
+    pub quiet: bool,
+}
+
+/// Outcome of checking a single corpus file.
+struct FileResult {
+    /// Path relative to the corpus root, with `/` separators.
+    file: String,
+    failures: Vec<DebugFailure>,
+    panic: Option<String>,
+    config_fallback: bool,
+}
+
+impl FileResult {
+    fn failure_kinds(&self) -> Vec<&'static str> {
+        let mut kinds: Vec<&'static str> = self.failures.iter().map(|f| f.kind.label()).collect();
+        if self.panic.is_some() {
+            kinds.push("panic");
+        }
+        kinds
+    }
+}
+
+thread_local! {
+    static LAST_PANIC: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Run the corpus checks and return whether every file passed.
+pub(crate) fn run_corpus(opts: &CorpusOptions) -> io::Result<bool> {
+    fs::create_dir_all(opts.out_dir)?;
+    let (root, revision) = resolve_source(opts.source, opts.out_dir, opts.quiet)?;
+    let logs_dir = opts.out_dir.join("logs");
+    remove_dir_if_exists(&logs_dir)?;
+
+    let (root_cfg, root_source) =
+        load_config_for_cli(opts.config, opts.isolated, None, &root, None, opts.flavor).or_else(
+            |err| {
+                if opts.config.is_some() {
+                    return Err(err);
+                }
+                warn_config_fallback(&root.display().to_string(), &err);
+                load_config_for_cli(None, true, None, &root, None, opts.flavor)
+            },
+        )?;
+    let anchor = panache::config::anchor_dir(&root_source, &root);
+    let targets = expand_paths(
+        std::slice::from_ref(&root),
+        &root_cfg,
+        &anchor,
+        false,
+        false,
+    )?;
+
+    let debug_checks = debug_checks_for(opts.checks);
+    let catch_panics = opts.checks.contains(&CorpusCheck::Panic);
+    let workers = effective_parallelism(opts.jobs, targets.len());
+
+    let previous_hook = catch_panics.then(|| {
+        let previous = panic::take_hook();
+        // Record the message for the report instead of spraying every panic
+        // across stderr from the worker threads.
+        panic::set_hook(Box::new(|info| {
+            let message = match info.location() {
+                Some(location) => format!("{} at {}", panic_payload(info.payload()), location),
+                None => panic_payload(info.payload()),
+            };
+            LAST_PANIC.with(|last| *last.borrow_mut() = Some(message));
+        }));
+        previous
+    });
+
+    let results: Vec<io::Result<FileResult>> = build_pool(workers).install(|| {
+        targets
+            .par_iter()
+            .map(|path| check_file(path, &root, &logs_dir, opts, debug_checks, catch_panics))
+            .collect()
+    });
+
+    if let Some(previous) = previous_hook {
+        panic::set_hook(previous);
+    }
+    let results = results.into_iter().collect::<io::Result<Vec<_>>>()?;
+
+    write_index(opts, &root, revision.as_deref(), &results)?;
+    print_summary(opts, &results)?;
+
+    Ok(results
+        .iter()
+        .all(|result| result.failure_kinds().is_empty()))
+}
+
+/// Map the corpus checks onto the `debug format` checks they share. Panic
+/// safety needs no comparison, only a parse and a format.
+fn debug_checks_for(checks: &[CorpusCheck]) -> Option<DebugChecks> {
+    match (
+        checks.contains(&CorpusCheck::Idempotency),
+        checks.contains(&CorpusCheck::Losslessness),
+    ) {
+        (true, true) => Some(DebugChecks::All),
+        (true, false) => Some(DebugChecks::Idempotency),
+        (false, true) => Some(DebugChecks::Losslessness),
+        (false, false) => None,
+    }
+}
+
+fn is_git_url(source: &str) -> bool {
+    ["https://", "http://", "ssh://", "git://", "git@"]
+        .iter()
+        .any(|prefix| source.starts_with(prefix))
+}
+
+/// Resolve the corpus root, cloning git URLs into `<OUT_DIR>/source`. Returns
+/// the root and, for git checkouts, the commit being scanned.
+fn resolve_source(
+    source: &str,
+    out_dir: &Path,
+    quiet: bool,
+) -> io::Result<(PathBuf, Option<String>)> {
+    let root = if is_git_url(source) && !Path::new(source).exists() {
+        let clone_dir = out_dir.join("source");
+        if clone_dir.join(".git").exists() {
+            if !quiet {
+                eprintln!("Reusing existing clone at {}", clone_dir.display());
+            }
+        } else {
+            if !quiet {
+                eprintln!("Cloning {source} into {}", clone_dir.display());
+            }
+            let status = Command::new("git")
+                .args(["clone", "--depth", "1", "--quiet", source])
+                .arg(&clone_dir)
+                .status()
+                .map_err(|err| io::Error::other(format!("failed to run git: {err}")))?;
+            if !status.success() {
+                return Err(io::Error::other(format!("git clone of {source} failed")));
+            }
+        }
+        clone_dir
+    } else {
+        let path = PathBuf::from(source);
+        if !path.is_dir() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("corpus source {source} is not a directory or git URL"),
+            ));
+        }
+        path
+    };
+
+    let revision = Command::new("git")
+        .arg("-C")
+        .arg(&root)
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string());
+    Ok((root, revision))
+}
+
+fn check_file(
+    path: &Path,
+    root: &Path,
+    logs_dir: &Path,
+    opts: &CorpusOptions,
+    debug_checks: Option<DebugChecks>,
+    catch_panics: bool,
+) -> io::Result<FileResult> {
+    let file = path
+        .strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/");
+    let start_dir = path.parent().unwrap_or(root);
+
+    // A corpus repo whose own config fails to parse (e.g. removed keys) is
+    // its breakage, not a panache regression: check against defaults. An
+    // explicit `--config` is the caller's own and stays fatal.
+    let mut config_fallback = false;
+    let (mut cfg, _) = load_config_for_cli(
+        opts.config,
+        opts.isolated,
+        None,
+        start_dir,
+        Some(path),
+        opts.flavor,
+    )
+    .or_else(|err| {
+        if opts.config.is_some() {
+            return Err(err);
+        }
+        warn_config_fallback(&file, &err);
+        config_fallback = true;
+        load_config_for_cli(None, true, None, start_dir, Some(path), opts.flavor)
+    })?;
+    // Never spawn commands configured by a scanned repository.
+    cfg.formatters.clear();
+    cfg.linters.clear();
+
+    let input = fs::read_to_string(path)?;
+    let run = || match debug_checks {
+        Some(checks) => run_debug_checks_for_content(&input, &cfg, checks, &file),
+        None => {
+            panache::format(&input, Some(cfg.clone()), None);
+            DebugRunArtifacts::default()
+        }
+    };
+    let (artifacts, panic) = if catch_panics {
+        match panic::catch_unwind(AssertUnwindSafe(run)) {
+            Ok(artifacts) => (artifacts, None),
+            Err(payload) => {
+                let message = LAST_PANIC
+                    .with(|last| last.borrow_mut().take())
+                    .unwrap_or_else(|| panic_payload(payload.as_ref()));
+                (DebugRunArtifacts::default(), Some(message))
+            }
+        }
+    } else {
+        (run(), None)
+    };
+
+    let result = FileResult {
+        file,
+        failures: artifacts.failures.clone(),
+        panic,
+        config_fallback,
+    };
+    let failed = !result.failure_kinds().is_empty();
+    if failed || opts.dump_passes {
+        let stem = sanitize_path_for_filename(&result.file);
+        let pass_dir = logs_dir.join(format!("{stem}.passes"));
+        write_debug_artifacts(&pass_dir, &stem, &artifacts, opts.dump_passes)?;
+        if result.panic.is_some() {
+            fs::write(pass_dir.join(format!("{stem}.panic.input.txt")), &input)?;
+        }
+    }
+    if failed {
+        let stem = sanitize_path_for_filename(&result.file);
+        fs::write(logs_dir.join(format!("{stem}.log")), file_log(&result))?;
+        fs::write(
+            logs_dir.join(format!("{stem}.report.md")),
+            file_report(&result, debug_checks),
+        )?;
+    }
+    Ok(result)
+}
+
+fn warn_config_fallback(label: &str, err: &io::Error) {
+    eprintln!("Warning: invalid config for {label}, checking with defaults instead: {err}");
+}
+
+fn panic_payload(payload: &(dyn std::any::Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "panic with a non-string payload".to_string())
+}
+
+fn file_log(result: &FileResult) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "file: {}", result.file);
+    if result.config_fallback {
+        let _ = writeln!(out, "config: invalid, checked with defaults");
+    }
+    for failure in &result.failures {
+        let _ = writeln!(out, "failed: {}", failure.kind.label());
+    }
+    if let Some(message) = &result.panic {
+        let _ = writeln!(out, "failed: panic");
+        let _ = writeln!(out, "panic: {message}");
+    }
+    out
+}
+
+fn file_report(result: &FileResult, debug_checks: Option<DebugChecks>) -> String {
+    let labelled: Vec<(String, DebugFailure)> = result
+        .failures
+        .iter()
+        .map(|failure| (result.file.clone(), failure.clone()))
+        .collect();
+    let mut out = match debug_checks {
+        Some(checks) if !labelled.is_empty() => build_debug_failure_report(checks, 1, &labelled),
+        _ => String::new(),
+    };
+    if let Some(message) = &result.panic {
+        let _ = write!(
+            out,
+            "## Panic\n\n`{}` panicked while parsing or formatting:\n\n```\n{}\n```\n",
+            result.file, message
+        );
+    }
+    out
+}
+
+fn write_index(
+    opts: &CorpusOptions,
+    root: &Path,
+    revision: Option<&str>,
+    results: &[FileResult],
+) -> io::Result<()> {
+    let mut tsv = String::from("file\tfailure_type\tlog_path\treport_path\tpasses_path\n");
+    let mut failures = Vec::new();
+    for result in results {
+        let stem = sanitize_path_for_filename(&result.file);
+        for kind in result.failure_kinds() {
+            let _ = writeln!(
+                tsv,
+                "{}\t{kind}\tlogs/{stem}.log\tlogs/{stem}.report.md\tlogs/{stem}.passes",
+                result.file
+            );
+            failures.push(json!({
+                "file": result.file,
+                "kind": kind,
+                "log": format!("logs/{stem}.log"),
+                "report": format!("logs/{stem}.report.md"),
+                "passes": format!("logs/{stem}.passes"),
+            }));
+        }
+    }
+
+    let summary = json!({
+        "source": opts.source,
+        "root": root.display().to_string(),
+        "revision": revision,
+        "panache_version": env!("CARGO_PKG_VERSION"),
+        "checks": opts
+            .checks
+            .iter()
+            .map(|check| format!("{check:?}").to_lowercase())
+            .collect::<Vec<_>>(),
+        "files_checked": results.len(),
+        "failure_count": failures.len(),
+        "failures": failures,
+    });
+    fs::write(opts.out_dir.join("failures.tsv"), tsv)?;
+    fs::write(
+        opts.out_dir.join("summary.json"),
+        serde_json::to_string_pretty(&summary).map_err(io::Error::other)? + "\n",
+    )
+}
+
+fn print_summary(opts: &CorpusOptions, results: &[FileResult]) -> io::Result<()> {
+    if opts.json {
+        let summary = fs::read_to_string(opts.out_dir.join("summary.json"))?;
+        print!("{summary}");
+        return Ok(());
+    }
+
+    let mut counts: Vec<(&'static str, usize)> = Vec::new();
+    for result in results {
+        let kinds = result.failure_kinds();
+        if kinds.is_empty() {
+            continue;
+        }
+        eprintln!("FAIL {} ({})", result.file, kinds.join(", "));
+        for kind in kinds {
+            match counts.iter_mut().find(|(label, _)| *label == kind) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((kind, 1)),
+            }
+        }
+    }
+
+    if opts.quiet {
+        return Ok(());
+    }
+    if counts.is_empty() {
+        println!("All checks passed (files: {})", results.len());
+    } else {
+        let breakdown: Vec<String> = counts
+            .iter()
+            .map(|(kind, count)| format!("{kind}: {count}"))
+            .collect();
+        println!(
+            "{} failures in {} files checked ({})",
+            counts.iter().map(|(_, count)| count).sum::<usize>(),
+            results.len(),
+            breakdown.join(", ")
+        );
+    }
+    println!("Wrote results to {}", opts.out_dir.display());
+    Ok(())
+}
@@ -1,86 +1,200 @@
+use std::fmt;
 use std::path::Path;
 
 use crate::cli::MessageFormat;
+use crate::term;
 use annotate_snippets::{AnnotationKind, Level, Renderer, Snippet};
 use panache::linter::{Diagnostic, DiagnosticNoteKind, DiagnosticOrigin, Severity};
+use unicode_width::UnicodeWidthStr;
 
 pub(crate) fn print_diagnostics(
     diagnostics: &[Diagnostic],
     file: Option<&Path>,
     source: Option<&str>,
-    use_color: bool,
     message_format: MessageFormat,
     show_summary: bool,
 ) {
     let file_name = file.and_then(Path::to_str).unwrap_or("<stdin>");
-    let renderer = if use_color {
-        Renderer::styled()
+    // Always style: `anstream` strips the escapes when color is off.
+    let renderer = Renderer::styled();
+
+    if matches!(message_format, MessageFormat::Grouped) {
+        print_grouped(diagnostics, file_name);
     } else {
-        Renderer::plain()
-    };
+        for diag in diagnostics {
+            if matches!(message_format, MessageFormat::Short) {
+                anstream::println!(
+                    "{}:{}:{}: {}[{}]: {}",
+                    file_name,
+                    diag.location.line,
+                    diag.location.column,
+                    term::paint(
+                        severity_style(&diag.severity),
+                        severity_name(&diag.severity)
+                    ),
+                    diag.code,
+                    diag.message,
+                );
+                continue;
+            }
 
-    for diag in diagnostics {
-        if matches!(message_format, MessageFormat::Short) {
-            println!(
-                "{}:{}:{}: {}[{}]: {}",
-                file_name,
-                diag.location.line,
-                diag.location.column,
-                severity_name(&diag.severity),
-                diag.code,
-                diag.message,
-            );
-            continue;
-        }
+            if let Some(source) = source {
+                print_source_snippet(diag, file_name, source, &renderer, diag.fix.as_ref());
+            } else {
+                anstream::println!(
+                    "{}[{}]: {}",
+                    term::paint(
+                        severity_style(&diag.severity),
+                        severity_name(&diag.severity)
+                    ),
+                    diag.code,
+                    diag.message
+                );
+                anstream::println!(
+                    "  --> {}:{}:{}",
+                    file_name,
+                    diag.location.line,
+                    diag.location.column
+                );
+            }
 
-        if let Some(source) = source {
-            print_source_snippet(diag, file_name, source, &renderer, diag.fix.as_ref());
-        } else {
-            println!(
-                "{}[{}]: {}",
-                severity_name(&diag.severity),
-                diag.code,
-                diag.message
-            );
-            println!(
-                "  --> {}:{}:{}",
-                file_name, diag.location.line, diag.location.column
-            );
-        }
+            if let Some(fix) = &diag.fix
+                && (source.is_none() || fix.edits.is_empty())
+            {
+                print_subdiag("help", &fix.message);
+            }
+            for note in &diag.notes {
+                let kind = match note.kind {
+                    DiagnosticNoteKind::Note => "note",
+                    DiagnosticNoteKind::Help => "help",
+                };
+                print_subdiag(kind, &note.message);
+            }
 
-        if let Some(fix) = &diag.fix
-            && (source.is_none() || fix.edits.is_empty())
-        {
-            print_subdiag("help", &fix.message);
-        }
-        for note in &diag.notes {
-            let kind = match note.kind {
-                DiagnosticNoteKind::Note => "note",
-                DiagnosticNoteKind::Help => "help",
-            };
-            print_subdiag(kind, &note.message);
+            if diag.origin == DiagnosticOrigin::BuiltIn {
+                print_subdiag(
+                    "note",
+                    &format!(
+                        "configure this rule in panache.toml with [lint.rules] {} = false",
+                        diag.code
+                    ),
+                );
+                print_subdiag(
+                    "help",
+                    &format!(
+                        "for further information visit https://panache.bz/reference/linter-rules.html#{}",
+                        diag.code
+                    ),
+                );
+            }
         }
+    }
+
+    if show_summary {
+        let counts = SeverityCounts::of(diagnostics);
+        anstream::println!("\nFound {} issue(s): {}", counts.total(), counts);
+    }
+}
+
+/// One block per file: the path, then a row per diagnostic with location,
+/// severity, message, and rule code in aligned columns.
+fn print_grouped(diagnostics: &[Diagnostic], file_name: &str) {
+    if diagnostics.is_empty() {
+        return;
+    }
+
+    let rows: Vec<(String, &Diagnostic)> = diagnostics
+        .iter()
+        .map(|diag| {
+            (
+                format!("{}:{}", diag.location.line, diag.location.column),
+                diag,
+            )
+        })
+        .collect();
+    let location_width = rows.iter().map(|(loc, _)| loc.len()).max().unwrap_or(0);
+    let severity_width = rows
+        .iter()
+        .map(|(_, diag)| severity_name(&diag.severity).len())
+        .max()
+        .unwrap_or(0);
+    let message_width = rows
+        .iter()
+        .map(|(_, diag)| diag.message.width())
+        .max()
+        .unwrap_or(0);
+
+    anstream::println!("{}", term::paint(term::PATH, file_name));
+    for (location, diag) in rows {
+        let severity = severity_name(&diag.severity);
+        // Pad outside the styled spans so escapes don't count toward widths.
+        anstream::println!(
+            "  {}{}  {}{}  {}{}  {}",
+            term::paint(term::DIM, &location),
+            " ".repeat(location_width - location.len()),
+            term::paint(severity_style(&diag.severity), severity),
+            " ".repeat(severity_width - severity.len()),
+            diag.message,
+            " ".repeat(message_width - diag.message.width()),
+            term::paint(term::DIM, &diag.code),
+        );
+    }
+    anstream::println!();
+}
+
+/// Diagnostic counts by severity, for summary lines.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct SeverityCounts {
+    pub errors: usize,
+    pub warnings: usize,
+    pub infos: usize,
+}
+
+impl SeverityCounts {
+    pub(crate) fn of(diagnostics: &[Diagnostic]) -> Self {
+        let mut counts = Self::default();
+        counts.add(diagnostics);
+        counts
+    }
 
-        if diag.origin == DiagnosticOrigin::BuiltIn {
-            print_subdiag(
-                "note",
-                &format!(
-                    "configure this rule in panache.toml with [lint.rules] {} = false",
-                    diag.code
-                ),
-            );
-            print_subdiag(
-                "help",
-                &format!(
-                    "for further information visit https://panache.bz/reference/linter-rules.html#{}",
-                    diag.code
-                ),
-            );
+    pub(crate) fn add(&mut self, diagnostics: &[Diagnostic]) {
+        for diag in diagnostics {
+            match diag.severity {
+                Severity::Error => self.errors += 1,
+                Severity::Warning => self.warnings += 1,
+                Severity::Info => self.infos += 1,
+            }
         }
     }
 
-    if show_summary {
-        println!("\nFound {} issue(s)", diagnostics.len());
+    pub(crate) fn total(&self) -> usize {
+        self.errors + self.warnings + self.infos
+    }
+}
+
+impl fmt::Display for SeverityCounts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts = [
+            (self.errors, "error", "errors", term::ERROR),
+            (self.warnings, "warning", "warnings", term::WARNING),
+            (self.infos, "info", "infos", term::INFO),
+        ];
+        let mut first = true;
+        for (count, singular, plural, style) in parts {
+            if count == 0 {
+                continue;
+            }
+            if !first {
+                f.write_str(", ")?;
+            }
+            first = false;
+            let label = if count == 1 { singular } else { plural };
+            write!(f, "{}", term::paint(style, format_args!("{count} {label}")))?;
+        }
+        if first {
+            f.write_str("no issues")?;
+        }
+        Ok(())
     }
 }
 
@@ -95,24 +209,30 @@ fn print_source_snippet(
     let end: usize = diag.location.range.end().into();
     let end = end.max(start.saturating_add(1)).min(source.len());
 
-    let primary = if let Some(fix) = fix
+    // Underline the whole flagged range; a fix touching only part of it (a
+    // heading's marker, say) gets its own annotation carrying the help.
+    let primary = AnnotationKind::Primary.span(start..end);
+    let snippet = Snippet::source(source).line_start(1).path(file_name);
+    let snippet = if let Some(fix) = fix
         && let Some(edit) = fix.edits.first()
     {
+        let help = format!("help: {}", fix.message);
         let edit_start: usize = edit.range.start().into();
         let edit_end: usize = edit.range.end().into();
         let edit_end = edit_end.max(edit_start.saturating_add(1)).min(source.len());
-        AnnotationKind::Primary
-            .span(edit_start..edit_end)
-            .label(format!("help: {}", fix.message))
+        if (edit_start, edit_end) == (start, end) {
+            snippet.annotation(primary.label(help))
+        } else {
+            snippet.annotation(primary).annotation(
+                AnnotationKind::Context
+                    .span(edit_start..edit_end)
+                    .label(help),
+            )
+        }
     } else {
-        AnnotationKind::Primary.span(start..end)
+        snippet.annotation(primary)
     };
 
-    let snippet = Snippet::source(source)
-        .line_start(1)
-        .path(file_name)
-        .annotation(primary);
-
     let snippet = if diag.code == "heading-hierarchy" {
         if let Some(context_span) = find_previous_heading_span(source, start) {
             snippet.annotation(
@@ -131,7 +251,7 @@ fn print_source_snippet(
     let report = &[severity_level(&diag.severity)
         .primary_title(&title)
         .element(snippet)];
-    println!("{}", renderer.render(report));
+    anstream::println!("{}", renderer.render(report));
 }
 
 fn severity_level(severity: &Severity) -> Level<'static> {
@@ -142,6 +262,14 @@ fn severity_level(severity: &Severity) -> Level<'static> {
     }
 }
 
+fn severity_style(severity: &Severity) -> anstyle::Style {
+    match severity {
+        Severity::Error => term::ERROR,
+        Severity::Warning => term::WARNING,
+        Severity::Info => term::INFO,
+    }
+}
+
 fn severity_name(severity: &Severity) -> &'static str {
     match severity {
         Severity::Error => "error",
@@ -151,7 +279,7 @@ fn severity_name(severity: &Severity) -> &'static str {
 }
 
 fn print_subdiag(kind: &str, message: &str) {
-    println!("  = {kind}: {message}");
+    anstream::println!("  = {kind}: {message}");
 }
 
 fn find_previous_heading_span(
@@ -184,7 +312,7 @@ fn find_previous_heading_span(
 
 #[cfg(test)]
 mod tests {
-    use super::severity_name;
+    use super::{SeverityCounts, severity_name};
     use panache::linter::{Diagnostic, DiagnosticOrigin, Location, Severity};
     use rowan::TextRange;
 
@@ -192,11 +320,7 @@ mod tests {
     fn built_in_diagnostics_show_panache_guidance() {
         let diag = Diagnostic {
             severity: Severity::Warning,
-            location: Location {
-                line: 1,
-                column: 1,
-                range: TextRange::new(0.into(), 1.into()),
-            },
+            location: Location::from_range(TextRange::new(0.into(), 1.into()), "#\n"),
             message: "msg".to_string(),
             code: "heading-hierarchy".to_string(),
             origin: DiagnosticOrigin::BuiltIn,
@@ -210,15 +334,31 @@ mod tests {
     #[test]
     fn external_diagnostics_can_be_marked_explicitly() {
         let diag = Diagnostic::warning(
-            Location {
-                line: 1,
-                column: 1,
-                range: TextRange::new(0.into(), 1.into()),
-            },
+            Location::from_range(TextRange::new(0.into(), 1.into()), "#\n"),
             "SA5009",
             "msg",
         )
         .with_origin(DiagnosticOrigin::External);
         assert_eq!(diag.origin, DiagnosticOrigin::External);
     }
+
+    #[test]
+    fn severity_counts_summarize_nonzero_severities() {
+        let location = Location::from_range(TextRange::new(0.into(), 1.into()), "#\n");
+        let diagnostics = vec![
+            Diagnostic::warning(location.clone(), "a", "msg"),
+            Diagnostic::warning(location.clone(), "b", "msg"),
+            Diagnostic::error(location, "c", "msg"),
+        ];
+        let counts = SeverityCounts::of(&diagnostics);
+        assert_eq!(counts.total(), 3);
+        assert_eq!(
+            anstream::adapter::strip_str(&counts.to_string()).to_string(),
+            "1 error, 2 warnings"
+        );
+        assert_eq!(
+            anstream::adapter::strip_str(&SeverityCounts::default().to_string()).to_string(),
+            "no issues"
+        );
+    }
 }
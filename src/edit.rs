@@ -0,0 +1,352 @@
+//! Programmatic edits to a parsed document.
+//!
+//! An [`Editor`] takes changes addressed by CST node: replace a node, insert a
+//! block next to another, delete a node or a whole section. [`Editor::finish`]
+//! applies them together to the source text and parses the result, so the new
+//! tree is always exactly what the parser makes of the new text, and
+//! [`Edited::format`] formats it.
+//!
+//! ```rust
+//! use panache::edit::Editor;
+//! use panache::syntax::SyntaxKind;
+//!
+//! let mut editor = Editor::new("# Intro\n\nOld text.\n\n# Notes\n\nDraft.\n", None);
+//! let tree = editor.tree().clone();
+//! let paragraph = tree
+//!     .descendants()
+//!     .find(|node| node.kind() == SyntaxKind::PARAGRAPH)
+//!     .unwrap();
+//! let notes = tree
+//!     .children()
+//!     .filter(|node| node.kind() == SyntaxKind::HEADING)
+//!     .nth(1)
+//!     .unwrap();
+//! editor.replace(&paragraph, "New text.\n").unwrap();
+//! editor.insert_after(&paragraph, "> A quote.").unwrap();
+//! editor.delete_section(&notes).unwrap();
+//!
+//! let edited = editor.finish();
+//! assert_eq!(edited.text, "# Intro\n\nNew text.\n\n> A quote.\n\n");
+//! assert_eq!(edited.format().unwrap(), "# Intro\n\nNew text.\n\n> A quote.\n");
+//! ```
+
+use std::fmt;
+use std::ops::Range;
+
+use crate::config::Config;
+use crate::edits::{self, TextEdit};
+use crate::error::FormatError;
+use crate::syntax::{AstNode, Heading, SyntaxKind, SyntaxNode};
+
+/// A heading and the content that belongs to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    /// Heading level (1-6).
+    pub level: usize,
+    /// Heading text as written, without the `#` markers or attributes.
+    pub title: String,
+    /// Bytes from the start of the heading to the start of the next heading
+    /// of the same or a higher level, or the end of the document.
+    pub range: Range<usize>,
+}
+
+/// The sections of the document `tree`, in document order. Only top-level
+/// headings start a section; headings in lists, block quotes, and fenced divs
+/// are part of the section they appear in.
+pub fn sections(tree: &SyntaxNode) -> Vec<Section> {
+    let headings: Vec<(usize, String, usize)> = tree
+        .children()
+        .filter_map(Heading::cast)
+        .map(|heading| {
+            let title = heading.text();
+            let title = title
+                .trim_end_matches(|c: char| c == '#' || c.is_whitespace())
+                .trim_start()
+                .to_string();
+            let start = usize::from(heading.syntax().text_range().start());
+            (heading.level(), title, start)
+        })
+        .collect();
+    let document_end = usize::from(tree.text_range().end());
+
+    headings
+        .iter()
+        .enumerate()
+        .map(|(idx, (level, title, start))| {
+            let end = headings[idx + 1..]
+                .iter()
+                .find(|(next_level, _, _)| next_level <= level)
+                .map_or(document_end, |(_, _, next_start)| *next_start);
+            Section {
+                level: *level,
+                title: title.clone(),
+                range: *start..end,
+            }
+        })
+        .collect()
+}
+
+/// Why an edit was rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EditError {
+    /// The node belongs to another tree than the editor's.
+    ForeignNode,
+    /// The range lies outside the document or splits a character.
+    InvalidRange(Range<usize>),
+    /// The edit overlaps one made earlier.
+    Overlap(Range<usize>),
+    /// Blocks can only be inserted next to a top-level block or one directly
+    /// inside a fenced div, where no line prefix is needed.
+    NotABlock(SyntaxKind),
+    /// Only top-level headings start a section.
+    NotASection,
+}
+
+impl fmt::Display for EditError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ForeignNode => write!(f, "node does not belong to the edited document"),
+            Self::InvalidRange(range) => {
+                write!(f, "range {range:?} is not a valid range of the document")
+            }
+            Self::Overlap(range) => write!(f, "edit at {range:?} overlaps an earlier edit"),
+            Self::NotABlock(kind) => {
+                write!(f, "cannot insert a block next to a {kind:?} node")
+            }
+            Self::NotASection => write!(f, "node is not a top-level heading"),
+        }
+    }
+}
+
+impl std::error::Error for EditError {}
+
+/// Collects edits to one document; see the [module docs](self).
+#[derive(Debug, Clone)]
+pub struct Editor {
+    input: String,
+    tree: SyntaxNode,
+    config: Config,
+    edits: Vec<TextEdit>,
+}
+
+/// The document after [`Editor::finish`].
+#[derive(Debug, Clone)]
+pub struct Edited {
+    /// The edited source text.
+    pub text: String,
+    /// The CST of `text`.
+    pub tree: SyntaxNode,
+    config: Config,
+}
+
+impl Editor {
+    /// Parse `input` for editing.
+    pub fn new(input: &str, config: Option<Config>) -> Self {
+        let config = config.unwrap_or_default();
+        let tree = crate::parser::parse(input, Some(config.clone()));
+        Self {
+            input: input.to_string(),
+            tree,
+            config,
+            edits: Vec::new(),
+        }
+    }
+
+    /// The CST of the original text. Nodes passed to the editor must come
+    /// from this tree.
+    pub fn tree(&self) -> &SyntaxNode {
+        &self.tree
+    }
+
+    /// The original text.
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// Replace `node`, including any trailing newline it holds, with `text`.
+    pub fn replace(&mut self, node: &SyntaxNode, text: &str) -> Result<(), EditError> {
+        let range = self.range_of(node)?;
+        self.replace_range(range, text)
+    }
+
+    /// Replace the bytes `range` of the original text with `text`.
+    pub fn replace_range(&mut self, range: Range<usize>, text: &str) -> Result<(), EditError> {
+        if range.start > range.end
+            || !self.input.is_char_boundary(range.start)
+            || !self.input.is_char_boundary(range.end)
+            || range.end > self.input.len()
+        {
+            return Err(EditError::InvalidRange(range));
+        }
+        let overlaps = self
+            .edits
+            .iter()
+            .any(|edit| edit.range.start < range.end && range.start < edit.range.end);
+        if overlaps {
+            return Err(EditError::Overlap(range));
+        }
+        self.edits.push(TextEdit {
+            range,
+            new_text: text.to_string(),
+        });
+        Ok(())
+    }
+
+    /// Delete `node`.
+    pub fn delete(&mut self, node: &SyntaxNode) -> Result<(), EditError> {
+        self.replace(node, "")
+    }
+
+    /// Delete the section `heading` starts: the heading and everything up to
+    /// the next heading of the same or a higher level.
+    pub fn delete_section(&mut self, heading: &SyntaxNode) -> Result<(), EditError> {
+        let range = self.range_of(heading)?;
+        let section = sections(&self.tree)
+            .into_iter()
+            .find(|section| section.range.start == range.start)
+            .filter(|_| heading.kind() == SyntaxKind::HEADING)
+            .ok_or(EditError::NotASection)?;
+        self.replace_range(section.range, "")
+    }
+
+    /// Insert the block(s) `markdown` before `block`, separated from it by a
+    /// blank line.
+    pub fn insert_before(&mut self, block: &SyntaxNode, markdown: &str) -> Result<(), EditError> {
+        let range = self.block_range(block)?;
+        let newline = self.newline();
+        let text = format!("{}{newline}{newline}", markdown.trim_end());
+        self.replace_range(range.start..range.start, &text)
+    }
+
+    /// Insert the block(s) `markdown` after `block`, separated from it by a
+    /// blank line.
+    pub fn insert_after(&mut self, block: &SyntaxNode, markdown: &str) -> Result<(), EditError> {
+        let range = self.block_range(block)?;
+        let newline = self.newline();
+        let separator = if self.input[..range.end].ends_with('\n') {
+            newline.to_string()
+        } else {
+            format!("{newline}{newline}")
+        };
+        let text = format!("{separator}{}{newline}", markdown.trim_end());
+        self.replace_range(range.end..range.end, &text)
+    }
+
+    /// Apply the edits and parse the result.
+    pub fn finish(mut self) -> Edited {
+        // Insertions at an offset go before a replacement starting there, and
+        // insertions at the same offset keep the order they were made in.
+        self.edits
+            .sort_by_key(|edit| (edit.range.start, edit.range.end));
+        let text = edits::apply(&self.input, &self.edits);
+        let tree = crate::parser::parse(&text, Some(self.config.clone()));
+        Edited {
+            text,
+            tree,
+            config: self.config,
+        }
+    }
+
+    fn range_of(&self, node: &SyntaxNode) -> Result<Range<usize>, EditError> {
+        if node.ancestors().last().as_ref() != Some(&self.tree) {
+            return Err(EditError::ForeignNode);
+        }
+        let range = node.text_range();
+        Ok(range.start().into()..range.end().into())
+    }
+
+    fn block_range(&self, block: &SyntaxNode) -> Result<Range<usize>, EditError> {
+        let range = self.range_of(block)?;
+        let in_block_position = block.parent().is_some_and(|parent| {
+            matches!(parent.kind(), SyntaxKind::DOCUMENT | SyntaxKind::FENCED_DIV)
+        });
+        if !in_block_position || block.kind() == SyntaxKind::BLANK_LINE {
+            return Err(EditError::NotABlock(block.kind()));
+        }
+        Ok(range)
+    }
+
+    fn newline(&self) -> &'static str {
+        if self.input.contains("\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        }
+    }
+}
+
+impl Edited {
+    /// The edited document, formatted with the editor's config.
+    pub fn format(&self) -> Result<String, FormatError> {
+        crate::try_format_with_tree(&self.text, &self.tree, &self.config, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn first(tree: &SyntaxNode, kind: SyntaxKind) -> SyntaxNode {
+        tree.descendants()
+            .find(|node| node.kind() == kind)
+            .expect("node of kind")
+    }
+
+    #[test]
+    fn sections_end_at_the_next_heading_of_the_same_or_higher_level() {
+        let input = "# A\n\n## B\n\ntext\n\n### C\n\n## D\n";
+        let tree = crate::parser::parse(input, None);
+        let ranges: Vec<_> = sections(&tree)
+            .into_iter()
+            .map(|section| (section.title, &input[section.range]))
+            .collect();
+        assert_eq!(
+            ranges,
+            vec![
+                ("A".to_string(), input),
+                ("B".to_string(), "## B\n\ntext\n\n### C\n\n"),
+                ("C".to_string(), "### C\n\n"),
+                ("D".to_string(), "## D\n"),
+            ]
+        );
+    }
+
+    #[test]
+    fn insertions_and_deletions_at_one_offset_apply_in_order() {
+        let mut editor = Editor::new("First.\n\nSecond.\n", None);
+        let tree = editor.tree().clone();
+        let paragraph = first(&tree, SyntaxKind::PARAGRAPH);
+        editor.delete(&paragraph).unwrap();
+        editor.insert_before(&paragraph, "A.").unwrap();
+        editor.insert_before(&paragraph, "B.").unwrap();
+        let edited = editor.finish();
+        assert_eq!(edited.text, "A.\n\nB.\n\n\nSecond.\n");
+        assert_eq!(edited.format().unwrap(), "A.\n\nB.\n\nSecond.\n");
+    }
+
+    #[test]
+    fn rejects_overlapping_foreign_and_misplaced_edits() {
+        let mut editor = Editor::new("- item\n\n# Title\n", None);
+        let tree = editor.tree().clone();
+        let list = first(&tree, SyntaxKind::LIST);
+        editor.replace(&list, "Text.\n").unwrap();
+        assert!(matches!(
+            editor.delete(&first(&tree, SyntaxKind::LIST_ITEM)),
+            Err(EditError::Overlap(_))
+        ));
+        assert_eq!(
+            editor.insert_after(&first(&tree, SyntaxKind::LIST_ITEM), "More."),
+            Err(EditError::NotABlock(SyntaxKind::LIST_ITEM))
+        );
+
+        let other = crate::parser::parse("# Title\n", None);
+        assert_eq!(
+            editor.delete(&first(&other, SyntaxKind::HEADING)),
+            Err(EditError::ForeignNode)
+        );
+        assert_eq!(
+            editor.delete_section(&first(&tree, SyntaxKind::LIST_ITEM)),
+            Err(EditError::NotASection)
+        );
+    }
+}
@@ -0,0 +1,108 @@
+//! Minimal text edits that turn a document into its formatted output.
+//!
+//! The LSP sends these instead of replacing the whole document, which keeps
+//! the editor's undo steps small and cursors in place, and `panache format
+//! --edits json` prints them for other integrations.
+//!
+//! ```rust
+//! let input = "#   Title\n\nText.\n";
+//! let output = panache::format(input, None, None);
+//! let edits = panache::edits::diff(input, &output);
+//! assert_eq!(edits.len(), 1);
+//! assert_eq!(edits[0].range, 2..4);
+//! assert_eq!(panache::edits::apply(input, &edits), output);
+//! ```
+
+use std::ops::Range;
+
+use crate::source_map::SourceMap;
+
+/// Replace the bytes `range` of the input with `new_text`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub range: Range<usize>,
+    pub new_text: String,
+}
+
+/// Non-overlapping edits, in document order, that turn `input` into `output`.
+///
+/// Each changed run of lines becomes one edit, narrowed to the part between
+/// the text it shares with its replacement at either end.
+pub fn diff(input: &str, output: &str) -> Vec<TextEdit> {
+    SourceMap::new(input, output)
+        .changes()
+        .map(|change| {
+            let old = &input[change.input.clone()];
+            let new = &output[change.output.clone()];
+            let prefix = common_prefix(old, new);
+            let suffix = common_suffix(&old[prefix..], &new[prefix..]);
+            TextEdit {
+                range: change.input.start + prefix..change.input.end - suffix,
+                new_text: new[prefix..new.len() - suffix].to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Apply `edits`, as returned by [`diff`], to `input`.
+pub fn apply(input: &str, edits: &[TextEdit]) -> String {
+    let mut output = input.to_string();
+    for edit in edits.iter().rev() {
+        output.replace_range(edit.range.clone(), &edit.new_text);
+    }
+    output
+}
+
+/// Length in bytes of the longest common prefix, on a char boundary.
+fn common_prefix(a: &str, b: &str) -> usize {
+    a.char_indices()
+        .zip(b.chars())
+        .find(|((_, x), y)| x != y)
+        .map_or(a.len().min(b.len()), |((idx, _), _)| idx)
+}
+
+/// Length in bytes of the longest common suffix, on a char boundary.
+fn common_suffix(a: &str, b: &str) -> usize {
+    a.chars()
+        .rev()
+        .zip(b.chars().rev())
+        .take_while(|(x, y)| x == y)
+        .map(|(x, _)| x.len_utf8())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edits_are_narrowed_within_changed_lines() {
+        let edits = diff("keep\n-   item\nkeep\n", "keep\n- item\nkeep\n");
+        assert_eq!(
+            edits,
+            [TextEdit {
+                range: 7..9,
+                new_text: String::new(),
+            }]
+        );
+    }
+
+    #[test]
+    fn narrowing_respects_char_boundaries() {
+        let input = "smörgås  \n";
+        let output = "smörgåsar\n";
+        let edits = diff(input, output);
+        assert_eq!(edits.len(), 1);
+        assert_eq!(&input[edits[0].range.clone()], "  ");
+        assert_eq!(edits[0].new_text, "ar");
+        assert_eq!(apply(input, &edits), output);
+    }
+
+    #[test]
+    fn apply_round_trips_several_hunks() {
+        let input = "a  \nsame\n\n\n\nb\nsame\nc";
+        let output = "a\nsame\n\nb\nsame\nc\n";
+        assert_eq!(apply(input, &diff(input, output)), output);
+        assert!(diff(output, output).is_empty());
+    }
+}
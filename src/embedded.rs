@@ -0,0 +1,436 @@
+//! Formatting Markdown embedded in source code.
+//!
+//! [`format_embedded`] rewrites the Markdown regions of an R or Python source
+//! file and copies everything else through byte for byte:
+//!
+//! - [`EmbeddedSyntax::Roxygen`]: runs of `#'` roxygen2 comment lines. The
+//!   untagged description and the bodies of prose tags (`@description`,
+//!   `@details`, `@section`, ...) are formatted; other tags, including
+//!   `@examples`, are left alone.
+//! - [`EmbeddedSyntax::Docstring`]: multi-line Python docstrings of modules,
+//!   functions, and classes. Docstrings containing backslash escapes are
+//!   skipped unless they are raw strings, since formatting could change what
+//!   the escapes mean.
+//!
+//! Each region is formatted at the configured line width minus its comment
+//! prefix or indentation.
+
+use crate::config::{Config, LineEnding};
+
+/// Which kind of embedded Markdown to format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmbeddedSyntax {
+    /// roxygen2 `#'` comments in R source.
+    Roxygen,
+    /// Python docstrings.
+    Docstring,
+}
+
+impl EmbeddedSyntax {
+    /// File patterns a directory walk selects for this syntax.
+    pub fn default_include(self) -> &'static [&'static str] {
+        match self {
+            EmbeddedSyntax::Roxygen => &["*.R", "*.r"],
+            EmbeddedSyntax::Docstring => &["*.py", "*.pyi"],
+        }
+    }
+}
+
+/// roxygen2 tags whose body is Markdown prose.
+const ROXYGEN_PROSE_TAGS: &[&str] = &[
+    "description",
+    "details",
+    "section",
+    "note",
+    "format",
+    "source",
+    "references",
+    "return",
+    "returns",
+];
+
+/// Format the Markdown regions of `input`, leaving the code around them
+/// untouched.
+pub fn format_embedded(input: &str, syntax: EmbeddedSyntax, config: &Config) -> String {
+    let lines: Vec<&str> = input.split_inclusive('\n').collect();
+    match syntax {
+        EmbeddedSyntax::Roxygen => format_roxygen(&lines, config),
+        EmbeddedSyntax::Docstring => format_docstrings(&lines, config),
+    }
+}
+
+/// Format `markdown` at `width` columns and return its lines.
+fn format_markdown(markdown: &str, width: usize, config: &Config) -> Vec<String> {
+    let mut config = config.clone();
+    config.line_width = width.max(1);
+    config.line_ending = Some(LineEnding::Lf);
+    crate::format(markdown, Some(config), None)
+        .trim_end_matches('\n')
+        .split('\n')
+        .map(str::to_string)
+        .collect()
+}
+
+fn line_ending(line: &str) -> &'static str {
+    if line.ends_with("\r\n") { "\r\n" } else { "\n" }
+}
+
+fn strip_line_ending(line: &str) -> &str {
+    line.strip_suffix('\n')
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .unwrap_or(line)
+}
+
+fn leading_whitespace(line: &str) -> &str {
+    &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+}
+
+/// Drop the final line ending when the original region ended without one.
+fn match_final_newline(mut out: String, original_last: &str) -> String {
+    if !original_last.ends_with('\n') {
+        let trimmed = strip_line_ending(&out).len();
+        out.truncate(trimmed);
+    }
+    out
+}
+
+fn format_roxygen(lines: &[&str], config: &Config) -> String {
+    let mut out = String::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let Some(indent) = roxygen_indent(lines[i]) else {
+            out.push_str(lines[i]);
+            i += 1;
+            continue;
+        };
+        let start = i;
+        while i < lines.len() && roxygen_indent(lines[i]) == Some(indent) {
+            i += 1;
+        }
+        out.push_str(&format_roxygen_block(&lines[start..i], indent, config));
+    }
+    out
+}
+
+fn roxygen_indent(line: &str) -> Option<&str> {
+    let indent = leading_whitespace(line);
+    line[indent.len()..].starts_with("#'").then_some(indent)
+}
+
+/// A roxygen block split at tag lines: the untagged lead-in, then one section
+/// per tag.
+struct RoxygenSection<'a> {
+    tag_line: Option<&'a str>,
+    body: Vec<(&'a str, &'a str)>,
+}
+
+fn format_roxygen_block(lines: &[&str], indent: &str, config: &Config) -> String {
+    let eol = line_ending(lines[0]);
+    let prefix_len = indent.len() + "#' ".len();
+
+    let mut sections = vec![RoxygenSection {
+        tag_line: None,
+        body: Vec::new(),
+    }];
+    for &raw in lines {
+        let after_marker = &strip_line_ending(raw)[indent.len() + 2..];
+        let content = after_marker.strip_prefix(' ').unwrap_or(after_marker);
+        if content.starts_with('@') && !content.starts_with("@@") {
+            sections.push(RoxygenSection {
+                tag_line: Some(raw),
+                body: Vec::new(),
+            });
+        } else if let Some(section) = sections.last_mut() {
+            section.body.push((raw, content));
+        }
+    }
+
+    let mut out = String::new();
+    for section in &sections {
+        if let Some(tag_line) = section.tag_line {
+            out.push_str(tag_line);
+        }
+        if !is_prose_section(section) {
+            for (raw, _) in &section.body {
+                out.push_str(raw);
+            }
+            continue;
+        }
+
+        let contents: Vec<&str> = section.body.iter().map(|(_, content)| *content).collect();
+        let first = contents.iter().position(|line| !line.trim().is_empty());
+        let last = contents.iter().rposition(|line| !line.trim().is_empty());
+        let (Some(first), Some(last)) = (first, last) else {
+            for (raw, _) in &section.body {
+                out.push_str(raw);
+            }
+            continue;
+        };
+
+        for (raw, _) in &section.body[..first] {
+            out.push_str(raw);
+        }
+        let markdown = contents[first..=last].join("\n");
+        for line in format_markdown(
+            &markdown,
+            config.line_width.saturating_sub(prefix_len),
+            config,
+        ) {
+            if line.is_empty() {
+                out.push_str(&format!("{indent}#'{eol}"));
+            } else {
+                out.push_str(&format!("{indent}#' {line}{eol}"));
+            }
+        }
+        for (raw, _) in &section.body[last + 1..] {
+            out.push_str(raw);
+        }
+    }
+
+    match_final_newline(out, lines[lines.len() - 1])
+}
+
+/// Whether a section's body is Markdown: the untagged lead-in, or a prose tag
+/// with nothing after it on the tag line (`@section` keeps its title there).
+fn is_prose_section(section: &RoxygenSection<'_>) -> bool {
+    let Some(tag_line) = section.tag_line else {
+        return true;
+    };
+    let content = tag_line.trim_start().trim_start_matches("#'").trim();
+    let Some(tag) = content.strip_prefix('@') else {
+        return false;
+    };
+    let (name, rest) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+    ROXYGEN_PROSE_TAGS.contains(&name) && (name == "section" || rest.trim().is_empty())
+}
+
+fn format_docstrings(lines: &[&str], config: &Config) -> String {
+    let mut out = String::new();
+    // A docstring is the first statement of the module or of a def/class body.
+    let mut expect_docstring = true;
+    let mut in_header = false;
+    let mut bracket_depth = 0i32;
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        let code = strip_line_ending(line).trim();
+
+        if code.is_empty() || code.starts_with('#') {
+            out.push_str(line);
+            i += 1;
+            continue;
+        }
+
+        if expect_docstring
+            && !in_header
+            && let Some(end) = docstring_end(lines, i)
+        {
+            out.push_str(&format_docstring(&lines[i..=end], config));
+            expect_docstring = false;
+            i = end + 1;
+            continue;
+        }
+
+        let starts_block = ["def ", "async def ", "class "]
+            .iter()
+            .any(|keyword| code.starts_with(keyword));
+        if in_header || starts_block {
+            if !in_header {
+                expect_docstring = true;
+                bracket_depth = 0;
+            }
+            bracket_depth += code.matches(['(', '[']).count() as i32;
+            bracket_depth -= code.matches([')', ']']).count() as i32;
+            let header = strip_comment(code);
+            in_header = bracket_depth > 0 || header.ends_with('\\');
+            if !in_header && !header.ends_with(':') {
+                // `def f(): return 1` has its body on the header line.
+                expect_docstring = false;
+            }
+        } else {
+            expect_docstring = false;
+        }
+
+        // Skip over other multi-line strings so their contents are never
+        // mistaken for code.
+        let end = unterminated_string_end(lines, i).unwrap_or(i);
+        for line in &lines[i..=end] {
+            out.push_str(line);
+        }
+        i = end + 1;
+    }
+    out
+}
+
+fn strip_comment(code: &str) -> &str {
+    code.split_once(" #")
+        .map_or(code, |(code, _)| code)
+        .trim_end()
+}
+
+/// The string prefix (`r`, `u`, ...) and quote of a triple-quoted string
+/// starting `code`.
+fn triple_quote_start(code: &str) -> Option<(&str, &'static str)> {
+    let prefix_len = code
+        .bytes()
+        .take_while(|b| matches!(b, b'r' | b'R' | b'u' | b'U'))
+        .count();
+    let rest = &code[prefix_len..];
+    ["\"\"\"", "'''"]
+        .into_iter()
+        .find(|quote| rest.starts_with(quote))
+        .map(|quote| (&code[..prefix_len], quote))
+}
+
+/// Index of the closing line of a multi-line docstring opening at `start`.
+/// `None` for single-line docstrings and anything that is not a docstring.
+fn docstring_end(lines: &[&str], start: usize) -> Option<usize> {
+    let code = strip_line_ending(lines[start]).trim_start();
+    let (prefix, quote) = triple_quote_start(code)?;
+    let after_open = &code[prefix.len() + quote.len()..];
+    if after_open.contains(quote) {
+        return None;
+    }
+    let end = (start + 1..lines.len()).find(|&j| lines[j].contains(quote))?;
+    let closing = strip_line_ending(lines[end]);
+    let after_close = &closing[closing.find(quote)? + quote.len()..];
+    if !after_close.trim().is_empty() {
+        return None;
+    }
+    let raw = prefix.contains(['r', 'R']);
+    let has_escape = lines[start..=end].iter().any(|line| line.contains('\\'));
+    (raw || !has_escape).then_some(end)
+}
+
+/// Closing line of a triple-quoted string left open on line `start`.
+fn unterminated_string_end(lines: &[&str], start: usize) -> Option<usize> {
+    let line = lines[start];
+    let quote = ["\"\"\"", "'''"]
+        .into_iter()
+        .filter(|quote| line.matches(quote).count() % 2 == 1)
+        .min_by_key(|quote| line.find(quote))?;
+    (start + 1..lines.len()).find(|&j| lines[j].contains(quote))
+}
+
+fn format_docstring(lines: &[&str], config: &Config) -> String {
+    let first = strip_line_ending(lines[0]);
+    let eol = line_ending(lines[0]);
+    let indent = leading_whitespace(first);
+    let (prefix, quote) = triple_quote_start(first.trim_start()).expect("docstring opening");
+    let opening = format!("{indent}{prefix}{quote}");
+    let summary = first[opening.len()..].trim();
+
+    let last = lines.len() - 1;
+    let closing = strip_line_ending(lines[last]);
+    let tail = closing[..closing.find(quote).expect("docstring closing")].trim_end();
+
+    let mut body: Vec<&str> = lines[1..last]
+        .iter()
+        .map(|line| strip_line_ending(line))
+        .collect();
+    if !tail.trim().is_empty() {
+        body.push(tail);
+    }
+    let dedent = body
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| leading_whitespace(line).len())
+        .min()
+        .unwrap_or(0);
+
+    let mut markdown: Vec<&str> = Vec::new();
+    if !summary.is_empty() {
+        markdown.push(summary);
+    }
+    markdown.extend(
+        body.iter()
+            .map(|line| line.get(dedent..).unwrap_or("").trim_end()),
+    );
+    let text = markdown.join("\n");
+    if text.trim().is_empty() {
+        return lines.concat();
+    }
+
+    // Keep the summary on the opening line when it started there.
+    let width = if summary.is_empty() {
+        config.line_width.saturating_sub(indent.len())
+    } else {
+        config.line_width.saturating_sub(opening.len())
+    };
+    let formatted = format_markdown(text.trim_matches('\n'), width, config);
+
+    let mut out = String::new();
+    let mut rest = formatted.iter();
+    if summary.is_empty() {
+        out.push_str(&format!("{opening}{eol}"));
+    } else {
+        let first_line = rest.next().map(String::as_str).unwrap_or("");
+        out.push_str(&format!("{opening}{first_line}{eol}"));
+    }
+    for line in rest {
+        if line.is_empty() {
+            out.push_str(eol);
+        } else {
+            out.push_str(&format!("{indent}{line}{eol}"));
+        }
+    }
+    out.push_str(&format!("{indent}{quote}{eol}"));
+    match_final_newline(out, lines[last])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(width: usize) -> Config {
+        Config {
+            line_width: width,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn roxygen_reflows_description_and_keeps_tags() {
+        let input = "#' Add numbers\n#'\n#' Adds   two numbers together and returns the result to the caller.\n#'\n#' @param x A   number.\n#' @examples\n#' add(1,   2)\nadd <- function(x, y) x + y\n";
+        let output = format_embedded(input, EmbeddedSyntax::Roxygen, &config(40));
+        assert_eq!(
+            output,
+            "#' Add numbers\n#'\n#' Adds two numbers together and returns\n#' the result to the caller.\n#'\n#' @param x A   number.\n#' @examples\n#' add(1,   2)\nadd <- function(x, y) x + y\n"
+        );
+    }
+
+    #[test]
+    fn roxygen_formats_prose_tag_bodies() {
+        let input =
+            "  #' Title\n  #'\n  #' @details\n  #' *  item one\n  #' *  item two\n  x <- 1\n";
+        let output = format_embedded(input, EmbeddedSyntax::Roxygen, &config(80));
+        assert_eq!(
+            output,
+            "  #' Title\n  #'\n  #' @details\n  #' - item one\n  #' - item two\n  x <- 1\n"
+        );
+    }
+
+    #[test]
+    fn docstring_is_reflowed_at_its_indentation() {
+        let input = "def f():\n    \"\"\"Summary line.\n\n    A   long description that should wrap at the narrower width.\n    \"\"\"\n    return 1\n";
+        let output = format_embedded(input, EmbeddedSyntax::Docstring, &config(40));
+        assert_eq!(
+            output,
+            "def f():\n    \"\"\"Summary line.\n\n    A long description that should\n    wrap at the narrower width.\n    \"\"\"\n    return 1\n"
+        );
+    }
+
+    #[test]
+    fn docstring_skips_single_line_and_escaped_strings() {
+        let input = "def f():\n    \"\"\"One   line.\"\"\"\n\ndef g():\n    \"\"\"Has\n    a \\n   escape.\n    \"\"\"\n";
+        let output = format_embedded(input, EmbeddedSyntax::Docstring, &config(80));
+        assert_eq!(output, input);
+    }
+
+    #[test]
+    fn docstring_ignores_strings_that_are_not_docstrings() {
+        let input = "x = 1\ntext = \"\"\"\ndef fake():\n    \"\"\"\n";
+        let output = format_embedded(input, EmbeddedSyntax::Docstring, &config(80));
+        assert_eq!(output, input);
+    }
+}
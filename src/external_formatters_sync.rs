@@ -3,22 +3,25 @@
 //! This module handles spawning external formatter processes using standard threads
 //! instead of async/await. Suitable for CLI and WASM contexts.
 
-use std::collections::{HashMap, HashSet};
-use std::io::Write;
-use std::process::{Command, Stdio};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::io::{Read, Write};
+use std::process::{Child, Command, Output, Stdio};
 use std::sync::{Mutex, OnceLock};
 
 use std::thread;
 use std::time::Duration;
 
-use crate::config::FormatterConfig;
-pub use crate::external_formatters_common::FormatterError;
+use crate::config::{ExternalSandboxConfig, FormatterConfig};
+pub use crate::external_formatters_common::{FormatterError, FormatterFailure};
 use crate::external_formatters_common::{
-    FormatterIoMode, find_missing_formatter_commands, log_formatter_invocation,
+    FormatterIoMode, disallowed_formatter_warning_message, find_missing_formatter_commands,
+    is_formatter_command_allowed, is_scrubbed_env_allowed, log_formatter_invocation,
     log_formatter_nonzero_exit, log_formatter_spawn_failed, log_formatter_success,
     log_formatter_timeout, log_missing_formatter_commands, resolve_file_args,
     resolve_formatter_configs, resolve_stdin_args, temp_file_extension_for_language,
 };
+use crate::external_tools_common::log_warning_once;
 use panache_formatter::{ExternalCodeBlock, FormattedCodeMap};
 
 /// Format a code block using an external formatter (synchronous).
@@ -27,6 +30,7 @@ use panache_formatter::{ExternalCodeBlock, FormattedCodeMap};
 /// * `code` - The code content to format
 /// * `config` - Formatter configuration (command, args, etc.)
 /// * `timeout` - Maximum duration to wait for the formatter
+/// * `sandbox` - Resource limits applied to the spawned process
 ///
 /// # Returns
 /// * `Ok(String)` - Formatted code on success
@@ -36,20 +40,110 @@ pub fn format_code_sync(
     language: &str,
     config: &FormatterConfig,
     timeout: Duration,
+    sandbox: &ExternalSandboxConfig,
 ) -> Result<String, FormatterError> {
     if config.stdin {
-        format_with_stdin(code, language, config, timeout)
+        format_with_stdin(code, language, config, timeout, sandbox)
     } else {
-        format_with_file(code, language, config, timeout)
+        format_with_file(code, language, config, timeout, sandbox)
     }
 }
 
+/// Build the process for `cmd args...`, applying the sandbox's memory cap and
+/// environment scrubbing.
+///
+/// The memory cap wraps the formatter in `sh -c 'ulimit -v ...; exec "$@"'`,
+/// so it needs a POSIX shell and is ignored (with a debug log) elsewhere. A
+/// shell whose `ulimit` rejects the value still runs the formatter unlimited
+/// rather than failing the block.
+fn build_formatter_command(cmd: &str, args: &[String], sandbox: &ExternalSandboxConfig) -> Command {
+    let mut command = match sandbox.max_memory_mb {
+        Some(mb) if cfg!(unix) => {
+            let mut wrapped = Command::new("sh");
+            wrapped
+                .arg("-c")
+                .arg(r#"ulimit -v "$1" 2>/dev/null; shift; exec "$@""#)
+                .arg("panache-formatter")
+                .arg(mb.saturating_mul(1024).to_string())
+                .arg(cmd)
+                .args(args);
+            wrapped
+        }
+        limit => {
+            if limit.is_some() {
+                log::debug!(
+                    "External formatter memory limit is only supported on Unix; running '{}' without it",
+                    cmd
+                );
+            }
+            let mut plain = Command::new(cmd);
+            plain.args(args);
+            plain
+        }
+    };
+
+    if sandbox.scrub_env {
+        command.env_clear();
+        command.envs(
+            std::env::vars_os()
+                .filter(|(name, _)| name.to_str().is_some_and(is_scrubbed_env_allowed)),
+        );
+    }
+
+    command
+}
+
+/// Wait for `child` and collect its output, enforcing `max_output_bytes` on
+/// stdout when set.
+///
+/// Stdout is read through a bounded reader so a runaway formatter cannot make
+/// panache buffer unbounded output; once the limit is crossed the child is
+/// killed. Stderr is drained on a separate thread to avoid pipe deadlocks.
+fn wait_with_limited_output(
+    mut child: Child,
+    max_output_bytes: Option<usize>,
+) -> Result<Output, FormatterError> {
+    let Some(limit) = max_output_bytes else {
+        return child.wait_with_output().map_err(FormatterError::IoError);
+    };
+    let Some(stdout) = child.stdout.take() else {
+        return child.wait_with_output().map_err(FormatterError::IoError);
+    };
+
+    let stderr_reader = child.stderr.take().map(|mut stderr| {
+        thread::spawn(move || {
+            let mut buf = Vec::new();
+            let _ = stderr.read_to_end(&mut buf);
+            buf
+        })
+    });
+
+    let mut stdout_buf = Vec::new();
+    stdout.take(limit as u64 + 1).read_to_end(&mut stdout_buf)?;
+    if stdout_buf.len() > limit {
+        let _ = child.kill();
+        let _ = child.wait();
+        return Err(FormatterError::OutputTooLarge { limit });
+    }
+
+    let status = child.wait()?;
+    let stderr = stderr_reader
+        .and_then(|handle| handle.join().ok())
+        .unwrap_or_default();
+    Ok(Output {
+        status,
+        stdout: stdout_buf,
+        stderr,
+    })
+}
+
 /// Format code by piping through stdin/stdout (synchronous).
 fn format_with_stdin(
     code: &str,
     language: &str,
     config: &FormatterConfig,
     timeout: Duration,
+    sandbox: &ExternalSandboxConfig,
 ) -> Result<String, FormatterError> {
     let resolved_args = resolve_stdin_args(&config.args, language);
     log_formatter_invocation(
@@ -60,9 +154,8 @@ fn format_with_stdin(
     );
 
     // Build command
-    let mut cmd = Command::new(&config.cmd);
-    cmd.args(&resolved_args)
-        .stdin(Stdio::piped())
+    let mut cmd = build_formatter_command(&config.cmd, &resolved_args, sandbox);
+    cmd.stdin(Stdio::piped())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
 
@@ -85,8 +178,9 @@ fn format_with_stdin(
     let (tx, rx) = mpsc::channel();
 
     // Spawn thread to wait for process
+    let max_output_bytes = sandbox.max_output_bytes;
     thread::spawn(move || {
-        let output = child.wait_with_output();
+        let output = wait_with_limited_output(child, max_output_bytes);
         let _ = tx.send(output);
     });
 
@@ -118,8 +212,8 @@ fn format_with_stdin(
             }
         }
         Ok(Err(e)) => {
-            log::error!("Formatter I/O error: {}", e);
-            Err(FormatterError::IoError(e))
+            log::error!("Formatter error: {}", e);
+            Err(e)
         }
         Err(_) => {
             log_formatter_timeout(&config.cmd, language, FormatterIoMode::Stdin);
@@ -134,6 +228,7 @@ fn format_with_file(
     language: &str,
     config: &FormatterConfig,
     timeout: Duration,
+    sandbox: &ExternalSandboxConfig,
 ) -> Result<String, FormatterError> {
     use std::fs;
 
@@ -158,8 +253,8 @@ fn format_with_file(
     );
 
     // Spawn the formatter process
-    let mut cmd = Command::new(&config.cmd);
-    cmd.args(&args).stderr(Stdio::piped());
+    let mut cmd = build_formatter_command(&config.cmd, &args, sandbox);
+    cmd.stderr(Stdio::piped());
 
     let child = cmd.spawn().map_err(|e| {
         log_formatter_spawn_failed(&config.cmd, language, FormatterIoMode::File, &e);
@@ -182,6 +277,17 @@ fn format_with_file(
     let start = Instant::now();
     let result = match rx.recv_timeout(timeout) {
         Ok(Ok(output)) => {
+            // Reject oversized output before reading it back into memory
+            if let Some(limit) = sandbox.max_output_bytes {
+                let len = fs::metadata(&temp_path)
+                    .map_err(FormatterError::IoError)?
+                    .len();
+                if len > limit as u64 {
+                    let _ = fs::remove_file(&temp_path);
+                    return Err(FormatterError::OutputTooLarge { limit });
+                }
+            }
+
             // Read formatted content from file
             let formatted = fs::read_to_string(&temp_path).map_err(FormatterError::IoError)?;
 
@@ -229,16 +335,20 @@ fn format_with_file(
 /// # Arguments
 /// * `blocks` - Vector of code blocks to format
 /// * `formatters` - Map of language to formatter config
-/// * `timeout` - Timeout per formatter invocation
+/// * `timeout` - Timeout per formatter invocation, unless the formatter sets
+///   its own `timeout-ms`
+/// * `sandbox` - Resource limits and command policy for spawned formatters
 ///
 /// # Returns
-/// HashMap of original code -> formatted code (only successful formats)
+/// Map of original code -> formatted code (only successful formats), and
+/// the formatter failures that left blocks unformatted
 pub fn run_formatters_parallel(
     blocks: Vec<ExternalCodeBlock>,
-    formatters: &HashMap<String, Vec<FormatterConfig>>,
+    formatters: &BTreeMap<String, Vec<FormatterConfig>>,
     timeout: Duration,
     max_parallel: usize,
-) -> FormattedCodeMap {
+    sandbox: &ExternalSandboxConfig,
+) -> (FormattedCodeMap, Vec<FormatterFailure>) {
     use rayon::prelude::*;
 
     let missing_formatters = find_missing_formatter_commands(formatters);
@@ -251,7 +361,7 @@ pub fn run_formatters_parallel(
     // output, so the formatter chain runs once per group instead of once per
     // block. Blocks in a group can still differ in `original`/`hashpipe_prefix`,
     // so each group fans back out to one map entry per block.
-    let mut groups: HashMap<(String, String), Vec<ExternalCodeBlock>> = HashMap::new();
+    let mut groups: BTreeMap<(String, String), Vec<ExternalCodeBlock>> = BTreeMap::new();
     for block in blocks {
         groups
             .entry((block.language.clone(), block.formatter_input.clone()))
@@ -259,23 +369,38 @@ pub fn run_formatters_parallel(
             .push(block);
     }
     let groups: Vec<((String, String), Vec<ExternalCodeBlock>)> = groups.into_iter().collect();
+    let mut run = FormatterRun::default();
+    for (lang, input) in groups.iter().map(|(key, _)| key) {
+        run.blocks.insert(block_key(lang, input));
+    }
 
     let pool = rayon::ThreadPoolBuilder::new()
         .num_threads(max_parallel)
         .build()
         .expect("failed to build rayon thread pool");
 
-    pool.install(|| {
+    let results: Vec<_> = pool.install(|| {
         groups
             .into_par_iter()
-            .flat_map(|((lang, input), blocks)| {
-                let Some(formatted) =
-                    run_formatter_chain(&lang, &input, formatters, &missing_formatters, timeout)
-                else {
-                    return Vec::new();
+            .map(|((lang, input), blocks)| {
+                let formatted = match run_formatter_chain(
+                    &lang,
+                    &input,
+                    formatters,
+                    &missing_formatters,
+                    timeout,
+                    sandbox,
+                ) {
+                    Ok(Some(formatted)) => formatted,
+                    Ok(None) => return (Vec::new(), None),
+                    Err(mut failure) => {
+                        let key = block_key(&lang, &input);
+                        failure.ranges = blocks.into_iter().map(|block| block.range).collect();
+                        return (Vec::new(), Some((key, failure)));
+                    }
                 };
 
-                blocks
+                let entries = blocks
                     .into_iter()
                     .filter_map(|block| {
                         if formatted == block.original {
@@ -287,34 +412,65 @@ pub fn run_formatters_parallel(
                         };
                         Some(((lang.clone(), block.original), output))
                     })
-                    .collect::<Vec<_>>()
+                    .collect::<Vec<_>>();
+                (entries, None)
             })
-            .collect::<FormattedCodeMap>()
-    })
+            .collect()
+    });
+
+    let mut formatted = FormattedCodeMap::new();
+    let mut failures: Vec<FormatterFailure> = Vec::new();
+    for (entries, failure) in results {
+        formatted.extend(entries);
+        if let Some((key, failure)) = failure {
+            run.failures.insert(key, failure.clone());
+            failures.push(failure);
+        }
+    }
+    failures.sort_by_key(|failure| failure.ranges.first().map(|r| r.start));
+    record_formatter_run(run);
+    (formatted, failures)
 }
 
 /// Run the configured formatter chain for `lang` over `input`, returning the
 /// formatted output (post-chain, before any hashpipe prefix is re-applied).
 ///
-/// Returns `None` when no formatter is configured, a required command is
-/// missing, or any step in the chain fails — matching the per-block fallback of
-/// leaving the block unchanged. Results are memoized through
-/// [`FORMATTER_CHAIN_CACHE`]; failures are never cached.
+/// Returns `Ok(None)` when no formatter is configured or a required command is
+/// missing or not allowlisted (both already warned about), and a
+/// [`FormatterFailure`] when a step in the chain fails. Either way the blocks
+/// are left unchanged. Results are memoized through [`FORMATTER_CHAIN_CACHE`];
+/// failures are never cached.
 fn run_formatter_chain(
     lang: &str,
     input: &str,
-    formatters: &HashMap<String, Vec<FormatterConfig>>,
+    formatters: &BTreeMap<String, Vec<FormatterConfig>>,
     missing_formatters: &HashSet<String>,
     timeout: Duration,
-) -> Option<String> {
-    let formatter_configs = resolve_formatter_configs(formatters, lang)?;
+    sandbox: &ExternalSandboxConfig,
+) -> Result<Option<String>, FormatterFailure> {
+    let Some(formatter_configs) = resolve_formatter_configs(formatters, lang) else {
+        return Ok(None);
+    };
     if formatter_configs.is_empty() {
-        return None;
+        return Ok(None);
+    }
+
+    // Checked before the cache so tightening the policy in a long-lived process
+    // (the LSP) also stops serving results produced by now-disallowed commands.
+    if let Some(disallowed) = formatter_configs
+        .iter()
+        .find(|cfg| !cfg.cmd.trim().is_empty() && !is_formatter_command_allowed(cfg, sandbox))
+    {
+        log_warning_once(&disallowed_formatter_warning_message(
+            disallowed.cmd.trim(),
+            lang,
+        ));
+        return Ok(None);
     }
 
     let chain_fp = chain_fingerprint(formatter_configs);
     if let Some(cached) = chain_cache_get(&chain_fp, lang, input) {
-        return Some(cached);
+        return Ok(Some(cached));
     }
 
     // Bound concurrent subprocesses to the shared external-tool budget. Held for
@@ -332,7 +488,7 @@ fn run_formatter_chain(
         }
 
         if missing_formatters.contains(formatter_cmd) {
-            return None;
+            return Ok(None);
         }
 
         log::debug!(
@@ -343,24 +499,78 @@ fn run_formatter_chain(
             formatter_configs.len()
         );
 
-        match format_code_sync(&current_code, lang, formatter_cfg, timeout) {
+        let timeout = formatter_cfg
+            .timeout_ms
+            .map_or(timeout, Duration::from_millis);
+        match format_code_sync(&current_code, lang, formatter_cfg, timeout, sandbox) {
             Ok(formatted) => {
                 current_code = formatted;
             }
             Err(e) => {
-                log::warn!(
+                log::debug!(
                     "{} formatter '{}' failed: {}. Falling back to original code block unchanged.",
                     lang,
                     formatter_cfg.cmd,
                     e
                 );
-                return None;
+                return Err(FormatterFailure::new(lang, formatter_cmd, &e));
             }
         }
     }
 
     chain_cache_put(&chain_fp, lang, input, &current_code);
-    Some(current_code)
+    Ok(Some(current_code))
+}
+
+/// The code blocks one [`run_formatters_parallel`] call formatted, and the
+/// failures among them. Blocks are identified by language and a hash of their
+/// formatter input, so a run holds no copies of the documents.
+#[derive(Default)]
+struct FormatterRun {
+    blocks: HashSet<(String, u64)>,
+    failures: HashMap<(String, u64), FormatterFailure>,
+}
+
+/// The most recent formatting runs, oldest first, so a long-lived process (the
+/// LSP) can report failed blocks as diagnostics after the formatting request
+/// that hit them has returned. The oldest run is evicted once
+/// [`FORMATTER_RUNS_CAP`] are kept.
+static FORMATTER_RUNS: Mutex<VecDeque<FormatterRun>> = Mutex::new(VecDeque::new());
+
+/// Number of formatting runs whose failures are kept.
+const FORMATTER_RUNS_CAP: usize = 32;
+
+fn block_key(lang: &str, input: &str) -> (String, u64) {
+    let mut hasher = std::hash::DefaultHasher::new();
+    input.hash(&mut hasher);
+    (lang.to_string(), hasher.finish())
+}
+
+fn record_formatter_run(run: FormatterRun) {
+    if run.blocks.is_empty() {
+        return;
+    }
+    let mut runs = FORMATTER_RUNS
+        .lock()
+        .expect("formatter runs mutex poisoned");
+    if runs.len() == FORMATTER_RUNS_CAP {
+        runs.pop_front();
+    }
+    runs.push_back(run);
+}
+
+/// The failure of the formatter chain for `lang` on `input` in the most recent
+/// run that formatted it, if that run failed. The returned failure carries no
+/// block ranges.
+pub fn recorded_formatter_failure(lang: &str, input: &str) -> Option<FormatterFailure> {
+    let key = block_key(lang, input);
+    let runs = FORMATTER_RUNS
+        .lock()
+        .expect("formatter runs mutex poisoned");
+    let run = runs.iter().rev().find(|run| run.blocks.contains(&key))?;
+    let mut failure = run.failures.get(&key)?.clone();
+    failure.ranges.clear();
+    Some(failure)
 }
 
 /// Process-global memoization of external-formatter chain results, keyed on the
@@ -436,6 +646,7 @@ mod tests {
             cmd: cmd.to_string(),
             args: args.iter().map(|a| a.to_string()).collect(),
             stdin,
+            timeout_ms: None,
         }
     }
 
@@ -466,6 +677,44 @@ mod tests {
         );
     }
 
+    #[test]
+    #[cfg(unix)]
+    fn failed_chain_reports_stderr_and_is_recorded_until_a_later_run() {
+        let run = |args: &[&str]| {
+            let formatters =
+                BTreeMap::from([("failtest".to_string(), vec![cfg("sh", args, true)])]);
+            let block = ExternalCodeBlock {
+                language: "failtest".to_string(),
+                original: "x=1\n".to_string(),
+                formatter_input: "x=1\n".to_string(),
+                hashpipe_prefix: None,
+                range: 0..12,
+            };
+            run_formatters_parallel(
+                vec![block],
+                &formatters,
+                Duration::from_secs(5),
+                1,
+                &ExternalSandboxConfig::default(),
+            )
+            .1
+        };
+
+        let failures = run(&["-c", "cat >/dev/null; echo 'cannot parse' >&2; exit 3"]);
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].command, "sh");
+        assert_eq!(failures[0].message, "exited with code 3: cannot parse");
+        assert_eq!(failures[0].ranges, vec![0..12]);
+        assert_eq!(
+            recorded_formatter_failure("failtest", "x=1\n").map(|f| f.message),
+            Some(failures[0].message.clone())
+        );
+        assert_eq!(recorded_formatter_failure("failtest", "y=2\n"), None);
+
+        assert!(run(&["-c", "cat"]).is_empty());
+        assert_eq!(recorded_formatter_failure("failtest", "x=1\n"), None);
+    }
+
     #[test]
     fn cache_round_trips_per_chain_lang_and_input() {
         // Use a fingerprint unique to this test so the process-global cache can't
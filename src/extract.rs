@@ -0,0 +1,213 @@
+//! Code cell extraction for `panache extract`.
+//!
+//! Pulls the fenced code blocks of one language out of a document so external
+//! tools can run over them. Blocks match by [`Config::language_key`], so
+//! `--lang python` also picks up fences labeled `py` or `{python}`.
+
+use std::collections::HashMap;
+
+use panache_parser::parser::blocks::code_blocks::{CodeBlockType, InfoString};
+use panache_parser::parser::utils::chunk_options::hashpipe_comment_prefix;
+
+use crate::config::Config;
+use crate::external_formatters_common::temp_file_extension_for_language;
+use crate::syntax::{SyntaxKind, SyntaxNode};
+use crate::utils::collect_code_blocks_by_language_key;
+
+/// A code block pulled out of a document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CodeCell {
+    /// Chunk label (`{r setup}`, `label = "setup"`, `#| label: setup`) or a
+    /// display block's `#id`.
+    pub label: Option<String>,
+    /// Block content without the fences.
+    pub content: String,
+    /// Line of the first content line in the document (1-indexed).
+    pub start_line: usize,
+}
+
+/// Code blocks in `tree` whose language is `language`, in document order.
+pub fn extract_cells(
+    tree: &SyntaxNode,
+    input: &str,
+    config: &Config,
+    language: &str,
+) -> Vec<CodeCell> {
+    let key = config.language_key(language);
+    let Some(blocks) = collect_code_blocks_by_language_key(tree, input, config).remove(&key) else {
+        return Vec::new();
+    };
+    let labels = collect_labels(tree, config);
+    blocks
+        .into_iter()
+        .map(|block| {
+            let label = labels
+                .get(&block.original_range.start)
+                .cloned()
+                .or_else(|| hashpipe_label(&block.content, &key));
+            CodeCell {
+                label,
+                content: block.content,
+                start_line: block.start_line,
+            }
+        })
+        .collect()
+}
+
+/// Append `cell` to `out`, after a blank line if `out` is not empty and a
+/// `---- label ----` comment if the cell has a label and the language's
+/// comment syntax is known. Returns the output line (1-indexed) of the cell's
+/// first content line.
+pub fn write_cell(out: &mut String, cell: &CodeCell, language: &str) -> usize {
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    if let Some(comment) = cell
+        .label
+        .as_deref()
+        .and_then(|label| label_comment(label, language))
+    {
+        out.push_str(&comment);
+        out.push('\n');
+    }
+    let line = out.matches('\n').count() + 1;
+    out.push_str(&cell.content);
+    if !cell.content.ends_with('\n') {
+        out.push('\n');
+    }
+    line
+}
+
+/// The knitr-style `# ---- label ----` comment written above a labeled cell,
+/// or `None` if the language's comment syntax is unknown.
+pub fn label_comment(label: &str, language: &str) -> Option<String> {
+    let prefix = hashpipe_comment_prefix(language)?.strip_suffix('|')?;
+    Some(format!("{prefix} ---- {label} ----"))
+}
+
+/// File extension for cells of `language` (`py` for Python, `jl` for Julia).
+/// Languages without a known extension use their own name.
+pub fn file_extension(language: &str) -> String {
+    match temp_file_extension_for_language(language) {
+        "txt" => language
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .collect(),
+        ext => ext.to_string(),
+    }
+}
+
+/// Labels from code block info strings, keyed by the byte offset where the
+/// block's content starts.
+fn collect_labels(tree: &SyntaxNode, config: &Config) -> HashMap<usize, String> {
+    let dialect = panache_parser::Dialect::for_flavor(config.flavor);
+    let mut labels = HashMap::new();
+    for node in tree.descendants() {
+        if node.kind() != SyntaxKind::CODE_BLOCK {
+            continue;
+        }
+        let info = node
+            .descendants()
+            .find(|child| child.kind() == SyntaxKind::CODE_INFO);
+        let content = node
+            .children()
+            .find(|child| child.kind() == SyntaxKind::CODE_CONTENT);
+        let (Some(info), Some(content)) = (info, content) else {
+            continue;
+        };
+        let info = InfoString::parse_with_dialect(&info.text().to_string(), dialect);
+        if let Some(label) = info_label(&info) {
+            labels.insert(content.text_range().start().into(), label);
+        }
+    }
+    labels
+}
+
+fn info_label(info: &InfoString) -> Option<String> {
+    let label = match info.block_type {
+        CodeBlockType::Executable { .. } => info
+            .attributes
+            .iter()
+            .find(|(key, _)| key == "label")
+            .and_then(|(_, value)| value.clone()),
+        _ => info.attributes.iter().find_map(|(key, value)| match value {
+            None => key.strip_prefix('#').map(str::to_string),
+            Some(_) => None,
+        }),
+    };
+    label.filter(|label| !label.is_empty())
+}
+
+/// A `#| label: name` option at the top of the cell.
+fn hashpipe_label(content: &str, language: &str) -> Option<String> {
+    let prefix = hashpipe_comment_prefix(language)?;
+    content
+        .lines()
+        .map_while(|line| line.trim_start().strip_prefix(prefix))
+        .filter_map(|option| option.split_once(':'))
+        .find(|(key, _)| key.trim() == "label")
+        .map(|(_, value)| value.trim().trim_matches(['"', '\'']).to_string())
+        .filter(|label| !label.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Extensions, Flavor};
+
+    fn cells(input: &str, language: &str) -> Vec<CodeCell> {
+        let config = Config {
+            flavor: Flavor::Quarto,
+            extensions: Extensions::for_flavor(Flavor::Quarto),
+            ..Default::default()
+        };
+        let tree = crate::parser::parse(input, Some(config.clone()));
+        extract_cells(&tree, input, &config, language)
+    }
+
+    #[test]
+    fn extracts_matching_blocks_with_labels() {
+        let input = "```{r setup}\nlibrary(x)\n```\n\n```{python}\nprint(1)\n```\n\n```{r}\n#| label: plot\nplot(x)\n```\n";
+        let cells = cells(input, "r");
+        assert_eq!(cells.len(), 2);
+        assert_eq!(cells[0].label.as_deref(), Some("setup"));
+        assert_eq!(cells[0].content, "library(x)\n");
+        assert_eq!(cells[0].start_line, 2);
+        assert_eq!(cells[1].label.as_deref(), Some("plot"));
+        assert_eq!(cells[1].start_line, 10);
+    }
+
+    #[test]
+    fn matches_language_aliases() {
+        let input = "```py\na = 1\n```\n\n``` {.python #lst-b}\nb = 2\n```\n";
+        let cells = cells(input, "python");
+        assert_eq!(cells.len(), 2);
+        assert_eq!(cells[0].label, None);
+        assert_eq!(cells[1].label.as_deref(), Some("lst-b"));
+    }
+
+    #[test]
+    fn write_cell_adds_label_comments_and_tracks_lines() {
+        let mut out = String::new();
+        let first = CodeCell {
+            label: Some("setup".to_string()),
+            content: "x <- 1\n".to_string(),
+            start_line: 2,
+        };
+        let second = CodeCell {
+            label: None,
+            content: "y <- 2".to_string(),
+            start_line: 8,
+        };
+        assert_eq!(write_cell(&mut out, &first, "r"), 2);
+        assert_eq!(write_cell(&mut out, &second, "r"), 4);
+        assert_eq!(out, "# ---- setup ----\nx <- 1\n\ny <- 2\n");
+    }
+
+    #[test]
+    fn file_extensions() {
+        assert_eq!(file_extension("python"), "py");
+        assert_eq!(file_extension("r"), "r");
+        assert_eq!(file_extension("stan"), "stan");
+    }
+}
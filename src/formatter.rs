@@ -1,10 +1,14 @@
-use crate::config::Config;
+use crate::config::{Config, FinalNewline};
+use crate::error::{self, FormatError};
 use crate::external_formatters_sync;
 use crate::syntax::{SyntaxKind, SyntaxNode, YamlFrontmatterRegion};
 use panache_formatter::FormattedCodeMap;
-use std::collections::HashMap;
+use panache_formatter::formatter::whitespace;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
 
-fn to_formatter_config(config: &Config) -> panache_formatter::Config {
+pub(crate) fn to_formatter_config(config: &Config) -> panache_formatter::Config {
     let line_ending = config.line_ending.as_ref().map(|ending| match ending {
         crate::config::LineEnding::Auto => panache_formatter::LineEnding::Auto,
         crate::config::LineEnding::Lf => panache_formatter::LineEnding::Lf,
@@ -21,6 +25,10 @@ fn to_formatter_config(config: &Config) -> panache_formatter::Config {
             panache_formatter::MathDelimiterStyle::Backslash
         }
     };
+    let final_newline = match config.final_newline {
+        crate::config::FinalNewline::Ensure => panache_formatter::FinalNewline::Ensure,
+        crate::config::FinalNewline::Preserve => panache_formatter::FinalNewline::Preserve,
+    };
     let tab_stops = match config.tab_stops {
         crate::config::TabStopMode::Normalize => panache_formatter::TabStopMode::Normalize,
         crate::config::TabStopMode::Preserve => panache_formatter::TabStopMode::Preserve,
@@ -34,6 +42,92 @@ fn to_formatter_config(config: &Config) -> panache_formatter::Config {
     let blank_lines = match config.blank_lines {
         crate::config::BlankLines::Preserve => panache_formatter::BlankLines::Preserve,
         crate::config::BlankLines::Collapse => panache_formatter::BlankLines::Collapse,
+        crate::config::BlankLines::Max(lines) => panache_formatter::BlankLines::Max(lines),
+    };
+    let emphasis_marker = |marker: crate::config::EmphasisMarker| match marker {
+        crate::config::EmphasisMarker::Asterisk => panache_formatter::EmphasisMarker::Asterisk,
+        crate::config::EmphasisMarker::Underscore => panache_formatter::EmphasisMarker::Underscore,
+        crate::config::EmphasisMarker::Preserve => panache_formatter::EmphasisMarker::Preserve,
+    };
+    let escaping = match config.escaping {
+        crate::config::Escaping::Preserve => panache_formatter::Escaping::Preserve,
+        crate::config::Escaping::Minimal => panache_formatter::Escaping::Minimal,
+    };
+    let link_destinations = match config.link_destinations {
+        crate::config::LinkDestinations::Preserve => panache_formatter::LinkDestinations::Preserve,
+        crate::config::LinkDestinations::AngleBrackets => {
+            panache_formatter::LinkDestinations::AngleBrackets
+        }
+        crate::config::LinkDestinations::PercentEncode => {
+            panache_formatter::LinkDestinations::PercentEncode
+        }
+    };
+    let bare_urls = match config.bare_urls {
+        crate::config::BareUrls::Preserve => panache_formatter::BareUrls::Preserve,
+        crate::config::BareUrls::Autolink => panache_formatter::BareUrls::Autolink,
+        crate::config::BareUrls::Link => panache_formatter::BareUrls::Link,
+    };
+    let allow_breaks_in = config
+        .allow_breaks_in
+        .iter()
+        .map(|construct| match construct {
+            crate::config::BreakableInline::LinkText => {
+                panache_formatter::BreakableInline::LinkText
+            }
+            crate::config::BreakableInline::ImageAlt => {
+                panache_formatter::BreakableInline::ImageAlt
+            }
+            crate::config::BreakableInline::Emphasis => {
+                panache_formatter::BreakableInline::Emphasis
+            }
+            crate::config::BreakableInline::Span => panache_formatter::BreakableInline::Span,
+        })
+        .collect();
+    let blank_lines_around = config
+        .blank_lines_around
+        .iter()
+        .map(|block| match block {
+            crate::config::BlankLinesAround::Headings => {
+                panache_formatter::BlankLinesAround::Headings
+            }
+            crate::config::BlankLinesAround::Code => panache_formatter::BlankLinesAround::Code,
+            crate::config::BlankLinesAround::Lists => panache_formatter::BlankLinesAround::Lists,
+        })
+        .collect();
+    let blockquotes = panache_formatter::BlockquoteStyle {
+        marker_spacing: config.blockquotes.marker_spacing,
+        collapse_lazy: config.blockquotes.collapse_lazy,
+    };
+    let code_blocks = panache_formatter::CodeBlockStyle {
+        attribute_style: match config.code_blocks.attribute_style {
+            crate::config::AttributeStyle::Shortcut => panache_formatter::AttributeStyle::Shortcut,
+            crate::config::AttributeStyle::Explicit => panache_formatter::AttributeStyle::Explicit,
+            crate::config::AttributeStyle::Canonical => {
+                panache_formatter::AttributeStyle::Canonical
+            }
+            crate::config::AttributeStyle::Preserve => panache_formatter::AttributeStyle::Preserve,
+        },
+        lowercase_language: config.code_blocks.lowercase_language,
+    };
+    let definition_lists = panache_formatter::DefinitionListStyle {
+        marker: match config.definition_lists.marker {
+            crate::config::DefinitionMarker::Colon => panache_formatter::DefinitionMarker::Colon,
+            crate::config::DefinitionMarker::Tilde => panache_formatter::DefinitionMarker::Tilde,
+        },
+        spaces: config.definition_lists.spaces,
+        compact: config.definition_lists.compact,
+    };
+    let div_fence_length = match config.divs.fence_length {
+        crate::config::DivFenceLength::DepthBased => panache_formatter::DivFenceLength::DepthBased,
+        crate::config::DivFenceLength::Preserve => panache_formatter::DivFenceLength::Preserve,
+        crate::config::DivFenceLength::Fixed(colons) => {
+            panache_formatter::DivFenceLength::Fixed(colons)
+        }
+    };
+    let heading_numbering = match config.headings.numbering {
+        crate::config::HeadingNumbering::Preserve => panache_formatter::HeadingNumbering::Preserve,
+        crate::config::HeadingNumbering::Renumber => panache_formatter::HeadingNumbering::Renumber,
+        crate::config::HeadingNumbering::Strip => panache_formatter::HeadingNumbering::Strip,
     };
     let horizontal_rule_style = match config.horizontal_rule_style {
         crate::config::HorizontalRuleStyle::LineWidth => {
@@ -69,7 +163,7 @@ fn to_formatter_config(config: &Config) -> panache_formatter::Config {
         smart_quotes: config.formatter_extensions.smart_quotes,
     };
 
-    let formatters: HashMap<String, Vec<panache_formatter::config::FormatterConfig>> = config
+    let formatters: BTreeMap<String, Vec<panache_formatter::config::FormatterConfig>> = config
         .formatters
         .iter()
         .map(|(lang, entries)| {
@@ -90,21 +184,44 @@ fn to_formatter_config(config: &Config) -> panache_formatter::Config {
         parser_extensions: config.extensions.clone(),
         formatter_extensions,
         line_ending,
+        final_newline,
+        trim_trailing_whitespace: config.trim_trailing_whitespace,
         line_width: config.line_width,
         math_indent: config.math_indent,
         math_delimiter_style,
         table_indent: config.table_indent,
+        table_max_width: config.table_max_width,
+        code_comment_width: config.code_comment_width,
         tab_stops,
         tab_width: config.tab_width,
         wrap,
+        max_width_strict: config.max_width_strict,
         blank_lines,
+        blank_lines_around,
+        blank_lines_before_heading: config.blank_lines_before_heading,
         horizontal_rule_style,
+        emphasis_marker: emphasis_marker(config.emphasis_marker),
+        strong_marker: emphasis_marker(config.strong_marker),
+        escaping,
+        allow_breaks_in,
+        blockquotes,
+        code_blocks,
+        definition_lists,
+        div_fence_length,
+        heading_numbering,
+        toc: panache_formatter::TocStyle {
+            min_level: config.toc.min_level,
+            max_level: config.toc.max_level,
+        },
+        link_destinations,
+        bare_urls,
         lang: config.lang.clone(),
         no_break_abbreviations,
         formatters,
         external_max_parallel: config.external_max_parallel,
         parser: config.parser,
         experimental_format_math: config.experimental.format_math,
+        fail_safe: config.fail_safe,
     }
 }
 
@@ -134,27 +251,192 @@ fn collect_yaml_frontmatter_region(tree: &SyntaxNode) -> Option<YamlFrontmatterR
 #[cfg(not(target_arch = "wasm32"))]
 fn format_code_blocks_sync(
     blocks: Vec<panache_formatter::ExternalCodeBlock>,
+    input: &str,
     config: &Config,
 ) -> FormattedCodeMap {
     use std::time::Duration;
     let timeout = Duration::from_secs(30);
-    external_formatters_sync::run_formatters_parallel(
+    let formatters = formatters_with_language_aliases(config);
+    let (formatted, failures) = external_formatters_sync::run_formatters_parallel(
         blocks,
-        &config.formatters,
+        &formatters,
         timeout,
         config.external_max_parallel,
-    )
+        &config.external_sandbox,
+    );
+    report_formatter_failures(input, &failures);
+    formatted
+}
+
+/// `config.formatters` plus an entry for every `[languages]` alias whose
+/// target language has formatters, so fences using the alias are formatted
+/// too. An exact `formatters` key for the alias itself still wins.
+#[cfg(not(target_arch = "wasm32"))]
+fn formatters_with_language_aliases(
+    config: &Config,
+) -> std::borrow::Cow<'_, BTreeMap<String, Vec<crate::config::FormatterConfig>>> {
+    use crate::external_formatters_common::resolve_formatter_configs;
+
+    if config.languages.is_empty() {
+        return std::borrow::Cow::Borrowed(&config.formatters);
+    }
+    let mut formatters = config.formatters.clone();
+    for (alias, language) in &config.languages {
+        if config.formatters.contains_key(alias) {
+            continue;
+        }
+        if let Some(configs) =
+            resolve_formatter_configs(&config.formatters, language).or_else(|| {
+                resolve_formatter_configs(&config.formatters, &config.language_key(language))
+            })
+        {
+            formatters.insert(alias.clone(), configs.clone());
+        }
+    }
+    std::borrow::Cow::Owned(formatters)
 }
 
 #[cfg(target_arch = "wasm32")]
 fn format_code_blocks_sync(
     _blocks: Vec<panache_formatter::ExternalCodeBlock>,
+    _input: &str,
     _config: &Config,
 ) -> FormattedCodeMap {
     FormattedCodeMap::new()
 }
 
-pub fn format_tree(tree: &SyntaxNode, config: &Config, range: Option<(usize, usize)>) -> String {
+/// Formats the content of one code block, given its language and code, or
+/// returns `None` to leave the block unchanged.
+pub type CodeFormatterCallback = Box<dyn Fn(&str, &str) -> Option<String>>;
+
+thread_local! {
+    static CODE_FORMATTER: RefCell<Option<CodeFormatterCallback>> = const { RefCell::new(None) };
+}
+
+/// Install `callback` as this thread's code block formatter, or remove it with
+/// `None`. While one is installed, code blocks are formatted by calling it
+/// instead of spawning the commands configured in [`Config::formatters`], and
+/// every code block is offered to it whether or not `formatters` is set. This
+/// is how the WASM build formats code cells with JavaScript functions.
+pub fn set_code_formatter(callback: Option<CodeFormatterCallback>) {
+    CODE_FORMATTER.with(|formatter| *formatter.borrow_mut() = callback);
+}
+
+fn has_code_formatter() -> bool {
+    CODE_FORMATTER.with(|formatter| formatter.borrow().is_some())
+}
+
+/// Format `blocks` with the installed code formatter callback, or with the
+/// configured external commands when there is none.
+fn format_code_blocks(
+    blocks: Vec<panache_formatter::ExternalCodeBlock>,
+    input: &str,
+    config: &Config,
+) -> FormattedCodeMap {
+    CODE_FORMATTER.with(|formatter| match formatter.borrow().as_ref() {
+        Some(callback) => format_code_blocks_with_callback(blocks, callback),
+        None => format_code_blocks_sync(blocks, input, config),
+    })
+}
+
+fn format_code_blocks_with_callback(
+    blocks: Vec<panache_formatter::ExternalCodeBlock>,
+    callback: &CodeFormatterCallback,
+) -> FormattedCodeMap {
+    let mut results: HashMap<(String, String), Option<String>> = HashMap::new();
+    let mut formatted_code = FormattedCodeMap::new();
+    for block in blocks {
+        let formatted = results
+            .entry((block.language.clone(), block.formatter_input.clone()))
+            .or_insert_with(|| callback(&block.language, &block.formatter_input));
+        let Some(formatted) = formatted else {
+            continue;
+        };
+        if *formatted == block.original {
+            continue;
+        }
+        let output = match &block.hashpipe_prefix {
+            Some(prefix) => format!("{prefix}{formatted}"),
+            None => formatted.clone(),
+        };
+        formatted_code.insert((block.language, block.original), output);
+    }
+    formatted_code
+}
+
+/// Warn about code blocks left unformatted because their external formatter
+/// failed, naming the language, the command, and the blocks' lines.
+#[cfg(not(target_arch = "wasm32"))]
+fn report_formatter_failures(input: &str, failures: &[external_formatters_sync::FormatterFailure]) {
+    for failure in failures {
+        let lines: Vec<String> = failure
+            .ranges
+            .iter()
+            .map(|range| (input[..range.start].matches('\n').count() + 1).to_string())
+            .collect();
+        let blocks = match lines.as_slice() {
+            [line] => format!("the code block at line {line}"),
+            lines => format!("the code blocks at lines {}", lines.join(", ")),
+        };
+        crate::external_tools_common::log_warning_once(&format!(
+            "{} formatter '{}' failed on {} ({}); left unformatted",
+            failure.language, failure.command, blocks, failure.message
+        ));
+    }
+}
+
+/// Diagnostics for the code blocks of `tree` whose external formatter failed
+/// the last time it ran on their current content (see
+/// [`external_formatters_sync::recorded_formatter_failure`]). Used by the LSP,
+/// where formatting and diagnostics are separate requests.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn formatter_failure_diagnostics(
+    tree: &SyntaxNode,
+    config: &Config,
+) -> Vec<crate::linter::diagnostics::Diagnostic> {
+    use crate::linter::diagnostics::{Diagnostic, DiagnosticOrigin, Location};
+
+    if config.formatters.is_empty() {
+        return Vec::new();
+    }
+    let input = tree.text().to_string();
+    let formatter_config = to_formatter_config(config);
+    panache_formatter::collect_code_blocks(tree, &input, &formatter_config)
+        .into_iter()
+        .filter_map(|block| {
+            let failure = external_formatters_sync::recorded_formatter_failure(
+                &block.language,
+                &block.formatter_input,
+            )?;
+            let range = rowan::TextRange::new(
+                (block.range.start as u32).into(),
+                (block.range.end as u32).into(),
+            );
+            Some(
+                Diagnostic::warning(
+                    Location::from_range(range, &input),
+                    "external-formatter",
+                    format!(
+                        "{} formatter '{}' failed ({}); the code block was left unformatted",
+                        failure.language, failure.command, failure.message
+                    ),
+                )
+                .with_origin(DiagnosticOrigin::External),
+            )
+        })
+        .collect()
+}
+
+/// Everything [`format_tree`] computes before rendering the markdown itself:
+/// the external-formatter results and the formatted YAML frontmatter.
+struct PreparedFormat {
+    formatter_config: panache_formatter::Config,
+    formatted_code: FormattedCodeMap,
+    frontmatter_region: Option<YamlFrontmatterRegion>,
+    formatted_yaml: Option<(String, String)>,
+}
+
+fn prepare_format(tree: &SyntaxNode, config: &Config) -> PreparedFormat {
     log::debug!(
         "Formatting document with config: line_width={}, wrap={:?}",
         config.line_width,
@@ -169,14 +451,16 @@ pub fn format_tree(tree: &SyntaxNode, config: &Config, range: Option<(usize, usi
         .as_ref()
         .map(|region| region.content.trim_end().to_string());
 
-    let formatted_code = if !config.formatters.is_empty() {
+    let formatted_code = if !config.formatters.is_empty() || has_code_formatter() {
         let code_blocks = panache_formatter::collect_code_blocks(tree, &input, &formatter_config);
         if !code_blocks.is_empty() {
             log::debug!(
                 "Found {} code blocks, spawning formatters...",
                 code_blocks.len()
             );
-            format_code_blocks_sync(code_blocks, config)
+            let _span =
+                tracing::debug_span!("external_formatters", blocks = code_blocks.len()).entered();
+            format_code_blocks(code_blocks, &input, config)
         } else {
             FormattedCodeMap::new()
         }
@@ -197,31 +481,224 @@ pub fn format_tree(tree: &SyntaxNode, config: &Config, range: Option<(usize, usi
     #[cfg(target_arch = "wasm32")]
     let formatted_yaml: Option<(String, String)> = None;
 
-    let mut output =
-        panache_formatter::formatter::Formatter::new(formatter_config, formatted_code, range)
-            .format(tree);
+    PreparedFormat {
+        formatter_config,
+        formatted_code,
+        frontmatter_region,
+        formatted_yaml,
+    }
+}
 
-    if let Some((original_yaml, formatted_yaml)) = formatted_yaml {
-        log::debug!(
-            "Applying formatted YAML: {} bytes -> {} bytes",
-            original_yaml.len(),
-            formatted_yaml.len()
-        );
-        if let Some(region) = frontmatter_region.as_ref()
-            && let Some(replaced) = apply_formatted_yaml_at_range(
-                &output,
-                region,
-                &format!("{}\n", formatted_yaml.trim_end()),
-            )
-        {
-            output = replaced;
-        } else {
-            log::warn!("Skipping YAML apply: no valid frontmatter region range");
-        }
+/// Splice the formatted YAML frontmatter into `output`, which must start at the
+/// beginning of the formatted document.
+fn apply_formatted_yaml(
+    output: String,
+    region: Option<&YamlFrontmatterRegion>,
+    formatted_yaml: Option<&(String, String)>,
+) -> String {
+    let Some((original_yaml, formatted_yaml)) = formatted_yaml else {
+        return output;
+    };
+    log::debug!(
+        "Applying formatted YAML: {} bytes -> {} bytes",
+        original_yaml.len(),
+        formatted_yaml.len()
+    );
+    if let Some(region) = region
+        && let Some(replaced) = apply_formatted_yaml_at_range(
+            &output,
+            region,
+            &format!("{}\n", formatted_yaml.trim_end()),
+        )
+    {
+        replaced
+    } else {
+        log::warn!("Skipping YAML apply: no valid frontmatter region range");
+        output
     }
+}
+
+pub fn format_tree(tree: &SyntaxNode, config: &Config, range: Option<(usize, usize)>) -> String {
+    let (output, failures) = format_tree_with_failures(tree, config, range, config.fail_safe);
+    report_block_failures(&block_errors(tree, failures));
+    output
+}
+
+/// Like [`format_tree`], but a top-level block whose formatting panics is an
+/// error rather than a crash. With [`Config::fail_safe`] the block is left
+/// unformatted and its error returned alongside the output instead.
+pub(crate) fn try_format_tree(
+    tree: &SyntaxNode,
+    config: &Config,
+    range: Option<(usize, usize)>,
+) -> Result<(String, Vec<FormatError>), FormatError> {
+    let (output, failures) = error::catch(|| format_tree_with_failures(tree, config, range, true))
+        .map_err(|message| FormatError::Format { message })?;
+    let mut failures = block_errors(tree, failures);
+    if !config.fail_safe && !failures.is_empty() {
+        return Err(failures.swap_remove(0));
+    }
+    Ok((output, failures))
+}
+
+fn block_errors(
+    tree: &SyntaxNode,
+    failures: Vec<panache_formatter::BlockFailure>,
+) -> Vec<FormatError> {
+    failures
+        .into_iter()
+        .map(|failure| FormatError::block(tree, failure))
+        .collect()
+}
+
+/// Format `tree`, returning the top-level blocks that panicked and were left
+/// unformatted when `fail_safe` is set.
+fn format_tree_with_failures(
+    tree: &SyntaxNode,
+    config: &Config,
+    range: Option<(usize, usize)>,
+    fail_safe: bool,
+) -> (String, Vec<panache_formatter::BlockFailure>) {
+    let prepared = prepare_format(tree, config);
+    let formatter_config = panache_formatter::Config {
+        fail_safe,
+        ..prepared.formatter_config
+    };
+
+    let (output, failures) = panache_formatter::formatter::Formatter::new(
+        formatter_config,
+        prepared.formatted_code,
+        range,
+    )
+    .format_with_failures(tree);
+    let output = apply_formatted_yaml(
+        output,
+        prepared.frontmatter_region.as_ref(),
+        prepared.formatted_yaml.as_ref(),
+    );
 
     log::debug!("Formatting complete: {} bytes output", output.len());
-    output.trim_end().to_string() + "\n"
+    let output = whitespace::finish_document(
+        &output,
+        config.trim_trailing_whitespace,
+        wants_final_newline(tree, config, range),
+    );
+    (output, failures)
+}
+
+/// Whether the formatted document ends with a newline. Range formatting
+/// always keeps it, since the output is spliced back between other lines.
+fn wants_final_newline(tree: &SyntaxNode, config: &Config, range: Option<(usize, usize)>) -> bool {
+    config.final_newline == FinalNewline::Ensure
+        || range.is_some()
+        || whitespace::ends_with_newline(tree)
+}
+
+/// Streaming counterpart of [`format_tree`] (without range support): writes the
+/// formatted document to `writer` chunk by chunk instead of building it as one
+/// `String`.
+///
+/// Each chunk is passed through `map_chunk` (used for line-ending
+/// normalization) before being written. Output is identical to
+/// `format_tree(tree, config, None)`.
+pub fn format_tree_to<W: std::io::Write>(
+    tree: &SyntaxNode,
+    config: &Config,
+    writer: &mut W,
+    map_chunk: impl Fn(&str) -> String,
+) -> std::io::Result<()> {
+    let failures = format_tree_to_with_failures(tree, config, writer, map_chunk)?;
+    report_block_failures(&failures);
+    Ok(())
+}
+
+/// [`format_tree_to`], returning the errors of blocks left unformatted under
+/// [`Config::fail_safe`] instead of logging them.
+pub(crate) fn format_tree_to_with_failures<W: std::io::Write>(
+    tree: &SyntaxNode,
+    config: &Config,
+    writer: &mut W,
+    map_chunk: impl Fn(&str) -> String,
+) -> std::io::Result<Vec<FormatError>> {
+    let prepared = prepare_format(tree, config);
+    let trim = config.trim_trailing_whitespace;
+    let formatter = panache_formatter::formatter::Formatter::new(
+        prepared.formatter_config,
+        prepared.formatted_code,
+        None,
+    );
+
+    // Chunks are held back until they cover the frontmatter, so the YAML splice
+    // sees document-relative offsets. The last chunk is held back too: it
+    // carries all trailing whitespace, which `finish_document` trims.
+    let yaml_end = match (&prepared.frontmatter_region, &prepared.formatted_yaml) {
+        (Some(region), Some(_)) => region.content_range.end,
+        _ => 0,
+    };
+    let mut yaml_applied = yaml_end == 0;
+    let mut pending = String::new();
+    let mut written = 0;
+
+    let failures = formatter.format_chunks(tree, |chunk| -> std::io::Result<()> {
+        pending.push_str(chunk);
+        if !yaml_applied {
+            if pending.len() >= yaml_end {
+                pending = apply_formatted_yaml(
+                    std::mem::take(&mut pending),
+                    prepared.frontmatter_region.as_ref(),
+                    prepared.formatted_yaml.as_ref(),
+                );
+                yaml_applied = true;
+            }
+            // Hold the spliced text until the next chunk marks a clean boundary.
+            return Ok(());
+        }
+        let flush_len = pending.len() - chunk.len();
+        if flush_len > 0 {
+            let flushed = if trim {
+                // Whether spaces ending the flushed text are a hard line break
+                // depends on the line that follows, the first of `chunk`.
+                let followed_by_content =
+                    chunk.lines().next().is_some_and(|l| !l.trim().is_empty());
+                Cow::Owned(whitespace::trim_trailing_whitespace(
+                    &pending[..flush_len],
+                    followed_by_content,
+                ))
+            } else {
+                Cow::Borrowed(&pending[..flush_len])
+            };
+            written += flushed.len();
+            writer.write_all(map_chunk(&flushed).as_bytes())?;
+            pending.replace_range(..flush_len, "");
+        }
+        Ok(())
+    })?;
+
+    if !yaml_applied {
+        pending = apply_formatted_yaml(
+            pending,
+            prepared.frontmatter_region.as_ref(),
+            prepared.formatted_yaml.as_ref(),
+        );
+    }
+    let tail = whitespace::finish_document(&pending, trim, wants_final_newline(tree, config, None));
+    writer.write_all(map_chunk(&tail).as_bytes())?;
+
+    log::debug!("Formatting complete: {} bytes output", written + tail.len());
+    Ok(block_errors(tree, failures))
+}
+
+/// Warn about top-level blocks that were left unformatted because formatting
+/// them panicked (only possible with [`Config::fail_safe`]), for callers that
+/// have no other way to surface them.
+pub(crate) fn report_block_failures(failures: &[FormatError]) {
+    for failure in failures {
+        let message = format!("{failure}; {}", failure.recovery());
+        #[cfg(not(target_arch = "wasm32"))]
+        crate::external_tools_common::log_warning_once(&message);
+        #[cfg(target_arch = "wasm32")]
+        log::warn!("{message}");
+    }
 }
 
 fn apply_formatted_yaml_at_range(
@@ -0,0 +1,353 @@
+//! Git queries behind `panache format --staged`, `--changed`, and
+//! `--restage`.
+//!
+//! Every function runs git in `dir` and reports paths relative to it, so the
+//! CLI passes the current directory and tests can use a scratch repository.
+
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Paths of the added, copied, modified, and renamed files in the git index.
+pub fn staged_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    diff_paths(
+        dir,
+        &[
+            "diff",
+            "--cached",
+            "--name-only",
+            "--diff-filter=ACMR",
+            "-z",
+        ],
+    )
+}
+
+/// Paths of the files whose working-tree copy differs from the git index.
+pub fn unstaged_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    diff_paths(dir, &["diff", "--name-only", "-z"])
+}
+
+/// Line ranges (1-indexed, inclusive) that differ between `since` and the
+/// working tree, per file.
+pub fn changed_line_ranges(
+    dir: &Path,
+    since: &str,
+) -> io::Result<HashMap<PathBuf, Vec<(usize, usize)>>> {
+    if since.starts_with('-') {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid --since revision `{since}`"),
+        ));
+    }
+    let root = repo_root(dir)?;
+    // Without `core.quotePath=false` git writes non-ASCII paths as quoted
+    // octal escapes (`"b/caf\303\251.md"`).
+    let diff = output(
+        dir,
+        &[
+            "-c",
+            "core.quotePath=false",
+            "diff",
+            "-U0",
+            "--no-color",
+            "--no-ext-diff",
+            "--src-prefix=a/",
+            "--dst-prefix=b/",
+            since,
+            "--",
+        ],
+    )?;
+    Ok(parse_diff_ranges(&String::from_utf8_lossy(&diff))
+        .into_iter()
+        .map(|(path, ranges)| (root.join(path), ranges))
+        .collect())
+}
+
+/// The new-side line ranges of every hunk in `git diff -U0` output, per file.
+/// A hunk that only deletes lines covers the lines on either side of the
+/// deletion. Deleted files are skipped.
+pub fn parse_diff_ranges(diff: &str) -> Vec<(String, Vec<(usize, usize)>)> {
+    let mut files: Vec<(String, Vec<(usize, usize)>)> = Vec::new();
+    let mut in_file = false;
+    let mut previous = "";
+    for line in diff.lines() {
+        // An added line can itself start with `++ `, so a `+++` header only
+        // counts right after its `---` header.
+        if previous.starts_with("--- ")
+            && let Some(path) = line.strip_prefix("+++ ")
+        {
+            in_file = path != "/dev/null";
+            if in_file {
+                let path = unquote_path(path.trim_end_matches('\t'));
+                files.push((
+                    path.strip_prefix("b/").unwrap_or(&path).to_string(),
+                    Vec::new(),
+                ));
+            }
+        } else if in_file && let Some(hunk) = line.strip_prefix("@@ ") {
+            // `@@ -a[,b] +c[,d] @@`: lines c..c+d of the new file. With
+            // `d = 0` the lines were deleted after line c.
+            let new = hunk
+                .split_whitespace()
+                .find_map(|part| part.strip_prefix('+'))
+                .unwrap_or_default();
+            let (start, count) = new.split_once(',').unwrap_or((new, "1"));
+            if let (Ok(start), Ok(count)) = (start.parse::<usize>(), count.parse::<usize>())
+                && let Some((_, ranges)) = files.last_mut()
+            {
+                ranges.push(if count == 0 {
+                    (start.max(1), start + 1)
+                } else {
+                    (start, start + count - 1)
+                });
+            }
+        }
+        previous = line;
+    }
+    files.retain(|(_, ranges)| !ranges.is_empty());
+    files
+}
+
+/// `path` from a diff header with git's C-style quoting undone. Git still
+/// quotes paths holding `"`, `\`, or control characters under
+/// `core.quotePath=false`.
+fn unquote_path(path: &str) -> String {
+    let Some(inner) = path
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+    else {
+        return path.to_string();
+    };
+    let mut bytes = Vec::with_capacity(inner.len());
+    let mut rest = inner.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        rest = tail;
+        if byte != b'\\' {
+            bytes.push(byte);
+            continue;
+        }
+        let Some((&escape, tail)) = rest.split_first() else {
+            bytes.push(byte);
+            break;
+        };
+        rest = tail;
+        bytes.push(match escape {
+            b'n' => b'\n',
+            b't' => b'\t',
+            b'a' => 0x07,
+            b'b' => 0x08,
+            b'f' => 0x0c,
+            b'v' => 0x0b,
+            b'r' => b'\r',
+            b'0'..=b'7' => {
+                let digits = rest
+                    .iter()
+                    .take(2)
+                    .take_while(|d| (b'0'..=b'7').contains(d));
+                let mut value = u32::from(escape - b'0');
+                let mut used = 0;
+                for digit in digits {
+                    value = value * 8 + u32::from(digit - b'0');
+                    used += 1;
+                }
+                rest = &rest[used..];
+                value as u8
+            }
+            other => other,
+        });
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Stage the `reformatted` files again, except those in `unstaged`: adding a
+/// file that also has unstaged changes would stage those too. Returns the
+/// files left for the user to add.
+pub fn restage(
+    dir: &Path,
+    reformatted: Vec<PathBuf>,
+    unstaged: &[PathBuf],
+) -> io::Result<Vec<PathBuf>> {
+    let (skipped, restaged): (Vec<PathBuf>, Vec<PathBuf>) = reformatted
+        .into_iter()
+        .partition(|path| unstaged.contains(path));
+    if !restaged.is_empty() {
+        let status = Command::new("git")
+            .current_dir(dir)
+            .arg("add")
+            .arg("--")
+            .args(&restaged)
+            .status()
+            .map_err(|err| io::Error::other(format!("failed to run git: {err}")))?;
+        if !status.success() {
+            return Err(io::Error::other("git add of the reformatted files failed"));
+        }
+    }
+    Ok(skipped)
+}
+
+/// Run git with `args` in `dir` and return its stdout, failing with git's own
+/// message.
+fn output(dir: &Path, args: &[&str]) -> io::Result<Vec<u8>> {
+    let output = Command::new("git")
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .map_err(|err| io::Error::other(format!("failed to run git: {err}")))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(io::Error::other(format!(
+            "git {} failed: {}",
+            args.first().copied().unwrap_or_default(),
+            stderr.trim()
+        )));
+    }
+    Ok(output.stdout)
+}
+
+/// The repository root, relative to `dir`.
+fn repo_root(dir: &Path) -> io::Result<PathBuf> {
+    let cdup = output(dir, &["rev-parse", "--show-cdup"])?;
+    Ok(PathBuf::from(String::from_utf8_lossy(&cdup).trim()))
+}
+
+/// The NUL-separated repository paths printed by the git command `args`,
+/// made relative to `dir`.
+fn diff_paths(dir: &Path, args: &[&str]) -> io::Result<Vec<PathBuf>> {
+    let root = repo_root(dir)?;
+    let names = output(dir, args)?;
+    Ok(names
+        .split(|&byte| byte == 0)
+        .filter(|name| !name.is_empty())
+        .map(|name| root.join(String::from_utf8_lossy(name).as_ref()))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A scratch repository with one committed file, `doc.md`.
+    fn repo() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        for args in [
+            &["init", "-q"][..],
+            &["config", "user.email", "test@example.com"],
+            &["config", "user.name", "Test"],
+        ] {
+            output(dir.path(), args).unwrap();
+        }
+        std::fs::write(dir.path().join("doc.md"), "one\ntwo\nthree\n").unwrap();
+        output(dir.path(), &["add", "doc.md"]).unwrap();
+        output(
+            dir.path(),
+            &["-c", "commit.gpgsign=false", "commit", "-q", "-m", "init"],
+        )
+        .unwrap();
+        dir
+    }
+
+    #[test]
+    fn diff_ranges_cover_added_and_changed_lines() {
+        let diff = "\
+diff --git a/doc.qmd b/doc.qmd
+--- a/doc.qmd
++++ b/doc.qmd
+@@ -3 +3 @@ Intro
+-old
++new
+@@ -10,2 +9,0 @@
+-gone
+-gone
+@@ -20,0 +19,3 @@
++++ an added line that looks like a header
++b
++c
+diff --git a/removed.qmd b/removed.qmd
+--- a/removed.qmd
++++ /dev/null
+@@ -1 +0,0 @@
+-bye
+";
+        assert_eq!(
+            parse_diff_ranges(diff),
+            vec![("doc.qmd".to_string(), vec![(3, 3), (9, 10), (19, 21)])]
+        );
+    }
+
+    #[test]
+    fn quoted_paths_are_unquoted() {
+        let diff = "\
+diff --git \"a/caf\\303\\251.md\" \"b/caf\\303\\251.md\"
+--- \"a/caf\\303\\251.md\"
++++ \"b/caf\\303\\251.md\"
+@@ -1 +1 @@
+-old
++new
+";
+        assert_eq!(
+            parse_diff_ranges(diff),
+            vec![("café.md".to_string(), vec![(1, 1)])]
+        );
+        assert_eq!(unquote_path("\"b/a\\\"b\\tc.md\""), "b/a\"b\tc.md");
+    }
+
+    #[test]
+    fn deletions_select_the_surrounding_lines() {
+        let dir = repo();
+        std::fs::write(dir.path().join("doc.md"), "one\nthree\n").unwrap();
+        std::fs::write(dir.path().join("café.md"), "x\n").unwrap();
+        output(dir.path(), &["add", "café.md"]).unwrap();
+
+        let ranges = changed_line_ranges(dir.path(), "HEAD").unwrap();
+        assert_eq!(ranges[&PathBuf::from("doc.md")], [(1, 2)]);
+        assert_eq!(ranges[&PathBuf::from("café.md")], [(1, 1)]);
+    }
+
+    #[test]
+    fn staged_and_unstaged_files_are_listed_separately() {
+        let dir = repo();
+        std::fs::write(dir.path().join("new.md"), "new\n").unwrap();
+        output(dir.path(), &["add", "new.md"]).unwrap();
+        std::fs::write(dir.path().join("doc.md"), "changed\n").unwrap();
+
+        assert_eq!(staged_files(dir.path()).unwrap(), [PathBuf::from("new.md")]);
+        assert_eq!(
+            unstaged_files(dir.path()).unwrap(),
+            [PathBuf::from("doc.md")]
+        );
+    }
+
+    #[test]
+    fn paths_are_relative_to_the_directory() {
+        let dir = repo();
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        std::fs::write(dir.path().join("doc.md"), "one\nTWO\nthree\n").unwrap();
+
+        let ranges = changed_line_ranges(&dir.path().join("sub"), "HEAD").unwrap();
+        assert_eq!(ranges[&PathBuf::from("../doc.md")], [(2, 2)]);
+    }
+
+    #[test]
+    fn restage_skips_files_with_unstaged_changes() {
+        let dir = repo();
+        std::fs::write(dir.path().join("doc.md"), "formatted\n").unwrap();
+        std::fs::write(dir.path().join("other.md"), "other\n").unwrap();
+
+        let skipped = restage(
+            dir.path(),
+            vec![PathBuf::from("doc.md"), PathBuf::from("other.md")],
+            &[PathBuf::from("other.md")],
+        )
+        .unwrap();
+        assert_eq!(skipped, [PathBuf::from("other.md")]);
+        assert_eq!(staged_files(dir.path()).unwrap(), [PathBuf::from("doc.md")]);
+    }
+
+    #[test]
+    fn option_like_revisions_are_rejected() {
+        let dir = repo();
+        let err = changed_line_ranges(dir.path(), "--output=x").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+}
@@ -321,8 +321,7 @@ pub struct ProjectRoots {
 impl ProjectRoots {
     /// Quarto wins over bookdown when both are present, matching the order
     /// used by `project_graph` and `visit_document`. Use [`Self::bookdown_first`]
-    /// for call sites that historically prefer bookdown (e.g.
-    /// `undefined_references` rule).
+    /// for call sites that historically prefer bookdown.
     pub fn quarto_first(&self) -> Option<PathBuf> {
         self.quarto.clone().or_else(|| self.bookdown.clone())
     }
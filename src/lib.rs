@@ -1,23 +1,43 @@
 pub mod bib;
 pub mod config;
 pub mod directives;
+pub mod edit;
+pub mod edits;
+pub mod embedded;
+mod error;
 #[cfg(not(target_arch = "wasm32"))]
 mod external_formatters_common;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod external_formatters_sync;
 #[cfg(any(feature = "lsp", not(target_arch = "wasm32")))]
 mod external_tools_common;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod extract;
 pub mod formatter;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod git;
 pub mod includes;
+#[cfg(any(feature = "cli", feature = "lsp"))]
+pub mod link_check;
 pub mod linter;
 #[cfg(feature = "lsp")]
 pub mod lsp;
 pub mod metadata;
+pub mod organize;
+pub mod pandoc_filter;
 pub mod parser;
+pub mod project;
 pub mod range_utils;
+pub mod render;
 pub mod salsa;
+pub mod source_map;
+pub mod stats;
 pub mod syntax;
+pub mod timings;
+#[cfg(any(feature = "lsp", not(target_arch = "wasm32")))]
+pub mod trust;
 mod utils;
+pub mod verify;
 mod yaml_engine;
 #[cfg(test)]
 mod yaml_regions;
@@ -25,12 +45,17 @@ mod yaml_regions;
 pub use config::BlankLines;
 pub use config::Config;
 pub use config::ConfigBuilder;
+pub use error::FormatError;
 #[cfg(not(target_arch = "wasm32"))]
 pub use external_tools_common::init_external_tool_budget;
 #[cfg(any(feature = "lsp", not(target_arch = "wasm32")))]
 pub use external_tools_common::set_warning_color_override;
 pub use formatter::format_tree;
+pub use formatter::format_tree_to;
+pub use panache_parser::slug;
 pub use parser::parse;
+pub use parser::parse_with_diagnostics;
+pub use source_map::SourceMap;
 pub use syntax::SyntaxNode;
 
 pub fn markdown_extensions() -> &'static [&'static str] {
@@ -50,6 +75,7 @@ pub fn all_document_extensions() -> &'static [&'static str] {
         "mkd",
         "mkdn",
         "svx",
+        "mdx",
     ]
 }
 
@@ -74,6 +100,13 @@ fn detect_line_ending(input: &str) -> &str {
     "\n"
 }
 
+/// Drop a leading UTF-8 byte order mark. Left in place it is ordinary text to
+/// the parser, so it would hide frontmatter or a heading on the first line; the
+/// formatted output is therefore always written without one.
+fn strip_bom(input: &str) -> &str {
+    input.strip_prefix('\u{feff}').unwrap_or(input)
+}
+
 /// Apply line ending normalization to formatted output.
 /// Converts all line endings in the output to the target line ending.
 fn apply_line_ending(text: &str, target: &str) -> String {
@@ -108,18 +141,135 @@ fn apply_line_ending(text: &str, target: &str) -> String {
 /// * `config` - Optional configuration (defaults to default config)
 /// * `range` - Optional line range (start_line, end_line) to format, 1-indexed and inclusive.
 ///   If None, formats entire document. Range will be expanded to complete block boundaries.
+///
+/// # Panics
+///
+/// Panics on an internal error; use [`try_format`] to get a [`FormatError`]
+/// instead.
 pub fn format(input: &str, config: Option<Config>, range: Option<(usize, usize)>) -> String {
+    try_format(input, config, range).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Fallible version of [`format`]: an internal error while parsing or
+/// formatting is returned as a [`FormatError`] naming the stage, and for a
+/// failing block its kind and byte offset, instead of panicking.
+///
+/// With [`Config::fail_safe`], a block that fails to format is left as written
+/// with a warning rather than reported as an error.
+///
+/// # Examples
+///
+/// ```rust
+/// let formatted = panache::try_format("#  Title\n", None, None).unwrap();
+/// assert_eq!(formatted, "# Title\n");
+/// ```
+pub fn try_format(
+    input: &str,
+    config: Option<Config>,
+    range: Option<(usize, usize)>,
+) -> Result<String, FormatError> {
     #[cfg(debug_assertions)]
     {
         init_logger();
     }
 
     let config = config.unwrap_or_default();
+    let input = strip_bom(input);
 
     // Parse document into complete CST (parser preserves all bytes including
     // CRLF), then format that tree.
+    let tree = try_parse(input, Some(config.clone()))?;
+    try_format_with_tree(input, &tree, &config, range)
+}
+
+/// Like [`try_format`], but the internal errors [`Config::fail_safe`] recovered
+/// from are returned next to the output instead of being logged, so a caller
+/// can report them as it reports other problems.
+///
+/// With `fail_safe`, a block that fails to format is left as written, and a
+/// document that fails to parse is returned unchanged with a
+/// [`FormatError::Parse`]. Without it, this is [`try_format`] with no failures.
+pub fn try_format_with_failures(
+    input: &str,
+    config: Option<Config>,
+    range: Option<(usize, usize)>,
+) -> Result<(String, Vec<FormatError>), FormatError> {
+    #[cfg(debug_assertions)]
+    {
+        init_logger();
+    }
+
+    let config = config.unwrap_or_default();
+    let input = strip_bom(input);
+    let tree = match try_parse(input, Some(config.clone())) {
+        Ok(tree) => tree,
+        Err(err) if config.fail_safe => return Ok((input.to_string(), vec![err])),
+        Err(err) => return Err(err),
+    };
+    format_byte_range(input, &tree, &config, expand_range(input, &tree, range))
+}
+
+/// Formats a document like [`format`] and also returns a [`SourceMap`] from
+/// byte offsets in `input` to byte offsets in the output, so an editor can move
+/// cursors and selections along with the text or apply only the changed
+/// ranges.
+///
+/// # Examples
+///
+/// ```rust
+/// let (output, map) = panache::format_with_map("#  Title\n\nText.\n", None);
+/// assert_eq!(output, "# Title\n\nText.\n");
+/// assert_eq!(map.changes().count(), 1);
+/// ```
+///
+/// # Panics
+///
+/// Panics on an internal error, like [`format`].
+pub fn format_with_map(input: &str, config: Option<Config>) -> (String, SourceMap) {
+    let output = format(input, config, None);
+    let map = SourceMap::new(input, &output);
+    (output, map)
+}
+
+/// Fallible version of [`parse`]: a parser panic is returned as
+/// [`FormatError::Parse`].
+pub fn try_parse(input: &str, config: Option<Config>) -> Result<SyntaxNode, FormatError> {
+    error::catch(|| parser::parse(input, config)).map_err(|message| FormatError::Parse { message })
+}
+
+/// Computes document metrics: words, headings by level, code cells by language,
+/// links, images, footnotes, and the longest line.
+///
+/// # Examples
+///
+/// ```rust
+/// let stats = panache::stats("# Intro\n\nTwo words.\n", None);
+/// assert_eq!(stats.words, 3);
+/// assert_eq!(stats.headings[0], 1);
+/// ```
+pub fn stats(input: &str, config: Option<Config>) -> stats::DocumentStats {
+    let config = config.unwrap_or_default();
+    let input = strip_bom(input);
+    let tree = parser::parse(input, Some(config.clone()));
+    stats::compute(&tree, input, &config)
+}
+
+/// Renders a document in another format, such as plain text without markup.
+///
+/// # Examples
+///
+/// ```rust
+/// use panache::render::RenderFormat;
+///
+/// let input = "# Intro\n\nSee [the *docs*](https://example.com).\n";
+/// let text = panache::render(input, RenderFormat::Plain, None);
+/// assert_eq!(text, "Intro\n\nSee the docs (https://example.com).\n");
+/// ```
+pub fn render(input: &str, format: render::RenderFormat, config: Option<Config>) -> String {
+    let config = config.unwrap_or_default();
+    let input = strip_bom(input);
     let tree = parser::parse(input, Some(config.clone()));
-    format_with_tree(input, &tree, &config, range)
+    format.renderer().render(&tree, &config)
 }
 
 /// Formats a document from an already-parsed CST, skipping the internal parse.
@@ -139,24 +289,39 @@ pub fn format(input: &str, config: Option<Config>, range: Option<(usize, usize)>
 /// * `config` - The configuration used to parse `input`
 /// * `range` - Optional line range (start_line, end_line), 1-indexed and
 ///   inclusive; see [`format`].
+///
+/// # Panics
+///
+/// Panics on an internal error; see [`try_format_with_tree`].
 pub fn format_with_tree(
     input: &str,
     tree: &SyntaxNode,
     config: &Config,
     range: Option<(usize, usize)>,
 ) -> String {
-    // Determine target line ending based on config
-    let target_line_ending = match config.line_ending {
-        Some(config::LineEnding::Lf) => "\n",
-        Some(config::LineEnding::Crlf) => "\r\n",
-        Some(config::LineEnding::Auto) | None => {
-            // Auto-detect from input: use first line ending found
-            detect_line_ending(input)
-        }
-    };
+    try_format_with_tree(input, tree, config, range).unwrap_or_else(|err| panic!("{err}"))
+}
 
-    // Expand line range to byte offsets and block boundaries if specified
-    let expanded_range = range.and_then(|(start_line, end_line)| {
+/// Fallible version of [`format_with_tree`]; see [`try_format`].
+pub fn try_format_with_tree(
+    input: &str,
+    tree: &SyntaxNode,
+    config: &Config,
+    range: Option<(usize, usize)>,
+) -> Result<String, FormatError> {
+    let (output, failures) =
+        format_byte_range(input, tree, config, expand_range(input, tree, range))?;
+    formatter::report_block_failures(&failures);
+    Ok(output)
+}
+
+/// Expand the line range `range` to byte offsets on block boundaries.
+fn expand_range(
+    input: &str,
+    tree: &SyntaxNode,
+    range: Option<(usize, usize)>,
+) -> Option<(usize, usize)> {
+    range.and_then(|(start_line, end_line)| {
         let result = range_utils::expand_line_range_to_blocks(tree, input, start_line, end_line);
         if let Some((start, end)) = result {
             log::debug!(
@@ -170,13 +335,194 @@ pub fn format_with_tree(
             );
         }
         result
-    });
+    })
+}
+
+/// Formats only the blocks overlapping any of `ranges` and returns the whole
+/// document, with everything outside those blocks left exactly as written.
+///
+/// Ranges are line ranges as for [`format`] (1-indexed, inclusive), in any
+/// order; each is expanded to complete block boundaries, and ranges whose
+/// blocks overlap are formatted together. This is what formatting only the
+/// changed hunks of a file needs.
+///
+/// # Examples
+///
+/// ```rust
+/// let input = "#  One\n\nkeep  this\n\n#  Two\n";
+/// let output = panache::try_format_ranges(input, None, &[(5, 5), (1, 1)]).unwrap();
+/// assert_eq!(output, "# One\n\nkeep  this\n\n# Two\n");
+/// ```
+pub fn try_format_ranges(
+    input: &str,
+    config: Option<Config>,
+    ranges: &[(usize, usize)],
+) -> Result<String, FormatError> {
+    let config = config.unwrap_or_default();
+    let input = strip_bom(input);
+    let tree = try_parse(input, Some(config.clone()))?;
+    try_format_ranges_with_tree(input, &tree, &config, ranges)
+}
+
+/// [`try_format_ranges`] over an already-parsed CST; see
+/// [`format_with_tree`] for the contract on `tree`.
+pub fn try_format_ranges_with_tree(
+    input: &str,
+    tree: &SyntaxNode,
+    config: &Config,
+    ranges: &[(usize, usize)],
+) -> Result<String, FormatError> {
+    let mut blocks: Vec<(usize, usize)> = ranges
+        .iter()
+        .filter_map(|&(start_line, end_line)| {
+            range_utils::expand_line_range_to_blocks(tree, input, start_line, end_line)
+        })
+        .collect();
+    blocks.sort_unstable();
+    let mut merged: Vec<(usize, usize)> = Vec::with_capacity(blocks.len());
+    for (start, end) in blocks {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+
+    let mut output = input.to_string();
+    for &(start, end) in merged.iter().rev() {
+        let (formatted, failures) = format_byte_range(input, tree, config, Some((start, end)))?;
+        formatter::report_block_failures(&failures);
+        output.replace_range(start..end.min(input.len()), &formatted);
+    }
+    Ok(output)
+}
+
+/// Format `tree`, or only its top-level blocks overlapping the byte range
+/// `expanded_range`, and normalize line endings. Also returns the blocks left
+/// unformatted under [`Config::fail_safe`].
+fn format_byte_range(
+    input: &str,
+    tree: &SyntaxNode,
+    config: &Config,
+    expanded_range: Option<(usize, usize)>,
+) -> Result<(String, Vec<FormatError>), FormatError> {
+    // `panache:` options in the frontmatter override the config for this document.
+    let frontmatter_config = config::frontmatter_config(tree, config);
+    let config = frontmatter_config.as_ref().unwrap_or(config);
+
+    // Determine target line ending based on config
+    let target_line_ending = match config.line_ending {
+        Some(config::LineEnding::Lf) => "\n",
+        Some(config::LineEnding::Crlf) => "\r\n",
+        Some(config::LineEnding::Auto) | None => {
+            // Auto-detect from input: use first line ending found
+            detect_line_ending(input)
+        }
+    };
 
     // Format the final CST (synchronously, includes external formatter support)
-    let out = formatter::format_tree(tree, config, expanded_range);
+    let (out, failures) = formatter::try_format_tree(tree, config, expanded_range)?;
 
     // Apply line ending normalization if needed
-    apply_line_ending(&out, target_line_ending)
+    Ok((apply_line_ending(&out, target_line_ending), failures))
+}
+
+/// Formats a document and writes the result to `writer` as it is produced.
+///
+/// Produces the same bytes as `format(input, config, None)`, but emits output
+/// per top-level block instead of building the whole formatted document as a
+/// `String`, which keeps peak memory down on very large files. Range formatting
+/// is not supported; use [`format`] for that.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut out = Vec::new();
+/// panache::format_to("# Title\n", None, &mut out).unwrap();
+/// assert_eq!(out, b"# Title\n");
+/// ```
+pub fn format_to<W: std::io::Write>(
+    input: &str,
+    config: Option<Config>,
+    writer: &mut W,
+) -> std::io::Result<()> {
+    #[cfg(debug_assertions)]
+    {
+        init_logger();
+    }
+
+    let failures = format_to_with_failures(input, config, writer)?;
+    formatter::report_block_failures(&failures);
+    Ok(())
+}
+
+/// Like [`format_to`], but the internal errors [`Config::fail_safe`] recovered
+/// from are returned instead of being logged; see [`try_format_with_failures`].
+/// Without `fail_safe`, an internal error while parsing is returned as an
+/// [`std::io::Error`] wrapping the [`FormatError`].
+pub fn format_to_with_failures<W: std::io::Write>(
+    input: &str,
+    config: Option<Config>,
+    writer: &mut W,
+) -> std::io::Result<Vec<FormatError>> {
+    let config = config.unwrap_or_default();
+    let input = strip_bom(input);
+    let target_line_ending = match config.line_ending {
+        Some(config::LineEnding::Lf) => "\n",
+        Some(config::LineEnding::Crlf) => "\r\n",
+        Some(config::LineEnding::Auto) | None => detect_line_ending(input),
+    };
+
+    let tree = match try_parse(input, Some(config.clone())) {
+        Ok(tree) => tree,
+        Err(err) if config.fail_safe => {
+            writer.write_all(input.as_bytes())?;
+            return Ok(vec![err]);
+        }
+        Err(err) => return Err(std::io::Error::other(err)),
+    };
+    let config = config::frontmatter_config(&tree, &config).unwrap_or(config);
+    formatter::format_tree_to_with_failures(&tree, &config, writer, |chunk| {
+        apply_line_ending(chunk, target_line_ending)
+    })
+}
+
+/// Formats an inline fragment, such as a title, caption, or table cell, without
+/// block structure.
+///
+/// The snippet is parsed as the inline content of a single paragraph, so text
+/// that would open a block in a document (`# `, `- `, `> `, `1. `) stays
+/// literal. Wrapping follows `config` as for any paragraph; set
+/// `wrap = preserve` to keep line breaks as written. Surrounding whitespace is
+/// trimmed and the result has no trailing newline.
+///
+/// # Examples
+///
+/// ```rust
+/// let config = panache::Config::default();
+/// assert_eq!(
+///     panache::format_inline("A caption\nthat spans lines\n", &config),
+///     "A caption that spans lines"
+/// );
+/// ```
+pub fn format_inline(snippet: &str, config: &Config) -> String {
+    #[cfg(debug_assertions)]
+    {
+        init_logger();
+    }
+
+    let snippet = snippet.trim();
+    if snippet.is_empty() {
+        return String::new();
+    }
+    let target_line_ending = match config.line_ending {
+        Some(config::LineEnding::Lf) => "\n",
+        Some(config::LineEnding::Crlf) => "\r\n",
+        Some(config::LineEnding::Auto) | None => detect_line_ending(snippet),
+    };
+
+    let tree = parser::parse_inline(snippet, Some(config.clone()));
+    let out = formatter::format_tree(&tree, config, None);
+    apply_line_ending(out.trim_end(), target_line_ending)
 }
 
 /// Formats a Quarto document string using default configuration.
@@ -0,0 +1,415 @@
+//! Link checking for `panache check-links`.
+//!
+//! [`LinkChecker`] validates the destinations of one document at a time:
+//! relative paths must exist, and a `#fragment` (on a relative path or on its
+//! own) must name a heading or anchor the target document declares. Target
+//! documents are parsed once per run and their anchors kept, so a book whose
+//! chapters all link to one another is not re-parsed per link.
+//!
+//! External `http(s)` destinations are only collected here. The CLI checks
+//! them with [`check_urls`] behind `--online`, after deduplicating them across
+//! every document of the run.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::linter::diagnostics::{Diagnostic, Location};
+use crate::linter::rules::undefined_anchor::document_anchors;
+use crate::syntax::{LinkKind, SyntaxNode, collect_links};
+
+/// Lint rule name used for missing files and anchors; the language server
+/// reports the same diagnostics, and `[lint.rules] broken-link = false` turns
+/// them off there.
+pub const BROKEN_LINK_CODE: &str = "broken-link";
+
+/// Diagnostic code for external URLs that fail to load.
+pub const BROKEN_URL_CODE: &str = "broken-url";
+
+/// An external `http(s)` destination found in a document.
+#[derive(Debug, Clone)]
+pub struct ExternalLink {
+    /// The destination without its `#fragment`, as requested.
+    pub url: String,
+    pub location: Location,
+}
+
+/// Outcome of [`LinkChecker::check_document`].
+#[derive(Debug, Default)]
+pub struct DocumentLinks {
+    /// `broken-link` diagnostics for missing files and anchors.
+    pub diagnostics: Vec<Diagnostic>,
+    /// External destinations, in document order, for [`check_urls`].
+    pub external: Vec<ExternalLink>,
+}
+
+/// Checks the relative links of documents, caching the anchors of every
+/// document a link points into.
+pub struct LinkChecker<'a> {
+    load_config: Box<dyn FnMut(&Path) -> Config + 'a>,
+    anchors: HashMap<PathBuf, Option<HashSet<String>>>,
+}
+
+impl<'a> LinkChecker<'a> {
+    /// A checker that parses link targets with the config `load_config`
+    /// returns for their path.
+    pub fn new(load_config: impl FnMut(&Path) -> Config + 'a) -> Self {
+        Self {
+            load_config: Box::new(load_config),
+            anchors: HashMap::new(),
+        }
+    }
+
+    /// Check the links of the document at `path`, whose text is `input`.
+    /// Relative destinations resolve against the directory of `path`.
+    pub fn check_document(
+        &mut self,
+        path: &Path,
+        input: &str,
+        tree: &SyntaxNode,
+        config: &Config,
+    ) -> DocumentLinks {
+        let base = path.parent().unwrap_or_else(|| Path::new("."));
+        let mut own_anchors = None;
+        let mut result = DocumentLinks::default();
+        for link in collect_links(tree) {
+            let raw = link.raw.trim();
+            if is_http_url(raw) {
+                let url = raw.split_once('#').map_or(raw, |(url, _)| url);
+                result.external.push(ExternalLink {
+                    url: url.to_string(),
+                    location: Location::from_range(link.range(), input),
+                });
+                continue;
+            }
+            // Reference links and images point at a definition, whose own
+            // destination is checked once as a `Definition`.
+            let direct = match link.kind {
+                LinkKind::Inline | LinkKind::Definition => true,
+                LinkKind::Image => link.label.is_none(),
+                LinkKind::Reference | LinkKind::Autolink | LinkKind::Include => false,
+            };
+            if !direct {
+                continue;
+            }
+
+            let message = if let Some(fragment) = raw.strip_prefix('#') {
+                let fragment = decode(fragment);
+                let anchors = own_anchors.get_or_insert_with(|| document_anchors(tree, config));
+                if fragment.is_empty() || anchors.contains(&fragment) {
+                    continue;
+                }
+                format!("Anchor '#{fragment}' not found in this document")
+            } else if let Some((target, fragment)) = resolve_relative(base, raw) {
+                if !target.exists() {
+                    format!("Link target '{raw}' does not exist")
+                } else if let Some(fragment) = fragment
+                    && is_document_path(&target)
+                    && let Some(anchors) = self.anchors_of(&target)
+                    && !anchors.contains(&fragment)
+                {
+                    let file = target
+                        .file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or_default();
+                    format!("Anchor '#{fragment}' not found in '{file}'")
+                } else {
+                    continue;
+                }
+            } else {
+                continue;
+            };
+            result.diagnostics.push(Diagnostic::warning(
+                Location::from_range(link.range(), input),
+                BROKEN_LINK_CODE,
+                message,
+            ));
+        }
+        result
+    }
+
+    /// The anchors the document at `path` declares, or `None` if it cannot be
+    /// read.
+    fn anchors_of(&mut self, path: &Path) -> Option<&HashSet<String>> {
+        if !self.anchors.contains_key(path) {
+            let anchors = std::fs::read_to_string(path).ok().map(|text| {
+                let config = (self.load_config)(path);
+                let tree = crate::parse(&text, Some(config.clone()));
+                document_anchors(&tree, &config)
+            });
+            self.anchors.insert(path.to_path_buf(), anchors);
+        }
+        self.anchors.get(path).and_then(Option::as_ref)
+    }
+}
+
+fn is_http_url(raw: &str) -> bool {
+    let lower = raw.get(..8).unwrap_or(raw).to_ascii_lowercase();
+    lower.starts_with("http://") || lower.starts_with("https://")
+}
+
+fn decode(text: &str) -> String {
+    percent_encoding::percent_decode_str(text)
+        .decode_utf8_lossy()
+        .into_owned()
+}
+
+/// Resolve a link destination to a filesystem path and optional fragment.
+/// `None` for external URLs, same-document fragments, and absolute paths
+/// (which Quarto resolves against the project root, not the filesystem).
+pub fn resolve_relative(base: &Path, raw: &str) -> Option<(PathBuf, Option<String>)> {
+    if raw.is_empty() || raw.starts_with('#') || is_external_target(raw) {
+        return None;
+    }
+    let (path_part, fragment) = match raw.split_once('#') {
+        Some((path, fragment)) => (path, Some(fragment)),
+        None => (raw, None),
+    };
+    let path_part = path_part
+        .split_once('?')
+        .map_or(path_part, |(path, _)| path);
+    if path_part.is_empty() || Path::new(path_part).is_absolute() || path_part.starts_with('/') {
+        return None;
+    }
+    let target = normalize(&base.join(decode(path_part)));
+    let fragment = fragment.filter(|fragment| !fragment.is_empty()).map(decode);
+    Some((target, fragment))
+}
+
+/// `path` with `.` components dropped and `..` applied lexically.
+pub fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => {
+                if !out.pop() {
+                    out.push("..");
+                }
+            }
+            other => out.push(other.as_os_str()),
+        }
+    }
+    out
+}
+
+/// Whether `path` has one of the document extensions panache handles.
+pub fn is_document_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| crate::all_document_extensions().contains(&ext))
+}
+
+/// Whether a link target is a URL or email address rather than a path.
+pub fn is_external_target(target: &str) -> bool {
+    let t = target.trim();
+    if t.contains('@') && !t.contains(':') {
+        return true;
+    }
+    let Some(idx) = t.find(':') else {
+        return false;
+    };
+    if idx == 1 {
+        let bytes = t.as_bytes();
+        if bytes.get(2).is_some_and(|b| *b == b'/' || *b == b'\\') {
+            return false;
+        }
+    }
+    let scheme = &t[..idx];
+    if scheme.is_empty() {
+        return false;
+    }
+    let mut chars = scheme.chars();
+    if !chars.next().is_some_and(|ch| ch.is_ascii_alphabetic()) {
+        return false;
+    }
+    chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '+' || ch == '-' || ch == '.')
+}
+
+/// Outcome of requesting an external URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UrlStatus {
+    /// The server answered with a status below 400.
+    Ok(u16),
+    /// The server answered with a 4xx or 5xx status.
+    Error(u16),
+    /// No answer: a DNS, connection, TLS, or timeout failure.
+    Unreachable(String),
+}
+
+impl UrlStatus {
+    pub fn is_ok(&self) -> bool {
+        matches!(self, Self::Ok(_))
+    }
+
+    /// The `broken-url` message for a link to `url` with this status, or
+    /// `None` when the URL loaded.
+    pub fn message(&self, url: &str) -> Option<String> {
+        match self {
+            Self::Ok(_) => None,
+            Self::Error(status) => Some(format!("URL '{url}' returned HTTP {status}")),
+            Self::Unreachable(err) => Some(format!("URL '{url}' could not be reached: {err}")),
+        }
+    }
+}
+
+/// Settings for [`check_urls`].
+#[derive(Debug, Clone)]
+pub struct OnlineOptions {
+    /// Requests in flight at once.
+    pub concurrency: usize,
+    /// Time allowed for each request, redirects included.
+    pub timeout: std::time::Duration,
+}
+
+/// Request every URL in `urls`, at most `options.concurrency` at a time.
+///
+/// A `HEAD` request is tried first; servers that refuse `HEAD` (403, 405, or
+/// 501) get a `GET`. Redirects are followed.
+#[cfg(all(feature = "cli", not(target_arch = "wasm32")))]
+pub fn check_urls(urls: &[String], options: &OnlineOptions) -> HashMap<String, UrlStatus> {
+    use rayon::prelude::*;
+
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_global(Some(options.timeout))
+        .http_status_as_error(false)
+        .build()
+        .into();
+    let check = |url: &String| (url.clone(), request(&agent, url));
+    match rayon::ThreadPoolBuilder::new()
+        .num_threads(options.concurrency.max(1))
+        .build()
+    {
+        Ok(pool) => pool.install(|| urls.par_iter().map(check).collect()),
+        Err(err) => {
+            log::warn!("Checking URLs sequentially: {err}");
+            urls.iter().map(check).collect()
+        }
+    }
+}
+
+#[cfg(all(feature = "cli", not(target_arch = "wasm32")))]
+fn request(agent: &ureq::Agent, url: &str) -> UrlStatus {
+    const USER_AGENT: &str = concat!("panache/", env!("CARGO_PKG_VERSION"));
+    let mut result = agent
+        .head(url)
+        .header("User-Agent", USER_AGENT)
+        .call()
+        .map(|response| response.status().as_u16());
+    if matches!(result, Ok(403 | 405 | 501)) {
+        result = agent
+            .get(url)
+            .header("User-Agent", USER_AGENT)
+            .call()
+            .map(|response| response.status().as_u16());
+    }
+    match result {
+        Ok(code) if code < 400 => UrlStatus::Ok(code),
+        Ok(code) => UrlStatus::Error(code),
+        Err(err) => UrlStatus::Unreachable(err.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn check(dir: &Path, name: &str) -> DocumentLinks {
+        let path = dir.join(name);
+        let input = std::fs::read_to_string(&path).expect("read document");
+        let config = Config::default();
+        let tree = crate::parse(&input, Some(config.clone()));
+        LinkChecker::new(|_| Config::default()).check_document(&path, &input, &tree, &config)
+    }
+
+    #[test]
+    fn resolves_relative_destinations() {
+        let base = Path::new("/repo/docs");
+        assert_eq!(
+            resolve_relative(base, "../chapters/02.qmd#sec-intro"),
+            Some((
+                PathBuf::from("/repo/chapters/02.qmd"),
+                Some("sec-intro".to_string())
+            ))
+        );
+        assert_eq!(
+            resolve_relative(base, "my%20file.md"),
+            Some((PathBuf::from("/repo/docs/my file.md"), None))
+        );
+        assert_eq!(resolve_relative(base, "https://example.com/a.qmd"), None);
+        assert_eq!(resolve_relative(base, "#local"), None);
+        assert_eq!(resolve_relative(base, "/abs/path.qmd"), None);
+        assert_eq!(resolve_relative(base, "mailto:me@example.com"), None);
+    }
+
+    #[test]
+    fn reports_missing_files_and_anchors() {
+        let temp = TempDir::new().expect("tempdir");
+        std::fs::write(temp.path().join("setup.md"), "# Installation\n").unwrap();
+        std::fs::write(
+            temp.path().join("index.md"),
+            "# Intro\n\n[ok](setup.md#installation) [bad](setup.md#usage) \
+             [gone](missing.md) [self](#intro) [nope](#outro)\n",
+        )
+        .unwrap();
+
+        let links = check(temp.path(), "index.md");
+        let messages: Vec<&str> = links
+            .diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.message.as_str())
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                "Anchor '#usage' not found in 'setup.md'",
+                "Link target 'missing.md' does not exist",
+                "Anchor '#outro' not found in this document",
+            ]
+        );
+        assert!(
+            links
+                .diagnostics
+                .iter()
+                .all(|diagnostic| diagnostic.code == BROKEN_LINK_CODE)
+        );
+    }
+
+    #[test]
+    fn collects_external_urls_without_fragments() {
+        let temp = TempDir::new().expect("tempdir");
+        std::fs::write(
+            temp.path().join("index.md"),
+            "[a](https://example.com/page#part) <http://example.org>\n\n\
+             [ref]: https://example.net/docs\n\n[mail](mailto:me@example.com)\n",
+        )
+        .unwrap();
+
+        let links = check(temp.path(), "index.md");
+        assert!(links.diagnostics.is_empty());
+        let urls: Vec<&str> = links
+            .external
+            .iter()
+            .map(|link| link.url.as_str())
+            .collect();
+        assert_eq!(
+            urls,
+            [
+                "https://example.com/page",
+                "http://example.org",
+                "https://example.net/docs",
+            ]
+        );
+        assert_eq!(links.external[1].location.column, 36);
+    }
+
+    #[test]
+    fn url_status_messages() {
+        assert_eq!(UrlStatus::Ok(200).message("https://a.b"), None);
+        assert_eq!(
+            UrlStatus::Error(404).message("https://a.b").as_deref(),
+            Some("URL 'https://a.b' returned HTTP 404")
+        );
+    }
+}
@@ -4,6 +4,7 @@ pub mod diagnostics;
 pub mod external_linters;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod external_linters_sync;
+pub mod fixes;
 pub(crate) mod fuzzy;
 pub mod index;
 pub mod metadata_diagnostics;
@@ -17,6 +18,7 @@ pub use diagnostics::{
     Diagnostic, DiagnosticNote, DiagnosticNoteKind, DiagnosticOrigin, Fix, FixSafety, Location,
     Severity,
 };
+pub use fixes::{FixResult, FixSelection, apply_fixes, select_fixes};
 pub use rules::{DiagnosticCode, Requirement, Rule, RuleMeta, RuleRegistry};
 pub use runner::LintRunner;
 
@@ -67,8 +69,10 @@ pub fn lint_with_external_sync_and_metadata(
 /// reads each rule's [`RuleMeta`] off it. Adding a rule means adding one entry
 /// here (plus its `impl Rule`), nothing else.
 fn all_rules() -> Vec<Box<dyn Rule>> {
-    vec![
+    #[allow(unused_mut)]
+    let mut rules: Vec<Box<dyn Rule>> = vec![
         Box::new(rules::heading_hierarchy::HeadingHierarchyRule),
+        Box::new(rules::heading_length::HeadingLengthRule),
         Box::new(rules::empty_list_item::EmptyListItemRule),
         Box::new(rules::empty_values::EmptyValuesRule),
         Box::new(rules::consumer_divergence::ConsumerDivergenceRule),
@@ -86,14 +90,33 @@ fn all_rules() -> Vec<Box<dyn Rule>> {
         Box::new(rules::crossref_as_link_target::CrossrefAsLinkTargetRule),
         Box::new(rules::chunk_label_spaces::ChunkLabelSpacesRule),
         Box::new(rules::missing_chunk_labels::MissingChunkLabelsRule),
+        Box::new(rules::execution_options::ChunkEvalIncludeRule),
+        Box::new(rules::execution_options::ChunkOptionConflictRule),
+        Box::new(rules::execution_options::ChunkOptionDottedNameRule),
         Box::new(rules::quarto_schema::QuartoSchemaRule),
         Box::new(rules::quarto_schema::QuartoSchemaUnknownKeyRule),
         Box::new(rules::figure_crossref_captions::FigureCrossrefCaptionsRule),
         Box::new(rules::emoji_aliases::EmojiAliasesRule),
         Box::new(rules::html_entities::HtmlEntitiesRule),
         Box::new(rules::link_text_is_url::LinkTextIsUrlRule),
+        Box::new(rules::bare_urls::BareUrlsRule),
         Box::new(rules::stray_fenced_div_markers::StrayFencedDivMarkersRule),
-    ]
+        Box::new(rules::mixed_line_endings::MixedLineEndingsRule),
+        Box::new(rules::trailing_whitespace::TrailingWhitespaceRule),
+        Box::new(rules::final_newline::FinalNewlineRule),
+        Box::new(rules::blanks_around::BlanksAroundHeadingsRule),
+        Box::new(rules::blanks_around::BlanksAroundFencesRule),
+        Box::new(rules::blanks_around::BlanksAroundListsRule),
+        Box::new(rules::heading_style::HeadingPunctuationRule),
+        Box::new(rules::heading_style::HeadingCaseRule),
+        Box::new(rules::emphasis_as_heading::EmphasisAsHeadingRule),
+        Box::new(rules::prose::DuplicateWordsRule),
+        Box::new(rules::prose::MultipleSpacesRule),
+        Box::new(rules::prose::SpaceBeforePunctuationRule),
+    ];
+    #[cfg(all(feature = "spellcheck", not(target_arch = "wasm32")))]
+    rules.push(Box::new(rules::spelling::SpellingRule));
+    rules
 }
 
 /// Metadata for every built-in rule, independent of config. The reference docs
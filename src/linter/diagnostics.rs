@@ -13,10 +13,14 @@ pub enum DiagnosticOrigin {
     External,
 }
 
+/// Where a diagnostic points: the flagged byte `range`, and the 1-indexed
+/// line and column (in characters) of its start and end.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Location {
     pub line: usize,
     pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
     pub range: TextRange,
 }
 
@@ -146,28 +150,69 @@ impl Diagnostic {
         });
         self
     }
+
+    /// The diagnostic as a JSON object for embedders (the WASM and C APIs):
+    /// `code`, `severity`, `message`, the 1-indexed `line`/`column` and
+    /// `end_line`/`end_column` and byte `start`/`end` of its range, and its
+    /// `fix`, if any.
+    pub fn to_json(&self) -> serde_json::Value {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info => "info",
+        };
+        let fix = self.fix.as_ref().map(|fix| {
+            let edits: Vec<serde_json::Value> = fix
+                .edits
+                .iter()
+                .map(|edit| {
+                    serde_json::json!({
+                        "start": u32::from(edit.range.start()),
+                        "end": u32::from(edit.range.end()),
+                        "replacement": edit.replacement,
+                    })
+                })
+                .collect();
+            serde_json::json!({
+                "message": fix.message,
+                "safe": fix.safety == FixSafety::Safe,
+                "edits": edits,
+            })
+        });
+        serde_json::json!({
+            "code": self.code,
+            "severity": severity,
+            "message": self.message,
+            "line": self.location.line,
+            "column": self.location.column,
+            "end_line": self.location.end_line,
+            "end_column": self.location.end_column,
+            "start": u32::from(self.location.range.start()),
+            "end": u32::from(self.location.range.end()),
+            "fix": fix,
+        })
+    }
 }
 
 impl Location {
     pub fn from_node(node: &crate::syntax::SyntaxNode, input: &str) -> Self {
-        let range = node.text_range();
-        let start_offset = range.start().into();
-        let (line, column) = offset_to_line_col(input, start_offset);
-
-        Self {
-            line,
-            column,
-            range,
-        }
+        Self::from_range(node.text_range(), input)
     }
 
     pub fn from_range(range: TextRange, input: &str) -> Self {
-        let start_offset = range.start().into();
-        let (line, column) = offset_to_line_col(input, start_offset);
+        let (line, column) = offset_to_line_col(input, range.start().into());
+        Self::reported(line, column, range, input)
+    }
 
+    /// A location whose start `line` and `column` come from an external tool
+    /// rather than from `range`, which is still mapped for the end.
+    pub fn reported(line: usize, column: usize, range: TextRange, input: &str) -> Self {
+        let (end_line, end_column) = offset_to_line_col(input, range.end().into());
         Self {
             line,
             column,
+            end_line,
+            end_column,
             range,
         }
     }
@@ -206,11 +251,25 @@ mod tests {
         assert_eq!(offset_to_line_col(input, 14), (3, 1)); // 'l' in line 3
     }
 
+    #[test]
+    fn location_spans_start_to_end() {
+        let input = "one\ntwo three\n";
+        let location = Location::from_range(TextRange::new(2.into(), 13.into()), input);
+        assert_eq!((location.line, location.column), (1, 3));
+        assert_eq!((location.end_line, location.end_column), (2, 10));
+
+        let reported = Location::reported(1, 2, TextRange::new(2.into(), 3.into()), input);
+        assert_eq!((reported.line, reported.column), (1, 2));
+        assert_eq!((reported.end_line, reported.end_column), (1, 4));
+    }
+
     #[test]
     fn test_diagnostic_builders() {
         let location = Location {
             line: 1,
             column: 5,
+            end_line: 1,
+            end_column: 15,
             range: TextRange::new(0.into(), 10.into()),
         };
 
@@ -232,15 +291,41 @@ mod tests {
 
     #[test]
     fn test_with_note_adds_diagnostic_note() {
-        let location = Location {
-            line: 1,
-            column: 1,
-            range: TextRange::new(0.into(), 1.into()),
-        };
+        let location = Location::from_range(TextRange::new(0.into(), 1.into()), "x\n");
         let diag = Diagnostic::warning(location, "test-warning", "msg")
             .with_note(DiagnosticNoteKind::Help, "try this");
         assert_eq!(diag.notes.len(), 1);
         assert_eq!(diag.notes[0].kind, DiagnosticNoteKind::Help);
         assert_eq!(diag.notes[0].message, "try this");
     }
+
+    #[test]
+    fn test_to_json() {
+        let location = Location::from_range(TextRange::new(4.into(), 6.into()), "a\n  * item\n");
+        let edit = Edit {
+            range: location.range,
+            replacement: "-".to_string(),
+        };
+        let diag = Diagnostic::warning(location, "list-marker", "Use `-`")
+            .with_fix(Fix::safe("Replace marker", vec![edit]));
+        assert_eq!(
+            diag.to_json(),
+            serde_json::json!({
+                "code": "list-marker",
+                "severity": "warning",
+                "message": "Use `-`",
+                "line": 2,
+                "column": 3,
+                "end_line": 2,
+                "end_column": 5,
+                "start": 4,
+                "end": 6,
+                "fix": {
+                    "message": "Replace marker",
+                    "safe": true,
+                    "edits": [{ "start": 4, "end": 6, "replacement": "-" }],
+                },
+            })
+        );
+    }
 }
@@ -14,6 +14,7 @@ use crate::linter::offsets::line_col_to_byte_offset_1based;
 mod clippy;
 mod eslint;
 mod jarl;
+mod lintr;
 mod ruff;
 mod shellcheck;
 mod staticcheck;
@@ -145,6 +146,20 @@ impl ExternalLinterRegistry {
                 supported_languages: vec!["r"],
             },
         );
+        linters.insert(
+            "lintr".to_string(),
+            LinterInfo {
+                name: "lintr",
+                description: "Static code analysis for R, run through Rscript; requires the lintr package.",
+                url: "https://lintr.r-lib.org/",
+                command: "Rscript",
+                args: vec![
+                    "-e",
+                    "for (l in lintr::lint(commandArgs(TRUE)[1])) writeLines(sprintf('%d:%d:%s:%s:%s', l$line_number, l$column_number, l$type, l$linter, gsub('[[:space:]]+', ' ', l$message)))",
+                ],
+                supported_languages: vec!["r"],
+            },
+        );
         linters.insert(
             "ruff".to_string(),
             LinterInfo {
@@ -279,6 +294,9 @@ pub fn parse_linter_output(
     if linter_name == jarl::JarlParser::NAME {
         return jarl::JarlParser::parse(&ctx);
     }
+    if linter_name == lintr::LintrParser::NAME {
+        return lintr::LintrParser::parse(&ctx);
+    }
     if linter_name == ruff::RuffParser::NAME {
         return ruff::RuffParser::parse(&ctx);
     }
@@ -344,6 +362,7 @@ mod tests {
     fn test_registry_contains_linters() {
         let registry = ExternalLinterRegistry::new();
         assert!(registry.get("jarl").is_some());
+        assert!(registry.get("lintr").is_some());
         assert!(registry.get("ruff").is_some());
         assert!(registry.get("eslint").is_some());
         assert!(registry.get("staticcheck").is_some());
@@ -356,6 +375,7 @@ mod tests {
         let registry = ExternalLinterRegistry::new();
         assert_eq!(registry.supports_language("jarl", "r"), Some(true));
         assert_eq!(registry.supports_language("jarl", "bash"), Some(false));
+        assert_eq!(registry.supports_language("lintr", "r"), Some(true));
         assert_eq!(registry.supports_language("ruff", "python"), Some(true));
         assert_eq!(registry.supports_language("eslint", "js"), Some(true));
         assert_eq!(
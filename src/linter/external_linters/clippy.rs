@@ -77,11 +77,12 @@ impl ExternalLinterParser for ClippyParser {
             )
             .unwrap_or(ctx.original_input.len());
 
-            let location = Location {
+            let location = Location::reported(
                 line,
                 column,
-                range: TextRange::new((start_offset as u32).into(), (end_offset as u32).into()),
-            };
+                TextRange::new((start_offset as u32).into(), (end_offset as u32).into()),
+                ctx.original_input,
+            );
 
             let code = msg
                 .code
@@ -64,11 +64,12 @@ impl ExternalLinterParser for EslintParser {
                 let end_offset = line_col_to_offset(ctx.original_input, end_line, end_column)
                     .unwrap_or(ctx.original_input.len());
 
-                let location = Location {
+                let location = Location::reported(
                     line,
                     column,
-                    range: TextRange::new((start_offset as u32).into(), (end_offset as u32).into()),
-                };
+                    TextRange::new((start_offset as u32).into(), (end_offset as u32).into()),
+                    ctx.original_input,
+                );
 
                 let code = msg.rule_id.unwrap_or_else(|| "eslint".to_string());
                 let diagnostic = match msg.severity {
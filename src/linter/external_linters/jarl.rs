@@ -69,11 +69,7 @@ impl ExternalLinterParser for JarlParser {
                 .min(ctx.original_input.len());
             let range = TextRange::new((start_offset as u32).into(), (end_offset as u32).into());
 
-            let location = Location {
-                line,
-                column,
-                range,
-            };
+            let location = Location::reported(line, column, range, ctx.original_input);
 
             let fix = if let Some(mappings) = ctx.mappings {
                 if !jarl_diag.fix.to_skip {
@@ -0,0 +1,122 @@
+use rowan::TextRange;
+
+use super::{ExternalLinterParser, LinterError, ParseContext, line_col_to_offset};
+use crate::linter::diagnostics::{Diagnostic, DiagnosticOrigin, Location};
+
+/// One lint as printed by the registry's `Rscript` one-liner:
+/// `line:column:type:linter:message`.
+#[derive(Debug)]
+struct LintrDiagnostic {
+    line: usize,
+    column: usize,
+    kind: String,
+    linter: String,
+    message: String,
+}
+
+pub(crate) struct LintrParser;
+
+impl ExternalLinterParser for LintrParser {
+    const NAME: &'static str = "lintr";
+
+    fn parse(ctx: &ParseContext<'_>) -> Result<Vec<Diagnostic>, LinterError> {
+        let lints = parse_lintr_output(ctx.output)?;
+
+        let mut output = Vec::new();
+        for lint in lints {
+            let line = lint.line;
+            let column = lint.column.max(1);
+            let start_offset = line_col_to_offset(ctx.original_input, line, column)
+                .unwrap_or(ctx.original_input.len());
+            let end_offset = line_col_to_offset(ctx.original_input, line, column.saturating_add(1))
+                .unwrap_or(ctx.original_input.len());
+
+            let location = Location::reported(
+                line,
+                column,
+                TextRange::new((start_offset as u32).into(), (end_offset as u32).into()),
+                ctx.original_input,
+            );
+
+            let diagnostic = match lint.kind.as_str() {
+                "error" => Diagnostic::error(location, lint.linter, lint.message),
+                "warning" => Diagnostic::warning(location, lint.linter, lint.message),
+                _ => Diagnostic::info(location, lint.linter, lint.message),
+            };
+            output.push(diagnostic.with_origin(DiagnosticOrigin::External));
+        }
+
+        Ok(output)
+    }
+}
+
+fn parse_lintr_output(output: &str) -> Result<Vec<LintrDiagnostic>, LinterError> {
+    let mut lints = Vec::new();
+    for line in output.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        // Anything else on the stream (e.g. R's "there is no package called
+        // 'lintr'") is an error from the one-liner, not a lint.
+        let invalid = || LinterError::ParseError(format!("unexpected lintr output: {}", line));
+        let mut fields = line.splitn(5, ':');
+        let mut next = || fields.next().ok_or_else(invalid);
+        let line_number = next()?.parse().map_err(|_| invalid())?;
+        let column = next()?.parse().map_err(|_| invalid())?;
+        let kind = next()?.to_string();
+        let linter = next()?.to_string();
+        let message = next()?.trim().to_string();
+        lints.push(LintrDiagnostic {
+            line: line_number,
+            column,
+            kind,
+            linter,
+            message,
+        });
+    }
+    Ok(lints)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linter::diagnostics::Severity;
+    use crate::linter::external_linters::ParseContext;
+
+    #[test]
+    fn parses_lintr_lines() {
+        let parsed =
+            parse_lintr_output("3:5:style:assignment_linter:Use <-, not =, for assignment.\n")
+                .unwrap();
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].line, 3);
+        assert_eq!(parsed[0].column, 5);
+        assert_eq!(parsed[0].linter, "assignment_linter");
+        assert_eq!(parsed[0].message, "Use <-, not =, for assignment.");
+    }
+
+    #[test]
+    fn rejects_r_errors() {
+        assert!(
+            parse_lintr_output("Error in loadNamespace(x) : there is no package called 'lintr'")
+                .is_err()
+        );
+        assert!(parse_lintr_output("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn maps_lintr_diagnostic_to_panache() {
+        let ctx = ParseContext {
+            output: "1:3:warning:object_usage_linter:no visible binding for global variable 'y'\n",
+            linted_input: "x = y\n",
+            original_input: "x = y\n",
+            mappings: None,
+        };
+        let diagnostics = LintrParser::parse(&ctx).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "object_usage_linter");
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(diagnostics[0].location.column, 3);
+    }
+}
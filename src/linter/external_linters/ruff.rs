@@ -64,11 +64,7 @@ impl ExternalLinterParser for RuffParser {
                 .unwrap_or(ctx.original_input.len());
 
             let range = TextRange::new((start_offset as u32).into(), (end_offset as u32).into());
-            let location = Location {
-                line,
-                column,
-                range,
-            };
+            let location = Location::reported(line, column, range, ctx.original_input);
 
             let fix = if let (Some(mappings), Some(fix)) = (ctx.mappings, ruff_diag.fix.as_ref()) {
                 let mut edits = Vec::new();
@@ -85,11 +85,7 @@ impl ExternalLinterParser for ShellcheckParser {
                 (sc_diag.line, sc_diag.column, start_offset, end_offset)
             };
             let range = TextRange::new((start_offset as u32).into(), (end_offset as u32).into());
-            let location = Location {
-                line,
-                column,
-                range,
-            };
+            let location = Location::reported(line, column, range, ctx.original_input);
 
             let fix = if let (Some(mappings), Some(fix)) = (ctx.mappings, sc_diag.fix.as_ref()) {
                 let mut edits: Vec<(usize, Edit)> = Vec::new();
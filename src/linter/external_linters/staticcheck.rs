@@ -38,11 +38,12 @@ impl ExternalLinterParser for StaticcheckParser {
             let end_offset = line_col_to_offset(ctx.original_input, line, column.saturating_add(1))
                 .unwrap_or(ctx.original_input.len());
 
-            let location = Location {
+            let location = Location::reported(
                 line,
                 column,
-                range: TextRange::new((start_offset as u32).into(), (end_offset as u32).into()),
-            };
+                TextRange::new((start_offset as u32).into(), (end_offset as u32).into()),
+                ctx.original_input,
+            );
 
             output.push(
                 Diagnostic::warning(location, diag.check, diag.message)
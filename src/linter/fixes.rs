@@ -0,0 +1,270 @@
+//! Applying lint fixes to a document.
+//!
+//! Fixes are computed independently by each rule, so two of them can touch
+//! the same text: a rule re-levelling a heading marker and another rewriting
+//! the whole heading line, say. [`select_fixes`] takes fixes in document order
+//! and skips any whose edits overlap one already taken, so every fix is
+//! applied whole or not at all. [`apply_fixes`] then re-lints the result and
+//! repeats, which picks up the skipped fixes (and any issue a fix uncovered)
+//! once the text they conflicted with has settled.
+
+use crate::linter::diagnostics::{Diagnostic, Edit, FixSafety};
+
+/// Most lint-and-fix passes [`apply_fixes`] makes. Fixes normally settle in
+/// two or three; the cap stops a pair of rules that undo each other.
+pub const MAX_FIX_PASSES: usize = 10;
+
+/// The edits of one round of fixing, and the fixes left out of it.
+#[derive(Debug, Default)]
+pub struct FixSelection<'a> {
+    /// Non-overlapping edits, sorted by position.
+    pub edits: Vec<&'a Edit>,
+    /// Number of fixes whose edits are all in [`Self::edits`].
+    pub applied: usize,
+    /// Diagnostics whose fix overlaps one taken earlier in the document.
+    pub conflicts: Vec<&'a Diagnostic>,
+}
+
+/// Outcome of [`apply_fixes`].
+#[derive(Debug, Clone)]
+pub struct FixResult {
+    /// The fixed document.
+    pub output: String,
+    /// Fixes applied, over all passes.
+    pub applied: usize,
+    /// Fixes still skipped for overlapping another in the last pass, with
+    /// locations in that pass's input.
+    pub conflicts: Vec<Diagnostic>,
+    /// Passes that changed the document.
+    pub passes: usize,
+}
+
+/// Pick the fixes of `diagnostics` that can be applied together: safe ones,
+/// plus unsafe ones with `allow_unsafe`, in order of their first edit. A fix
+/// overlapping one already picked is skipped and reported as a conflict; an
+/// edit identical to a picked one (two rules proposing the same change) is
+/// applied once.
+pub fn select_fixes(diagnostics: &[Diagnostic], allow_unsafe: bool) -> FixSelection<'_> {
+    let mut fixable: Vec<(&Diagnostic, &[Edit])> = diagnostics
+        .iter()
+        .filter_map(|diagnostic| {
+            let fix = diagnostic.fix.as_ref()?;
+            let applies = allow_unsafe || fix.safety == FixSafety::Safe;
+            (applies && !fix.edits.is_empty()).then_some((diagnostic, fix.edits.as_slice()))
+        })
+        .collect();
+    fixable.sort_by_key(|(_, edits)| {
+        edits
+            .iter()
+            .map(|edit| (edit.range.start(), edit.range.end()))
+            .min()
+    });
+
+    let mut selection = FixSelection::default();
+    for (diagnostic, edits) in fixable {
+        let conflicts = edits.iter().any(|edit| {
+            selection
+                .edits
+                .iter()
+                .any(|taken| *taken != edit && overlaps(taken, edit))
+        });
+        if conflicts {
+            selection.conflicts.push(diagnostic);
+            continue;
+        }
+        for edit in edits {
+            if !selection.edits.contains(&edit) {
+                selection.edits.push(edit);
+            }
+        }
+        selection.applied += 1;
+    }
+    selection
+        .edits
+        .sort_by_key(|edit| (edit.range.start(), edit.range.end()));
+    selection
+}
+
+/// Apply the fixes of `diagnostics` to `input`, then re-lint the result with
+/// `relint` and fix again until nothing fixable remains, nothing changes, or
+/// [`MAX_FIX_PASSES`] is reached. `relint` should run the same rules that
+/// produced `diagnostics`, external linters and metadata checks included, so
+/// later passes see the same issues as the first.
+pub fn apply_fixes(
+    input: &str,
+    diagnostics: &[Diagnostic],
+    allow_unsafe: bool,
+    mut relint: impl FnMut(&str) -> Vec<Diagnostic>,
+) -> FixResult {
+    let mut result = FixResult {
+        output: input.to_string(),
+        applied: 0,
+        conflicts: Vec::new(),
+        passes: 0,
+    };
+    let mut relinted: Vec<Diagnostic>;
+    let mut diagnostics = diagnostics;
+    while result.passes < MAX_FIX_PASSES {
+        let selection = select_fixes(diagnostics, allow_unsafe);
+        result.conflicts = selection.conflicts.into_iter().cloned().collect();
+        let output = splice(&result.output, &selection.edits);
+        if output == result.output {
+            break;
+        }
+        result.output = output;
+        result.applied += selection.applied;
+        result.passes += 1;
+
+        relinted = relint(&result.output);
+        diagnostics = &relinted;
+    }
+    result
+}
+
+/// Whether applying both edits would make their order ambiguous: their ranges
+/// overlap, or both insert at the same offset.
+fn overlaps(a: &Edit, b: &Edit) -> bool {
+    if a.range.is_empty() && b.range.is_empty() {
+        return a.range.start() == b.range.start();
+    }
+    a.range.start() < b.range.end() && b.range.start() < a.range.end()
+}
+
+/// `input` with sorted, non-overlapping `edits` applied.
+fn splice(input: &str, edits: &[&Edit]) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut last_end = 0;
+    for edit in edits {
+        let start = usize::from(edit.range.start());
+        output.push_str(&input[last_end..start]);
+        output.push_str(&edit.replacement);
+        last_end = usize::from(edit.range.end());
+    }
+    output.push_str(&input[last_end..]);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use rowan::{TextRange, TextSize};
+
+    use super::*;
+    use crate::config::Config;
+    use crate::linter::diagnostics::{Fix, Location};
+
+    fn edit(start: u32, end: u32, replacement: &str) -> Edit {
+        Edit {
+            range: TextRange::new(TextSize::from(start), TextSize::from(end)),
+            replacement: replacement.to_string(),
+        }
+    }
+
+    fn diagnostic(input: &str, code: &str, fix: Fix) -> Diagnostic {
+        let range = fix.edits[0].range;
+        Diagnostic::warning(Location::from_range(range, input), code, code).with_fix(fix)
+    }
+
+    #[test]
+    fn skips_later_overlapping_fix_and_dedups_identical_edits() {
+        let input = "one two three\n";
+        let diagnostics = vec![
+            diagnostic(input, "b", Fix::safe("b", vec![edit(4, 7, "2")])),
+            diagnostic(input, "a", Fix::safe("a", vec![edit(0, 7, "1 2")])),
+            diagnostic(input, "c", Fix::safe("c", vec![edit(8, 13, "3")])),
+            diagnostic(input, "d", Fix::safe("d", vec![edit(8, 13, "3")])),
+        ];
+        let selection = select_fixes(&diagnostics, false);
+        assert_eq!(selection.applied, 3);
+        assert_eq!(selection.edits.len(), 2);
+        let conflicts: Vec<&str> = selection
+            .conflicts
+            .iter()
+            .map(|d| d.code.as_str())
+            .collect();
+        assert_eq!(conflicts, ["b"]);
+        assert_eq!(splice(input, &selection.edits), "1 2 3\n");
+    }
+
+    #[test]
+    fn a_fix_is_applied_whole_or_not_at_all() {
+        let input = "abcdef\n";
+        let diagnostics = vec![
+            diagnostic(input, "a", Fix::safe("a", vec![edit(0, 1, "A")])),
+            diagnostic(
+                input,
+                "b",
+                Fix::safe("b", vec![edit(4, 5, "E"), edit(0, 1, "X")]),
+            ),
+            diagnostic(input, "c", Fix::safe("c", vec![edit(2, 2, "+")])),
+            diagnostic(input, "d", Fix::safe("d", vec![edit(2, 2, "-")])),
+        ];
+        let selection = select_fixes(&diagnostics, false);
+        let conflicts: Vec<&str> = selection
+            .conflicts
+            .iter()
+            .map(|d| d.code.as_str())
+            .collect();
+        assert_eq!(conflicts, ["b", "d"]);
+        assert_eq!(splice(input, &selection.edits), "Ab+cdef\n");
+    }
+
+    #[test]
+    fn unsafe_fixes_need_opting_in() {
+        let input = "text\n";
+        let diagnostics = vec![diagnostic(
+            input,
+            "u",
+            Fix::unsafe_fix("u", vec![edit(0, 4, "TEXT")]),
+        )];
+        assert_eq!(select_fixes(&diagnostics, false).applied, 0);
+        assert_eq!(select_fixes(&diagnostics, true).applied, 1);
+    }
+
+    #[test]
+    fn later_passes_pick_up_fixes_skipped_for_conflicts() {
+        let input = "# H1\n\n### H3\n";
+        let config = Config::default();
+        let tree = crate::parse(input, Some(config.clone()));
+        let mut diagnostics = vec![diagnostic(
+            input,
+            "retitle",
+            Fix::safe("retitle", vec![edit(0, 7, "# Title\n\n#")]),
+        )];
+        diagnostics.extend(crate::linter::lint(&tree, input, &config));
+        assert!(diagnostics.iter().any(|d| d.code == "heading-hierarchy"));
+
+        let result = apply_fixes(input, &diagnostics, false, |text| {
+            crate::linter::lint(&crate::parse(text, Some(config.clone())), text, &config)
+        });
+        assert_eq!(result.output, "# Title\n\n## H3\n");
+        assert_eq!(result.applied, 2);
+        assert_eq!(result.passes, 2);
+        assert!(result.conflicts.is_empty());
+    }
+
+    #[test]
+    fn later_passes_use_the_callers_relint() {
+        let input = "one\n";
+        let diagnostics = vec![diagnostic(
+            input,
+            "first",
+            Fix::safe("first", vec![edit(0, 3, "two")]),
+        )];
+        let mut seen = Vec::new();
+        let result = apply_fixes(input, &diagnostics, false, |text| {
+            seen.push(text.to_string());
+            if text == "two\n" {
+                vec![diagnostic(
+                    text,
+                    "external",
+                    Fix::safe("external", vec![edit(0, 3, "three")]),
+                )]
+            } else {
+                Vec::new()
+            }
+        });
+        assert_eq!(result.output, "three\n");
+        assert_eq!(result.applied, 2);
+        assert_eq!(seen, ["two\n", "three\n"]);
+    }
+}
@@ -6,6 +6,8 @@ use crate::linter::index::LintIndex;
 use crate::syntax::{SyntaxKind, SyntaxNode, SyntaxToken};
 
 pub mod adjacent_footnote_refs;
+pub mod bare_urls;
+pub mod blanks_around;
 pub mod chunk_label_spaces;
 pub mod citation_keys;
 pub mod citation_nonbreaking_space;
@@ -13,19 +15,29 @@ pub mod consumer_divergence;
 pub mod crossref_as_link_target;
 pub mod duplicate_references;
 pub mod emoji_aliases;
+pub mod emphasis_as_heading;
 pub mod empty_list_item;
 pub mod empty_values;
+pub mod execution_options;
 pub mod figure_crossref_captions;
+pub mod final_newline;
 pub mod footnote_ref_in_footnote_def;
 pub mod heading_eaten_attrs;
 pub mod heading_hierarchy;
+pub mod heading_length;
 pub mod heading_strip_comments_residue;
+pub mod heading_style;
 pub mod html_entities;
 pub mod link_text_is_url;
 pub mod math_content;
 pub mod missing_chunk_labels;
+pub mod mixed_line_endings;
+pub mod prose;
 pub mod quarto_schema;
+#[cfg(all(feature = "spellcheck", not(target_arch = "wasm32")))]
+pub mod spelling;
 pub mod stray_fenced_div_markers;
+pub mod trailing_whitespace;
 pub mod undefined_anchor;
 pub mod undefined_references;
 pub mod unused_definitions;
@@ -52,6 +64,8 @@ pub enum Requirement {
     FencedDivs,
     /// Needs `extensions.emoji`.
     Emoji,
+    /// Needs `extensions.autolink-bare-uris`.
+    AutolinkBareUris,
     /// Needs any `tex-math-*` extension (dollars, gfm, single/double backslash).
     TexMath,
     /// Needs a flavor with executable chunks (Quarto or R Markdown).
@@ -73,6 +87,7 @@ impl Requirement {
             Requirement::FencedCodeAttributes => ext.fenced_code_attributes,
             Requirement::FencedDivs => ext.fenced_divs,
             Requirement::Emoji => ext.emoji,
+            Requirement::AutolinkBareUris => ext.autolink_bare_uris,
             Requirement::TexMath => {
                 ext.tex_math_dollars
                     || ext.tex_math_gfm
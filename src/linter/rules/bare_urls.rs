@@ -0,0 +1,127 @@
+use crate::config::BareUrls;
+use crate::linter::diagnostics::{Diagnostic, DiagnosticNoteKind, Edit, Fix, Location};
+use crate::linter::rules::{DiagnosticCode, LintContext, Requirement, Rule, RuleMeta};
+use crate::syntax::{AstNode, AutoLink, SyntaxKind};
+
+/// Flags bare URLs in prose (MD034), which only render as links under
+/// `autolink-bare-uris`.
+///
+/// When `[format] bare-urls` rewrites them, the formatter already does what
+/// the fix would, so the rule stays quiet.
+pub struct BareUrlsRule;
+
+impl Rule for BareUrlsRule {
+    fn name(&self) -> &str {
+        "bare-urls"
+    }
+
+    fn metadata(&self) -> RuleMeta {
+        RuleMeta {
+            name: "bare-urls",
+            default_on: false,
+            requires: Requirement::AutolinkBareUris,
+            auto_fix: true,
+            codes: const { &[DiagnosticCode::warning("bare-urls")] },
+        }
+    }
+
+    fn node_interests(&self) -> &'static [SyntaxKind] {
+        &[SyntaxKind::AUTO_LINK]
+    }
+
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        if cx.config.bare_urls != BareUrls::Preserve {
+            return Vec::new();
+        }
+
+        let mut diagnostics = Vec::new();
+        for autolink in cx
+            .nodes(SyntaxKind::AUTO_LINK)
+            .iter()
+            .cloned()
+            .filter_map(AutoLink::cast)
+        {
+            let node = autolink.syntax();
+            // `<url>` is already explicit.
+            if node
+                .children_with_tokens()
+                .any(|child| child.kind() == SyntaxKind::AUTO_LINK_MARKER)
+            {
+                continue;
+            }
+            // Inside link text the URL is only text; wrapping it would nest links.
+            if node
+                .ancestors()
+                .any(|a| matches!(a.kind(), SyntaxKind::LINK_TEXT | SyntaxKind::IMAGE_ALT))
+            {
+                continue;
+            }
+
+            let url = autolink.target();
+            let range = node.text_range();
+            let replacement = format!("<{url}>");
+            diagnostics.push(
+                Diagnostic::warning(
+                    Location::from_range(range, cx.input),
+                    "bare-urls",
+                    format!("Bare URL '{url}'"),
+                )
+                .with_note(
+                    DiagnosticNoteKind::Help,
+                    format!("write it as an autolink: `{replacement}`"),
+                )
+                .with_fix(Fix::safe(
+                    "Wrap in angle brackets",
+                    vec![Edit { range, replacement }],
+                )),
+            );
+        }
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Config, Flavor};
+
+    fn lint_gfm(input: &str, bare_urls: BareUrls) -> Vec<Diagnostic> {
+        let config = Config {
+            flavor: Flavor::Gfm,
+            extensions: panache_parser::Extensions::for_flavor(Flavor::Gfm),
+            bare_urls,
+            ..Default::default()
+        };
+        let tree = crate::parser::parse(input, Some(config.clone()));
+        BareUrlsRule.check_tree(&tree, input, &config, None)
+    }
+
+    #[test]
+    fn fires_on_bare_url_with_autolink_fix() {
+        let diagnostics = lint_gfm("See https://example.com/path.\n", BareUrls::Preserve);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "bare-urls");
+        assert_eq!(diagnostics[0].location.column, 5);
+        let fix = diagnostics[0].fix.as_ref().expect("autofix");
+        assert_eq!(fix.edits[0].replacement, "<https://example.com/path>");
+    }
+
+    #[test]
+    fn skips_angle_bracket_autolinks_and_links() {
+        let input = "Visit <https://example.com> or [the site](https://example.com).\n";
+        assert!(lint_gfm(input, BareUrls::Preserve).is_empty());
+    }
+
+    #[test]
+    fn skips_urls_in_link_text() {
+        let input = "[see https://example.com](https://example.com)\n";
+        assert!(lint_gfm(input, BareUrls::Preserve).is_empty());
+    }
+
+    #[test]
+    fn quiet_when_the_formatter_rewrites_bare_urls() {
+        let input = "See https://example.com today.\n";
+        assert!(lint_gfm(input, BareUrls::Autolink).is_empty());
+        assert!(lint_gfm(input, BareUrls::Link).is_empty());
+    }
+}
@@ -0,0 +1,260 @@
+//! Blank lines around blocks (markdownlint MD022, MD031, and MD032).
+//!
+//! Three rules share the check here, each with its own name so it can be
+//! toggled independently in `[lint.rules]`:
+//!
+//! - [`BlanksAroundHeadingsRule`] (`blanks-around-headings`) for headings.
+//! - [`BlanksAroundFencesRule`] (`blanks-around-fences`) for code blocks.
+//! - [`BlanksAroundListsRule`] (`blanks-around-lists`) for lists.
+//!
+//! Like `[format] blank-lines-around`, they only look at blocks at the document
+//! level and in fenced divs there. Inside a list item a blank line would make
+//! the list loose, and inside a block quote it would end the quote.
+
+use rowan::{TextRange, TextSize};
+
+use crate::linter::diagnostics::{Diagnostic, Edit, Fix, Location};
+use crate::linter::rules::{DiagnosticCode, LintContext, Requirement, Rule, RuleMeta};
+use crate::syntax::{SyntaxKind, SyntaxNode};
+
+/// Flags headings without a blank line before and after them (MD022).
+pub struct BlanksAroundHeadingsRule;
+
+/// Flags code blocks without a blank line before and after them (MD031).
+pub struct BlanksAroundFencesRule;
+
+/// Flags lists without a blank line before and after them (MD032).
+pub struct BlanksAroundListsRule;
+
+impl Rule for BlanksAroundHeadingsRule {
+    fn name(&self) -> &str {
+        "blanks-around-headings"
+    }
+
+    fn metadata(&self) -> RuleMeta {
+        RuleMeta {
+            name: "blanks-around-headings",
+            default_on: false,
+            requires: Requirement::Always,
+            auto_fix: true,
+            codes: const { &[DiagnosticCode::warning("blanks-around-headings")] },
+        }
+    }
+
+    fn node_interests(&self) -> &'static [SyntaxKind] {
+        &[SyntaxKind::HEADING]
+    }
+
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        check_blocks(cx, SyntaxKind::HEADING, "blanks-around-headings", "Heading")
+    }
+}
+
+impl Rule for BlanksAroundFencesRule {
+    fn name(&self) -> &str {
+        "blanks-around-fences"
+    }
+
+    fn metadata(&self) -> RuleMeta {
+        RuleMeta {
+            name: "blanks-around-fences",
+            default_on: false,
+            requires: Requirement::Always,
+            auto_fix: true,
+            codes: const { &[DiagnosticCode::warning("blanks-around-fences")] },
+        }
+    }
+
+    fn node_interests(&self) -> &'static [SyntaxKind] {
+        &[SyntaxKind::CODE_BLOCK]
+    }
+
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        check_blocks(
+            cx,
+            SyntaxKind::CODE_BLOCK,
+            "blanks-around-fences",
+            "Code block",
+        )
+    }
+}
+
+impl Rule for BlanksAroundListsRule {
+    fn name(&self) -> &str {
+        "blanks-around-lists"
+    }
+
+    fn metadata(&self) -> RuleMeta {
+        RuleMeta {
+            name: "blanks-around-lists",
+            default_on: false,
+            requires: Requirement::Always,
+            auto_fix: true,
+            codes: const { &[DiagnosticCode::warning("blanks-around-lists")] },
+        }
+    }
+
+    fn node_interests(&self) -> &'static [SyntaxKind] {
+        &[SyntaxKind::LIST]
+    }
+
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        check_blocks(cx, SyntaxKind::LIST, "blanks-around-lists", "List")
+    }
+}
+
+fn check_blocks(
+    cx: &LintContext,
+    kind: SyntaxKind,
+    code: &'static str,
+    label: &str,
+) -> Vec<Diagnostic> {
+    let input = cx.input;
+    let mut diagnostics = Vec::new();
+    for node in cx.nodes(kind) {
+        if !is_separable(node) {
+            continue;
+        }
+
+        let mut edits = Vec::new();
+        // A block of the same kind right before reports the shared gap itself.
+        if node
+            .prev_sibling()
+            .is_some_and(|prev| !is_separator(prev.kind()) && !ends_with_blank_line(&prev))
+        {
+            let start = usize::from(node.text_range().start());
+            let line_start = input[..start].rfind('\n').map_or(0, |idx| idx + 1);
+            edits.push(insert_newline(line_start));
+        }
+        if let Some(next) = node.next_sibling()
+            && next.kind() != kind
+            && !is_separator(next.kind())
+            && !ends_with_blank_line(node)
+            && input[..usize::from(node.text_range().end())].ends_with('\n')
+        {
+            edits.push(insert_newline(usize::from(node.text_range().end())));
+        }
+        if edits.is_empty() {
+            continue;
+        }
+
+        let range = node_line_range(node, input);
+        diagnostics.push(
+            Diagnostic::warning(
+                Location::from_range(range, input),
+                code,
+                format!("{label} should be surrounded by blank lines"),
+            )
+            .with_fix(Fix::safe("Add blank lines", edits)),
+        );
+    }
+    diagnostics
+}
+
+/// Whether `node` sits at the document level or in fenced divs there.
+pub(super) fn is_separable(node: &SyntaxNode) -> bool {
+    node.parent().is_some_and(|parent| {
+        parent.ancestors().all(|ancestor| {
+            matches!(
+                ancestor.kind(),
+                SyntaxKind::DOCUMENT | SyntaxKind::FENCED_DIV
+            )
+        })
+    })
+}
+
+/// Siblings that already separate a block: blank lines and div fences.
+fn is_separator(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::BLANK_LINE | SyntaxKind::DIV_FENCE_OPEN | SyntaxKind::DIV_FENCE_CLOSE
+    )
+}
+
+/// Whether `node` ends with a blank line of its own (lists can absorb the blank
+/// line after their last item).
+fn ends_with_blank_line(node: &SyntaxNode) -> bool {
+    let text = node.text().to_string();
+    text.ends_with('\n')
+        && text
+            .lines()
+            .last()
+            .is_some_and(|line| line.trim().is_empty())
+}
+
+fn insert_newline(offset: usize) -> Edit {
+    Edit {
+        range: TextRange::empty(TextSize::from(offset as u32)),
+        replacement: "\n".to_string(),
+    }
+}
+
+/// The first line of `node`, for the diagnostic location.
+fn node_line_range(node: &SyntaxNode, input: &str) -> TextRange {
+    let start = usize::from(node.text_range().start());
+    let end = input[start..]
+        .find('\n')
+        .map_or(input.len(), |idx| start + idx)
+        .min(usize::from(node.text_range().end()));
+    TextRange::new(TextSize::from(start as u32), TextSize::from(end as u32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Config, Extensions, Flavor};
+
+    fn lint(rule: &dyn Rule, input: &str) -> Vec<Diagnostic> {
+        let config = Config {
+            flavor: Flavor::Gfm,
+            extensions: Extensions::for_flavor(Flavor::Gfm),
+            ..Default::default()
+        };
+        let tree = crate::parser::parse(input, Some(config.clone()));
+        rule.check_tree(&tree, input, &config, None)
+    }
+
+    fn fixed(input: &str, diagnostics: &[Diagnostic]) -> String {
+        let mut edits: Vec<&Edit> = diagnostics
+            .iter()
+            .flat_map(|diagnostic| &diagnostic.fix.as_ref().expect("autofix").edits)
+            .collect();
+        edits.sort_by_key(|edit| edit.range.start());
+        let mut output = input.to_string();
+        for edit in edits.iter().rev() {
+            let range = usize::from(edit.range.start())..usize::from(edit.range.end());
+            output.replace_range(range, &edit.replacement);
+        }
+        output
+    }
+
+    #[test]
+    fn flags_fence_without_blank_lines_and_fixes_both_sides() {
+        let input = "Text.\n```\ncode\n```\nMore.\n";
+        let diagnostics = lint(&BlanksAroundFencesRule, input);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "blanks-around-fences");
+        assert_eq!(diagnostics[0].location.line, 2);
+        assert_eq!(
+            fixed(input, &diagnostics),
+            "Text.\n\n```\ncode\n```\n\nMore.\n"
+        );
+    }
+
+    #[test]
+    fn flags_heading_and_list_gaps() {
+        let input = "# Title\n- a\n- b\n## Next\n";
+        let headings = lint(&BlanksAroundHeadingsRule, input);
+        assert_eq!(headings.len(), 2);
+        let lists = lint(&BlanksAroundListsRule, input);
+        assert_eq!(lists.len(), 1);
+        assert_eq!(fixed(input, &lists), "# Title\n\n- a\n- b\n\n## Next\n");
+    }
+
+    #[test]
+    fn quiet_when_separated_first_or_inside_list_items() {
+        assert!(lint(&BlanksAroundFencesRule, "```\ncode\n```\n\nText.\n").is_empty());
+        assert!(lint(&BlanksAroundFencesRule, "- item\n  ```\n  code\n  ```\n").is_empty());
+        assert!(lint(&BlanksAroundListsRule, "- a\n  - nested\n").is_empty());
+    }
+}
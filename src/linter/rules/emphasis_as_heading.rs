@@ -0,0 +1,149 @@
+use rowan::NodeOrToken;
+
+use crate::linter::diagnostics::{Diagnostic, Edit, Fix, Location};
+use crate::linter::rules::blanks_around::is_separable;
+use crate::linter::rules::{DiagnosticCode, LintContext, Requirement, Rule, RuleMeta};
+use crate::syntax::{AstNode, Heading, SyntaxKind, SyntaxNode};
+
+/// Flags a paragraph that is nothing but one line of bold or italic text, a
+/// heading in all but name (markdownlint MD036).
+///
+/// Such a line renders like a heading but is missing from the table of
+/// contents and cannot be linked to. Lines ending in punctuation read as a
+/// sentence and are left alone, as are paragraphs inside lists and block
+/// quotes, where a bold lead-in is the usual way to label an item.
+pub struct EmphasisAsHeadingRule;
+
+impl Rule for EmphasisAsHeadingRule {
+    fn name(&self) -> &str {
+        "emphasis-as-heading"
+    }
+
+    fn metadata(&self) -> RuleMeta {
+        RuleMeta {
+            name: "emphasis-as-heading",
+            default_on: false,
+            requires: Requirement::Always,
+            auto_fix: true,
+            codes: const { &[DiagnosticCode::warning("emphasis-as-heading")] },
+        }
+    }
+
+    fn node_interests(&self) -> &'static [SyntaxKind] {
+        &[SyntaxKind::PARAGRAPH, SyntaxKind::HEADING]
+    }
+
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        let headings = cx.nodes(SyntaxKind::HEADING);
+        let mut diagnostics = Vec::new();
+        for paragraph in cx.nodes(SyntaxKind::PARAGRAPH) {
+            if !is_separable(paragraph) {
+                continue;
+            }
+            let Some(emphasis) = sole_emphasis(paragraph) else {
+                continue;
+            };
+            let Some(text) = emphasized_text(&emphasis, cx.input) else {
+                continue;
+            };
+            if text.contains('\n')
+                || text.ends_with([
+                    '.', ',', ';', ':', '!', '?', '。', '，', '；', '：', '！', '？',
+                ])
+            {
+                continue;
+            }
+
+            // One level below the heading before it: the line usually titles
+            // a subsection of it.
+            let level = headings
+                .iter()
+                .take_while(|heading| heading.text_range().end() <= paragraph.text_range().start())
+                .last()
+                .and_then(|heading| Heading::cast(heading.clone()))
+                .map_or(2, |heading| (heading.level() + 1).min(6));
+            diagnostics.push(
+                Diagnostic::warning(
+                    Location::from_range(emphasis.text_range(), cx.input),
+                    "emphasis-as-heading",
+                    "Emphasis used instead of a heading",
+                )
+                .with_fix(Fix::unsafe_fix(
+                    "Convert to a heading",
+                    vec![Edit {
+                        range: emphasis.text_range(),
+                        replacement: format!("{} {text}", "#".repeat(level)),
+                    }],
+                )),
+            );
+        }
+        diagnostics
+    }
+}
+
+/// The `STRONG` or `EMPHASIS` node that makes up all of `paragraph`.
+fn sole_emphasis(paragraph: &SyntaxNode) -> Option<SyntaxNode> {
+    let mut elements = paragraph
+        .children_with_tokens()
+        .filter(|element| !matches!(element.kind(), SyntaxKind::WHITESPACE | SyntaxKind::NEWLINE));
+    match (elements.next(), elements.next()) {
+        (Some(NodeOrToken::Node(node)), None)
+            if matches!(node.kind(), SyntaxKind::STRONG | SyntaxKind::EMPHASIS) =>
+        {
+            Some(node)
+        }
+        _ => None,
+    }
+}
+
+/// The source text between the outer markers of `emphasis`, trimmed.
+fn emphasized_text<'a>(emphasis: &SyntaxNode, input: &'a str) -> Option<&'a str> {
+    let markers: Vec<_> = emphasis
+        .children_with_tokens()
+        .filter_map(|element| element.into_token())
+        .filter(|token| {
+            matches!(
+                token.kind(),
+                SyntaxKind::STRONG_MARKER | SyntaxKind::EMPHASIS_MARKER
+            )
+        })
+        .collect();
+    let [open, .., close] = markers.as_slice() else {
+        return None;
+    };
+    let start = usize::from(open.text_range().end());
+    let end = usize::from(close.text_range().start());
+    let text = input.get(start..end)?.trim();
+    (!text.is_empty()).then_some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn lint(input: &str) -> Vec<Diagnostic> {
+        let config = Config::default();
+        let tree = crate::parser::parse(input, Some(config.clone()));
+        EmphasisAsHeadingRule.check_tree(&tree, input, &config, None)
+    }
+
+    #[test]
+    fn flags_bold_line_and_suggests_next_heading_level() {
+        let input = "## Setup\n\n**Installing on Linux**\n\nRun the installer.\n";
+        let diagnostics = lint(input);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].location.line, 3);
+        let edit = &diagnostics[0].fix.as_ref().expect("fix").edits[0];
+        assert_eq!(edit.replacement, "### Installing on Linux");
+        assert_eq!(&input[edit.range], "**Installing on Linux**");
+    }
+
+    #[test]
+    fn quiet_for_sentences_mixed_paragraphs_and_list_items() {
+        assert!(lint("*Thanks for reading!*\n").is_empty());
+        assert!(lint("**Note:** the default is off.\n").is_empty());
+        assert!(lint("- **Label**\n").is_empty());
+        assert!(lint("**Line one\nline two**\n").is_empty());
+    }
+}
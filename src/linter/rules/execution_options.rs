@@ -0,0 +1,324 @@
+//! Execution-option hygiene for executable chunks.
+//!
+//! Three rules share the option plumbing here, each with its own name so it can
+//! be toggled independently in `[lint.rules]`:
+//!
+//! - [`ChunkEvalIncludeRule`] (`chunk-eval-include`) flags an explicit
+//!   `eval: true` next to `include: false`. The chunk still runs, but neither
+//!   its code nor its output reaches the document, which is rarely what the
+//!   explicit `eval: true` was meant to say.
+//! - [`ChunkOptionConflictRule`] (`chunk-option-conflict`) flags an execution
+//!   option (`echo`, `eval`, ...) set to different values on the fence line
+//!   and in the hashpipe YAML of the same chunk. Only one of the two takes
+//!   effect, so the other one misleads the reader.
+//! - [`ChunkOptionDottedNameRule`] (`chunk-option-dotted-name`, Quarto only)
+//!   flags knitr-style dotted hashpipe keys (`fig.width`) and renames them to
+//!   the hyphenated form Quarto documents (`fig-width`).
+
+use std::collections::HashSet;
+
+use crate::linter::diagnostics::{Diagnostic, Edit, Fix, Location};
+use crate::linter::rules::{DiagnosticCode, LintContext, Requirement, Rule, RuleMeta};
+use crate::parser::utils::chunk_options::hashpipe_comment_prefix;
+use crate::syntax::{AstNode, ChunkOptionEntry, CodeBlock, SyntaxKind};
+
+/// Options that control whether and how a chunk executes and what it shows.
+const EXECUTION_OPTIONS: &[&str] = &[
+    "eval", "echo", "include", "output", "warning", "message", "error",
+];
+
+pub struct ChunkEvalIncludeRule;
+pub struct ChunkOptionConflictRule;
+pub struct ChunkOptionDottedNameRule;
+
+impl Rule for ChunkEvalIncludeRule {
+    fn name(&self) -> &str {
+        "chunk-eval-include"
+    }
+
+    fn metadata(&self) -> RuleMeta {
+        RuleMeta {
+            name: "chunk-eval-include",
+            default_on: true,
+            requires: Requirement::ChunkFlavor,
+            auto_fix: false,
+            codes: const { &[DiagnosticCode::warning("chunk-eval-include")] },
+        }
+    }
+
+    fn node_interests(&self) -> &'static [SyntaxKind] {
+        &[SyntaxKind::CODE_BLOCK]
+    }
+
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        for block in executable_chunks(cx) {
+            let entries = block.merged_chunk_option_entries();
+            let Some(eval) = find_option(&entries, "eval") else {
+                continue;
+            };
+            let hidden = find_option(&entries, "include")
+                .and_then(|include| include.value())
+                .and_then(|value| parse_bool(&value))
+                == Some(false);
+            if !hidden || eval.value().and_then(|value| parse_bool(&value)) != Some(true) {
+                continue;
+            }
+
+            diagnostics.push(Diagnostic::warning(
+                Location::from_range(eval.declaration_range(), cx.input),
+                "chunk-eval-include",
+                "Chunk sets `eval: true` with `include: false`; it runs, but neither its \
+                 code nor its output appears in the document",
+            ));
+        }
+        diagnostics
+    }
+}
+
+impl Rule for ChunkOptionConflictRule {
+    fn name(&self) -> &str {
+        "chunk-option-conflict"
+    }
+
+    fn metadata(&self) -> RuleMeta {
+        RuleMeta {
+            name: "chunk-option-conflict",
+            default_on: true,
+            requires: Requirement::ChunkFlavor,
+            auto_fix: false,
+            codes: const { &[DiagnosticCode::warning("chunk-option-conflict")] },
+        }
+    }
+
+    fn node_interests(&self) -> &'static [SyntaxKind] {
+        &[SyntaxKind::CODE_BLOCK]
+    }
+
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        for block in executable_chunks(cx) {
+            let inline = block.inline_chunk_option_entries();
+            let prefix = block
+                .language()
+                .as_deref()
+                .and_then(hashpipe_comment_prefix)
+                .unwrap_or("#|");
+
+            for entry in block.hashpipe_chunk_option_entries() {
+                let Some(key) = entry.key().map(|key| canonical_key(&key)) else {
+                    continue;
+                };
+                if !EXECUTION_OPTIONS.contains(&key.as_str()) {
+                    continue;
+                }
+                let Some(header) = find_option(&inline, &key) else {
+                    continue;
+                };
+                let (Some(hashpipe_value), Some(header_value)) = (entry.value(), header.value())
+                else {
+                    continue;
+                };
+                if same_value(&hashpipe_value, &header_value) {
+                    continue;
+                }
+
+                diagnostics.push(Diagnostic::warning(
+                    Location::from_range(entry.declaration_range(), cx.input),
+                    "chunk-option-conflict",
+                    format!(
+                        "`{key}` is `{}` in the chunk header but `{}` in `{prefix}` options; \
+                         set it in one place",
+                        header_value.trim(),
+                        hashpipe_value.trim()
+                    ),
+                ));
+            }
+        }
+        diagnostics
+    }
+}
+
+impl Rule for ChunkOptionDottedNameRule {
+    fn name(&self) -> &str {
+        "chunk-option-dotted-name"
+    }
+
+    fn metadata(&self) -> RuleMeta {
+        RuleMeta {
+            name: "chunk-option-dotted-name",
+            default_on: true,
+            requires: Requirement::Quarto,
+            auto_fix: true,
+            codes: const { &[DiagnosticCode::warning("chunk-option-dotted-name")] },
+        }
+    }
+
+    fn node_interests(&self) -> &'static [SyntaxKind] {
+        &[SyntaxKind::CODE_BLOCK]
+    }
+
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        for block in executable_chunks(cx) {
+            let entries = block.hashpipe_chunk_option_entries();
+            let existing: HashSet<String> = entries.iter().filter_map(|e| e.key()).collect();
+
+            for entry in &entries {
+                let (Some(key), Some(key_range)) = (entry.key(), entry.key_range()) else {
+                    continue;
+                };
+                if !key.contains('.') {
+                    continue;
+                }
+                let hyphenated = canonical_key(&key);
+
+                let diagnostic = Diagnostic::warning(
+                    Location::from_range(key_range, cx.input),
+                    "chunk-option-dotted-name",
+                    format!("Chunk option `{key}` uses the knitr spelling; use `{hyphenated}`"),
+                );
+                // Renaming onto a key the chunk already sets would duplicate it.
+                if existing.contains(&hyphenated) {
+                    diagnostics.push(diagnostic);
+                    continue;
+                }
+                diagnostics.push(diagnostic.with_fix(Fix::safe(
+                    format!("Rename to `{hyphenated}`"),
+                    vec![Edit {
+                        range: key_range,
+                        replacement: hyphenated,
+                    }],
+                )));
+            }
+        }
+        diagnostics
+    }
+}
+
+fn executable_chunks<'a>(cx: &'a LintContext) -> impl Iterator<Item = CodeBlock> + 'a {
+    cx.nodes(SyntaxKind::CODE_BLOCK)
+        .iter()
+        .cloned()
+        .filter_map(CodeBlock::cast)
+        .filter(CodeBlock::is_executable_chunk)
+}
+
+/// `fig.width` and `fig-width` name the same option.
+fn canonical_key(key: &str) -> String {
+    key.trim().replace('.', "-")
+}
+
+fn find_option<'a>(entries: &'a [ChunkOptionEntry], key: &str) -> Option<&'a ChunkOptionEntry> {
+    entries
+        .iter()
+        .find(|entry| entry.key().is_some_and(|k| canonical_key(&k) == key))
+}
+
+/// Booleans as written in YAML (`true`) or R (`TRUE`, `T`).
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim() {
+        "true" | "True" | "TRUE" | "T" => Some(true),
+        "false" | "False" | "FALSE" | "F" => Some(false),
+        _ => None,
+    }
+}
+
+fn same_value(a: &str, b: &str) -> bool {
+    match (parse_bool(a), parse_bool(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => a.trim() == b.trim(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Config, Flavor};
+
+    fn lint_with(rule: &dyn Rule, input: &str) -> Vec<Diagnostic> {
+        let config = Config {
+            flavor: Flavor::Quarto,
+            extensions: crate::config::Extensions::for_flavor(Flavor::Quarto),
+            ..Default::default()
+        };
+        let tree = crate::parser::parse(input, Some(config.clone()));
+        rule.check_tree(&tree, input, &config, None)
+    }
+
+    #[test]
+    fn flags_eval_true_with_include_false() {
+        let input = "```{r}\n#| eval: true\n#| include: false\nx <- 1\n```\n";
+        let diagnostics = lint_with(&ChunkEvalIncludeRule, input);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "chunk-eval-include");
+        assert_eq!(diagnostics[0].location.line, 2);
+    }
+
+    #[test]
+    fn flags_eval_include_across_header_and_hashpipe() {
+        let input = "```{r, include=FALSE}\n#| eval: true\nx <- 1\n```\n";
+        assert_eq!(lint_with(&ChunkEvalIncludeRule, input).len(), 1);
+    }
+
+    #[test]
+    fn accepts_include_false_without_explicit_eval() {
+        let input = "```{r}\n#| include: false\nx <- 1\n```\n";
+        assert!(lint_with(&ChunkEvalIncludeRule, input).is_empty());
+
+        let input = "```{r}\n#| eval: false\n#| include: false\nx <- 1\n```\n";
+        assert!(lint_with(&ChunkEvalIncludeRule, input).is_empty());
+    }
+
+    #[test]
+    fn flags_echo_conflict_between_header_and_hashpipe() {
+        let input = "```{r, echo=FALSE}\n#| echo: true\nx <- 1\n```\n";
+        let diagnostics = lint_with(&ChunkOptionConflictRule, input);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "chunk-option-conflict");
+        assert_eq!(diagnostics[0].location.line, 2);
+        assert!(diagnostics[0].message.contains("`echo` is `FALSE`"));
+    }
+
+    #[test]
+    fn accepts_matching_values_in_different_spellings() {
+        let input = "```{r, echo=FALSE}\n#| echo: false\nx <- 1\n```\n";
+        assert!(lint_with(&ChunkOptionConflictRule, input).is_empty());
+    }
+
+    #[test]
+    fn ignores_non_execution_options() {
+        let input = "```{r, fig.width=4}\n#| fig-width: 6\nx <- 1\n```\n";
+        assert!(lint_with(&ChunkOptionConflictRule, input).is_empty());
+    }
+
+    #[test]
+    fn renames_dotted_hashpipe_key() {
+        let input = "```{r}\n#| fig.width: 6\nplot(1)\n```\n";
+        let diagnostics = lint_with(&ChunkOptionDottedNameRule, input);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "chunk-option-dotted-name");
+
+        let fix = diagnostics[0].fix.as_ref().expect("fix");
+        assert_eq!(fix.edits.len(), 1);
+        assert_eq!(fix.edits[0].replacement, "fig-width");
+        let range = fix.edits[0].range;
+        assert_eq!(&input[range], "fig.width");
+    }
+
+    #[test]
+    fn does_not_rename_onto_existing_key() {
+        let input = "```{r}\n#| fig.width: 6\n#| fig-width: 4\nplot(1)\n```\n";
+        let diagnostics = lint_with(&ChunkOptionDottedNameRule, input);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].fix.is_none());
+    }
+
+    #[test]
+    fn leaves_dotted_header_options_alone() {
+        // Header options are R arguments, where the dotted name is the only
+        // valid spelling.
+        let input = "```{r, fig.width=6}\nplot(1)\n```\n";
+        assert!(lint_with(&ChunkOptionDottedNameRule, input).is_empty());
+    }
+}
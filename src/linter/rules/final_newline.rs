@@ -0,0 +1,135 @@
+use rowan::{TextRange, TextSize};
+
+use crate::config::{FinalNewline, LineEnding};
+use crate::linter::diagnostics::{Diagnostic, Edit, Fix, Location};
+use crate::linter::rules::{DiagnosticCode, LintContext, Requirement, Rule, RuleMeta};
+
+/// Flags a document that does not end with exactly one newline (MD047): a
+/// missing final newline, or blank lines after the last content line.
+///
+/// Quiet under `final-newline = "preserve"`, which keeps whatever the input
+/// has.
+pub struct FinalNewlineRule;
+
+impl Rule for FinalNewlineRule {
+    fn name(&self) -> &str {
+        "final-newline"
+    }
+
+    fn metadata(&self) -> RuleMeta {
+        RuleMeta {
+            name: "final-newline",
+            default_on: false,
+            requires: Requirement::Always,
+            auto_fix: true,
+            codes: const { &[DiagnosticCode::warning("final-newline")] },
+        }
+    }
+
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        let input = cx.input;
+        if cx.config.final_newline == FinalNewline::Preserve || input.trim().is_empty() {
+            return Vec::new();
+        }
+
+        let content_end = input.trim_end().len();
+        let diagnostic = match input[content_end..].find('\n') {
+            None => {
+                let end = TextSize::from(input.len() as u32);
+                let range = TextRange::empty(end);
+                let newline = if expects_crlf(input, cx.config.line_ending.as_ref()) {
+                    "\r\n"
+                } else {
+                    "\n"
+                };
+                Diagnostic::warning(
+                    Location::from_range(range, input),
+                    "final-newline",
+                    "File does not end with a newline",
+                )
+                .with_fix(Fix::safe(
+                    "Add a final newline",
+                    vec![Edit {
+                        range,
+                        replacement: newline.to_string(),
+                    }],
+                ))
+            }
+            Some(idx) => {
+                let first_newline_end = content_end + idx + 1;
+                if first_newline_end == input.len() {
+                    return Vec::new();
+                }
+                let range = TextRange::new(
+                    TextSize::from(first_newline_end as u32),
+                    TextSize::from(input.len() as u32),
+                );
+                Diagnostic::warning(
+                    Location::from_range(range, input),
+                    "final-newline",
+                    "File ends with blank lines",
+                )
+                .with_fix(Fix::safe(
+                    "Remove trailing blank lines",
+                    vec![Edit {
+                        range,
+                        replacement: String::new(),
+                    }],
+                ))
+            }
+        };
+        vec![diagnostic]
+    }
+}
+
+/// Whether the missing newline should be CRLF: as configured, or under `auto`
+/// when the first line ending in the document is.
+fn expects_crlf(input: &str, line_ending: Option<&LineEnding>) -> bool {
+    match line_ending {
+        Some(LineEnding::Crlf) => true,
+        Some(LineEnding::Lf) => false,
+        Some(LineEnding::Auto) | None => input
+            .find('\n')
+            .is_some_and(|idx| input[..idx].ends_with('\r')),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn lint_with(input: &str, final_newline: FinalNewline) -> Vec<Diagnostic> {
+        let config = Config {
+            final_newline,
+            ..Default::default()
+        };
+        let tree = crate::parser::parse(input, Some(config.clone()));
+        FinalNewlineRule.check_tree(&tree, input, &config, None)
+    }
+
+    #[test]
+    fn flags_missing_final_newline() {
+        let diagnostics = lint_with("one\r\ntwo", FinalNewline::Ensure);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].location.line, 2);
+        let fix = diagnostics[0].fix.as_ref().expect("autofix");
+        assert_eq!(fix.edits[0].replacement, "\r\n");
+    }
+
+    #[test]
+    fn flags_trailing_blank_lines() {
+        let diagnostics = lint_with("text\n\n \n", FinalNewline::Ensure);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "File ends with blank lines");
+        let edit = &diagnostics[0].fix.as_ref().expect("autofix").edits[0];
+        assert_eq!(usize::from(edit.range.start()), 5);
+    }
+
+    #[test]
+    fn quiet_for_single_newline_empty_input_and_preserve() {
+        assert!(lint_with("text\n", FinalNewline::Ensure).is_empty());
+        assert!(lint_with("", FinalNewline::Ensure).is_empty());
+        assert!(lint_with("text", FinalNewline::Preserve).is_empty());
+    }
+}
@@ -1,6 +1,6 @@
 use crate::linter::diagnostics::{Diagnostic, Edit, Fix, Location};
 use crate::linter::rules::{DiagnosticCode, LintContext, Requirement, Rule, RuleMeta};
-use crate::syntax::{Heading, SyntaxNode};
+use crate::syntax::{Heading, SyntaxKind, SyntaxNode};
 use rowan::ast::AstNode;
 
 pub struct HeadingHierarchyRule;
@@ -22,44 +22,159 @@ impl Rule for HeadingHierarchyRule {
 
     fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
         let (tree, input, config) = (cx.tree, cx.input, cx.config);
-        let mut diagnostics = Vec::new();
-        let headings = collect_headings(tree, &config.extensions);
+        let options = &config.lint.heading_hierarchy;
+        let headings: Vec<HeadingEntry> = collect_headings(tree, &config.extensions)
+            .into_iter()
+            .filter_map(|(range, level)| {
+                let node = heading_node_at_range(tree, range);
+                if options.ignore_in_divs
+                    && node.as_ref().is_some_and(|node| {
+                        node.ancestors()
+                            .any(|ancestor| ancestor.kind() == SyntaxKind::FENCED_DIV)
+                    })
+                {
+                    return None;
+                }
+                Some(HeadingEntry { range, level, node })
+            })
+            .collect();
 
-        let mut prev_level: Option<usize> = None;
+        let mut diagnostics = Vec::new();
+        let mut skips: Vec<Skip> = Vec::new();
+        // Levels each heading would have once every skip fix is applied, and
+        // the skip whose fix rewrites it.
+        let mut fixed_levels: Vec<(usize, Option<usize>)> = Vec::with_capacity(headings.len());
+        // Open shift scopes: (original level, promotion, skip index). A skip
+        // promotes its heading and every following heading at or below its
+        // original level, so the section keeps its internal structure.
+        let mut scopes: Vec<(usize, usize, usize)> = Vec::new();
+        let mut prev: Option<(usize, usize)> = None;
+        let mut seen_h1 = false;
 
-        for (range, level) in headings {
-            if let Some(prev) = prev_level
-                && level > prev + 1
+        for heading in &headings {
+            let level = heading.level;
+            while scopes
+                .last()
+                .is_some_and(|&(scope_level, _, _)| level < scope_level)
             {
-                let location = Location::from_range(range, input);
-                let expected_level = prev + 1;
+                scopes.pop();
+            }
+            let shift: usize = scopes.iter().map(|&(_, shift, _)| shift).sum();
+            let mut fixed = level - shift;
 
-                let diagnostic = Diagnostic::warning(
-                    location,
-                    "heading-hierarchy",
-                    format!(
+            // The first heading is compared against a virtual parent one
+            // level above `start-level`, when configured.
+            let parent = match (prev, options.start_level) {
+                (Some((_, prev_fixed)), _) => Some(prev_fixed),
+                (None, Some(start)) => Some(start - 1),
+                (None, None) => None,
+            };
+            if let Some(parent) = parent
+                && fixed > parent + 1
+            {
+                let expected = parent + 1;
+                let message = match prev {
+                    Some((prev_level, _)) => format!(
                         "Heading level skipped from h{} to h{}; expected h{}",
-                        prev, level, expected_level
+                        prev_level, level, expected
                     ),
-                )
-                .with_fix({
-                    if let Some(node) = heading_node_at_range(tree, range) {
-                        create_fix(&node, level, expected_level)
-                    } else {
-                        Fix::safe("Could not create fix", vec![])
-                    }
+                    None => format!(
+                        "Document starts at h{}; headings should start at h{}",
+                        level, expected
+                    ),
+                };
+                scopes.push((level, fixed - expected, skips.len()));
+                skips.push(Skip {
+                    range: heading.range,
+                    level,
+                    message,
                 });
+                fixed = expected;
+            }
+
+            if let Some(start) = options.start_level
+                && level < start
+            {
+                diagnostics.push(Diagnostic::warning(
+                    Location::from_range(heading.range, input),
+                    "heading-hierarchy",
+                    format!("Heading h{level} is above the configured start level h{start}"),
+                ));
+            }
+            if level == 1 {
+                if seen_h1 && !options.allow_multiple_h1 {
+                    diagnostics.push(Diagnostic::warning(
+                        Location::from_range(heading.range, input),
+                        "heading-hierarchy",
+                        "Multiple h1 headings; only one top-level heading is allowed",
+                    ));
+                }
+                seen_h1 = true;
+            }
+
+            fixed_levels.push((fixed, scopes.last().map(|&(_, _, skip)| skip)));
+            prev = Some((level, fixed));
+        }
 
-                diagnostics.push(diagnostic);
+        for (index, skip) in skips.into_iter().enumerate() {
+            let mut edits = Vec::new();
+            let mut fixable = true;
+            for (heading, &(fixed, owner)) in headings.iter().zip(&fixed_levels) {
+                if owner != Some(index) || fixed == heading.level {
+                    continue;
+                }
+                match heading
+                    .node
+                    .as_ref()
+                    .and_then(|node| Heading::cast(node.clone()))
+                    .and_then(|heading| heading.atx_marker_range())
+                {
+                    Some(range) => edits.push(Edit {
+                        range,
+                        replacement: "#".repeat(fixed),
+                    }),
+                    // Setext headings can't be re-levelled by a marker edit.
+                    None => fixable = false,
+                }
             }
 
-            prev_level = Some(level);
+            let mut diagnostic = Diagnostic::warning(
+                Location::from_range(skip.range, input),
+                "heading-hierarchy",
+                skip.message,
+            );
+            if fixable && !edits.is_empty() {
+                let description = if edits.len() == 1 {
+                    format!(
+                        "Change heading level from {} to {}",
+                        skip.level,
+                        edits[0].replacement.len()
+                    )
+                } else {
+                    format!("Promote this section's {} headings", edits.len())
+                };
+                diagnostic = diagnostic.with_fix(Fix::safe(description, edits));
+            }
+            diagnostics.push(diagnostic);
         }
 
+        diagnostics.sort_by_key(|diagnostic| diagnostic.location.range.start());
         diagnostics
     }
 }
 
+struct HeadingEntry {
+    range: rowan::TextRange,
+    level: usize,
+    node: Option<SyntaxNode>,
+}
+
+struct Skip {
+    range: rowan::TextRange,
+    level: usize,
+    message: String,
+}
+
 fn collect_headings(
     tree: &SyntaxNode,
     extensions: &crate::config::Extensions,
@@ -77,30 +192,18 @@ fn heading_node_at_range(tree: &SyntaxNode, range: rowan::TextRange) -> Option<S
     })
 }
 
-fn create_fix(heading: &SyntaxNode, current_level: usize, expected_level: usize) -> Fix {
-    if let Some(heading) = Heading::cast(heading.clone())
-        && let Some(range) = heading.atx_marker_range()
-    {
-        let replacement = "#".repeat(expected_level);
-        return Fix::safe(
-            format!(
-                "Change heading level from {} to {}",
-                current_level, expected_level
-            ),
-            vec![Edit { range, replacement }],
-        );
-    }
-
-    Fix::safe("Could not create fix", vec![])
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::Config;
+    use crate::config::{Config, HeadingHierarchyOptions};
 
     fn parse_and_lint(input: &str) -> Vec<Diagnostic> {
-        let config = Config::default();
+        lint_with_options(input, HeadingHierarchyOptions::default())
+    }
+
+    fn lint_with_options(input: &str, options: HeadingHierarchyOptions) -> Vec<Diagnostic> {
+        let mut config = Config::default();
+        config.lint.heading_hierarchy = options;
         // Use main parse function which now includes inline parsing
         let tree = crate::parser::parse(input, Some(config.clone()));
 
@@ -173,4 +276,95 @@ mod tests {
         assert_eq!(diagnostics.len(), 1);
         assert!(diagnostics[0].message.contains("h1 to h3"));
     }
+
+    fn apply(input: &str, diagnostics: &[Diagnostic]) -> String {
+        let mut edits: Vec<&Edit> = diagnostics
+            .iter()
+            .filter_map(|d| d.fix.as_ref())
+            .flat_map(|fix| &fix.edits)
+            .collect();
+        edits.sort_by_key(|edit| std::cmp::Reverse(edit.range.start()));
+        let mut output = input.to_string();
+        for edit in edits {
+            output.replace_range(
+                usize::from(edit.range.start())..usize::from(edit.range.end()),
+                &edit.replacement,
+            );
+        }
+        output
+    }
+
+    #[test]
+    fn test_fix_promotes_whole_section() {
+        let input = "# A\n\n### B\n\n#### C\n\n### D\n\n## E\n";
+        let diagnostics = parse_and_lint(input);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            apply(input, &diagnostics),
+            "# A\n\n## B\n\n### C\n\n## D\n\n## E\n"
+        );
+    }
+
+    #[test]
+    fn test_nested_skips_fix_without_overlapping_edits() {
+        let input = "# A\n\n### B\n\n###### C\n\n###### D\n\n#### E\n";
+        let diagnostics = parse_and_lint(input);
+        assert_eq!(diagnostics.len(), 2);
+        let fixed = apply(input, &diagnostics);
+        assert_eq!(fixed, "# A\n\n## B\n\n### C\n\n### D\n\n### E\n");
+        assert!(parse_and_lint(&fixed).is_empty());
+    }
+
+    #[test]
+    fn test_multiple_h1_option() {
+        let input = "# One\n\n# Two\n";
+        assert!(parse_and_lint(input).is_empty());
+
+        let diagnostics = lint_with_options(
+            input,
+            HeadingHierarchyOptions {
+                allow_multiple_h1: false,
+                ..Default::default()
+            },
+        );
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("Multiple h1"));
+        assert_eq!(diagnostics[0].location.line, 3);
+    }
+
+    #[test]
+    fn test_start_level_option() {
+        let options = HeadingHierarchyOptions {
+            start_level: Some(2),
+            ..Default::default()
+        };
+
+        let diagnostics = lint_with_options("#### Deep\n\n##### Deeper\n", options.clone());
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("starts at h4"));
+        assert_eq!(
+            apply("#### Deep\n\n##### Deeper\n", &diagnostics),
+            "## Deep\n\n### Deeper\n"
+        );
+
+        let diagnostics = lint_with_options("## Section\n\n# Title\n", options);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("start level h2"));
+        assert!(diagnostics[0].fix.is_none());
+    }
+
+    #[test]
+    fn test_ignore_in_divs_option() {
+        let input = "# H1\n\n::: {.callout-note}\n#### Note\n:::\n\n## H2\n";
+        assert_eq!(parse_and_lint(input).len(), 1);
+
+        let diagnostics = lint_with_options(
+            input,
+            HeadingHierarchyOptions {
+                ignore_in_divs: true,
+                ..Default::default()
+            },
+        );
+        assert!(diagnostics.is_empty());
+    }
 }
@@ -0,0 +1,123 @@
+use unicode_width::UnicodeWidthStr;
+
+use crate::linter::diagnostics::{Diagnostic, Location};
+use crate::linter::rules::{DiagnosticCode, LintContext, Requirement, Rule, RuleMeta};
+use crate::syntax::{Heading, SyntaxKind, SyntaxNode};
+use rowan::ast::AstNode;
+
+/// Flags headings whose text is wider than `max-length` display columns.
+///
+/// The formatter never wraps headings, so a long heading is the one line that
+/// `line-width` cannot keep in check; the limit defaults to that width.
+pub struct HeadingLengthRule;
+
+impl Rule for HeadingLengthRule {
+    fn name(&self) -> &str {
+        "heading-length"
+    }
+
+    fn metadata(&self) -> RuleMeta {
+        RuleMeta {
+            name: "heading-length",
+            default_on: false,
+            requires: Requirement::Always,
+            auto_fix: false,
+            codes: const { &[DiagnosticCode::warning("heading-length")] },
+        }
+    }
+
+    fn node_interests(&self) -> &'static [SyntaxKind] {
+        &[SyntaxKind::HEADING]
+    }
+
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        let max_length = cx
+            .config
+            .lint
+            .heading_length
+            .max_length
+            .unwrap_or(cx.config.line_width);
+
+        let mut diagnostics = Vec::new();
+        for heading in cx
+            .nodes(SyntaxKind::HEADING)
+            .iter()
+            .cloned()
+            .filter_map(Heading::cast)
+        {
+            let Some(content) = heading.content() else {
+                continue;
+            };
+            let length = rendered_text(content.syntax()).trim().width();
+            if length <= max_length {
+                continue;
+            }
+
+            diagnostics.push(Diagnostic::warning(
+                Location::from_range(content.syntax().text_range(), cx.input),
+                "heading-length",
+                format!("Heading is {length} columns long; keep it within {max_length}"),
+            ));
+        }
+        diagnostics
+    }
+}
+
+/// The text of heading `content` as it renders: link text without the link
+/// destination or reference label, and code spans without their backticks.
+fn rendered_text(content: &SyntaxNode) -> String {
+    content
+        .descendants_with_tokens()
+        .filter_map(|element| element.into_token())
+        .filter(|token| {
+            matches!(
+                token.kind(),
+                SyntaxKind::TEXT
+                    | SyntaxKind::INLINE_CODE_CONTENT
+                    | SyntaxKind::INLINE_EXEC_CONTENT
+            ) && !token
+                .parent_ancestors()
+                .any(|node| matches!(node.kind(), SyntaxKind::LINK_DEST | SyntaxKind::LINK_REF))
+        })
+        .map(|token| token.text().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Config, HeadingLengthOptions};
+
+    fn lint_with(input: &str, max_length: Option<usize>) -> Vec<Diagnostic> {
+        let mut config = Config {
+            line_width: 20,
+            ..Default::default()
+        };
+        config.lint.heading_length = HeadingLengthOptions { max_length };
+        let tree = crate::parser::parse(input, Some(config.clone()));
+        HeadingLengthRule.check_tree(&tree, input, &config, None)
+    }
+
+    #[test]
+    fn defaults_to_line_width() {
+        let input = "# Short heading\n\n## A heading that runs past twenty\n";
+        let diagnostics = lint_with(input, None);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "heading-length");
+        assert_eq!(diagnostics[0].location.line, 3);
+        assert!(diagnostics[0].message.contains("within 20"));
+    }
+
+    #[test]
+    fn honors_max_length_option() {
+        let input = "# Short heading\n";
+        assert_eq!(lint_with(input, Some(10)).len(), 1);
+        assert!(lint_with(input, Some(13)).is_empty());
+    }
+
+    #[test]
+    fn measures_rendered_text_not_link_destinations() {
+        let input = "# [Docs](https://example.com/a/very/long/path)\n";
+        assert!(lint_with(input, None).is_empty());
+    }
+}
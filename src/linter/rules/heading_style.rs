@@ -0,0 +1,412 @@
+//! Heading style: trailing punctuation (markdownlint MD026) and capitalization.
+//!
+//! - [`HeadingPunctuationRule`] (`heading-punctuation`) flags headings ending
+//!   in punctuation such as a period or colon.
+//! - [`HeadingCaseRule`] (`heading-case`) flags headings not in the configured
+//!   sentence or title case.
+//!
+//! Both only look at the text of the heading: code spans, math, link
+//! destinations, attributes, and citations are left as written.
+
+use rowan::{TextRange, TextSize};
+
+use crate::config::HeadingCase;
+use crate::linter::diagnostics::{Diagnostic, Edit, Fix, Location};
+use crate::linter::rules::{DiagnosticCode, LintContext, Requirement, Rule, RuleMeta};
+use crate::syntax::{AstNode, Heading, HeadingContent, SyntaxKind, SyntaxToken};
+
+/// Flags headings that end in punctuation (MD026).
+pub struct HeadingPunctuationRule;
+
+/// Flags headings not in the configured sentence or title case.
+pub struct HeadingCaseRule;
+
+impl Rule for HeadingPunctuationRule {
+    fn name(&self) -> &str {
+        "heading-punctuation"
+    }
+
+    fn metadata(&self) -> RuleMeta {
+        RuleMeta {
+            name: "heading-punctuation",
+            default_on: false,
+            requires: Requirement::Always,
+            auto_fix: true,
+            codes: const { &[DiagnosticCode::warning("heading-punctuation")] },
+        }
+    }
+
+    fn node_interests(&self) -> &'static [SyntaxKind] {
+        &[SyntaxKind::HEADING]
+    }
+
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        let punctuation = &cx.config.lint.heading_punctuation.punctuation;
+        let mut diagnostics = Vec::new();
+        for content in headings(cx) {
+            // A heading ending in code, math, or a link ends in that, not in
+            // punctuation of its own.
+            let Some(token) = content
+                .syntax()
+                .descendants_with_tokens()
+                .filter_map(|element| element.into_token())
+                .filter(|token| !token.text().trim().is_empty())
+                .last()
+            else {
+                continue;
+            };
+            if token.kind() != SyntaxKind::TEXT || is_excluded(&token) {
+                continue;
+            }
+            let text = token.text().trim_end();
+            let kept = text.trim_end_matches(|c| punctuation.contains(c));
+            if kept.len() == text.len() || kept.trim().is_empty() || ends_with_entity(text) {
+                continue;
+            }
+
+            let start = token.text_range().start() + TextSize::of(kept);
+            let range = TextRange::at(start, TextSize::of(&text[kept.len()..]));
+            diagnostics.push(
+                Diagnostic::warning(
+                    Location::from_range(range, cx.input),
+                    "heading-punctuation",
+                    format!("Heading ends with punctuation '{}'", &text[kept.len()..]),
+                )
+                .with_fix(Fix::safe(
+                    "Remove trailing punctuation",
+                    vec![Edit {
+                        range,
+                        replacement: String::new(),
+                    }],
+                )),
+            );
+        }
+        diagnostics
+    }
+}
+
+impl Rule for HeadingCaseRule {
+    fn name(&self) -> &str {
+        "heading-case"
+    }
+
+    fn metadata(&self) -> RuleMeta {
+        RuleMeta {
+            name: "heading-case",
+            default_on: false,
+            requires: Requirement::Always,
+            auto_fix: true,
+            codes: const { &[DiagnosticCode::warning("heading-case")] },
+        }
+    }
+
+    fn node_interests(&self) -> &'static [SyntaxKind] {
+        &[SyntaxKind::HEADING]
+    }
+
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        let options = &cx.config.lint.heading_case;
+        let style = match options.style {
+            HeadingCase::Sentence => "sentence",
+            HeadingCase::Title => "title",
+        };
+        let kept: Vec<String> = options
+            .words
+            .iter()
+            .map(|word| word.to_lowercase())
+            .collect();
+
+        let mut diagnostics = Vec::new();
+        for content in headings(cx) {
+            let words = heading_words(&content);
+            let last = words.len().saturating_sub(1);
+            let edits: Vec<Edit> = words
+                .iter()
+                .enumerate()
+                .filter(|(_, word)| word.judged && !kept.contains(&word.text.to_lowercase()))
+                .filter_map(|(idx, word)| {
+                    let capitalize = expected_capital(options.style, word, idx == last)?;
+                    recase(word, capitalize)
+                })
+                .collect();
+            if edits.is_empty() {
+                continue;
+            }
+
+            diagnostics.push(
+                Diagnostic::warning(
+                    Location::from_range(content.syntax().text_range(), cx.input),
+                    "heading-case",
+                    format!("Heading is not in {style} case"),
+                )
+                .with_fix(Fix::unsafe_fix(
+                    format!("Convert heading to {style} case"),
+                    edits,
+                )),
+            );
+        }
+        diagnostics
+    }
+}
+
+fn headings<'a>(cx: &'a LintContext) -> impl Iterator<Item = HeadingContent> + 'a {
+    cx.nodes(SyntaxKind::HEADING)
+        .iter()
+        .cloned()
+        .filter_map(Heading::cast)
+        .filter_map(|heading| heading.content())
+}
+
+/// Inline nodes inside a heading whose text is not prose.
+const EXCLUDED_ANCESTOR_KINDS: &[SyntaxKind] = &[
+    SyntaxKind::INLINE_MATH,
+    SyntaxKind::DISPLAY_MATH,
+    SyntaxKind::RAW_INLINE,
+    SyntaxKind::INLINE_HTML,
+    SyntaxKind::LATEX_COMMAND,
+    SyntaxKind::AUTO_LINK,
+    SyntaxKind::LINK_DEST,
+    SyntaxKind::LINK_REF,
+    SyntaxKind::ATTRIBUTE,
+    SyntaxKind::SPAN_ATTRIBUTES,
+    SyntaxKind::CITATION,
+    SyntaxKind::CROSSREF,
+    SyntaxKind::SHORTCODE,
+    SyntaxKind::EMOJI,
+    SyntaxKind::FOOTNOTE_REFERENCE,
+    SyntaxKind::INLINE_FOOTNOTE,
+];
+
+fn is_excluded(token: &SyntaxToken) -> bool {
+    token
+        .parent_ancestors()
+        .take_while(|node| node.kind() != SyntaxKind::HEADING_CONTENT)
+        .any(|node| EXCLUDED_ANCESTOR_KINDS.contains(&node.kind()))
+}
+
+/// Whether `text` ends in an HTML entity such as `&amp;`, whose `;` is not
+/// punctuation.
+fn ends_with_entity(text: &str) -> bool {
+    text.strip_suffix(';')
+        .and_then(|rest| rest.rfind('&').map(|amp| &rest[amp + 1..]))
+        .is_some_and(|name| {
+            !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '#')
+        })
+}
+
+/// Where a word sits in its heading, which decides its expected case.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Position {
+    /// The first word of the heading.
+    First,
+    /// The first word after a colon, which may start a subtitle.
+    AfterColon,
+    /// A part of a hyphenated compound after its first hyphen.
+    AfterHyphen,
+    Inner,
+}
+
+#[derive(Debug)]
+struct Word {
+    range: TextRange,
+    text: String,
+    position: Position,
+    /// Whether the case of the word can be judged: plain letters with at most
+    /// a capital first letter, longer than one letter unless it starts the
+    /// heading. Acronyms, mixed-case names like `macOS`, words with digits or
+    /// inner dots, code spans, and math are kept as written.
+    judged: bool,
+}
+
+/// The words of a heading, in order.
+fn heading_words(content: &HeadingContent) -> Vec<Word> {
+    let mut words = Vec::new();
+    let mut position = Position::First;
+    for token in content
+        .syntax()
+        .descendants_with_tokens()
+        .filter_map(|element| element.into_token())
+    {
+        if token.kind() != SyntaxKind::TEXT || is_excluded(&token) {
+            if token.text().chars().any(char::is_alphanumeric) {
+                words.push(Word {
+                    range: token.text_range(),
+                    text: token.text().to_string(),
+                    position,
+                    judged: false,
+                });
+                position = Position::Inner;
+            }
+            continue;
+        }
+
+        let token_start = token.text_range().start();
+        let text = token.text();
+        let mut chunk_start = 0;
+        for chunk in text.split_inclusive(char::is_whitespace) {
+            let offset = chunk_start;
+            chunk_start += chunk.len();
+            let trimmed = chunk.trim_end();
+            let core_start = trimmed.find(char::is_alphanumeric).unwrap_or(trimmed.len());
+            let core = trimmed[core_start..].trim_end_matches(|c: char| !c.is_alphanumeric());
+            if core.is_empty() {
+                continue;
+            }
+
+            let mut part_start = offset + core_start;
+            for (idx, part) in core.split('-').enumerate() {
+                let part_position = if idx == 0 {
+                    position
+                } else {
+                    Position::AfterHyphen
+                };
+                words.push(Word {
+                    range: TextRange::at(
+                        token_start + TextSize::from(part_start as u32),
+                        TextSize::of(part),
+                    ),
+                    text: part.to_string(),
+                    position: part_position,
+                    judged: is_judgeable(part, part_position),
+                });
+                part_start += part.len() + 1;
+            }
+            position = if trimmed.ends_with(':') {
+                Position::AfterColon
+            } else {
+                Position::Inner
+            };
+        }
+    }
+    words
+}
+
+fn is_judgeable(word: &str, position: Position) -> bool {
+    let mut chars = word.chars();
+    let Some(first) = chars.next() else {
+        return false;
+    };
+    let rest: Vec<char> = chars.collect();
+    first.is_alphabetic()
+        && (position == Position::First || !rest.is_empty())
+        && rest
+            .iter()
+            .all(|c| c.is_lowercase() || *c == '\'' || *c == '\u{2019}')
+}
+
+/// Short articles, conjunctions, and prepositions that title case leaves in
+/// lower case inside a heading.
+const MINOR_WORDS: &[&str] = &[
+    "a", "an", "and", "as", "at", "but", "by", "for", "from", "in", "into", "nor", "of", "on",
+    "or", "per", "the", "to", "via", "vs", "with", "yet",
+];
+
+/// Whether `word` should start with a capital letter, or `None` when either
+/// case is fine.
+fn expected_capital(style: HeadingCase, word: &Word, is_last: bool) -> Option<bool> {
+    match (style, word.position) {
+        (_, Position::First) => Some(true),
+        (HeadingCase::Sentence, Position::AfterColon) => None,
+        (HeadingCase::Sentence, _) => Some(false),
+        (HeadingCase::Title, Position::AfterColon) => Some(true),
+        (HeadingCase::Title, _) => {
+            let minor = MINOR_WORDS.contains(&word.text.to_lowercase().as_str());
+            Some(!minor || (is_last && word.position == Position::Inner))
+        }
+    }
+}
+
+/// The edit giving `word` a capital or lower-case first letter, if it needs
+/// one.
+fn recase(word: &Word, capitalize: bool) -> Option<Edit> {
+    let first = word.text.chars().next()?;
+    if first.is_uppercase() == capitalize {
+        return None;
+    }
+    let replacement: String = if capitalize {
+        first.to_uppercase().collect()
+    } else {
+        first.to_lowercase().collect()
+    };
+    Some(Edit {
+        range: TextRange::at(word.range.start(), TextSize::of(first)),
+        replacement,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Config, HeadingCaseOptions};
+
+    fn lint(rule: &dyn Rule, input: &str, config: &Config) -> Vec<Diagnostic> {
+        let tree = crate::parser::parse(input, Some(config.clone()));
+        rule.check_tree(&tree, input, config, None)
+    }
+
+    fn fixed(input: &str, diagnostics: &[Diagnostic]) -> String {
+        let mut edits: Vec<&Edit> = diagnostics
+            .iter()
+            .flat_map(|diagnostic| &diagnostic.fix.as_ref().expect("autofix").edits)
+            .collect();
+        edits.sort_by_key(|edit| edit.range.start());
+        let mut output = input.to_string();
+        for edit in edits.iter().rev() {
+            let range = usize::from(edit.range.start())..usize::from(edit.range.end());
+            output.replace_range(range, &edit.replacement);
+        }
+        output
+    }
+
+    fn case_config(style: HeadingCase, words: &[&str]) -> Config {
+        let mut config = Config::default();
+        config.lint.heading_case = HeadingCaseOptions {
+            style,
+            words: words.iter().map(|word| word.to_string()).collect(),
+        };
+        config
+    }
+
+    #[test]
+    fn flags_and_removes_trailing_punctuation() {
+        let input = "# Introduction:\n\n## Why? {#why}\n\n### Setup.\n";
+        let diagnostics = lint(&HeadingPunctuationRule, input, &Config::default());
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].message, "Heading ends with punctuation ':'");
+        assert_eq!(
+            fixed(input, &diagnostics),
+            "# Introduction\n\n## Why? {#why}\n\n### Setup\n"
+        );
+    }
+
+    #[test]
+    fn punctuation_ignores_code_and_entities() {
+        let input = "# Calling `f();`\n\n## Q&amp;\n";
+        assert!(lint(&HeadingPunctuationRule, input, &Config::default()).is_empty());
+    }
+
+    #[test]
+    fn sentence_case_lowers_inner_words() {
+        let config = case_config(HeadingCase::Sentence, &["Quarto"]);
+        let input = "# Getting Started with Quarto\n\n## Using the `Foo` API: An overview\n";
+        let diagnostics = lint(&HeadingCaseRule, input, &config);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "Heading is not in sentence case");
+        assert_eq!(
+            fixed(input, &diagnostics),
+            "# Getting started with Quarto\n\n## Using the `Foo` API: An overview\n"
+        );
+    }
+
+    #[test]
+    fn title_case_capitalizes_all_but_minor_words() {
+        let config = case_config(HeadingCase::Title, &[]);
+        let input = "# the state of the art in well-known tools to rely on\n";
+        let diagnostics = lint(&HeadingCaseRule, input, &config);
+        assert_eq!(
+            fixed(input, &diagnostics),
+            "# The State of the Art in Well-Known Tools to Rely On\n"
+        );
+        assert!(lint(&HeadingCaseRule, "# Notes on macOS and iOS\n", &config).is_empty());
+    }
+}
@@ -0,0 +1,136 @@
+use rowan::{TextRange, TextSize};
+
+use crate::config::LineEnding;
+use crate::linter::diagnostics::{Diagnostic, Edit, Fix, Location};
+use crate::linter::rules::{DiagnosticCode, LintContext, Requirement, Rule, RuleMeta};
+
+/// Flags lines whose ending differs from the rest of a document that mixes
+/// LF and CRLF.
+///
+/// The expected ending is `line-ending` when that is `lf` or `crlf`, and the
+/// first ending in the document under `auto`, which is the one the formatter
+/// writes. A document that uses one ending throughout is left alone even if
+/// it differs from `line-ending`: that is a formatting change, not a mix.
+pub struct MixedLineEndingsRule;
+
+impl Rule for MixedLineEndingsRule {
+    fn name(&self) -> &str {
+        "mixed-line-endings"
+    }
+
+    fn metadata(&self) -> RuleMeta {
+        RuleMeta {
+            name: "mixed-line-endings",
+            default_on: true,
+            requires: Requirement::Always,
+            auto_fix: true,
+            codes: const { &[DiagnosticCode::warning("mixed-line-endings")] },
+        }
+    }
+
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        let input = cx.input;
+        let endings = line_endings(input);
+        let has_crlf = endings.iter().any(|&(_, crlf)| crlf);
+        let has_lf = endings.iter().any(|&(_, crlf)| !crlf);
+        if !(has_crlf && has_lf) {
+            return Vec::new();
+        }
+
+        let expect_crlf = match cx.config.line_ending {
+            Some(LineEnding::Lf) => false,
+            Some(LineEnding::Crlf) => true,
+            Some(LineEnding::Auto) | None => endings[0].1,
+        };
+        let (expected, replacement) = if expect_crlf {
+            ("CRLF", "\r\n")
+        } else {
+            ("LF", "\n")
+        };
+
+        endings
+            .into_iter()
+            .filter(|&(_, crlf)| crlf != expect_crlf)
+            .map(|(start, crlf)| {
+                let len = if crlf { 2 } else { 1 };
+                let range = TextRange::new(
+                    TextSize::from(start as u32),
+                    TextSize::from((start + len) as u32),
+                );
+                let found = if crlf { "CRLF" } else { "LF" };
+                Diagnostic::warning(
+                    Location::from_range(range, input),
+                    "mixed-line-endings",
+                    format!("Line ends with {found}; expected {expected}"),
+                )
+                .with_fix(Fix::safe(
+                    format!("Use {expected}"),
+                    vec![Edit {
+                        range,
+                        replacement: replacement.to_string(),
+                    }],
+                ))
+            })
+            .collect()
+    }
+}
+
+/// Byte offset of every line ending in `input`, and whether it is CRLF.
+fn line_endings(input: &str) -> Vec<(usize, bool)> {
+    let bytes = input.as_bytes();
+    input
+        .match_indices('\n')
+        .map(|(idx, _)| {
+            if idx > 0 && bytes[idx - 1] == b'\r' {
+                (idx - 1, true)
+            } else {
+                (idx, false)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn lint_with(input: &str, line_ending: Option<LineEnding>) -> Vec<Diagnostic> {
+        let config = Config {
+            line_ending,
+            ..Default::default()
+        };
+        let tree = crate::parser::parse(input, Some(config.clone()));
+        MixedLineEndingsRule.check_tree(&tree, input, &config, None)
+    }
+
+    #[test]
+    fn flags_endings_that_differ_from_the_first() {
+        let input = "one\r\ntwo\nthree\r\n";
+        let diagnostics = lint_with(input, Some(LineEnding::Auto));
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "mixed-line-endings");
+        assert_eq!(diagnostics[0].location.line, 2);
+        let fix = diagnostics[0].fix.as_ref().expect("autofix");
+        assert_eq!(fix.edits[0].replacement, "\r\n");
+    }
+
+    #[test]
+    fn configured_ending_decides_which_lines_are_flagged() {
+        let input = "one\r\ntwo\nthree\r\n";
+        let diagnostics = lint_with(input, Some(LineEnding::Lf));
+        assert_eq!(diagnostics.len(), 2);
+        assert!(
+            diagnostics
+                .iter()
+                .all(|d| d.message.contains("expected LF"))
+        );
+    }
+
+    #[test]
+    fn uniform_endings_are_not_flagged() {
+        assert!(lint_with("one\r\ntwo\r\n", Some(LineEnding::Lf)).is_empty());
+        assert!(lint_with("one\ntwo\n", Some(LineEnding::Crlf)).is_empty());
+        assert!(lint_with("one\ntwo\n", None).is_empty());
+    }
+}
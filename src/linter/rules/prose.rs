@@ -0,0 +1,385 @@
+//! Typos in the spacing and wording of prose.
+//!
+//! Three rules scan the `TEXT` tokens of prose, each with its own name so it
+//! can be toggled independently in `[lint.rules]`:
+//!
+//! - [`DuplicateWordsRule`] (`duplicate-words`) for a word repeated right
+//!   after itself ("the the").
+//! - [`MultipleSpacesRule`] (`multiple-spaces`) for runs of spaces between
+//!   words.
+//! - [`SpaceBeforePunctuationRule`] (`space-before-punctuation`) for a space
+//!   before a comma, period, or other punctuation mark.
+//!
+//! Code, math, raw HTML and TeX, URLs, attributes, and YAML are not prose and
+//! are skipped. Alignment in tables and line blocks is deliberate, so
+//! `multiple-spaces` skips those too.
+
+use rowan::{NodeOrToken, TextRange, TextSize};
+
+use crate::linter::diagnostics::{Diagnostic, Edit, Fix, Location};
+use crate::linter::rules::{DiagnosticCode, LintContext, Requirement, Rule, RuleMeta};
+use crate::syntax::{SyntaxKind, SyntaxToken};
+
+/// Flags a word repeated right after itself.
+pub struct DuplicateWordsRule;
+
+/// Flags runs of more than one space between words.
+pub struct MultipleSpacesRule;
+
+/// Flags spaces before a punctuation mark.
+pub struct SpaceBeforePunctuationRule;
+
+impl Rule for DuplicateWordsRule {
+    fn name(&self) -> &str {
+        "duplicate-words"
+    }
+
+    fn metadata(&self) -> RuleMeta {
+        RuleMeta {
+            name: "duplicate-words",
+            default_on: false,
+            requires: Requirement::Always,
+            auto_fix: true,
+            codes: const { &[DiagnosticCode::warning("duplicate-words")] },
+        }
+    }
+
+    fn wants_text_tokens(&self) -> bool {
+        true
+    }
+
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        for token in prose_tokens(cx, &[]) {
+            let mut words = word_ranges(&token);
+            // A repeat can straddle a line break, which ends the TEXT token;
+            // pairs inside the previous token were checked with it.
+            if let Some(previous) = previous_text(&token)
+                && let Some(last) = word_ranges(&previous).pop()
+            {
+                words.insert(0, last);
+            }
+
+            for pair in words.windows(2) {
+                let &[first, second] = pair else {
+                    continue;
+                };
+                let first_word = &cx.input[first];
+                let second_word = &cx.input[second];
+                let between = &cx.input[TextRange::new(first.end(), second.start())];
+                if first_word.to_lowercase() != second_word.to_lowercase()
+                    || between.is_empty()
+                    || !between.chars().all(char::is_whitespace)
+                {
+                    continue;
+                }
+                diagnostics.push(
+                    Diagnostic::warning(
+                        Location::from_range(TextRange::new(first.start(), second.end()), cx.input),
+                        "duplicate-words",
+                        format!("Word '{second_word}' is repeated"),
+                    )
+                    .with_fix(Fix::unsafe_fix(
+                        "Remove the repeated word",
+                        vec![Edit {
+                            range: TextRange::new(first.end(), second.end()),
+                            replacement: String::new(),
+                        }],
+                    )),
+                );
+            }
+        }
+        diagnostics
+    }
+}
+
+impl Rule for MultipleSpacesRule {
+    fn name(&self) -> &str {
+        "multiple-spaces"
+    }
+
+    fn metadata(&self) -> RuleMeta {
+        RuleMeta {
+            name: "multiple-spaces",
+            default_on: false,
+            requires: Requirement::Always,
+            auto_fix: true,
+            codes: const { &[DiagnosticCode::warning("multiple-spaces")] },
+        }
+    }
+
+    fn wants_text_tokens(&self) -> bool {
+        true
+    }
+
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        for token in prose_tokens(cx, ALIGNED_KINDS) {
+            let text = token.text();
+            let start = token.text_range().start();
+            let mut search = 0;
+            while let Some(found) = text[search..].find("  ") {
+                let run_start = search + found;
+                let run_end = text[run_start..]
+                    .find(|c| c != ' ')
+                    .map_or(text.len(), |idx| run_start + idx);
+                search = run_end;
+                // Leading spaces indent and trailing ones break lines.
+                if run_start == 0 || run_end == text.len() {
+                    continue;
+                }
+                let range = TextRange::new(
+                    start + TextSize::from(run_start as u32),
+                    start + TextSize::from(run_end as u32),
+                );
+                diagnostics.push(
+                    Diagnostic::warning(
+                        Location::from_range(range, cx.input),
+                        "multiple-spaces",
+                        format!("{} spaces between words", run_end - run_start),
+                    )
+                    .with_fix(Fix::safe(
+                        "Replace with a single space",
+                        vec![Edit {
+                            range,
+                            replacement: " ".to_string(),
+                        }],
+                    )),
+                );
+            }
+        }
+        diagnostics
+    }
+}
+
+impl Rule for SpaceBeforePunctuationRule {
+    fn name(&self) -> &str {
+        "space-before-punctuation"
+    }
+
+    fn metadata(&self) -> RuleMeta {
+        RuleMeta {
+            name: "space-before-punctuation",
+            default_on: false,
+            requires: Requirement::Always,
+            auto_fix: true,
+            codes: const { &[DiagnosticCode::warning("space-before-punctuation")] },
+        }
+    }
+
+    fn wants_text_tokens(&self) -> bool {
+        true
+    }
+
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        for token in prose_tokens(cx, &[]) {
+            let text = token.text();
+            let start = token.text_range().start();
+            for (idx, mark) in text.char_indices() {
+                if !matches!(mark, ',' | '.' | ';' | ':' | '!' | '?') {
+                    continue;
+                }
+                // `.5`, `...`, `:)`, and `.gitignore` are not punctuation
+                // ending a word.
+                let after = &text[idx + 1..];
+                if !after.chars().next().is_none_or(char::is_whitespace) {
+                    continue;
+                }
+                let before = &text[..idx];
+                let word_end = before.trim_end_matches(' ').len();
+                if word_end == before.len() || !before[..word_end].ends_with(char::is_alphanumeric)
+                {
+                    continue;
+                }
+                let range = TextRange::new(
+                    start + TextSize::from(word_end as u32),
+                    start + TextSize::from(idx as u32),
+                );
+                diagnostics.push(
+                    Diagnostic::warning(
+                        Location::from_range(range, cx.input),
+                        "space-before-punctuation",
+                        format!("Space before '{mark}'"),
+                    )
+                    .with_fix(Fix::safe(
+                        "Remove the space",
+                        vec![Edit {
+                            range,
+                            replacement: String::new(),
+                        }],
+                    )),
+                );
+            }
+        }
+        diagnostics
+    }
+}
+
+/// Nodes whose text is not prose.
+const NON_PROSE_KINDS: &[SyntaxKind] = &[
+    SyntaxKind::INLINE_CODE,
+    SyntaxKind::INLINE_EXEC,
+    SyntaxKind::CODE_BLOCK,
+    SyntaxKind::MYST_DIRECTIVE_BODY,
+    SyntaxKind::INLINE_MATH,
+    SyntaxKind::DISPLAY_MATH,
+    SyntaxKind::MATH_CONTENT,
+    SyntaxKind::TEX_BLOCK,
+    SyntaxKind::LATEX_COMMAND,
+    SyntaxKind::RAW_INLINE,
+    SyntaxKind::INLINE_HTML,
+    SyntaxKind::HTML_BLOCK,
+    SyntaxKind::COMMENT,
+    SyntaxKind::AUTO_LINK,
+    SyntaxKind::LINK_DEST,
+    SyntaxKind::LINK_REF,
+    SyntaxKind::REFERENCE_DEFINITION,
+    SyntaxKind::ATTRIBUTE,
+    SyntaxKind::SPAN_ATTRIBUTES,
+    SyntaxKind::CITATION,
+    SyntaxKind::CROSSREF,
+    SyntaxKind::SHORTCODE,
+    SyntaxKind::CODE_INFO,
+    SyntaxKind::CHUNK_OPTIONS,
+    SyntaxKind::YAML_METADATA,
+    SyntaxKind::MMD_TITLE_BLOCK,
+];
+
+/// Blocks whose spacing lines things up.
+const ALIGNED_KINDS: &[SyntaxKind] = &[
+    SyntaxKind::SIMPLE_TABLE,
+    SyntaxKind::MULTILINE_TABLE,
+    SyntaxKind::PIPE_TABLE,
+    SyntaxKind::GRID_TABLE,
+    SyntaxKind::LINE_BLOCK,
+];
+
+/// The `TEXT` tokens of prose, leaving out those under `skip` (and always
+/// those under [`NON_PROSE_KINDS`]).
+fn prose_tokens<'a>(
+    cx: &'a LintContext,
+    skip: &'static [SyntaxKind],
+) -> impl Iterator<Item = SyntaxToken> + 'a {
+    cx.text_tokens()
+        .iter()
+        .filter(move |token| {
+            !token
+                .parent_ancestors()
+                .any(|node| NON_PROSE_KINDS.contains(&node.kind()) || skip.contains(&node.kind()))
+        })
+        .cloned()
+}
+
+/// The ranges of the words in `token`: runs of letters and inner apostrophes.
+/// Numbers are not words here.
+fn word_ranges(token: &SyntaxToken) -> Vec<TextRange> {
+    let text = token.text();
+    let token_start = token.text_range().start();
+    let mut words = Vec::new();
+    let mut start = None;
+    for (idx, ch) in text.char_indices().chain([(text.len(), ' ')]) {
+        let in_word = ch.is_alphabetic() || (matches!(ch, '\'' | '\u{2019}') && start.is_some());
+        match (start, in_word) {
+            (None, true) => start = Some(idx),
+            (Some(word_start), false) => {
+                let word = text[word_start..idx].trim_end_matches(['\'', '\u{2019}']);
+                words.push(TextRange::at(
+                    token_start + TextSize::from(word_start as u32),
+                    TextSize::of(word),
+                ));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    words
+}
+
+/// The `TEXT` token before `token` when only a line break or spaces separate
+/// them.
+fn previous_text(token: &SyntaxToken) -> Option<SyntaxToken> {
+    let mut element = token.prev_sibling_or_token();
+    while let Some(NodeOrToken::Token(previous)) = element {
+        match previous.kind() {
+            SyntaxKind::TEXT => return Some(previous),
+            SyntaxKind::NEWLINE | SyntaxKind::WHITESPACE => {
+                element = previous.prev_sibling_or_token();
+            }
+            _ => return None,
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn lint(rule: &dyn Rule, input: &str) -> Vec<Diagnostic> {
+        let config = Config::default();
+        let tree = crate::parser::parse(input, Some(config.clone()));
+        rule.check_tree(&tree, input, &config, None)
+    }
+
+    fn fixed(input: &str, diagnostics: &[Diagnostic]) -> String {
+        let mut edits: Vec<&Edit> = diagnostics
+            .iter()
+            .flat_map(|diagnostic| &diagnostic.fix.as_ref().expect("autofix").edits)
+            .collect();
+        edits.sort_by_key(|edit| edit.range.start());
+        let mut output = input.to_string();
+        for edit in edits.iter().rev() {
+            let range = usize::from(edit.range.start())..usize::from(edit.range.end());
+            output.replace_range(range, &edit.replacement);
+        }
+        output
+    }
+
+    #[test]
+    fn flags_repeated_words_across_line_breaks() {
+        let input = "This is the the end of\nof the line, and And so on.\n";
+        let diagnostics = lint(&DuplicateWordsRule, input);
+        let messages: Vec<&str> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            [
+                "Word 'the' is repeated",
+                "Word 'of' is repeated",
+                "Word 'And' is repeated"
+            ]
+        );
+        assert_eq!(
+            fixed(input, &diagnostics),
+            "This is the end of the line, and so on.\n"
+        );
+    }
+
+    #[test]
+    fn repeated_words_need_only_whitespace_between() {
+        assert!(lint(&DuplicateWordsRule, "It is, is it? Use `x x` here.\n").is_empty());
+    }
+
+    #[test]
+    fn flags_space_runs_between_words_only() {
+        let input = "One  two.   Three `a  b`\n\n| a  | b |\n|----|---|\n| c  | d |\n";
+        let diagnostics = lint(&MultipleSpacesRule, input);
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(
+            fixed(input, &diagnostics),
+            "One two. Three `a  b`\n\n| a  | b |\n|----|---|\n| c  | d |\n"
+        );
+    }
+
+    #[test]
+    fn flags_space_before_punctuation() {
+        let input = "Wait , what ? Pi is .5 and ... fine :) see .gitignore.\n";
+        let diagnostics = lint(&SpaceBeforePunctuationRule, input);
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(
+            fixed(input, &diagnostics),
+            "Wait, what? Pi is .5 and ... fine :) see .gitignore.\n"
+        );
+    }
+}
@@ -0,0 +1,282 @@
+//! Spell checking of prose (`spelling`, behind the `spellcheck` feature).
+//!
+//! Only `TEXT` tokens outside code, math, raw HTML/TeX, URLs, attributes,
+//! citations, shortcodes, and YAML are checked, so the checker never sees
+//! identifiers or LaTeX commands the way a file-based spell checker does.
+//! Words are handed to `hunspell -l` or `aspell list` in one batch; whatever it
+//! echoes back is unknown.
+
+use std::collections::{BTreeSet, HashSet};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use rowan::{TextRange, TextSize};
+
+use crate::config::{SpellChecker, SpellingOptions};
+use crate::linter::diagnostics::{Diagnostic, Location};
+use crate::linter::rules::{DiagnosticCode, LintContext, Requirement, Rule, RuleMeta};
+use crate::syntax::{SyntaxKind, SyntaxToken};
+
+/// Flags words the configured spell checker does not know.
+pub struct SpellingRule;
+
+impl Rule for SpellingRule {
+    fn name(&self) -> &str {
+        "spelling"
+    }
+
+    fn metadata(&self) -> RuleMeta {
+        RuleMeta {
+            name: "spelling",
+            default_on: false,
+            requires: Requirement::Always,
+            auto_fix: false,
+            codes: const { &[DiagnosticCode::warning("spelling")] },
+        }
+    }
+
+    fn wants_text_tokens(&self) -> bool {
+        true
+    }
+
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        let options = &cx.config.lint.spelling;
+        let project_words: HashSet<String> = options
+            .words
+            .iter()
+            .map(|word| word.to_lowercase())
+            .collect();
+
+        let mut words = Vec::new();
+        for token in cx.text_tokens() {
+            if has_excluded_ancestor(token) {
+                continue;
+            }
+            let token_start = token.text_range().start();
+            for (offset, word) in prose_words(token.text()) {
+                if project_words.contains(&word.to_lowercase()) {
+                    continue;
+                }
+                let start = token_start + TextSize::from(offset as u32);
+                let range = TextRange::at(start, TextSize::of(word));
+                words.push((range, word));
+            }
+        }
+        if words.is_empty() {
+            return Vec::new();
+        }
+
+        let unique: BTreeSet<&str> = words.iter().map(|(_, word)| *word).collect();
+        let unknown = match unknown_words(options, &unique) {
+            Ok(unknown) => unknown,
+            Err(err) => {
+                log::warn!("spelling: {err}");
+                return Vec::new();
+            }
+        };
+
+        words
+            .into_iter()
+            .filter(|(_, word)| unknown.contains(*word))
+            .map(|(range, word)| {
+                Diagnostic::warning(
+                    Location::from_range(range, cx.input),
+                    "spelling",
+                    format!("Unknown word '{word}'"),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Nodes whose text is not prose.
+const EXCLUDED_ANCESTOR_KINDS: &[SyntaxKind] = &[
+    SyntaxKind::INLINE_CODE,
+    SyntaxKind::INLINE_EXEC,
+    SyntaxKind::CODE_BLOCK,
+    SyntaxKind::MYST_DIRECTIVE_BODY,
+    SyntaxKind::INLINE_MATH,
+    SyntaxKind::DISPLAY_MATH,
+    SyntaxKind::MATH_CONTENT,
+    SyntaxKind::TEX_BLOCK,
+    SyntaxKind::LATEX_COMMAND,
+    SyntaxKind::RAW_INLINE,
+    SyntaxKind::INLINE_HTML,
+    SyntaxKind::HTML_BLOCK,
+    SyntaxKind::COMMENT,
+    SyntaxKind::AUTO_LINK,
+    SyntaxKind::LINK_DEST,
+    SyntaxKind::LINK_REF,
+    SyntaxKind::WIKI_LINK_URL,
+    SyntaxKind::REFERENCE_DEFINITION,
+    SyntaxKind::FOOTNOTE_REFERENCE,
+    SyntaxKind::ATTRIBUTE,
+    SyntaxKind::SPAN_ATTRIBUTES,
+    SyntaxKind::CITATION,
+    SyntaxKind::CROSSREF,
+    SyntaxKind::SHORTCODE,
+    SyntaxKind::EMOJI,
+    SyntaxKind::CODE_INFO,
+    SyntaxKind::CHUNK_OPTIONS,
+    SyntaxKind::YAML_METADATA,
+    SyntaxKind::MMD_TITLE_BLOCK,
+];
+
+fn has_excluded_ancestor(token: &SyntaxToken) -> bool {
+    token
+        .parent_ancestors()
+        .any(|node| EXCLUDED_ANCESTOR_KINDS.contains(&node.kind()))
+}
+
+/// The words of `text` worth checking, with their byte offsets: runs of
+/// letters and inner apostrophes, skipping single letters, anything with a
+/// digit, and whitespace-separated chunks that look like URLs or addresses.
+fn prose_words(text: &str) -> Vec<(usize, &str)> {
+    let mut words = Vec::new();
+    let mut chunk_start = 0;
+    for chunk in text.split_inclusive(char::is_whitespace) {
+        let offset = chunk_start;
+        chunk_start += chunk.len();
+        if chunk.contains("://") || chunk.starts_with("www.") || chunk.contains('@') {
+            continue;
+        }
+        let mut start = None;
+        for (idx, ch) in chunk.char_indices().chain([(chunk.len(), ' ')]) {
+            let in_word = ch.is_alphanumeric() || (is_apostrophe(ch) && start.is_some());
+            match (start, in_word) {
+                (None, true) => start = Some(idx),
+                (Some(word_start), false) => {
+                    let word = chunk[word_start..idx].trim_end_matches(is_apostrophe);
+                    if word.chars().nth(1).is_some() && !word.chars().any(|c| c.is_numeric()) {
+                        words.push((offset + word_start, word));
+                    }
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+    }
+    words
+}
+
+fn is_apostrophe(ch: char) -> bool {
+    matches!(ch, '\'' | '\u{2019}')
+}
+
+/// The subset of `words` the checker reports as misspelled.
+fn unknown_words(
+    options: &SpellingOptions,
+    words: &BTreeSet<&str>,
+) -> Result<HashSet<String>, String> {
+    let command = options.checker.command();
+    let mut cmd = Command::new(command);
+    match options.checker {
+        SpellChecker::Hunspell => cmd.args(["-l", "-d", &options.language]),
+        SpellChecker::Aspell => cmd.args(["list", "--lang", &options.language]),
+    };
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| format!("failed to run {command}: {err}"))?;
+
+    // Write from another thread: the checker answers as it reads, and would
+    // block on a full stdout pipe before we finished writing.
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let input: String = words.iter().flat_map(|word| [*word, "\n"]).collect();
+    let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+    let output = child
+        .wait_with_output()
+        .map_err(|err| format!("failed to run {command}: {err}"))?;
+    let _ = writer.join();
+
+    if !output.status.success() {
+        return Err(format!(
+            "{command} exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn checked_words(input: &str) -> Vec<String> {
+        let config = Config::default();
+        let tree = crate::parser::parse(input, Some(config.clone()));
+        tree.descendants_with_tokens()
+            .filter_map(|element| element.into_token())
+            .filter(|token| token.kind() == SyntaxKind::TEXT && !has_excluded_ancestor(token))
+            .flat_map(|token| {
+                prose_words(token.text())
+                    .into_iter()
+                    .map(|(_, word)| word.to_string())
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn splits_prose_into_words_with_offsets() {
+        assert_eq!(
+            prose_words("Don't re-run it 3 times, x2 or ‘quoted’."),
+            [
+                (0, "Don't"),
+                (6, "re"),
+                (9, "run"),
+                (13, "it"),
+                (18, "times"),
+                (28, "or"),
+                (34, "quoted"),
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_urls_and_addresses() {
+        assert_eq!(
+            prose_words("see https://exmple.com or www.exmple.com, mail me@exmple.com"),
+            [(0, "see"), (23, "or"), (42, "mail")]
+        );
+    }
+
+    #[test]
+    fn only_checks_prose() {
+        let input = "---\ntitle: Teh title\n---\n\nSome *emphasied* text with `cde`, $\\alpha$, \
+                     and [a lnk](https://exmple.com) [@knuth84].\n\n```r\nfoo <- 1\n```\n";
+        assert_eq!(
+            checked_words(input),
+            ["Some", "emphasied", "text", "with", "and", "lnk"]
+        );
+    }
+
+    #[test]
+    fn reports_unknown_words_from_the_checker() {
+        if which::which("hunspell").is_err() {
+            println!("Skipping spelling test - hunspell not installed");
+            return;
+        }
+        let mut config = Config::default();
+        config.lint.spelling.words = vec!["panache".to_string()];
+        let input = "Panache checks teh spelling.\n";
+        let tree = crate::parser::parse(input, Some(config.clone()));
+        let diagnostics = SpellingRule.check_tree(&tree, input, &config, None);
+        if diagnostics.is_empty() {
+            println!("Skipping spelling test - no en_US dictionary");
+            return;
+        }
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "Unknown word 'teh'");
+        assert_eq!(diagnostics[0].location.column, 16);
+    }
+}
@@ -0,0 +1,82 @@
+use panache_formatter::formatter::whitespace::removable_trailing_whitespace;
+use rowan::{TextRange, TextSize};
+
+use crate::linter::diagnostics::{Diagnostic, Edit, Fix, Location};
+use crate::linter::rules::{DiagnosticCode, LintContext, Requirement, Rule, RuleMeta};
+
+/// Flags trailing spaces and tabs (MD009), the ones `trim-trailing-whitespace`
+/// removes.
+///
+/// Two or more spaces before a line that continues the text are a hard line
+/// break, and whitespace after a backslash would turn into one if removed;
+/// both are left alone.
+pub struct TrailingWhitespaceRule;
+
+impl Rule for TrailingWhitespaceRule {
+    fn name(&self) -> &str {
+        "trailing-whitespace"
+    }
+
+    fn metadata(&self) -> RuleMeta {
+        RuleMeta {
+            name: "trailing-whitespace",
+            default_on: false,
+            requires: Requirement::Always,
+            auto_fix: true,
+            codes: const { &[DiagnosticCode::warning("trailing-whitespace")] },
+        }
+    }
+
+    fn check(&self, cx: &LintContext) -> Vec<Diagnostic> {
+        removable_trailing_whitespace(cx.input, false)
+            .into_iter()
+            .map(|range| {
+                let range = TextRange::new(
+                    TextSize::from(range.start as u32),
+                    TextSize::from(range.end as u32),
+                );
+                Diagnostic::warning(
+                    Location::from_range(range, cx.input),
+                    "trailing-whitespace",
+                    "Trailing whitespace",
+                )
+                .with_fix(Fix::safe(
+                    "Remove trailing whitespace",
+                    vec![Edit {
+                        range,
+                        replacement: String::new(),
+                    }],
+                ))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn lint(input: &str) -> Vec<Diagnostic> {
+        let config = Config::default();
+        let tree = crate::parser::parse(input, Some(config.clone()));
+        TrailingWhitespaceRule.check_tree(&tree, input, &config, None)
+    }
+
+    #[test]
+    fn flags_trailing_spaces_and_tabs() {
+        let diagnostics = lint("Some text \n\n```\ncode\t\n```\n");
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].location.line, 1);
+        assert_eq!(diagnostics[0].location.column, 10);
+        assert_eq!(diagnostics[1].location.line, 4);
+        let fix = diagnostics[0].fix.as_ref().expect("autofix");
+        assert_eq!(fix.edits[0].replacement, "");
+    }
+
+    #[test]
+    fn keeps_hard_line_breaks() {
+        assert!(lint("first line  \nsecond line\n").is_empty());
+        assert_eq!(lint("last line  \n\nNext paragraph.\n").len(), 1);
+    }
+}
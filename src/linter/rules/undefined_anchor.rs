@@ -100,6 +100,15 @@ fn collect_anchors(
     anchors
 }
 
+/// Every anchor id `tree` declares on its own: explicit and implicit heading
+/// ids, attribute ids, and citation `ref-` targets. Used to validate
+/// `other.qmd#id` links across documents.
+pub(crate) fn document_anchors(tree: &SyntaxNode, config: &Config) -> HashSet<String> {
+    let mut anchors = HashSet::new();
+    extend_anchors(&mut anchors, tree, config);
+    anchors
+}
+
 fn extend_anchors(anchors: &mut HashSet<String>, tree: &SyntaxNode, config: &Config) {
     let db = crate::salsa::SalsaDb::default();
     let symbol_index = crate::salsa::symbol_usage_index_from_tree(&db, tree, &config.extensions);
@@ -187,6 +187,9 @@ fn collect_definition_labels(
     let Some(metadata) = metadata else {
         return labels;
     };
+    if !config.lint.undefined_references.project {
+        return labels;
+    }
 
     // Canonicalize to absolute path so project root discovery and path comparisons work
     // correctly regardless of whether the path was given as relative or absolute.
@@ -194,13 +197,11 @@ fn collect_definition_labels(
         .source_path
         .canonicalize()
         .unwrap_or_else(|_| metadata.source_path.clone());
-    let roots = crate::includes::find_project_roots(&doc_path);
-    let Some(project_root) = roots.bookdown_first() else {
+    let Some(project) = crate::project::Project::discover(&doc_path) else {
         return labels;
     };
-    let is_bookdown = roots.bookdown.is_some();
 
-    for path in crate::includes::find_project_documents(&project_root, config, is_bookdown) {
+    for path in project.documents(config) {
         if path == doc_path {
             continue;
         }
@@ -573,4 +574,35 @@ mod tests {
             "empty _bookdown.yml should auto-discover .Rmd files in the project"
         );
     }
+
+    #[test]
+    fn project_option_limits_crossrefs_to_the_document() {
+        let temp = TempDir::new().expect("tempdir");
+        let root = temp.path();
+        fs::write(
+            root.join("_quarto.yml"),
+            "project:\n  render:\n    - a.qmd\n    - b.qmd\n",
+        )
+        .expect("write _quarto.yml");
+        fs::write(root.join("a.qmd"), "![Plot](plot.png){#fig-plot}\n").expect("write a.qmd");
+        let input = "See @fig-plot.\n";
+        fs::write(root.join("b.qmd"), input).expect("write b.qmd");
+
+        let mut config = Config {
+            flavor: Flavor::Quarto,
+            extensions: crate::config::Extensions::for_flavor(Flavor::Quarto),
+            ..Default::default()
+        };
+        let tree = crate::parser::parse(input, Some(config.clone()));
+        let metadata = crate::metadata::extract_project_metadata(&tree, &root.join("b.qmd"))
+            .expect("metadata");
+        let rule = UndefinedReferencesRule;
+        let diagnostics = rule.check_tree(&tree, input, &config, Some(&metadata));
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+
+        config.lint.undefined_references.project = false;
+        let diagnostics = rule.check_tree(&tree, input, &config, Some(&metadata));
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("@fig-plot"));
+    }
 }
@@ -252,14 +252,11 @@ fn extend_usage_labels_from_project(
         .source_path
         .canonicalize()
         .unwrap_or_else(|_| metadata.source_path.clone());
-    let project_root = crate::includes::find_bookdown_root(&doc_path)
-        .or_else(|| crate::includes::find_quarto_root(&doc_path));
-    let Some(project_root) = project_root else {
+    let Some(project) = crate::project::Project::discover(&doc_path) else {
         return;
     };
-    let is_bookdown = crate::includes::find_bookdown_root(&doc_path).is_some();
 
-    for path in crate::includes::find_project_documents(&project_root, config, is_bookdown) {
+    for path in project.documents(config) {
         extend_usage_labels_from_file(usage, &path, config);
     }
 }
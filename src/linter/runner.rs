@@ -13,7 +13,7 @@ use crate::linter::rules::LintContext;
 use crate::linter::rules::RuleRegistry;
 use crate::syntax::{SyntaxKind, SyntaxNode};
 #[cfg(not(target_arch = "wasm32"))]
-use crate::utils::collect_code_blocks;
+use crate::utils::collect_code_blocks_by_language_key;
 
 pub struct LintRunner {
     registry: RuleRegistry,
@@ -73,8 +73,10 @@ impl LintRunner {
                 rule_diagnostics.len()
             );
 
+            let fix_enabled = config.lint.is_rule_fix_enabled(rule.name());
+
             // Filter out diagnostics in ignored ranges
-            for diagnostic in rule_diagnostics {
+            for mut diagnostic in rule_diagnostics {
                 let byte_offset: usize = diagnostic.location.range.start().into();
                 let is_ignored = ignored_ranges
                     .iter()
@@ -87,6 +89,11 @@ impl LintRunner {
                 );
 
                 if !is_ignored {
+                    // `[lint.rules.<rule>] fix = false` keeps the diagnostic
+                    // but withholds the fix from `--fix` and code actions.
+                    if !fix_enabled || !config.lint.is_rule_fix_enabled(&diagnostic.code) {
+                        diagnostic.fix = None;
+                    }
                     diagnostics.push(diagnostic);
                 }
             }
@@ -119,7 +126,7 @@ impl LintRunner {
         log_missing_linter_commands(&missing_linter_commands);
 
         // Collect code blocks by language
-        let code_blocks = collect_code_blocks(tree, input);
+        let code_blocks = collect_code_blocks_by_language_key(tree, input, config);
 
         // Resolve which (language, linter) pairs are actually runnable. This
         // pre-pass stays sequential: the skip/warn logging is cheap and its
@@ -152,7 +159,7 @@ impl LintRunner {
                 continue;
             }
 
-            let Some(blocks) = code_blocks.get(language) else {
+            let Some(blocks) = code_blocks.get(&config.language_key(language)) else {
                 continue;
             };
             if blocks.is_empty() {
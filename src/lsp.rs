@@ -26,8 +26,10 @@ mod task_pool;
 #[doc(hidden)]
 pub mod testing;
 mod uri_ext;
+mod workspace_links;
 
 pub(crate) use global_state::{ClientSender, GlobalState};
+pub use handlers::file_rename::{FileRenameEdit, plan_file_rename};
 #[doc(hidden)]
 pub use testing::{LspTester, WorkspaceSymbolSummary};
 #[doc(hidden)]
@@ -49,6 +51,8 @@ pub struct DocumentState {
 #[derive(Debug, Clone, Default)]
 pub struct LspRuntimeSettings {
     pub experimental_incremental_parsing: bool,
+    /// The client's trace level is `verbose` (`initialize` or `$/setTrace`).
+    pub trace_verbose: bool,
 }
 
 fn to_io<E: std::fmt::Display>(e: E) -> std::io::Error {
@@ -62,9 +66,19 @@ pub fn run() -> std::io::Result<()> {
     // Drive the handshake by hand (rather than `Connection::initialize`) so the
     // `InitializeResult` can carry `serverInfo` alongside capabilities; the
     // convenience helper hardcodes `serverInfo: null`.
-    let (id, init_value) = connection.initialize_start().map_err(to_io)?;
+    let (id, init_value) = match connection.initialize_start() {
+        Ok(request) => request,
+        // A client that goes away before `initialize` (or stdin at EOF) has
+        // nothing left to serve: shut down cleanly rather than as an error.
+        Err(err) if err.channel_is_disconnected() => {
+            drop(connection);
+            io_threads.join()?;
+            return Ok(());
+        }
+        Err(err) => return Err(to_io(err)),
+    };
     let init_result = serde_json::json!({
-        "capabilities": dispatch::server_capabilities(),
+        "capabilities": dispatch::server_capabilities_json(),
         "serverInfo": dispatch::server_info(),
     });
     connection
@@ -103,10 +103,13 @@ pub(crate) fn try_load_config_with_chain(
             .map(Path::to_path_buf)
             .unwrap_or_else(|| root.clone());
         match crate::config::load_with_chain(None, &start_dir, input_file.as_deref(), None) {
-            Ok((config, source, chain)) => {
+            Ok((mut config, source, chain)) => {
                 if let Some(p) = source.path() {
                     log::info!("Loaded config from {}", p.display());
                 }
+                if crate::trust::safe_mode_enabled(true) {
+                    crate::trust::enforce(&mut config, &source);
+                }
                 return Ok((config, source, chain));
             }
             Err(e) => {
@@ -147,6 +150,7 @@ pub(crate) fn default_config_for_uri(document_uri: Option<&Uri>) -> crate::Confi
         config.flavor = flavor;
         config.extensions = crate::config::Extensions::for_flavor(flavor);
     }
+    crate::config::apply_path_extensions(&mut config, &file_path);
     config
 }
 
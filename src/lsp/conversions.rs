@@ -238,11 +238,7 @@ mod tests {
 
         let diag = PanacheDiagnostic {
             severity: Severity::Warning,
-            location: Location {
-                line: 3,
-                column: 1,
-                range: TextRange::new(7.into(), 14.into()),
-            },
+            location: Location::from_range(TextRange::new(7.into(), 14.into()), text),
             message: "Heading level skipped from h1 to h3".to_string(),
             code: "heading-hierarchy".to_string(),
             origin: DiagnosticOrigin::BuiltIn,
@@ -275,11 +271,7 @@ mod tests {
 
         let error_diag = PanacheDiagnostic {
             severity: Severity::Error,
-            location: Location {
-                line: 1,
-                column: 1,
-                range: TextRange::new(0.into(), 4.into()),
-            },
+            location: Location::from_range(TextRange::new(0.into(), 4.into()), text),
             message: "Error".to_string(),
             code: "test-error".to_string(),
             origin: DiagnosticOrigin::BuiltIn,
@@ -292,11 +284,7 @@ mod tests {
 
         let info_diag = PanacheDiagnostic {
             severity: Severity::Info,
-            location: Location {
-                line: 1,
-                column: 1,
-                range: TextRange::new(0.into(), 4.into()),
-            },
+            location: Location::from_range(TextRange::new(0.into(), 4.into()), text),
             message: "Info".to_string(),
             code: "test-info".to_string(),
             origin: DiagnosticOrigin::BuiltIn,
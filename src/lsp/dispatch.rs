@@ -12,14 +12,16 @@ use std::time::{Duration, Instant};
 use lsp_server::{ErrorCode, ExtractError, Notification, Request, RequestId, Response};
 use lsp_types::notification::Notification as _;
 use lsp_types::request::Request as _;
-use lsp_types::{InitializeParams, ServerCapabilities, ServerInfo, Uri};
+use lsp_types::{
+    InitializeParams, ServerCapabilities, ServerInfo, SetTraceParams, TraceValue, Uri,
+};
 use serde::Serialize;
 use serde_json::Value;
 
 use super::global_state::{GlobalState, StateSnapshot, Task};
 use super::helpers::catch_cancelled;
 use super::uri_ext::UriExt;
-use super::{documents, handlers};
+use super::{documents, handlers, workspace_links};
 
 /// Which worker pool a request runs on: the shared `Main` pool for interactive
 /// reads, or the single-thread `Fmt` pool that isolates slow external
@@ -63,7 +65,10 @@ pub(crate) fn server_capabilities() -> ServerCapabilities {
             first_trigger_character: "\n".to_string(),
             more_trigger_character: None,
         }),
-        code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+        code_action_provider: Some(CodeActionProviderCapability::Options(CodeActionOptions {
+            code_action_kinds: Some(handlers::code_actions::supported_kinds()),
+            ..Default::default()
+        })),
         document_symbol_provider: Some(OneOf::Left(true)),
         document_link_provider: Some(DocumentLinkOptions {
             resolve_provider: Some(true),
@@ -75,7 +80,7 @@ pub(crate) fn server_capabilities() -> ServerCapabilities {
         definition_provider: Some(OneOf::Left(true)),
         hover_provider: Some(HoverProviderCapability::Simple(true)),
         completion_provider: Some(CompletionOptions {
-            trigger_characters: Some(vec!["(".into(), "/".into(), "<".into()]),
+            trigger_characters: Some(vec!["(".into(), "/".into(), "<".into(), "[".into()]),
             resolve_provider: Some(true),
             ..Default::default()
         }),
@@ -149,6 +154,15 @@ pub(crate) fn server_capabilities() -> ServerCapabilities {
     }
 }
 
+/// [`server_capabilities`] as sent to the client, plus what lsp-types does
+/// not model: `rangesSupport` for `textDocument/rangesFormatting` (LSP 3.18).
+pub(crate) fn server_capabilities_json() -> Value {
+    let mut capabilities =
+        serde_json::to_value(server_capabilities()).expect("server capabilities serialize to JSON");
+    capabilities["documentRangeFormattingProvider"] = serde_json::json!({ "rangesSupport": true });
+    capabilities
+}
+
 fn watched_document_glob() -> Vec<lsp_types::FileSystemWatcher> {
     use lsp_types::*;
     crate::all_document_extensions()
@@ -236,6 +250,7 @@ impl GlobalState {
         log::debug!(
             "lsp runtime setting experimental.incrementalParsing={experimental} (initialize options)"
         );
+        self.runtime_settings.trace_verbose = params.trace == Some(TraceValue::Verbose);
 
         // Pull diagnostics mode-switch: a client that advertises
         // `textDocument.diagnostic` is served via pull only (push is suppressed).
@@ -337,6 +352,10 @@ impl GlobalState {
         self.send_request::<lsp_types::request::RegisterCapability>(RegistrationParams {
             registrations: vec![registration],
         });
+
+        // Index the workspace's relative links in the background; the result
+        // arms a settle that publishes cross-file `broken-link` diagnostics.
+        self.schedule_workspace_link_scan();
     }
 
     /// Route an incoming request: pool it over a read snapshot, or reject.
@@ -415,6 +434,11 @@ impl GlobalState {
             handlers::formatting::format_range,
             spawn_format_request
         );
+        pool!(
+            handlers::formatting::RangesFormatting,
+            handlers::formatting::format_ranges,
+            spawn_format_request
+        );
         pool!(
             r::OnTypeFormatting,
             handlers::formatting::format_on_type,
@@ -524,6 +548,7 @@ impl GlobalState {
             handlers::file_operations::did_delete_files
         );
         handle!(n::Cancel, GlobalState::on_cancel);
+        handle!(n::SetTrace, GlobalState::on_set_trace);
 
         log::debug!("ignoring notification: {}", not.method);
     }
@@ -539,6 +564,11 @@ impl GlobalState {
         }
     }
 
+    /// `$/setTrace`: `verbose` adds per-phase timings to formatting logs.
+    fn on_set_trace(&mut self, params: SetTraceParams) {
+        self.runtime_settings.trace_verbose = params.value == TraceValue::Verbose;
+    }
+
     /// Turn a completed worker result into client messages.
     pub(crate) fn on_task(&mut self, task: Task) {
         match task {
@@ -600,6 +630,14 @@ impl GlobalState {
                 // no-op for push clients).
                 self.send_diagnostic_refresh();
             }
+            Task::WorkspaceLinks { generation, index } => {
+                if generation != self.workspace_links_generation {
+                    return;
+                }
+                self.last_applied_workspace_links_generation = generation;
+                self.workspace_links = Some(index);
+                self.arm_settle();
+            }
         }
     }
 
@@ -785,6 +823,18 @@ impl GlobalState {
                         }
                     }
                 }
+                // Cross-file link validation runs once over the whole workspace
+                // (open documents live, closed ones from the background index).
+                for (target, _version, diags) in
+                    workspace_links::link_publishes(&snap, snap.workspace_links.as_deref())
+                {
+                    let slot = merged.entry(target).or_default();
+                    for diag in diags {
+                        if !slot.contains(&diag) {
+                            slot.push(diag);
+                        }
+                    }
+                }
                 merged
                     .into_iter()
                     .map(|(uri, mut diags)| {
@@ -365,6 +365,7 @@ pub(crate) fn did_save(gs: &mut GlobalState, params: DidSaveTextDocumentParams)
     }
     // Save is the heavy pass: external linters for the saved document. Debounced
     // like every other settle so a save-all burst coalesces into one pass.
+    gs.schedule_workspace_link_scan();
     gs.arm_settle_external(uri);
 }
 
@@ -401,7 +402,9 @@ pub(crate) fn did_close(gs: &mut GlobalState, params: DidCloseTextDocumentParams
     // Closing a document changes the database for the remaining open docs (a
     // closed include affects its parent), and the eviction above may cancel an
     // in-flight pass. Arm the settle so the remaining docs are re-linted over the
-    // post-close snapshot.
+    // post-close snapshot. The closed buffer's on-disk contents re-enter the
+    // link index.
+    gs.schedule_workspace_link_scan();
     gs.arm_settle();
 }
 
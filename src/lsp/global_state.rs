@@ -22,6 +22,7 @@ use super::DocumentState;
 use super::LspRuntimeSettings;
 use super::config::load_config;
 use super::task_pool::{TaskPool, default_pool_size};
+use super::workspace_links::WorkspaceLinkIndex;
 use crate::Config;
 use crate::syntax::{ParsedYamlRegionSnapshot, SyntaxNode};
 
@@ -221,6 +222,10 @@ pub(crate) struct StateSnapshot {
     /// Client capabilities the pull handler needs, copied so it runs off-thread.
     pub(crate) supports_pull_diagnostics: bool,
     pub(crate) supports_related_documents: bool,
+    /// The workspace link index at snapshot time, for the settle pass.
+    pub(crate) workspace_links: Option<Arc<WorkspaceLinkIndex>>,
+    /// Where to log per-phase timings, set while the client traces verbosely.
+    pub(crate) timings_log: Option<ClientSender>,
 }
 
 impl StateSnapshot {
@@ -346,6 +351,12 @@ pub(crate) enum Task {
         publishes: Vec<(Uri, Option<i32>, Vec<Diagnostic>)>,
         external_ran: HashSet<Uri>,
     },
+    /// A finished background scan of the workspace's relative links. Dropped
+    /// when a newer scan has been dispatched since (`generation` is stale).
+    WorkspaceLinks {
+        generation: u64,
+        index: Arc<WorkspaceLinkIndex>,
+    },
 }
 
 /// The synchronous, single-threaded-mutation server state.
@@ -438,6 +449,10 @@ pub(crate) struct GlobalState {
     /// on the config file itself; this is the one-shot heads-up.
     pub(crate) config_error_reports: HashMap<PathBuf, String>,
 
+    /// Untrusted project configs whose external commands safe mode skipped and
+    /// that have already been reported via `window/showMessage`.
+    pub(crate) untrusted_config_reports: HashSet<PathBuf>,
+
     /// Canonical paths of config files reached via `extend` by some open
     /// document's config. The config-name globs only match `panache.toml` /
     /// `.panache.toml`, so a differently-named base (`base.toml`) would go
@@ -445,6 +460,18 @@ pub(crate) struct GlobalState {
     /// when such a base changes. Accumulated as documents load their config;
     /// stale entries only cost an occasional harmless (idempotent) reload.
     pub(crate) watched_config_files: HashSet<PathBuf>,
+
+    /// The latest background scan of every workspace document's relative
+    /// links, consumed by the settle pass for cross-file `broken-link`
+    /// diagnostics. `None` until the first scan lands (open documents are still
+    /// checked against the filesystem meanwhile).
+    pub(crate) workspace_links: Option<Arc<WorkspaceLinkIndex>>,
+    /// Generation of the most recently dispatched link scan; a landing scan
+    /// with an older generation is dropped.
+    pub(crate) workspace_links_generation: u64,
+    /// Generation of the last link scan actually applied (the test harness's
+    /// `pump` waits for it to catch up, like `last_applied_lint_generation`).
+    pub(crate) last_applied_workspace_links_generation: u64,
 }
 
 impl GlobalState {
@@ -475,7 +502,11 @@ impl GlobalState {
             last_applied_lint_generation: 0,
             external_pending: HashSet::new(),
             config_error_reports: HashMap::new(),
+            untrusted_config_reports: HashSet::new(),
             watched_config_files: HashSet::new(),
+            workspace_links: None,
+            workspace_links_generation: 0,
+            last_applied_workspace_links_generation: 0,
         }
     }
 
@@ -492,6 +523,14 @@ impl GlobalState {
             Ok((config, source, chain)) => {
                 if let Some(path) = source.path() {
                     self.config_error_reports.remove(path);
+                    if crate::trust::was_blocked(path)
+                        && self.untrusted_config_reports.insert(path.to_path_buf())
+                    {
+                        self.sender.show_message(
+                            MessageType::WARNING,
+                            format!("panache: {}", crate::trust::untrusted_message(path)),
+                        );
+                    }
                 }
                 // Track every file in the extend chain so the watcher reloads
                 // this document when a base config (any name/location) changes.
@@ -540,6 +579,11 @@ impl GlobalState {
             diagnostics: self.diagnostics.shared(),
             supports_pull_diagnostics: self.supports_pull_diagnostics,
             supports_related_documents: self.supports_related_documents,
+            workspace_links: self.workspace_links.clone(),
+            timings_log: self
+                .runtime_settings
+                .trace_verbose
+                .then(|| self.sender.clone()),
         }
     }
 
@@ -588,6 +632,25 @@ impl GlobalState {
         self.settle_deadline = Some(Instant::now() + DIAGNOSTICS_DEBOUNCE);
     }
 
+    /// Rescan the workspace's relative links on the worker pool. Called when
+    /// the filesystem may have changed underneath the index (initialization,
+    /// watcher events, file operations, saves, closes); the result lands as
+    /// [`Task::WorkspaceLinks`] and arms a settle. A no-op without workspace
+    /// folders.
+    pub(crate) fn schedule_workspace_link_scan(&mut self) {
+        if self.workspace_folders.is_empty() {
+            return;
+        }
+        self.workspace_links_generation += 1;
+        let generation = self.workspace_links_generation;
+        let folders = self.workspace_folders.clone();
+        let sender = self.pool.result_sender();
+        self.pool.spawn(move || {
+            let index = Arc::new(WorkspaceLinkIndex::build(&folders));
+            let _ = sender.send(Task::WorkspaceLinks { generation, index });
+        });
+    }
+
     /// Arm the settle timer and mark `uri` as needing external linters on the
     /// next pass (the on-open/-save/referenced-file-change signal).
     pub(crate) fn arm_settle_external(&mut self, uri: Uri) {
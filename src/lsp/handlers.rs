@@ -1,4 +1,5 @@
 pub(crate) mod code_actions;
+pub(crate) mod code_cell_conversion;
 pub(crate) mod completion;
 pub(crate) mod configuration;
 pub(crate) mod diagnostics;
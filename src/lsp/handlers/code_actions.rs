@@ -8,7 +8,25 @@ use crate::lsp::global_state::StateSnapshot;
 use crate::syntax::{AstNode, List};
 
 use super::super::conversions::{convert_diagnostic, offset_to_position, position_to_offset};
-use super::{footnote_conversion, heading_link_conversion, link_conversion, list_conversion};
+use super::{
+    code_cell_conversion, footnote_conversion, heading_link_conversion, link_conversion,
+    list_conversion,
+};
+
+/// Kind of the aggregate "fix all" action. A sub-kind of `source.fixAll`, so
+/// clients requesting the generic kind (e.g. fix-on-save) still receive it,
+/// while editors running several fixers can target panache specifically.
+pub(crate) const SOURCE_FIX_ALL_PANACHE: CodeActionKind =
+    CodeActionKind::new("source.fixAll.panache");
+
+/// Code action kinds advertised in the server capabilities.
+pub(crate) fn supported_kinds() -> Vec<CodeActionKind> {
+    vec![
+        CodeActionKind::QUICKFIX,
+        CodeActionKind::REFACTOR,
+        SOURCE_FIX_ALL_PANACHE,
+    ]
+}
 
 /// Handle textDocument/codeAction request
 pub(crate) fn code_action(
@@ -16,6 +34,7 @@ pub(crate) fn code_action(
     params: CodeActionParams,
 ) -> Option<CodeActionResponse> {
     let uri = params.text_document.uri;
+    let only = params.context.only;
     let (text, config) = snap.document_and_config(&uri)?;
     let request_range = params.range;
     let parsed_yaml_regions = snap.parsed_yaml_regions(&uri);
@@ -56,9 +75,10 @@ pub(crate) fn code_action(
         let mut jobs = Vec::new();
 
         if !config.linters.is_empty() {
-            let code_blocks = crate::utils::collect_code_blocks(&tree, &text);
+            let code_blocks =
+                crate::utils::collect_code_blocks_by_language_key(&tree, &text, &config);
             for (language, linter_name) in &config.linters {
-                let Some(blocks) = code_blocks.get(language) else {
+                let Some(blocks) = code_blocks.get(&config.language_key(language)) else {
                     continue;
                 };
                 if blocks.is_empty() {
@@ -102,35 +122,28 @@ pub(crate) fn code_action(
     }
 
     let mut actions = Vec::new();
-    let mut fix_all_edits: Vec<(usize, usize, String)> = Vec::new();
 
     // Add lint fix code actions
     for diag in &diagnostics {
         if let Some(ref fix) = diag.fix {
+            // Unsafe fixes may change the document's meaning, so they are still
+            // offered individually (labeled) but excluded from the aggregate
+            // "fix all" action below.
+            let is_unsafe = fix.safety == linter::FixSafety::Unsafe;
             let lsp_diag = convert_diagnostic(diag, &text);
             if !should_offer_quickfix(request_range, lsp_diag.range) {
                 continue;
             }
-            // Unsafe fixes may change the document's meaning, so they are still
-            // offered individually (labeled) but excluded from the aggregate
-            // "fix all" action, matching the CLI's safe-by-default `--fix`.
-            let is_unsafe = fix.safety == linter::FixSafety::Unsafe;
             let mut changes = HashMap::new();
             let text_edits: Vec<TextEdit> = fix
                 .edits
                 .iter()
-                .map(|edit| {
-                    let start_offset: usize = edit.range.start().into();
-                    let end_offset: usize = edit.range.end().into();
-                    let start = offset_to_position(&text, start_offset);
-                    let end = offset_to_position(&text, end_offset);
-                    if !is_unsafe {
-                        fix_all_edits.push((start_offset, end_offset, edit.replacement.clone()));
-                    }
-                    TextEdit {
-                        range: Range { start, end },
-                        new_text: edit.replacement.clone(),
-                    }
+                .map(|edit| TextEdit {
+                    range: Range {
+                        start: offset_to_position(&text, edit.range.start().into()),
+                        end: offset_to_position(&text, edit.range.end().into()),
+                    },
+                    new_text: edit.replacement.clone(),
                 })
                 .collect();
 
@@ -156,45 +169,36 @@ pub(crate) fn code_action(
         }
     }
 
-    if !fix_all_edits.is_empty() {
-        fix_all_edits.sort_by_key(|a| (a.0, a.1));
-        let mut selected_edits: Vec<(usize, usize, String)> = Vec::new();
-        for edit in fix_all_edits {
-            if selected_edits
-                .last()
-                .is_some_and(|prev| edit.0 < prev.1 || edit == *prev)
-            {
-                continue;
-            }
-            selected_edits.push(edit);
-        }
-
-        if !selected_edits.is_empty() {
-            let mut changes = HashMap::new();
-            let text_edits: Vec<TextEdit> = selected_edits
-                .into_iter()
-                .map(|(start_offset, end_offset, replacement)| TextEdit {
-                    range: Range {
-                        start: offset_to_position(&text, start_offset),
-                        end: offset_to_position(&text, end_offset),
-                    },
-                    new_text: replacement,
-                })
-                .collect();
-            changes.insert(uri.clone(), text_edits);
-
-            let fix_all_action = CodeAction {
-                title: "Fix all auto-fixable lint issues".to_string(),
-                kind: Some(CodeActionKind::SOURCE_FIX_ALL),
-                diagnostics: None,
-                edit: Some(WorkspaceEdit {
-                    changes: Some(changes),
-                    ..Default::default()
-                }),
+    // The aggregate covers the whole document, not just the request range, and
+    // takes the same safe, non-overlapping fixes as the CLI's first `--fix`
+    // pass. Fixes it skips for overlapping show up again once it is applied.
+    let selection = linter::select_fixes(&diagnostics, false);
+    if selection.applied > 0 {
+        let mut changes = HashMap::new();
+        let text_edits: Vec<TextEdit> = selection
+            .edits
+            .iter()
+            .map(|edit| TextEdit {
+                range: Range {
+                    start: offset_to_position(&text, edit.range.start().into()),
+                    end: offset_to_position(&text, edit.range.end().into()),
+                },
+                new_text: edit.replacement.clone(),
+            })
+            .collect();
+        changes.insert(uri.clone(), text_edits);
+
+        let fix_all_action = CodeAction {
+            title: "Fix all auto-fixable lint issues".to_string(),
+            kind: Some(SOURCE_FIX_ALL_PANACHE),
+            diagnostics: None,
+            edit: Some(WorkspaceEdit {
+                changes: Some(changes),
                 ..Default::default()
-            };
-            actions.push(CodeActionOrCommand::CodeAction(fix_all_action));
-        }
+            }),
+            ..Default::default()
+        };
+        actions.push(CodeActionOrCommand::CodeAction(fix_all_action));
     }
 
     let tree = crate::parse(&text, Some(config.clone()));
@@ -503,9 +507,79 @@ pub(crate) fn code_action(
         }
     }
 
+    // Add code cell split/join code actions (refactoring)
+    if !in_frontmatter_region
+        && let Some(offset) = position_to_offset(&text, request_range.start)
+        && let Some(block_node) = code_cell_conversion::find_code_block_at_position(&tree, offset)
+    {
+        let edits = code_cell_conversion::split_code_cell(&block_node, &text, offset);
+        if !edits.is_empty() {
+            let mut changes = HashMap::new();
+            changes.insert(uri.clone(), edits);
+
+            let action = CodeAction {
+                title: "Split code cell".to_string(),
+                kind: Some(CodeActionKind::REFACTOR),
+                diagnostics: None,
+                edit: Some(WorkspaceEdit {
+                    changes: Some(changes),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            };
+
+            actions.push(CodeActionOrCommand::CodeAction(action));
+        }
+
+        if let Some(next_node) = code_cell_conversion::find_joinable_next_cell(&block_node) {
+            let edits = code_cell_conversion::join_code_cells(&block_node, &next_node, &text);
+            if !edits.is_empty() {
+                let mut changes = HashMap::new();
+                changes.insert(uri.clone(), edits);
+
+                let action = CodeAction {
+                    title: "Join with next code cell".to_string(),
+                    kind: Some(CodeActionKind::REFACTOR),
+                    diagnostics: None,
+                    edit: Some(WorkspaceEdit {
+                        changes: Some(changes),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                };
+
+                actions.push(CodeActionOrCommand::CodeAction(action));
+            }
+        }
+    }
+
+    if let Some(only) = only.as_deref() {
+        actions.retain(|action| match action {
+            CodeActionOrCommand::CodeAction(action) => action
+                .kind
+                .as_ref()
+                .is_some_and(|kind| kind_requested(only, kind)),
+            CodeActionOrCommand::Command(_) => false,
+        });
+    }
+
     Some(actions)
 }
 
+/// Whether `kind` matches one of the kinds in the client's `only` filter.
+/// Kinds are hierarchical: requesting `source.fixAll` includes
+/// `source.fixAll.panache`.
+fn kind_requested(only: &[CodeActionKind], kind: &CodeActionKind) -> bool {
+    only.iter().any(|requested| {
+        let requested = requested.as_str();
+        kind.as_str() == requested
+            || kind
+                .as_str()
+                .strip_prefix(requested)
+                .is_some_and(|rest| rest.starts_with('.'))
+    })
+}
+
 fn range_contains(outer: Range, inner: Range) -> bool {
     (outer.start.line, outer.start.character) <= (inner.start.line, inner.start.character)
         && (inner.end.line, inner.end.character) <= (outer.end.line, outer.end.character)
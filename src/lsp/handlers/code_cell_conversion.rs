@@ -0,0 +1,359 @@
+//! Code cell splitting and joining utilities for code actions.
+//!
+//! Splitting duplicates the cell header (opening fence plus hashpipe chunk
+//! options) into the new cell, dropping label declarations so the document
+//! does not end up with two cells sharing a label. Joining merges a cell with
+//! the next adjacent cell of the same language when doing so does not lose
+//! any of the second cell's options.
+
+use crate::syntax::{AstNode, ChunkLabelSource, CodeBlock, SyntaxKind, SyntaxNode};
+use lsp_types::{Range, TextEdit};
+use rowan::TextRange;
+
+use super::super::conversions::offset_to_position;
+
+/// Find the innermost fenced CODE_BLOCK node at the given position.
+pub fn find_code_block_at_position(tree: &SyntaxNode, offset: usize) -> Option<SyntaxNode> {
+    let text_size = rowan::TextSize::from(offset as u32);
+    let token = tree.token_at_offset(text_size).right_biased()?;
+    token
+        .parent_ancestors()
+        .find_map(CodeBlock::cast)
+        .map(|block| block.syntax().clone())
+        .filter(|node| child_of_kind(node, SyntaxKind::CODE_FENCE_OPEN).is_some())
+}
+
+/// Split a code cell at the line containing `offset`.
+///
+/// The line under the cursor becomes the first line of the new cell. Returns
+/// no edits when either half would be empty, when the cursor is inside the
+/// cell header, or when the cell is nested inside a container whose line
+/// prefix is not plain indentation (e.g. block quotes or list markers).
+pub fn split_code_cell(block_node: &SyntaxNode, text: &str, offset: usize) -> Vec<TextEdit> {
+    let Some(block) = CodeBlock::cast(block_node.clone()) else {
+        return vec![];
+    };
+    let Some(close) = child_of_kind(block_node, SyntaxKind::CODE_FENCE_CLOSE) else {
+        return vec![];
+    };
+    let Some(header_range) = header_range(&block, text) else {
+        return vec![];
+    };
+    let body_start: usize = header_range.end().into();
+    let close_start: usize = close.text_range().start().into();
+
+    let split_at = line_start(text, offset.min(text.len()));
+    if split_at <= body_start || split_at >= close_start {
+        return vec![];
+    }
+
+    let header = header_without_labels(&block, text, header_range);
+    let prefix = &text[usize::from(header_range.start())..fence_open_start(block_node)];
+    let close_fence = close.text().to_string();
+    let close_fence = close_fence.trim_end_matches(['\n', '\r']).trim_start();
+
+    let position = offset_to_position(text, split_at);
+    vec![TextEdit {
+        range: Range {
+            start: position,
+            end: position,
+        },
+        new_text: format!("{prefix}{close_fence}\n\n{header}"),
+    }]
+}
+
+/// Find the code cell directly following `block_node` (separated only by
+/// blank lines) that can be joined into it.
+pub fn find_joinable_next_cell(block_node: &SyntaxNode) -> Option<SyntaxNode> {
+    let block = CodeBlock::cast(block_node.clone())?;
+    child_of_kind(block_node, SyntaxKind::CODE_FENCE_CLOSE)?;
+
+    let next = block_node
+        .siblings(rowan::Direction::Next)
+        .skip(1)
+        .find(|sibling| sibling.kind() != SyntaxKind::BLANK_LINE)?;
+    let next_block = CodeBlock::cast(next.clone())?;
+    child_of_kind(&next, SyntaxKind::CODE_FENCE_OPEN)?;
+
+    if block.language().is_none()
+        || block.language() != next_block.language()
+        || block.is_executable_chunk() != next_block.is_executable_chunk()
+    {
+        return None;
+    }
+
+    // Joining keeps the first cell's header, so refuse when that would
+    // silently drop a label or options the second cell does not share.
+    if next_block.has_chunk_label() {
+        return None;
+    }
+    let next_options = option_pairs(&next_block);
+    if !next_options.is_empty() && next_options != option_pairs(&block) {
+        return None;
+    }
+
+    Some(next)
+}
+
+/// Join `block_node` with the following cell `next_node`, keeping the first
+/// cell's header and dropping the second cell's header.
+pub fn join_code_cells(
+    block_node: &SyntaxNode,
+    next_node: &SyntaxNode,
+    text: &str,
+) -> Vec<TextEdit> {
+    let (Some(close), Some(next_block)) = (
+        child_of_kind(block_node, SyntaxKind::CODE_FENCE_CLOSE),
+        CodeBlock::cast(next_node.clone()),
+    ) else {
+        return vec![];
+    };
+    let Some(next_header) = header_range(&next_block, text) else {
+        return vec![];
+    };
+
+    let start: usize = close.text_range().start().into();
+    let end: usize = next_header.end().into();
+    vec![TextEdit {
+        range: Range {
+            start: offset_to_position(text, start),
+            end: offset_to_position(text, end),
+        },
+        new_text: String::new(),
+    }]
+}
+
+fn child_of_kind(node: &SyntaxNode, kind: SyntaxKind) -> Option<SyntaxNode> {
+    node.children().find(|child| child.kind() == kind)
+}
+
+fn line_start(text: &str, offset: usize) -> usize {
+    text[..offset].rfind('\n').map_or(0, |idx| idx + 1)
+}
+
+fn fence_open_start(block_node: &SyntaxNode) -> usize {
+    child_of_kind(block_node, SyntaxKind::CODE_FENCE_OPEN)
+        .map(|open| open.text_range().start().into())
+        .unwrap_or_else(|| block_node.text_range().start().into())
+}
+
+/// Range covering the full lines of the cell header: the opening fence line
+/// (including any leading indentation) and the hashpipe option preamble.
+/// Returns `None` when the fence is preceded by anything but whitespace.
+fn header_range(block: &CodeBlock, text: &str) -> Option<TextRange> {
+    let open = child_of_kind(block.syntax(), SyntaxKind::CODE_FENCE_OPEN)?;
+    let open_start: usize = open.text_range().start().into();
+    let start = line_start(text, open_start);
+    if !text[start..open_start]
+        .chars()
+        .all(|c| c == ' ' || c == '\t')
+    {
+        return None;
+    }
+
+    let end = block
+        .hashpipe_yaml_preamble()
+        .map(|preamble| preamble.syntax().text_range().end())
+        .unwrap_or_else(|| open.text_range().end());
+    Some(TextRange::new((start as u32).into(), end))
+}
+
+/// Header text with every label declaration removed: hashpipe `label:` lines
+/// are dropped entirely, inline labels are cut along with their separator.
+fn header_without_labels(block: &CodeBlock, text: &str, header: TextRange) -> String {
+    let header_start: usize = header.start().into();
+    let header_end: usize = header.end().into();
+
+    let mut removals: Vec<(usize, usize)> = Vec::new();
+    for entry in block.chunk_label_entries() {
+        let range = entry.declaration_range();
+        let (start, end): (usize, usize) = (range.start().into(), range.end().into());
+        if start < header_start || end > header_end {
+            continue;
+        }
+        let in_fence_line = text[header_start..start].find('\n').is_none();
+        if entry.source() == ChunkLabelSource::InlineLabel || in_fence_line {
+            removals.push(inline_removal(text, start, end));
+        } else {
+            // Hashpipe map entries extend over the next line's `#|` prefix,
+            // so find the line end from the value instead.
+            let end = usize::from(entry.value_range().end()).min(header_end);
+            let line_end = text[end..header_end]
+                .find('\n')
+                .map_or(header_end, |idx| end + idx + 1);
+            removals.push((line_start(text, start), line_end));
+        }
+    }
+    removals.sort_unstable();
+
+    let mut out = String::new();
+    let mut cursor = header_start;
+    for (start, end) in removals {
+        if start < cursor {
+            continue;
+        }
+        out.push_str(&text[cursor..start]);
+        cursor = end;
+    }
+    out.push_str(&text[cursor..header_end]);
+    out
+}
+
+/// Extend an inline label declaration over its trailing `,` separator, or
+/// over the preceding whitespace/comma when it is the last item.
+fn inline_removal(text: &str, start: usize, end: usize) -> (usize, usize) {
+    let rest = &text[end..];
+    let trimmed = rest.trim_start_matches([' ', '\t']);
+    if let Some(after_comma) = trimmed.strip_prefix(',') {
+        let after = after_comma.trim_start_matches([' ', '\t']);
+        return (start, text.len() - after.len());
+    }
+    let before = text[..start].trim_end_matches([' ', '\t']);
+    let before = before.strip_suffix(',').unwrap_or(before);
+    let before = before.trim_end_matches([' ', '\t']);
+    (before.len(), end)
+}
+
+fn option_pairs(block: &CodeBlock) -> Vec<(String, Option<String>)> {
+    let mut pairs: Vec<(String, Option<String>)> = block
+        .merged_chunk_option_entries()
+        .into_iter()
+        .filter_map(|entry| {
+            let key = entry.key()?.trim().to_ascii_lowercase();
+            (key != "label").then(|| (key, entry.value()))
+        })
+        .collect();
+    pairs.sort();
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Config, Extensions, Flavor};
+
+    fn parse(input: &str) -> SyntaxNode {
+        let config = Config {
+            flavor: Flavor::Quarto,
+            extensions: Extensions::for_flavor(Flavor::Quarto),
+            ..Config::default()
+        };
+        crate::parse(input, Some(config))
+    }
+
+    fn apply(text: &str, edits: &[TextEdit]) -> String {
+        let mut edits: Vec<_> = edits
+            .iter()
+            .map(|edit| {
+                let start =
+                    crate::lsp::conversions::position_to_offset(text, edit.range.start).unwrap();
+                let end =
+                    crate::lsp::conversions::position_to_offset(text, edit.range.end).unwrap();
+                (start, end, edit.new_text.clone())
+            })
+            .collect();
+        edits.sort_by_key(|edit| std::cmp::Reverse(edit.0));
+        let mut out = text.to_string();
+        for (start, end, new_text) in edits {
+            out.replace_range(start..end, &new_text);
+        }
+        out
+    }
+
+    fn split_at(input: &str, needle: &str) -> String {
+        let tree = parse(input);
+        let offset = input.find(needle).unwrap();
+        let block = find_code_block_at_position(&tree, offset).expect("code block");
+        apply(input, &split_code_cell(&block, input, offset))
+    }
+
+    #[test]
+    fn split_duplicates_hashpipe_options_without_label() {
+        let input = "```{r}\n#| label: setup\n#| echo: false\nx <- 1\ny <- 2\n```\n";
+        assert_eq!(
+            split_at(input, "y <- 2"),
+            "```{r}\n#| label: setup\n#| echo: false\nx <- 1\n```\n\n```{r}\n#| echo: false\ny <- 2\n```\n"
+        );
+    }
+
+    #[test]
+    fn split_drops_inline_label() {
+        let input = "```{r setup, echo=FALSE}\nx <- 1\ny <- 2\n```\n";
+        assert_eq!(
+            split_at(input, "y <- 2"),
+            "```{r setup, echo=FALSE}\nx <- 1\n```\n\n```{r echo=FALSE}\ny <- 2\n```\n"
+        );
+    }
+
+    #[test]
+    fn split_keeps_fence_length_and_indentation() {
+        let input = "  ````python\n  a = 1\n  b = 2\n  ````\n";
+        assert_eq!(
+            split_at(input, "b = 2"),
+            "  ````python\n  a = 1\n  ````\n\n  ````python\n  b = 2\n  ````\n"
+        );
+    }
+
+    #[test]
+    fn split_requires_content_on_both_sides() {
+        let input = "```{r}\n#| echo: false\nx <- 1\n```\n";
+        let tree = parse(input);
+        let offset = input.find("x <- 1").unwrap();
+        let block = find_code_block_at_position(&tree, offset).unwrap();
+        assert!(split_code_cell(&block, input, offset).is_empty());
+
+        let offset = input.find("echo").unwrap();
+        assert!(split_code_cell(&block, input, offset).is_empty());
+    }
+
+    #[test]
+    fn split_not_offered_inside_blockquote() {
+        let input = "> ```r\n> a\n> b\n> ```\n";
+        let tree = parse(input);
+        let offset = input.find("b\n").unwrap();
+        let block = find_code_block_at_position(&tree, offset).unwrap();
+        assert!(split_code_cell(&block, input, offset).is_empty());
+    }
+
+    #[test]
+    fn join_round_trips_split() {
+        let input = "```{r}\n#| label: setup\n#| echo: false\nx <- 1\ny <- 2\n```\n";
+        let split = split_at(input, "y <- 2");
+        let tree = parse(&split);
+        let block = find_code_block_at_position(&tree, 0).unwrap();
+        let next = find_joinable_next_cell(&block).expect("joinable");
+        assert_eq!(
+            apply(&split, &join_code_cells(&block, &next, &split)),
+            input
+        );
+    }
+
+    #[test]
+    fn join_requires_same_language() {
+        let input = "```{r}\nx <- 1\n```\n\n```{python}\ny = 2\n```\n";
+        let tree = parse(input);
+        let block = find_code_block_at_position(&tree, 0).unwrap();
+        assert!(find_joinable_next_cell(&block).is_none());
+    }
+
+    #[test]
+    fn join_refuses_to_drop_distinct_options_or_labels() {
+        let input = "```{r}\nx <- 1\n```\n\n```{r}\n#| echo: false\ny <- 2\n```\n";
+        let tree = parse(input);
+        let block = find_code_block_at_position(&tree, 0).unwrap();
+        assert!(find_joinable_next_cell(&block).is_none());
+
+        let input = "```{r}\nx <- 1\n```\n\n```{r}\n#| label: other\ny <- 2\n```\n";
+        let tree = parse(input);
+        let block = find_code_block_at_position(&tree, 0).unwrap();
+        assert!(find_joinable_next_cell(&block).is_none());
+    }
+
+    #[test]
+    fn join_skips_non_adjacent_cells() {
+        let input = "```{r}\nx <- 1\n```\n\nText.\n\n```{r}\ny <- 2\n```\n";
+        let tree = parse(input);
+        let block = find_code_block_at_position(&tree, 0).unwrap();
+        assert!(find_joinable_next_cell(&block).is_none());
+    }
+}
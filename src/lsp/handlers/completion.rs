@@ -4,7 +4,9 @@ use lsp_types::*;
 use std::path::{Path, PathBuf};
 
 use crate::lsp::global_state::StateSnapshot;
-use crate::syntax::{AstNode, ImageLink, Link, LinkDest, Shortcode, SyntaxKind, SyntaxNode};
+use crate::syntax::{
+    AstNode, ImageLink, Link, LinkDest, ReferenceDefinition, Shortcode, SyntaxKind, SyntaxNode,
+};
 use crate::utils::normalize_anchor_label;
 
 use super::super::conversions::offset_to_position;
@@ -65,6 +67,16 @@ pub(crate) fn completion(
     } else {
         None
     };
+    let reference_label_query = if link_ctx_opt.is_none() && shortcode_ctx_opt.is_none() {
+        reference_label_query_prefix(&text, offset)
+    } else {
+        None
+    };
+    let reference_definitions = if reference_label_query.is_some() {
+        collect_reference_definitions(&root)
+    } else {
+        Vec::new()
+    };
     drop(root);
 
     // Path-completion branches: cursor inside `[text](…)` / `![alt](…)` destination,
@@ -90,6 +102,26 @@ pub(crate) fn completion(
         };
     }
 
+    // Reference label branch: cursor inside the label of `[text][…]`.
+    if let Some(query) = reference_label_query {
+        let mut seen = std::collections::HashSet::new();
+        let items: Vec<CompletionItem> = reference_definitions
+            .into_iter()
+            .filter(|(label, _)| {
+                seen.insert(crate::utils::normalize_label(label)) && matches_query(label, &query)
+            })
+            .map(|(label, url)| CompletionItem {
+                label: label.clone(),
+                kind: Some(CompletionItemKind::REFERENCE),
+                detail: url,
+                insert_text: Some(label),
+                insert_text_format: Some(InsertTextFormat::PLAIN_TEXT),
+                ..Default::default()
+            })
+            .collect();
+        return (!items.is_empty()).then_some(CompletionResponse::Array(items));
+    }
+
     let query = citation_query_prefix(&text, offset)?;
 
     let (salsa_file, salsa_config, doc_path) = match snap.document_state(uri) {
@@ -104,7 +136,7 @@ pub(crate) fn completion(
     }
 
     // Bibliography/citation completions only apply to saved documents.
-    doc_path?;
+    doc_path.as_ref()?;
     let yaml_ok = helpers::is_yaml_frontmatter_valid(parsed_yaml_regions);
     if !yaml_ok {
         return None;
@@ -115,9 +147,30 @@ pub(crate) fn completion(
     let symbol_index =
         crate::salsa::symbol_usage_index(snap.db(), salsa_file, salsa_config).clone();
 
-    let has_crossref_candidates = symbol_index
-        .crossref_declaration_entries()
-        .any(|(key, _)| is_supported_crossref_completion_key(key));
+    let crossrefs_enabled =
+        config.extensions.quarto_crossrefs || config.extensions.bookdown_references;
+    // Crossref labels declared in other project documents (includes, book
+    // chapters) resolve too, so offer them after the document's own labels.
+    let project_crossrefs: Vec<(String, PathBuf)> = if crossrefs_enabled {
+        let mut entries: Vec<(String, PathBuf)> = snap
+            .definition_index_with_includes(uri)
+            .crossref_entries()
+            .filter(|(key, location)| {
+                is_supported_crossref_completion_key(key)
+                    && doc_path.as_deref() != Some(location.path())
+            })
+            .map(|(key, location)| (key.clone(), location.path().to_path_buf()))
+            .collect();
+        entries.sort();
+        entries
+    } else {
+        Vec::new()
+    };
+
+    let has_crossref_candidates = !project_crossrefs.is_empty()
+        || symbol_index
+            .crossref_declaration_entries()
+            .any(|(key, _)| is_supported_crossref_completion_key(key));
     if parse.is_none() && metadata.inline_references.is_empty() && !has_crossref_candidates {
         return None;
     }
@@ -166,7 +219,7 @@ pub(crate) fn completion(
         });
     }
 
-    if config.extensions.quarto_crossrefs || config.extensions.bookdown_references {
+    if crossrefs_enabled {
         for (label, _) in symbol_index.crossref_declaration_entries() {
             if !is_supported_crossref_completion_key(label) {
                 continue;
@@ -186,6 +239,21 @@ pub(crate) fn completion(
                 ..Default::default()
             });
         }
+        for (label, path) in project_crossrefs {
+            if !seen.insert(label.to_lowercase()) || !matches_query(&label, &query) {
+                continue;
+            }
+            items.push(CompletionItem {
+                label: label.clone(),
+                kind: Some(CompletionItemKind::REFERENCE),
+                detail: path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned()),
+                insert_text: Some(label),
+                insert_text_format: Some(InsertTextFormat::PLAIN_TEXT),
+                ..Default::default()
+            });
+        }
     }
 
     if items.is_empty() {
@@ -274,6 +342,29 @@ fn citation_query_prefix(text: &str, offset: usize) -> Option<String> {
     }
 }
 
+/// Text typed so far inside the label of a full reference link or image,
+/// i.e. the cursor sits after `][` in `[text][lab`. Shortcut references
+/// (`[lab`) are not completed: any opening bracket would trigger them.
+fn reference_label_query_prefix(text: &str, offset: usize) -> Option<String> {
+    let line_start = text.get(..offset)?.rfind('\n').map_or(0, |idx| idx + 1);
+    let before = &text[line_start..offset];
+    let open = before.rfind('[')?;
+    let query = &before[open + 1..];
+    if query.contains(']') || query.starts_with('^') || !before[..open].ends_with(']') {
+        return None;
+    }
+    Some(query.to_string())
+}
+
+/// Reference definitions (`[label]: url`) in document order, as written.
+fn collect_reference_definitions(root: &SyntaxNode) -> Vec<(String, Option<String>)> {
+    root.descendants()
+        .filter_map(ReferenceDefinition::cast)
+        .map(|def| (def.label(), def.url()))
+        .filter(|(label, _)| !label.is_empty())
+        .collect()
+}
+
 fn matches_query(candidate: &str, query: &str) -> bool {
     if query.is_empty() {
         return true;
@@ -223,6 +223,28 @@ pub(crate) fn compute_publishes_with_dependents(
 /// Combines the built-in lint plan (+ external linters when `run_external`) with
 /// the project-graph accumulated diagnostics, keyed by file path. Returns one
 /// [`Publish`] per affected document.
+/// Hints for the malformed structure the parser recovered from, such as an
+/// unclosed code fence, so the editor can point at the cause of a surprising
+/// parse.
+fn parse_warning_hints(warnings: &[crate::parser::ParseWarning], text: &str) -> Vec<Diagnostic> {
+    warnings
+        .iter()
+        .map(|warning| Diagnostic {
+            range: Range {
+                start: offset_to_position(text, warning.range.start().into()),
+                end: offset_to_position(text, warning.range.end().into()),
+            },
+            severity: Some(DiagnosticSeverity::HINT),
+            code: Some(lsp_types::NumberOrString::String(
+                warning.kind.name().to_string(),
+            )),
+            source: Some("panache".to_owned()),
+            message: warning.message.clone(),
+            ..Default::default()
+        })
+        .collect()
+}
+
 pub(crate) fn compute_publishes(
     snap: &StateSnapshot,
     uri: &Uri,
@@ -267,10 +289,27 @@ pub(crate) fn compute_publishes(
         panache_diagnostics.sort_by_key(|d| (d.location.line, d.location.column));
     }
 
-    let own_diagnostics: Vec<Diagnostic> = panache_diagnostics
+    // Code blocks whose external formatter failed on their current content,
+    // recorded by the last formatting request.
+    #[cfg(not(target_arch = "wasm32"))]
+    if run_external {
+        let tree =
+            crate::salsa::parsed_tree_root(snap.db(), doc_state.salsa_file, doc_state.salsa_config);
+        let config = doc_state.salsa_config.config(snap.db());
+        let failures = crate::formatter::formatter_failure_diagnostics(&tree, config);
+        if !failures.is_empty() {
+            panache_diagnostics.extend(failures);
+            panache_diagnostics.sort_by_key(|d| (d.location.line, d.location.column));
+        }
+    }
+
+    let mut own_diagnostics: Vec<Diagnostic> = panache_diagnostics
         .iter()
         .map(|d| convert_diagnostic(d, &text))
         .collect();
+    let warnings =
+        crate::salsa::parse_warnings(snap.db(), doc_state.salsa_file, doc_state.salsa_config);
+    own_diagnostics.extend(parse_warning_hints(warnings, &text));
 
     // The document's own path, if it has one (an in-memory buffer does not, so
     // it contributes no project-graph entry and is published only under its URI).
@@ -639,6 +678,17 @@ mod tests {
         ProgressToken::Number(1)
     }
 
+    #[test]
+    fn parse_warning_hints_point_at_the_unclosed_fence() {
+        let text = "Text.\n\n```r\nx <- 1\n";
+        let (_, warnings) = crate::parser::parse_with_diagnostics(text, None);
+        let hints = parse_warning_hints(&warnings, text);
+        assert_eq!(hints.len(), 1);
+        assert_eq!(hints[0].severity, Some(DiagnosticSeverity::HINT));
+        assert_eq!(hints[0].range.start.line, 2);
+        assert_eq!(hints[0].range.end.character, 4);
+    }
+
     fn workspace_item(i: usize) -> WorkspaceDocumentDiagnosticReport {
         let uri: Uri = format!("file:///doc{i}.qmd").parse().unwrap();
         WorkspaceDocumentDiagnosticReport::Full(WorkspaceFullDocumentDiagnosticReport {
@@ -5,6 +5,7 @@ use crate::lsp::uri_ext::UriExt;
 use lsp_types::{DocumentLink, DocumentLinkParams, Range, Uri};
 
 use crate::lsp::global_state::StateSnapshot;
+pub(crate) use crate::syntax::extract_first_destination_token;
 use crate::syntax::{AstNode, AutoLink, ImageLink, Link, Shortcode};
 use crate::utils::normalize_label;
 use serde_json::json;
@@ -325,17 +326,6 @@ pub(crate) fn build_reference_targets(
     out
 }
 
-pub(crate) fn extract_first_destination_token(raw_dest: &str) -> &str {
-    let trimmed = raw_dest.trim();
-    if let Some(rest) = trimmed.strip_prefix('<')
-        && let Some(end) = rest.find('>')
-    {
-        return &rest[..end];
-    }
-
-    trimmed.split_whitespace().next().unwrap_or("")
-}
-
 fn looks_like_email(text: &str) -> bool {
     text.contains('@') && !text.contains(':')
 }
@@ -46,6 +46,7 @@ pub(crate) fn did_create_files(gs: &mut GlobalState, params: CreateFilesParams)
         }
     }
     reload_open_documents_referenced_files(gs);
+    gs.schedule_workspace_link_scan();
     gs.arm_settle();
 }
 
@@ -66,6 +67,7 @@ pub(crate) fn did_delete_files(gs: &mut GlobalState, params: DeleteFilesParams)
         }
     }
     reload_open_documents_referenced_files(gs);
+    gs.schedule_workspace_link_scan();
     gs.arm_settle();
 }
 
@@ -90,5 +92,6 @@ pub(crate) fn did_rename_files(gs: &mut GlobalState, params: RenameFilesParams)
         }
     }
     reload_open_documents_referenced_files(gs);
+    gs.schedule_workspace_link_scan();
     gs.arm_settle();
 }
@@ -5,9 +5,10 @@ use crate::lsp::uri_ext::UriExt;
 use lsp_types::{Range, RenameFilesParams, TextEdit, Uri, WorkspaceEdit};
 use rowan::TextSize;
 
-use crate::lsp::conversions::offset_to_position;
+use crate::link_check::is_external_target;
+use crate::lsp::conversions::{offset_to_position, position_to_offset};
 use crate::lsp::global_state::StateSnapshot;
-use crate::syntax::{AstNode, ImageLink, Link, Shortcode, SyntaxKind};
+use crate::syntax::{AstNode, ImageLink, Link, ReferenceDefinition, Shortcode, SyntaxKind};
 
 use super::document_links::{extract_first_destination_token, resolve_link_target};
 use super::shortcode_args::{ShortcodeKind, shortcode_token_value_span, shortcode_tokens};
@@ -70,6 +71,9 @@ struct DocInput {
     uri: Uri,
     path: PathBuf,
     text: String,
+    /// New location of this document when it is itself the file being moved;
+    /// its own relative targets are then rebased onto that location.
+    moved_to: Option<PathBuf>,
 }
 
 #[derive(Clone)]
@@ -83,44 +87,7 @@ fn candidate_documents_for_scan(snap: &StateSnapshot) -> Vec<DocInput> {
     // Scan every workspace folder; a rename can touch documents in any of them.
     // The `by_path` map dedupes documents reachable from more than one folder.
     for root in &snap.workspace_folders {
-        let has_quarto = root.join("_quarto.yml").exists();
-        let has_bookdown = root.join("_bookdown.yml").exists();
-
-        let candidate_paths = if has_quarto || has_bookdown {
-            let cfg = match crate::config::load(None, root, None, None) {
-                Ok((cfg, _)) => cfg,
-                Err(_) => crate::Config::default(),
-            };
-            crate::includes::find_project_documents(root, &cfg, has_bookdown)
-        } else {
-            discover_standalone_workspace_documents(root)
-        };
-
-        for path in candidate_paths {
-            let Ok(text) = std::fs::read_to_string(&path) else {
-                continue;
-            };
-            let Some(uri) = Uri::from_file_path(&path) else {
-                continue;
-            };
-            by_path.insert(path.clone(), DocInput { uri, path, text });
-        }
-
-        if has_quarto {
-            let quarto_path = root.join("_quarto.yml");
-            if let Ok(text) = std::fs::read_to_string(&quarto_path)
-                && let Some(uri) = Uri::from_file_path(&quarto_path)
-            {
-                by_path.insert(
-                    quarto_path.clone(),
-                    DocInput {
-                        uri,
-                        path: quarto_path,
-                        text,
-                    },
-                );
-            }
-        }
+        collect_root_documents(root, &mut by_path);
     }
 
     let states = snap.document_map.values().cloned().collect::<Vec<_>>();
@@ -132,7 +99,15 @@ fn candidate_documents_for_scan(snap: &StateSnapshot) -> Vec<DocInput> {
         let Some(uri) = Uri::from_file_path(&path) else {
             continue;
         };
-        by_path.insert(path.clone(), DocInput { uri, path, text });
+        by_path.insert(
+            path.clone(),
+            DocInput {
+                uri,
+                path,
+                text,
+                moved_to: None,
+            },
+        );
     }
 
     let mut docs = by_path.into_values().collect::<Vec<_>>();
@@ -140,6 +115,112 @@ fn candidate_documents_for_scan(snap: &StateSnapshot) -> Vec<DocInput> {
     docs
 }
 
+fn collect_root_documents(root: &Path, by_path: &mut HashMap<PathBuf, DocInput>) {
+    let has_quarto = root.join("_quarto.yml").exists();
+    let has_bookdown = root.join("_bookdown.yml").exists();
+
+    let candidate_paths = if has_quarto || has_bookdown {
+        let cfg = match crate::config::load(None, root, None, None) {
+            Ok((cfg, _)) => cfg,
+            Err(_) => crate::Config::default(),
+        };
+        crate::includes::find_project_documents(root, &cfg, has_bookdown)
+    } else {
+        discover_standalone_workspace_documents(root)
+    };
+
+    let mut paths = candidate_paths;
+    if has_quarto {
+        paths.push(root.join("_quarto.yml"));
+    }
+    for path in paths {
+        let Ok(text) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let Some(uri) = Uri::from_file_path(&path) else {
+            continue;
+        };
+        by_path.insert(
+            path.clone(),
+            DocInput {
+                uri,
+                path,
+                text,
+                moved_to: None,
+            },
+        );
+    }
+}
+
+/// A text replacement planned by [`plan_file_rename`], as a byte range into
+/// the current contents of `path`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileRenameEdit {
+    pub path: PathBuf,
+    pub range: std::ops::Range<usize>,
+    pub new_text: String,
+}
+
+/// Plans the edits that keep the documents under `root` pointing at
+/// `old_path` after it is moved to `new_path`: links, images, path-taking
+/// shortcodes, frontmatter paths and `_quarto.yml` entries. Relative targets
+/// inside the moved document itself are rebased onto its new location; those
+/// edits are reported against `old_path`.
+///
+/// Both paths must be absolute. This is the offline counterpart of the
+/// `workspace/willRenameFiles` handler and scans the same document set.
+pub fn plan_file_rename(root: &Path, old_path: &Path, new_path: &Path) -> Vec<FileRenameEdit> {
+    let mut by_path: HashMap<PathBuf, DocInput> = HashMap::new();
+    collect_root_documents(root, &mut by_path);
+    if !by_path.contains_key(old_path)
+        && let Ok(text) = std::fs::read_to_string(old_path)
+        && let Some(uri) = Uri::from_file_path(old_path)
+    {
+        by_path.insert(
+            old_path.to_path_buf(),
+            DocInput {
+                uri,
+                path: old_path.to_path_buf(),
+                text,
+                moved_to: None,
+            },
+        );
+    }
+    if let Some(doc) = by_path.get_mut(old_path)
+        && !is_quarto_project_config(old_path)
+    {
+        doc.moved_to = Some(new_path.to_path_buf());
+    }
+
+    let (Some(old_uri), Some(new_uri)) =
+        (Uri::from_file_path(old_path), Uri::from_file_path(new_path))
+    else {
+        return Vec::new();
+    };
+
+    let mut docs = by_path.into_values().collect::<Vec<_>>();
+    docs.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let mut out = Vec::new();
+    for candidate in rename_candidates_for_pair(&docs, &old_uri, &new_uri) {
+        let Some(doc) = docs.iter().find(|doc| doc.uri == candidate.uri) else {
+            continue;
+        };
+        let (Some(start), Some(end)) = (
+            position_to_offset(&doc.text, candidate.edit.range.start),
+            position_to_offset(&doc.text, candidate.edit.range.end),
+        ) else {
+            continue;
+        };
+        out.push(FileRenameEdit {
+            path: doc.path.clone(),
+            range: start..end,
+            new_text: candidate.edit.new_text,
+        });
+    }
+    out
+}
+
 fn discover_standalone_workspace_documents(root: &Path) -> Vec<PathBuf> {
     let mut out = Vec::new();
     let mut dirs = vec![root.to_path_buf()];
@@ -225,8 +306,7 @@ fn rename_candidates_for_links(
             if let Some(dest) = link.dest() {
                 let raw = dest.url();
                 if let Some(edit) = candidate_edit_for_destination(
-                    &doc.path,
-                    &doc.text,
+                    doc,
                     old_uri,
                     new_uri,
                     dest.syntax().text_range(),
@@ -246,8 +326,7 @@ fn rename_candidates_for_links(
         {
             let raw = dest.url();
             if let Some(edit) = candidate_edit_for_destination(
-                &doc.path,
-                &doc.text,
+                doc,
                 old_uri,
                 new_uri,
                 dest.syntax().text_range(),
@@ -260,6 +339,56 @@ fn rename_candidates_for_links(
             }
         }
 
+        if let Some(definition) = ReferenceDefinition::cast(node.clone())
+            && let Some(url) = definition
+                .syntax()
+                .children()
+                .find(|child| child.kind() == SyntaxKind::REFERENCE_URL)
+            && let Some(edit) = candidate_edit_for_destination(
+                doc,
+                old_uri,
+                new_uri,
+                url.text_range(),
+                &url.text().to_string(),
+            )
+        {
+            out.push(CandidateEdit {
+                uri: doc.uri.clone(),
+                edit,
+            });
+        }
+
+        if !matches!(
+            node.kind(),
+            SyntaxKind::LINK_DEST | SyntaxKind::REFERENCE_URL
+        ) {
+            for token in node
+                .children_with_tokens()
+                .filter_map(|element| element.into_token())
+                .filter(|token| token.kind() == SyntaxKind::TEXT)
+            {
+                let token_start = token.text_range().start();
+                for (start, end) in angle_bracket_spans(token.text()) {
+                    let range = rowan::TextRange::new(
+                        token_start + TextSize::from(start as u32),
+                        token_start + TextSize::from(end as u32),
+                    );
+                    if let Some(edit) = candidate_edit_for_destination(
+                        doc,
+                        old_uri,
+                        new_uri,
+                        range,
+                        &token.text()[start..end],
+                    ) {
+                        out.push(CandidateEdit {
+                            uri: doc.uri.clone(),
+                            edit,
+                        });
+                    }
+                }
+            }
+        }
+
         if let Some(shortcode) = Shortcode::cast(node)
             && let Some(edit) = candidate_edit_for_shortcode_path(doc, &shortcode, old_uri, new_uri)
         {
@@ -272,6 +401,32 @@ fn rename_candidates_for_links(
     out
 }
 
+/// Byte spans of `<path>` autolinks in plain text, brackets included.
+///
+/// Links with a scheme (`<https://…>`) parse as `AUTO_LINK` nodes and never
+/// point at a workspace file; a bare relative path in angle brackets stays
+/// text in the CST, so it is found here.
+fn angle_bracket_spans(text: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut rest_start = 0;
+    while let Some(open) = text[rest_start..].find('<') {
+        let start = rest_start + open;
+        let Some(len) = text[start + 1..].find(['<', '>']) else {
+            break;
+        };
+        let end = start + 1 + len;
+        let inner = &text[start + 1..end];
+        if text.as_bytes()[end] == b'>' && !inner.is_empty() && !inner.contains(char::is_whitespace)
+        {
+            spans.push((start, end + 1));
+            rest_start = end + 1;
+        } else {
+            rest_start = end;
+        }
+    }
+    spans
+}
+
 /// Rewrites file paths declared in a document's own YAML frontmatter
 /// (`bibliography`, `csl`, `css`) when the referenced file is renamed.
 ///
@@ -380,9 +535,7 @@ fn push_frontmatter_edit(
         TextSize::from(absolute_end as u32),
     );
     let raw = &doc.text[absolute_start..absolute_end];
-    if let Some(edit) =
-        candidate_edit_for_destination(&doc.path, &doc.text, old_uri, new_uri, range, raw)
-    {
+    if let Some(edit) = candidate_edit_for_destination(doc, old_uri, new_uri, range, raw) {
         out.push(CandidateEdit {
             uri: doc.uri.clone(),
             edit,
@@ -414,7 +567,7 @@ fn candidate_edit_for_shortcode_path(
     let absolute_end = content_node.text_range().start() + TextSize::from(value_end as u32);
     let range = rowan::TextRange::new(absolute_start, absolute_end);
 
-    candidate_edit_for_destination(&doc.path, &doc.text, old_uri, new_uri, range, raw_target)
+    candidate_edit_for_destination(doc, old_uri, new_uri, range, raw_target)
 }
 
 fn rename_candidates_for_quarto_config_yaml(
@@ -518,9 +671,9 @@ fn rename_candidates_for_quarto_config_yaml(
                             TextSize::from(absolute_end as u32),
                         );
                         let raw = &doc.text[absolute_start..absolute_end];
-                        if let Some(edit) = candidate_edit_for_destination(
-                            &doc.path, &doc.text, old_uri, new_uri, range, raw,
-                        ) {
+                        if let Some(edit) =
+                            candidate_edit_for_destination(doc, old_uri, new_uri, range, raw)
+                        {
                             out.push(CandidateEdit {
                                 uri: doc.uri.clone(),
                                 edit,
@@ -541,9 +694,9 @@ fn rename_candidates_for_quarto_config_yaml(
                             TextSize::from(absolute_end as u32),
                         );
                         let raw = &doc.text[absolute_start..absolute_end];
-                        if let Some(edit) = candidate_edit_for_destination(
-                            &doc.path, &doc.text, old_uri, new_uri, range, raw,
-                        ) {
+                        if let Some(edit) =
+                            candidate_edit_for_destination(doc, old_uri, new_uri, range, raw)
+                        {
                             out.push(CandidateEdit {
                                 uri: doc.uri.clone(),
                                 edit,
@@ -562,9 +715,9 @@ fn rename_candidates_for_quarto_config_yaml(
                             TextSize::from(absolute_end as u32),
                         );
                         let raw = &doc.text[absolute_start..absolute_end];
-                        if let Some(edit) = candidate_edit_for_destination(
-                            &doc.path, &doc.text, old_uri, new_uri, range, raw,
-                        ) {
+                        if let Some(edit) =
+                            candidate_edit_for_destination(doc, old_uri, new_uri, range, raw)
+                        {
                             out.push(CandidateEdit {
                                 uri: doc.uri.clone(),
                                 edit,
@@ -593,9 +746,7 @@ fn rename_candidates_for_quarto_config_yaml(
                 TextSize::from(absolute_end as u32),
             );
             let raw = &doc.text[absolute_start..absolute_end];
-            if let Some(edit) =
-                candidate_edit_for_destination(&doc.path, &doc.text, old_uri, new_uri, range, raw)
-            {
+            if let Some(edit) = candidate_edit_for_destination(doc, old_uri, new_uri, range, raw) {
                 out.push(CandidateEdit {
                     uri: doc.uri.clone(),
                     edit,
@@ -620,9 +771,7 @@ fn rename_candidates_for_quarto_config_yaml(
                 TextSize::from(absolute_end as u32),
             );
             let raw = &doc.text[absolute_start..absolute_end];
-            if let Some(edit) =
-                candidate_edit_for_destination(&doc.path, &doc.text, old_uri, new_uri, range, raw)
-            {
+            if let Some(edit) = candidate_edit_for_destination(doc, old_uri, new_uri, range, raw) {
                 out.push(CandidateEdit {
                     uri: doc.uri.clone(),
                     edit,
@@ -647,9 +796,7 @@ fn rename_candidates_for_quarto_config_yaml(
                 TextSize::from(absolute_end as u32),
             );
             let raw = &doc.text[absolute_start..absolute_end];
-            if let Some(edit) =
-                candidate_edit_for_destination(&doc.path, &doc.text, old_uri, new_uri, range, raw)
-            {
+            if let Some(edit) = candidate_edit_for_destination(doc, old_uri, new_uri, range, raw) {
                 out.push(CandidateEdit {
                     uri: doc.uri.clone(),
                     edit,
@@ -732,8 +879,7 @@ fn extract_shortcode_path_argument_span(content: &str) -> Option<(usize, usize)>
 }
 
 fn candidate_edit_for_destination(
-    doc_path: &Path,
-    doc_text: &str,
+    doc: &DocInput,
     old_uri: &Uri,
     new_uri: &Uri,
     range: rowan::TextRange,
@@ -743,21 +889,24 @@ fn candidate_edit_for_destination(
     if raw_target.is_empty() {
         return None;
     }
-    let resolved = resolve_link_target(raw_target, Some(doc_path), None)?;
+    let resolved = resolve_link_target(raw_target, Some(&doc.path), None)?;
     let resolved_path = resolved.to_file_path()?;
     let old_path = old_uri.to_file_path()?;
-    if resolved_path.as_ref() != old_path.as_ref() {
-        return None;
-    }
-
     let new_path = new_uri.to_file_path()?;
-    let replacement = rewrite_destination_target(doc_path, raw_target, &old_path, &new_path)?;
+    let replacement = if let Some(moved_to) = &doc.moved_to {
+        rebase_destination_target(moved_to, raw_target, &resolved_path, &old_path, &new_path)?
+    } else {
+        if normalize_lexically(&resolved_path) != normalize_lexically(&old_path) {
+            return None;
+        }
+        rewrite_destination_target(&doc.path, raw_target, &old_path, &new_path)?
+    };
     if replacement == raw_target {
         return None;
     }
 
-    let start = offset_to_position(doc_text, range.start().into());
-    let end = offset_to_position(doc_text, range.end().into());
+    let start = offset_to_position(&doc.text, range.start().into());
+    let end = offset_to_position(&doc.text, range.end().into());
 
     let replaced_full = raw_destination.replacen(raw_target, &replacement, 1);
     Some(TextEdit {
@@ -766,6 +915,61 @@ fn candidate_edit_for_destination(
     })
 }
 
+/// Rewrites a relative target of the document being moved so it still points
+/// at the same file from the document's new location (`moved_to`). Targets
+/// that pointed at the moved document itself follow it to `new_path`.
+fn rebase_destination_target(
+    moved_to: &Path,
+    raw_target: &str,
+    resolved_path: &Path,
+    old_path: &Path,
+    new_path: &Path,
+) -> Option<String> {
+    if is_external_target(raw_target) || raw_target.starts_with('#') {
+        return None;
+    }
+    let (path_part, fragment) = split_fragment(raw_target);
+    let resolved_path = normalize_lexically(resolved_path);
+    let points_at_self = resolved_path == normalize_lexically(old_path);
+    let rebuilt = if Path::new(path_part).is_absolute() {
+        if !points_at_self {
+            return None;
+        }
+        new_path.to_string_lossy().to_string()
+    } else {
+        let target = if points_at_self {
+            new_path.to_path_buf()
+        } else {
+            resolved_path
+        };
+        let base = moved_to.parent().unwrap_or_else(|| Path::new("."));
+        relative_path_from(&normalize_lexically(base), &normalize_lexically(&target))?
+    };
+    Some(if let Some(fragment) = fragment {
+        format!("{rebuilt}#{fragment}")
+    } else {
+        rebuilt
+    })
+}
+
+/// Resolves `.` and `..` components without touching the filesystem, so
+/// paths to files that do not exist (yet) can still be compared.
+fn normalize_lexically(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => {
+                if !out.pop() {
+                    out.push("..");
+                }
+            }
+            other => out.push(other.as_os_str()),
+        }
+    }
+    out
+}
+
 fn rewrite_destination_target(
     doc_path: &Path,
     raw_target: &str,
@@ -801,31 +1005,6 @@ fn split_fragment(target: &str) -> (&str, Option<&str>) {
     }
 }
 
-fn is_external_target(target: &str) -> bool {
-    let t = target.trim();
-    if t.contains('@') && !t.contains(':') {
-        return true;
-    }
-    let Some(idx) = t.find(':') else {
-        return false;
-    };
-    if idx == 1 {
-        let bytes = t.as_bytes();
-        if bytes.get(2).is_some_and(|b| *b == b'/' || *b == b'\\') {
-            return false;
-        }
-    }
-    let scheme = &t[..idx];
-    if scheme.is_empty() {
-        return false;
-    }
-    let mut chars = scheme.chars();
-    if !chars.next().is_some_and(|ch| ch.is_ascii_alphabetic()) {
-        return false;
-    }
-    chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '+' || ch == '-' || ch == '.')
-}
-
 fn relative_path_from(base: &Path, target: &Path) -> Option<String> {
     let base_components = base.components().collect::<Vec<_>>();
     let target_components = target.components().collect::<Vec<_>>();
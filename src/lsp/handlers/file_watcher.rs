@@ -162,6 +162,7 @@ pub(crate) fn did_change_watched_files(gs: &mut GlobalState, params: DidChangeWa
     // text); arm the settle so the all-docs pass re-lints over the fresh state
     // even when no document was flagged for external linters above.
     if !changed_paths.is_empty() {
+        gs.schedule_workspace_link_scan();
         gs.arm_settle();
     }
 }
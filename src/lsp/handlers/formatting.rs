@@ -1,4 +1,4 @@
-//! `textDocument/formatting` and `rangeFormatting`.
+//! `textDocument/formatting`, `rangeFormatting`, and `rangesFormatting`.
 //!
 //! Runs on a [`TaskPool`](crate::lsp::task_pool) worker over a
 //! [`StateSnapshot`]; formatting itself is the synchronous [`crate::format`],
@@ -6,8 +6,10 @@
 
 use lsp_types::{
     DocumentFormattingParams, DocumentOnTypeFormattingParams, DocumentRangeFormattingParams,
-    Position, Range, TextEdit,
+    FormattingOptions, MessageType, Range, TextDocumentIdentifier, TextEdit, Uri,
+    WorkDoneProgressParams,
 };
+use serde::{Deserialize, Serialize};
 
 use super::super::conversions::{offset_to_position, position_to_offset};
 use super::super::helpers::is_uri_excluded;
@@ -47,29 +49,53 @@ pub(crate) fn format_document(
     // Reuse the salsa-cached parse (the one hover/symbols read) instead of
     // parsing afresh, saving a parse per format request. Falls back to a fresh
     // parse only if the document somehow isn't open.
-    let formatted = match snap.parsed_tree(&uri) {
-        Some(tree) => crate::format_with_tree(&text, &tree, &config, None),
-        None => crate::format(&text, Some(config), None),
+    let formatted = with_timings(snap, &uri, || match snap.parsed_tree(&uri) {
+        Some(tree) => crate::try_format_with_tree(&text, &tree, &config, None),
+        None => crate::try_format(&text, Some(config), None),
+    });
+    let formatted = match formatted {
+        Ok(formatted) => formatted,
+        Err(err) => {
+            log::warn!("Failed to format {}: {err}", uri.as_str());
+            return None;
+        }
     };
 
     if formatted == text {
         return None;
     }
 
-    // Replace the entire document; use text.len() to include trailing newlines.
-    let end_position = offset_to_position(&text, text.len());
-    let range = Range {
-        start: Position {
-            line: 0,
-            character: 0,
-        },
-        end: end_position,
+    Some(minimal_edits(&text, &formatted))
+}
+
+/// Run `format`, logging the time it spent in each phase to the client when it
+/// traces verbosely (`window/logMessage`).
+fn with_timings<T>(snap: &StateSnapshot, uri: &Uri, format: impl FnOnce() -> T) -> T {
+    let Some(sender) = snap.timings_log.as_ref() else {
+        return format();
     };
+    let (value, timings) = crate::timings::record(format);
+    sender.log_message(
+        MessageType::LOG,
+        format!("Formatted {}: {timings}", uri.as_str()),
+    );
+    value
+}
 
-    Some(vec![TextEdit {
-        range,
-        new_text: formatted,
-    }])
+/// The [`crate::edits::diff`] of `text` and `formatted` as LSP edits, so the
+/// client keeps cursors, folds, marks, and undo steps in the untouched parts of
+/// the document.
+fn minimal_edits(text: &str, formatted: &str) -> Vec<TextEdit> {
+    crate::edits::diff(text, formatted)
+        .into_iter()
+        .map(|edit| TextEdit {
+            range: Range {
+                start: offset_to_position(text, edit.range.start),
+                end: offset_to_position(text, edit.range.end),
+            },
+            new_text: edit.new_text,
+        })
+        .collect()
 }
 
 /// Handle `textDocument/onTypeFormatting`.
@@ -147,13 +173,7 @@ pub(crate) fn format_range(
         }
     };
 
-    // Convert LSP range (0-indexed lines, end-exclusive) to panache range
-    // (1-indexed, inclusive).
-    let start_line = (range.start.line + 1) as usize;
-    let mut end_line = (range.end.line + 1) as usize;
-    if range.end.character == 0 && range.end.line > range.start.line {
-        end_line = range.end.line as usize;
-    }
+    let (start_line, end_line) = line_range(range);
 
     let _ = (
         position_to_offset(&text, range.start),
@@ -167,7 +187,16 @@ pub(crate) fn format_range(
         .unwrap_or_else(|| parser::parse(&text, Some(config.clone())));
     let expanded_range =
         range_utils::expand_line_range_to_blocks(&tree, &text, start_line, end_line);
-    let formatted = crate::format_with_tree(&text, &tree, &config, Some((start_line, end_line)));
+    let formatted = with_timings(snap, &uri, || {
+        crate::try_format_with_tree(&text, &tree, &config, Some((start_line, end_line)))
+    });
+    let formatted = match formatted {
+        Ok(formatted) => formatted,
+        Err(err) => {
+            log::warn!("Failed to format {}: {err}", uri.as_str());
+            return None;
+        }
+    };
 
     if formatted.is_empty() || formatted == text {
         return None;
@@ -185,3 +214,79 @@ pub(crate) fn format_range(
         new_text: formatted,
     }])
 }
+
+/// Convert an LSP range (0-indexed lines, end-exclusive) to a panache line
+/// range (1-indexed, inclusive).
+fn line_range(range: Range) -> (usize, usize) {
+    let start_line = (range.start.line + 1) as usize;
+    let mut end_line = (range.end.line + 1) as usize;
+    if range.end.character == 0 && range.end.line > range.start.line {
+        end_line = range.end.line as usize;
+    }
+    (start_line, end_line)
+}
+
+/// `textDocument/rangesFormatting` (LSP 3.18), which lsp-types only models
+/// behind its `proposed` feature.
+pub(crate) enum RangesFormatting {}
+
+impl lsp_types::request::Request for RangesFormatting {
+    type Params = DocumentRangesFormattingParams;
+    type Result = Option<Vec<TextEdit>>;
+    const METHOD: &'static str = "textDocument/rangesFormatting";
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DocumentRangesFormattingParams {
+    pub text_document: TextDocumentIdentifier,
+    pub ranges: Vec<Range>,
+    pub options: FormattingOptions,
+    #[serde(flatten)]
+    pub work_done_progress_params: WorkDoneProgressParams,
+}
+
+/// Handle `textDocument/rangesFormatting`: format the blocks under every
+/// range in one pass and answer with minimal edits. Like
+/// [`format_range`], it bypasses `exclude`.
+pub(crate) fn format_ranges(
+    snap: &StateSnapshot,
+    params: DocumentRangesFormattingParams,
+) -> Option<Vec<TextEdit>> {
+    let uri = params.text_document.uri;
+    log::debug!(
+        "format_ranges uri={} ranges={}",
+        uri.as_str(),
+        params.ranges.len()
+    );
+
+    let text = snap.document_content(&uri)?;
+    // Refuse to range-format under a broken config (see `format_document`).
+    let config = match crate::lsp::config::try_load_config(&snap.workspace_folders, Some(&uri)) {
+        Ok((config, _source)) => config,
+        Err(err) => {
+            log::warn!("Refusing to range-format {}: {err}", uri.as_str());
+            return None;
+        }
+    };
+
+    let ranges: Vec<(usize, usize)> = params.ranges.into_iter().map(line_range).collect();
+    let tree = snap
+        .parsed_tree(&uri)
+        .unwrap_or_else(|| parser::parse(&text, Some(config.clone())));
+    let formatted = with_timings(snap, &uri, || {
+        crate::try_format_ranges_with_tree(&text, &tree, &config, &ranges)
+    });
+    let formatted = match formatted {
+        Ok(formatted) => formatted,
+        Err(err) => {
+            log::warn!("Failed to format {}: {err}", uri.as_str());
+            return None;
+        }
+    };
+
+    if formatted == text {
+        return None;
+    }
+    Some(minimal_edits(&text, &formatted))
+}
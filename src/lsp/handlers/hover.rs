@@ -2,6 +2,7 @@
 //!
 //! Provides hover information for:
 //! - Footnote references: `[^id]` → shows footnote content from `[^id]: content`
+//! - Reference links: `[text][ref]` → shows the URL and title from `[ref]: url "title"`
 
 use std::collections::HashSet;
 use std::path::Path;
@@ -203,7 +204,45 @@ pub(crate) fn hover(snap: &StateSnapshot, params: HoverParams) -> Option<Hover>
         }
     };
 
-    let label = pending_footnote?;
+    let Some(label) = pending_footnote else {
+        // Reference links whose definition is neither a heading anchor nor a
+        // local document fall back to previewing the definition's URL.
+        let Some(SymbolTarget::Reference {
+            label,
+            is_footnote: false,
+        }) = target.as_ref()
+        else {
+            return None;
+        };
+        let on_definition = {
+            let root = ctx.syntax_root();
+            helpers::find_node_at_offset(&root, offset).is_some_and(|node| {
+                node.ancestors()
+                    .any(|ancestor| ReferenceDefinition::can_cast(ancestor.kind()))
+            })
+        };
+        if on_definition {
+            return None;
+        }
+        let doc_indices = crate::lsp::navigation::project_symbol_documents(
+            snap.db(),
+            salsa_file,
+            salsa_config,
+            &doc_path,
+            uri,
+            &content_for_offset,
+        );
+        let markdown = doc_indices
+            .iter()
+            .find_map(|doc| reference_definition_hover_markdown(doc, label))?;
+        return Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: markdown,
+            }),
+            range: None,
+        });
+    };
 
     // Cross-document footnote lookup via symbol usage index.
     let doc_indices = crate::lsp::navigation::project_symbol_documents(
@@ -223,8 +262,8 @@ pub(crate) fn hover(snap: &StateSnapshot, params: HoverParams) -> Option<Hover>
             continue;
         };
 
-        let tree = crate::parse(&doc.text, None);
-        let Some(footnote_def) = tree
+        let Some(footnote_def) = doc
+            .tree
             .descendants()
             .filter_map(FootnoteDefinition::cast)
             .find(|def| def.syntax().text_range() == *range)
@@ -265,9 +304,8 @@ fn equation_hover_markdown(
         return None;
     }
 
-    let tree = crate::parse(&doc.text, None);
     for declaration in declaration_ranges {
-        let Some(math) = display_math_for_declaration(&tree, declaration) else {
+        let Some(math) = display_math_for_declaration(&doc.tree, declaration) else {
             continue;
         };
         let content = math.content();
@@ -286,8 +324,7 @@ fn section_hover_markdown(
     label: &str,
 ) -> Option<String> {
     let heading_range = first_heading_definition_range(&doc.symbol_index, label)?;
-    let tree = crate::parse(&doc.text, None);
-    let document = Document::cast(tree)?;
+    let document = Document::cast(doc.tree.clone())?;
 
     let blocks: Vec<_> = document.blocks().collect();
     let heading_idx = blocks.iter().position(|node| {
@@ -322,9 +359,9 @@ fn reference_definition_heading_target(
     doc: &crate::lsp::navigation::IndexedDocument,
     label: &str,
 ) -> Option<String> {
-    let tree = crate::parse(&doc.text, None);
     let normalized = normalize_label(label);
-    let def = tree
+    let def = doc
+        .tree
         .descendants()
         .filter_map(ReferenceDefinition::cast)
         .find(|def| normalize_label(&def.label()) == normalized)?;
@@ -332,6 +369,28 @@ fn reference_definition_heading_target(
     heading_label_from_destination(&destination)
 }
 
+fn reference_definition_hover_markdown(
+    doc: &crate::lsp::navigation::IndexedDocument,
+    label: &str,
+) -> Option<String> {
+    let normalized = normalize_label(label);
+    let def = doc
+        .tree
+        .descendants()
+        .filter_map(ReferenceDefinition::cast)
+        .find(|def| normalize_label(&def.label()) == normalized)?;
+    let url = def.url()?;
+    let mut markdown = format!("`{url}`");
+    if let Some(title) = def.title().filter(|title| !title.trim().is_empty()) {
+        markdown.push_str("\n\n");
+        markdown.push_str(&crop_preview(
+            &normalize_preview_text(&title),
+            HOVER_PREVIEW_MAX_CHARS,
+        ));
+    }
+    Some(markdown)
+}
+
 fn heading_label_from_destination(destination: &str) -> Option<String> {
     let mut target = destination.trim();
     if let Some(rest) = target.strip_prefix('<')
@@ -461,8 +520,14 @@ fn linked_document_hover_markdown(
     if target_path == doc_path {
         return None;
     }
-    let target_text = std::fs::read_to_string(&target_path).ok()?;
-    linked_doc_preview_markdown(&target_text, &target_path)
+    let tree = match crate::salsa::Db::file_text(db, target_path.to_path_buf()) {
+        Some(file) => crate::salsa::parsed_tree_root(db, file, salsa_config),
+        None => {
+            let target_text = std::fs::read_to_string(&target_path).ok()?;
+            crate::lsp::navigation::parse_with_config(&target_text, salsa_config.config(db))
+        }
+    };
+    linked_doc_preview_markdown(tree, &target_path)
 }
 
 fn hovered_link_target(root: &crate::syntax::SyntaxNode, offset: usize) -> Option<String> {
@@ -536,8 +601,10 @@ fn is_markdown_family_path(path: &Path) -> bool {
     matches!(ext, "md" | "qmd" | "Rmd" | "markdown")
 }
 
-fn linked_doc_preview_markdown(target_text: &str, target_path: &Path) -> Option<String> {
-    let tree = crate::parse(target_text, None);
+fn linked_doc_preview_markdown(
+    tree: crate::syntax::SyntaxNode,
+    target_path: &Path,
+) -> Option<String> {
     let document = Document::cast(tree)?;
     let blocks: Vec<_> = document.blocks().collect();
     let title = blocks
@@ -11,6 +11,7 @@ use std::collections::HashSet;
 
 use lsp_types::{Range, TextEdit};
 
+use crate::slug;
 use crate::syntax::{AstNode, Link, ReferenceDefinition, SyntaxNode};
 use crate::utils::normalize_label;
 
@@ -240,9 +241,9 @@ fn strip_title_delimiters(raw: &str) -> String {
 /// and finally to a numeric suffix when the slug would be empty or collides
 /// with an existing label.
 fn generate_label(text: &str, url: &str, existing: &HashSet<String>) -> String {
-    let mut base = slugify(text);
+    let mut base = slug::pandoc(text);
     if base.is_empty() {
-        base = slugify(url_host(url));
+        base = slug::pandoc(url_host(url));
     }
     if base.is_empty() {
         base = "link".to_string();
@@ -259,24 +260,6 @@ fn generate_label(text: &str, url: &str, existing: &HashSet<String>) -> String {
     unreachable!("the integer range is exhausted before a free label is found")
 }
 
-fn slugify(input: &str) -> String {
-    let mut out = String::with_capacity(input.len());
-    let mut prev_dash = false;
-    for ch in input.chars() {
-        if ch.is_ascii_alphanumeric() {
-            out.push(ch.to_ascii_lowercase());
-            prev_dash = false;
-        } else if !prev_dash && !out.is_empty() {
-            out.push('-');
-            prev_dash = true;
-        }
-    }
-    while out.ends_with('-') {
-        out.pop();
-    }
-    out
-}
-
 fn url_host(url: &str) -> &str {
     let after_scheme = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
     after_scheme.split('/').next().unwrap_or("")
@@ -35,5 +35,6 @@ pub(crate) fn did_change_workspace_folders(
     }
 
     crate::lsp::documents::reload_open_documents_config(gs);
+    gs.schedule_workspace_link_scan();
     gs.arm_settle();
 }
@@ -49,14 +49,7 @@ pub(crate) fn is_uri_excluded(
         .unwrap_or_else(|| path.file_name().map(PathBuf::from).unwrap_or(path.clone()));
     let rel_str = rel.to_string_lossy().replace('\\', "/");
 
-    let mut patterns = cfg.exclude.clone().unwrap_or_else(|| {
-        crate::config::DEFAULT_EXCLUDE_PATTERNS
-            .iter()
-            .map(|s| s.to_string())
-            .collect()
-    });
-    patterns.extend(cfg.extend_exclude.iter().cloned());
-
+    let patterns = crate::config::effective_exclude_patterns(cfg);
     match crate::config::GlobMatcher::build(&patterns) {
         Ok(matcher) => matcher.is_match(&rel_str),
         Err(_) => false,
@@ -174,10 +167,27 @@ pub(crate) fn extract_reference_target(node: &SyntaxNode) -> Option<(String, boo
         return Some(reference);
     }
 
-    if let Some(link) = Link::cast(node.clone())
-        && let Some(link_ref) = link.reference()
-    {
-        return extract_reference_label(link_ref.syntax());
+    if let Some(link) = Link::cast(node.clone()) {
+        if let Some(link_ref) = link.reference() {
+            let label = normalize_label(&link_ref.label());
+            if !label.is_empty() {
+                return Some((label, false));
+            }
+        }
+        // Shortcut (`[label]`) and collapsed (`[label][]`) references take
+        // their label from the raw link text, as the definition map does.
+        if link.dest().is_none()
+            && !link
+                .syntax()
+                .ancestors()
+                .any(|ancestor| ancestor.kind() == SyntaxKind::REFERENCE_DEFINITION)
+        {
+            return link
+                .text()
+                .map(|text| normalize_label(&text.raw_label()))
+                .filter(|label| !label.is_empty())
+                .map(|label| (label, false));
+        }
     }
 
     if let Some(image) = ImageLink::cast(node.clone())
@@ -511,13 +521,8 @@ fn heading_target_from_link(link: &Link) -> Option<String> {
         return (!id.is_empty()).then_some(id);
     }
 
-    if link.reference().is_none()
-        && let Some(text) = link.text()
-    {
-        let label = normalize_label(&text.text_content());
-        return (!label.is_empty()).then_some(label);
-    }
-
+    // A shortcut `[label]` only parses as `LINK` when a reference definition
+    // matches it; implicit heading references stay `UNRESOLVED_REFERENCE`.
     None
 }
 
@@ -12,6 +12,9 @@ pub(crate) struct IndexedDocument {
     pub(crate) uri: Uri,
     pub(crate) text: String,
     pub(crate) symbol_index: crate::salsa::SymbolUsageIndex,
+    /// The salsa-cached CST `symbol_index` was built from, so its ranges
+    /// match this tree's nodes.
+    pub(crate) tree: crate::syntax::SyntaxNode,
 }
 
 #[derive(Clone)]
@@ -132,6 +135,7 @@ pub(crate) fn indexed_documents_from_inputs(
         };
 
         let symbol_index = crate::salsa::symbol_usage_index(db, file, salsa_config).clone();
+        let tree = crate::salsa::parsed_tree_root(db, file, salsa_config);
         let uri = if path == doc_path {
             current_uri.clone()
         } else {
@@ -141,6 +145,7 @@ pub(crate) fn indexed_documents_from_inputs(
             uri,
             text,
             symbol_index,
+            tree,
         });
     }
 
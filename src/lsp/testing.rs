@@ -145,6 +145,12 @@ impl LspTester {
         self.gs.on_initialize(params);
     }
 
+    /// Send the `initialized` notification (watcher registration and the
+    /// background workspace link scan).
+    pub fn initialized(&mut self) {
+        self.gs.on_initialized();
+    }
+
     /// Initialize with multiple workspace folders (multi-root), exercising
     /// `workspace/didChangeWorkspaceFolders` and per-document config resolution.
     pub fn initialize_with_folders(&mut self, root_uris: &[&str]) {
@@ -314,6 +320,27 @@ impl LspTester {
         handlers::formatting::format_range(&self.snapshot(), params)
     }
 
+    /// `textDocument/rangesFormatting` with `(start_line, start_char,
+    /// end_line, end_char)` ranges.
+    pub fn format_ranges(
+        &self,
+        uri: &str,
+        ranges: &[(u32, u32, u32, u32)],
+    ) -> Option<Vec<TextEdit>> {
+        let params = handlers::formatting::DocumentRangesFormattingParams {
+            text_document: text_doc(uri),
+            ranges: ranges
+                .iter()
+                .map(|&(start_line, start_char, end_line, end_char)| {
+                    range(start_line, start_char, end_line, end_char)
+                })
+                .collect(),
+            options: fmt_options(),
+            work_done_progress_params: WorkDoneProgressParams::default(),
+        };
+        handlers::formatting::format_ranges(&self.snapshot(), params)
+    }
+
     pub fn on_type_formatting(
         &self,
         uri: &str,
@@ -393,13 +420,27 @@ impl LspTester {
         start_char: u32,
         end_line: u32,
         end_char: u32,
+    ) -> Option<CodeActionResponse> {
+        self.get_code_actions_only(uri, start_line, start_char, end_line, end_char, None)
+    }
+
+    /// Like [`Self::get_code_actions`], with the client's `context.only` kind
+    /// filter (as sent e.g. by fix-on-save requesting `source.fixAll`).
+    pub fn get_code_actions_only(
+        &self,
+        uri: &str,
+        start_line: u32,
+        start_char: u32,
+        end_line: u32,
+        end_char: u32,
+        only: Option<Vec<CodeActionKind>>,
     ) -> Option<CodeActionResponse> {
         let params = CodeActionParams {
             text_document: text_doc(uri),
             range: range(start_line, start_char, end_line, end_char),
             context: CodeActionContext {
                 diagnostics: vec![],
-                only: None,
+                only,
                 trigger_kind: None,
             },
             work_done_progress_params: WorkDoneProgressParams::default(),
@@ -646,9 +687,13 @@ impl LspTester {
                     // `settle_deadline` but its result is still in flight; exiting
                     // here would abandon it and lose the batch's diagnostics (a
                     // burst `did_open` over many docs outruns the poll step).
+                    // A background link scan counts too: it arms a settle when
+                    // it lands.
                     let settle_in_flight =
                         self.gs.last_applied_lint_generation != self.gs.lint_generation;
-                    if self.gs.settle_deadline.is_none() && !settle_in_flight {
+                    let scan_in_flight = self.gs.last_applied_workspace_links_generation
+                        != self.gs.workspace_links_generation;
+                    if self.gs.settle_deadline.is_none() && !settle_in_flight && !scan_in_flight {
                         break;
                     }
                     self.gs.dispatch_due_lints();
@@ -0,0 +1,375 @@
+//! Workspace-wide validation of relative links between documents.
+//!
+//! A background pass walks every workspace folder (honoring `.gitignore` and
+//! friends via the `ignore` crate), parses each document once, and records its
+//! relative link destinations and the anchors it declares in a
+//! [`WorkspaceLinkIndex`]. The main loop keeps the latest index and hands it to
+//! every settle pass, which then reports `broken-link` diagnostics for
+//! destinations whose file does not exist or whose `#fragment` names no
+//! heading/anchor in the target document.
+//!
+//! With the `wikilinks` extension on, `[[Target|Display]]` targets are checked
+//! as well and reported as `broken-wikilink`. They resolve the way Obsidian
+//! does: case-insensitively, against any file in the workspace whose path ends
+//! with the target (the `.md` suffix may be left out).
+//!
+//! Open documents are always checked and resolved against their live buffer
+//! (the index only stands in for closed files), so editing a heading or a link
+//! updates the cross-file diagnostics on the next settle without a re-index.
+//! The index itself is rebuilt when the filesystem changes underneath it
+//! (watcher events, file operations, saves, closes).
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Range, Uri};
+
+use crate::link_check::{is_document_path, resolve_relative};
+use crate::lsp::conversions::offset_to_position;
+use crate::lsp::global_state::StateSnapshot;
+use crate::lsp::uri_ext::UriExt;
+use crate::syntax::{AstNode, LinkKind, SyntaxKind, SyntaxNode, WikiLink, collect_links};
+
+pub(crate) use crate::link_check::BROKEN_LINK_CODE;
+
+/// Lint rule name for unresolved wikilink targets.
+pub(crate) const BROKEN_WIKILINK_CODE: &str = "broken-wikilink";
+
+/// Relative links and declared anchors of every document in the workspace, as
+/// of the last background scan.
+#[derive(Debug, Default)]
+pub(crate) struct WorkspaceLinkIndex {
+    documents: HashMap<PathBuf, DocumentLinks>,
+    roots: Vec<PathBuf>,
+    /// Every file under the roots (attachments included), as a lowercase
+    /// `/`-separated path relative to its root. Wikilinks resolve against it.
+    files: HashSet<String>,
+}
+
+#[derive(Debug, Default)]
+struct DocumentLinks {
+    enabled: bool,
+    anchors: HashSet<String>,
+    links: Vec<RelativeLink>,
+    wikilinks: Vec<WikiTarget>,
+}
+
+/// A relative destination, resolved against the linking document's directory.
+#[derive(Debug, Clone)]
+struct RelativeLink {
+    range: Range,
+    raw: String,
+    target: PathBuf,
+    fragment: Option<String>,
+}
+
+/// The target of a `[[Target|Display]]` wikilink, without its `#heading`.
+#[derive(Debug, Clone)]
+struct WikiTarget {
+    range: Range,
+    target: String,
+}
+
+impl WorkspaceLinkIndex {
+    /// Scan every document under `folders`.
+    pub(crate) fn build(folders: &[PathBuf]) -> Self {
+        let mut documents = HashMap::new();
+        let mut configs: HashMap<(PathBuf, String), crate::Config> = HashMap::new();
+        let roots: Vec<PathBuf> = folders
+            .iter()
+            .map(|root| root.canonicalize().unwrap_or_else(|_| root.clone()))
+            .collect();
+        let mut files = HashSet::new();
+        for root in &roots {
+            for path in workspace_files(root) {
+                if let Some(relative) = relative_key(&roots, &path) {
+                    files.insert(relative);
+                }
+                if !is_document_path(&path) {
+                    continue;
+                }
+                let Ok(text) = std::fs::read_to_string(&path) else {
+                    continue;
+                };
+                let key = (
+                    path.parent().map(Path::to_path_buf).unwrap_or_default(),
+                    path.extension()
+                        .and_then(|ext| ext.to_str())
+                        .unwrap_or_default()
+                        .to_string(),
+                );
+                let config = configs
+                    .entry(key)
+                    .or_insert_with(|| {
+                        let uri = Uri::from_file_path(&path);
+                        crate::lsp::config::load_config(folders, uri.as_ref())
+                    })
+                    .clone();
+                let tree = crate::parse(&text, Some(config.clone()));
+                documents.insert(path.clone(), scan_document(&path, &text, &tree, &config));
+            }
+        }
+        Self {
+            documents,
+            roots,
+            files,
+        }
+    }
+
+    fn contains(&self, path: &Path) -> bool {
+        self.documents.contains_key(path)
+    }
+}
+
+/// Every file under `root`, documents and attachments alike.
+fn workspace_files(root: &Path) -> Vec<PathBuf> {
+    ignore::WalkBuilder::new(root)
+        .build()
+        .flatten()
+        .map(ignore::DirEntry::into_path)
+        .filter(|path| path.is_file())
+        .map(|path| path.canonicalize().unwrap_or(path))
+        .collect()
+}
+
+/// `path` relative to the first root containing it, lowercased and
+/// `/`-separated, as stored in [`WorkspaceLinkIndex::files`].
+fn relative_key(roots: &[PathBuf], path: &Path) -> Option<String> {
+    let relative = roots.iter().find_map(|root| path.strip_prefix(root).ok())?;
+    let parts: Vec<String> = relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy().to_lowercase())
+        .collect();
+    Some(parts.join("/"))
+}
+
+/// Whether a wikilink `target` names a file in `files`: the target, or the
+/// target with `.md` appended, equals a file's relative path or a trailing
+/// run of its path segments. Matching is case-insensitive.
+fn wikilink_resolves<'a>(files: impl IntoIterator<Item = &'a String>, target: &str) -> bool {
+    let target = target.trim().trim_start_matches('/').to_lowercase();
+    let with_md = format!("{target}.md");
+    let matches = |file: &str, candidate: &str| {
+        file == candidate
+            || file
+                .strip_suffix(candidate)
+                .is_some_and(|prefix| prefix.ends_with('/'))
+    };
+    files
+        .into_iter()
+        .any(|file| matches(file, &target) || matches(file, &with_md))
+}
+
+fn scan_document(
+    path: &Path,
+    text: &str,
+    tree: &SyntaxNode,
+    config: &crate::Config,
+) -> DocumentLinks {
+    let enabled = config.lint.is_rule_enabled(BROKEN_LINK_CODE);
+    let anchors = crate::linter::rules::undefined_anchor::document_anchors(tree, config);
+    let base = path.parent().unwrap_or_else(|| Path::new("."));
+
+    let mut links = Vec::new();
+    for link in collect_links(tree) {
+        // Reference links and images point at a definition, whose own
+        // destination is checked once as a `Definition`.
+        let direct = match link.kind {
+            LinkKind::Inline | LinkKind::Definition => true,
+            LinkKind::Image => link.label.is_none(),
+            LinkKind::Reference | LinkKind::Autolink | LinkKind::Include => false,
+        };
+        if !direct {
+            continue;
+        }
+        if let Some((target, fragment)) = resolve_relative(base, &link.raw) {
+            let range = link.range();
+            links.push(RelativeLink {
+                range: Range {
+                    start: offset_to_position(text, range.start().into()),
+                    end: offset_to_position(text, range.end().into()),
+                },
+                raw: link.raw,
+                target,
+                fragment,
+            });
+        }
+    }
+
+    let wikilinks_enabled = config.extensions.wikilinks_title_after_pipe
+        || config.extensions.wikilinks_title_before_pipe;
+    let mut wikilinks = Vec::new();
+    if wikilinks_enabled && config.lint.is_rule_enabled(BROKEN_WIKILINK_CODE) {
+        for wikilink in tree.descendants().filter_map(WikiLink::cast) {
+            let Some(url) = wikilink
+                .syntax()
+                .children()
+                .find(|child| child.kind() == SyntaxKind::WIKI_LINK_URL)
+            else {
+                continue;
+            };
+            // `[[#Heading]]` points into the same note.
+            let raw = url.text().to_string();
+            let target = raw.split_once('#').map_or(raw.as_str(), |(path, _)| path);
+            if target.trim().is_empty() {
+                continue;
+            }
+            let range = url.text_range();
+            wikilinks.push(WikiTarget {
+                range: Range {
+                    start: offset_to_position(text, range.start().into()),
+                    end: offset_to_position(text, range.end().into()),
+                },
+                target: target.trim().to_string(),
+            });
+        }
+    }
+
+    DocumentLinks {
+        enabled,
+        anchors,
+        links,
+        wikilinks,
+    }
+}
+
+/// `broken-link` and `broken-wikilink` diagnostics for every indexed and open
+/// document, one publish per document that has any. Open documents are
+/// scanned from their live buffer; closed ones come from `index`. Wikilinks
+/// are only checked once an index exists, since they resolve against the
+/// whole workspace.
+pub(crate) fn link_publishes(
+    snap: &StateSnapshot,
+    index: Option<&WorkspaceLinkIndex>,
+) -> Vec<super::handlers::diagnostics::Publish> {
+    let mut open: HashMap<PathBuf, (Uri, DocumentLinks)> = HashMap::new();
+    for (key, state) in snap.document_map.iter() {
+        let Some(path) = state.path.clone() else {
+            continue;
+        };
+        let path = path.canonicalize().unwrap_or(path);
+        let Ok(uri) = key.parse::<Uri>() else {
+            continue;
+        };
+        let Some((text, tree)) = snap.document_content_and_tree(&uri) else {
+            continue;
+        };
+        let config = state.salsa_config.config(snap.db()).clone();
+        let links = scan_document(&path, &text, &tree, &config);
+        open.insert(path, (uri, links));
+    }
+
+    let anchors_of = |path: &Path| -> Option<&HashSet<String>> {
+        open.get(path).map(|(_, links)| &links.anchors).or_else(|| {
+            index
+                .and_then(|index| index.documents.get(path))
+                .map(|doc| &doc.anchors)
+        })
+    };
+    let exists = |path: &Path| {
+        open.contains_key(path) || index.is_some_and(|index| index.contains(path)) || path.exists()
+    };
+
+    let mut documents: Vec<(Uri, &DocumentLinks)> = open
+        .values()
+        .map(|(uri, links)| (uri.clone(), links))
+        .collect();
+    if let Some(index) = index {
+        for (path, links) in &index.documents {
+            if open.contains_key(path) {
+                continue;
+            }
+            if let Some(uri) = Uri::from_file_path(path) {
+                documents.push((uri, links));
+            }
+        }
+    }
+
+    // Open documents may not be on disk yet; they still count as targets.
+    let open_files: Vec<String> = index
+        .map(|index| {
+            open.keys()
+                .filter_map(|path| relative_key(&index.roots, path))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut publishes = Vec::new();
+    for (uri, links) in documents {
+        let mut diagnostics = Vec::new();
+        if let Some(index) = index {
+            for wikilink in &links.wikilinks {
+                if wikilink_resolves(index.files.iter().chain(&open_files), &wikilink.target) {
+                    continue;
+                }
+                diagnostics.push(Diagnostic {
+                    range: wikilink.range,
+                    severity: Some(DiagnosticSeverity::WARNING),
+                    code: Some(NumberOrString::String(BROKEN_WIKILINK_CODE.to_string())),
+                    source: Some("panache".to_string()),
+                    message: format!(
+                        "Wikilink target '{}' not found in workspace",
+                        wikilink.target
+                    ),
+                    ..Default::default()
+                });
+            }
+        }
+        let relative_links: &[RelativeLink] = if links.enabled { &links.links } else { &[] };
+        for link in relative_links {
+            let message = if !exists(&link.target) {
+                format!("Link target '{}' does not exist", link.raw)
+            } else if let Some(fragment) = &link.fragment
+                && is_document_path(&link.target)
+                && let Some(anchors) = anchors_of(&link.target)
+                && !anchors.contains(fragment)
+            {
+                let file = link
+                    .target
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                format!("Anchor '#{fragment}' not found in '{file}'")
+            } else {
+                continue;
+            };
+            diagnostics.push(Diagnostic {
+                range: link.range,
+                severity: Some(DiagnosticSeverity::WARNING),
+                code: Some(NumberOrString::String(BROKEN_LINK_CODE.to_string())),
+                source: Some("panache".to_string()),
+                message,
+                ..Default::default()
+            });
+        }
+        if !diagnostics.is_empty() {
+            publishes.push((uri, None, diagnostics));
+        }
+    }
+    publishes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_wikilinks_like_obsidian() {
+        let files: HashSet<String> = [
+            "notes/daily/2024-01-01.md",
+            "index.md",
+            "assets/diagram.png",
+        ]
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+        assert!(wikilink_resolves(&files, "2024-01-01"));
+        assert!(wikilink_resolves(&files, "daily/2024-01-01"));
+        assert!(wikilink_resolves(&files, "Index"));
+        assert!(wikilink_resolves(&files, "index.md"));
+        assert!(wikilink_resolves(&files, "diagram.png"));
+        assert!(!wikilink_resolves(&files, "diagram"));
+        assert!(!wikilink_resolves(&files, "01-01"));
+        assert!(!wikilink_resolves(&files, "missing"));
+    }
+}
@@ -1,7 +1,8 @@
 use std::fs;
-use std::io::{self, IsTerminal, Read};
+use std::io::{self, Read, Write};
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
 
 use clap::Parser;
 use similar::{ChangeTag, TextDiff};
@@ -11,26 +12,39 @@ use serde_json::json;
 
 mod cache;
 mod cli;
+mod config_command;
+mod corpus;
 mod diagnostic_renderer;
+mod report;
+mod term;
 use cache::{
     CachedLintDocument, CliCache, FormatCacheMode, FormatStoreArgs, global_cache_base_dir,
     resolve_cache_dir_for_cli,
 };
-use cli::{Cli, CliFlavor, ColorMode, Commands, DebugChecks, DebugCommands, ParseOutput};
-use diagnostic_renderer::print_diagnostics;
-use panache::config::{Flavor, WrapMode};
+use cli::{
+    Cli, CliEmbedded, CliLineEnding, Commands, ConfigCommands, DebugChecks, DebugCommands,
+    DiffFormat, MessageFormat, ParseOutput, RenderFormat, StatsFormat,
+};
+use diagnostic_renderer::{SeverityCounts, print_diagnostics};
+use panache::config::{Flavor, FlavorSpec};
+use panache::embedded::{EmbeddedSyntax, format_embedded};
+use report::{FileReport, FormatReport, build_debug_failure_report};
+
+impl From<CliEmbedded> for EmbeddedSyntax {
+    fn from(value: CliEmbedded) -> Self {
+        match value {
+            CliEmbedded::Roxygen => EmbeddedSyntax::Roxygen,
+            CliEmbedded::Docstring => EmbeddedSyntax::Docstring,
+        }
+    }
+}
 
-impl From<CliFlavor> for Flavor {
-    fn from(value: CliFlavor) -> Self {
+impl From<CliLineEnding> for panache::config::LineEnding {
+    fn from(value: CliLineEnding) -> Self {
         match value {
-            CliFlavor::Pandoc => Flavor::Pandoc,
-            CliFlavor::Quarto => Flavor::Quarto,
-            CliFlavor::RMarkdown => Flavor::RMarkdown,
-            CliFlavor::Gfm => Flavor::Gfm,
-            CliFlavor::CommonMark => Flavor::CommonMark,
-            CliFlavor::MultiMarkdown => Flavor::MultiMarkdown,
-            CliFlavor::Mdsvex => Flavor::Mdsvex,
-            CliFlavor::Myst => Flavor::Myst,
+            CliLineEnding::Auto => Self::Auto,
+            CliLineEnding::Lf => Self::Lf,
+            CliLineEnding::Crlf => Self::Crlf,
         }
     }
 }
@@ -46,44 +60,6 @@ fn apply_format_overrides(cfg: &mut panache::Config, overrides: &[String]) -> Re
         let key = key.trim();
         let value = value.trim();
         match key {
-            "line-width" => {
-                let n: usize = value.parse().map_err(|_| {
-                    format!("invalid value for `line-width`: `{value}` (expected positive integer)")
-                })?;
-                if n == 0 {
-                    return Err(
-                        "invalid value for `line-width`: 0 (expected positive integer)".into(),
-                    );
-                }
-                cfg.line_width = n;
-            }
-            "wrap" => {
-                let mode = match value {
-                    "reflow" => WrapMode::Reflow,
-                    "sentence" => WrapMode::Sentence,
-                    "semantic" => WrapMode::Semantic,
-                    "preserve" => WrapMode::Preserve,
-                    other => {
-                        return Err(format!(
-                            "invalid value for `wrap`: `{other}` (expected one of: reflow, sentence, semantic, preserve)"
-                        ));
-                    }
-                };
-                cfg.wrap = Some(mode);
-            }
-            "table-indent" => {
-                let indent: usize = value.parse().map_err(|_| {
-                    format!(
-                        "invalid value for `table-indent`: `{value}` (expected an integer 0, 1, 2, or 3)"
-                    )
-                })?;
-                if indent > 3 {
-                    return Err(format!(
-                        "invalid value for `table-indent`: `{indent}` (expected 0, 1, 2, or 3)"
-                    ));
-                }
-                cfg.table_indent = indent;
-            }
             ext_key if ext_key.starts_with("extensions.") => {
                 let name = ext_key["extensions.".len()..].trim();
                 if name.is_empty() {
@@ -103,9 +79,11 @@ fn apply_format_overrides(cfg: &mut panache::Config, overrides: &[String]) -> Re
                 extension_overrides.insert(name.to_string(), bool_value);
             }
             other => {
-                return Err(format!(
-                    "unknown config key in --option: `{other}` (supported: line-width, wrap, table-indent, extensions.<name>)"
-                ));
+                if !panache::config::apply_format_option(cfg, other, value)? {
+                    return Err(format!(
+                        "unknown config key in --option: `{other}` (supported: line-width, wrap, table-indent, extensions.<name>)"
+                    ));
+                }
             }
         }
     }
@@ -131,6 +109,8 @@ const SUPPORTED_EXTENSIONS: &[&str] = &[
     // mdsvex: bare `.svx`. The compound `.svelte.md` ends in `.md`, so it is
     // already accepted by the `md` entry above.
     "svx",
+    // MDX: formatted with the `mdx` extension turned on.
+    "mdx",
 ];
 
 fn init_logger(debug_log: Option<&Path>) {
@@ -167,17 +147,6 @@ struct PathFilters {
     include: panache::config::GlobMatcher,
 }
 
-fn effective_exclude_patterns(cfg: &panache::Config) -> Vec<String> {
-    let mut patterns = cfg.exclude.clone().unwrap_or_else(|| {
-        panache::config::DEFAULT_EXCLUDE_PATTERNS
-            .iter()
-            .map(|s| s.to_string())
-            .collect()
-    });
-    patterns.extend(cfg.extend_exclude.iter().cloned());
-    patterns
-}
-
 fn effective_include_patterns(cfg: &panache::Config) -> Vec<String> {
     let mut patterns = cfg.include.clone().unwrap_or_else(|| {
         panache::config::DEFAULT_INCLUDE_PATTERNS
@@ -192,8 +161,9 @@ fn effective_include_patterns(cfg: &panache::Config) -> Vec<String> {
 fn build_path_filters(cfg: &panache::Config) -> io::Result<PathFilters> {
     // Anchoring is applied by computing config-dir-relative paths in
     // `expand_paths`; the matchers themselves are anchor-agnostic.
-    let exclude = panache::config::GlobMatcher::build(&effective_exclude_patterns(cfg))
-        .map_err(io::Error::other)?;
+    let exclude =
+        panache::config::GlobMatcher::build(&panache::config::effective_exclude_patterns(cfg))
+            .map_err(io::Error::other)?;
     let include = panache::config::GlobMatcher::build(&effective_include_patterns(cfg))
         .map_err(io::Error::other)?;
     Ok(PathFilters { exclude, include })
@@ -211,6 +181,20 @@ fn relative_path_from_root(path: &Path, root: &Path) -> Option<PathBuf> {
         .map(Path::to_path_buf)
 }
 
+/// Apply `--include`/`--exclude` to the traversal config. CLI includes replace
+/// the configured include set (so they can narrow a run); CLI excludes are
+/// appended so the default and configured excludes keep applying.
+fn apply_path_filter_overrides(cfg: &mut panache::Config, include: &[String], exclude: &[String]) {
+    if !include.is_empty() {
+        cfg.include = Some(include.to_vec());
+        cfg.extend_include.clear();
+    }
+    cfg.extend_exclude.extend(exclude.iter().cloned());
+}
+
+/// Gitignore-syntax file whose patterns only affect Panache's directory walks.
+const PANACHE_IGNORE_FILENAME: &str = ".panacheignore";
+
 /// Expand paths to include all supported files, recursively handling directories
 fn expand_paths(
     paths: &[PathBuf],
@@ -252,11 +236,12 @@ fn expand_paths(
                 );
             }
         } else if path.is_dir() {
-            // Walk directory recursively, respecting .gitignore
+            // Walk directory recursively, respecting .gitignore and .panacheignore
             let walker = WalkBuilder::new(path)
                 .hidden(false) // Don't skip hidden files by default
                 .git_ignore(true) // Respect .gitignore
                 .git_global(true) // Respect global gitignore
+                .add_custom_ignore_filename(PANACHE_IGNORE_FILENAME)
                 .build();
 
             for entry in walker {
@@ -402,6 +387,108 @@ fn file_count_label(count: usize, singular: &str, plural: &str) -> String {
     }
 }
 
+/// Replace the contents of `path` without ever leaving it half-written.
+///
+/// The new contents go to a temporary file in the same directory, which then
+/// takes over the original's permissions and is renamed over it. Symlinks are
+/// followed so the link itself survives. With `backup`, the original is first
+/// copied to `<path><backup>` (for example `doc.qmd.orig`).
+fn write_in_place(path: &Path, contents: &str, backup: Option<&str>) -> io::Result<()> {
+    let mut tmp = stage_in_place(path)?;
+    tmp.write_all(contents.as_bytes())?;
+    tmp.as_file().sync_all()?;
+    persist_in_place(path, tmp.into_temp_path(), backup)
+}
+
+/// A temporary file in the directory of `path` for its new contents, so that
+/// [`persist_in_place`] can rename it over `path`.
+fn stage_in_place(path: &Path) -> io::Result<tempfile::NamedTempFile> {
+    let target = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let dir = match target.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    tempfile::Builder::new()
+        .prefix(".panache-")
+        .suffix(".tmp")
+        .tempfile_in(dir)
+}
+
+/// Replace `path` with the file staged by [`stage_in_place`], which has been
+/// written and synced, keeping the permissions of `path` and first copying it
+/// to a backup when `backup` is set.
+fn persist_in_place(path: &Path, tmp: tempfile::TempPath, backup: Option<&str>) -> io::Result<()> {
+    let target = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let permissions = fs::metadata(&target)?.permissions();
+
+    if let Some(ext) = backup {
+        fs::copy(&target, backup_path(&target, ext))?;
+    }
+
+    fs::set_permissions(&tmp, permissions)?;
+    tmp.persist(&target).map_err(|err| err.error)?;
+    Ok(())
+}
+
+/// Format `input`, read from `path`, into a file staged next to `path`, so the
+/// formatted document is never held in memory. The staged file is closed, so a
+/// large run does not keep one open per file, and is `None` when the output
+/// equals `input`. Also returns the `--fail-safe` warnings.
+fn format_to_staged(
+    path: &Path,
+    input: &str,
+    cfg: &panache::Config,
+) -> io::Result<(Option<tempfile::TempPath>, Vec<String>)> {
+    let mut tmp = stage_in_place(path)?;
+    let mut writer = CompareWriter {
+        inner: io::BufWriter::new(&mut tmp),
+        expected: input.as_bytes(),
+        matches: true,
+    };
+    let failures = panache::format_to_with_failures(input, Some(cfg.clone()), &mut writer)?;
+    writer.inner.flush()?;
+    let unchanged = writer.matches && writer.expected.is_empty();
+    drop(writer);
+    if !unchanged {
+        tmp.as_file().sync_all()?;
+    }
+    let warnings = fail_safe_warning_lines(&path.display().to_string(), &failures);
+    Ok(((!unchanged).then(|| tmp.into_temp_path()), warnings))
+}
+
+/// Writes through to `inner` while checking that the bytes written so far are
+/// a prefix of `expected`; `expected` keeps the part not yet matched.
+struct CompareWriter<'a, W> {
+    inner: W,
+    expected: &'a [u8],
+    matches: bool,
+}
+
+impl<W: Write> Write for CompareWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        if self.matches {
+            match self.expected.strip_prefix(&buf[..written]) {
+                Some(rest) => self.expected = rest,
+                None => self.matches = false,
+            }
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// `path` with `ext` appended to its file name; a leading `.` is optional.
+fn backup_path(path: &Path, ext: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(ext.trim_start_matches('.'));
+    path.with_file_name(name)
+}
+
 fn remove_dir_if_exists(path: &Path) -> io::Result<bool> {
     let mut attempt: usize = 0;
     loop {
@@ -511,10 +598,110 @@ fn start_dir_for(input_path: Option<&Path>) -> io::Result<PathBuf> {
     }
 }
 
+/// Keep the files a directory walk would have picked: those matching the
+/// include patterns and not the exclude patterns. Used for `--staged`, whose
+/// paths come from git rather than from the user.
+fn filter_as_discovered(
+    paths: &[PathBuf],
+    cfg: &panache::Config,
+    anchor: &Path,
+) -> io::Result<Vec<PathBuf>> {
+    let filters = build_path_filters(cfg)?;
+    Ok(paths
+        .iter()
+        .filter(|path| path.is_file())
+        .filter(|path| {
+            let rel_path =
+                relative_path_from_root(path, anchor).unwrap_or_else(|| path.to_path_buf());
+            let rel = rel_path.to_string_lossy().replace('\\', "/");
+            filters.include.is_match(&rel) && !filters.exclude.is_match(&rel)
+        })
+        .cloned()
+        .collect())
+}
+
+/// The `panache debug capabilities` report for the resolved `cfg`.
+fn capabilities(cfg: &panache::Config) -> serde_json::Value {
+    use panache::config::{Extensions, FormatterExtensions};
+
+    let extensions: serde_json::Map<String, serde_json::Value> = Extensions::KNOWN_NAMES
+        .iter()
+        .filter_map(|name| Some((name.to_string(), cfg.extensions.get_by_name(name)?.into())))
+        .collect();
+    let formatter_extensions: serde_json::Map<String, serde_json::Value> =
+        FormatterExtensions::KNOWN_NAMES
+            .iter()
+            .filter_map(|name| {
+                let enabled = cfg.formatter_extensions.get_by_name(name)?;
+                Some((name.to_string(), enabled.into()))
+            })
+            .collect();
+    let kinds = |kinds: &[panache::syntax::SyntaxKind]| {
+        kinds
+            .iter()
+            .map(|kind| format!("{kind:?}"))
+            .collect::<Vec<_>>()
+    };
+    json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "flavor": cfg.flavor,
+        "extensions": extensions,
+        "formatter_extensions": formatter_extensions,
+        "formatter": {
+            "normalized": kinds(panache_formatter::formatter::NORMALIZED_KINDS),
+            "verbatim": kinds(panache_formatter::formatter::VERBATIM_KINDS),
+        },
+    })
+}
+
+/// Newline-separated paths from `list`, or from stdin when it is `-`.
+fn read_path_list(list: &Path) -> io::Result<Vec<PathBuf>> {
+    let text = if list.as_os_str() == "-" {
+        read_all(None)?
+    } else {
+        fs::read_to_string(list).map_err(|err| {
+            io::Error::new(
+                err.kind(),
+                format!("failed to read {}: {err}", list.display()),
+            )
+        })?
+    };
+    Ok(text
+        .lines()
+        .map(|line| line.trim_end_matches('\r'))
+        .filter(|line| !line.trim().is_empty())
+        .map(PathBuf::from)
+        .collect())
+}
+
 fn has_explicit_file_targets(paths: &[PathBuf]) -> bool {
     paths.iter().any(|path| !path.is_dir())
 }
 
+/// `--enable-extension` / `--disable-extension` overrides, applied to every
+/// config the CLI loads. Set once in [`run`].
+static EXTENSION_OVERRIDES: OnceLock<std::collections::HashMap<String, bool>> = OnceLock::new();
+
+/// Collect the `--flavor` modifiers and the extension flags into
+/// `name -> enabled` overrides, rejecting unknown names. The flags win over the
+/// modifiers, and disabling wins when a name is given to both flags.
+fn extension_overrides(
+    flavor: &[(String, bool)],
+    enable: &[String],
+    disable: &[String],
+) -> Result<std::collections::HashMap<String, bool>, String> {
+    let mut overrides: std::collections::HashMap<String, bool> = flavor.iter().cloned().collect();
+    for (names, enabled) in [(enable, true), (disable, false)] {
+        for name in names {
+            let name = name.trim().replace('_', "-");
+            panache::config::check_extension_name(&name)
+                .map_err(|err| format!("invalid extension flag: {err}"))?;
+            overrides.insert(name, enabled);
+        }
+    }
+    Ok(overrides)
+}
+
 fn load_config_for_cli(
     config_path: Option<&Path>,
     isolated: bool,
@@ -523,8 +710,29 @@ fn load_config_for_cli(
     input_path: Option<&Path>,
     flavor_override: Option<Flavor>,
 ) -> io::Result<(panache::Config, panache::config::ConfigSource)> {
+    let (cfg, source, _chain) = load_config_with_chain_for_cli(
+        config_path,
+        isolated,
+        cli_cache_dir,
+        start_dir,
+        input_path,
+        flavor_override,
+    )?;
+    Ok((cfg, source))
+}
+
+/// Like [`load_config_for_cli`], but also returns the config files that
+/// contributed, most derived first.
+fn load_config_with_chain_for_cli(
+    config_path: Option<&Path>,
+    isolated: bool,
+    cli_cache_dir: Option<&Path>,
+    start_dir: &Path,
+    input_path: Option<&Path>,
+    flavor_override: Option<Flavor>,
+) -> io::Result<(panache::Config, panache::config::ConfigSource, Vec<PathBuf>)> {
     let mut loaded = if !isolated {
-        panache::config::load(config_path, start_dir, input_path, flavor_override)?
+        panache::config::load_with_chain(config_path, start_dir, input_path, flavor_override)?
     } else {
         let mut cfg = panache::Config::default();
         // Delegate to the canonical extension→flavor map so `--isolated` stays
@@ -537,85 +745,373 @@ fn load_config_for_cli(
             cfg.flavor = flavor;
             cfg.extensions = panache::config::Extensions::for_flavor(flavor);
         }
-        (cfg, panache::config::ConfigSource::None)
+        if let Some(path) = input_path {
+            panache::config::apply_path_extensions(&mut cfg, path);
+        }
+        panache::config::apply_sandbox_overrides(&mut cfg.external_sandbox);
+        (cfg, panache::config::ConfigSource::None, Vec::new())
     };
 
     if let Some(cache_dir) = cli_cache_dir {
         loaded.0.cache_dir = Some(cache_dir.to_string_lossy().to_string());
     }
 
+    if let Some(overrides) = EXTENSION_OVERRIDES.get().filter(|o| !o.is_empty()) {
+        loaded.0.extensions.apply_overrides(overrides.clone());
+        loaded
+            .0
+            .formatter_extensions
+            .apply_overrides(overrides.clone());
+    }
+
+    if panache::trust::safe_mode_enabled(false) {
+        panache::trust::enforce(&mut loaded.0, &loaded.1);
+    }
+
     Ok(loaded)
 }
 
-fn color_enabled(mode: ColorMode, no_color: bool) -> bool {
-    resolve_color(
-        mode,
-        no_color,
-        std::env::var_os("NO_COLOR").is_some(),
-        std::env::var_os("TERM").as_deref(),
-        io::stdout().is_terminal(),
-    )
-}
-
-fn resolve_color(
-    mode: ColorMode,
-    no_color_flag: bool,
-    no_color_env: bool,
-    term_env: Option<&std::ffi::OsStr>,
-    stdout_is_terminal: bool,
-) -> bool {
-    if no_color_flag {
-        return false;
-    }
-    match mode {
-        ColorMode::Always => true,
-        ColorMode::Never => false,
-        ColorMode::Auto => {
-            if no_color_env {
-                return false;
-            }
-            match term_env {
-                Some(term) if term == "dumb" => return false,
-                None => return false,
-                _ => {}
-            }
-            stdout_is_terminal
+/// How a file's formatted output differs from its input, so `--check` can call
+/// out changes that are invisible in a line diff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChangeKind {
+    /// The text itself changes.
+    Content,
+    /// Only line endings change (e.g. CRLF normalized to LF).
+    LineEndings,
+    /// Only the leading byte order mark is dropped.
+    Bom,
+    /// Only line endings change and the byte order mark is dropped.
+    LineEndingsAndBom,
+}
+
+impl ChangeKind {
+    /// `None` when `formatted` equals `original`.
+    fn classify(original: &str, formatted: &str) -> Option<Self> {
+        if original == formatted {
+            return None;
+        }
+        let bom_changed = original.starts_with('\u{feff}') != formatted.starts_with('\u{feff}');
+        let original = original.strip_prefix('\u{feff}').unwrap_or(original);
+        let formatted = formatted.strip_prefix('\u{feff}').unwrap_or(formatted);
+        if original == formatted {
+            return Some(if bom_changed {
+                Self::Bom
+            } else {
+                Self::Content
+            });
+        }
+        if original.replace("\r\n", "\n") != formatted.replace("\r\n", "\n") {
+            return Some(Self::Content);
+        }
+        Some(if bom_changed {
+            Self::LineEndingsAndBom
+        } else {
+            Self::LineEndings
+        })
+    }
+
+    /// Whether `--ignore-line-endings`/`--ignore-bom` cover this change.
+    fn is_ignored(self, ignore_line_endings: bool, ignore_bom: bool) -> bool {
+        match self {
+            Self::Content => false,
+            Self::LineEndings => ignore_line_endings,
+            Self::Bom => ignore_bom,
+            Self::LineEndingsAndBom => ignore_line_endings && ignore_bom,
         }
     }
+
+    fn description(self) -> &'static str {
+        match self {
+            Self::Content => "content",
+            Self::LineEndings => "line endings only",
+            Self::Bom => "byte order mark only",
+            Self::LineEndingsAndBom => "line endings and byte order mark only",
+        }
+    }
+
+    fn json_name(self) -> &'static str {
+        match self {
+            Self::Content => "content",
+            Self::LineEndings => "line-endings",
+            Self::Bom => "bom",
+            Self::LineEndingsAndBom => "line-endings-and-bom",
+        }
+    }
+
+    fn ignore_hint(self) -> &'static str {
+        match self {
+            Self::Content => "",
+            Self::LineEndings => "--ignore-line-endings",
+            Self::Bom => "--ignore-bom",
+            Self::LineEndingsAndBom => "--ignore-line-endings --ignore-bom",
+        }
+    }
+}
+
+/// `--check` report for one changed file in the inline or unified format. A
+/// line diff of a line-ending or BOM change shows identical lines, so those
+/// get a one-line explanation instead.
+fn print_check_change(
+    file_path: &str,
+    original: &str,
+    formatted: &str,
+    kind: ChangeKind,
+    diff_format: DiffFormat,
+) {
+    match (kind, diff_format) {
+        (ChangeKind::Content, DiffFormat::Unified) => {
+            print_unified_diff(file_path, original, formatted)
+        }
+        (ChangeKind::Content, _) => print_diff(file_path, original, formatted),
+        _ => anstream::println!(
+            "Diff in {}: {} (pass {} to skip)",
+            file_path,
+            term::paint(term::INFO, kind.description()),
+            kind.ignore_hint()
+        ),
+    }
 }
 
-fn print_diff(file_path: &str, original: &str, formatted: &str, use_color: bool) {
+fn print_diff(file_path: &str, original: &str, formatted: &str) {
     let diff = TextDiff::from_lines(original, formatted);
 
     for (idx, group) in diff.grouped_ops(3).iter().enumerate() {
         if idx > 0 {
-            println!("---");
+            anstream::println!("---");
         }
 
         // Print header similar to rustfmt
-        println!("Diff in {}:{}:", file_path, group[0].old_range().start + 1);
+        anstream::println!("Diff in {}:{}:", file_path, group[0].old_range().start + 1);
 
         for op in group {
             for change in diff.iter_changes(op) {
                 let (sign, style) = match change.tag() {
-                    ChangeTag::Delete => ("-", "\x1b[31m"), // red
-                    ChangeTag::Insert => ("+", "\x1b[32m"), // green
-                    ChangeTag::Equal => (" ", "\x1b[0m"),   // normal
+                    ChangeTag::Delete => ("-", term::REMOVED),
+                    ChangeTag::Insert => ("+", term::ADDED),
+                    ChangeTag::Equal => (" ", anstyle::Style::new()),
                 };
+                // Style the line without its newline so the reset lands
+                // before the line break.
+                let line = change.value();
+                let (text, newline) = match line.strip_suffix('\n') {
+                    Some(text) => (text, "\n"),
+                    None => (line, ""),
+                };
+                anstream::print!(
+                    "{}{}",
+                    term::paint(style, format_args!("{sign}{text}")),
+                    newline
+                );
+            }
+        }
+    }
+}
 
-                if use_color {
-                    print!("{}{}{}", style, sign, change.value());
-                } else {
-                    print!("{}{}", sign, change.value());
-                }
+/// Run `f`, printing the time it spent in each formatting phase to stderr
+/// under `label` when `enabled` (`--timings`).
+fn print_timings<T>(enabled: bool, label: &str, f: impl FnOnce() -> T) -> T {
+    if !enabled {
+        return f();
+    }
+    let (value, timings) = panache::timings::record(f);
+    eprintln!("{label}: {timings}");
+    value
+}
 
-                // Reset color at end of line if it was colored
-                if use_color && change.tag() != ChangeTag::Equal {
-                    print!("\x1b[0m");
-                }
-            }
+/// `--verbose` lines for the malformed structure the parser recovered from in
+/// `input`, as `Warning: path:line:column: message`.
+fn parse_warning_lines(path: &str, input: &str, cfg: &panache::Config) -> Vec<String> {
+    let (_, warnings) = panache::parse_with_diagnostics(input, Some(cfg.clone()));
+    warnings
+        .iter()
+        .map(|warning| {
+            let (line, column) = line_column(input, warning.range.start().into());
+            format!("Warning: {path}:{line}:{column}: {}", warning.message)
+        })
+        .collect()
+}
+
+/// `Warning:` lines for the `panache:` frontmatter options of `input` that are
+/// skipped. Only a document opening with a frontmatter fence is parsed.
+fn frontmatter_warning_lines(path: &str, input: &str, cfg: &panache::Config) -> Vec<String> {
+    if !input.trim_start_matches('\u{feff}').starts_with("---") {
+        return Vec::new();
+    }
+    let tree = panache::parse(input, Some(cfg.clone()));
+    panache::config::frontmatter_warnings(&tree, cfg)
+        .into_iter()
+        .map(|warning| format!("Warning: {path}: {warning}"))
+        .collect()
+}
+
+/// Format `input` like [`panache::try_format`], returning `Warning:` lines for
+/// the internal errors `--fail-safe` recovered from.
+fn try_format_fail_safe(
+    path: &str,
+    input: &str,
+    cfg: &panache::Config,
+    range: Option<(usize, usize)>,
+) -> io::Result<(String, Vec<String>)> {
+    let (output, failures) = panache::try_format_with_failures(input, Some(cfg.clone()), range)
+        .map_err(io::Error::other)?;
+    Ok((output, fail_safe_warning_lines(path, &failures)))
+}
+
+/// `Warning:` lines for the internal errors `--fail-safe` recovered from.
+fn fail_safe_warning_lines(path: &str, failures: &[panache::FormatError]) -> Vec<String> {
+    failures
+        .iter()
+        .map(|failure| format!("Warning: {path}: {failure}; {}", failure.recovery()))
+        .collect()
+}
+
+/// 1-indexed line and column (in characters) of byte `offset` in `input`.
+fn line_column(input: &str, offset: usize) -> (usize, usize) {
+    let before = &input[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = before[before.rfind('\n').map_or(0, |nl| nl + 1)..]
+        .chars()
+        .count()
+        + 1;
+    (line, column)
+}
+
+/// The [`panache::edits::diff`] of `original` → `formatted` as a JSON object
+/// for `--edits json`. Positions carry a 1-indexed line and column (in
+/// characters) and the byte offset.
+fn edits_to_json(file_path: &str, original: &str, formatted: &str) -> serde_json::Value {
+    let position = |offset: usize| {
+        let (line, column) = line_column(original, offset);
+        json!({ "line": line, "column": column, "offset": offset })
+    };
+    let edits: Vec<serde_json::Value> = panache::edits::diff(original, formatted)
+        .into_iter()
+        .map(|edit| {
+            json!({
+                "start": position(edit.range.start),
+                "end": position(edit.range.end),
+                "new_text": edit.new_text,
+            })
+        })
+        .collect();
+    json!({ "path": file_path, "edits": edits })
+}
+
+/// A changed file as listed by `--summary`, noting encoding-only changes.
+fn summary_label(path: &str, kind: ChangeKind) -> String {
+    match kind {
+        ChangeKind::Content => path.to_string(),
+        _ => format!("{path} ({})", kind.description()),
+    }
+}
+
+/// `--summary` report for `format --check`: one `Would reformat:` line per
+/// changed file (skipped with `--list-different`, which already printed the
+/// paths), then the totals.
+fn print_check_summary<S: AsRef<str>>(changed: &[S], total: usize, paths_listed: bool) {
+    if !paths_listed {
+        for path in changed {
+            anstream::println!("Would reformat: {}", term::paint(term::PATH, path.as_ref()));
         }
     }
+    anstream::println!(
+        "{}, {}",
+        file_count_label(
+            changed.len(),
+            "file would be reformatted",
+            "files would be reformatted"
+        ),
+        file_count_label(
+            total - changed.len(),
+            "file already formatted",
+            "files already formatted"
+        )
+    );
+}
+
+/// Print `original` → `formatted` as a `diff -u` patch against `a/` and `b/`
+/// paths.
+fn print_unified_diff(file_path: &str, original: &str, formatted: &str) {
+    let diff = TextDiff::from_lines(original, formatted);
+    let patch = diff
+        .unified_diff()
+        .context_radius(3)
+        .header(&format!("a/{file_path}"), &format!("b/{file_path}"))
+        .to_string();
+
+    for (idx, line) in patch.split_inclusive('\n').enumerate() {
+        let (text, newline) = match line.strip_suffix('\n') {
+            Some(text) => (text, "\n"),
+            None => (line, ""),
+        };
+        let style = if idx < 2 {
+            term::HEADER
+        } else if text.starts_with("@@") {
+            term::INFO
+        } else if text.starts_with('-') {
+            term::REMOVED
+        } else if text.starts_with('+') {
+            term::ADDED
+        } else {
+            anstyle::Style::new()
+        };
+        anstream::print!("{}{}", term::paint(style, text), newline);
+    }
+}
+
+/// The changed hunks of `original` → `formatted` as a JSON object for
+/// `--diff-format json`, tagged with the [`ChangeKind`]. Line numbers are
+/// 1-indexed; line text excludes the trailing newline.
+fn diff_to_json(
+    file_path: &str,
+    original: &str,
+    formatted: &str,
+    kind: ChangeKind,
+) -> serde_json::Value {
+    let diff = TextDiff::from_lines(original, formatted);
+    let hunks: Vec<serde_json::Value> = diff
+        .grouped_ops(3)
+        .iter()
+        .filter_map(|group| {
+            let (first, last) = (group.first()?, group.last()?);
+            let old_range = first.old_range().start..last.old_range().end;
+            let new_range = first.new_range().start..last.new_range().end;
+            let changes: Vec<serde_json::Value> = group
+                .iter()
+                .flat_map(|op| diff.iter_changes(op))
+                .map(|change| {
+                    let kind = match change.tag() {
+                        ChangeTag::Delete => "removed",
+                        ChangeTag::Insert => "added",
+                        ChangeTag::Equal => "context",
+                    };
+                    let line = change.value();
+                    json!({
+                        "kind": kind,
+                        "line": line.strip_suffix('\n').unwrap_or(line),
+                    })
+                })
+                .collect();
+            Some(json!({
+                "old_start": old_range.start + 1,
+                "old_lines": old_range.len(),
+                "new_start": new_range.start + 1,
+                "new_lines": new_range.len(),
+                "changes": changes,
+            }))
+        })
+        .collect();
+    json!({ "path": file_path, "kind": kind.json_name(), "hunks": hunks })
+}
+
+fn print_diff_json(files: Vec<serde_json::Value>) -> io::Result<()> {
+    let output = json!({ "files": files });
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&output).map_err(io::Error::other)?
+    );
+    Ok(())
 }
 
 #[derive(Clone, Copy)]
@@ -652,57 +1148,6 @@ struct DebugFailure {
     right: String,
 }
 
-fn build_debug_failure_report(
-    checks: DebugChecks,
-    files_checked: usize,
-    failures: &[(String, DebugFailure)],
-) -> String {
-    let mut out = String::new();
-    out.push_str("# Debug-format regression report\n\n");
-    out.push_str(&format!(
-        "- Checks: `{}`\n- Files checked: {}\n- Failures: {}\n\n",
-        format!("{:?}", checks).to_lowercase(),
-        files_checked,
-        failures.len()
-    ));
-
-    if failures.is_empty() {
-        out.push_str("All checks passed.\n");
-        return out;
-    }
-
-    out.push_str("## Failures\n\n");
-    for (idx, (file, failure)) in failures.iter().enumerate() {
-        let diff = TextDiff::from_lines(&failure.left, &failure.right);
-        let location_line = diff
-            .grouped_ops(0)
-            .first()
-            .and_then(|group| group.first().map(|op| op.old_range().start + 1))
-            .unwrap_or(1);
-
-        out.push_str(&format!(
-            "### {}. `{}` ({})\n\n",
-            idx + 1,
-            file,
-            failure.kind.label()
-        ));
-        out.push_str(&format!("- Approx. diff start line: {}\n\n", location_line));
-        out.push_str("```diff\n");
-        for change in diff.iter_all_changes() {
-            let sign = match change.tag() {
-                ChangeTag::Delete => "-",
-                ChangeTag::Insert => "+",
-                ChangeTag::Equal => " ",
-            };
-            out.push_str(sign);
-            out.push_str(change.value());
-        }
-        out.push_str("```\n\n");
-    }
-
-    out
-}
-
 #[derive(Default)]
 struct DebugRunArtifacts {
     losslessness: Option<(String, String)>,
@@ -832,15 +1277,792 @@ fn run_debug_checks_for_content(
     artifacts
 }
 
-fn main() -> io::Result<()> {
+#[cfg(feature = "lsp")]
+fn resolve_mv_paths(old: &Path, new: &Path) -> io::Result<(PathBuf, PathBuf)> {
+    let old_abs = fs::canonicalize(old).map_err(|err| {
+        io::Error::new(err.kind(), format!("cannot move {}: {err}", old.display()))
+    })?;
+    if !old_abs.is_file() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("cannot move {}: not a file", old.display()),
+        ));
+    }
+
+    let mut new_abs = std::env::current_dir()?.join(new);
+    if new_abs.is_dir()
+        && let Some(name) = old_abs.file_name()
+    {
+        new_abs.push(name);
+    }
+    // Canonicalize the deepest existing ancestor so the destination is
+    // comparable with the canonical document paths found by the scan.
+    let mut missing = Vec::new();
+    let mut existing = new_abs.as_path();
+    while !existing.exists() {
+        let (Some(parent), Some(name)) = (existing.parent(), existing.file_name()) else {
+            break;
+        };
+        missing.push(name.to_os_string());
+        existing = parent;
+    }
+    let mut new_abs = fs::canonicalize(existing)?;
+    for name in missing.into_iter().rev() {
+        new_abs.push(name);
+    }
+
+    if new_abs.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("cannot move to {}: destination exists", new.display()),
+        ));
+    }
+    Ok((old_abs, new_abs))
+}
+
+#[cfg(feature = "lsp")]
+fn display_relative(path: &Path, cwd: &Path) -> String {
+    path.strip_prefix(cwd).unwrap_or(path).display().to_string()
+}
+
+fn run_config_show(
+    config_path: Option<&Path>,
+    isolated: bool,
+    cli_cache_dir: Option<&Path>,
+    flavor_override: Option<Flavor>,
+    file: Option<&Path>,
+) -> io::Result<()> {
+    let start_dir = start_dir_for(file)?;
+    let (mut cfg, source, chain) = load_config_with_chain_for_cli(
+        config_path,
+        isolated,
+        cli_cache_dir,
+        &start_dir,
+        file,
+        flavor_override,
+    )?;
+    let cli_extensions = EXTENSION_OVERRIDES.get().cloned().unwrap_or_default();
+    let mut origins =
+        config_command::Origins::new(&chain, flavor_override.is_some(), cli_extensions, file)?;
+    if let Some(file) = file {
+        let input = fs::read_to_string(file)?;
+        origins.apply_frontmatter(&mut cfg, &input);
+    }
+    print!(
+        "{}",
+        config_command::render_resolved(&cfg, &source, &origins)
+    );
+    Ok(())
+}
+
+fn run_config_validate(config_path: Option<&Path>, quiet: bool) -> io::Result<()> {
+    let path = match config_path {
+        Some(path) => Some(path.to_path_buf()),
+        None => panache::config::discover(&std::env::current_dir()?),
+    };
+    let Some(path) = path else {
+        if !quiet {
+            println!("No config file found; nothing to validate");
+        }
+        return Ok(());
+    };
+    if !path.is_file() {
+        eprintln!("Error: config file not found: {}", path.display());
+        std::process::exit(EXIT_ERROR);
+    }
+
+    match panache::config::validate(&path) {
+        Ok(chain) => {
+            if !quiet {
+                match chain.len() {
+                    0 | 1 => println!("{} is valid", path.display()),
+                    n => println!(
+                        "{} is valid (including {} extended file(s))",
+                        path.display(),
+                        n - 1
+                    ),
+                }
+            }
+            Ok(())
+        }
+        Err(err) => {
+            eprintln!("Error: {err}");
+            std::process::exit(EXIT_ERROR);
+        }
+    }
+}
+
+fn run_config_init(force: bool, quiet: bool) -> io::Result<()> {
+    let path = std::env::current_dir()?.join(config_command::INIT_FILENAME);
+    if path.exists() && !force {
+        eprintln!(
+            "Error: {} already exists (use --force to overwrite)",
+            path.display()
+        );
+        std::process::exit(EXIT_ERROR);
+    }
+    fs::write(&path, config_command::INIT_TEMPLATE)?;
+    if !quiet {
+        println!("Wrote {}", path.display());
+    }
+    Ok(())
+}
+
+fn run_trust(path: Option<&Path>, revoke: bool, quiet: bool) -> io::Result<()> {
+    let target = match path {
+        Some(path) => path.to_path_buf(),
+        None => std::env::current_dir()?,
+    };
+    let (start_dir, input_path) = if target.is_dir() {
+        (target.clone(), None)
+    } else {
+        (start_dir_for(Some(&target))?, Some(target.as_path()))
+    };
+    // Load directly rather than through `load_config_for_cli`, which would
+    // already have dropped the external commands we are about to fingerprint.
+    let (cfg, source) = panache::config::load(None, &start_dir, input_path, None)?;
+
+    let panache::config::ConfigSource::Discovered(config_path) = source else {
+        if !quiet {
+            println!(
+                "No project config found for {}; nothing to trust",
+                target.display()
+            );
+        }
+        return Ok(());
+    };
+
+    let mut store = panache::trust::TrustStore::load();
+    if revoke {
+        let removed = store.revoke(&config_path);
+        store.save()?;
+        if !quiet {
+            if removed {
+                println!("Revoked trust for {}", config_path.display());
+            } else {
+                println!("{} was not trusted", config_path.display());
+            }
+        }
+        return Ok(());
+    }
+
+    store.trust(&config_path, &cfg);
+    store.save()?;
+    if !quiet {
+        println!("Trusted {}", config_path.display());
+        for (lang, chain) in &cfg.formatters {
+            for formatter in chain {
+                println!(
+                    "  formatter ({lang}): {} {}",
+                    formatter.cmd,
+                    formatter.args.join(" ")
+                );
+            }
+        }
+        for (lang, linter) in &cfg.linters {
+            println!("  linter ({lang}): {linter}");
+        }
+    }
+    Ok(())
+}
+
+#[cfg(feature = "lsp")]
+fn run_mv(old: &Path, new: &Path, dry_run: bool, quiet: bool) -> io::Result<()> {
+    let (old_abs, new_abs) = resolve_mv_paths(old, new)?;
+    let cwd = fs::canonicalize(std::env::current_dir()?)?;
+    let root = panache::includes::find_project_roots(&old_abs)
+        .quarto_first()
+        .unwrap_or_else(|| cwd.clone());
+
+    let edits = panache::lsp::plan_file_rename(&root, &old_abs, &new_abs);
+    let mut by_file: std::collections::BTreeMap<PathBuf, Vec<panache::lsp::FileRenameEdit>> =
+        std::collections::BTreeMap::new();
+    for edit in edits {
+        by_file.entry(edit.path.clone()).or_default().push(edit);
+    }
+
+    if dry_run {
+        println!(
+            "Would move {} -> {}",
+            display_relative(&old_abs, &cwd),
+            display_relative(&new_abs, &cwd)
+        );
+        for (path, edits) in &by_file {
+            let text = fs::read_to_string(path)?;
+            for edit in edits {
+                let before = &text[..edit.range.start];
+                let line = before.matches('\n').count() + 1;
+                let column = before.len() - before.rfind('\n').map_or(0, |idx| idx + 1) + 1;
+                println!(
+                    "{}:{line}:{column}: {} -> {}",
+                    display_relative(path, &cwd),
+                    &text[edit.range.clone()],
+                    edit.new_text
+                );
+            }
+        }
+        return Ok(());
+    }
+
+    // Rewrite every file in memory first, so nothing is touched if one of
+    // them cannot be read.
+    let mut updated_links = 0usize;
+    let mut rewrites = Vec::with_capacity(by_file.len());
+    for (path, mut edits) in by_file {
+        let original = fs::read_to_string(&path)?;
+        let mut text = original.clone();
+        edits.sort_by_key(|edit| std::cmp::Reverse(edit.range.start));
+        for edit in &edits {
+            text.replace_range(edit.range.clone(), &edit.new_text);
+        }
+        updated_links += edits.len();
+        // The moved file's own links are written at its new location.
+        let path = if path == old_abs {
+            new_abs.clone()
+        } else {
+            path
+        };
+        rewrites.push((path, original, text));
+    }
+
+    if let Some(parent) = new_abs.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::rename(&old_abs, &new_abs)?;
+    for (idx, (path, _, text)) in rewrites.iter().enumerate() {
+        if let Err(err) = fs::write(path, text) {
+            // Undo the move and the files written so far, including the one
+            // that failed part way.
+            for (path, original, _) in &rewrites[..=idx] {
+                let _ = fs::write(path, original);
+            }
+            let _ = fs::rename(&new_abs, &old_abs);
+            return Err(err);
+        }
+    }
+
+    if !quiet {
+        let link_word = if updated_links == 1 { "link" } else { "links" };
+        let file_word = if rewrites.len() == 1 { "file" } else { "files" };
+        println!(
+            "Moved {} -> {} (updated {updated_links} {link_word} in {} {file_word})",
+            display_relative(&old_abs, &cwd),
+            display_relative(&new_abs, &cwd),
+            rewrites.len()
+        );
+    }
+    Ok(())
+}
+
+/// Print or write the `lang` code blocks of each input, plus the source map
+/// if asked for.
+fn run_extract(
+    files: Vec<PathBuf>,
+    lang: &str,
+    out_dir: Option<&Path>,
+    source_map: Option<&Path>,
+    stdin_filename: Option<&Path>,
+    load_config: impl Fn(Option<&Path>) -> io::Result<panache::Config>,
+    quiet: bool,
+) -> io::Result<()> {
+    let files = normalize_input_paths(files)?;
+    let inputs: Vec<Option<PathBuf>> = if files.is_empty() {
+        vec![None]
+    } else {
+        files.into_iter().map(Some).collect()
+    };
+    if let Some(dir) = out_dir {
+        fs::create_dir_all(dir)?;
+    }
+
+    let mut stream = String::new();
+    let mut entries = Vec::new();
+    for file in &inputs {
+        let name = file.as_deref().or(stdin_filename);
+        let cfg = load_config(name)?;
+        let input = read_all(file.as_ref())?;
+        let tree = parse(&input, Some(cfg.clone()));
+        let language = cfg.language_key(lang);
+        let source = name.map_or_else(|| "-".to_string(), |path| path.display().to_string());
+        let stem = name.and_then(Path::file_stem).map_or_else(
+            || "stdin".to_string(),
+            |stem| stem.to_string_lossy().into_owned(),
+        );
+
+        let cells = panache::extract::extract_cells(&tree, &input, &cfg, lang);
+        for (idx, cell) in cells.iter().enumerate() {
+            let (output, output_line) = match out_dir {
+                Some(dir) => {
+                    let path = dir.join(format!(
+                        "{stem}-{}.{}",
+                        idx + 1,
+                        panache::extract::file_extension(&language)
+                    ));
+                    let mut text = String::new();
+                    let line = panache::extract::write_cell(&mut text, cell, &language);
+                    fs::write(&path, text)?;
+                    (path.display().to_string(), line)
+                }
+                None => (
+                    "-".to_string(),
+                    panache::extract::write_cell(&mut stream, cell, &language),
+                ),
+            };
+            entries.push(json!({
+                "output": output,
+                "output_line": output_line,
+                "source": source,
+                "source_line": cell.start_line,
+                "lines": cell.content.lines().count(),
+                "label": cell.label,
+            }));
+        }
+    }
+
+    if out_dir.is_none() {
+        io::stdout().write_all(stream.as_bytes())?;
+    }
+    if let Some(path) = source_map {
+        let json_output = serde_json::to_string_pretty(&entries).map_err(io::Error::other)?;
+        fs::write(path, json_output + "\n")?;
+    }
+    if !quiet {
+        if entries.is_empty() {
+            eprintln!("No `{lang}` code blocks found");
+        } else if let Some(dir) = out_dir {
+            println!(
+                "Wrote {} to {}",
+                file_count_label(entries.len(), "file", "files"),
+                dir.display()
+            );
+        }
+    }
+    Ok(())
+}
+
+fn run_stats(
+    files: Vec<PathBuf>,
+    output_format: StatsFormat,
+    stdin_filename: Option<&Path>,
+    load_config: impl Fn(Option<&Path>) -> io::Result<panache::Config>,
+) -> io::Result<()> {
+    let files = normalize_input_paths(files)?;
+    let inputs: Vec<Option<PathBuf>> = if files.is_empty() {
+        vec![None]
+    } else {
+        files.into_iter().map(Some).collect()
+    };
+
+    let mut results = Vec::new();
+    for file in &inputs {
+        let name = file.as_deref().or(stdin_filename);
+        let cfg = load_config(name)?;
+        let input = read_all(file.as_ref())?;
+        let path = name.map_or_else(|| "-".to_string(), |path| path.display().to_string());
+        results.push((path, panache::stats(&input, Some(cfg))));
+    }
+
+    let mut out = String::new();
+    match output_format {
+        StatsFormat::Json => {
+            let entries = results
+                .iter()
+                .map(|(path, stats)| {
+                    let mut entry = serde_json::to_value(stats).map_err(io::Error::other)?;
+                    entry["path"] = json!(path);
+                    Ok(entry)
+                })
+                .collect::<io::Result<Vec<_>>>()?;
+            out = serde_json::to_string_pretty(&entries).map_err(io::Error::other)? + "\n";
+        }
+        StatsFormat::Table => {
+            let mut total = panache::stats::DocumentStats::default();
+            for (path, stats) in &results {
+                write_stats_table(&mut out, path, stats);
+                total.words += stats.words;
+                for (sum, count) in total.headings.iter_mut().zip(stats.headings) {
+                    *sum += count;
+                }
+                for (language, count) in &stats.code_cells {
+                    *total.code_cells.entry(language.clone()).or_default() += count;
+                }
+                total.links += stats.links;
+                total.images += stats.images;
+                total.footnotes += stats.footnotes;
+            }
+            if results.len() > 1 {
+                write_stats_table(&mut out, "total", &total);
+            }
+        }
+    }
+    io::stdout().write_all(out.as_bytes())
+}
+
+fn run_render(
+    files: Vec<PathBuf>,
+    to: RenderFormat,
+    stdin_filename: Option<&Path>,
+    load_config: impl Fn(Option<&Path>) -> io::Result<panache::Config>,
+) -> io::Result<()> {
+    let files = normalize_input_paths(files)?;
+    let inputs: Vec<Option<PathBuf>> = if files.is_empty() {
+        vec![None]
+    } else {
+        files.into_iter().map(Some).collect()
+    };
+    let format = match to {
+        RenderFormat::Plain => panache::render::RenderFormat::Plain,
+    };
+
+    let mut outputs = Vec::new();
+    for file in &inputs {
+        let cfg = load_config(file.as_deref().or(stdin_filename))?;
+        let input = read_all(file.as_ref())?;
+        outputs.push(panache::render(&input, format, Some(cfg)));
+    }
+    let out = outputs
+        .iter()
+        .filter(|output| !output.is_empty())
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join("\n");
+    io::stdout().write_all(out.as_bytes())
+}
+
+/// Run `panache organize`. Returns whether any file is (or, with `check`,
+/// would be) changed.
+fn run_organize(
+    files: Vec<PathBuf>,
+    sort_headings_under: &[String],
+    check: bool,
+    stdin_filename: Option<&Path>,
+    load_config: impl Fn(Option<&Path>) -> io::Result<panache::Config>,
+    quiet: bool,
+) -> io::Result<bool> {
+    let files = normalize_input_paths(files)?;
+    let inputs: Vec<Option<PathBuf>> = if files.is_empty() {
+        vec![None]
+    } else {
+        files.into_iter().map(Some).collect()
+    };
+
+    let mut changed = Vec::new();
+    for file in &inputs {
+        let name = file.as_deref().or(stdin_filename);
+        let cfg = load_config(name)?;
+        let headings = if sort_headings_under.is_empty() {
+            cfg.organize.sort_headings_under.clone()
+        } else {
+            sort_headings_under.to_vec()
+        };
+        if headings.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "nothing to organize: pass --sort-headings-under or set \
+                 `sort-headings-under` in [organize]",
+            ));
+        }
+        let headings: Vec<&str> = headings.iter().map(String::as_str).collect();
+        let path = name.map_or_else(|| "-".to_string(), |path| path.display().to_string());
+        let input = read_all(file.as_ref())?;
+        let output = panache::organize::sort_headings_under(&input, &headings, Some(cfg))
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, format!("{path}: {err}")))?;
+
+        if output != input {
+            changed.push(path);
+        }
+        match file {
+            None if !check => io::stdout().write_all(output.as_bytes())?,
+            Some(file) if !check && output != input => write_in_place(file, &output, None)?,
+            _ => {}
+        }
+    }
+
+    if check {
+        for path in &changed {
+            anstream::println!("Would organize: {}", term::paint(term::PATH, path));
+        }
+    }
+    // Organized standard input goes to stdout, with nothing to summarize.
+    let to_stdout = inputs.iter().all(Option::is_none) && !check;
+    if !quiet && !to_stdout {
+        let verb = if check {
+            ["file would be organized", "files would be organized"]
+        } else {
+            ["file organized", "files organized"]
+        };
+        println!(
+            "{}, {}",
+            file_count_label(changed.len(), verb[0], verb[1]),
+            file_count_label(
+                inputs.len() - changed.len(),
+                "file left unchanged",
+                "files left unchanged"
+            )
+        );
+    }
+    Ok(!changed.is_empty())
+}
+
+/// Append one `panache stats` table block to `out`. The longest line is left
+/// out when it has no line number, as in the totals.
+fn write_stats_table(out: &mut String, title: &str, stats: &panache::stats::DocumentStats) {
+    use std::fmt::Write as _;
+
+    fn breakdown(total: usize, parts: Vec<String>) -> String {
+        if parts.is_empty() {
+            total.to_string()
+        } else {
+            format!("{total} ({})", parts.join(", "))
+        }
+    }
+
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    let headings = breakdown(
+        stats.headings.iter().sum(),
+        (1..)
+            .zip(stats.headings)
+            .filter(|(_, count)| *count > 0)
+            .map(|(level, count)| format!("h{level}: {count}"))
+            .collect(),
+    );
+    let code_cells = breakdown(
+        stats.code_cells.values().sum(),
+        stats
+            .code_cells
+            .iter()
+            .map(|(language, count)| format!("{language}: {count}"))
+            .collect(),
+    );
+    let _ = writeln!(out, "{title}");
+    let _ = writeln!(out, "  words         {}", stats.words);
+    let _ = writeln!(out, "  headings      {headings}");
+    let _ = writeln!(out, "  code cells    {code_cells}");
+    let _ = writeln!(out, "  links         {}", stats.links);
+    let _ = writeln!(out, "  images        {}", stats.images);
+    let _ = writeln!(out, "  footnotes     {}", stats.footnotes);
+    if stats.longest_line.line > 0 {
+        let _ = writeln!(
+            out,
+            "  longest line  {} columns (line {})",
+            stats.longest_line.width, stats.longest_line.line
+        );
+    }
+}
+
+/// How long a URL that loaded is trusted before `check-links --online`
+/// requests it again.
+const URL_CACHE_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Settings of `panache check-links` beyond its inputs.
+struct CheckLinksOptions {
+    online: Option<panache::link_check::OnlineOptions>,
+    message_format: MessageFormat,
+    json: bool,
+    quiet: bool,
+}
+
+/// Check the links of `files` (stdin when empty) and print the broken ones.
+/// Returns whether any were found.
+fn run_check_links(
+    files: &[PathBuf],
+    options: &CheckLinksOptions,
+    stdin_filename: Option<&Path>,
+    mut cache: Option<CliCache>,
+    load_config: impl Fn(Option<&Path>) -> io::Result<panache::Config>,
+) -> io::Result<bool> {
+    use panache::link_check::{BROKEN_URL_CODE, LinkChecker, UrlStatus};
+    use std::collections::{HashMap, HashSet};
+
+    let inputs: Vec<Option<PathBuf>> = if files.is_empty() {
+        vec![None]
+    } else {
+        files.iter().cloned().map(Some).collect()
+    };
+
+    let mut checker = LinkChecker::new(|path| load_config(Some(path)).unwrap_or_default());
+    let mut documents = Vec::new();
+    for file in &inputs {
+        let name = file.as_deref().or(stdin_filename);
+        let cfg = load_config(name)?;
+        let input = read_all(file.as_ref())?;
+        let tree = parse(&input, Some(cfg.clone()));
+        let path = name.unwrap_or(Path::new("stdin.md"));
+        let links = checker.check_document(path, &input, &tree, &cfg);
+        documents.push((name.map(Path::to_path_buf), input, links));
+    }
+
+    if let Some(online) = &options.online {
+        let mut statuses: HashMap<String, UrlStatus> = HashMap::new();
+        let mut pending = Vec::new();
+        let mut seen = HashSet::new();
+        for link in documents.iter().flat_map(|(_, _, links)| &links.external) {
+            if !seen.insert(link.url.as_str()) {
+                continue;
+            }
+            match cache
+                .as_ref()
+                .and_then(|cache| cache.get_url(&link.url, URL_CACHE_MAX_AGE))
+            {
+                Some(status) => {
+                    statuses.insert(link.url.clone(), UrlStatus::Ok(status));
+                }
+                None => pending.push(link.url.clone()),
+            }
+        }
+        log::debug!(
+            "Requesting {} URL(s), {} cached",
+            pending.len(),
+            statuses.len()
+        );
+
+        for (url, status) in panache::link_check::check_urls(&pending, online) {
+            if let (UrlStatus::Ok(code), Some(cache)) = (&status, cache.as_mut()) {
+                cache.put_url(&url, *code);
+            }
+            statuses.insert(url, status);
+        }
+        if let Some(cache) = cache.as_mut()
+            && let Err(err) = cache.save_if_dirty()
+        {
+            log::warn!("Failed to save CLI cache: {err}");
+        }
+
+        for (_, _, links) in &mut documents {
+            for link in &links.external {
+                if let Some(message) = statuses
+                    .get(&link.url)
+                    .and_then(|status| status.message(&link.url))
+                {
+                    links.diagnostics.push(panache::linter::Diagnostic::warning(
+                        link.location.clone(),
+                        BROKEN_URL_CODE,
+                        message,
+                    ));
+                }
+            }
+            links
+                .diagnostics
+                .sort_by_key(|diagnostic| diagnostic.location.range.start());
+        }
+    }
+
+    let broken = documents
+        .iter()
+        .any(|(_, _, links)| !links.diagnostics.is_empty());
+    if options.json {
+        let entries: Vec<serde_json::Value> = documents
+            .iter()
+            .map(|(name, _, links)| {
+                let diagnostics: Vec<serde_json::Value> = links
+                    .diagnostics
+                    .iter()
+                    .map(panache::linter::Diagnostic::to_json)
+                    .collect();
+                let path = name
+                    .as_ref()
+                    .map_or_else(|| "-".to_string(), |path| path.display().to_string());
+                json!({ "path": path, "diagnostics": diagnostics })
+            })
+            .collect();
+        let out = serde_json::to_string_pretty(&entries).map_err(io::Error::other)? + "\n";
+        io::stdout().write_all(out.as_bytes())?;
+        return Ok(broken);
+    }
+
+    let mut counts = SeverityCounts::default();
+    for (name, input, links) in &documents {
+        if links.diagnostics.is_empty() {
+            continue;
+        }
+        if !options.quiet {
+            print_diagnostics(
+                &links.diagnostics,
+                name.as_deref(),
+                Some(input),
+                options.message_format,
+                false,
+            );
+        }
+        counts.add(&links.diagnostics);
+    }
+    if broken {
+        anstream::eprintln!(
+            "\nFound {} broken link(s) across {} file(s): {}",
+            counts.total(),
+            documents.len(),
+            counts
+        );
+    } else if !options.quiet {
+        println!("No broken links found in {} file(s)", documents.len());
+    }
+    Ok(broken)
+}
+
+/// Exit code for runtime errors (unreadable input, invalid config, bad
+/// arguments), kept distinct from the `1` that `format --check` and `lint` use
+/// for "files need attention" so CI scripts can tell the two apart. Clap's own
+/// usage errors exit with the same code.
+const EXIT_ERROR: i32 = 2;
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("Error: {err}");
+        std::process::exit(EXIT_ERROR);
+    }
+}
+
+fn run() -> io::Result<()> {
     let cli = Cli::parse();
-    let use_color = color_enabled(cli.color, cli.no_color);
+    let use_color = term::init(cli.color, cli.no_color);
     panache::set_warning_color_override(use_color);
     let debug_log = match &cli.command {
         Commands::Lsp { debug } if *debug => Some(init_lsp_debug_log()?),
         _ => None,
     };
     init_logger(debug_log.as_deref());
+    if let Some(safe) = cli.safe {
+        panache::trust::set_safe_mode(safe);
+    }
+    let sandbox_overrides = cli
+        .sandbox
+        .iter()
+        .map(|raw| {
+            raw.split_once('=')
+                .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+                .ok_or_else(|| format!("invalid --sandbox `{raw}`: expected key=value"))
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .and_then(panache::config::set_sandbox_overrides);
+    if let Err(err) = sandbox_overrides {
+        eprintln!("Error: {err}");
+        std::process::exit(EXIT_ERROR);
+    }
+    let cli_flavor_spec = match cli.flavor.as_deref().map(str::parse::<FlavorSpec>) {
+        Some(Ok(spec)) => Some(spec),
+        Some(Err(err)) => {
+            eprintln!("Error: invalid value for --flavor: {err}");
+            std::process::exit(EXIT_ERROR);
+        }
+        None => None,
+    };
+    let cli_flavor = cli_flavor_spec.as_ref().map(|spec| spec.flavor);
+    let flavor_extensions = cli_flavor_spec.map(|spec| spec.extensions);
+    match extension_overrides(
+        flavor_extensions.as_deref().unwrap_or_default(),
+        &cli.enable_extension,
+        &cli.disable_extension,
+    ) {
+        Ok(overrides) => {
+            let _ = EXTENSION_OVERRIDES.set(overrides);
+        }
+        Err(err) => {
+            eprintln!("Error: {err}");
+            std::process::exit(EXIT_ERROR);
+        }
+    }
 
     match cli.command {
         Commands::Parse { file, to, json } => {
@@ -853,7 +2075,7 @@ fn main() -> io::Result<()> {
                 cli.cache_dir.as_deref(),
                 &start_dir,
                 input_path,
-                cli.flavor.map(Flavor::from),
+                cli_flavor,
             )?;
 
             if let Some(path) = cfg_source.path() {
@@ -886,28 +2108,93 @@ fn main() -> io::Result<()> {
         Commands::Format {
             files,
             check,
+            diff_format,
+            stdout,
+            edits,
+            backup,
+            list_different,
+            summary,
+            ignore_line_endings,
+            line_ending,
+            ignore_bom,
             range,
+            embedded,
             force_exclude,
+            staged,
+            restage,
+            changed,
+            since,
+            files_from,
+            include,
+            exclude,
+            fail_safe,
+            max_width_strict,
+            timings,
+            report,
             option,
         } => {
-            let files = match normalize_input_paths(files) {
+            panache::config::set_command_line_options(
+                option
+                    .iter()
+                    .filter_map(|raw| raw.split_once('='))
+                    .map(|(key, _)| key.trim().to_string())
+                    .collect(),
+            );
+            // `-l` is a check that reports paths instead of diffs.
+            let check = check || list_different;
+            let embedded = embedded.map(EmbeddedSyntax::from);
+            // roxygen2 and most docstring tooling render CommonMark.
+            let flavor_override = cli_flavor.or(embedded.map(|_| Flavor::CommonMark));
+            let mut files = match normalize_input_paths(files) {
                 Ok(files) => files,
                 Err(err) => {
                     eprintln!("Error: {}", err);
-                    std::process::exit(1);
+                    std::process::exit(EXIT_ERROR);
                 }
             };
+            if staged {
+                files = panache::git::staged_files(Path::new("."))?;
+            }
+            // Restaging a file that also has unstaged changes would stage them
+            // too, so those files are left for the user to add.
+            let unstaged = if restage {
+                panache::git::unstaged_files(Path::new("."))?
+            } else {
+                Vec::new()
+            };
+            let changed_ranges = if changed {
+                panache::git::changed_line_ranges(
+                    Path::new("."),
+                    since.as_deref().unwrap_or("HEAD"),
+                )?
+            } else {
+                std::collections::HashMap::new()
+            };
+            if changed {
+                files = changed_ranges.keys().cloned().collect();
+                files.sort();
+            }
+            if let Some(list) = files_from.as_deref() {
+                files.extend(read_path_list(list)?);
+            }
+            // An empty list must not fall through to formatting stdin.
+            if (staged || changed || files_from.is_some()) && files.is_empty() {
+                if !cli.quiet {
+                    println!("No files to format");
+                }
+                return Ok(());
+            }
             // Parse range if provided (only valid for single file or stdin)
             let parsed_range = if let Some(range_str) = range {
                 if files.len() > 1 {
                     eprintln!("Error: --range cannot be used with multiple files");
-                    std::process::exit(1);
+                    std::process::exit(EXIT_ERROR);
                 }
                 match parse_range(&range_str) {
                     Ok(r) => Some(r),
                     Err(e) => {
                         eprintln!("Error: {}", e);
-                        std::process::exit(1);
+                        std::process::exit(EXIT_ERROR);
                     }
                 }
             } else {
@@ -923,11 +2210,16 @@ fn main() -> io::Result<()> {
                     cli.cache_dir.as_deref(),
                     &start_dir,
                     cli.stdin_filename.as_deref(),
-                    cli.flavor.map(Flavor::from),
+                    flavor_override,
                 )?;
+                cfg.fail_safe = fail_safe;
+                cfg.max_width_strict |= max_width_strict;
+                if let Some(ending) = line_ending {
+                    cfg.line_ending = Some(ending.into());
+                }
                 if let Err(err) = apply_format_overrides(&mut cfg, &option) {
                     eprintln!("Error: {err}");
-                    std::process::exit(2);
+                    std::process::exit(EXIT_ERROR);
                 }
 
                 if let Some(path) = cfg_source.path() {
@@ -937,11 +2229,96 @@ fn main() -> io::Result<()> {
                 }
 
                 let input = read_all(None)?;
-                let output = format(&input, Some(cfg), parsed_range);
+                if embedded.is_none() {
+                    for warning in frontmatter_warning_lines("<stdin>", &input, &cfg) {
+                        eprintln!("{warning}");
+                    }
+                }
+                if cli.verbose && embedded.is_none() {
+                    for warning in parse_warning_lines("<stdin>", &input, &cfg) {
+                        eprintln!("{warning}");
+                    }
+                }
 
-                if check {
-                    if input != output {
-                        print_diff("<stdin>", &input, &output, use_color);
+                if !check
+                    && parsed_range.is_none()
+                    && embedded.is_none()
+                    && report.is_none()
+                    && edits.is_none()
+                {
+                    // Stdin: stream to stdout so large documents are never held
+                    // in memory as a second, fully formatted copy.
+                    let mut stdout = io::stdout().lock();
+                    let failures = print_timings(timings, "<stdin>", || {
+                        panache::format_to_with_failures(&input, Some(cfg), &mut stdout)
+                    })?;
+                    stdout.flush()?;
+                    for warning in fail_safe_warning_lines("<stdin>", &failures) {
+                        eprintln!("{warning}");
+                    }
+                    return Ok(());
+                }
+
+                let start = Instant::now();
+                let (output, warnings) = print_timings(timings, "<stdin>", || match embedded {
+                    Some(syntax) => Ok((format_embedded(&input, syntax, &cfg), Vec::new())),
+                    None => try_format_fail_safe("<stdin>", &input, &cfg, parsed_range),
+                })?;
+                for warning in &warnings {
+                    eprintln!("{warning}");
+                }
+                if let Some(path) = report.as_deref() {
+                    let changed = ChangeKind::classify(&input, &output)
+                        .is_some_and(|kind| !kind.is_ignored(ignore_line_endings, ignore_bom));
+                    let elapsed = start.elapsed();
+                    FormatReport {
+                        check,
+                        files: vec![FileReport::new(
+                            "<stdin>", &input, &output, changed, elapsed,
+                        )],
+                        duration: elapsed,
+                    }
+                    .write(path)?;
+                }
+
+                if edits.is_some() {
+                    let change = ChangeKind::classify(&input, &output)
+                        .filter(|kind| !kind.is_ignored(ignore_line_endings, ignore_bom));
+                    let files = change
+                        .map(|_| edits_to_json("<stdin>", &input, &output))
+                        .into_iter()
+                        .collect();
+                    print_diff_json(files)?;
+                } else if check {
+                    let change = ChangeKind::classify(&input, &output)
+                        .filter(|kind| !kind.is_ignored(ignore_line_endings, ignore_bom));
+                    let changed = change.is_some();
+                    match (diff_format, change) {
+                        _ if list_different => {
+                            if changed {
+                                println!("<stdin>");
+                            }
+                        }
+                        (DiffFormat::Json, change) => {
+                            let files = change
+                                .map(|kind| diff_to_json("<stdin>", &input, &output, kind))
+                                .into_iter()
+                                .collect();
+                            print_diff_json(files)?;
+                        }
+                        (_, None) => {}
+                        (diff_format, Some(kind)) => {
+                            print_check_change("<stdin>", &input, &output, kind, diff_format)
+                        }
+                    }
+                    let changed_files: Vec<String> = change
+                        .map(|kind| summary_label("<stdin>", kind))
+                        .into_iter()
+                        .collect();
+                    if summary && (list_different || diff_format != DiffFormat::Json) {
+                        print_check_summary(&changed_files, 1, list_different);
+                    }
+                    if changed {
                         std::process::exit(1);
                     }
                 } else {
@@ -963,22 +2340,37 @@ fn main() -> io::Result<()> {
             } else {
                 start_dir_for(None)?
             };
-            let (traversal_cfg, traversal_cfg_source) = load_config_for_cli(
+            let (mut traversal_cfg, traversal_cfg_source) = load_config_for_cli(
                 cli.config.as_deref(),
                 cli.isolated,
                 cli.cache_dir.as_deref(),
                 &traversal_start_dir,
                 traversal_anchor,
-                cli.flavor.map(Flavor::from),
+                flavor_override,
             )?;
+            apply_path_filter_overrides(&mut traversal_cfg, &include, &exclude);
+            if let Some(syntax) = embedded
+                && include.is_empty()
+            {
+                let default_include: Vec<String> = syntax
+                    .default_include()
+                    .iter()
+                    .map(|pattern| pattern.to_string())
+                    .collect();
+                apply_path_filter_overrides(&mut traversal_cfg, &default_include, &[]);
+            }
             let anchor = panache::config::anchor_dir(&traversal_cfg_source, &traversal_start_dir);
-            let expanded_files = expand_paths(
-                &files,
-                &traversal_cfg,
-                &anchor,
-                force_exclude,
-                cli.flavor.is_some(),
-            )?;
+            let expanded_files = if staged || changed {
+                filter_as_discovered(&files, &traversal_cfg, &anchor)?
+            } else {
+                expand_paths(
+                    &files,
+                    &traversal_cfg,
+                    &anchor,
+                    force_exclude,
+                    cli_flavor.is_some() || embedded.is_some(),
+                )?
+            };
             let mut cache = if cli.no_cache || !traversal_cfg.cache {
                 None
             } else {
@@ -990,12 +2382,12 @@ fn main() -> io::Result<()> {
             };
 
             if expanded_files.is_empty() {
-                if force_exclude {
+                if force_exclude || staged || changed {
                     return Ok(());
                 }
                 if has_explicit_file_targets(&files) {
                     eprintln!("Error: No supported files found");
-                    std::process::exit(1);
+                    std::process::exit(EXIT_ERROR);
                 }
                 if !cli.quiet {
                     println!("No supported files found");
@@ -1014,8 +2406,22 @@ fn main() -> io::Result<()> {
                 file_path: PathBuf,
                 input: String,
                 output: String,
+                /// Set when the output was streamed to a staged file instead
+                /// of `output`: the file, or `None` if nothing changed.
+                staged: Option<Option<tempfile::TempPath>>,
+                warnings: Vec<String>,
             }
 
+            // Output written back in place is streamed to a staged file rather
+            // than built as a string, unless it is needed to report or compare
+            // the change.
+            let stream = !check
+                && edits.is_none()
+                && !stdout
+                && report.is_none()
+                && !ignore_line_endings
+                && !ignore_bom;
+
             let cache_shared: Option<Arc<Mutex<CliCache>>> =
                 cache.take().map(|c| Arc::new(Mutex::new(c)));
 
@@ -1027,11 +2433,16 @@ fn main() -> io::Result<()> {
                     cli.cache_dir.as_deref(),
                     &start_dir,
                     Some(file_path),
-                    cli.flavor.map(Flavor::from),
+                    flavor_override,
                 )?;
+                cfg.fail_safe = fail_safe;
+                cfg.max_width_strict |= max_width_strict;
+                if let Some(ending) = line_ending {
+                    cfg.line_ending = Some(ending.into());
+                }
                 if let Err(err) = apply_format_overrides(&mut cfg, &option) {
                     eprintln!("Error: {err}");
-                    std::process::exit(2);
+                    std::process::exit(EXIT_ERROR);
                 }
                 // Size the shared external-tool budget from the user-configured
                 // value, then split that ceiling across the files processed
@@ -1056,7 +2467,16 @@ fn main() -> io::Result<()> {
                     FormatCacheMode::Write
                 };
 
-                let output = if parsed_range.is_none() {
+                let label = file_path.display().to_string();
+                let mut warnings = Vec::new();
+                let mut staged = None;
+                let output = if let Some(syntax) = embedded {
+                    format_embedded(&input, syntax, &cfg)
+                } else if changed {
+                    let ranges = changed_ranges.get(file_path).map_or(&[][..], Vec::as_slice);
+                    panache::try_format_ranges(&input, Some(cfg.clone()), ranges)
+                        .map_err(io::Error::other)?
+                } else if parsed_range.is_none() {
                     if let Some(cache_handle) = cache_shared.as_ref() {
                         let file_fingerprint = CliCache::file_fingerprint(&input);
                         let config_fingerprint = CliCache::config_fingerprint(&cfg);
@@ -1079,10 +2499,38 @@ fn main() -> io::Result<()> {
                         };
                         if let Some(cached) = cached {
                             cached
+                        } else if stream {
+                            let tmp;
+                            (tmp, warnings) = format_to_staged(file_path, &input, &cfg)?;
+                            let mut guard = cache_handle.lock().unwrap();
+                            // Only an unchanged result can be cached without
+                            // holding the output.
+                            if tmp.is_none()
+                                && warnings.is_empty()
+                                && guard.supports_format_mode(&cfg, mode)
+                            {
+                                guard.put_format(
+                                    file_path,
+                                    mode,
+                                    FormatStoreArgs {
+                                        file_fingerprint,
+                                        config_fingerprint,
+                                        tool_fingerprint,
+                                        unchanged: true,
+                                        output: input.clone(),
+                                    },
+                                );
+                            }
+                            staged = Some(tmp);
+                            String::new()
                         } else {
-                            let output = format(&input, Some(cfg.clone()), parsed_range);
+                            let output;
+                            (output, warnings) =
+                                try_format_fail_safe(&label, &input, &cfg, parsed_range)?;
                             let mut guard = cache_handle.lock().unwrap();
-                            if guard.supports_format_mode(&cfg, mode) {
+                            // A result with recovered failures is not cached, so
+                            // the next run reports them again.
+                            if warnings.is_empty() && guard.supports_format_mode(&cfg, mode) {
                                 let unchanged = input == output;
                                 guard.put_format(
                                     file_path,
@@ -1098,26 +2546,52 @@ fn main() -> io::Result<()> {
                             }
                             output
                         }
+                    } else if stream {
+                        let tmp;
+                        (tmp, warnings) = format_to_staged(file_path, &input, &cfg)?;
+                        staged = Some(tmp);
+                        String::new()
                     } else {
-                        format(&input, Some(cfg.clone()), parsed_range)
+                        let output;
+                        (output, warnings) =
+                            try_format_fail_safe(&label, &input, &cfg, parsed_range)?;
+                        output
                     }
                 } else {
-                    format(&input, Some(cfg.clone()), parsed_range)
+                    let output;
+                    (output, warnings) = try_format_fail_safe(&label, &input, &cfg, parsed_range)?;
+                    output
                 };
 
+                if embedded.is_none() {
+                    warnings.extend(frontmatter_warning_lines(&label, &input, &cfg));
+                }
+                if cli.verbose && embedded.is_none() {
+                    warnings.extend(parse_warning_lines(&label, &input, &cfg));
+                }
+
                 Ok(FormatOutcome {
                     file_path: file_path.clone(),
                     input,
                     output,
+                    staged,
+                    warnings,
                 })
             };
 
-            let outcomes: Vec<io::Result<FormatOutcome>> = if parallel {
+            let run_start = Instant::now();
+            let timed_process_file = |file_path: &PathBuf| {
+                let start = Instant::now();
+                let label = file_path.display().to_string();
+                let outcome = print_timings(timings, &label, || process_file(file_path));
+                (outcome, start.elapsed())
+            };
+            let outcomes: Vec<(io::Result<FormatOutcome>, Duration)> = if parallel {
                 use rayon::prelude::*;
                 let pool = build_pool(workers);
-                pool.install(|| expanded_files.par_iter().map(&process_file).collect())
+                pool.install(|| expanded_files.par_iter().map(&timed_process_file).collect())
             } else {
-                expanded_files.iter().map(&process_file).collect()
+                expanded_files.iter().map(&timed_process_file).collect()
             };
 
             // Recover the cache for the final flush.
@@ -1134,39 +2608,144 @@ fn main() -> io::Result<()> {
 
             // Sequential post-pass: emit messages, write files, tally counters.
             // Keeps output deterministic in input order.
-            let mut all_formatted = true;
+            let mut changed_files = Vec::new();
+            let mut reformatted_files = Vec::new();
             let mut reformatted_count = 0usize;
             let mut unchanged_count = 0usize;
-            for outcome in outcomes {
-                let o = outcome?;
+            let json_diff = check && !list_different && diff_format == DiffFormat::Json;
+            let mut json_files = Vec::new();
+            let mut report_files = Vec::new();
+            let mut failed = false;
+            for (file_path, (outcome, elapsed)) in expanded_files.iter().zip(outcomes) {
+                let o = match outcome {
+                    Ok(o) => o,
+                    // One bad file must not abort a directory run: report it
+                    // and exit with an error once the others are done.
+                    Err(err) => {
+                        eprintln!("Error: {}: {err}", file_path.display());
+                        if report.is_some() {
+                            report_files.push(FileReport::error(
+                                &file_path.display().to_string(),
+                                &err,
+                                elapsed,
+                            ));
+                        }
+                        failed = true;
+                        continue;
+                    }
+                };
+                for warning in &o.warnings {
+                    eprintln!("{warning}");
+                }
+                if let Some(staged) = o.staged {
+                    match staged {
+                        Some(tmp) => {
+                            persist_in_place(&o.file_path, tmp, backup.as_deref())?;
+                            if !cli.quiet {
+                                println!("Formatted {}", o.file_path.display());
+                            }
+                            reformatted_files.push(o.file_path);
+                            reformatted_count += 1;
+                        }
+                        None => unchanged_count += 1,
+                    }
+                    continue;
+                }
+                let change = ChangeKind::classify(&o.input, &o.output)
+                    .filter(|kind| !kind.is_ignored(ignore_line_endings, ignore_bom));
+                if report.is_some() {
+                    report_files.push(FileReport::new(
+                        &o.file_path.display().to_string(),
+                        &o.input,
+                        &o.output,
+                        change.is_some(),
+                        elapsed,
+                    ));
+                }
                 if check {
-                    if o.input != o.output {
+                    if let Some(kind) = change {
                         let file_name = o.file_path.to_str().unwrap_or("<unknown>");
-                        print_diff(file_name, &o.input, &o.output, use_color);
-                        all_formatted = false;
-                    } else if expanded_files.len() == 1 && !cli.quiet {
+                        match diff_format {
+                            _ if list_different => println!("{}", o.file_path.display()),
+                            DiffFormat::Json => {
+                                json_files.push(diff_to_json(file_name, &o.input, &o.output, kind))
+                            }
+                            _ => print_check_change(
+                                file_name,
+                                &o.input,
+                                &o.output,
+                                kind,
+                                diff_format,
+                            ),
+                        }
+                        changed_files.push(summary_label(&o.file_path.display().to_string(), kind));
+                    } else if expanded_files.len() == 1
+                        && !cli.quiet
+                        && !json_diff
+                        && !list_different
+                        && !summary
+                    {
                         println!("{} is correctly formatted", o.file_path.display());
                     }
-                } else if o.input != o.output {
-                    fs::write(&o.file_path, &o.output)?;
+                } else if edits.is_some() {
+                    if change.is_some() {
+                        let file_name = o.file_path.to_str().unwrap_or("<unknown>");
+                        json_files.push(edits_to_json(file_name, &o.input, &o.output));
+                    }
+                } else if stdout {
+                    print!("{}", o.output);
+                } else if change.is_some() {
+                    write_in_place(&o.file_path, &o.output, backup.as_deref())?;
                     if !cli.quiet {
                         println!("Formatted {}", o.file_path.display());
                     }
+                    reformatted_files.push(o.file_path);
                     reformatted_count += 1;
                 } else {
                     unchanged_count += 1;
                 }
             }
 
+            if restage {
+                let skipped = panache::git::restage(Path::new("."), reformatted_files, &unstaged)?;
+                for path in &skipped {
+                    eprintln!(
+                        "Warning: not restaging {}: it has unstaged changes",
+                        path.display()
+                    );
+                }
+            }
+            if let Some(path) = report.as_deref() {
+                FormatReport {
+                    check,
+                    files: report_files,
+                    duration: run_start.elapsed(),
+                }
+                .write(path)?;
+            }
+            if failed {
+                std::process::exit(EXIT_ERROR);
+            }
+            if json_diff || edits.is_some() {
+                print_diff_json(json_files)?;
+            }
+            if check && summary && !json_diff {
+                print_check_summary(&changed_files, expanded_files.len(), list_different);
+            }
             if check {
-                if all_formatted {
-                    if expanded_files.len() > 1 && !cli.quiet {
+                if changed_files.is_empty() {
+                    if expanded_files.len() > 1
+                        && !cli.quiet
+                        && !json_diff
+                        && !list_different
+                        && !summary
+                    {
                         println!("All {} files are correctly formatted", expanded_files.len());
                     }
                 } else {
                     std::process::exit(1);
                 }
-            } else if !cli.quiet {
+            } else if !cli.quiet && !stdout && edits.is_none() {
                 if reformatted_count == 0 {
                     println!(
                         "{}",
@@ -1206,7 +2785,7 @@ fn main() -> io::Result<()> {
                 cli.cache_dir.as_deref(),
                 &start_dir,
                 None,
-                cli.flavor.map(Flavor::from),
+                cli_flavor,
             )?;
 
             let report_clean = |message: String| {
@@ -1304,6 +2883,76 @@ fn main() -> io::Result<()> {
             Ok(())
         }
         Commands::Debug { command } => match command {
+            DebugCommands::Parse {
+                file,
+                check_lossless,
+                json,
+            } => {
+                let file = normalize_parse_path(file);
+                let input_path = file.as_deref().or(cli.stdin_filename.as_deref());
+                let start_dir = start_dir_for(input_path)?;
+                let (cfg, _) = load_config_for_cli(
+                    cli.config.as_deref(),
+                    cli.isolated,
+                    cli.cache_dir.as_deref(),
+                    &start_dir,
+                    input_path,
+                    cli_flavor,
+                )?;
+                let input = read_all(file.as_ref())?;
+                let label = file
+                    .as_deref()
+                    .map_or_else(|| "<stdin>".to_string(), |p| p.display().to_string());
+
+                if !check_lossless {
+                    println!("{:#?}", parse(&input, Some(cfg)));
+                    return Ok(());
+                }
+
+                let result = panache::parser::check_lossless(&input, Some(cfg));
+                if json {
+                    let output = match &result {
+                        Ok(_) => json!({ "file": label, "lossless": true }),
+                        Err(mismatch) => json!({
+                            "file": label,
+                            "lossless": false,
+                            "offset": mismatch.offset,
+                            "line": mismatch.line,
+                            "column": mismatch.column,
+                            "expected": mismatch.expected,
+                            "actual": mismatch.actual,
+                            "input_len": mismatch.input_len,
+                            "tree_len": mismatch.tree_len,
+                            "node_path": mismatch
+                                .node_path
+                                .iter()
+                                .map(|kind| format!("{kind:?}"))
+                                .collect::<Vec<_>>(),
+                        }),
+                    };
+                    println!(
+                        "{}",
+                        serde_json::to_string_pretty(&output).map_err(io::Error::other)?
+                    );
+                } else if let Err(mismatch) = &result {
+                    eprintln!("{label}:{}:{}: {mismatch}", mismatch.line, mismatch.column);
+                    if !mismatch.node_path.is_empty() {
+                        let path: Vec<String> = mismatch
+                            .node_path
+                            .iter()
+                            .map(|kind| format!("{kind:?}"))
+                            .collect();
+                        eprintln!("  node path: {}", path.join(" > "));
+                    }
+                } else if !cli.quiet {
+                    println!("{label} is lossless");
+                }
+
+                if result.is_err() {
+                    std::process::exit(1);
+                }
+                Ok(())
+            }
             DebugCommands::Format {
                 files,
                 checks,
@@ -1315,18 +2964,18 @@ fn main() -> io::Result<()> {
             } => {
                 if json && report {
                     eprintln!("Error: --json and --report cannot be used together");
-                    std::process::exit(1);
+                    std::process::exit(EXIT_ERROR);
                 }
                 if dump_passes && dump_dir.is_none() {
                     eprintln!("Error: --dump-passes requires --dump-dir <DIR>");
-                    std::process::exit(1);
+                    std::process::exit(EXIT_ERROR);
                 }
 
                 let files = match normalize_input_paths(files) {
                     Ok(files) => files,
                     Err(err) => {
                         eprintln!("Error: {}", err);
-                        std::process::exit(1);
+                        std::process::exit(EXIT_ERROR);
                     }
                 };
                 let use_stdin = files.is_empty();
@@ -1349,7 +2998,7 @@ fn main() -> io::Result<()> {
                         cli.cache_dir.as_deref(),
                         &traversal_start_dir,
                         traversal_anchor,
-                        cli.flavor.map(Flavor::from),
+                        cli_flavor,
                     )?;
                     let anchor =
                         panache::config::anchor_dir(&traversal_cfg_source, &traversal_start_dir);
@@ -1358,14 +3007,14 @@ fn main() -> io::Result<()> {
                         &traversal_cfg,
                         &anchor,
                         force_exclude,
-                        cli.flavor.is_some(),
+                        cli_flavor.is_some(),
                     )?
                 };
 
                 if !use_stdin && targets.is_empty() {
                     if has_explicit_file_targets(&files) {
                         eprintln!("Error: No supported files found");
-                        std::process::exit(1);
+                        std::process::exit(EXIT_ERROR);
                     }
                     if json {
                         let output = json!({
@@ -1397,7 +3046,7 @@ fn main() -> io::Result<()> {
                         cli.cache_dir.as_deref(),
                         &start_dir,
                         cli.stdin_filename.as_deref(),
-                        cli.flavor.map(Flavor::from),
+                        cli_flavor,
                     )?;
                     let input = read_all(None)?;
                     files_checked += 1;
@@ -1411,7 +3060,7 @@ fn main() -> io::Result<()> {
                         failure_count += 1;
                         if !json && !report {
                             eprintln!("Debug check failed ({}) in <stdin>", failure.kind.label());
-                            print_diff("<stdin>", &failure.left, &failure.right, use_color);
+                            print_diff("<stdin>", &failure.left, &failure.right);
                         }
                         json_failures.push(json!({
                             "file": "<stdin>",
@@ -1430,7 +3079,7 @@ fn main() -> io::Result<()> {
                             cli.cache_dir.as_deref(),
                             &start_dir,
                             Some(file_path),
-                            cli.flavor.map(Flavor::from),
+                            cli_flavor,
                         )?;
                         let input = fs::read_to_string(file_path)?;
                         files_checked += 1;
@@ -1451,7 +3100,7 @@ fn main() -> io::Result<()> {
                                     failure.kind.label(),
                                     file_label
                                 );
-                                print_diff(file_label, &failure.left, &failure.right, use_color);
+                                print_diff(file_label, &failure.left, &failure.right);
                             }
                             json_failures.push(json!({
                                 "file": file_label,
@@ -1506,12 +3155,249 @@ fn main() -> io::Result<()> {
                 }
                 Ok(())
             }
+            DebugCommands::Corpus {
+                source,
+                checks,
+                out_dir,
+                dump_passes,
+                json,
+            } => {
+                let passed = corpus::run_corpus(&corpus::CorpusOptions {
+                    source: &source,
+                    checks: &checks,
+                    out_dir: &out_dir,
+                    dump_passes,
+                    json,
+                    config: cli.config.as_deref(),
+                    isolated: cli.isolated,
+                    flavor: cli_flavor,
+                    jobs: cli.jobs,
+                    quiet: cli.quiet,
+                })?;
+                if !passed {
+                    std::process::exit(1);
+                }
+                Ok(())
+            }
+            DebugCommands::Capabilities { file } => {
+                let input_path = file.as_deref().or(cli.stdin_filename.as_deref());
+                let (cfg, _) = load_config_for_cli(
+                    cli.config.as_deref(),
+                    cli.isolated,
+                    cli.cache_dir.as_deref(),
+                    &start_dir_for(input_path)?,
+                    input_path,
+                    cli_flavor,
+                )?;
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&capabilities(&cfg)).map_err(io::Error::other)?
+                );
+                Ok(())
+            }
         },
         #[cfg(feature = "lsp")]
         Commands::Lsp { .. } => {
             panache::lsp::run()?;
             Ok(())
         }
+        #[cfg(feature = "lsp")]
+        Commands::Mv { old, new, dry_run } => run_mv(&old, &new, dry_run, cli.quiet),
+        Commands::Extract {
+            files,
+            lang,
+            out_dir,
+            source_map,
+        } => run_extract(
+            files,
+            &lang,
+            out_dir.as_deref(),
+            source_map.as_deref(),
+            cli.stdin_filename.as_deref(),
+            |input_path| {
+                load_config_for_cli(
+                    cli.config.as_deref(),
+                    cli.isolated,
+                    cli.cache_dir.as_deref(),
+                    &start_dir_for(input_path)?,
+                    input_path,
+                    cli_flavor,
+                )
+                .map(|(cfg, _)| cfg)
+            },
+            cli.quiet,
+        ),
+        Commands::Stats {
+            files,
+            output_format,
+        } => run_stats(
+            files,
+            output_format,
+            cli.stdin_filename.as_deref(),
+            |input_path| {
+                load_config_for_cli(
+                    cli.config.as_deref(),
+                    cli.isolated,
+                    cli.cache_dir.as_deref(),
+                    &start_dir_for(input_path)?,
+                    input_path,
+                    cli_flavor,
+                )
+                .map(|(cfg, _)| cfg)
+            },
+        ),
+        Commands::Render { files, to } => {
+            run_render(files, to, cli.stdin_filename.as_deref(), |input_path| {
+                load_config_for_cli(
+                    cli.config.as_deref(),
+                    cli.isolated,
+                    cli.cache_dir.as_deref(),
+                    &start_dir_for(input_path)?,
+                    input_path,
+                    cli_flavor,
+                )
+                .map(|(cfg, _)| cfg)
+            })
+        }
+        Commands::Organize {
+            files,
+            sort_headings_under,
+            check,
+        } => {
+            let changed = run_organize(
+                files,
+                &sort_headings_under,
+                check,
+                cli.stdin_filename.as_deref(),
+                |input_path| {
+                    load_config_for_cli(
+                        cli.config.as_deref(),
+                        cli.isolated,
+                        cli.cache_dir.as_deref(),
+                        &start_dir_for(input_path)?,
+                        input_path,
+                        cli_flavor,
+                    )
+                    .map(|(cfg, _)| cfg)
+                },
+                cli.quiet,
+            )?;
+            if check && changed {
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+        Commands::Filter { .. } => {
+            let input_path = cli.stdin_filename.as_deref();
+            let (cfg, _) = load_config_for_cli(
+                cli.config.as_deref(),
+                cli.isolated,
+                cli.cache_dir.as_deref(),
+                &start_dir_for(input_path)?,
+                input_path,
+                cli_flavor,
+            )?;
+            let input = read_all(None)?;
+            let output = panache::pandoc_filter::filter(&input, &cfg)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            println!("{output}");
+            Ok(())
+        }
+        Commands::Trust { path, revoke } => run_trust(path.as_deref(), revoke, cli.quiet),
+        Commands::Config { command } => match command {
+            ConfigCommands::Show { file } => run_config_show(
+                cli.config.as_deref(),
+                cli.isolated,
+                cli.cache_dir.as_deref(),
+                cli_flavor,
+                file.as_deref(),
+            ),
+            ConfigCommands::Validate { config } => {
+                run_config_validate(config.or(cli.config).as_deref(), cli.quiet)
+            }
+            ConfigCommands::Init { force } => run_config_init(force, cli.quiet),
+        },
+        Commands::CheckLinks {
+            files,
+            online,
+            concurrency,
+            timeout,
+            message_format,
+            json,
+        } => {
+            let files = normalize_input_paths(files)?;
+            let traversal_anchor = files.first().map(PathBuf::as_path);
+            let traversal_start_dir = match traversal_anchor {
+                Some(anchor) if anchor.is_dir() => anchor.to_path_buf(),
+                anchor => start_dir_for(anchor)?,
+            };
+            let (traversal_cfg, traversal_cfg_source) = load_config_for_cli(
+                cli.config.as_deref(),
+                cli.isolated,
+                cli.cache_dir.as_deref(),
+                &traversal_start_dir,
+                traversal_anchor,
+                cli_flavor,
+            )?;
+            let files = if files.is_empty() {
+                files
+            } else {
+                let anchor =
+                    panache::config::anchor_dir(&traversal_cfg_source, &traversal_start_dir);
+                let expanded =
+                    expand_paths(&files, &traversal_cfg, &anchor, false, cli_flavor.is_some())?;
+                if expanded.is_empty() {
+                    if has_explicit_file_targets(&files) {
+                        eprintln!("Error: No supported files found");
+                        std::process::exit(EXIT_ERROR);
+                    }
+                    if !cli.quiet {
+                        println!("No supported files found");
+                    }
+                    return Ok(());
+                }
+                expanded
+            };
+            let cache = if !online || cli.no_cache || !traversal_cfg.cache {
+                None
+            } else {
+                open_cli_cache_best_effort(
+                    &traversal_cfg,
+                    cli.config.as_deref(),
+                    &traversal_start_dir,
+                )
+            };
+            let options = CheckLinksOptions {
+                online: online.then(|| panache::link_check::OnlineOptions {
+                    concurrency,
+                    timeout: Duration::from_secs(timeout),
+                }),
+                message_format,
+                json,
+                quiet: cli.quiet,
+            };
+            let broken = run_check_links(
+                &files,
+                &options,
+                cli.stdin_filename.as_deref(),
+                cache,
+                |input_path| {
+                    load_config_for_cli(
+                        cli.config.as_deref(),
+                        cli.isolated,
+                        cli.cache_dir.as_deref(),
+                        &start_dir_for(input_path)?,
+                        input_path,
+                        cli_flavor,
+                    )
+                    .map(|(cfg, _)| cfg)
+                },
+            )?;
+            if broken {
+                std::process::exit(1);
+            }
+            Ok(())
+        }
         Commands::Lint {
             files,
             check,
@@ -1519,6 +3405,8 @@ fn main() -> io::Result<()> {
             unsafe_fixes,
             message_format,
             force_exclude,
+            include,
+            exclude,
         } => {
             if check {
                 eprintln!(
@@ -1530,7 +3418,7 @@ fn main() -> io::Result<()> {
                 Ok(files) => files,
                 Err(err) => {
                     eprintln!("Error: {}", err);
-                    std::process::exit(1);
+                    std::process::exit(EXIT_ERROR);
                 }
             };
             // Quarto project manifests (`_quarto.yml` / `_metadata.yml`) are
@@ -1551,7 +3439,7 @@ fn main() -> io::Result<()> {
                     cli.cache_dir.as_deref(),
                     &start_dir,
                     cli.stdin_filename.as_deref(),
-                    cli.flavor.map(Flavor::from),
+                    cli_flavor,
                 )?;
 
                 if let Some(path) = cfg_source.path() {
@@ -1594,8 +3482,22 @@ fn main() -> io::Result<()> {
                 }
 
                 if fix {
-                    let fixed_output = apply_fixes(&input, &diagnostics, unsafe_fixes);
-                    print!("{}", fixed_output);
+                    let fixed =
+                        panache::linter::apply_fixes(&input, &diagnostics, unsafe_fixes, |text| {
+                            let tree = parse(text, Some(cfg.clone()));
+                            let metadata =
+                                panache::metadata::extract_project_metadata(&tree, stdin_path).ok();
+                            panache::linter::lint_with_external_sync_and_metadata(
+                                &tree,
+                                text,
+                                &cfg,
+                                metadata.as_ref(),
+                            )
+                        });
+                    print!("{}", fixed.output);
+                    if !cli.quiet {
+                        print_fix_conflicts(&fixed.conflicts, "<stdin>");
+                    }
                     let unsafe_skipped = if unsafe_fixes {
                         0
                     } else {
@@ -1610,14 +3512,7 @@ fn main() -> io::Result<()> {
                 }
 
                 if !cli.quiet {
-                    print_diagnostics(
-                        &diagnostics,
-                        None,
-                        Some(&input),
-                        use_color,
-                        message_format,
-                        true,
-                    );
+                    print_diagnostics(&diagnostics, None, Some(&input), message_format, true);
                 }
 
                 // Reporting mode exits non-zero whenever violations are found.
@@ -1635,21 +3530,22 @@ fn main() -> io::Result<()> {
             } else {
                 start_dir_for(None)?
             };
-            let (traversal_cfg, traversal_cfg_source) = load_config_for_cli(
+            let (mut traversal_cfg, traversal_cfg_source) = load_config_for_cli(
                 cli.config.as_deref(),
                 cli.isolated,
                 cli.cache_dir.as_deref(),
                 &traversal_start_dir,
                 traversal_anchor,
-                cli.flavor.map(Flavor::from),
+                cli_flavor,
             )?;
+            apply_path_filter_overrides(&mut traversal_cfg, &include, &exclude);
             let anchor = panache::config::anchor_dir(&traversal_cfg_source, &traversal_start_dir);
             let expanded_files = expand_paths(
                 &files,
                 &traversal_cfg,
                 &anchor,
                 force_exclude,
-                cli.flavor.is_some(),
+                cli_flavor.is_some(),
             )?;
             let mut cache = if cli.no_cache || !traversal_cfg.cache {
                 None
@@ -1667,7 +3563,7 @@ fn main() -> io::Result<()> {
                 }
                 if has_explicit_file_targets(&files) {
                     eprintln!("Error: No supported files found");
-                    std::process::exit(1);
+                    std::process::exit(EXIT_ERROR);
                 }
                 if !cli.quiet {
                     println!("No supported files found");
@@ -1680,6 +3576,7 @@ fn main() -> io::Result<()> {
 
             struct LintOutcome {
                 file_path: PathBuf,
+                config: panache::Config,
                 root_doc: Option<LintedDocument>,
                 included_docs: Vec<LintedDocument>,
             }
@@ -1695,7 +3592,7 @@ fn main() -> io::Result<()> {
                     cli.cache_dir.as_deref(),
                     &start_dir,
                     Some(file_path),
-                    cli.flavor.map(Flavor::from),
+                    cli_flavor,
                 )?;
                 // Size the shared external-tool budget from the user-configured
                 // value, then split that ceiling across the files processed
@@ -1771,6 +3668,7 @@ fn main() -> io::Result<()> {
 
                 Ok(LintOutcome {
                     file_path: file_path.clone(),
+                    config: cfg,
                     root_doc,
                     included_docs,
                 })
@@ -1796,10 +3694,11 @@ fn main() -> io::Result<()> {
             }
 
             let mut any_issues = false;
-            let mut total_issues = 0;
+            let mut counts = SeverityCounts::default();
             for outcome in outcomes {
                 let LintOutcome {
                     file_path,
+                    config,
                     root_doc,
                     included_docs,
                 } = outcome?;
@@ -1810,7 +3709,7 @@ fn main() -> io::Result<()> {
 
                 if !root_doc.diagnostics.is_empty() {
                     any_issues = true;
-                    total_issues += root_doc.diagnostics.len();
+                    counts.add(&root_doc.diagnostics);
 
                     if fix {
                         let fixable = root_doc
@@ -1840,23 +3739,46 @@ fn main() -> io::Result<()> {
                         } else {
                             count_unsafe_fixes(&root_doc.diagnostics)
                         };
+                        let mut fixed = 0;
                         if fixable > 0 {
-                            let fixed_output =
-                                apply_fixes(&root_doc.input, &root_doc.diagnostics, unsafe_fixes);
-                            fs::write(&file_path, fixed_output)?;
+                            let result = panache::linter::apply_fixes(
+                                &root_doc.input,
+                                &root_doc.diagnostics,
+                                unsafe_fixes,
+                                |text| {
+                                    let tree = parse(text, Some(config.clone()));
+                                    let metadata = panache::metadata::extract_project_metadata(
+                                        &tree, &file_path,
+                                    )
+                                    .ok();
+                                    panache::linter::lint_with_external_sync_and_metadata(
+                                        &tree,
+                                        text,
+                                        &config,
+                                        metadata.as_ref(),
+                                    )
+                                },
+                            );
+                            write_in_place(&file_path, &result.output, None)?;
+                            fixed = result.applied;
+                            if !cli.quiet {
+                                print_fix_conflicts(
+                                    &result.conflicts,
+                                    &file_path.display().to_string(),
+                                );
+                            }
                         }
                         if !remaining.is_empty() && !cli.quiet {
                             print_diagnostics(
                                 &remaining,
                                 Some(file_path.as_path()),
                                 Some(&root_doc.input),
-                                use_color,
                                 message_format,
                                 false,
                             );
                         }
                         if !cli.quiet {
-                            print_fix_summary(fixable, no_fix_count, &file_path);
+                            print_fix_summary(fixed, no_fix_count, &file_path);
                             if unsafe_skipped > 0 {
                                 println!("{}", unsafe_fixes_hint(unsafe_skipped));
                             }
@@ -1866,7 +3788,6 @@ fn main() -> io::Result<()> {
                             &root_doc.diagnostics,
                             Some(file_path.as_path()),
                             Some(&root_doc.input),
-                            use_color,
                             message_format,
                             true,
                         );
@@ -1879,13 +3800,12 @@ fn main() -> io::Result<()> {
                             continue;
                         }
                         any_issues = true;
-                        total_issues += doc.diagnostics.len();
+                        counts.add(&doc.diagnostics);
                         if !cli.quiet {
                             print_diagnostics(
                                 &doc.diagnostics,
                                 Some(doc.path.as_path()),
                                 Some(&doc.input),
-                                use_color,
                                 message_format,
                                 true,
                             );
@@ -1906,19 +3826,19 @@ fn main() -> io::Result<()> {
                     cli.config.as_deref(),
                     cli.isolated,
                     cli.cache_dir.as_deref(),
-                    cli.flavor.map(Flavor::from),
+                    cli_flavor,
                 ) {
                     Ok(doc) => doc,
                     Err(err) => {
                         eprintln!("Error: {}: {}", manifest_path.display(), err);
-                        std::process::exit(1);
+                        std::process::exit(EXIT_ERROR);
                     }
                 };
                 if manifest_doc.diagnostics.is_empty() {
                     continue;
                 }
                 any_issues = true;
-                total_issues += manifest_doc.diagnostics.len();
+                counts.add(&manifest_doc.diagnostics);
                 if !cli.quiet {
                     // Manifest diagnostics carry no auto-fixes; print them as-is
                     // even under `--fix`.
@@ -1926,7 +3846,6 @@ fn main() -> io::Result<()> {
                         &manifest_doc.diagnostics,
                         Some(manifest_path.as_path()),
                         Some(&manifest_doc.input),
-                        use_color,
                         message_format,
                         true,
                     );
@@ -1959,25 +3878,24 @@ fn main() -> io::Result<()> {
                         cli.config.as_deref(),
                         cli.isolated,
                         cli.cache_dir.as_deref(),
-                        cli.flavor.map(Flavor::from),
+                        cli_flavor,
                     ) {
                         Ok(doc) => doc,
                         Err(err) => {
                             eprintln!("Error: {}: {}", manifest_path.display(), err);
-                            std::process::exit(1);
+                            std::process::exit(EXIT_ERROR);
                         }
                     };
                     if manifest_doc.diagnostics.is_empty() {
                         continue;
                     }
                     any_issues = true;
-                    total_issues += manifest_doc.diagnostics.len();
+                    counts.add(&manifest_doc.diagnostics);
                     if !cli.quiet {
                         print_diagnostics(
                             &manifest_doc.diagnostics,
                             Some(manifest_path.as_path()),
                             Some(&manifest_doc.input),
-                            use_color,
                             message_format,
                             true,
                         );
@@ -1997,9 +3915,11 @@ fn main() -> io::Result<()> {
             if any_issues && !fix {
                 // The summary is emitted even under `--quiet` so CI can surface the
                 // count on stderr while stdout stays clean.
-                eprintln!(
-                    "\nFound {} issue(s) across {} file(s)",
-                    total_issues, total_files
+                anstream::eprintln!(
+                    "\nFound {} issue(s) across {} file(s): {}",
+                    counts.total(),
+                    total_files,
+                    counts
                 );
                 std::process::exit(1);
             }
@@ -2297,37 +4217,20 @@ fn unsafe_fixes_hint(count: usize) -> String {
     format!("{count} unsafe fix(es) available; run with --unsafe-fixes to apply.")
 }
 
-fn apply_fixes(
-    input: &str,
-    diagnostics: &[panache::linter::Diagnostic],
-    allow_unsafe: bool,
-) -> String {
-    use panache::linter::FixSafety;
-    use panache::linter::diagnostics::Edit;
-
-    let mut edits: Vec<&Edit> = diagnostics
-        .iter()
-        .filter_map(|d| d.fix.as_ref())
-        .filter(|f| allow_unsafe || f.safety == FixSafety::Safe)
-        .flat_map(|f| &f.edits)
-        .collect();
-
-    edits.sort_by_key(|e| e.range.start());
-
-    let mut output = String::new();
-    let mut last_end = 0;
-
-    for edit in edits {
-        let start: usize = edit.range.start().into();
-        let end: usize = edit.range.end().into();
-
-        output.push_str(&input[last_end..start]);
-        output.push_str(&edit.replacement);
-        last_end = end;
+/// Warn about fixes `--fix` left out because they overlap another fix.
+fn print_fix_conflicts(conflicts: &[panache::linter::Diagnostic], file: &str) {
+    for conflict in conflicts {
+        eprintln!(
+            "Warning: {file}:{}:{}: skipped fix for [{}] ({}): it overlaps another fix",
+            conflict.location.line,
+            conflict.location.column,
+            conflict.code,
+            conflict
+                .fix
+                .as_ref()
+                .map_or(conflict.message.as_str(), |fix| fix.message.as_str()),
+        );
     }
-
-    output.push_str(&input[last_end..]);
-    output
 }
 
 fn merge_missing_diagnostics(
@@ -2452,6 +4355,8 @@ fn cached_diagnostic_from_runtime(diag: &panache::linter::Diagnostic) -> cache::
         location: CachedLocation {
             line: diag.location.line,
             column: diag.location.column,
+            end_line: diag.location.end_line,
+            end_column: diag.location.end_column,
             start: u32::from(diag.location.range.start()),
             end: u32::from(diag.location.range.end()),
         },
@@ -2507,6 +4412,8 @@ fn runtime_diagnostic_from_cached(diag: &cache::CachedDiagnostic) -> panache::li
         location: panache::linter::Location {
             line: diag.location.line,
             column: diag.location.column,
+            end_line: diag.location.end_line,
+            end_column: diag.location.end_column,
             range: TextRange::new(
                 TextSize::from(diag.location.start),
                 TextSize::from(diag.location.end),
@@ -2522,8 +4429,50 @@ fn runtime_diagnostic_from_cached(diag: &cache::CachedDiagnostic) -> panache::li
 
 #[cfg(test)]
 mod tests {
-    use super::{ColorMode, per_file_external_parallel, resolve_color};
-    use std::ffi::OsStr;
+    use super::{fail_safe_warning_lines, format_to_staged, per_file_external_parallel};
+
+    #[test]
+    fn staged_output_is_only_kept_when_it_differs() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("doc.md");
+        let cfg = panache::Config::default();
+
+        let (staged, _) = format_to_staged(&path, "# Title\n", &cfg).unwrap();
+        assert!(staged.is_none());
+        let (staged, _) = format_to_staged(&path, "# Title\n\nText\n\n\n", &cfg).unwrap();
+        let staged = staged.expect("output differs");
+        assert_eq!(
+            std::fs::read_to_string(&staged).unwrap(),
+            "# Title\n\nText\n"
+        );
+        // A prefix of the input is a change too.
+        let (staged, _) = format_to_staged(&path, "# Title", &cfg).unwrap();
+        assert!(staged.is_some());
+    }
+
+    #[test]
+    fn fail_safe_warnings_say_what_was_left_alone() {
+        let failures = [
+            panache::FormatError::Parse {
+                message: "index out of bounds".to_string(),
+            },
+            panache::FormatError::Block {
+                kind: panache::syntax::SyntaxKind::PARAGRAPH,
+                range: 9..20,
+                line: 3,
+                message: "boom".to_string(),
+            },
+        ];
+        assert_eq!(
+            fail_safe_warning_lines("doc.md", &failures),
+            [
+                "Warning: doc.md: internal error while parsing: index out of bounds; \
+                 left the document unchanged",
+                "Warning: doc.md: internal error while formatting the PARAGRAPH block at line 3: \
+                 boom; left the block unformatted",
+            ]
+        );
+    }
 
     #[test]
     fn few_files_split_the_budget_to_saturate_it() {
@@ -2549,64 +4498,28 @@ mod tests {
         assert_eq!(per_file_external_parallel(4, 0), 4);
     }
 
-    #[test]
-    fn auto_disables_color_when_term_is_dumb() {
-        assert!(!resolve_color(
-            ColorMode::Auto,
-            false,
-            false,
-            Some(OsStr::new("dumb")),
-            true,
-        ));
-    }
-
-    #[test]
-    fn auto_disables_color_when_term_is_unset() {
-        assert!(!resolve_color(ColorMode::Auto, false, false, None, true));
-    }
-
-    #[test]
-    fn auto_enables_color_on_tty_with_real_term() {
-        assert!(resolve_color(
-            ColorMode::Auto,
-            false,
-            false,
-            Some(OsStr::new("xterm-256color")),
-            true,
-        ));
-    }
+    mod extension_flags {
+        use crate::extension_overrides;
 
-    #[test]
-    fn auto_disables_color_when_not_a_tty() {
-        assert!(!resolve_color(
-            ColorMode::Auto,
-            false,
-            false,
-            Some(OsStr::new("xterm-256color")),
-            false,
-        ));
-    }
+        fn names(names: &[&str]) -> Vec<String> {
+            names.iter().map(|name| name.to_string()).collect()
+        }
 
-    #[test]
-    fn always_overrides_dumb_term() {
-        assert!(resolve_color(
-            ColorMode::Always,
-            false,
-            false,
-            Some(OsStr::new("dumb")),
-            false,
-        ));
-    }
+        #[test]
+        fn normalizes_names_and_lets_disable_win() {
+            let overrides =
+                extension_overrides(&[], &names(&["fancy_lists", "emoji"]), &names(&["emoji"]))
+                    .unwrap();
+            assert_eq!(overrides.get("fancy-lists"), Some(&true));
+            assert_eq!(overrides.get("emoji"), Some(&false));
+        }
 
-    #[test]
-    fn no_color_flag_overrides_always() {
-        assert!(!resolve_color(
-            ColorMode::Always,
-            true,
-            false,
-            Some(OsStr::new("xterm-256color")),
-            true,
-        ));
+        #[test]
+        fn rejects_unknown_names() {
+            let err = extension_overrides(&[], &names(&["footnote"]), &[]).unwrap_err();
+            assert!(err.contains("`footnote`"), "{err}");
+            assert!(err.contains("did you mean `footnotes`?"), "{err}");
+        }
     }
 
     mod format_overrides {
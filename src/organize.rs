@@ -0,0 +1,243 @@
+//! Section rewrites for `panache organize`.
+//!
+//! A section is a top-level heading together with everything after it up to
+//! the next heading of the same or a higher level. [`sections`] lists them,
+//! and the transforms here move whole sections around as [`TextEdit`]s, so a
+//! heading never gets separated from the blocks under it.
+//!
+//! ```rust
+//! let input = "## FAQ\n\n### Why?\n\nBecause.\n\n### How?\n\nLike this.\n";
+//! let output = panache::organize::sort_headings_under(input, &["## FAQ"], None).unwrap();
+//! assert_eq!(output, "## FAQ\n\n### How?\n\nLike this.\n\n### Why?\n\nBecause.\n");
+//! ```
+
+use std::fmt;
+use std::ops::Range;
+
+use crate::config::Config;
+use crate::edit::{Section, sections};
+use crate::edits::{self, TextEdit};
+use crate::syntax::SyntaxNode;
+
+/// Why a section rewrite could not be applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OrganizeError {
+    /// The heading is not of the form `Title` or `## Title`.
+    InvalidHeading(String),
+    /// No section has the heading.
+    HeadingNotFound(String),
+}
+
+impl fmt::Display for OrganizeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidHeading(heading) => write!(
+                f,
+                "invalid heading '{heading}': expected a title, optionally preceded by 1-6 `#`"
+            ),
+            Self::HeadingNotFound(heading) => write!(f, "no heading matches '{heading}'"),
+        }
+    }
+}
+
+impl std::error::Error for OrganizeError {}
+
+/// A heading to look for: `FAQ` matches a heading of any level with that
+/// text, `## FAQ` only a level-2 one. Runs of whitespace compare equal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct HeadingSelector {
+    level: Option<usize>,
+    title: String,
+}
+
+impl HeadingSelector {
+    fn parse(text: &str) -> Result<Self, OrganizeError> {
+        let text = text.trim();
+        let hashes = text.len() - text.trim_start_matches('#').len();
+        let title = text[hashes..].trim();
+        let valid_marker = hashes == 0 || (hashes <= 6 && text[hashes..].starts_with([' ', '\t']));
+        if title.is_empty() || !valid_marker {
+            return Err(OrganizeError::InvalidHeading(text.to_string()));
+        }
+        Ok(Self {
+            level: (hashes > 0).then_some(hashes),
+            title: collapse_whitespace(title),
+        })
+    }
+
+    fn matches(&self, section: &Section) -> bool {
+        self.level.is_none_or(|level| level == section.level)
+            && collapse_whitespace(&section.title) == self.title
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Edits that sort the subsections directly under each heading in
+/// `headings` alphabetically by title (ignoring case), each keeping its
+/// content. Text between a heading and its first subsection stays in place.
+/// Subsections with equal titles keep their order.
+pub fn sort_edits(
+    input: &str,
+    tree: &SyntaxNode,
+    headings: &[&str],
+) -> Result<Vec<TextEdit>, OrganizeError> {
+    let sections = sections(tree);
+    let newline = if input.contains("\r\n") { "\r\n" } else { "\n" };
+    let mut edits: Vec<TextEdit> = Vec::new();
+
+    for heading in headings {
+        let selector = HeadingSelector::parse(heading)?;
+        let mut found = false;
+        for parent in sections.iter().filter(|section| selector.matches(section)) {
+            found = true;
+            if let Some(edit) = sort_children(input, &sections, parent, newline)
+                && !edits
+                    .iter()
+                    .any(|other| overlaps(&other.range, &edit.range))
+            {
+                edits.push(edit);
+            }
+        }
+        if !found {
+            return Err(OrganizeError::HeadingNotFound(heading.trim().to_string()));
+        }
+    }
+
+    edits.sort_by_key(|edit| edit.range.start);
+    Ok(edits)
+}
+
+/// Sort the subsections under each heading in `headings` of the document
+/// `input`; see [`sort_edits`].
+pub fn sort_headings_under(
+    input: &str,
+    headings: &[&str],
+    config: Option<Config>,
+) -> Result<String, OrganizeError> {
+    let tree = crate::parser::parse(input, config);
+    let edits = sort_edits(input, &tree, headings)?;
+    Ok(edits::apply(input, &edits))
+}
+
+/// The edit that sorts the direct subsections of `parent`, if they are not in
+/// order already.
+fn sort_children(
+    input: &str,
+    sections: &[Section],
+    parent: &Section,
+    newline: &str,
+) -> Option<TextEdit> {
+    let nested: Vec<&Section> = sections
+        .iter()
+        .filter(|section| {
+            section.range.start > parent.range.start && section.range.end <= parent.range.end
+        })
+        .collect();
+    let child_level = nested.iter().map(|section| section.level).min()?;
+    let children: Vec<&Section> = nested
+        .into_iter()
+        .filter(|section| section.level == child_level)
+        .collect();
+    if children.len() < 2 {
+        return None;
+    }
+
+    let mut sorted = children.clone();
+    sorted.sort_by_cached_key(|section| section.title.to_lowercase());
+    if sorted
+        .iter()
+        .zip(&children)
+        .all(|(a, b)| a.range == b.range)
+    {
+        return None;
+    }
+
+    let range = children[0].range.start..parent.range.end;
+    let region = &input[range.clone()];
+    let trailing = &region[region.trim_end().len()..];
+    let separator = format!("{newline}{newline}");
+    let new_text = sorted
+        .iter()
+        .map(|section| input[section.range.clone()].trim_end())
+        .collect::<Vec<_>>()
+        .join(&separator)
+        + trailing;
+    Some(TextEdit { range, new_text })
+}
+
+fn overlaps(a: &Range<usize>, b: &Range<usize>) -> bool {
+    a.start < b.end && b.start < a.end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sorts_only_the_direct_subsections_and_keeps_the_intro() {
+        let input = "\
+# Glossary
+
+Terms used in this guide.
+
+## Zebra
+
+Striped.
+
+### Details
+
+More.
+
+## apple
+
+A fruit.
+
+# Next
+";
+        let expected = "\
+# Glossary
+
+Terms used in this guide.
+
+## apple
+
+A fruit.
+
+## Zebra
+
+Striped.
+
+### Details
+
+More.
+
+# Next
+";
+        let output = sort_headings_under(input, &["# Glossary"], None).unwrap();
+        assert_eq!(output, expected);
+        assert_eq!(
+            sort_headings_under(&output, &["Glossary"], None).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_and_malformed_headings() {
+        let input = "## FAQ\n";
+        assert_eq!(
+            sort_headings_under(input, &["### FAQ"], None),
+            Err(OrganizeError::HeadingNotFound("### FAQ".to_string()))
+        );
+        assert_eq!(
+            sort_headings_under(input, &["##"], None),
+            Err(OrganizeError::InvalidHeading("##".to_string()))
+        );
+        assert_eq!(
+            sort_headings_under(input, &["##FAQ"], None),
+            Err(OrganizeError::InvalidHeading("##FAQ".to_string()))
+        );
+    }
+}
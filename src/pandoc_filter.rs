@@ -0,0 +1,448 @@
+//! Pandoc JSON filter: re-wraps paragraph text in a Pandoc AST per the
+//! configured `wrap` mode, so panache can sit in a Pandoc pipeline
+//! (`pandoc -t json | panache filter | pandoc -f json`).
+//!
+//! Line breaks in a Pandoc AST are `SoftBreak` inlines; a writer run with
+//! `--wrap=preserve` turns each one into a newline. The filter rewrites the
+//! `Space` and `SoftBreak` inlines of every `Para` and `Plain` block and keeps
+//! everything else, including AST nodes it does not know, untouched. Widths
+//! are measured on the Markdown rendering of the inlines, with list and quote
+//! nesting counted as indentation.
+//!
+//! ```rust
+//! let input = r#"{"pandoc-api-version":[1,23,1,1],"meta":{},"blocks":[
+//!     {"t":"Para","c":[{"t":"Str","c":"One."},{"t":"Space"},{"t":"Str","c":"Two."}]}]}"#;
+//! let config = panache::ConfigBuilder::default()
+//!     .wrap(panache::config::WrapMode::Sentence)
+//!     .build();
+//! let output = panache::pandoc_filter::filter(input, &config).unwrap();
+//! assert!(output.contains(r#"{"t":"SoftBreak"}"#));
+//! ```
+
+use serde_json::{Value, json};
+use unicode_width::UnicodeWidthStr;
+
+use crate::config::{Config, WrapMode};
+
+/// Why a filter input could not be processed.
+#[derive(Debug)]
+pub enum FilterError {
+    /// The input is not valid JSON.
+    Json(serde_json::Error),
+    /// The input is JSON but not a Pandoc document.
+    NotPandoc,
+}
+
+impl std::fmt::Display for FilterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Json(err) => write!(f, "invalid Pandoc JSON: {err}"),
+            Self::NotPandoc => write!(
+                f,
+                "input is not a Pandoc JSON document (expected `pandoc-api-version` and `blocks`)"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for FilterError {}
+
+/// Re-wrap the Pandoc JSON document `input` and return it as JSON.
+pub fn filter(input: &str, config: &Config) -> Result<String, FilterError> {
+    let mut doc: Value = serde_json::from_str(input).map_err(FilterError::Json)?;
+    filter_document(&mut doc, config)?;
+    serde_json::to_string(&doc).map_err(FilterError::Json)
+}
+
+/// Re-wrap a parsed Pandoc JSON document in place.
+pub fn filter_document(doc: &mut Value, config: &Config) -> Result<(), FilterError> {
+    if doc.get("pandoc-api-version").is_none() || !doc["blocks"].is_array() {
+        return Err(FilterError::NotPandoc);
+    }
+    let wrap = config.wrap.clone().unwrap_or(WrapMode::Reflow);
+    if wrap == WrapMode::Preserve {
+        return Ok(());
+    }
+    let cx = Context {
+        wrap,
+        line_width: config.line_width,
+        lang: meta_lang(&doc["meta"]),
+        formatter_config: crate::formatter::to_formatter_config(config),
+    };
+    walk(&mut doc["blocks"], 0, &cx);
+    Ok(())
+}
+
+struct Context {
+    wrap: WrapMode,
+    line_width: usize,
+    /// The document's `lang` metadata, for sentence boundaries.
+    lang: Option<String>,
+    formatter_config: panache_formatter::Config,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Break {
+    Space,
+    Soft,
+    Hard,
+}
+
+impl Break {
+    fn to_json(self) -> Value {
+        match self {
+            Self::Space => json!({"t": "Space"}),
+            Self::Soft => json!({"t": "SoftBreak"}),
+            Self::Hard => json!({"t": "LineBreak"}),
+        }
+    }
+}
+
+/// An inline flattened for measuring: rendered text, or a break opportunity.
+enum Piece {
+    /// `markup` text (emphasis markers, link destinations) counts toward the
+    /// width but not toward the words sentence detection sees.
+    Text {
+        text: String,
+        markup: bool,
+    },
+    Break(Break),
+}
+
+fn walk(value: &mut Value, indent: usize, cx: &Context) {
+    match value {
+        Value::Array(items) => {
+            for item in items {
+                walk(item, indent, cx);
+            }
+        }
+        Value::Object(map) => {
+            let tag = map.get("t").and_then(Value::as_str).unwrap_or_default();
+            let indent = indent + container_indent(tag);
+            let is_paragraph = matches!(tag, "Para" | "Plain");
+            if is_paragraph && let Some(Value::Array(inlines)) = map.get_mut("c") {
+                rewrap(inlines, indent, cx);
+            }
+            // Notes inside the inlines hold paragraphs of their own.
+            for child in map.values_mut() {
+                walk(child, indent, cx);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Columns a block container adds in front of its content in Markdown.
+fn container_indent(tag: &str) -> usize {
+    match tag {
+        "BlockQuote" | "BulletList" => 2,
+        "OrderedList" => 3,
+        "DefinitionList" | "Note" => 4,
+        _ => 0,
+    }
+}
+
+fn rewrap(inlines: &mut [Value], indent: usize, cx: &Context) {
+    let mut pieces = Vec::new();
+    collect(inlines, &mut pieces);
+
+    let mut words = vec![(String::new(), 0)];
+    let mut breaks = Vec::new();
+    for piece in pieces {
+        match piece {
+            Piece::Text { text, markup } => {
+                let (word, width) = words.last_mut().expect("words start non-empty");
+                *width += text.width();
+                if !markup {
+                    word.push_str(&text);
+                }
+            }
+            Piece::Break(kind) => {
+                breaks.push(kind);
+                words.push((String::new(), 0));
+            }
+        }
+    }
+    if breaks.is_empty() {
+        return;
+    }
+
+    let decided = match cx.wrap {
+        WrapMode::Preserve => return,
+        WrapMode::Reflow => reflow(&words, &breaks, indent, cx.line_width),
+        WrapMode::Sentence | WrapMode::Semantic => {
+            let texts: Vec<&str> = words.iter().map(|(word, _)| word.as_str()).collect();
+            let ends = panache_formatter::formatter::sentence_ends(
+                &texts,
+                cx.lang.as_deref(),
+                &cx.formatter_config,
+            );
+            let keep_soft = cx.wrap == WrapMode::Semantic;
+            breaks
+                .iter()
+                .zip(ends)
+                .map(|(&kind, end)| match kind {
+                    Break::Hard => Break::Hard,
+                    Break::Soft if keep_soft => Break::Soft,
+                    _ if end => Break::Soft,
+                    _ => Break::Space,
+                })
+                .collect()
+        }
+    };
+    apply(inlines, &mut decided.into_iter());
+}
+
+/// Greedy fill: break before a word that would run past `line_width`.
+fn reflow(
+    words: &[(String, usize)],
+    breaks: &[Break],
+    indent: usize,
+    line_width: usize,
+) -> Vec<Break> {
+    let mut column = indent + words[0].1;
+    breaks
+        .iter()
+        .zip(&words[1..])
+        .map(|(&kind, &(_, width))| {
+            if kind == Break::Hard || (column > indent && column + 1 + width > line_width) {
+                column = indent + width;
+                if kind == Break::Hard {
+                    Break::Hard
+                } else {
+                    Break::Soft
+                }
+            } else {
+                column += 1 + width;
+                Break::Space
+            }
+        })
+        .collect()
+}
+
+fn collect(inlines: &[Value], out: &mut Vec<Piece>) {
+    for inline in inlines {
+        let tag = inline["t"].as_str().unwrap_or_default();
+        let content = &inline["c"];
+        match tag {
+            "Space" => out.push(Piece::Break(Break::Space)),
+            "SoftBreak" => out.push(Piece::Break(Break::Soft)),
+            "LineBreak" => out.push(Piece::Break(Break::Hard)),
+            _ => {
+                if let Some(children) = children_pointer(tag)
+                    .and_then(|pointer| inline.pointer(pointer))
+                    .and_then(Value::as_array)
+                {
+                    let (open, close) = markers(tag, content);
+                    out.push(markup(open));
+                    collect(children, out);
+                    out.push(markup(close));
+                } else {
+                    out.push(Piece::Text {
+                        text: atom_text(tag, content),
+                        markup: false,
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Replace each break in `inlines`, in the order [`collect`] visited them.
+fn apply(inlines: &mut [Value], decided: &mut impl Iterator<Item = Break>) {
+    for inline in inlines {
+        let tag = inline["t"].as_str().unwrap_or_default();
+        if matches!(tag, "Space" | "SoftBreak" | "LineBreak") {
+            if let Some(kind) = decided.next() {
+                *inline = kind.to_json();
+            }
+        } else if let Some(children) = children_pointer(tag)
+            .and_then(|pointer| inline.pointer_mut(pointer))
+            .and_then(Value::as_array_mut)
+        {
+            apply(children, decided);
+        }
+    }
+}
+
+/// Where a container inline keeps the inlines a line may break inside.
+fn children_pointer(tag: &str) -> Option<&'static str> {
+    match tag {
+        "Emph" | "Strong" | "Strikeout" | "Superscript" | "Subscript" | "Underline"
+        | "SmallCaps" => Some("/c"),
+        "Span" | "Quoted" | "Link" | "Image" => Some("/c/1"),
+        _ => None,
+    }
+}
+
+/// Markdown written around a container inline's children.
+fn markers(tag: &str, content: &Value) -> (String, String) {
+    let (open, close) = match tag {
+        "Emph" => ("*", "*"),
+        "Strong" => ("**", "**"),
+        "Strikeout" => ("~~", "~~"),
+        "Superscript" => ("^", "^"),
+        "Subscript" => ("~", "~"),
+        "Underline" => ("[", "]{.underline}"),
+        "SmallCaps" => ("[", "]{.smallcaps}"),
+        "Span" => ("[", "]"),
+        "Quoted" if content[0]["t"] == "SingleQuote" => ("'", "'"),
+        "Quoted" => ("\"", "\""),
+        "Link" | "Image" => {
+            let open = if tag == "Image" { "![" } else { "[" };
+            let url = content[2][0].as_str().unwrap_or_default();
+            return (open.to_string(), format!("]({url})"));
+        }
+        _ => ("", ""),
+    };
+    (open.to_string(), close.to_string())
+}
+
+fn markup(text: String) -> Piece {
+    Piece::Text { text, markup: true }
+}
+
+/// Markdown text of an inline that cannot be broken inside.
+fn atom_text(tag: &str, content: &Value) -> String {
+    match tag {
+        "Str" => content.as_str().unwrap_or_default().to_string(),
+        "Code" => format!("`{}`", content[1].as_str().unwrap_or_default()),
+        "Math" if content[0]["t"] == "DisplayMath" => {
+            format!("$${}$$", content[1].as_str().unwrap_or_default())
+        }
+        "Math" => format!("${}$", content[1].as_str().unwrap_or_default()),
+        "RawInline" => content[1].as_str().unwrap_or_default().to_string(),
+        "Cite" => stringify(&content[1]),
+        "Note" => "[^1]".to_string(),
+        _ => String::new(),
+    }
+}
+
+/// Plain text of an inline list, with breaks as single spaces.
+fn stringify(inlines: &Value) -> String {
+    let mut pieces = Vec::new();
+    collect(inlines.as_array().map_or(&[], Vec::as_slice), &mut pieces);
+    pieces
+        .into_iter()
+        .map(|piece| match piece {
+            Piece::Text { text, .. } => text,
+            Piece::Break(_) => " ".to_string(),
+        })
+        .collect()
+}
+
+/// The `lang` metadata field, written either as a string or as inlines.
+fn meta_lang(meta: &Value) -> Option<String> {
+    let lang = &meta["lang"];
+    let text = match lang["t"].as_str()? {
+        "MetaString" => lang["c"].as_str()?.to_string(),
+        "MetaInlines" => stringify(&lang["c"]),
+        _ => return None,
+    };
+    Some(text.trim().to_string()).filter(|text| !text.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConfigBuilder;
+
+    fn para(words: &str) -> Value {
+        let mut inlines = Vec::new();
+        for (idx, word) in words.split(' ').enumerate() {
+            if idx > 0 {
+                inlines.push(json!({"t": "Space"}));
+            }
+            inlines.push(json!({"t": "Str", "c": word}));
+        }
+        json!({"t": "Para", "c": inlines})
+    }
+
+    fn document(blocks: Vec<Value>) -> Value {
+        json!({"pandoc-api-version": [1, 23, 1, 1], "meta": {}, "blocks": blocks})
+    }
+
+    fn break_tags(block: &Value) -> Vec<&str> {
+        block["c"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|inline| inline["t"].as_str())
+            .filter(|tag| *tag != "Str")
+            .collect()
+    }
+
+    #[test]
+    fn reflow_breaks_at_line_width() {
+        let config = ConfigBuilder::default().line_width(10).build();
+        let mut doc = document(vec![para("one two three four")]);
+        filter_document(&mut doc, &config).unwrap();
+        assert_eq!(
+            break_tags(&doc["blocks"][0]),
+            ["Space", "SoftBreak", "Space"]
+        );
+    }
+
+    #[test]
+    fn reflow_counts_list_indentation() {
+        let config = ConfigBuilder::default().line_width(10).build();
+        let mut doc = document(vec![json!({"t": "BulletList", "c": [[para("aaa bbb")]]})]);
+        filter_document(&mut doc, &config).unwrap();
+        assert_eq!(break_tags(&doc["blocks"][0]["c"][0][0]), ["Space"]);
+
+        let config = ConfigBuilder::default().line_width(8).build();
+        filter_document(&mut doc, &config).unwrap();
+        assert_eq!(break_tags(&doc["blocks"][0]["c"][0][0]), ["SoftBreak"]);
+    }
+
+    #[test]
+    fn sentence_mode_breaks_after_sentences_only() {
+        let config = ConfigBuilder::default().wrap(WrapMode::Sentence).build();
+        let mut doc = document(vec![para("See e.g. this. Then more.")]);
+        filter_document(&mut doc, &config).unwrap();
+        assert_eq!(
+            break_tags(&doc["blocks"][0]),
+            ["Space", "Space", "SoftBreak", "Space"]
+        );
+    }
+
+    #[test]
+    fn breaks_inside_emphasis_are_rewritten() {
+        let config = ConfigBuilder::default().line_width(12).build();
+        let mut doc = document(vec![json!({"t": "Para", "c": [
+            {"t": "Str", "c": "Some"},
+            {"t": "SoftBreak"},
+            {"t": "Emph", "c": [
+                {"t": "Str", "c": "short"},
+                {"t": "Space"},
+                {"t": "Str", "c": "words"},
+            ]},
+        ]})]);
+        filter_document(&mut doc, &config).unwrap();
+        let inlines = &doc["blocks"][0]["c"];
+        assert_eq!(inlines[1]["t"], "Space");
+        assert_eq!(inlines[2]["c"][1]["t"], "SoftBreak");
+    }
+
+    #[test]
+    fn preserve_leaves_the_document_alone() {
+        let config = ConfigBuilder::default()
+            .line_width(5)
+            .wrap(WrapMode::Preserve)
+            .build();
+        let original = document(vec![para("one two three")]);
+        let mut doc = original.clone();
+        filter_document(&mut doc, &config).unwrap();
+        assert_eq!(doc, original);
+    }
+
+    #[test]
+    fn rejects_json_that_is_not_pandoc() {
+        let config = Config::default();
+        assert!(matches!(
+            filter("{\"blocks\": []}", &config),
+            Err(FilterError::NotPandoc)
+        ));
+        assert!(matches!(filter("[", &config), Err(FilterError::Json(_))));
+    }
+}
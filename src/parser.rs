@@ -7,6 +7,8 @@ pub use panache_parser::parser::inlines;
 pub use panache_parser::parser::utils;
 pub use panache_parser::parser::yaml;
 pub use panache_parser::parser::{Diagnostics, SyntaxError, SyntaxErrorSource};
+pub use panache_parser::parser::{ParseWarning, ParseWarningKind, collect_warnings};
+pub use panache_parser::{LosslessMismatch, find_lossless_mismatch};
 pub use panache_parser::{RefdefMap, collect_refdef_labels};
 pub use panache_parser::{to_pandoc_ast, to_pandoc_json};
 
@@ -18,6 +20,30 @@ pub fn parse(input: &str, config: Option<Config>) -> SyntaxNode {
     panache_parser::parser::parse(input, parser_config)
 }
 
+/// Parse a fragment as inline content only, with no block structure. See
+/// [`panache_parser::parse_inline`].
+pub fn parse_inline(snippet: &str, config: Option<Config>) -> SyntaxNode {
+    let parser_config = config.map(|c| c.parser_options());
+    panache_parser::parser::parse_inline(snippet, parser_config)
+}
+
+/// Parse `input`, also returning warnings for malformed structure the parser
+/// recovered from. See [`panache_parser::parse_with_diagnostics`].
+pub fn parse_with_diagnostics(
+    input: &str,
+    config: Option<Config>,
+) -> (SyntaxNode, Vec<ParseWarning>) {
+    let parser_config = config.map(|c| c.parser_options());
+    panache_parser::parser::parse_with_diagnostics(input, parser_config)
+}
+
+/// Parse `input` and verify the CST text reproduces it exactly. See
+/// [`panache_parser::check_lossless`].
+pub fn check_lossless(input: &str, config: Option<Config>) -> Result<SyntaxNode, LosslessMismatch> {
+    let parser_config = config.map(|c| c.parser_options());
+    panache_parser::check_lossless(input, parser_config)
+}
+
 /// Parse with a caller-supplied refdef set, skipping the
 /// `collect_refdef_labels` scan. See
 /// [`panache_parser::parse_with_refdefs`].
@@ -38,6 +64,17 @@ pub fn parse_with_refdefs_and_errors(
     panache_parser::parser::parse_with_refdefs_and_errors(input, parser_config, refdefs)
 }
 
+/// Like [`parse_with_refdefs_and_errors`], but also returning the recovery
+/// warnings. See [`panache_parser::parser::parse_with_refdefs_and_diagnostics`].
+pub fn parse_with_refdefs_and_diagnostics(
+    input: &str,
+    config: Option<Config>,
+    refdefs: RefdefMap,
+) -> (SyntaxNode, Vec<SyntaxError>, Vec<ParseWarning>) {
+    let parser_config = config.map(|c| c.parser_options());
+    panache_parser::parser::parse_with_refdefs_and_diagnostics(input, parser_config, refdefs)
+}
+
 pub fn parse_incremental_suffix(
     input: &str,
     config: Option<Config>,
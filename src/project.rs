@@ -0,0 +1,296 @@
+//! Quarto and bookdown project structure.
+//!
+//! A document belongs to a project when an ancestor directory holds a
+//! `_quarto.yml` or `_bookdown.yml` manifest. The manifest decides which files
+//! make up the project (the chapter list, or Quarto's `project: render:`
+//! patterns) and can carry project-wide reader options such as
+//! `from: markdown+emoji`. Config resolution uses a [`Project`] to pick the
+//! flavor and extensions of documents the config file says nothing about, and
+//! cross-file lints use it to find sibling documents.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::config::{Config, Flavor, FlavorSpec};
+use crate::includes::find_project_documents;
+use crate::metadata::project::{BookdownFiles, read_bookdown_files};
+use crate::syntax::{YamlBlockMapValue, YamlNode, parse_yaml_document};
+
+/// The tool a project manifest belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectKind {
+    /// A `_quarto.yml` project (website, book, or default).
+    Quarto,
+    /// A `_bookdown.yml` book.
+    Bookdown,
+}
+
+/// A Quarto or bookdown project, as described by its manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Project {
+    pub kind: ProjectKind,
+    /// Directory holding the manifest.
+    pub root: PathBuf,
+    /// Chapter files in book order: Quarto's `book: chapters:` and
+    /// `book: appendices:`, or bookdown's `rmd_files`. Empty when the manifest
+    /// lists none.
+    pub chapters: Vec<PathBuf>,
+    /// Pandoc reader string from the manifest's `from:` key, at the top level
+    /// or under a `format:` entry.
+    pub from: Option<String>,
+}
+
+impl Project {
+    /// The project `doc_path` belongs to, if any. A Quarto manifest wins over
+    /// a bookdown one when both are found.
+    pub fn discover(doc_path: &Path) -> Option<Self> {
+        let roots = crate::includes::find_project_roots(doc_path);
+        if let Some(root) = roots.quarto {
+            return Some(Self::load_cached(&root, ProjectKind::Quarto));
+        }
+        roots
+            .bookdown
+            .map(|root| Self::load_cached(&root, ProjectKind::Bookdown))
+    }
+
+    /// [`Self::load`], reusing the last read of the manifest for as long as
+    /// its modification time is unchanged.
+    fn load_cached(root: &Path, kind: ProjectKind) -> Self {
+        let manifest = manifest_path(root, kind);
+        let modified = std::fs::metadata(&manifest)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        PROJECT_CACHE.with(|cache| {
+            if let Some((seen, project)) = cache.borrow().get(&manifest)
+                && *seen == modified
+            {
+                return project.clone();
+            }
+            let project = Self::load(root, kind);
+            cache
+                .borrow_mut()
+                .insert(manifest, (modified, project.clone()));
+            project
+        })
+    }
+
+    /// Read the manifest of the `kind` project at `root`. A missing or
+    /// unparsable manifest yields a project with no chapters or options.
+    pub fn load(root: &Path, kind: ProjectKind) -> Self {
+        let mut project = Self {
+            kind,
+            root: root.to_path_buf(),
+            chapters: Vec::new(),
+            from: None,
+        };
+        match kind {
+            ProjectKind::Quarto => {
+                if let Ok(yaml) = std::fs::read_to_string(project.manifest()) {
+                    project.read_quarto_manifest(&yaml);
+                }
+            }
+            ProjectKind::Bookdown => {
+                project.chapters = match read_bookdown_files(root) {
+                    Some(BookdownFiles::List(files)) => files,
+                    Some(BookdownFiles::ByFormat(formats)) => {
+                        // Per-format lists usually repeat the same chapters;
+                        // merge them in a stable order.
+                        let mut formats: Vec<_> = formats.into_iter().collect();
+                        formats.sort_by(|a, b| a.0.cmp(&b.0));
+                        let mut files: Vec<PathBuf> = Vec::new();
+                        for path in formats.into_iter().flat_map(|(_, files)| files) {
+                            if !files.contains(&path) {
+                                files.push(path);
+                            }
+                        }
+                        files
+                    }
+                    None => Vec::new(),
+                };
+            }
+        }
+        project
+    }
+
+    /// Path of the project manifest.
+    pub fn manifest(&self) -> PathBuf {
+        manifest_path(&self.root, self.kind)
+    }
+
+    /// Flavor of the project's Markdown documents.
+    pub fn flavor(&self) -> Flavor {
+        match self.kind {
+            ProjectKind::Quarto => Flavor::Quarto,
+            ProjectKind::Bookdown => Flavor::RMarkdown,
+        }
+    }
+
+    /// Extensions toggled by the `+ext` / `-ext` modifiers of the manifest's
+    /// `from:` reader, in kebab-case. The reader's base name is ignored: the
+    /// project's flavor already says which Markdown it renders.
+    pub fn extension_modifiers(&self) -> Vec<(String, bool)> {
+        let Some(from) = &self.from else {
+            return Vec::new();
+        };
+        match from.parse::<FlavorSpec>() {
+            Ok(spec) => spec.extensions,
+            Err(err) => {
+                log::warn!(
+                    "Ignoring `from: {from}` in {}: {err}",
+                    self.manifest().display()
+                );
+                Vec::new()
+            }
+        }
+    }
+
+    /// Every document that belongs to the project: the chapters in book
+    /// order, then the other documents in walk order.
+    pub fn documents(&self, config: &Config) -> Vec<PathBuf> {
+        let mut documents: Vec<PathBuf> = self
+            .chapters
+            .iter()
+            .filter(|path| path.is_file())
+            .cloned()
+            .collect();
+        for path in find_project_documents(&self.root, config, self.kind == ProjectKind::Bookdown) {
+            if !documents.contains(&path) {
+                documents.push(path);
+            }
+        }
+        documents
+    }
+
+    fn read_quarto_manifest(&mut self, yaml: &str) {
+        let Some(map) = parse_yaml_document(yaml).and_then(|document| document.block_map()) else {
+            return;
+        };
+
+        if let Some(book) = map.value_of("book").and_then(|book| book.as_block_map()) {
+            for key in ["chapters", "appendices"] {
+                if let Some(value) = book.value_of(key) {
+                    collect_chapters(&value, &self.root, &mut self.chapters);
+                }
+            }
+        }
+
+        self.from = map
+            .value_of("from")
+            .and_then(|from| from.as_scalar())
+            .map(|from| from.value())
+            .or_else(|| {
+                map.value_of("format")?
+                    .as_block_map()?
+                    .entries()
+                    .filter_map(|entry| entry.value()?.as_block_map())
+                    .find_map(|format| Some(format.value_of("from")?.as_scalar()?.value()))
+            });
+    }
+}
+
+thread_local! {
+    /// Projects by manifest path, with the manifest's modification time when
+    /// it was read.
+    static PROJECT_CACHE: RefCell<HashMap<PathBuf, (Option<SystemTime>, Project)>> =
+        RefCell::new(HashMap::new());
+}
+
+fn manifest_path(root: &Path, kind: ProjectKind) -> PathBuf {
+    match kind {
+        ProjectKind::Quarto => root.join("_quarto.yml"),
+        ProjectKind::Bookdown => root.join("_bookdown.yml"),
+    }
+}
+
+/// Append the chapter files of a `chapters:` list to `out`. Items are file
+/// names, or `part:` maps with their own nested `chapters:`.
+fn collect_chapters(value: &YamlBlockMapValue, root: &Path, out: &mut Vec<PathBuf>) {
+    let Some(sequence) = value.as_block_sequence() else {
+        return;
+    };
+    for item in sequence.items() {
+        match item.as_node() {
+            Some(YamlNode::Scalar(scalar)) => out.push(root.join(scalar.value())),
+            Some(YamlNode::BlockMap(part)) => {
+                if let Some(part_file) = part.value_of("part").and_then(|part| part.as_scalar()) {
+                    let part_file = part_file.value();
+                    // A part title that is not a file name only groups chapters.
+                    if Path::new(&part_file).extension().is_some() {
+                        out.push(root.join(part_file));
+                    }
+                }
+                if let Some(chapters) = part.value_of("chapters") {
+                    collect_chapters(&chapters, root, out);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn reads_quarto_book_chapters_and_from() {
+        let temp = TempDir::new().expect("tempdir");
+        let root = temp.path();
+        fs::write(
+            root.join("_quarto.yml"),
+            "project:\n  type: book\nbook:\n  chapters:\n    - index.qmd\n    - part: parts/one.qmd\n      chapters:\n        - intro.qmd\n    - part: \"Two\"\n      chapters:\n        - methods.qmd\n  appendices:\n    - data.qmd\nformat:\n  html:\n    from: markdown+emoji-smart\n",
+        )
+        .expect("write _quarto.yml");
+        let doc = root.join("intro.qmd");
+        fs::write(&doc, "# Intro\n").expect("write intro.qmd");
+
+        let project = Project::discover(&doc).expect("project");
+        assert_eq!(project.kind, ProjectKind::Quarto);
+        assert_eq!(project.flavor(), Flavor::Quarto);
+        assert_eq!(
+            project.chapters,
+            [
+                "index.qmd",
+                "parts/one.qmd",
+                "intro.qmd",
+                "methods.qmd",
+                "data.qmd"
+            ]
+            .map(|name| root.join(name))
+        );
+        assert_eq!(project.from.as_deref(), Some("markdown+emoji-smart"));
+        assert_eq!(
+            project.extension_modifiers(),
+            vec![("emoji".to_string(), true), ("smart".to_string(), false)]
+        );
+    }
+
+    #[test]
+    fn reads_bookdown_rmd_files() {
+        let temp = TempDir::new().expect("tempdir");
+        let root = temp.path();
+        fs::write(
+            root.join("_bookdown.yml"),
+            "rmd_files: [\"index.Rmd\", \"01-intro.Rmd\"]\n",
+        )
+        .expect("write _bookdown.yml");
+
+        let project = Project::load(root, ProjectKind::Bookdown);
+        assert_eq!(project.flavor(), Flavor::RMarkdown);
+        assert_eq!(
+            project.chapters,
+            vec![root.join("index.Rmd"), root.join("01-intro.Rmd")]
+        );
+        assert!(project.extension_modifiers().is_empty());
+    }
+
+    #[test]
+    fn no_project_without_manifest() {
+        let temp = TempDir::new().expect("tempdir");
+        assert!(Project::discover(&temp.path().join("doc.md")).is_none());
+    }
+}
@@ -0,0 +1,35 @@
+//! Rendering documents to other formats for `panache render`.
+//!
+//! Each output format is a [`Renderer`] over the parsed CST, so renderers see
+//! the same document structure as the formatter and linter and need no
+//! separate AST.
+
+pub mod plain;
+
+use crate::config::Config;
+use crate::syntax::SyntaxNode;
+
+pub use plain::PlainRenderer;
+
+/// Turns a parsed document into text in some output format.
+pub trait Renderer {
+    /// Render the document `tree`, which was parsed with `config`.
+    fn render(&self, tree: &SyntaxNode, config: &Config) -> String;
+}
+
+/// An output format of [`render`](crate::render()).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RenderFormat {
+    /// Readable text without markup; see [`PlainRenderer`].
+    #[default]
+    Plain,
+}
+
+impl RenderFormat {
+    /// The renderer for this format.
+    pub fn renderer(self) -> Box<dyn Renderer> {
+        match self {
+            Self::Plain => Box::new(PlainRenderer),
+        }
+    }
+}
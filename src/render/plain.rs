@@ -0,0 +1,460 @@
+//! Plain text output, for search indexes, word counters, and other tools that
+//! want the words of a document without its markup.
+
+use std::collections::HashMap;
+
+use rowan::{NodeOrToken, TextRange};
+use unicode_width::UnicodeWidthStr;
+
+use super::Renderer;
+use crate::config::Config;
+use crate::syntax::{
+    AstNode, AutoLink, LinkKind, List, ListItem, SyntaxKind, SyntaxNode, WikiLink, collect_links,
+};
+
+/// Renders a document as readable plain text.
+///
+/// Blocks are separated by blank lines and each paragraph is one line.
+/// Emphasis and other markers are dropped; links read `text (url)`, images
+/// their alt text, and code blocks and block quotes are indented. Tables are
+/// laid out in aligned columns under an underlined header. Frontmatter,
+/// comments, raw HTML and TeX, shortcodes, and reference definitions are left
+/// out.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PlainRenderer;
+
+impl Renderer for PlainRenderer {
+    fn render(&self, tree: &SyntaxNode, _config: &Config) -> String {
+        let targets = collect_links(tree)
+            .into_iter()
+            .filter(|link| matches!(link.kind, LinkKind::Inline | LinkKind::Reference))
+            .filter_map(|link| Some((link.range(), link.target?)))
+            .collect();
+        let text = Plain { targets }.blocks(tree, "\n\n");
+        if text.is_empty() { text } else { text + "\n" }
+    }
+}
+
+struct Plain {
+    /// Link destinations by the range [`collect_links`] reports for them.
+    targets: HashMap<TextRange, String>,
+}
+
+impl Plain {
+    /// The block children of `parent`, rendered and joined by `separator`.
+    fn blocks(&self, parent: &SyntaxNode, separator: &str) -> String {
+        parent
+            .children()
+            .filter_map(|child| self.block(&child))
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join(separator)
+    }
+
+    /// `node` as a block without a trailing newline, or `None` when it has no
+    /// text to show.
+    fn block(&self, node: &SyntaxNode) -> Option<String> {
+        let text = match node.kind() {
+            SyntaxKind::PARAGRAPH | SyntaxKind::PLAIN | SyntaxKind::FIGURE => self.inlines(node),
+            SyntaxKind::HEADING => {
+                let content = node
+                    .children()
+                    .find(|child| child.kind() == SyntaxKind::HEADING_CONTENT)?;
+                self.inlines(&content)
+            }
+            SyntaxKind::BLOCK_QUOTE | SyntaxKind::ALERT => indent(&self.blocks(node, "\n\n"), "  "),
+            SyntaxKind::FENCED_DIV | SyntaxKind::ADMONITION | SyntaxKind::HTML_BLOCK_DIV => {
+                self.blocks(node, "\n\n")
+            }
+            SyntaxKind::LIST => self.list(node),
+            SyntaxKind::DEFINITION_LIST => self.definition_list(node),
+            SyntaxKind::CODE_BLOCK => {
+                let content = node
+                    .children()
+                    .find(|child| child.kind() == SyntaxKind::CODE_CONTENT)?;
+                indent(content.text().to_string().trim_end(), "    ")
+            }
+            SyntaxKind::LINE_BLOCK => node
+                .children()
+                .filter(|line| line.kind() == SyntaxKind::LINE_BLOCK_LINE)
+                .map(|line| self.inlines(&line))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            SyntaxKind::SIMPLE_TABLE
+            | SyntaxKind::MULTILINE_TABLE
+            | SyntaxKind::PIPE_TABLE
+            | SyntaxKind::GRID_TABLE => self.table(node),
+            SyntaxKind::FOOTNOTE_DEFINITION => {
+                let id = node
+                    .children_with_tokens()
+                    .filter_map(NodeOrToken::into_token)
+                    .find(|token| token.kind() == SyntaxKind::FOOTNOTE_LABEL_ID)?;
+                hang(&format!("[{}] ", id.text()), &self.blocks(node, "\n\n"))
+            }
+            _ => return None,
+        };
+        Some(text)
+    }
+
+    fn list(&self, node: &SyntaxNode) -> String {
+        let list = List::cast(node.clone()).expect("LIST node");
+        let separator = if list.is_loose() { "\n\n" } else { "\n" };
+        list.items()
+            .map(|item| self.list_item(&item, separator))
+            .collect::<Vec<_>>()
+            .join(separator)
+    }
+
+    fn list_item(&self, item: &ListItem, separator: &str) -> String {
+        let mut marker = item.marker().unwrap_or_else(|| "-".to_string());
+        if let Some(checkbox) = item
+            .syntax()
+            .children_with_tokens()
+            .filter_map(NodeOrToken::into_token)
+            .find(|token| token.kind() == SyntaxKind::TASK_CHECKBOX)
+        {
+            marker = format!("{marker} {}", checkbox.text());
+        }
+        hang(
+            &format!("{marker} "),
+            &self.blocks(item.syntax(), separator),
+        )
+    }
+
+    /// Each term on its own line, its definitions indented under it.
+    fn definition_list(&self, node: &SyntaxNode) -> String {
+        node.children()
+            .filter(|item| item.kind() == SyntaxKind::DEFINITION_ITEM)
+            .map(|item| {
+                item.children()
+                    .filter_map(|child| match child.kind() {
+                        SyntaxKind::TERM => Some(self.inlines(&child)),
+                        SyntaxKind::DEFINITION => {
+                            Some(indent(&self.blocks(&child, "\n\n"), "    "))
+                        }
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    /// The cells in columns padded to their widest entry and separated by two
+    /// spaces, the header underlined, and the caption after a blank line.
+    fn table(&self, node: &SyntaxNode) -> String {
+        let mut rows: Vec<Vec<String>> = Vec::new();
+        let mut has_header = false;
+        let mut caption = String::new();
+        for child in node.children() {
+            match child.kind() {
+                SyntaxKind::TABLE_HEADER | SyntaxKind::TABLE_ROW => {
+                    has_header |= child.kind() == SyntaxKind::TABLE_HEADER && rows.is_empty();
+                    rows.push(
+                        child
+                            .children()
+                            .filter(|cell| cell.kind() == SyntaxKind::TABLE_CELL)
+                            .map(|cell| self.inlines(&cell))
+                            .collect(),
+                    );
+                }
+                SyntaxKind::TABLE_CAPTION => caption = self.inlines(&child),
+                _ => {}
+            }
+        }
+
+        let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let widths: Vec<usize> = (0..columns)
+            .map(|column| {
+                rows.iter()
+                    .filter_map(|row| row.get(column))
+                    .map(|cell| cell.width())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let mut lines: Vec<String> = Vec::new();
+        for (idx, row) in rows.iter().enumerate() {
+            let line = widths
+                .iter()
+                .enumerate()
+                .map(|(column, width)| {
+                    let cell = row.get(column).map_or("", String::as_str);
+                    format!("{cell}{}", " ".repeat(width - cell.width()))
+                })
+                .collect::<Vec<_>>()
+                .join("  ");
+            lines.push(line.trim_end().to_string());
+            if idx == 0 && has_header {
+                let rule = widths
+                    .iter()
+                    .map(|width| "-".repeat((*width).max(1)))
+                    .collect::<Vec<_>>()
+                    .join("  ");
+                lines.push(rule);
+            }
+        }
+
+        let mut text = lines.join("\n");
+        if !caption.is_empty() {
+            if !text.is_empty() {
+                text.push_str("\n\n");
+            }
+            text.push_str(&caption);
+        }
+        text
+    }
+
+    /// The inline content of `node` on one line, with hard line breaks kept.
+    fn inlines(&self, node: &SyntaxNode) -> String {
+        let mut text = String::new();
+        self.push_inlines(node, &mut text);
+        text.split('\n')
+            .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+            .collect::<Vec<_>>()
+            .join("\n")
+            .trim()
+            .to_string()
+    }
+
+    fn push_inlines(&self, node: &SyntaxNode, out: &mut String) {
+        for element in node.children_with_tokens() {
+            match element {
+                NodeOrToken::Token(token) => match token.kind() {
+                    SyntaxKind::TEXT | SyntaxKind::ESCAPED_CHAR => out.push_str(token.text()),
+                    SyntaxKind::WHITESPACE
+                    | SyntaxKind::NEWLINE
+                    | SyntaxKind::NONBREAKING_SPACE => out.push(' '),
+                    SyntaxKind::HARD_LINE_BREAK => out.push('\n'),
+                    _ => {}
+                },
+                NodeOrToken::Node(child) => self.push_inline(&child, out),
+            }
+        }
+    }
+
+    fn push_inline(&self, node: &SyntaxNode, out: &mut String) {
+        match node.kind() {
+            SyntaxKind::LINK => self.push_link(node, out),
+            SyntaxKind::AUTO_LINK => {
+                if let Some(link) = AutoLink::cast(node.clone()) {
+                    out.push_str(&link.target());
+                }
+            }
+            SyntaxKind::WIKI_LINK | SyntaxKind::IMAGE_WIKI_LINK => {
+                if let Some(link) = WikiLink::cast(node.clone()) {
+                    out.push_str(&link.title().or_else(|| link.url()).unwrap_or_default());
+                }
+            }
+            SyntaxKind::IMAGE_LINK => {
+                if let Some(alt) = node
+                    .children()
+                    .find(|child| child.kind() == SyntaxKind::IMAGE_ALT)
+                {
+                    self.push_inlines(&alt, out);
+                }
+            }
+            SyntaxKind::INLINE_CODE => push_tokens(node, SyntaxKind::INLINE_CODE_CONTENT, out),
+            SyntaxKind::INLINE_MATH | SyntaxKind::DISPLAY_MATH => {
+                match node
+                    .children()
+                    .find(|child| child.kind() == SyntaxKind::MATH_CONTENT)
+                {
+                    Some(content) => out.push_str(content.text().to_string().trim()),
+                    None => push_tokens(node, SyntaxKind::TEXT, out),
+                }
+            }
+            SyntaxKind::FOOTNOTE_REFERENCE => {
+                out.push('[');
+                push_tokens(node, SyntaxKind::FOOTNOTE_LABEL_ID, out);
+                out.push(']');
+            }
+            SyntaxKind::INLINE_FOOTNOTE => {
+                out.push('[');
+                self.push_inlines(node, out);
+                out.push(']');
+            }
+            SyntaxKind::CITATION | SyntaxKind::CROSSREF => {
+                out.push_str(&node.text().to_string());
+            }
+            kind if is_excluded(kind) => out.push(' '),
+            _ => self.push_inlines(node, out),
+        }
+    }
+
+    /// `text (url)`, or just the text when the destination is unknown or
+    /// reads the same as the text.
+    fn push_link(&self, node: &SyntaxNode, out: &mut String) {
+        let mut text = String::new();
+        let mut range = None;
+        for child in node.children() {
+            match child.kind() {
+                SyntaxKind::LINK_TEXT => {
+                    self.push_inlines(&child, &mut text);
+                    range.get_or_insert(child.text_range());
+                }
+                SyntaxKind::LINK_DEST | SyntaxKind::LINK_REF => range = Some(child.text_range()),
+                _ => {}
+            }
+        }
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        let url = range.and_then(|range| self.targets.get(&range));
+        match url {
+            Some(url) if !url.is_empty() && text.is_empty() => out.push_str(url),
+            Some(url) if !url.is_empty() && *url != text => {
+                out.push_str(&format!("{text} ({url})"));
+            }
+            _ => out.push_str(&text),
+        }
+    }
+}
+
+/// Inline and block constructs that hold no prose.
+fn is_excluded(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::ATTRIBUTE
+            | SyntaxKind::SPAN_ATTRIBUTES
+            | SyntaxKind::COMMENT
+            | SyntaxKind::INLINE_EXEC
+            | SyntaxKind::RAW_INLINE
+            | SyntaxKind::INLINE_HTML
+            | SyntaxKind::LATEX_COMMAND
+            | SyntaxKind::SHORTCODE
+            | SyntaxKind::EMOJI
+            | SyntaxKind::OBSIDIAN_COMMENT
+            | SyntaxKind::MYST_ROLE
+            | SyntaxKind::MYST_TARGET
+            | SyntaxKind::MYST_COMMENT
+            | SyntaxKind::MDX_JSX
+            | SyntaxKind::MDX_EXPRESSION
+            | SyntaxKind::SVELTE_TAG
+            | SyntaxKind::SVELTE_EXPRESSION
+    )
+}
+
+fn push_tokens(node: &SyntaxNode, kind: SyntaxKind, out: &mut String) {
+    node.descendants_with_tokens()
+        .filter_map(NodeOrToken::into_token)
+        .filter(|token| token.kind() == kind)
+        .for_each(|token| out.push_str(token.text()));
+}
+
+/// `text` with `prefix` before each non-empty line.
+fn indent(text: &str, prefix: &str) -> String {
+    text.lines()
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("{prefix}{line}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// `text` after `marker`, its later lines indented to line up with the first.
+fn hang(marker: &str, text: &str) -> String {
+    let padding = " ".repeat(marker.width());
+    let mut lines = text.lines();
+    let first = lines.next().unwrap_or_default();
+    let mut out = format!("{marker}{first}").trim_end().to_string();
+    for line in lines {
+        out.push('\n');
+        if !line.is_empty() {
+            out.push_str(&padding);
+            out.push_str(line);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::render::RenderFormat;
+
+    fn plain(input: &str) -> String {
+        crate::render(input, RenderFormat::Plain, None)
+    }
+
+    #[test]
+    fn strips_inline_markup_and_keeps_link_destinations() {
+        let input = "\
+# The *Intro* {#intro}
+
+Some **bold**\\
+and `code` with a [link](https://example.com \"Title\"), a [ref][docs],
+<https://example.org>, and ![an image](plot.png).[^1]
+
+[docs]: https://docs.rs
+
+[^1]: A note.
+";
+        assert_eq!(
+            plain(input),
+            "\
+The Intro
+
+Some bold
+and code with a link (https://example.com), a ref (https://docs.rs), \
+https://example.org, and an image.[1]
+
+[1] A note.
+"
+        );
+    }
+
+    #[test]
+    fn lays_out_lists_quotes_and_code() {
+        let input = "\
+- one
+- two
+  1. nested
+     lines
+
+> quoted
+> text
+
+```r
+x <- 1
+```
+";
+        assert_eq!(
+            plain(input),
+            "\
+- one
+- two
+  1. nested lines
+
+  quoted text
+
+    x <- 1
+"
+        );
+    }
+
+    #[test]
+    fn aligns_table_columns() {
+        let input = "\
+| Name | Description |
+|------|-------------|
+| `a`  | First       |
+| long name | Second |
+
+: Options
+";
+        assert_eq!(
+            plain(input),
+            "\
+Name       Description
+---------  -----------
+a          First
+long name  Second
+
+Options
+"
+        );
+    }
+}
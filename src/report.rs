@@ -0,0 +1,320 @@
+//! Reports for CI dashboards and issue descriptions.
+//!
+//! `format --report` writes a [`FormatReport`] as JSON or Markdown;
+//! `debug format --report` and `corpus` render debug-check failures as
+//! Markdown. The shared pieces (status counts, fenced diffs) live here so the
+//! reports read alike.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use serde_json::json;
+use similar::{ChangeTag, TextDiff};
+
+use crate::DebugFailure;
+use crate::cli::DebugChecks;
+
+/// What formatting did to one file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum FileStatus {
+    /// Formatting changes the file: it was rewritten, or with `--check` it
+    /// would be.
+    Formatted,
+    /// The file is already formatted.
+    Unchanged,
+    /// The file could not be read or formatted.
+    Error,
+}
+
+impl FileStatus {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Formatted => "formatted",
+            Self::Unchanged => "unchanged",
+            Self::Error => "error",
+        }
+    }
+}
+
+/// One row of a [`FormatReport`].
+#[derive(Clone, Debug)]
+pub(crate) struct FileReport {
+    pub path: String,
+    pub status: FileStatus,
+    /// Diff hunks between the input and the formatted output.
+    pub hunks: usize,
+    pub duration: Duration,
+    pub error: Option<String>,
+}
+
+impl FileReport {
+    pub(crate) fn new(
+        path: &str,
+        input: &str,
+        output: &str,
+        changed: bool,
+        duration: Duration,
+    ) -> Self {
+        Self {
+            path: path.to_string(),
+            status: if changed {
+                FileStatus::Formatted
+            } else {
+                FileStatus::Unchanged
+            },
+            hunks: if changed {
+                TextDiff::from_lines(input, output).grouped_ops(3).len()
+            } else {
+                0
+            },
+            duration,
+            error: None,
+        }
+    }
+
+    pub(crate) fn error(path: &str, error: &io::Error, duration: Duration) -> Self {
+        Self {
+            path: path.to_string(),
+            status: FileStatus::Error,
+            hunks: 0,
+            duration,
+            error: Some(error.to_string()),
+        }
+    }
+}
+
+/// Per-file results of a `format` run.
+#[derive(Clone, Debug)]
+pub(crate) struct FormatReport {
+    /// Whether the run was `--check`, which leaves files untouched.
+    pub check: bool,
+    pub files: Vec<FileReport>,
+    pub duration: Duration,
+}
+
+impl FormatReport {
+    fn count(&self, status: FileStatus) -> usize {
+        self.files
+            .iter()
+            .filter(|file| file.status == status)
+            .count()
+    }
+
+    pub(crate) fn to_json(&self) -> serde_json::Value {
+        let files: Vec<serde_json::Value> = self
+            .files
+            .iter()
+            .map(|file| {
+                let mut entry = json!({
+                    "path": file.path,
+                    "status": file.status.label(),
+                    "hunks": file.hunks,
+                    "duration_ms": millis(file.duration),
+                });
+                if let Some(error) = &file.error {
+                    entry["error"] = json!(error);
+                }
+                entry
+            })
+            .collect();
+        json!({
+            "mode": if self.check { "check" } else { "format" },
+            "files": files,
+            "summary": {
+                "formatted": self.count(FileStatus::Formatted),
+                "unchanged": self.count(FileStatus::Unchanged),
+                "error": self.count(FileStatus::Error),
+                "duration_ms": millis(self.duration),
+            },
+        })
+    }
+
+    pub(crate) fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        out.push_str("# Format report\n\n");
+        out.push_str(&format!(
+            "- Mode: `{}`\n- Files: {}\n- Formatted: {}\n- Unchanged: {}\n- Errors: {}\n- Duration: {:.1} ms\n\n",
+            if self.check { "check" } else { "format" },
+            self.files.len(),
+            self.count(FileStatus::Formatted),
+            self.count(FileStatus::Unchanged),
+            self.count(FileStatus::Error),
+            millis(self.duration),
+        ));
+        if self.files.is_empty() {
+            return out;
+        }
+
+        out.push_str("| File | Status | Hunks | Time (ms) |\n");
+        out.push_str("|------|--------|------:|----------:|\n");
+        for file in &self.files {
+            out.push_str(&format!(
+                "| `{}` | {} | {} | {:.1} |\n",
+                file.path,
+                file.status.label(),
+                file.hunks,
+                millis(file.duration)
+            ));
+        }
+
+        let errors: Vec<&FileReport> = self
+            .files
+            .iter()
+            .filter(|file| file.error.is_some())
+            .collect();
+        if !errors.is_empty() {
+            out.push_str("\n## Errors\n\n");
+            for file in errors {
+                out.push_str(&format!(
+                    "- `{}`: {}\n",
+                    file.path,
+                    file.error.as_deref().unwrap_or_default()
+                ));
+            }
+        }
+        out
+    }
+
+    /// Write the report to `path`: Markdown for `.md` and `.markdown` files,
+    /// JSON otherwise.
+    pub(crate) fn write(&self, path: &Path) -> io::Result<()> {
+        let markdown = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| matches!(ext, "md" | "markdown"));
+        let text = if markdown {
+            self.to_markdown()
+        } else {
+            serde_json::to_string_pretty(&self.to_json()).map_err(io::Error::other)? + "\n"
+        };
+        fs::write(path, text).map_err(|err| {
+            io::Error::new(
+                err.kind(),
+                format!("failed to write report {}: {err}", path.display()),
+            )
+        })
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    (duration.as_secs_f64() * 1_000_000.0).round() / 1000.0
+}
+
+/// Append `diff` as a fenced `diff` block.
+fn push_diff_block(out: &mut String, diff: &TextDiff<'_, '_, str>) {
+    out.push_str("```diff\n");
+    for change in diff.iter_all_changes() {
+        let sign = match change.tag() {
+            ChangeTag::Delete => "-",
+            ChangeTag::Insert => "+",
+            ChangeTag::Equal => " ",
+        };
+        out.push_str(sign);
+        out.push_str(change.value());
+    }
+    out.push_str("```\n\n");
+}
+
+pub(crate) fn build_debug_failure_report(
+    checks: DebugChecks,
+    files_checked: usize,
+    failures: &[(String, DebugFailure)],
+) -> String {
+    let mut out = String::new();
+    out.push_str("# Debug-format regression report\n\n");
+    out.push_str(&format!(
+        "- Checks: `{}`\n- Files checked: {}\n- Failures: {}\n\n",
+        format!("{:?}", checks).to_lowercase(),
+        files_checked,
+        failures.len()
+    ));
+
+    if failures.is_empty() {
+        out.push_str("All checks passed.\n");
+        return out;
+    }
+
+    out.push_str("## Failures\n\n");
+    for (idx, (file, failure)) in failures.iter().enumerate() {
+        let diff = TextDiff::from_lines(&failure.left, &failure.right);
+        let location_line = diff
+            .grouped_ops(0)
+            .first()
+            .and_then(|group| group.first().map(|op| op.old_range().start + 1))
+            .unwrap_or(1);
+
+        out.push_str(&format!(
+            "### {}. `{}` ({})\n\n",
+            idx + 1,
+            file,
+            failure.kind.label()
+        ));
+        out.push_str(&format!("- Approx. diff start line: {}\n\n", location_line));
+        push_diff_block(&mut out, &diff);
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> FormatReport {
+        FormatReport {
+            check: true,
+            files: vec![
+                FileReport::new(
+                    "a.qmd",
+                    "#  A\n\ntext\n",
+                    "# A\n\ntext\n",
+                    true,
+                    Duration::from_micros(1500),
+                ),
+                FileReport::new("b.qmd", "# B\n", "# B\n", false, Duration::ZERO),
+                FileReport::error(
+                    "c.qmd",
+                    &io::Error::other("stream did not contain valid UTF-8"),
+                    Duration::ZERO,
+                ),
+            ],
+            duration: Duration::from_millis(2),
+        }
+    }
+
+    #[test]
+    fn json_report_counts_statuses() {
+        let json = report().to_json();
+        assert_eq!(json["mode"], "check");
+        assert_eq!(json["files"][0]["status"], "formatted");
+        assert_eq!(json["files"][0]["hunks"], 1);
+        assert_eq!(json["files"][0]["duration_ms"], 1.5);
+        assert_eq!(
+            json["files"][2]["error"],
+            "stream did not contain valid UTF-8"
+        );
+        assert!(json["files"][1].get("error").is_none());
+        assert_eq!(json["summary"]["formatted"], 1);
+        assert_eq!(json["summary"]["unchanged"], 1);
+        assert_eq!(json["summary"]["error"], 1);
+    }
+
+    #[test]
+    fn markdown_report_has_a_row_per_file_and_lists_errors() {
+        let markdown = report().to_markdown();
+        assert!(
+            markdown.contains("| `a.qmd` | formatted | 1 | 1.5 |"),
+            "{markdown}"
+        );
+        assert!(
+            markdown.contains("| `b.qmd` | unchanged | 0 | 0.0 |"),
+            "{markdown}"
+        );
+        assert!(
+            markdown.contains("## Errors\n\n- `c.qmd`: stream did not contain valid UTF-8\n"),
+            "{markdown}"
+        );
+    }
+}
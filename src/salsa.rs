@@ -135,19 +135,20 @@ pub fn refdef_set(db: &dyn Db, file: FileText, config: FileConfig) -> crate::par
 /// edits which don't change refdefs short-circuit at the refdef layer
 /// without re-scanning the document inside `parse`.
 /// A cached parse: the green tree plus the embedded-sublanguage syntax errors
-/// (host-ranged malformed YAML) the parser surfaced. Parsed once and cached
-/// together so both the tree and the diagnostics are available without a second
-/// pass.
+/// (host-ranged malformed YAML) and the recovery warnings (unclosed fences and
+/// comments) the parser surfaced. Parsed once and cached together so the tree
+/// and the diagnostics are available without a second pass.
 #[derive(Debug, Clone)]
 pub struct ParsedDocument {
     pub green: rowan::GreenNode,
     pub errors: Vec<crate::parser::SyntaxError>,
+    pub warnings: Vec<crate::parser::ParseWarning>,
 }
 
 #[salsa::tracked(returns(ref), lru = 512, no_eq, unsafe(non_salsa_values))]
 pub fn parsed_document(db: &dyn Db, file: FileText, config: FileConfig) -> ParsedDocument {
     let refdefs = refdef_set(db, file, config).clone();
-    let (tree, errors) = crate::parser::parse_with_refdefs_and_errors(
+    let (tree, errors, warnings) = crate::parser::parse_with_refdefs_and_diagnostics(
         file.content_or_empty(db),
         Some(config.config(db).clone()),
         refdefs,
@@ -155,6 +156,7 @@ pub fn parsed_document(db: &dyn Db, file: FileText, config: FileConfig) -> Parse
     ParsedDocument {
         green: tree.green().into_owned(),
         errors,
+        warnings,
     }
 }
 
@@ -173,6 +175,16 @@ pub fn parse_syntax_errors(
     &parsed_document(db, file, config).errors
 }
 
+/// The recovery warnings (unclosed fences, fenced divs and comments) for
+/// `(file, config)`.
+pub fn parse_warnings(
+    db: &dyn Db,
+    file: FileText,
+    config: FileConfig,
+) -> &[crate::parser::ParseWarning] {
+    &parsed_document(db, file, config).warnings
+}
+
 /// Materialize the cached parse for `(file, config)` as a fresh `SyntaxNode`.
 pub fn parsed_tree_root(db: &dyn Db, file: FileText, config: FileConfig) -> SyntaxNode {
     SyntaxNode::new_root(parsed_tree(db, file, config).clone())
@@ -530,9 +542,9 @@ pub fn built_in_lint_plan(db: &dyn Db, file: FileText, config: FileConfig) -> Bu
 
     let mut external_jobs = Vec::new();
     if !cfg.linters.is_empty() {
-        let code_blocks = crate::utils::collect_code_blocks(&tree, text);
+        let code_blocks = crate::utils::collect_code_blocks_by_language_key(&tree, text, &cfg);
         for (language, linter_name) in &cfg.linters {
-            let Some(blocks) = code_blocks.get(language) else {
+            let Some(blocks) = code_blocks.get(&cfg.language_key(language)) else {
                 continue;
             };
             if blocks.is_empty() {
@@ -1465,6 +1477,11 @@ impl DefinitionIndex {
         self.example_labels.get(&key)
     }
 
+    /// Crossref labels and their definition sites, keyed by normalized label.
+    pub fn crossref_entries(&self) -> impl Iterator<Item = (&String, &DefinitionLocation)> {
+        self.crossrefs.iter()
+    }
+
     pub fn find_crossref_resolved(
         &self,
         id: &str,
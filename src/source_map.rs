@@ -0,0 +1,150 @@
+//! Position mapping between a document and its formatted output, for editor
+//! integrations that keep cursors and selections stable across formatting.
+//!
+//! The map is a line diff of input and output: runs of identical lines map
+//! byte for byte, and each changed run maps as a whole onto its replacement.
+//!
+//! ```rust
+//! let (output, map) = panache::format_with_map("#   Title\n\nText.\n", None);
+//! assert_eq!(output, "# Title\n\nText.\n");
+//! // "Text." moved two bytes to the left.
+//! assert_eq!(map.map_offset(11), 9);
+//! ```
+
+use std::ops::Range;
+
+use similar::{DiffTag, TextDiff};
+
+/// A pair of corresponding byte ranges in the input and the output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mapping {
+    pub input: Range<usize>,
+    pub output: Range<usize>,
+    /// Whether the output text differs from the input text. Unchanged
+    /// mappings have equal lengths.
+    pub changed: bool,
+}
+
+/// Mapping of byte offsets in a document to byte offsets in its formatted
+/// output.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceMap {
+    mappings: Vec<Mapping>,
+}
+
+impl SourceMap {
+    /// Build the map from `input` to `output`.
+    pub fn new(input: &str, output: &str) -> Self {
+        let diff = TextDiff::from_lines(input, output);
+        let input_lines = line_starts(input);
+        let output_lines = line_starts(output);
+        let mut mappings: Vec<Mapping> = Vec::new();
+        for op in diff.ops() {
+            let old = op.old_range();
+            let new = op.new_range();
+            let input = input_lines[old.start]..input_lines[old.end];
+            let output = output_lines[new.start]..output_lines[new.end];
+            let changed = op.tag() != DiffTag::Equal;
+            // Merge adjacent changed ops (a delete followed by an insert) into
+            // a single replacement.
+            match mappings.last_mut() {
+                Some(last) if changed && last.changed => {
+                    last.input.end = input.end;
+                    last.output.end = output.end;
+                }
+                _ => mappings.push(Mapping {
+                    input,
+                    output,
+                    changed,
+                }),
+            }
+        }
+        Self { mappings }
+    }
+
+    /// All mappings in document order. Together they cover the input and the
+    /// output without gaps.
+    pub fn mappings(&self) -> &[Mapping] {
+        &self.mappings
+    }
+
+    /// The changed mappings only: the replacements that turn the input into
+    /// the output.
+    pub fn changes(&self) -> impl Iterator<Item = &Mapping> {
+        self.mappings.iter().filter(|mapping| mapping.changed)
+    }
+
+    /// Output offset corresponding to `offset` in the input. Offsets inside
+    /// a changed run keep their distance from the run's start, clamped to
+    /// the replacement's end; offsets past the input's end map to the end of
+    /// the output.
+    pub fn map_offset(&self, offset: usize) -> usize {
+        for mapping in &self.mappings {
+            if offset < mapping.input.end {
+                let delta = offset.saturating_sub(mapping.input.start);
+                return (mapping.output.start + delta).min(mapping.output.end);
+            }
+        }
+        self.mappings.last().map_or(0, |last| last.output.end)
+    }
+}
+
+/// Byte offset of the start of each line, plus the length of `text`, so that
+/// line range `a..b` covers `starts[a]..starts[b]`.
+fn line_starts(text: &str) -> Vec<usize> {
+    let mut starts = vec![0];
+    starts.extend(text.split_inclusive('\n').scan(0, |offset, line| {
+        *offset += line.len();
+        Some(*offset)
+    }));
+    starts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identical_text_is_one_unchanged_mapping() {
+        let map = SourceMap::new("a\nb\n", "a\nb\n");
+        assert_eq!(
+            map.mappings(),
+            [Mapping {
+                input: 0..4,
+                output: 0..4,
+                changed: false,
+            }]
+        );
+        assert_eq!(map.changes().count(), 0);
+        assert_eq!(map.map_offset(3), 3);
+    }
+
+    #[test]
+    fn replacements_are_merged_and_offsets_shift() {
+        let map = SourceMap::new("keep\nold  line\nkeep\n", "keep\nnew\nkeep\n");
+        let changes: Vec<_> = map.changes().collect();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].input, 5..15);
+        assert_eq!(changes[0].output, 5..9);
+        // Inside the replacement: clamped to its end.
+        assert_eq!(map.map_offset(12), 9);
+        // After it: shifted by the length difference.
+        assert_eq!(map.map_offset(16), 10);
+        assert_eq!(map.map_offset(100), 14);
+    }
+
+    #[test]
+    fn mappings_cover_both_texts() {
+        let input = "a\n\n\nb\nc";
+        let output = "a\n\nb\nc\n";
+        let map = SourceMap::new(input, output);
+        let mappings = map.mappings();
+        assert_eq!(mappings.first().unwrap().input.start, 0);
+        assert_eq!(mappings.last().unwrap().input.end, input.len());
+        assert_eq!(mappings.last().unwrap().output.end, output.len());
+        for pair in mappings.windows(2) {
+            assert_eq!(pair[0].input.end, pair[1].input.start);
+            assert_eq!(pair[0].output.end, pair[1].output.start);
+        }
+    }
+}
@@ -0,0 +1,208 @@
+//! Document metrics for `panache stats`: words, headings, code cells, links,
+//! images, footnotes, and the longest line.
+
+use std::collections::BTreeMap;
+
+use rowan::{NodeOrToken, WalkEvent};
+use serde::Serialize;
+use unicode_width::UnicodeWidthStr;
+
+use crate::config::Config;
+use crate::syntax::{AstNode, Heading, SyntaxKind, SyntaxNode};
+use crate::utils::collect_code_blocks_by_language_key;
+
+/// Counts for one document.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DocumentStats {
+    /// Words of prose. Code, math, raw HTML and TeX, frontmatter, comments,
+    /// and link destinations are not counted.
+    pub words: usize,
+    /// Headings per level: `headings[0]` counts level 1, `headings[5]` level 6.
+    pub headings: [usize; 6],
+    /// Code blocks per language, keyed by [`Config::language_key`].
+    pub code_cells: BTreeMap<String, usize>,
+    /// Inline, reference, wiki, and automatic links.
+    pub links: usize,
+    /// Images, figures included.
+    pub images: usize,
+    /// Footnote definitions and inline footnotes.
+    pub footnotes: usize,
+    /// The widest line of the source.
+    pub longest_line: LongestLine,
+}
+
+/// Where the widest line is and how wide it is.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct LongestLine {
+    /// Line number (1-indexed); the first one wins a tie.
+    pub line: usize,
+    /// Display width in columns.
+    pub width: usize,
+}
+
+/// Compute the metrics of `input`, which `tree` was parsed from.
+pub fn compute(tree: &SyntaxNode, input: &str, config: &Config) -> DocumentStats {
+    let mut stats = DocumentStats {
+        words: count_words(tree),
+        code_cells: collect_code_blocks_by_language_key(tree, input, config)
+            .into_iter()
+            .map(|(language, blocks)| (language, blocks.len()))
+            .collect(),
+        longest_line: longest_line(input),
+        ..Default::default()
+    };
+
+    for node in tree.descendants() {
+        match node.kind() {
+            SyntaxKind::HEADING => {
+                if let Some(heading) = Heading::cast(node) {
+                    let level = heading.level().clamp(1, 6);
+                    stats.headings[level - 1] += 1;
+                }
+            }
+            SyntaxKind::LINK | SyntaxKind::AUTO_LINK | SyntaxKind::WIKI_LINK => stats.links += 1,
+            SyntaxKind::IMAGE_LINK | SyntaxKind::IMAGE_WIKI_LINK => stats.images += 1,
+            SyntaxKind::FOOTNOTE_DEFINITION | SyntaxKind::INLINE_FOOTNOTE => stats.footnotes += 1,
+            _ => {}
+        }
+    }
+    stats
+}
+
+/// Count words in the prose of `tree`. A word is a whitespace-separated run
+/// with at least one letter or digit, so table pipes and stray punctuation do
+/// not count. Emphasis markers join their neighbours: `*em*phasis` is one word.
+fn count_words(tree: &SyntaxNode) -> usize {
+    let mut text = String::new();
+    let mut walk = tree.preorder_with_tokens();
+    while let Some(event) = walk.next() {
+        let WalkEvent::Enter(element) = event else {
+            continue;
+        };
+        match element {
+            NodeOrToken::Node(node) => {
+                if is_excluded(node.kind()) {
+                    walk.skip_subtree();
+                    text.push(' ');
+                }
+            }
+            NodeOrToken::Token(token) => match token.kind() {
+                SyntaxKind::TEXT | SyntaxKind::ESCAPED_CHAR => text.push_str(token.text()),
+                SyntaxKind::BACKSLASH
+                | SyntaxKind::EMPHASIS_MARKER
+                | SyntaxKind::STRONG_MARKER
+                | SyntaxKind::STRIKEOUT_MARKER
+                | SyntaxKind::MARK_MARKER
+                | SyntaxKind::SUPERSCRIPT_MARKER
+                | SyntaxKind::SUBSCRIPT_MARKER => {}
+                _ => text.push(' '),
+            },
+        }
+    }
+    text.split_whitespace()
+        .filter(|word| word.chars().any(char::is_alphanumeric))
+        .count()
+}
+
+/// Subtrees whose text is not prose.
+fn is_excluded(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::YAML_METADATA
+            | SyntaxKind::PANDOC_TITLE_BLOCK
+            | SyntaxKind::MMD_TITLE_BLOCK
+            | SyntaxKind::CODE_BLOCK
+            | SyntaxKind::HTML_BLOCK
+            | SyntaxKind::HTML_BLOCK_RAW
+            | SyntaxKind::HTML_BLOCK_TAG
+            | SyntaxKind::TEX_BLOCK
+            | SyntaxKind::COMMENT
+            | SyntaxKind::DIV_FENCE_OPEN
+            | SyntaxKind::ATTRIBUTE
+            | SyntaxKind::SPAN_ATTRIBUTES
+            | SyntaxKind::TABLE_SEPARATOR
+            | SyntaxKind::INLINE_CODE
+            | SyntaxKind::INLINE_EXEC
+            | SyntaxKind::RAW_INLINE
+            | SyntaxKind::INLINE_MATH
+            | SyntaxKind::DISPLAY_MATH
+            | SyntaxKind::INLINE_HTML
+            | SyntaxKind::LATEX_COMMAND
+            | SyntaxKind::LINK_DEST
+            | SyntaxKind::LINK_REF
+            | SyntaxKind::AUTO_LINK
+            | SyntaxKind::REFERENCE_DEFINITION
+            | SyntaxKind::SHORTCODE
+            | SyntaxKind::EMOJI
+            | SyntaxKind::OBSIDIAN_COMMENT
+            | SyntaxKind::MYST_DIRECTIVE_OPEN
+            | SyntaxKind::MYST_DIRECTIVE_OPTION
+            | SyntaxKind::MYST_DIRECTIVE_BODY
+            | SyntaxKind::MYST_ROLE
+            | SyntaxKind::MYST_TARGET
+            | SyntaxKind::MYST_COMMENT
+            | SyntaxKind::MDX_ESM
+            | SyntaxKind::MDX_FLOW
+            | SyntaxKind::MDX_JSX
+            | SyntaxKind::MDX_EXPRESSION
+            | SyntaxKind::SVELTE_BLOCK
+            | SyntaxKind::SVELTE_BLOCK_LOGIC
+            | SyntaxKind::SVELTE_TAG
+            | SyntaxKind::SVELTE_EXPRESSION
+    )
+}
+
+fn longest_line(input: &str) -> LongestLine {
+    let mut longest = LongestLine::default();
+    for (idx, line) in input.lines().enumerate() {
+        let width = line.trim_end_matches('\r').width();
+        if width > longest.width {
+            longest = LongestLine {
+                line: idx + 1,
+                width,
+            };
+        }
+    }
+    longest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Extensions, Flavor};
+
+    fn stats(input: &str) -> DocumentStats {
+        let config = Config {
+            flavor: Flavor::Quarto,
+            extensions: Extensions::for_flavor(Flavor::Quarto),
+            ..Default::default()
+        };
+        let tree = crate::parser::parse(input, Some(config.clone()));
+        compute(&tree, input, &config)
+    }
+
+    #[test]
+    fn counts_prose_words_only() {
+        let input = "---\ntitle: Not counted\n---\n\n# Intro\n\nSome *em*phasis and `code`, with $x^2$ and a [link](https://example.com).\n\n```{r}\nx <- 1\n```\n";
+        // Intro, Some, emphasis, and, with, and, a, link
+        assert_eq!(stats(input).words, 8);
+    }
+
+    #[test]
+    fn counts_structure() {
+        let input = "# One\n\n## Two\n\n## Three\n\nSee [a](a.qmd) and <https://x.org>.[^1]\n\n![Figure](plot.png)\n\n[^1]: A note.\n\n```{r}\n1\n```\n\n```py\n2\n```\n\n```python\n3\n```\n";
+        let stats = stats(input);
+        assert_eq!(stats.headings, [1, 2, 0, 0, 0, 0]);
+        assert_eq!(stats.links, 2);
+        assert_eq!(stats.images, 1);
+        assert_eq!(stats.footnotes, 1);
+        assert_eq!(stats.code_cells.get("python"), Some(&2));
+        assert_eq!(stats.code_cells.get("r"), Some(&1));
+    }
+
+    #[test]
+    fn longest_line_is_the_first_widest() {
+        let longest = stats("ab\r\nabcd\nwxyz\n").longest_line;
+        assert_eq!(longest, LongestLine { line: 2, width: 4 });
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 pub use panache_parser::syntax::*;
 use panache_parser::syntax::{
     SyntaxElement as ParserSyntaxElement, SyntaxNode as ParserSyntaxNode,
@@ -10,17 +12,37 @@ struct JsonRange {
     end: u32,
 }
 
+/// Zero-based line/column point. `column` counts UTF-8 bytes from the line
+/// start; `column_utf16` counts UTF-16 code units, matching LSP positions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct JsonPoint {
+    pub line: u32,
+    pub column: u32,
+    pub column_utf16: u32,
+}
+
+/// Start and end points of a range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct JsonPosition {
+    pub start: JsonPoint,
+    pub end: JsonPoint,
+}
+
 #[derive(Debug, serde::Serialize)]
 pub struct JsonToken {
+    id: u32,
     kind: String,
     range: JsonRange,
+    position: JsonPosition,
     text: String,
 }
 
 #[derive(Debug, serde::Serialize)]
 pub struct JsonNode {
+    id: u32,
     kind: String,
     range: JsonRange,
+    position: JsonPosition,
     children: Vec<JsonElement>,
 }
 
@@ -31,33 +53,523 @@ pub enum JsonElement {
     Token(JsonToken),
 }
 
-fn range_to_json(range: rowan::TextRange) -> JsonRange {
-    JsonRange {
-        start: u32::from(range.start()),
-        end: u32::from(range.end()),
+/// One step of a [`node_path_at_offset`] lookup. `id` matches the `id` of the
+/// same element in [`cst_to_json`] output for the same root.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct JsonPathEntry {
+    pub id: u32,
+    pub kind: String,
+    pub start: u32,
+    pub end: u32,
+}
+
+/// Column unit used when looking up a node path by line/column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnEncoding {
+    Utf8,
+    Utf16,
+}
+
+/// Line start offsets of a document, for byte offset <-> line/column mapping.
+struct LineIndex<'a> {
+    text: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    fn new(text: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(idx, _)| idx + 1))
+            .collect();
+        Self { text, line_starts }
+    }
+
+    fn point(&self, offset: usize) -> JsonPoint {
+        let offset = offset.min(self.text.len());
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let line_start = self.line_starts[line];
+        JsonPoint {
+            line: line as u32,
+            column: (offset - line_start) as u32,
+            column_utf16: self.text[line_start..offset].encode_utf16().count() as u32,
+        }
+    }
+
+    fn offset(&self, line: u32, column: u32, encoding: ColumnEncoding) -> Option<usize> {
+        let line_start = *self.line_starts.get(line as usize)?;
+        let line_end = self
+            .line_starts
+            .get(line as usize + 1)
+            .copied()
+            .unwrap_or(self.text.len());
+        let line_text = &self.text[line_start..line_end];
+        match encoding {
+            ColumnEncoding::Utf8 => {
+                let column = column as usize;
+                (column <= line_text.len() && line_text.is_char_boundary(column))
+                    .then_some(line_start + column)
+            }
+            ColumnEncoding::Utf16 => {
+                let mut units = 0u32;
+                for (idx, ch) in line_text.char_indices() {
+                    if units >= column {
+                        return (units == column).then_some(line_start + idx);
+                    }
+                    units += ch.len_utf16() as u32;
+                }
+                (units == column).then_some(line_end)
+            }
+        }
     }
 }
 
-fn token_to_json(token: ParserSyntaxToken) -> JsonToken {
-    JsonToken {
-        kind: format!("{:?}", token.kind()),
-        range: range_to_json(token.text_range()),
-        text: token.text().to_string(),
+struct JsonBuilder<'a> {
+    index: LineIndex<'a>,
+    next_id: u32,
+}
+
+impl JsonBuilder<'_> {
+    fn range(&self, range: rowan::TextRange) -> (JsonRange, JsonPosition) {
+        let (start, end) = (u32::from(range.start()), u32::from(range.end()));
+        (
+            JsonRange { start, end },
+            JsonPosition {
+                start: self.index.point(start as usize),
+                end: self.index.point(end as usize),
+            },
+        )
+    }
+
+    fn token(&mut self, token: ParserSyntaxToken) -> JsonToken {
+        let id = self.next_id;
+        self.next_id += 1;
+        let (range, position) = self.range(token.text_range());
+        JsonToken {
+            id,
+            kind: format!("{:?}", token.kind()),
+            range,
+            position,
+            text: token.text().to_string(),
+        }
     }
+
+    fn element(&mut self, element: ParserSyntaxElement) -> JsonElement {
+        match element {
+            rowan::NodeOrToken::Node(node) => JsonElement::Node(self.node(&node)),
+            rowan::NodeOrToken::Token(token) => JsonElement::Token(self.token(token)),
+        }
+    }
+
+    fn node(&mut self, node: &ParserSyntaxNode) -> JsonNode {
+        let id = self.next_id;
+        self.next_id += 1;
+        let (range, position) = self.range(node.text_range());
+        let children: Vec<JsonElement> = node
+            .children_with_tokens()
+            .map(|element| self.element(element))
+            .collect();
+        JsonNode {
+            id,
+            kind: format!("{:?}", node.kind()),
+            range,
+            position,
+            children,
+        }
+    }
+}
+
+fn root_text(node: &ParserSyntaxNode) -> String {
+    node.ancestors()
+        .last()
+        .unwrap_or_else(|| node.clone())
+        .text()
+        .to_string()
+}
+
+/// Serialize a CST subtree for downstream tooling.
+///
+/// Every node and token carries an `id` (pre-order index, `0` for `node`
+/// itself), its byte `range`, and a line/column `position` with both UTF-8
+/// and UTF-16 columns. IDs are a pure function of the tree shape, so parsing
+/// the same input with the same config always yields the same IDs.
+pub fn cst_to_json(node: &ParserSyntaxNode) -> JsonNode {
+    let text = root_text(node);
+    let mut builder = JsonBuilder {
+        index: LineIndex::new(&text),
+        next_id: 0,
+    };
+    builder.node(node)
 }
 
-fn element_to_json(element: ParserSyntaxElement) -> JsonElement {
+/// Path from `node` down to the innermost element covering byte `offset`,
+/// using the same IDs as [`cst_to_json`]. The last entry is the token at the
+/// offset (right-biased at boundaries). Empty when `offset` is out of range.
+pub fn node_path_at_offset(node: &ParserSyntaxNode, offset: u32) -> Vec<JsonPathEntry> {
+    let range = node.text_range();
+    if offset < u32::from(range.start()) || offset > u32::from(range.end()) {
+        return Vec::new();
+    }
+
+    let entry = |id: u32, element: &ParserSyntaxElement| JsonPathEntry {
+        id,
+        kind: format!("{:?}", element.kind()),
+        start: u32::from(element.text_range().start()),
+        end: u32::from(element.text_range().end()),
+    };
+
+    let mut path = vec![entry(0, &node.clone().into())];
+    let mut current = node.clone();
+    let mut current_id = 0u32;
+    loop {
+        let mut next_id = current_id + 1;
+        let mut found = None;
+        for child in current.children_with_tokens() {
+            let child_range = child.text_range();
+            let covers = child_range.contains(offset.into())
+                || (u32::from(child_range.end()) == offset && offset == u32::from(range.end()));
+            if covers {
+                found = Some(child);
+                break;
+            }
+            next_id += subtree_size(&child);
+        }
+        let Some(child) = found else {
+            break;
+        };
+        path.push(entry(next_id, &child));
+        match child {
+            rowan::NodeOrToken::Node(child) => {
+                current = child;
+                current_id = next_id;
+            }
+            rowan::NodeOrToken::Token(_) => break,
+        }
+    }
+    path
+}
+
+/// Like [`node_path_at_offset`], addressing the position by zero-based line
+/// and column. Returns `None` when the position is not inside the document or
+/// splits a character.
+pub fn node_path_at_position(
+    node: &ParserSyntaxNode,
+    line: u32,
+    column: u32,
+    encoding: ColumnEncoding,
+) -> Option<Vec<JsonPathEntry>> {
+    let text = root_text(node);
+    let offset = LineIndex::new(&text).offset(line, column, encoding)?;
+    Some(node_path_at_offset(node, offset as u32))
+}
+
+fn subtree_size(element: &ParserSyntaxElement) -> u32 {
     match element {
-        rowan::NodeOrToken::Node(node) => JsonElement::Node(cst_to_json(&node)),
-        rowan::NodeOrToken::Token(token) => JsonElement::Token(token_to_json(token)),
+        rowan::NodeOrToken::Node(node) => node.descendants_with_tokens().count() as u32,
+        rowan::NodeOrToken::Token(_) => 1,
     }
 }
 
-pub fn cst_to_json(node: &ParserSyntaxNode) -> JsonNode {
-    let children: Vec<JsonElement> = node.children_with_tokens().map(element_to_json).collect();
-    JsonNode {
-        kind: format!("{:?}", node.kind()),
-        range: range_to_json(node.text_range()),
-        children,
+/// Construct a [`CollectedLink`] was found in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LinkKind {
+    /// `[text](dest)`.
+    Inline,
+    /// `[text][label]`, `[label][]`, or `[label]`.
+    Reference,
+    /// `<https://example.com>`.
+    Autolink,
+    /// `![alt](src)` or `![alt][label]`.
+    Image,
+    /// `{{< include path >}}`.
+    Include,
+    /// `[label]: dest`.
+    Definition,
+}
+
+/// A link destination found by [`collect_links`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct CollectedLink {
+    pub kind: LinkKind,
+    /// Destination as written: the first token of an inline destination with
+    /// `<…>` stripped, the autolink target, or the include path. Empty for
+    /// reference links and images, which name a `label` instead.
+    pub raw: String,
+    /// Reference label, for reference links, reference images, and
+    /// definitions.
+    pub label: Option<String>,
+    /// Where the link points: `raw` for direct destinations, the matching
+    /// definition's destination for references, `None` when no definition
+    /// in the document matches the label.
+    pub target: Option<String>,
+    /// Byte range of the destination, reference label, or (for autolinks and
+    /// includes) the whole construct.
+    pub start: u32,
+    pub end: u32,
+    pub position: JsonPosition,
+}
+
+impl CollectedLink {
+    pub fn range(&self) -> rowan::TextRange {
+        rowan::TextRange::new(self.start.into(), self.end.into())
+    }
+}
+
+/// First whitespace-separated token of a link destination, with `<…>`
+/// brackets stripped (`<a b.md> "Title"` -> `a b.md`).
+pub(crate) fn extract_first_destination_token(raw_dest: &str) -> &str {
+    let trimmed = raw_dest.trim();
+    if let Some(rest) = trimmed.strip_prefix('<')
+        && let Some(end) = rest.find('>')
+    {
+        return &rest[..end];
+    }
+
+    trimmed.split_whitespace().next().unwrap_or("")
+}
+
+/// Collect every link destination under `node`, in document order.
+///
+/// Covers inline and reference links, autolinks, images, Quarto
+/// `include` shortcodes, and reference definitions. References are
+/// resolved against the definitions in the same tree (first definition
+/// wins, labels compared case-insensitively); include paths are reported as
+/// written, since resolving them needs the document's location on disk.
+pub fn collect_links(node: &ParserSyntaxNode) -> Vec<CollectedLink> {
+    let text = root_text(node);
+    let index = LineIndex::new(&text);
+
+    let mut definitions: HashMap<String, String> = HashMap::new();
+    for def in node.descendants().filter_map(ReferenceDefinition::cast) {
+        if let Some(url) = definition_url(&def) {
+            definitions
+                .entry(crate::utils::normalize_label(&def.label()))
+                .or_insert(url.0);
+        }
+    }
+
+    let mut links = Vec::new();
+    let mut push = |kind, range: rowan::TextRange, raw: String, label: Option<String>| {
+        let target = match &label {
+            Some(label) if raw.is_empty() => definitions
+                .get(&crate::utils::normalize_label(label))
+                .cloned(),
+            _ => Some(raw.clone()),
+        };
+        let (start, end) = (u32::from(range.start()), u32::from(range.end()));
+        links.push(CollectedLink {
+            kind,
+            raw,
+            label,
+            target,
+            start,
+            end,
+            position: JsonPosition {
+                start: index.point(start as usize),
+                end: index.point(end as usize),
+            },
+        });
+    };
+
+    for node in node.descendants() {
+        if node
+            .ancestors()
+            .skip(1)
+            .any(|ancestor| ReferenceDefinition::can_cast(ancestor.kind()))
+        {
+            continue;
+        }
+
+        if let Some(link) = Link::cast(node.clone()) {
+            if let Some(dest) = link.dest() {
+                let raw = extract_first_destination_token(&dest.url_content()).to_string();
+                push(LinkKind::Inline, dest.syntax().text_range(), raw, None);
+            } else if let Some(link_ref) = link.reference() {
+                let mut label = link_ref.label();
+                if label.is_empty()
+                    && let Some(text) = link.text()
+                {
+                    label = text.raw_label();
+                }
+                let range = link_ref.syntax().text_range();
+                push(LinkKind::Reference, range, String::new(), Some(label));
+            } else if let Some(text) = link.text() {
+                let range = text.syntax().text_range();
+                push(
+                    LinkKind::Reference,
+                    range,
+                    String::new(),
+                    Some(text.raw_label()),
+                );
+            }
+        } else if let Some(image) = ImageLink::cast(node.clone()) {
+            if let Some(dest) = image.dest() {
+                let raw = extract_first_destination_token(&dest.url_content()).to_string();
+                push(LinkKind::Image, dest.syntax().text_range(), raw, None);
+            } else if let Some(link_ref) = image.reference() {
+                let mut label = link_ref.label();
+                if label.is_empty()
+                    && let Some(alt) = image.alt()
+                {
+                    label = alt.syntax().text().to_string();
+                }
+                let range = link_ref.syntax().text_range();
+                push(LinkKind::Image, range, String::new(), Some(label));
+            }
+        } else if let Some(autolink) = AutoLink::cast(node.clone()) {
+            push(
+                LinkKind::Autolink,
+                autolink.syntax().text_range(),
+                autolink.target(),
+                None,
+            );
+        } else if let Some(shortcode) = Shortcode::cast(node.clone()) {
+            if shortcode.is_escaped() || shortcode.name().as_deref() != Some("include") {
+                continue;
+            }
+            if let Some(path) = shortcode.args().get(1) {
+                let range = shortcode.syntax().text_range();
+                push(LinkKind::Include, range, path.clone(), None);
+            }
+        } else if let Some(def) = ReferenceDefinition::cast(node)
+            && let Some((url, range)) = definition_url(&def)
+        {
+            push(LinkKind::Definition, range, url, Some(def.label()));
+        }
+    }
+
+    links
+}
+
+/// Destination of a reference definition and the range of its URL node.
+fn definition_url(def: &ReferenceDefinition) -> Option<(String, rowan::TextRange)> {
+    let url = def
+        .syntax()
+        .children()
+        .find(|child| child.kind() == SyntaxKind::REFERENCE_URL)?;
+    let text = url.text().to_string();
+    Some((
+        extract_first_destination_token(&text).to_string(),
+        url.text_range(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect_ids(node: &JsonNode, out: &mut Vec<(u32, String, u32, u32)>) {
+        out.push((node.id, node.kind.clone(), node.range.start, node.range.end));
+        for child in &node.children {
+            match child {
+                JsonElement::Node(child) => collect_ids(child, out),
+                JsonElement::Token(token) => out.push((
+                    token.id,
+                    token.kind.clone(),
+                    token.range.start,
+                    token.range.end,
+                )),
+            }
+        }
+    }
+
+    #[test]
+    fn json_positions_report_utf8_and_utf16_columns() {
+        let tree = crate::parse("é😀 *x*\n", None);
+        let json = cst_to_json(&tree);
+        let mut ids = Vec::new();
+        collect_ids(&json, &mut ids);
+        assert_eq!(
+            ids.iter().map(|entry| entry.0).collect::<Vec<_>>(),
+            (0..ids.len() as u32).collect::<Vec<_>>()
+        );
+
+        let emphasis = tree
+            .descendants()
+            .find(|node| node.kind() == SyntaxKind::EMPHASIS)
+            .unwrap();
+        let json = cst_to_json(&emphasis);
+        assert_eq!(
+            json.position.start,
+            JsonPoint {
+                line: 0,
+                column: 7,
+                column_utf16: 4,
+            }
+        );
+    }
+
+    #[test]
+    fn node_path_ids_match_json_ids() {
+        let input = "# Head\n\nSome *emph* text.\n";
+        let tree = crate::parse(input, None);
+        let mut ids = Vec::new();
+        collect_ids(&cst_to_json(&tree), &mut ids);
+
+        let offset = input.find("emph").unwrap() as u32;
+        let path = node_path_at_offset(&tree, offset);
+        let kinds: Vec<&str> = path.iter().map(|entry| entry.kind.as_str()).collect();
+        assert_eq!(kinds.first(), Some(&"DOCUMENT"));
+        assert!(kinds.contains(&"EMPHASIS"), "{kinds:?}");
+        for entry in &path {
+            assert!(
+                ids.contains(&(entry.id, entry.kind.clone(), entry.start, entry.end)),
+                "{entry:?} not in JSON export"
+            );
+        }
+    }
+
+    #[test]
+    fn node_path_at_position_handles_utf16_columns() {
+        let input = "😀 *x*\n";
+        let tree = crate::parse(input, None);
+        let utf16 = node_path_at_position(&tree, 0, 4, ColumnEncoding::Utf16).unwrap();
+        let utf8 = node_path_at_position(&tree, 0, 6, ColumnEncoding::Utf8).unwrap();
+        assert_eq!(utf16, utf8);
+        assert_eq!(utf16.last().unwrap().kind, "TEXT");
+        assert!(node_path_at_position(&tree, 0, 1, ColumnEncoding::Utf16).is_none());
+        assert!(node_path_at_position(&tree, 5, 0, ColumnEncoding::Utf8).is_none());
+    }
+
+    #[test]
+    fn collect_links_resolves_references_and_reports_positions() {
+        let input = "See [docs](<guide/a b.qmd> \"Title\"), [Ref][x], and <https://e.org>.\n\n\
+                     ![plot](img/p.png)\n\n[X]: https://x.org\n";
+        let tree = crate::parse(input, None);
+        let links = collect_links(&tree);
+        let summary: Vec<_> = links
+            .iter()
+            .map(|link| (link.kind, link.raw.as_str(), link.target.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (LinkKind::Inline, "guide/a b.qmd", Some("guide/a b.qmd")),
+                (LinkKind::Reference, "", Some("https://x.org")),
+                (LinkKind::Autolink, "https://e.org", Some("https://e.org")),
+                (LinkKind::Image, "img/p.png", Some("img/p.png")),
+                (LinkKind::Definition, "https://x.org", Some("https://x.org")),
+            ]
+        );
+
+        let image = &links[3];
+        assert_eq!(&input[image.range()], "img/p.png");
+        assert_eq!(image.position.start.line, 2);
+        assert_eq!(image.position.start.column, 8);
+    }
+
+    #[test]
+    fn collect_links_reports_include_shortcodes() {
+        let config = crate::Config {
+            flavor: crate::config::Flavor::Quarto,
+            extensions: crate::config::Extensions::for_flavor(crate::config::Flavor::Quarto),
+            ..Default::default()
+        };
+        let input = "{{< include _part.qmd >}}\n\n{{{< include _skip.qmd >}}}\n";
+        let tree = crate::parse(input, Some(config));
+        let links = collect_links(&tree);
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].kind, LinkKind::Include);
+        assert_eq!(links[0].raw, "_part.qmd");
     }
 }
@@ -0,0 +1,179 @@
+//! Terminal styling for CLI output.
+//!
+//! Whether to color is decided once at startup from `--color`, `--no-color`,
+//! `NO_COLOR`, `TERM`, and whether stdout is a terminal, then installed as
+//! [`anstream`]'s global choice. Output written through `anstream::println!`
+//! and friends has its ANSI styling stripped when color is off, and
+//! translated to console API calls on Windows consoles without VT support.
+
+use std::fmt;
+use std::io::{self, IsTerminal};
+
+use anstyle::{AnsiColor, Style};
+
+use crate::cli::ColorMode;
+
+pub(crate) const ERROR: Style = AnsiColor::Red.on_default().bold();
+pub(crate) const WARNING: Style = AnsiColor::Yellow.on_default().bold();
+pub(crate) const INFO: Style = AnsiColor::Cyan.on_default().bold();
+pub(crate) const REMOVED: Style = AnsiColor::Red.on_default();
+pub(crate) const ADDED: Style = AnsiColor::Green.on_default();
+pub(crate) const HEADER: Style = Style::new().bold();
+pub(crate) const PATH: Style = Style::new().bold().underline();
+pub(crate) const DIM: Style = Style::new().dimmed();
+
+/// Resolve the color choice for this process and install it for `anstream`.
+/// Returns whether color is enabled.
+pub(crate) fn init(mode: ColorMode, no_color: bool) -> bool {
+    let use_color = resolve_color(
+        mode,
+        no_color,
+        std::env::var_os("NO_COLOR").is_some(),
+        std::env::var_os("TERM").as_deref(),
+        io::stdout().is_terminal(),
+    );
+    let choice = if use_color {
+        anstream::ColorChoice::Always
+    } else {
+        anstream::ColorChoice::Never
+    };
+    choice.write_global();
+    use_color
+}
+
+pub(crate) fn resolve_color(
+    mode: ColorMode,
+    no_color_flag: bool,
+    no_color_env: bool,
+    term_env: Option<&std::ffi::OsStr>,
+    stdout_is_terminal: bool,
+) -> bool {
+    if no_color_flag {
+        return false;
+    }
+    match mode {
+        ColorMode::Always => true,
+        ColorMode::Never => false,
+        ColorMode::Auto => {
+            if no_color_env {
+                return false;
+            }
+            match term_env {
+                Some(term) if term == "dumb" => return false,
+                // Windows consoles don't set TERM.
+                None if !cfg!(windows) => return false,
+                _ => {}
+            }
+            stdout_is_terminal
+        }
+    }
+}
+
+/// `value` wrapped in `style`; plain text once `anstream` strips it.
+pub(crate) fn paint<T: fmt::Display>(style: Style, value: T) -> Painted<T> {
+    Painted { style, value }
+}
+
+pub(crate) struct Painted<T> {
+    style: Style,
+    value: T,
+}
+
+impl<T: fmt::Display> fmt::Display for Painted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}{:#}", self.style, self.value, self.style)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsStr;
+
+    #[test]
+    fn auto_disables_color_when_term_is_dumb() {
+        assert!(!resolve_color(
+            ColorMode::Auto,
+            false,
+            false,
+            Some(OsStr::new("dumb")),
+            true,
+        ));
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn auto_disables_color_when_term_is_unset() {
+        assert!(!resolve_color(ColorMode::Auto, false, false, None, true));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn auto_enables_color_on_windows_console_without_term() {
+        assert!(resolve_color(ColorMode::Auto, false, false, None, true));
+    }
+
+    #[test]
+    fn auto_enables_color_on_tty_with_real_term() {
+        assert!(resolve_color(
+            ColorMode::Auto,
+            false,
+            false,
+            Some(OsStr::new("xterm-256color")),
+            true,
+        ));
+    }
+
+    #[test]
+    fn auto_disables_color_when_not_a_tty() {
+        assert!(!resolve_color(
+            ColorMode::Auto,
+            false,
+            false,
+            Some(OsStr::new("xterm-256color")),
+            false,
+        ));
+    }
+
+    #[test]
+    fn auto_respects_no_color_env() {
+        assert!(!resolve_color(
+            ColorMode::Auto,
+            false,
+            true,
+            Some(OsStr::new("xterm-256color")),
+            true,
+        ));
+    }
+
+    #[test]
+    fn always_overrides_dumb_term() {
+        assert!(resolve_color(
+            ColorMode::Always,
+            false,
+            false,
+            Some(OsStr::new("dumb")),
+            false,
+        ));
+    }
+
+    #[test]
+    fn no_color_flag_overrides_always() {
+        assert!(!resolve_color(
+            ColorMode::Always,
+            true,
+            false,
+            Some(OsStr::new("xterm-256color")),
+            true,
+        ));
+    }
+
+    #[test]
+    fn painted_wraps_value_in_style_codes() {
+        assert_eq!(
+            paint(ADDED, "+line").to_string(),
+            "\u{1b}[32m+line\u{1b}[0m"
+        );
+        assert_eq!(paint(Style::new(), "plain").to_string(), "plain");
+    }
+}
@@ -0,0 +1,122 @@
+//! Per-phase timing of a format run.
+//!
+//! The parser and formatter mark their phases with `tracing` spans:
+//! `block_parse`, `inline_parse` (inside `block_parse`, since inlines are
+//! parsed in the same pass), `external_formatters`, and `format`. [`record`]
+//! runs a closure under a subscriber that adds up the time spent in each, for
+//! `panache format --timings` and the LSP's verbose trace. Any other
+//! `tracing` subscriber sees the same spans.
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tracing::Subscriber;
+use tracing::span::Id;
+use tracing_subscriber::Registry;
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+
+/// Time spent in each phase. A phase re-entered from inside itself (the
+/// formatter re-parsing a snippet inside `format`, say) is counted once, by
+/// its outermost span.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PhaseTimings {
+    /// Block parsing, including the inline parsing it drives.
+    pub block_parse: Duration,
+    /// The part of [`Self::block_parse`] spent parsing inlines.
+    pub inline_parse: Duration,
+    /// Waiting on external code formatters.
+    pub external_formatters: Duration,
+    /// Rendering the formatted document.
+    pub format: Duration,
+}
+
+impl PhaseTimings {
+    fn phase_mut(&mut self, name: &str) -> Option<&mut Duration> {
+        match name {
+            "block_parse" => Some(&mut self.block_parse),
+            "inline_parse" => Some(&mut self.inline_parse),
+            "external_formatters" => Some(&mut self.external_formatters),
+            "format" => Some(&mut self.format),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for PhaseTimings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "parse {:.2?} (inline {:.2?}), external formatters {:.2?}, format {:.2?}",
+            self.block_parse, self.inline_parse, self.external_formatters, self.format
+        )
+    }
+}
+
+/// Run `f` on this thread and return its result with the time it spent in
+/// each phase. Work `f` hands to other threads counts only while this thread
+/// waits on it inside a phase.
+pub fn record<T>(f: impl FnOnce() -> T) -> (T, PhaseTimings) {
+    let totals = Arc::new(Mutex::new(PhaseTimings::default()));
+    let subscriber = Registry::default().with(PhaseTimer {
+        totals: Arc::clone(&totals),
+    });
+    let value = tracing::subscriber::with_default(subscriber, f);
+    let timings = *totals.lock().unwrap();
+    (value, timings)
+}
+
+struct PhaseTimer {
+    totals: Arc<Mutex<PhaseTimings>>,
+}
+
+/// When the outermost span of a phase was entered.
+struct Entered(Instant);
+
+impl<S> Layer<S> for PhaseTimer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let name = span.name();
+        if PhaseTimings::default().phase_mut(name).is_none()
+            || span.scope().skip(1).any(|outer| outer.name() == name)
+        {
+            return;
+        }
+        span.extensions_mut().replace(Entered(Instant::now()));
+    }
+
+    fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let Some(Entered(start)) = span.extensions_mut().remove::<Entered>() else {
+            return;
+        };
+        if let Some(total) = self.totals.lock().unwrap().phase_mut(span.name()) {
+            *total += start.elapsed();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_parse_and_format_phases() {
+        let input = "# Title\n\nSome *emphasis* and `code`.\n".repeat(50);
+        let (output, timings) = record(|| crate::format(&input, None, None));
+        assert!(output.starts_with("# Title\n"));
+        assert!(timings.block_parse > Duration::ZERO);
+        assert!(timings.inline_parse > Duration::ZERO);
+        assert!(timings.inline_parse <= timings.block_parse);
+        assert!(timings.format > Duration::ZERO);
+        assert_eq!(timings.external_formatters, Duration::ZERO);
+    }
+}
@@ -0,0 +1,350 @@
+//! Workspace trust for repo-local configuration.
+//!
+//! A `panache.toml` checked into a repository can configure external
+//! `[formatters]` and `[linters]`, which panache then spawns. In safe mode
+//! (`--safe`, the default for the language server), those commands only run
+//! once the user has approved the config; until then they are dropped from the
+//! loaded [`Config`] and everything else (style, lint rules, extensions) still
+//! applies.
+//!
+//! Approvals are persisted in `trusted-configs.json` under panache's state
+//! directory (`$XDG_STATE_HOME/panache` on Linux, overridable with
+//! `PANACHE_STATE_HOME`). Each entry records the config path together with a
+//! fingerprint of its external commands, so editing the commands requires a
+//! fresh approval. Only configs discovered by walking up from the input are
+//! subject to trust: an explicit `--config` and the user-level config are the
+//! user's own choice.
+
+use std::collections::{BTreeMap, HashSet};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{Config, ConfigSource};
+use crate::external_tools_common::log_warning_once;
+
+const TRUST_FILE_NAME: &str = "trusted-configs.json";
+
+static SAFE_MODE: OnceLock<bool> = OnceLock::new();
+static BLOCKED_CONFIGS: OnceLock<Mutex<HashSet<PathBuf>>> = OnceLock::new();
+
+/// Set the process-wide safe-mode policy. Idempotent — the first caller wins.
+pub fn set_safe_mode(enabled: bool) {
+    let _ = SAFE_MODE.set(enabled);
+}
+
+/// Whether safe mode is active, falling back to `default` when
+/// [`set_safe_mode`] was never called (the CLI defaults to off, the LSP to on).
+pub fn safe_mode_enabled(default: bool) -> bool {
+    SAFE_MODE.get().copied().unwrap_or(default)
+}
+
+/// Result of checking a loaded config against the trust store.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrustStatus {
+    /// The config configures no external commands, or was not discovered from
+    /// the project (explicit `--config`, user config, defaults).
+    NotRequired,
+    /// The config's current external commands have been approved.
+    Trusted,
+    /// The discovered config at this path has unapproved external commands.
+    Untrusted(PathBuf),
+}
+
+/// Check whether the external commands in `cfg` may run.
+pub fn check(cfg: &Config, source: &ConfigSource) -> TrustStatus {
+    let ConfigSource::Discovered(path) = source else {
+        return TrustStatus::NotRequired;
+    };
+    if !has_external_commands(cfg) {
+        return TrustStatus::NotRequired;
+    }
+    if TrustStore::load().is_trusted(path, cfg) {
+        TrustStatus::Trusted
+    } else {
+        TrustStatus::Untrusted(path.clone())
+    }
+}
+
+/// Check `cfg` and, when it is untrusted, drop its external formatters and
+/// linters. Blocked paths are recorded (see [`was_blocked`]) and warned about
+/// once per process.
+pub fn enforce(cfg: &mut Config, source: &ConfigSource) -> TrustStatus {
+    let status = check(cfg, source);
+    if let TrustStatus::Untrusted(path) = &status {
+        block(cfg, path);
+    }
+    status
+}
+
+/// Drop the external commands of the untrusted config at `path` from `cfg`.
+pub fn block(cfg: &mut Config, path: &Path) {
+    cfg.formatters.clear();
+    cfg.linters.clear();
+
+    BLOCKED_CONFIGS
+        .get_or_init(|| Mutex::new(HashSet::new()))
+        .lock()
+        .expect("blocked config mutex poisoned")
+        .insert(path.to_path_buf());
+    log_warning_once(&untrusted_message(path));
+}
+
+/// Whether the config at `path` had its external commands dropped in this
+/// process.
+pub fn was_blocked(path: &Path) -> bool {
+    BLOCKED_CONFIGS.get().is_some_and(|blocked| {
+        blocked
+            .lock()
+            .expect("blocked config mutex poisoned")
+            .contains(path)
+    })
+}
+
+/// Human-readable explanation for a blocked config, including how to approve it.
+pub fn untrusted_message(path: &Path) -> String {
+    let dir = path.parent().unwrap_or(path);
+    format!(
+        "External formatters and linters from untrusted config {} were not run. \
+         Review it and run `panache trust {}` to allow them.",
+        path.display(),
+        dir.display()
+    )
+}
+
+/// Whether `cfg` configures any external formatter or linter.
+pub fn has_external_commands(cfg: &Config) -> bool {
+    cfg.formatters
+        .values()
+        .flatten()
+        .any(|formatter| !formatter.cmd.trim().is_empty())
+        || !cfg.linters.is_empty()
+}
+
+/// Stable fingerprint of the external commands `cfg` would spawn.
+///
+/// Sorted so it does not depend on `HashMap` iteration order, and hashed with
+/// FNV-1a so it stays stable across Rust releases (unlike `DefaultHasher`).
+pub fn external_commands_fingerprint(cfg: &Config) -> String {
+    let mut entries: Vec<String> = cfg
+        .formatters
+        .iter()
+        .map(|(lang, chain)| {
+            let steps: Vec<String> = chain
+                .iter()
+                .map(|f| format!("{}\u{1}{}\u{1}{}", f.cmd, f.args.join("\u{1}"), f.stdin))
+                .collect();
+            format!("formatter\u{2}{lang}\u{2}{}", steps.join("\u{3}"))
+        })
+        .chain(
+            cfg.linters
+                .iter()
+                .map(|(lang, linter)| format!("linter\u{2}{lang}\u{2}{linter}")),
+        )
+        .collect();
+    entries.sort_unstable();
+
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in entries.join("\u{0}").bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{hash:016x}")
+}
+
+/// Location of the persisted trust store, if a state directory is available.
+pub fn trust_store_path() -> Option<PathBuf> {
+    trust_store_path_from(std::env::var_os("PANACHE_STATE_HOME"), || {
+        dirs::state_dir().or_else(dirs::data_local_dir)
+    })
+}
+
+fn trust_store_path_from<F>(
+    override_value: Option<std::ffi::OsString>,
+    system_state_dir: F,
+) -> Option<PathBuf>
+where
+    F: FnOnce() -> Option<PathBuf>,
+{
+    let base = match override_value {
+        Some(value) if !value.is_empty() => PathBuf::from(value),
+        _ => system_state_dir()?,
+    };
+    Some(base.join("panache").join(TRUST_FILE_NAME))
+}
+
+/// Approved configs, keyed by canonical config path.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TrustStore {
+    #[serde(skip)]
+    path: Option<PathBuf>,
+    /// Config path → fingerprint of the approved external commands.
+    configs: BTreeMap<String, String>,
+}
+
+impl TrustStore {
+    /// Load the store from [`trust_store_path`]. A missing or unreadable file
+    /// yields an empty store, so nothing is trusted by accident.
+    pub fn load() -> Self {
+        let Some(path) = trust_store_path() else {
+            return Self::default();
+        };
+        Self::load_from(path)
+    }
+
+    /// Load the store from an explicit file path.
+    pub fn load_from(path: PathBuf) -> Self {
+        let mut store = match std::fs::read_to_string(&path) {
+            Ok(raw) => serde_json::from_str::<Self>(&raw).unwrap_or_else(|err| {
+                log::warn!(
+                    "Ignoring unreadable trust store {}: {}",
+                    path.display(),
+                    err
+                );
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        };
+        store.path = Some(path);
+        store
+    }
+
+    /// Whether `cfg`, loaded from `config_path`, has been approved as-is.
+    pub fn is_trusted(&self, config_path: &Path, cfg: &Config) -> bool {
+        self.configs
+            .get(&store_key(config_path))
+            .is_some_and(|fingerprint| *fingerprint == external_commands_fingerprint(cfg))
+    }
+
+    /// Approve the external commands currently configured in `cfg`.
+    pub fn trust(&mut self, config_path: &Path, cfg: &Config) {
+        self.configs
+            .insert(store_key(config_path), external_commands_fingerprint(cfg));
+    }
+
+    /// Withdraw approval for `config_path`. Returns whether it was trusted.
+    pub fn revoke(&mut self, config_path: &Path) -> bool {
+        self.configs.remove(&store_key(config_path)).is_some()
+    }
+
+    /// Write the store back to disk, creating the state directory if needed.
+    pub fn save(&self) -> io::Result<()> {
+        let Some(path) = self.path.as_deref() else {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "no state directory available for the trust store",
+            ));
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        std::fs::write(path, json + "\n")
+    }
+}
+
+fn store_key(config_path: &Path) -> String {
+    config_path
+        .canonicalize()
+        .unwrap_or_else(|_| config_path.to_path_buf())
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::FormatterConfig;
+
+    fn config_with_formatter(cmd: &str) -> Config {
+        let mut cfg = Config::default();
+        cfg.formatters.insert(
+            "r".to_string(),
+            vec![FormatterConfig {
+                cmd: cmd.to_string(),
+                args: vec!["format".to_string()],
+                stdin: true,
+                timeout_ms: None,
+            }],
+        );
+        cfg
+    }
+
+    #[test]
+    fn only_discovered_configs_with_commands_need_trust() {
+        let cfg = config_with_formatter("air");
+        let path = PathBuf::from("/nonexistent/panache.toml");
+        assert_eq!(
+            check(&cfg, &ConfigSource::Explicit(path.clone())),
+            TrustStatus::NotRequired
+        );
+        assert_eq!(
+            check(&cfg, &ConfigSource::Global(path.clone())),
+            TrustStatus::NotRequired
+        );
+        assert_eq!(
+            check(&Config::default(), &ConfigSource::Discovered(path)),
+            TrustStatus::NotRequired
+        );
+    }
+
+    #[test]
+    fn fingerprint_tracks_commands_only() {
+        let base = external_commands_fingerprint(&config_with_formatter("air"));
+        assert_ne!(
+            base,
+            external_commands_fingerprint(&config_with_formatter("curl"))
+        );
+
+        let mut restyled = config_with_formatter("air");
+        restyled.line_width = 100;
+        assert_eq!(base, external_commands_fingerprint(&restyled));
+    }
+
+    #[test]
+    fn store_round_trips_and_invalidates_on_command_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("panache.toml");
+        std::fs::write(&config_path, "").unwrap();
+        let store_path = dir.path().join("state").join(TRUST_FILE_NAME);
+
+        let cfg = config_with_formatter("air");
+        let mut store = TrustStore::load_from(store_path.clone());
+        assert!(!store.is_trusted(&config_path, &cfg));
+        store.trust(&config_path, &cfg);
+        store.save().unwrap();
+
+        let reloaded = TrustStore::load_from(store_path.clone());
+        assert!(reloaded.is_trusted(&config_path, &cfg));
+        assert!(!reloaded.is_trusted(&config_path, &config_with_formatter("curl")));
+
+        let mut reloaded = reloaded;
+        assert!(reloaded.revoke(&config_path));
+        assert!(!reloaded.is_trusted(&config_path, &cfg));
+    }
+
+    #[test]
+    fn block_strips_external_commands() {
+        let mut cfg = config_with_formatter("air");
+        cfg.linters.insert("r".to_string(), "jarl".to_string());
+        let path = PathBuf::from("/nonexistent/blocked/panache.toml");
+
+        block(&mut cfg, &path);
+        assert!(!has_external_commands(&cfg));
+        assert!(was_blocked(&path));
+    }
+
+    #[test]
+    fn trust_store_path_honors_override() {
+        let path = trust_store_path_from(Some("/state".into()), || None).unwrap();
+        assert_eq!(
+            path,
+            PathBuf::from("/state")
+                .join("panache")
+                .join(TRUST_FILE_NAME)
+        );
+        assert!(trust_store_path_from(None, || None).is_none());
+    }
+}
@@ -1,4 +1,5 @@
 use crate::config::Extensions;
+use crate::slug::{SlugStyle, Slugger};
 use crate::syntax::{AstNode, Heading, SyntaxKind, SyntaxNode};
 use rowan::NodeOrToken;
 use std::collections::HashMap;
@@ -56,6 +57,29 @@ pub fn collect_code_blocks(tree: &SyntaxNode, input: &str) -> HashMap<String, Ve
     blocks
 }
 
+/// Collect fenced code blocks grouped by [`Config::language_key`], so fences
+/// labeled `py`, `Python`, and `{python}` land under one key. Blocks within a
+/// group stay in document order.
+///
+/// [`Config::language_key`]: crate::config::Config::language_key
+pub fn collect_code_blocks_by_language_key(
+    tree: &SyntaxNode,
+    input: &str,
+    config: &crate::config::Config,
+) -> HashMap<String, Vec<CodeBlock>> {
+    let mut grouped: HashMap<String, Vec<CodeBlock>> = HashMap::new();
+    for (label, blocks) in collect_code_blocks(tree, input) {
+        grouped
+            .entry(config.language_key(&label))
+            .or_default()
+            .extend(blocks);
+    }
+    for blocks in grouped.values_mut() {
+        blocks.sort_by_key(|block| block.original_range.start);
+    }
+    grouped
+}
+
 /// Extract a verbatim MyST directive body as a lintable code block, keyed by the
 /// directive argument (e.g. `python` in `` ```{code-block} python ``). Returns
 /// `None` for non-verbatim directives (no `MYST_DIRECTIVE_BODY` child) or when
@@ -252,7 +276,7 @@ pub struct ImplicitHeadingId {
 
 pub fn implicit_heading_ids(tree: &SyntaxNode, extensions: &Extensions) -> Vec<ImplicitHeadingId> {
     let mut out = Vec::new();
-    let mut seen: HashMap<String, usize> = HashMap::new();
+    let mut slugger = Slugger::new(SlugStyle::for_extensions(extensions));
 
     for heading in tree.descendants().filter_map(Heading::cast) {
         let raw_text = heading
@@ -264,19 +288,9 @@ pub fn implicit_heading_ids(tree: &SyntaxNode, extensions: &Extensions) -> Vec<I
             continue;
         }
 
-        let base = heading_slugify(&normalized, extensions);
-        if base.is_empty() {
+        let Some(id) = slugger.slug(&normalized) else {
             continue;
-        }
-
-        let count = seen.entry(base.clone()).or_insert(0);
-        let id = if *count == 0 {
-            base
-        } else {
-            format!("{}-{}", base, *count)
         };
-        *count += 1;
-
         out.push(ImplicitHeadingId {
             id,
             heading: heading.syntax().clone(),
@@ -286,78 +300,13 @@ pub fn implicit_heading_ids(tree: &SyntaxNode, extensions: &Extensions) -> Vec<I
     out
 }
 
-/// Generate an auto identifier from heading text based on extension settings.
-pub fn heading_slugify(text: &str, extensions: &Extensions) -> String {
-    if extensions.gfm_auto_identifiers {
-        gfm_slugify(text)
-    } else {
-        pandoc_slugify(text)
-    }
-}
-
-/// Generate a GitHub-style auto identifier from heading text.
-pub fn gfm_slugify(text: &str) -> String {
-    let mut out = String::new();
-    let mut prev_dash = false;
-
-    for ch in text.chars() {
-        if ch.is_whitespace() {
-            if !out.is_empty() && !prev_dash {
-                out.push('-');
-                prev_dash = true;
-            }
-            continue;
-        }
-
-        for lc in ch.to_lowercase() {
-            if lc.is_alphanumeric() || lc == '_' || lc == '-' {
-                out.push(lc);
-                prev_dash = lc == '-';
-            }
-        }
-    }
-
-    while out.ends_with('-') {
-        out.pop();
-    }
-
-    out
-}
-
-/// Generate a Pandoc-style auto identifier from heading text.
-pub fn pandoc_slugify(text: &str) -> String {
-    let mut out = String::new();
-    let mut prev_dash = false;
-
-    for ch in text.chars() {
-        if ch.is_whitespace() {
-            if !out.is_empty() && !prev_dash {
-                out.push('-');
-                prev_dash = true;
-            }
-            continue;
-        }
-
-        for lc in ch.to_lowercase() {
-            if lc.is_alphanumeric() || lc == '_' || lc == '-' || lc == '.' {
-                out.push(lc);
-                prev_dash = lc == '-';
-            }
-        }
-    }
-
-    while out.ends_with('-') {
-        out.pop();
-    }
-
-    out
-}
-
 #[cfg(test)]
 mod tests {
     #[cfg(feature = "lsp")]
     use super::crossref_symbol_labels;
-    use super::{crossref_resolution_labels, implicit_heading_ids};
+    use super::{
+        collect_code_blocks_by_language_key, crossref_resolution_labels, implicit_heading_ids,
+    };
 
     #[test]
     fn crossref_resolution_labels_keep_exact_match() {
@@ -403,4 +352,19 @@ mod tests {
             .collect::<Vec<_>>();
         assert_eq!(ids, vec!["3-applications"]);
     }
+
+    #[test]
+    fn code_blocks_group_by_language_key_in_document_order() {
+        let input = "```python\na = 1\n```\n\n```py\nb = 2\n```\n\n```R\nx <- 1\n```\n\n```Python\nc = 3\n```\n";
+        let config = crate::config::Config::default();
+        let tree = crate::parse(input, Some(config.clone()));
+        let blocks = collect_code_blocks_by_language_key(&tree, input, &config);
+
+        let python: Vec<&str> = blocks["python"]
+            .iter()
+            .map(|b| b.content.as_str())
+            .collect();
+        assert_eq!(python, ["a = 1\n", "b = 2\n", "c = 3\n"]);
+        assert_eq!(blocks["r"].len(), 1);
+    }
 }
@@ -0,0 +1,125 @@
+//! Formatter invariants for downstream tests and fuzzers.
+//!
+//! The idempotency check of `panache debug format` and the losslessness check
+//! of `panache debug parse --check-lossless`, as functions returning structured
+//! failures instead of reports:
+//!
+//! ```rust
+//! let input = "# Title\n\nSome *text*.\n";
+//! panache::verify::lossless(input, None).unwrap();
+//! panache::verify::idempotent(input, None).unwrap();
+//! ```
+
+use crate::config::Config;
+use crate::parser::LosslessMismatch;
+
+/// Formatting a document a second time changed it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdempotencyFailure {
+    /// The input formatted once.
+    pub first: String,
+    /// `first` formatted again.
+    pub second: String,
+    /// First line (1-indexed) where `first` and `second` differ.
+    pub line: usize,
+}
+
+impl std::fmt::Display for IdempotencyFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "formatting is not idempotent: second pass differs at line {}",
+            self.line
+        )
+    }
+}
+
+impl std::error::Error for IdempotencyFailure {}
+
+/// Check that formatting `input` twice gives the same result as formatting it
+/// once.
+pub fn idempotent(input: &str, config: Option<Config>) -> Result<(), IdempotencyFailure> {
+    let config = config.unwrap_or_default();
+    let first = crate::format(input, Some(config.clone()), None);
+    let second = crate::format(&first, Some(config), None);
+    match IdempotencyFailure::between(first, second) {
+        Some(failure) => Err(failure),
+        None => Ok(()),
+    }
+}
+
+/// Check that the syntax tree of `input` contains every byte of it, in order.
+/// The mismatch locates the first byte the tree gets wrong.
+pub fn lossless(input: &str, config: Option<Config>) -> Result<(), LosslessMismatch> {
+    crate::parser::check_lossless(input, config).map(|_| ())
+}
+
+impl IdempotencyFailure {
+    /// The failure for a first pass `first` and second pass `second`, if they
+    /// differ.
+    fn between(first: String, second: String) -> Option<Self> {
+        (first != second).then(|| Self {
+            line: first_divergent_line(&first, &second),
+            first,
+            second,
+        })
+    }
+}
+
+/// First line (1-indexed) where `left` and `right` differ, counting a change
+/// of line ending as a difference.
+fn first_divergent_line(left: &str, right: &str) -> usize {
+    left.split_inclusive('\n')
+        .zip(right.split_inclusive('\n'))
+        .take_while(|(a, b)| a == b)
+        .count()
+        + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_divergent_line_finds_changed_lines_and_endings() {
+        assert_eq!(first_divergent_line("a\nb\nc\n", "a\nB\nc\n"), 2);
+        assert_eq!(first_divergent_line("a\nb\n", "a\nb\nc\n"), 3);
+        assert_eq!(first_divergent_line("a\r\nb\n", "a\nb\n"), 1);
+        assert_eq!(first_divergent_line("a\nb", "a\nb\n"), 2);
+    }
+
+    #[test]
+    fn well_behaved_documents_pass() {
+        let inputs = [
+            "Some  *text*\nwrapped\n\n- a list\n- item\n",
+            "\u{feff}# Title\r\n\r\n```\nunclosed fence\n",
+            "> quote\n>\n> - [link](<a b> \"t\")\n\n|a|b|\n|-|-|\n|1|2|\n",
+        ];
+        for input in inputs {
+            assert_eq!(lossless(input, None), Ok(()), "{input:?}");
+            assert_eq!(idempotent(input, None), Ok(()), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn failures_describe_the_divergent_line() {
+        let input = "# Title\n\nA paragraph long enough to wrap at a narrow width.\n";
+        let wide = crate::format(input, None, None);
+        let narrow = crate::format(
+            input,
+            Some(
+                crate::config::ConfigBuilder::default()
+                    .line_width(20)
+                    .build(),
+            ),
+            None,
+        );
+        let failure = IdempotencyFailure::between(wide, narrow).expect("passes differ");
+        assert_eq!(failure.line, 3);
+        assert_eq!(
+            failure.to_string(),
+            "formatting is not idempotent: second pass differs at line 3"
+        );
+        assert!(IdempotencyFailure::between(input.into(), input.into()).is_none());
+    }
+}
@@ -0,0 +1,75 @@
+//! Check-links subcommand tests
+
+use std::fs;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use predicates::prelude::*;
+use tempfile::TempDir;
+
+fn write_docs(root: &std::path::Path) {
+    fs::create_dir_all(root.join("docs")).unwrap();
+    fs::write(
+        root.join("docs/index.qmd"),
+        "# Intro\n\nSee [setup](setup.qmd#install), [usage](setup.qmd#usage), and \
+         [the FAQ](faq.qmd).\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("docs/setup.qmd"),
+        "# Install\n\nBack to [intro](index.qmd#intro).\n",
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_check_links_reports_missing_files_and_anchors() {
+    let temp_dir = TempDir::new().unwrap();
+    write_docs(temp_dir.path());
+
+    cargo_bin_cmd!("panache")
+        .current_dir(temp_dir.path())
+        .args(["check-links", "--message-format", "short", "docs"])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains(
+            "index.qmd:3:41: warning[broken-link]: Anchor '#usage' not found in 'setup.qmd'",
+        ))
+        .stdout(predicate::str::contains(
+            "index.qmd:3:73: warning[broken-link]: Link target 'faq.qmd' does not exist",
+        ))
+        .stdout(predicate::str::contains("setup.qmd:").not());
+}
+
+#[test]
+fn test_check_links_succeeds_when_links_resolve() {
+    let temp_dir = TempDir::new().unwrap();
+    write_docs(temp_dir.path());
+
+    cargo_bin_cmd!("panache")
+        .current_dir(temp_dir.path())
+        .args(["check-links", "docs/setup.qmd"])
+        .assert()
+        .success()
+        .stdout("No broken links found in 1 file(s)\n");
+}
+
+#[test]
+fn test_check_links_json_lists_each_file() {
+    let temp_dir = TempDir::new().unwrap();
+    write_docs(temp_dir.path());
+
+    let output = cargo_bin_cmd!("panache")
+        .current_dir(temp_dir.path())
+        .args(["check-links", "--json", "docs/index.qmd", "docs/setup.qmd"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+
+    let files: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let files = files.as_array().unwrap();
+    assert_eq!(files.len(), 2);
+    assert_eq!(files[0]["path"], "docs/index.qmd");
+    assert_eq!(files[0]["diagnostics"].as_array().unwrap().len(), 2);
+    assert_eq!(files[0]["diagnostics"][0]["code"], "broken-link");
+    assert!(files[1]["diagnostics"].as_array().unwrap().is_empty());
+}
@@ -0,0 +1,156 @@
+//! `panache config` tests
+
+use std::fs;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use predicates::prelude::*;
+use tempfile::TempDir;
+
+#[test]
+fn test_config_show_reports_sources() {
+    let temp_dir = TempDir::new().unwrap();
+    let workspace = temp_dir.path();
+    fs::create_dir_all(workspace.join(".git")).unwrap();
+    fs::write(
+        workspace.join("panache.toml"),
+        "[format]\nline-width = 100\n",
+    )
+    .unwrap();
+    fs::write(
+        workspace.join("doc.qmd"),
+        "---\ntitle: Doc\npanache:\n  wrap: preserve\n---\n\nText\n",
+    )
+    .unwrap();
+
+    cargo_bin_cmd!("panache")
+        .current_dir(workspace)
+        .args(["config", "show", "doc.qmd"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "flavor = \"quarto\"  # detected from doc.qmd",
+        ))
+        .stdout(predicate::str::is_match(r#"line-width = 100  # .*panache\.toml"#).unwrap())
+        .stdout(predicate::str::contains(
+            "wrap = \"preserve\"  # frontmatter",
+        ))
+        .stdout(predicate::str::contains("tab-width = 4  # default"))
+        .stdout(predicate::str::contains("[extensions]"));
+}
+
+#[test]
+fn test_config_show_flavor_flag() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir_all(temp_dir.path().join(".git")).unwrap();
+
+    cargo_bin_cmd!("panache")
+        .current_dir(temp_dir.path())
+        .args(["config", "show", "--flavor", "gfm"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "# Config file: none (built-in defaults)",
+        ))
+        .stdout(predicate::str::contains("flavor = \"gfm\"  # --flavor"));
+}
+
+#[test]
+fn test_config_show_flavor_modifiers() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir_all(temp_dir.path().join(".git")).unwrap();
+    fs::write(
+        temp_dir.path().join("panache.toml"),
+        "flavor = \"markdown+emoji\"\n",
+    )
+    .unwrap();
+
+    cargo_bin_cmd!("panache")
+        .current_dir(temp_dir.path())
+        .args(["config", "show"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r#"flavor = "pandoc"  # .*panache\.toml"#).unwrap())
+        .stdout(predicate::str::is_match(r#"emoji = true  # .*panache\.toml"#).unwrap());
+
+    cargo_bin_cmd!("panache")
+        .current_dir(temp_dir.path())
+        .args(["config", "show", "--flavor", "gfm-footnotes"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("flavor = \"gfm\"  # --flavor"))
+        .stdout(predicate::str::contains(
+            "footnotes = false  # command line",
+        ));
+}
+
+#[test]
+fn test_config_init_writes_template_once() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join(".panache.toml");
+
+    cargo_bin_cmd!("panache")
+        .current_dir(temp_dir.path())
+        .args(["config", "init"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Wrote"));
+    let written = fs::read_to_string(&config_path).unwrap();
+    assert!(written.contains("# line-width = 80"));
+
+    cargo_bin_cmd!("panache")
+        .current_dir(temp_dir.path())
+        .args(["config", "init"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("already exists"));
+
+    fs::write(&config_path, "# edited\n").unwrap();
+    cargo_bin_cmd!("panache")
+        .current_dir(temp_dir.path())
+        .args(["config", "init", "--force"])
+        .assert()
+        .success();
+    assert_eq!(fs::read_to_string(&config_path).unwrap(), written);
+}
+
+#[test]
+fn test_config_validate_accepts_valid_config() {
+    let temp_dir = TempDir::new().unwrap();
+    let workspace = temp_dir.path();
+    fs::create_dir_all(workspace.join(".git")).unwrap();
+    fs::write(
+        workspace.join("base.toml"),
+        "[format]\nwrap = \"sentence\"\n",
+    )
+    .unwrap();
+    fs::write(
+        workspace.join("panache.toml"),
+        "extend = \"base.toml\"\n\n[format]\nline-width = 100\n",
+    )
+    .unwrap();
+
+    cargo_bin_cmd!("panache")
+        .current_dir(workspace)
+        .args(["config", "validate"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "panache.toml is valid (including 1 extended file(s))",
+        ));
+}
+
+#[test]
+fn test_config_validate_reports_location_and_suggestion() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("custom.toml");
+    fs::write(&config_path, "[format]\nline-widht = 100\n").unwrap();
+
+    cargo_bin_cmd!("panache")
+        .args(["config", "validate"])
+        .arg(&config_path)
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("line 2, column 1"))
+        .stderr(predicate::str::contains("unknown field `line-widht`"))
+        .stderr(predicate::str::contains("did you mean `line-width`?"));
+}
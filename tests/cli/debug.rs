@@ -5,6 +5,26 @@ use predicates::prelude::*;
 use std::fs;
 use tempfile::TempDir;
 
+#[test]
+fn test_debug_parse_check_lossless_stdin() {
+    cargo_bin_cmd!("panache")
+        .args(["debug", "parse", "--check-lossless"])
+        .write_stdin("# Heading\n\n- item\n\n```r\n1\n```\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("<stdin> is lossless"));
+}
+
+#[test]
+fn test_debug_parse_check_lossless_json() {
+    cargo_bin_cmd!("panache")
+        .args(["debug", "parse", "--check-lossless", "--json"])
+        .write_stdin("Paragraph with *emphasis*.\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"lossless\": true"));
+}
+
 #[test]
 fn test_debug_format_stdin_success() {
     cargo_bin_cmd!("panache")
@@ -213,3 +233,88 @@ fn test_debug_format_dash_mixed_with_path_errors() {
             "'-' (stdin) cannot be combined with file path arguments",
         ));
 }
+
+#[test]
+fn test_debug_corpus_directory_writes_summary() {
+    let corpus = TempDir::new().unwrap();
+    fs::write(corpus.path().join("a.md"), "# Heading\n\nParagraph.\n").unwrap();
+    fs::create_dir(corpus.path().join("chapters")).unwrap();
+    fs::write(
+        corpus.path().join("chapters").join("b.qmd"),
+        "## Section\n\n- item\n",
+    )
+    .unwrap();
+    fs::write(corpus.path().join("notes.txt"), "not markdown\n").unwrap();
+    let out = TempDir::new().unwrap();
+
+    cargo_bin_cmd!("panache")
+        .args(["debug", "corpus"])
+        .arg(corpus.path())
+        .arg("--out-dir")
+        .arg(out.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("All checks passed (files: 2)"));
+
+    let summary: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(out.path().join("summary.json")).unwrap())
+            .unwrap();
+    assert_eq!(summary["files_checked"], 2);
+    assert_eq!(summary["failure_count"], 0);
+    assert_eq!(
+        summary["checks"],
+        serde_json::json!(["idempotency", "losslessness", "panic"])
+    );
+    assert_eq!(
+        fs::read_to_string(out.path().join("failures.tsv")).unwrap(),
+        "file\tfailure_type\tlog_path\treport_path\tpasses_path\n"
+    );
+}
+
+#[test]
+fn test_debug_corpus_dump_passes_writes_artifacts() {
+    let corpus = TempDir::new().unwrap();
+    fs::write(corpus.path().join("doc.md"), "Some *text*.\n").unwrap();
+    let out = TempDir::new().unwrap();
+
+    cargo_bin_cmd!("panache")
+        .args(["debug", "corpus", "--checks", "idempotency", "--dump-passes"])
+        .arg(corpus.path())
+        .arg("--out-dir")
+        .arg(out.path())
+        .assert()
+        .success();
+
+    let passes = out.path().join("logs").join("doc.md.passes");
+    assert!(passes.join("doc.md.idempotency.once.txt").exists());
+    assert!(!passes.join("doc.md.losslessness.input.txt").exists());
+}
+
+#[test]
+fn test_debug_corpus_rejects_missing_source() {
+    let out = TempDir::new().unwrap();
+    cargo_bin_cmd!("panache")
+        .args(["debug", "corpus", "does-not-exist"])
+        .arg("--out-dir")
+        .arg(out.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("is not a directory or git URL"));
+}
+
+#[test]
+fn test_debug_capabilities_reports_extensions_and_kinds() {
+    let output = cargo_bin_cmd!("panache")
+        .args(["--isolated", "--flavor", "gfm", "debug", "capabilities"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["flavor"], "gfm");
+    assert_eq!(report["extensions"]["task-lists"], true);
+    assert_eq!(report["extensions"]["fenced-divs"], false);
+    let normalized = report["formatter"]["normalized"].as_array().unwrap();
+    assert!(normalized.iter().any(|kind| kind == "PARAGRAPH"));
+    let verbatim = report["formatter"]["verbatim"].as_array().unwrap();
+    assert!(verbatim.iter().any(|kind| kind == "HTML_BLOCK"));
+}
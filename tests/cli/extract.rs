@@ -0,0 +1,89 @@
+//! Extract subcommand tests
+
+use std::fs;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use predicates::prelude::*;
+use tempfile::TempDir;
+
+const DOCUMENT: &str = "\
+# Analysis
+
+```{r setup}
+library(stats)
+```
+
+```{python}
+print(1)
+```
+
+```{r}
+#| label: fit
+fit <- lm(y ~ x)
+```
+";
+
+#[test]
+fn test_extract_concatenates_blocks_with_label_comments() {
+    let temp_dir = TempDir::new().unwrap();
+    let doc = temp_dir.path().join("analysis.qmd");
+    fs::write(&doc, DOCUMENT).unwrap();
+
+    cargo_bin_cmd!("panache")
+        .current_dir(temp_dir.path())
+        .args(["extract", "--lang", "r", "analysis.qmd"])
+        .assert()
+        .success()
+        .stdout(
+            "# ---- setup ----\nlibrary(stats)\n\n# ---- fit ----\n#| label: fit\nfit <- lm(y ~ x)\n",
+        );
+}
+
+#[test]
+fn test_extract_writes_files_and_source_map() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::write(root.join("analysis.qmd"), DOCUMENT).unwrap();
+
+    cargo_bin_cmd!("panache")
+        .current_dir(root)
+        .args([
+            "extract",
+            "--lang",
+            "r",
+            "--out-dir",
+            "code",
+            "--source-map",
+            "map.json",
+            "analysis.qmd",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Wrote 2 files to code"));
+
+    assert_eq!(
+        fs::read_to_string(root.join("code/analysis-1.r")).unwrap(),
+        "# ---- setup ----\nlibrary(stats)\n"
+    );
+    assert!(root.join("code/analysis-2.r").exists());
+
+    let map: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(root.join("map.json")).unwrap()).unwrap();
+    let entries = map.as_array().unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[1]["source_line"], 12);
+    assert_eq!(entries[1]["output_line"], 2);
+    assert_eq!(entries[1]["lines"], 2);
+    assert_eq!(entries[1]["label"], "fit");
+}
+
+#[test]
+fn test_extract_reports_no_matching_blocks() {
+    cargo_bin_cmd!("panache")
+        .args(["extract", "--lang", "julia", "--stdin-filename", "doc.qmd"])
+        .write_stdin(DOCUMENT)
+        .assert()
+        .success()
+        .stdout("")
+        .stderr(predicate::str::contains("No `julia` code blocks found"));
+}
@@ -0,0 +1,30 @@
+//! Filter subcommand tests
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use predicates::prelude::*;
+
+#[test]
+fn test_filter_rewraps_paragraphs() {
+    let input = r#"{"pandoc-api-version":[1,23,1,1],"meta":{},"blocks":[{"t":"Para","c":[{"t":"Str","c":"One."},{"t":"SoftBreak"},{"t":"Str","c":"Two."}]},{"t":"Unknown","c":"kept"}]}"#;
+    let output = cargo_bin_cmd!("panache")
+        .args(["filter", "markdown", "--isolated"])
+        .write_stdin(input)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let doc: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(doc["blocks"][0]["c"][1]["t"], "Space");
+    assert_eq!(doc["blocks"][1]["c"], "kept");
+}
+
+#[test]
+fn test_filter_rejects_non_pandoc_json() {
+    cargo_bin_cmd!("panache")
+        .args(["filter", "--isolated"])
+        .write_stdin("{\"blocks\": []}")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("not a Pandoc JSON document"));
+}
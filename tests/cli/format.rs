@@ -79,6 +79,302 @@ fn test_format_check_diff_output() {
         .stdout(predicate::str::contains("+"));
 }
 
+#[test]
+fn test_format_check_unified_diff_output() {
+    cargo_bin_cmd!("panache")
+        .args(["format", "--check", "--diff-format", "unified"])
+        .write_stdin("# Heading\n\nFirst line\nsecond line.\n")
+        .assert()
+        .failure()
+        .stdout(predicate::str::starts_with(
+            "--- a/<stdin>\n+++ b/<stdin>\n@@ -1,4 +1,3 @@\n",
+        ))
+        .stdout(predicate::str::contains("-First line\n-second line.\n"))
+        .stdout(predicate::str::contains("+First line second line.\n"))
+        .stdout(predicate::str::contains("Diff in").not())
+        .stdout(predicate::str::contains("\u{1b}[").not());
+}
+
+#[test]
+fn test_format_check_json_diff_output() {
+    let temp_dir = TempDir::new().unwrap();
+    let clean = temp_dir.path().join("clean.qmd");
+    let messy = temp_dir.path().join("messy.qmd");
+    fs::write(&clean, "# Heading\n\nParagraph.\n").unwrap();
+    fs::write(&messy, "# Heading\n\nFirst line\nsecond line.\n").unwrap();
+
+    let output = cargo_bin_cmd!("panache")
+        .args(["format", "--check", "--diff-format", "json"])
+        .arg(&clean)
+        .arg(&messy)
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let files = json["files"].as_array().unwrap();
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0]["path"], messy.to_str().unwrap());
+    assert_eq!(files[0]["kind"], "content");
+    let hunk = &files[0]["hunks"][0];
+    assert_eq!(hunk["old_start"], 1);
+    assert_eq!(hunk["old_lines"], 4);
+    assert_eq!(hunk["new_lines"], 3);
+    let changes = hunk["changes"].as_array().unwrap();
+    assert!(changes.contains(&serde_json::json!({
+        "kind": "removed",
+        "line": "second line.",
+    })));
+    assert!(changes.contains(&serde_json::json!({
+        "kind": "added",
+        "line": "First line second line.",
+    })));
+}
+
+#[test]
+fn test_format_check_json_diff_when_formatted() {
+    cargo_bin_cmd!("panache")
+        .args(["format", "--check", "--diff-format", "json"])
+        .write_stdin("# Heading\n\nParagraph.\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"files\": []"));
+}
+
+#[test]
+fn test_format_diff_format_requires_check() {
+    cargo_bin_cmd!("panache")
+        .args(["format", "--diff-format", "unified"])
+        .write_stdin("# Heading\n")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--check"));
+}
+
+#[test]
+fn test_format_stdout_leaves_file_untouched() {
+    let temp_dir = TempDir::new().unwrap();
+    let test_file = temp_dir.path().join("test.qmd");
+    let original = "# Heading\n\nFirst line\nsecond line.\n";
+    fs::write(&test_file, original).unwrap();
+
+    cargo_bin_cmd!("panache")
+        .args(["format", "--stdout", test_file.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout("# Heading\n\nFirst line second line.\n");
+
+    assert_eq!(fs::read_to_string(&test_file).unwrap(), original);
+}
+
+#[test]
+fn test_format_backup_keeps_original() {
+    let temp_dir = TempDir::new().unwrap();
+    let changed = temp_dir.path().join("changed.qmd");
+    let unchanged = temp_dir.path().join("unchanged.qmd");
+    let original = "# Heading\n\nFirst line\nsecond line.\n";
+    fs::write(&changed, original).unwrap();
+    fs::write(&unchanged, "# Heading\n\nParagraph.\n").unwrap();
+
+    cargo_bin_cmd!("panache")
+        .args(["format", "--backup", "orig"])
+        .arg(&changed)
+        .arg(&unchanged)
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(&changed).unwrap(),
+        "# Heading\n\nFirst line second line.\n"
+    );
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("changed.qmd.orig")).unwrap(),
+        original
+    );
+    assert!(!temp_dir.path().join("unchanged.qmd.orig").exists());
+}
+
+#[test]
+fn test_format_in_place_leaves_no_temp_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let test_file = temp_dir.path().join("test.qmd");
+    fs::write(&test_file, "# Heading\n\nFirst line\nsecond line.\n").unwrap();
+
+    cargo_bin_cmd!("panache")
+        .args(["format", test_file.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let leftovers: Vec<_> = fs::read_dir(temp_dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .filter(|name| name.ends_with(".tmp"))
+        .collect();
+    assert!(
+        leftovers.is_empty(),
+        "temp files left behind: {leftovers:?}"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_format_in_place_preserves_permissions() {
+    let temp_dir = TempDir::new().unwrap();
+    let test_file = temp_dir.path().join("test.qmd");
+    fs::write(&test_file, "# Heading\n\nFirst line\nsecond line.\n").unwrap();
+    fs::set_permissions(&test_file, fs::Permissions::from_mode(0o640)).unwrap();
+
+    cargo_bin_cmd!("panache")
+        .args(["format", test_file.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let mode = fs::metadata(&test_file).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o640);
+}
+
+#[test]
+fn test_format_stdout_conflicts_with_check() {
+    cargo_bin_cmd!("panache")
+        .args(["format", "--check", "--stdout"])
+        .write_stdin("# Heading\n")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn test_format_list_different_prints_only_changed_paths() {
+    let temp_dir = TempDir::new().unwrap();
+    let clean = temp_dir.path().join("clean.qmd");
+    let messy = temp_dir.path().join("messy.qmd");
+    let messy_input = "# Heading\n\nFirst line\nsecond line.\n";
+    fs::write(&clean, "# Heading\n\nParagraph.\n").unwrap();
+    fs::write(&messy, messy_input).unwrap();
+
+    cargo_bin_cmd!("panache")
+        .args(["format", "-l"])
+        .arg(&clean)
+        .arg(&messy)
+        .assert()
+        .code(1)
+        .stdout(format!("{}\n", messy.display()));
+
+    assert_eq!(fs::read_to_string(&messy).unwrap(), messy_input);
+}
+
+#[test]
+fn test_format_check_summary_lists_changed_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let clean = temp_dir.path().join("clean.qmd");
+    let messy = temp_dir.path().join("messy.qmd");
+    fs::write(&clean, "# Heading\n\nParagraph.\n").unwrap();
+    fs::write(&messy, "# Heading\n\nFirst line\nsecond line.\n").unwrap();
+
+    cargo_bin_cmd!("panache")
+        .args(["format", "--check", "--summary"])
+        .arg(&clean)
+        .arg(&messy)
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("Diff in"))
+        .stdout(predicate::str::contains(format!(
+            "Would reformat: {}\n",
+            messy.display()
+        )))
+        .stdout(predicate::str::contains(
+            "1 file would be reformatted, 1 file already formatted",
+        ));
+}
+
+#[test]
+fn test_format_check_error_exit_code_is_distinct() {
+    let temp_dir = TempDir::new().unwrap();
+    let test_file = temp_dir.path().join("test.qmd");
+    let config_file = temp_dir.path().join("panache.toml");
+    fs::write(&test_file, "# Heading\n").unwrap();
+    fs::write(&config_file, "line-width = \"wide\"\n").unwrap();
+
+    cargo_bin_cmd!("panache")
+        .args(["format", "--check", "--config"])
+        .arg(&config_file)
+        .arg(&test_file)
+        .assert()
+        .code(2)
+        .stderr(predicate::str::starts_with("Error: "))
+        .stderr(predicate::str::contains("Custom {").not());
+}
+
+#[test]
+fn test_format_check_reports_line_ending_only_changes() {
+    let temp_dir = TempDir::new().unwrap();
+    let test_file = temp_dir.path().join("test.md");
+    let original = "# Heading\r\n\r\nParagraph.\r\n";
+    fs::write(&test_file, original).unwrap();
+    fs::write(
+        temp_dir.path().join("panache.toml"),
+        "[format]\nline-ending = \"lf\"\n",
+    )
+    .unwrap();
+
+    cargo_bin_cmd!("panache")
+        .current_dir(temp_dir.path())
+        .args(["format", "--no-cache", "--check", "test.md"])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("line endings only"))
+        .stdout(predicate::str::contains("--ignore-line-endings"));
+
+    cargo_bin_cmd!("panache")
+        .current_dir(temp_dir.path())
+        .args([
+            "format",
+            "--no-cache",
+            "--check",
+            "--ignore-line-endings",
+            "test.md",
+        ])
+        .assert()
+        .success();
+
+    cargo_bin_cmd!("panache")
+        .current_dir(temp_dir.path())
+        .args(["format", "--no-cache", "--ignore-line-endings", "test.md"])
+        .assert()
+        .success();
+    assert_eq!(fs::read_to_string(&test_file).unwrap(), original);
+}
+
+#[test]
+fn test_format_strips_bom_and_check_reports_it() {
+    let temp_dir = TempDir::new().unwrap();
+    let test_file = temp_dir.path().join("test.qmd");
+    fs::write(&test_file, "\u{feff}---\ntitle: Report\n---\n\n# Heading\n").unwrap();
+
+    cargo_bin_cmd!("panache")
+        .args(["format", "--no-cache", "--check"])
+        .arg(&test_file)
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("byte order mark only"));
+
+    cargo_bin_cmd!("panache")
+        .args(["format", "--no-cache", "--check", "--ignore-bom"])
+        .arg(&test_file)
+        .assert()
+        .success();
+
+    cargo_bin_cmd!("panache")
+        .args(["format", "--no-cache"])
+        .arg(&test_file)
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_to_string(&test_file).unwrap(),
+        "---\ntitle: Report\n---\n\n# Heading\n"
+    );
+}
+
 #[test]
 fn test_format_with_config() {
     let temp_dir = TempDir::new().unwrap();
@@ -166,6 +462,138 @@ exclude = ["tests/"]
         .stdout(predicate::str::contains("snapshot.md").not());
 }
 
+#[test]
+fn test_format_directory_cli_include_and_exclude() {
+    let temp_dir = TempDir::new().unwrap();
+    let docs_dir = temp_dir.path().join("docs");
+    let vendor_dir = temp_dir.path().join("vendor");
+    fs::create_dir_all(&docs_dir).unwrap();
+    fs::create_dir_all(&vendor_dir).unwrap();
+    fs::write(temp_dir.path().join("README.md"), "# Readme\n").unwrap();
+    fs::write(docs_dir.join("guide.qmd"), "# Guide\n").unwrap();
+    fs::write(docs_dir.join("notes.md"), "# Notes\n").unwrap();
+    fs::write(vendor_dir.join("upstream.md"), "# Upstream\n").unwrap();
+
+    cargo_bin_cmd!("panache")
+        .current_dir(temp_dir.path())
+        .args(["format", ".", "--exclude", "vendor/"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("3 files left unchanged"));
+
+    cargo_bin_cmd!("panache")
+        .current_dir(temp_dir.path())
+        .args(["format", ".", "--include", "docs/**/*.qmd"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 file left unchanged"));
+}
+
+#[test]
+fn test_format_directory_respects_panacheignore() {
+    let temp_dir = TempDir::new().unwrap();
+    let docs_dir = temp_dir.path().join("docs");
+    fs::create_dir_all(docs_dir.join("_book")).unwrap();
+    fs::write(temp_dir.path().join(".panacheignore"), "CHANGELOG.md\n").unwrap();
+    fs::write(docs_dir.join(".panacheignore"), "_book/\n").unwrap();
+    fs::write(temp_dir.path().join("CHANGELOG.md"), "#   Changes\n").unwrap();
+    fs::write(docs_dir.join("guide.qmd"), "# Guide\n").unwrap();
+    fs::write(docs_dir.join("_book").join("index.md"), "#   Book\n").unwrap();
+
+    cargo_bin_cmd!("panache")
+        .current_dir(temp_dir.path())
+        .args(["format", "--no-cache", "."])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 file left unchanged"));
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("CHANGELOG.md")).unwrap(),
+        "#   Changes\n"
+    );
+
+    // Explicitly named files are still formatted.
+    cargo_bin_cmd!("panache")
+        .current_dir(temp_dir.path())
+        .args(["format", "--no-cache", "--check", "CHANGELOG.md"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_format_embedded_roxygen_directory() {
+    let temp_dir = TempDir::new().unwrap();
+    let r_dir = temp_dir.path().join("R");
+    fs::create_dir_all(&r_dir).unwrap();
+    fs::write(
+        r_dir.join("add.R"),
+        "#' Add   numbers\n#'\n#' @param x A   number.\nadd <- function(x,y) x+y\n",
+    )
+    .unwrap();
+    fs::write(temp_dir.path().join("README.md"), "#   Readme\n").unwrap();
+
+    cargo_bin_cmd!("panache")
+        .current_dir(temp_dir.path())
+        .args(["format", "--no-cache", "--embedded", "roxygen", "."])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 file reformatted"));
+
+    assert_eq!(
+        fs::read_to_string(r_dir.join("add.R")).unwrap(),
+        "#' Add numbers\n#'\n#' @param x A   number.\nadd <- function(x,y) x+y\n"
+    );
+    // Markdown files are not selected in embedded mode.
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("README.md")).unwrap(),
+        "#   Readme\n"
+    );
+}
+
+#[test]
+fn test_format_embedded_docstring_stdin() {
+    let input = "def f():\n    \"\"\"Summary.\n\n    *   item\n    \"\"\"\n";
+    // Embedded Markdown is CommonMark, where the bullet character is kept.
+    cargo_bin_cmd!("panache")
+        .args(["format", "--embedded", "docstring"])
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout("def f():\n    \"\"\"Summary.\n\n    * item\n    \"\"\"\n");
+}
+
+#[test]
+fn test_format_directory_skips_generated_dirs_unless_opted_in() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = temp_dir.path().join("panache.toml");
+    fs::write(
+        temp_dir.path().join("doc.qmd"),
+        "# Included\n\nParagraph.\n",
+    )
+    .unwrap();
+    for dir in ["_freeze", "_site", ".quarto"] {
+        let generated_dir = temp_dir.path().join(dir);
+        fs::create_dir_all(&generated_dir).unwrap();
+        fs::write(generated_dir.join("generated.md"), "# Generated\n").unwrap();
+    }
+
+    // A user `exclude` replaces the default base but not the generated set.
+    fs::write(&config, "exclude = []\n").unwrap();
+    cargo_bin_cmd!("panache")
+        .current_dir(temp_dir.path())
+        .args(["format", "."])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 file left unchanged"));
+
+    fs::write(&config, "exclude = []\nexclude-generated = false\n").unwrap();
+    cargo_bin_cmd!("panache")
+        .current_dir(temp_dir.path())
+        .args(["format", "."])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("4 files left unchanged"));
+}
+
 #[test]
 fn test_format_directory_respects_exclude_in_dot_config() {
     // A `.config/panache.toml` must behave like a top-level config: its exclude
@@ -783,6 +1211,21 @@ fn test_format_option_line_width_overrides_config() {
     );
 }
 
+#[test]
+fn test_format_option_line_width_overrides_frontmatter() {
+    let input = format!("---\npanache:\n  line-width: 200\n---\n\n{WRAP_PROBE}");
+    let assert = cargo_bin_cmd!("panache")
+        .args(["format", "-o", "line-width=40"])
+        .write_stdin(input)
+        .assert()
+        .success();
+    let stdout = String::from_utf8_lossy(&assert.get_output().stdout).into_owned();
+    assert!(
+        stdout.lines().all(|line| line.chars().count() <= 40),
+        "-o line-width=40 should beat frontmatter line-width: 200, got:\n{stdout}"
+    );
+}
+
 #[test]
 fn test_format_option_line_width_rejects_zero() {
     cargo_bin_cmd!("panache")
@@ -963,3 +1406,377 @@ fn test_format_global_xdg_config_exclude_anchors_at_traversal_dir() {
         .stdout(predicate::str::contains("1 file left unchanged"))
         .stdout(predicate::str::contains("snapshot.md").not());
 }
+
+fn git(dir: &std::path::Path, args: &[&str]) -> String {
+    let output = std::process::Command::new("git")
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .expect("git should be installed");
+    assert!(output.status.success(), "git {args:?} failed");
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn test_format_staged_formats_and_restages_staged_files_only() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    git(root, &["init", "--quiet"]);
+    fs::create_dir_all(root.join("docs")).unwrap();
+    fs::write(root.join("docs/staged.qmd"), "#   Staged\n").unwrap();
+    fs::write(root.join("unstaged.qmd"), "#   Unstaged\n").unwrap();
+    fs::write(root.join("notes.txt"), "#   Not markdown\n").unwrap();
+    git(root, &["add", "docs/staged.qmd", "notes.txt"]);
+
+    cargo_bin_cmd!("panache")
+        .current_dir(root.join("docs"))
+        .args(["format", "--staged", "--restage"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Formatted ../docs/staged.qmd"));
+
+    assert_eq!(
+        fs::read_to_string(root.join("docs/staged.qmd")).unwrap(),
+        "# Staged\n"
+    );
+    assert_eq!(
+        fs::read_to_string(root.join("unstaged.qmd")).unwrap(),
+        "#   Unstaged\n"
+    );
+    assert_eq!(git(root, &["show", ":docs/staged.qmd"]), "# Staged\n");
+}
+
+#[test]
+fn test_format_restage_skips_files_with_unstaged_changes() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    git(root, &["init", "--quiet"]);
+    fs::write(root.join("doc.qmd"), "#   Staged\n").unwrap();
+    git(root, &["add", "doc.qmd"]);
+    fs::write(root.join("doc.qmd"), "#   Staged\n\nNot   staged.\n").unwrap();
+
+    cargo_bin_cmd!("panache")
+        .current_dir(root)
+        .args(["format", "--staged", "--restage"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "not restaging doc.qmd: it has unstaged changes",
+        ));
+
+    assert_eq!(
+        fs::read_to_string(root.join("doc.qmd")).unwrap(),
+        "# Staged\n\nNot staged.\n"
+    );
+    assert_eq!(git(root, &["show", ":doc.qmd"]), "#   Staged\n");
+}
+
+#[test]
+fn test_format_staged_with_nothing_staged_succeeds() {
+    let temp_dir = TempDir::new().unwrap();
+    git(temp_dir.path(), &["init", "--quiet"]);
+
+    cargo_bin_cmd!("panache")
+        .current_dir(temp_dir.path())
+        .args(["format", "--staged", "--check"])
+        .assert()
+        .success()
+        .stdout("No files to format\n");
+}
+
+#[test]
+fn test_format_changed_formats_only_changed_blocks() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    git(root, &["init", "--quiet"]);
+    let original = "#   Kept\n\nOld   text.\n\n#   Also kept\n";
+    fs::write(root.join("doc.qmd"), original).unwrap();
+    fs::write(root.join("clean.qmd"), "#   Untouched\n").unwrap();
+    git(root, &["add", "."]);
+    git(
+        root,
+        &[
+            "-c",
+            "user.name=panache",
+            "-c",
+            "user.email=panache@example.com",
+            "commit",
+            "--quiet",
+            "-m",
+            "init",
+        ],
+    );
+    fs::write(
+        root.join("doc.qmd"),
+        "#   Kept\n\nNew   text.\n\n#   Also kept\n",
+    )
+    .unwrap();
+
+    cargo_bin_cmd!("panache")
+        .current_dir(root)
+        .args(["format", "--changed"])
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(root.join("doc.qmd")).unwrap(),
+        "#   Kept\n\nNew text.\n\n#   Also kept\n"
+    );
+    assert_eq!(
+        fs::read_to_string(root.join("clean.qmd")).unwrap(),
+        "#   Untouched\n"
+    );
+}
+
+#[test]
+fn test_format_changed_since_ref_includes_committed_changes() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let commit = |message: &str| {
+        git(root, &["add", "."]);
+        git(
+            root,
+            &[
+                "-c",
+                "user.name=panache",
+                "-c",
+                "user.email=panache@example.com",
+                "commit",
+                "--quiet",
+                "-m",
+                message,
+            ],
+        );
+    };
+    git(root, &["init", "--quiet"]);
+    fs::write(root.join("doc.qmd"), "#   Base\n\nOld   text.\n").unwrap();
+    commit("base");
+    git(root, &["tag", "base"]);
+    fs::write(root.join("doc.qmd"), "#   Base\n\nNew   text.\n").unwrap();
+    commit("edit");
+
+    cargo_bin_cmd!("panache")
+        .current_dir(root)
+        .args(["format", "--changed"])
+        .assert()
+        .success()
+        .stdout("No files to format\n");
+
+    cargo_bin_cmd!("panache")
+        .current_dir(root)
+        .args(["format", "--changed", "--since", "base"])
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_to_string(root.join("doc.qmd")).unwrap(),
+        "#   Base\n\nNew text.\n"
+    );
+}
+
+#[test]
+fn test_format_files_from_stdin() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::write(root.join("a.qmd"), "#   A\n").unwrap();
+    fs::write(root.join("b.md"), "# B\n").unwrap();
+    fs::write(root.join("c.qmd"), "#   C\n").unwrap();
+
+    cargo_bin_cmd!("panache")
+        .current_dir(root)
+        .args(["format", "--check", "-l", "--files-from", "-"])
+        .write_stdin("a.qmd\n\nb.md\r\n")
+        .assert()
+        .code(1)
+        .stdout("a.qmd\n");
+}
+
+#[test]
+fn test_format_check_writes_json_report() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::write(root.join("a.qmd"), "#   A\n\ntext\n").unwrap();
+    fs::write(root.join("b.qmd"), "# B\n").unwrap();
+
+    cargo_bin_cmd!("panache")
+        .current_dir(root)
+        .args([
+            "format",
+            "--check",
+            "--report",
+            "report.json",
+            "a.qmd",
+            "b.qmd",
+        ])
+        .assert()
+        .code(1);
+
+    let report: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(root.join("report.json")).unwrap()).unwrap();
+    assert_eq!(report["mode"], "check");
+    assert_eq!(report["files"][0]["path"], "a.qmd");
+    assert_eq!(report["files"][0]["status"], "formatted");
+    assert_eq!(report["files"][0]["hunks"], 1);
+    assert!(report["files"][0]["duration_ms"].is_number());
+    assert_eq!(report["files"][1]["status"], "unchanged");
+    assert_eq!(report["summary"]["formatted"], 1);
+    assert_eq!(report["summary"]["unchanged"], 1);
+    assert_eq!(
+        fs::read_to_string(root.join("a.qmd")).unwrap(),
+        "#   A\n\ntext\n"
+    );
+}
+
+#[test]
+fn test_format_report_records_errors_and_continues() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::write(root.join("bad.qmd"), b"# \xff\n").unwrap();
+    fs::write(root.join("good.qmd"), "#   Good\n").unwrap();
+
+    cargo_bin_cmd!("panache")
+        .current_dir(root)
+        .args(["format", "--report", "report.md", "bad.qmd", "good.qmd"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("Error: bad.qmd:"));
+
+    assert_eq!(
+        fs::read_to_string(root.join("good.qmd")).unwrap(),
+        "# Good\n"
+    );
+    let report = fs::read_to_string(root.join("report.md")).unwrap();
+    assert!(report.starts_with("# Format report\n"), "{report}");
+    assert!(report.contains("| `bad.qmd` | error | 0 |"), "{report}");
+    assert!(
+        report.contains("| `good.qmd` | formatted | 1 |"),
+        "{report}"
+    );
+    assert!(report.contains("## Errors\n\n- `bad.qmd`: "), "{report}");
+}
+
+#[test]
+fn test_format_unreadable_file_does_not_abort_the_run() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::write(root.join("a-bad.qmd"), b"# \xff\n").unwrap();
+    fs::write(root.join("b-good.qmd"), "#   Good\n").unwrap();
+
+    cargo_bin_cmd!("panache")
+        .current_dir(root)
+        .args(["format", "."])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("a-bad.qmd"));
+
+    assert_eq!(
+        fs::read_to_string(root.join("b-good.qmd")).unwrap(),
+        "# Good\n"
+    );
+}
+
+#[test]
+fn test_format_mdx_file_keeps_jsx() {
+    let temp_dir = TempDir::new().unwrap();
+    let doc = temp_dir.path().join("doc.mdx");
+    fs::write(
+        &doc,
+        "import Tabs from '@theme/Tabs';\nexport const meta = {\n  title:   'Install',\n};\n\n\
+         <Tabs groupId=\"os\">\n\nRun    *this*   command.\n\n</Tabs>\n",
+    )
+    .unwrap();
+
+    cargo_bin_cmd!("panache")
+        .current_dir(temp_dir.path())
+        .args(["format", "doc.mdx"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Skipping").not());
+
+    assert_eq!(
+        fs::read_to_string(&doc).unwrap(),
+        "import Tabs from '@theme/Tabs';\nexport const meta = {\n  title:   'Install',\n};\n\n\
+         <Tabs groupId=\"os\">\n\nRun *this* command.\n\n</Tabs>\n"
+    );
+}
+
+#[test]
+fn test_format_warns_about_unknown_frontmatter_options() {
+    let temp_dir = TempDir::new().unwrap();
+    let doc = temp_dir.path().join("doc.md");
+    fs::write(
+        &doc,
+        "---\npanache:\n  line-widht: 100\n  flavor: gfm\n---\n\nText.\n",
+    )
+    .unwrap();
+
+    cargo_bin_cmd!("panache")
+        .current_dir(temp_dir.path())
+        .args(["format", "--check", "doc.md"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "Warning: doc.md: ignoring unknown frontmatter option `panache.line-widht`; \
+             did you mean `line-width`?",
+        ))
+        .stderr(predicate::str::contains(
+            "ignoring frontmatter option `panache.flavor`: it changes how the document is parsed",
+        ));
+}
+
+#[test]
+fn test_format_check_missing_file_is_an_error() {
+    let temp_dir = TempDir::new().unwrap();
+
+    cargo_bin_cmd!("panache")
+        .current_dir(temp_dir.path())
+        .args(["format", "--check", "missing.md"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("Error:"));
+}
+
+#[test]
+fn test_format_verbose_warns_about_unclosed_fences() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = temp_dir.path().join("doc.qmd");
+    fs::write(&file, "Text.\n\n```r\nx <- 1\n").unwrap();
+
+    cargo_bin_cmd!("panache")
+        .args(["format", "--check", "--verbose"])
+        .arg(&file)
+        .assert()
+        .stderr(predicate::str::contains(
+            "doc.qmd:3:1: code fence is never closed",
+        ));
+
+    cargo_bin_cmd!("panache")
+        .args(["format", "--check"])
+        .arg(&file)
+        .assert()
+        .stderr(predicate::str::contains("never closed").not());
+}
+
+#[test]
+fn test_format_edits_json_prints_edits_without_writing() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = temp_dir.path().join("doc.qmd");
+    let input = "#   Title\n\nText.\n";
+    fs::write(&file, input).unwrap();
+
+    let output = cargo_bin_cmd!("panache")
+        .args(["format", "--edits", "json"])
+        .arg(&file)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let edits = &json["files"][0]["edits"];
+    assert_eq!(edits.as_array().unwrap().len(), 1);
+    assert_eq!(edits[0]["start"]["line"], 1);
+    assert_eq!(edits[0]["start"]["column"], 3);
+    assert_eq!(edits[0]["end"]["offset"], 4);
+    assert_eq!(edits[0]["new_text"], "");
+    assert_eq!(fs::read_to_string(&file).unwrap(), input);
+}
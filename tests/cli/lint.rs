@@ -139,6 +139,55 @@ fn test_lint_fix_skips_unfixable_diagnostics() {
     assert_eq!(after, original, "unfixable diagnostic must not modify file");
 }
 
+#[test]
+fn test_lint_fix_renames_dotted_chunk_options() {
+    let temp_dir = TempDir::new().unwrap();
+    let test_file = temp_dir.path().join("test.qmd");
+    fs::write(
+        &test_file,
+        "```{r}\n#| label: fig-cars\n#| fig.width: 6\nplot(cars)\n```\n",
+    )
+    .unwrap();
+
+    cargo_bin_cmd!("panache")
+        .args(["lint", "--no-cache", "--fix", test_file.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Fixed"));
+
+    let after = fs::read_to_string(&test_file).unwrap();
+    assert_eq!(
+        after,
+        "```{r}\n#| label: fig-cars\n#| fig-width: 6\nplot(cars)\n```\n"
+    );
+}
+
+#[test]
+fn test_lint_fix_respects_per_rule_fix_opt_out() {
+    // `fix = false` keeps the heading-hierarchy diagnostic but must stop
+    // `--fix` from rewriting the heading level.
+    let temp_dir = TempDir::new().unwrap();
+    let test_file = temp_dir.path().join("test.qmd");
+    let original = "# Heading\n\n### Subheading\n\nContent.\n";
+    fs::write(&test_file, original).unwrap();
+    fs::write(
+        temp_dir.path().join("panache.toml"),
+        "[lint.rules.heading-hierarchy]\nfix = false\n",
+    )
+    .unwrap();
+
+    cargo_bin_cmd!("panache")
+        .current_dir(temp_dir.path())
+        .args(["lint", "--no-cache", "--fix", "test.qmd"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("heading-hierarchy"))
+        .stdout(predicate::str::contains("Fixed").not());
+
+    let after = fs::read_to_string(&test_file).unwrap();
+    assert_eq!(after, original, "opted-out rule must not rewrite the file");
+}
+
 #[test]
 fn test_lint_fix_reports_remaining_when_some_fixed() {
     // Mixed case: heading-hierarchy is auto-fixable, missing-chunk-labels is not.
@@ -297,6 +346,34 @@ exclude = ["tests/"]
         .stdout(predicate::str::contains("No issues found in 1 file(s)"));
 }
 
+#[test]
+fn test_lint_directory_cli_exclude_keeps_config_excludes() {
+    let temp_dir = TempDir::new().unwrap();
+    let vendor_dir = temp_dir.path().join("vendor");
+    let tests_dir = temp_dir.path().join("tests");
+    fs::create_dir_all(&vendor_dir).unwrap();
+    fs::create_dir_all(&tests_dir).unwrap();
+    fs::write(
+        temp_dir.path().join(".panache.toml"),
+        "extend-exclude = [\"tests/\"]\n",
+    )
+    .unwrap();
+    fs::write(
+        temp_dir.path().join("doc.qmd"),
+        "# Heading\n\n## Subheading\n",
+    )
+    .unwrap();
+    fs::write(vendor_dir.join("upstream.md"), "# Heading\n\n### Skipped\n").unwrap();
+    fs::write(tests_dir.join("snapshot.md"), "# Heading\n\n### Skipped\n").unwrap();
+
+    cargo_bin_cmd!("panache")
+        .current_dir(temp_dir.path())
+        .args(["lint", ".", "--exclude", "vendor/"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No issues found in 1 file(s)"));
+}
+
 #[test]
 fn test_lint_directory_include_patterns_resolve_from_config_root() {
     let temp_dir = TempDir::new().unwrap();
@@ -440,6 +517,46 @@ fn test_lint_file_short_message_format() {
         .stdout(predicate::str::contains("= note:").not());
 }
 
+#[test]
+fn test_lint_grouped_message_format_aligns_columns() {
+    let temp_dir = TempDir::new().unwrap();
+    let test_file = temp_dir.path().join("grouped.qmd");
+    fs::write(&test_file, "# H1\n\n### H3\n\n# Again\n\n#### H4\n").unwrap();
+
+    let output = cargo_bin_cmd!("panache")
+        .args([
+            "lint",
+            "--message-format",
+            "grouped",
+            "--color",
+            "never",
+            test_file.to_str().unwrap(),
+        ])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(stdout.contains("grouped.qmd\n"), "{stdout}");
+    assert!(
+        stdout.contains(
+            "  3:1  warning  Heading level skipped from h1 to h3; expected h2  heading-hierarchy"
+        ),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains(
+            "  7:1  warning  Heading level skipped from h1 to h4; expected h2  heading-hierarchy"
+        ),
+        "{stdout}"
+    );
+    assert!(!stdout.contains("\x1b["), "{stdout}");
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .contains("Found 2 issue(s) across 1 file(s): 2 warnings")
+    );
+}
+
 #[test]
 fn test_lint_short_message_format_preserves_diagnostic_order() {
     let mut cmd = cargo_bin_cmd!("panache");
@@ -8,11 +8,21 @@
 //! - Error handling
 
 mod cache;
+mod check_links;
 mod common;
+mod config;
 mod debug;
+mod extract;
+mod filter;
 mod format;
 mod lint;
+mod organize;
 mod parse;
+mod render;
+mod stats;
+mod trust;
 
 #[cfg(feature = "lsp")]
 mod lsp;
+#[cfg(feature = "lsp")]
+mod mv;
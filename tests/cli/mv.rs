@@ -0,0 +1,146 @@
+//! Mv command tests
+
+use std::fs;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use predicates::prelude::*;
+use tempfile::TempDir;
+
+fn write_workspace(root: &std::path::Path) {
+    fs::create_dir_all(root.join("chapters")).unwrap();
+    fs::create_dir_all(root.join("assets")).unwrap();
+    fs::write(
+        root.join("index.qmd"),
+        "See [tables](chapters/tables.qmd#sec-one).\n\n{{< include chapters/tables.qmd >}}\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("chapters/tables.qmd"),
+        "![plot](../assets/plot.png)\n\nBack to [index](../index.qmd).\n",
+    )
+    .unwrap();
+    fs::write(root.join("assets/plot.png"), "").unwrap();
+}
+
+#[test]
+fn test_mv_renames_and_rewrites_links() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    write_workspace(root);
+
+    cargo_bin_cmd!("panache")
+        .current_dir(root)
+        .args(["mv", "chapters/tables.qmd", "tables.qmd"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Moved chapters/tables.qmd -> tables.qmd",
+        ));
+
+    assert!(!root.join("chapters/tables.qmd").exists());
+    assert_eq!(
+        fs::read_to_string(root.join("index.qmd")).unwrap(),
+        "See [tables](tables.qmd#sec-one).\n\n{{< include tables.qmd >}}\n"
+    );
+    assert_eq!(
+        fs::read_to_string(root.join("tables.qmd")).unwrap(),
+        "![plot](assets/plot.png)\n\nBack to [index](index.qmd).\n"
+    );
+}
+
+#[test]
+fn test_mv_into_new_directory() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    write_workspace(root);
+
+    cargo_bin_cmd!("panache")
+        .current_dir(root)
+        .args(["mv", "index.qmd", "book/start.qmd"])
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(root.join("book/start.qmd")).unwrap(),
+        "See [tables](../chapters/tables.qmd#sec-one).\n\n{{< include ../chapters/tables.qmd >}}\n"
+    );
+    assert_eq!(
+        fs::read_to_string(root.join("chapters/tables.qmd")).unwrap(),
+        "![plot](../assets/plot.png)\n\nBack to [index](../book/start.qmd).\n"
+    );
+}
+
+#[test]
+fn test_mv_dry_run_leaves_files_untouched() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    write_workspace(root);
+    let index_before = fs::read_to_string(root.join("index.qmd")).unwrap();
+
+    cargo_bin_cmd!("panache")
+        .current_dir(root)
+        .args(["mv", "--dry-run", "chapters/tables.qmd", "tables.qmd"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Would move chapters/tables.qmd -> tables.qmd",
+        ))
+        .stdout(predicate::str::contains(
+            "index.qmd:1:14: chapters/tables.qmd#sec-one -> tables.qmd#sec-one",
+        ))
+        .stdout(predicate::str::contains(
+            "chapters/tables.qmd:1:9: ../assets/plot.png -> assets/plot.png",
+        ));
+
+    assert!(root.join("chapters/tables.qmd").exists());
+    assert!(!root.join("tables.qmd").exists());
+    assert_eq!(
+        fs::read_to_string(root.join("index.qmd")).unwrap(),
+        index_before
+    );
+}
+
+#[test]
+fn test_mv_refuses_existing_destination() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    write_workspace(root);
+    fs::write(root.join("tables.qmd"), "taken\n").unwrap();
+
+    cargo_bin_cmd!("panache")
+        .current_dir(root)
+        .args(["mv", "chapters/tables.qmd", "tables.qmd"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("destination exists"));
+
+    assert!(root.join("chapters/tables.qmd").exists());
+}
+
+#[test]
+fn test_mv_rewrites_reference_definitions_and_autolinks() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("ch")).unwrap();
+    fs::write(root.join("ch/one.qmd"), "# One\n").unwrap();
+    fs::write(
+        root.join("index.qmd"),
+        "See [one][ref], [again][angled] and <ch/one.qmd>.\n\n\
+         [ref]: ch/one.qmd \"Chapter one\"\n\
+         [angled]: <ch/one.qmd#sec>\n",
+    )
+    .unwrap();
+
+    cargo_bin_cmd!("panache")
+        .current_dir(root)
+        .args(["mv", "ch/one.qmd", "ch/two.qmd"])
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(root.join("index.qmd")).unwrap(),
+        "See [one][ref], [again][angled] and <ch/two.qmd>.\n\n\
+         [ref]: ch/two.qmd \"Chapter one\"\n\
+         [angled]: <ch/two.qmd#sec>\n"
+    );
+}
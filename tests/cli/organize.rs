@@ -0,0 +1,106 @@
+//! Organize subcommand tests
+
+use std::fs;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use predicates::prelude::*;
+use tempfile::TempDir;
+
+const FAQ: &str = "\
+# Project
+
+## FAQ
+
+### Why panache?
+
+Speed.
+
+### How do I install it?
+
+Use cargo.
+
+## License
+
+MIT.
+";
+
+const SORTED_FAQ: &str = "\
+# Project
+
+## FAQ
+
+### How do I install it?
+
+Use cargo.
+
+### Why panache?
+
+Speed.
+
+## License
+
+MIT.
+";
+
+#[test]
+fn test_organize_sorts_stdin_to_stdout() {
+    cargo_bin_cmd!("panache")
+        .args(["organize", "--sort-headings-under", "## FAQ"])
+        .write_stdin(FAQ)
+        .assert()
+        .success()
+        .stdout(SORTED_FAQ);
+}
+
+#[test]
+fn test_organize_check_reports_without_writing() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("README.md");
+    fs::write(&path, FAQ).unwrap();
+
+    cargo_bin_cmd!("panache")
+        .current_dir(temp_dir.path())
+        .args([
+            "organize",
+            "--check",
+            "--sort-headings-under",
+            "FAQ",
+            "README.md",
+        ])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("Would organize: README.md"));
+    assert_eq!(fs::read_to_string(&path).unwrap(), FAQ);
+}
+
+#[test]
+fn test_organize_uses_config_headings_and_rewrites_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("README.md");
+    fs::write(&path, FAQ).unwrap();
+    fs::write(
+        temp_dir.path().join("panache.toml"),
+        "[organize]\nsort-headings-under = [\"## FAQ\"]\n",
+    )
+    .unwrap();
+
+    cargo_bin_cmd!("panache")
+        .current_dir(temp_dir.path())
+        .args(["organize", "README.md"])
+        .assert()
+        .success()
+        .stdout("1 file organized, 0 files left unchanged\n");
+    assert_eq!(fs::read_to_string(&path).unwrap(), SORTED_FAQ);
+}
+
+#[test]
+fn test_organize_fails_for_unknown_heading() {
+    cargo_bin_cmd!("panache")
+        .args(["organize", "--sort-headings-under", "## Changelog"])
+        .write_stdin(FAQ)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "no heading matches '## Changelog'",
+        ));
+}
@@ -108,6 +108,20 @@ fn test_parse_json_output() {
     assert!(json_output.contains("\"kind\""));
     assert!(json_output.contains("\"DOCUMENT\""));
     assert!(json_output.contains("\"text\""));
+
+    let value: serde_json::Value = serde_json::from_str(&json_output).unwrap();
+    assert_eq!(value["id"], 0);
+    let heading = &value["children"][0];
+    assert_eq!(heading["kind"], "HEADING");
+    assert_eq!(heading["id"], 1);
+    let paragraph = value["children"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|child| child["kind"] == "PARAGRAPH")
+        .unwrap();
+    assert_eq!(paragraph["position"]["start"]["line"], 2);
+    assert_eq!(paragraph["position"]["start"]["column_utf16"], 0);
 }
 
 #[test]
@@ -274,3 +288,33 @@ fn test_parse_to_pandoc_ast_with_json_writes_both() {
     let json_output = fs::read_to_string(&output_file).unwrap();
     assert!(json_output.contains("\"DOCUMENT\""));
 }
+
+#[test]
+fn test_parse_extension_flags() {
+    let input = "Text[^1]\n\n[^1]: Note\n";
+
+    cargo_bin_cmd!("panache")
+        .args(["--isolated", "parse"])
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("FOOTNOTE_DEFINITION"));
+
+    cargo_bin_cmd!("panache")
+        .args(["--isolated", "parse", "--disable-extension", "footnotes"])
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("FOOTNOTE_DEFINITION").not());
+}
+
+#[test]
+fn test_parse_rejects_unknown_extension_flag() {
+    cargo_bin_cmd!("panache")
+        .args(["--isolated", "parse", "--enable-extension", "fancy_listz"])
+        .write_stdin("Text\n")
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("unknown extension `fancy-listz`"))
+        .stderr(predicate::str::contains("did you mean `fancy-lists`?"));
+}
@@ -0,0 +1,57 @@
+//! Render subcommand tests
+
+use std::fs;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use tempfile::TempDir;
+
+#[test]
+fn test_render_plain_strips_markup() {
+    let input = "\
+---
+title: Hidden
+---
+
+# Methods
+
+We fit a *linear* model; see [the docs](https://example.com).
+
+<!-- a comment -->
+
+| Term | Estimate |
+|------|----------|
+| x    | 0.5      |
+";
+    cargo_bin_cmd!("panache")
+        .args(["render", "--to", "plain"])
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout(
+            "\
+Methods
+
+We fit a linear model; see the docs (https://example.com).
+
+Term  Estimate
+----  --------
+x     0.5
+",
+        );
+}
+
+#[test]
+fn test_render_separates_files_with_a_blank_line() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::write(root.join("one.md"), "**One**\n").unwrap();
+    fs::write(root.join("two.md"), "- two\n").unwrap();
+
+    cargo_bin_cmd!("panache")
+        .arg("render")
+        .arg(root.join("one.md"))
+        .arg(root.join("two.md"))
+        .assert()
+        .success()
+        .stdout("One\n\n- two\n");
+}
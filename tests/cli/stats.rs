@@ -0,0 +1,86 @@
+//! Stats subcommand tests
+
+use std::fs;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use tempfile::TempDir;
+
+const CHAPTER: &str = "\
+# Methods
+
+We fit a *linear* model; see [the docs](https://example.com).[^1]
+
+## Data
+
+![A plot](plot.png)
+
+```{r}
+fit <- lm(y ~ x)
+```
+
+[^1]: Ordinary least squares.
+";
+
+#[test]
+fn test_stats_prints_a_table() {
+    cargo_bin_cmd!("panache")
+        .args(["stats", "--stdin-filename", "chapter.qmd"])
+        .write_stdin(CHAPTER)
+        .assert()
+        .success()
+        .stdout(
+            "chapter.qmd
+  words         15
+  headings      2 (h1: 1, h2: 1)
+  code cells    1 (r: 1)
+  links         1
+  images        1
+  footnotes     1
+  longest line  65 columns (line 3)
+",
+        );
+}
+
+#[test]
+fn test_stats_json_has_one_object_per_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::write(root.join("one.qmd"), CHAPTER).unwrap();
+    fs::write(root.join("two.qmd"), "Just three words.\n").unwrap();
+
+    let output = cargo_bin_cmd!("panache")
+        .current_dir(root)
+        .args(["stats", "--output-format", "json", "one.qmd", "two.qmd"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stats: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let files = stats.as_array().unwrap();
+    assert_eq!(files.len(), 2);
+    assert_eq!(files[0]["path"], "one.qmd");
+    assert_eq!(files[0]["headings"], serde_json::json!([1, 1, 0, 0, 0, 0]));
+    assert_eq!(files[0]["code_cells"]["r"], 1);
+    assert_eq!(files[1]["words"], 3);
+    assert_eq!(files[1]["longest_line"]["width"], 17);
+}
+
+#[test]
+fn test_stats_table_totals_several_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::write(root.join("one.qmd"), "# One\n\nTwo words.\n").unwrap();
+    fs::write(root.join("two.qmd"), "# Two\n\nThree more words.\n").unwrap();
+
+    let output = cargo_bin_cmd!("panache")
+        .current_dir(root)
+        .args(["stats", "one.qmd", "two.qmd"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.ends_with("total\n  words         7\n  headings      2 (h1: 2)\n  code cells    0\n  links         0\n  images        0\n  footnotes     0\n"),
+        "{stdout}"
+    );
+}
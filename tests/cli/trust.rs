@@ -0,0 +1,135 @@
+//! Workspace trust (`--safe` and `panache trust`) tests
+
+use std::fs;
+use std::path::Path;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use predicates::prelude::*;
+use tempfile::TempDir;
+
+const UNTRUSTED_CONFIG: &str = r#"[formatters]
+test = "upper"
+
+[formatters.upper]
+cmd = "tr"
+args = ["[:lower:]", "[:upper:]"]
+"#;
+
+const DOCUMENT: &str = "```test\nhello\n```\n";
+
+fn setup_workspace(temp_dir: &TempDir) -> (std::path::PathBuf, std::path::PathBuf) {
+    let workspace = temp_dir.path().join("workspace");
+    fs::create_dir_all(workspace.join(".git")).unwrap();
+    fs::write(workspace.join("panache.toml"), UNTRUSTED_CONFIG).unwrap();
+    let doc = workspace.join("doc.md");
+    fs::write(&doc, DOCUMENT).unwrap();
+    (workspace, doc)
+}
+
+fn format_safe(workspace: &Path, state_home: &Path, doc: &Path) -> assert_cmd::assert::Assert {
+    cargo_bin_cmd!("panache")
+        .current_dir(workspace)
+        .env("PANACHE_STATE_HOME", state_home)
+        .args(["format", "--safe", "--no-cache", doc.to_str().unwrap()])
+        .assert()
+}
+
+#[cfg(unix)]
+#[test]
+fn test_safe_mode_skips_untrusted_formatters_until_trusted() {
+    let temp_dir = TempDir::new().unwrap();
+    let state_home = temp_dir.path().join("state");
+    let (workspace, doc) = setup_workspace(&temp_dir);
+
+    format_safe(&workspace, &state_home, &doc)
+        .success()
+        .stderr(predicate::str::contains("panache trust"));
+    assert_eq!(fs::read_to_string(&doc).unwrap(), DOCUMENT);
+
+    cargo_bin_cmd!("panache")
+        .current_dir(&workspace)
+        .env("PANACHE_STATE_HOME", &state_home)
+        .arg("trust")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Trusted"));
+
+    format_safe(&workspace, &state_home, &doc).success();
+    assert_eq!(fs::read_to_string(&doc).unwrap(), "```test\nHELLO\n```\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_changed_commands_require_new_approval() {
+    let temp_dir = TempDir::new().unwrap();
+    let state_home = temp_dir.path().join("state");
+    let (workspace, doc) = setup_workspace(&temp_dir);
+
+    cargo_bin_cmd!("panache")
+        .env("PANACHE_STATE_HOME", &state_home)
+        .args(["trust", workspace.to_str().unwrap()])
+        .assert()
+        .success();
+
+    fs::write(
+        workspace.join("panache.toml"),
+        UNTRUSTED_CONFIG.replace("[:upper:]", "[:lower:]X"),
+    )
+    .unwrap();
+
+    format_safe(&workspace, &state_home, &doc).success();
+    assert_eq!(fs::read_to_string(&doc).unwrap(), DOCUMENT);
+}
+
+#[test]
+fn test_trust_revoke_reports_untrusted_config() {
+    let temp_dir = TempDir::new().unwrap();
+    let state_home = temp_dir.path().join("state");
+    let (workspace, _) = setup_workspace(&temp_dir);
+
+    cargo_bin_cmd!("panache")
+        .current_dir(&workspace)
+        .env("PANACHE_STATE_HOME", &state_home)
+        .args(["trust", "--revoke"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("was not trusted"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_project_config_cannot_loosen_the_user_sandbox() {
+    let temp_dir = TempDir::new().unwrap();
+    let (workspace, doc) = setup_workspace(&temp_dir);
+    fs::write(
+        workspace.join("panache.toml"),
+        format!("{UNTRUSTED_CONFIG}\n[external-sandbox]\nrestrict-commands = false\n"),
+    )
+    .unwrap();
+    let xdg = temp_dir.path().join("xdg");
+    fs::create_dir_all(xdg.join("panache")).unwrap();
+    fs::write(
+        xdg.join("panache").join("config.toml"),
+        "[external-sandbox]\nrestrict-commands = true\n",
+    )
+    .unwrap();
+
+    let format = |extra: &[&str]| {
+        cargo_bin_cmd!("panache")
+            .current_dir(&workspace)
+            .env("XDG_CONFIG_HOME", &xdg)
+            .env_remove("PANACHE_SANDBOX")
+            .args(["format", "--no-cache"])
+            .args(extra)
+            .arg(&doc)
+            .assert()
+    };
+
+    format(&[])
+        .success()
+        .stderr(predicate::str::contains("not allowlisted"));
+    assert_eq!(fs::read_to_string(&doc).unwrap(), DOCUMENT);
+
+    format(&["--sandbox", "allowed-commands=tr"]).success();
+    assert_eq!(fs::read_to_string(&doc).unwrap(), "```test\nHELLO\n```\n");
+}
@@ -1,6 +1,6 @@
-use panache::config::{Extensions, Flavor};
+use panache::config::{Extensions, ExternalSandboxConfig, Flavor};
 use panache::{Config, format};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 #[test]
 fn code_block_with_shfmt() {
@@ -10,13 +10,14 @@ fn code_block_with_shfmt() {
         return;
     }
 
-    let mut formatters = HashMap::new();
+    let mut formatters = BTreeMap::new();
     formatters.insert(
         "sh".to_string(),
         vec![panache::config::FormatterConfig {
             cmd: "shfmt".to_string(),
             args: vec![],
             stdin: true,
+            timeout_ms: None,
         }],
     );
 
@@ -51,13 +52,14 @@ fn formatter_key_resolves_via_language_alias() {
         return;
     }
 
-    let mut formatters = HashMap::new();
+    let mut formatters = BTreeMap::new();
     formatters.insert(
         "bash".to_string(),
         vec![panache::config::FormatterConfig {
             cmd: "shfmt".to_string(),
             args: vec![],
             stdin: true,
+            timeout_ms: None,
         }],
     );
 
@@ -93,13 +95,14 @@ fn identical_blocks_are_deduplicated_and_all_formatted() {
         return;
     }
 
-    let mut formatters = HashMap::new();
+    let mut formatters = BTreeMap::new();
     formatters.insert(
         "sh".to_string(),
         vec![panache::config::FormatterConfig {
             cmd: "shfmt".to_string(),
             args: vec![],
             stdin: true,
+            timeout_ms: None,
         }],
     );
 
@@ -133,13 +136,14 @@ fn identical_blocks_are_deduplicated_and_all_formatted() {
 #[test]
 fn code_block_with_external_formatter() {
     // Use 'tr' to uppercase as a simple mock formatter
-    let mut formatters = HashMap::new();
+    let mut formatters = BTreeMap::new();
     formatters.insert(
         "test".to_string(),
         vec![panache::config::FormatterConfig {
             cmd: "tr".to_string(),
             args: vec!["[:lower:]".to_string(), "[:upper:]".to_string()],
             stdin: true,
+            timeout_ms: None,
         }],
     );
 
@@ -170,13 +174,14 @@ fn myst_directive_body_with_external_formatter() {
     // A verbatim MyST `{code-block}` body should be routed to the external
     // formatter keyed by the directive argument (the language), like a fenced
     // code block. Use `tr` to uppercase as a deterministic mock formatter.
-    let mut formatters = HashMap::new();
+    let mut formatters = BTreeMap::new();
     formatters.insert(
         "test".to_string(),
         vec![panache::config::FormatterConfig {
             cmd: "tr".to_string(),
             args: vec!["[:lower:]".to_string(), "[:upper:]".to_string()],
             stdin: true,
+            timeout_ms: None,
         }],
     );
 
@@ -216,13 +221,14 @@ fn formatter_args_substitute_lang_placeholder() {
         return;
     }
 
-    let mut formatters = HashMap::new();
+    let mut formatters = BTreeMap::new();
     formatters.insert(
         "python".to_string(),
         vec![panache::config::FormatterConfig {
             cmd: "sed".to_string(),
             args: vec!["s/{lang}/REPL/g".to_string()],
             stdin: true,
+            timeout_ms: None,
         }],
     );
 
@@ -251,13 +257,14 @@ print("python rocks")
 #[test]
 fn untagged_code_block_with_empty_string_formatter_key() {
     // `[formatters.""]` matches only truly untagged blocks, never ```plain.
-    let mut formatters = HashMap::new();
+    let mut formatters = BTreeMap::new();
     formatters.insert(
         String::new(),
         vec![panache::config::FormatterConfig {
             cmd: "tr".to_string(),
             args: vec!["[:lower:]".to_string(), "[:upper:]".to_string()],
             stdin: true,
+            timeout_ms: None,
         }],
     );
 
@@ -299,7 +306,7 @@ plain tagged block
 fn code_block_without_formatter_unchanged() {
     // Create config with empty formatters (no built-in defaults)
     let config = Config {
-        formatters: HashMap::new(),
+        formatters: BTreeMap::new(),
         ..Default::default()
     };
 
@@ -321,7 +328,7 @@ hello world
 fn code_block_with_disabled_formatter() {
     // In the new format, disabled formatters are handled by not including them in the map
     // This test now verifies that an empty formatter list means no formatting
-    let formatters = HashMap::new(); // No formatter configured
+    let formatters = BTreeMap::new(); // No formatter configured
 
     let config = Config {
         flavor: Flavor::Quarto,
@@ -346,13 +353,14 @@ hello world
 
 #[test]
 fn code_block_with_failing_formatter() {
-    let mut formatters = HashMap::new();
+    let mut formatters = BTreeMap::new();
     formatters.insert(
         "test".to_string(),
         vec![panache::config::FormatterConfig {
             cmd: "false".to_string(), // Always fails
             args: vec![],
             stdin: true,
+            timeout_ms: None,
         }],
     );
 
@@ -375,15 +383,128 @@ hello world
     assert!(!output.contains("HELLO WORLD"));
 }
 
+fn uppercase_formatters() -> BTreeMap<String, Vec<panache::config::FormatterConfig>> {
+    let mut formatters = BTreeMap::new();
+    formatters.insert(
+        "test".to_string(),
+        vec![panache::config::FormatterConfig {
+            cmd: "tr".to_string(),
+            args: vec!["[:lower:]".to_string(), "[:upper:]".to_string()],
+            stdin: true,
+            timeout_ms: None,
+        }],
+    );
+    formatters
+}
+
+#[test]
+fn restricted_sandbox_skips_non_allowlisted_formatter() {
+    let config = Config {
+        formatters: uppercase_formatters(),
+        external_sandbox: ExternalSandboxConfig {
+            restrict_commands: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let input = "```test\nnot on the allowlist\n```\n";
+    let output = format(input, Some(config), None);
+
+    // `tr` is not a preset command, so the block is left alone
+    assert!(output.contains("not on the allowlist"));
+}
+
+#[test]
+fn restricted_sandbox_runs_allowlisted_formatter() {
+    let config = Config {
+        formatters: uppercase_formatters(),
+        external_sandbox: ExternalSandboxConfig {
+            restrict_commands: true,
+            allowed_commands: vec!["tr".to_string()],
+            scrub_env: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let input = "```test\nexplicitly allowed\n```\n";
+    let output = format(input, Some(config), None);
+
+    assert!(output.contains("EXPLICITLY ALLOWED"));
+}
+
+#[test]
+fn sandbox_output_limit_leaves_block_unchanged() {
+    let config = Config {
+        formatters: uppercase_formatters(),
+        external_sandbox: ExternalSandboxConfig {
+            max_output_bytes: Some(4),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let input = "```test\noutput over the limit\n```\n";
+    let output = format(input, Some(config), None);
+
+    assert!(output.contains("output over the limit"));
+    assert!(!output.contains("OUTPUT OVER THE LIMIT"));
+}
+
+#[test]
+#[cfg(unix)]
+fn sandbox_memory_limit_still_runs_formatter() {
+    let config = Config {
+        formatters: uppercase_formatters(),
+        external_sandbox: ExternalSandboxConfig {
+            max_memory_mb: Some(512),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let input = "```test\nunder the memory cap\n```\n";
+    let output = format(input, Some(config), None);
+
+    assert!(output.contains("UNDER THE MEMORY CAP"));
+}
+
+#[test]
+#[cfg(unix)]
+fn formatter_timeout_ms_leaves_slow_block_unchanged() {
+    let mut formatters = BTreeMap::new();
+    formatters.insert(
+        "test".to_string(),
+        vec![panache::config::FormatterConfig {
+            cmd: "sh".to_string(),
+            args: vec!["-c".to_string(), "sleep 5; tr a-z A-Z".to_string()],
+            stdin: true,
+            timeout_ms: Some(100),
+        }],
+    );
+    let config = Config {
+        formatters,
+        ..Default::default()
+    };
+
+    let start = std::time::Instant::now();
+    let output = format("```test\ntoo slow\n```\n", Some(config), None);
+
+    assert!(output.contains("too slow"));
+    assert!(start.elapsed() < std::time::Duration::from_secs(5));
+}
+
 #[test]
 fn python_hashpipe_prefix_preserved_with_external_formatter() {
-    let mut formatters = HashMap::new();
+    let mut formatters = BTreeMap::new();
     formatters.insert(
         "python".to_string(),
         vec![panache::config::FormatterConfig {
             cmd: "tr".to_string(),
             args: vec!["[:lower:]".to_string(), "[:upper:]".to_string()],
             stdin: true,
+            timeout_ms: None,
         }],
     );
 
@@ -420,13 +541,14 @@ fn r_air_formats_equals_spacing_in_quarto_r_block() {
         return;
     }
 
-    let mut formatters = HashMap::new();
+    let mut formatters = BTreeMap::new();
     formatters.insert(
         "r".to_string(),
         vec![panache::config::FormatterConfig {
             cmd: "air".to_string(),
             args: vec!["format".to_string(), "{}".to_string()],
             stdin: false,
+            timeout_ms: None,
         }],
     );
 
@@ -459,13 +581,14 @@ fn r_air_preserves_single_blank_line_between_hashpipe_options_and_code() {
         return;
     }
 
-    let mut formatters = HashMap::new();
+    let mut formatters = BTreeMap::new();
     formatters.insert(
         "r".to_string(),
         vec![panache::config::FormatterConfig {
             cmd: "air".to_string(),
             args: vec!["format".to_string(), "{}".to_string()],
             stdin: false,
+            timeout_ms: None,
         }],
     );
 
@@ -498,3 +621,102 @@ fn r_air_preserves_single_blank_line_between_hashpipe_options_and_code() {
     let output_twice = format(&output, Some(config), None);
     assert_eq!(output, output_twice, "Formatting should be idempotent");
 }
+
+#[test]
+fn panache_ignore_class_skips_external_formatting() {
+    let config = Config {
+        formatters: uppercase_formatters(),
+        ..Default::default()
+    };
+
+    let input = "```test\nhello\n```\n\n```{.test .panache-ignore}\nhello\n```\n";
+    let output = format(input, Some(config), None);
+
+    // The identical first block is formatted; the opted-out one is not, also
+    // once its info string is in the canonical `test {.panache-ignore}` form.
+    assert!(output.contains("```test\nHELLO\n```"), "{output}");
+    assert!(
+        output.contains("```test {.panache-ignore}\nhello\n```"),
+        "{output}"
+    );
+    let config = Config {
+        formatters: uppercase_formatters(),
+        ..Default::default()
+    };
+    assert_eq!(format(&output, Some(config), None), output);
+}
+
+#[test]
+fn panache_false_chunk_option_skips_external_formatting() {
+    let mut formatters = uppercase_formatters();
+    let python = formatters.remove("test").expect("test formatter");
+    formatters.insert("python".to_string(), python);
+    let config = Config {
+        flavor: Flavor::Quarto,
+        extensions: Extensions::for_flavor(Flavor::Quarto),
+        formatters,
+        ..Default::default()
+    };
+
+    let input = "```{python}\n#| panache: false\nx = 1\n```\n\n```{python}\ny = 2\n```\n";
+    let output = format(input, Some(config), None);
+
+    assert!(output.contains("#| panache: false\n\nx = 1\n"), "{output}");
+    assert!(output.contains("Y = 2"), "{output}");
+}
+
+#[test]
+fn ignore_region_skips_external_formatting() {
+    let config = Config {
+        formatters: uppercase_formatters(),
+        ..Default::default()
+    };
+
+    let input = "<!-- panache-ignore-format-start -->\n\n```test\nkeep me\n```\n\n<!-- panache-ignore-format-end -->\n\n```test\nformat me\n```\n";
+    let output = format(input, Some(config), None);
+
+    assert!(output.contains("keep me"), "{output}");
+    assert!(output.contains("FORMAT ME"), "{output}");
+}
+
+#[test]
+fn code_formatter_callback_replaces_external_commands() {
+    panache::formatter::set_code_formatter(Some(Box::new(|language, code| {
+        (language == "python").then(|| code.to_uppercase())
+    })));
+
+    let config = Config {
+        flavor: Flavor::Quarto,
+        extensions: Extensions::for_flavor(Flavor::Quarto),
+        ..Default::default()
+    };
+    let input = "```{python}\n#| echo: false\nx = 1\n```\n\n```r\ny <- 2\n```\n";
+    let output = format(input, Some(config), None);
+    panache::formatter::set_code_formatter(None);
+
+    assert!(output.contains("#| echo: false\n\nX = 1\n"), "{output}");
+    assert!(output.contains("y <- 2"), "{output}");
+}
+
+#[test]
+fn formatting_is_deterministic_across_runs() {
+    let mut formatters = uppercase_formatters();
+    for language in ["alpha", "beta", "gamma", "delta"] {
+        formatters.insert(language.to_string(), formatters["test"].clone());
+    }
+    let config = Config {
+        formatters,
+        languages: BTreeMap::from([
+            ("a".to_string(), "alpha".to_string()),
+            ("b".to_string(), "beta".to_string()),
+        ]),
+        ..Default::default()
+    };
+
+    let input = "# Title\n\nSome *text* with a footnote.[^1]\n\n```alpha\none\n```\n\n- item\n\n    ```b\n    two\n    ```\n\n| a | b |\n|---|---|\n| 1 | 2 |\n\n```gamma\nthree\n```\n\n```a\none\n```\n\n```delta\nfour\n```\n\n[^1]: Note.\n";
+    let first = format(input, Some(config.clone()), None);
+    assert!(first.contains("ONE") && first.contains("FOUR"), "{first}");
+    for _ in 0..100 {
+        assert_eq!(format(input, Some(config.clone()), None), first);
+    }
+}
@@ -3,7 +3,7 @@
 #[cfg(test)]
 mod tests {
     use panache::{Config, linter, parse};
-    use std::collections::HashMap;
+    use std::collections::BTreeMap;
 
     #[test]
     fn test_jarl_linter_integration() {
@@ -23,7 +23,7 @@ result <- TRUE
 
         // Create config with jarl enabled
         let mut config = Config::default();
-        let mut linters = HashMap::new();
+        let mut linters = BTreeMap::new();
         linters.insert("r".to_string(), "jarl".to_string());
         config.linters = linters;
 
@@ -69,7 +69,7 @@ any(is.na(y))
 "#;
 
         let mut config = Config::default();
-        let mut linters = HashMap::new();
+        let mut linters = BTreeMap::new();
         linters.insert("r".to_string(), "jarl".to_string());
         config.linters = linters;
 
@@ -111,7 +111,7 @@ any(is.na(y))
             extensions: Extensions::for_flavor(Flavor::Myst),
             ..Default::default()
         };
-        let mut linters = HashMap::new();
+        let mut linters = BTreeMap::new();
         linters.insert("python".to_string(), "ruff".to_string());
         config.linters = linters;
 
@@ -160,7 +160,7 @@ import os
 "#;
 
         let mut config = Config::default();
-        let mut linters = HashMap::new();
+        let mut linters = BTreeMap::new();
         linters.insert("python".to_string(), "ruff".to_string());
         config.linters = linters;
 
@@ -194,7 +194,7 @@ print("ok")
 "#;
 
         let mut config = Config::default();
-        let mut linters = HashMap::new();
+        let mut linters = BTreeMap::new();
         linters.insert("python".to_string(), "ruff".to_string());
         config.linters = linters;
 
@@ -251,7 +251,7 @@ echo $UNSET
 "#;
 
         let mut config = Config::default();
-        let mut linters = HashMap::new();
+        let mut linters = BTreeMap::new();
         linters.insert("sh".to_string(), "shellcheck".to_string());
         config.linters = linters;
 
@@ -298,7 +298,7 @@ echo $UNSET
 "#;
 
         let mut config = Config::default();
-        let mut linters = HashMap::new();
+        let mut linters = BTreeMap::new();
         linters.insert("python".to_string(), "ruff".to_string());
         linters.insert("sh".to_string(), "shellcheck".to_string());
         config.linters = linters;
@@ -334,7 +334,7 @@ echo "hello"
 "#;
 
         let mut config = Config::default();
-        let mut linters = HashMap::new();
+        let mut linters = BTreeMap::new();
         linters.insert("sh".to_string(), "shellcheck".to_string());
         config.linters = linters;
 
@@ -363,7 +363,7 @@ echo $UNSET
 "#;
 
         let mut config = Config::default();
-        let mut linters = HashMap::new();
+        let mut linters = BTreeMap::new();
         linters.insert("sh".to_string(), "shellcheck".to_string());
         config.linters = linters;
 
@@ -418,7 +418,7 @@ console.log(1)
 "#;
 
         let mut config = Config::default();
-        let mut linters = HashMap::new();
+        let mut linters = BTreeMap::new();
         linters.insert("js".to_string(), "eslint".to_string());
         config.linters = linters;
 
@@ -457,7 +457,7 @@ console.log(1)
 "#;
 
         let mut config = Config::default();
-        let mut linters = HashMap::new();
+        let mut linters = BTreeMap::new();
         linters.insert("js".to_string(), "eslint".to_string());
         config.linters = linters;
 
@@ -515,7 +515,7 @@ func main() {
 "#;
 
         let mut config = Config::default();
-        let mut linters = HashMap::new();
+        let mut linters = BTreeMap::new();
         linters.insert("go".to_string(), "staticcheck".to_string());
         config.linters = linters;
 
@@ -552,7 +552,7 @@ fn main() {
 "#;
 
         let mut config = Config::default();
-        let mut linters = HashMap::new();
+        let mut linters = BTreeMap::new();
         linters.insert("rust".to_string(), "clippy".to_string());
         config.linters = linters;
 
@@ -582,7 +582,7 @@ x <- 1
 "#;
 
         let mut config = Config::default();
-        let mut linters = HashMap::new();
+        let mut linters = BTreeMap::new();
         linters.insert("r".to_string(), "unknown_linter_12345".to_string());
         config.linters = linters;
 
@@ -603,7 +603,7 @@ import os
 "#;
 
         let mut config = Config::default();
-        let mut linters = HashMap::new();
+        let mut linters = BTreeMap::new();
         linters.insert("python".to_string(), "jarl".to_string());
         config.linters = linters;
 
@@ -645,7 +645,7 @@ More text.
 "#;
 
         let mut config = Config::default();
-        let mut linters = HashMap::new();
+        let mut linters = BTreeMap::new();
         linters.insert("r".to_string(), "jarl".to_string());
         config.linters = linters;
 
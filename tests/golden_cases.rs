@@ -10,7 +10,7 @@
 use panache::{
     Config,
     config::{Extensions, Flavor, FormatterExtensions},
-    format,
+    format, format_inline, format_to,
 };
 use std::{
     fs,
@@ -120,6 +120,16 @@ fn run_golden_case(case_name: &str) {
     let output_twice = format(&output, config.clone(), None);
     similar_asserts::assert_eq!(output, output_twice, "idempotency: {}", case_name);
 
+    // The streaming API must produce byte-identical output
+    let mut streamed = Vec::new();
+    format_to(&input, config.clone(), &mut streamed).unwrap();
+    similar_asserts::assert_eq!(
+        output,
+        String::from_utf8(streamed).unwrap(),
+        "streaming: {}",
+        case_name
+    );
+
     if update_expected {
         fs::write(&expected_path, &output).unwrap();
         return;
@@ -130,6 +140,48 @@ fn run_golden_case(case_name: &str) {
     similar_asserts::assert_eq!(expected, output, "case: {}", case_name);
 }
 
+#[test]
+fn streaming_matches_format_on_large_document() {
+    // Large enough to cross the formatter's chunk flush threshold several times,
+    // with frontmatter (spliced after formatting), CRLF line endings (converted
+    // per chunk), and trailing blank lines (trimmed from the final chunk).
+    let mut input = String::from("---\r\ntitle:   Large\r\n---\r\n\r\n");
+    for i in 0..4000 {
+        input.push_str(&format!(
+            "## Section {i}\r\n\r\nSome *emphasis* and a   long line that keeps going well past the default line width so it wraps.\r\n\r\n- item {i}\r\n- another   item\r\n\r\n"
+        ));
+    }
+    input.push_str("\r\n\r\n\r\n");
+
+    let expected = format(&input, None, None);
+    let mut streamed = Vec::new();
+    format_to(&input, None, &mut streamed).unwrap();
+    let streamed = String::from_utf8(streamed).unwrap();
+
+    assert!(expected.len() > 4 * 64 * 1024);
+    similar_asserts::assert_eq!(expected, streamed);
+}
+
+#[test]
+fn format_inline_formats_fragment_without_block_structure() {
+    let config = Config::default();
+    assert_eq!(
+        format_inline("  A caption\nthat   spans lines  ", &config),
+        "A caption that spans lines"
+    );
+    // Block openers stay inline text rather than becoming a heading or list.
+    assert!(!format_inline("# Not a heading", &config).is_empty());
+    assert_eq!(
+        format_inline("Results for\n- group A", &config),
+        format_inline("Results for - group A", &config)
+    );
+    assert_eq!(format_inline(" \n ", &config), "");
+
+    let once = format_inline("Title with *emphasis* and `code`", &config);
+    assert_eq!(format_inline(&once, &config), once);
+    assert!(!once.ends_with('\n'));
+}
+
 /// Macro to generate individual test functions for each golden case.
 ///
 /// Usage: `golden_test_cases!(case1, case2, case3);`
@@ -159,12 +211,15 @@ golden_test_cases!(
     myst_toctree,
     myst_block_break,
     myst_code_block_body,
+    obsidian,
+    mdx_tolerance,
     blankline_concatenation,
     blockquote_depth_change,
     blockquote_html_block,
     blockquote_in_fenced_div_idempotency_310,
     blockquote_list_blanks,
     blockquote_list_blockquote,
+    blockquote_nested_list,
     blockquote_pipe_table,
     blockquote_pipe_table_caption,
     blockquotes,
@@ -172,7 +227,9 @@ golden_test_cases!(
     bookdown,
     chunk_options_complex,
     code_blocks_executable,
+    code_blocks_explicit_style,
     code_blocks_raw,
+    code_blocks_shortcut_style,
     code_spans,
     code_spans_unmatched_backtick_run_commonmark,
     commonmark_code_fence_info_string_preserved,
@@ -436,12 +493,14 @@ golden_test_cases!(
     semantic_wrap_abbreviations,
     table_with_caption,
     table_caption_duplicate,
+    table_caption_inline_formatting,
     tables_sequential,
     tab_handling,
     tab_preserve,
     trailing_blanklines,
     umlauts,
     unicode,
+    utf8_bom_frontmatter,
     issue_171_gfm_inline_links,
     issue_231_gfm_tilde_idempotency,
     issue_172_hashpipe_inline_list_idempotency,
@@ -506,4 +565,5 @@ golden_test_cases!(
     yaml_metadata_dots_closer,
     yaml_metadata_normalization,
     yaml_metadata_opening_blank_not_metadata,
+    yaml_metadata_panache_options,
 );
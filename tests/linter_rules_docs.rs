@@ -15,14 +15,24 @@ const DOC: &str = include_str!("../docs/reference/linter-rules.qmd");
 /// The body of the `## Rules` section: everything between the `## Rules`
 /// heading and the next top-level section (`## YAML diagnostics`). The YAML
 /// codes are emitted by the parser, not the rule registry, so they live in
-/// their own section and are out of scope here.
+/// their own section and are out of scope here. `## ` lines inside fenced
+/// example blocks are not section headings.
 fn rules_section() -> &'static str {
     let start = DOC
         .find("\n## Rules\n")
         .expect("docs must have a `## Rules` section");
     let after = &DOC[start + "\n## Rules\n".len()..];
+    let mut in_fence = false;
     let end = after
-        .find("\n## ")
+        .match_indices('\n')
+        .map(|(nl, _)| nl)
+        .find(|&nl| {
+            let line = &after[nl + 1..];
+            if line.starts_with("```") {
+                in_fence = !in_fence;
+            }
+            !in_fence && line.starts_with("## ")
+        })
         .expect("`## Rules` must be followed by another `## ` section");
     &after[..end]
 }
@@ -92,6 +102,7 @@ fn requirement_token(req: Requirement) -> Option<&'static str> {
         Requirement::FencedCodeAttributes => Some("fenced-code-attributes"),
         Requirement::FencedDivs => Some("fenced-divs"),
         Requirement::Emoji => Some("emoji"),
+        Requirement::AutolinkBareUris => Some("autolink-bare-uris"),
         Requirement::TexMath => Some("tex-math"),
         Requirement::ChunkFlavor => Some("chunk"),
         Requirement::Quarto => Some("quarto"),
@@ -112,8 +123,12 @@ fn every_rule_is_documented_and_nothing_extra() {
         .iter()
         .map(|m| m.name.to_string())
         .collect();
-    let doc_names: std::collections::BTreeSet<String> =
-        rule_chunks().into_iter().map(|(name, _)| name).collect();
+    // `spelling` is only compiled in with the `spellcheck` feature.
+    let doc_names: std::collections::BTreeSet<String> = rule_chunks()
+        .into_iter()
+        .map(|(name, _)| name)
+        .filter(|name| cfg!(feature = "spellcheck") || name != "spelling")
+        .collect();
 
     let missing: Vec<_> = meta_names.difference(&doc_names).collect();
     assert!(
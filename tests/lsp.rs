@@ -40,4 +40,5 @@ mod lsp {
     pub(super) mod test_rename;
     pub(super) mod test_semantic_tokens;
     pub(super) mod test_workspace_folders;
+    pub(super) mod test_workspace_links;
 }
@@ -40,3 +40,30 @@ pub fn incremental_change(
         text: text.to_string(),
     }
 }
+
+/// Apply formatting `edits` (non-overlapping, in document order) to `text`.
+pub fn apply_edits(text: &str, edits: &[TextEdit]) -> String {
+    let offset = |position: Position| {
+        let line_start: usize = text
+            .split_inclusive('\n')
+            .take(position.line as usize)
+            .map(str::len)
+            .sum();
+        let mut units = 0;
+        let column = text[line_start..]
+            .char_indices()
+            .find(|(_, c)| {
+                let reached = units >= position.character as usize;
+                units += c.len_utf16();
+                reached
+            })
+            .map_or(text.len() - line_start, |(idx, _)| idx);
+        line_start + column
+    };
+    let mut result = text.to_string();
+    for edit in edits.iter().rev() {
+        let range = offset(edit.range.start)..offset(edit.range.end);
+        result.replace_range(range, &edit.new_text);
+    }
+    result
+}
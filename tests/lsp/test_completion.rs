@@ -880,3 +880,73 @@ fn test_resolve_completion_item_ignores_non_citation_items() {
     assert_eq!(resolved.documentation, None);
     assert_eq!(resolved.label, plain.label);
 }
+
+#[test]
+fn test_completion_reference_link_labels() {
+    let mut server = TestLspServer::new();
+
+    let content = "See [the docs][Do] and [x][].\n\n[Docs]: https://example.com/docs\n[Other]: https://example.com/other\n[^note]: Footnote.\n";
+    server.open_document("file:///test.md", content, "markdown");
+
+    // Cursor after `[the docs][Do`.
+    let result = server.completion("file:///test.md", 0, 17);
+    let Some(CompletionResponse::Array(items)) = result else {
+        panic!("Expected reference label completion items");
+    };
+    let labels: Vec<&str> = items.iter().map(|item| item.label.as_str()).collect();
+    assert_eq!(labels, vec!["Docs"]);
+    assert_eq!(items[0].detail.as_deref(), Some("https://example.com/docs"));
+
+    // Empty label `[x][]` lists every definition, but not footnotes.
+    let result = server.completion("file:///test.md", 0, 27);
+    let Some(CompletionResponse::Array(items)) = result else {
+        panic!("Expected reference label completion items");
+    };
+    let labels: Vec<&str> = items.iter().map(|item| item.label.as_str()).collect();
+    assert_eq!(labels, vec!["Docs", "Other"]);
+}
+
+#[test]
+fn test_completion_no_reference_labels_outside_full_reference() {
+    let mut server = TestLspServer::new();
+
+    let content = "Plain [bracket\n\n[Docs]: https://example.com/docs\n";
+    server.open_document("file:///test.md", content, "markdown");
+
+    assert!(server.completion("file:///test.md", 0, 10).is_none());
+}
+
+#[test]
+fn test_completion_crossrefs_from_included_documents() {
+    let mut server = TestLspServer::new();
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+
+    fs::write(
+        root.join("_child.qmd"),
+        "![Plot](plot.png){#fig-child}\n\n| a |\n|---|\n| 1 |\n\n: Table {#tbl-child}\n",
+    )
+    .unwrap();
+    let doc_path = root.join("doc.qmd");
+    let content = "{{< include _child.qmd >}}\n\nSee @fig\n";
+    fs::write(&doc_path, content).unwrap();
+
+    let root_uri = Uri::from_file_path(root).expect("temp dir should be absolute");
+    server.initialize(root_uri.as_str());
+    let doc_uri = Uri::from_file_path(&doc_path).expect("doc uri");
+    server.open_document(doc_uri.as_str(), content, "quarto");
+
+    let result = server.completion(doc_uri.as_str(), 2, 8);
+    let Some(CompletionResponse::Array(items)) = result else {
+        panic!("Expected crossref completion items");
+    };
+    let item = items
+        .iter()
+        .find(|item| item.label == "fig-child")
+        .expect("Expected crossref label from included document");
+    assert_eq!(item.detail.as_deref(), Some("_child.qmd"));
+    assert!(
+        !items.iter().any(|item| item.label == "tbl-child"),
+        "Query `fig` should filter out table labels"
+    );
+}
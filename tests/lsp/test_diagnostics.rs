@@ -395,7 +395,7 @@ fn test_code_actions_offer_source_fix_all_for_fixable_diagnostics() {
 
     let fix_all = code_actions.iter().find_map(|action| {
         if let CodeActionOrCommand::CodeAction(ca) = action
-            && ca.kind == Some(CodeActionKind::SOURCE_FIX_ALL)
+            && ca.kind == Some(CodeActionKind::new("source.fixAll.panache"))
         {
             return Some(ca);
         }
@@ -415,6 +415,48 @@ fn test_code_actions_offer_source_fix_all_for_fixable_diagnostics() {
     );
 }
 
+#[test]
+fn test_code_actions_only_source_fix_all_filters_other_kinds() {
+    let mut server = TestLspServer::new();
+    let content = "# Heading 1\n\n### Heading 3\n\n- a\n- b\n";
+    server.open_document("file:///test.qmd", content, "quarto");
+
+    // Fix-on-save clients request the generic `source.fixAll` kind; the
+    // panache-specific sub-kind must match it, and nothing else may leak in.
+    let code_actions = server
+        .get_code_actions_only(
+            "file:///test.qmd",
+            4,
+            2,
+            4,
+            2,
+            Some(vec![CodeActionKind::SOURCE_FIX_ALL]),
+        )
+        .expect("code actions response");
+
+    assert_eq!(code_actions.len(), 1, "got: {code_actions:?}");
+    let CodeActionOrCommand::CodeAction(ca) = &code_actions[0] else {
+        panic!("expected a code action");
+    };
+    assert_eq!(ca.kind, Some(CodeActionKind::new("source.fixAll.panache")));
+
+    let quickfixes = server
+        .get_code_actions_only(
+            "file:///test.qmd",
+            2,
+            0,
+            2,
+            0,
+            Some(vec![CodeActionKind::QUICKFIX]),
+        )
+        .expect("code actions response");
+    assert!(!quickfixes.is_empty());
+    assert!(quickfixes.iter().all(|action| matches!(
+        action,
+        CodeActionOrCommand::CodeAction(ca) if ca.kind == Some(CodeActionKind::QUICKFIX)
+    )));
+}
+
 /// Saving a document in a multi-file include graph drives
 /// `relint_with_dependents`, which reads `project_graph` (and the lint plan) on
 /// *cloned* salsa handles with the global lock released, then performs
@@ -469,7 +511,7 @@ fn test_code_actions_do_not_offer_source_fix_all_without_fixes() {
 
     let has_fix_all = code_actions.iter().any(|action| {
         if let CodeActionOrCommand::CodeAction(ca) = action {
-            ca.kind == Some(CodeActionKind::SOURCE_FIX_ALL)
+            ca.kind == Some(CodeActionKind::new("source.fixAll.panache"))
         } else {
             false
         }
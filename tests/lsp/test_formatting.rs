@@ -1,7 +1,7 @@
 //! Tests for formatting workflows.
 
 use super::helpers::*;
-use lsp_types::Uri;
+use lsp_types::{Position, Uri};
 use std::fs;
 use tempfile::TempDir;
 
@@ -234,10 +234,9 @@ fn test_format_document_with_umlauts_frontmatter() {
 
     let edits = server.format_document("file:///umlauts.qmd");
     if let Some(edits) = edits {
-        assert_eq!(edits.len(), 1);
-        let new_text = &edits[0].new_text;
-        assert!(new_text.contains("smörgås"));
-        assert!(new_text.contains("åäö"));
+        let formatted = apply_edits(content, &edits);
+        assert!(formatted.contains("smörgås"));
+        assert!(formatted.contains("åäö"));
     }
 }
 
@@ -250,9 +249,44 @@ fn test_format_document_normalizes_yaml_frontmatter_with_builtin_engine() {
 
     let edits = server.format_document("file:///frontmatter.qmd");
     assert!(edits.is_some());
-    let edit = &edits.unwrap()[0];
-    assert!(edit.new_text.contains("\necho: false\n"));
-    assert!(edit.new_text.contains("\nlist:\n  - a\n  - b\n"));
+    let formatted = apply_edits(content, &edits.unwrap());
+    assert!(formatted.contains("\necho: false\n"));
+    assert!(formatted.contains("\nlist:\n  - a\n  - b\n"));
+}
+
+#[test]
+fn test_format_document_edits_only_changed_lines() {
+    let mut server = TestLspServer::new();
+    let content = "#   Heading\n\nShort paragraph.\n\n-   item\n";
+    server.open_document("file:///minimal.qmd", content, "quarto");
+
+    let edits = server.format_document("file:///minimal.qmd").unwrap();
+    // Only the surplus spaces after each marker are removed.
+    assert_eq!(edits.len(), 2);
+    assert_eq!(edits[0].range.start, Position::new(0, 2));
+    assert_eq!(edits[0].range.end, Position::new(0, 4));
+    assert_eq!(edits[0].new_text, "");
+    assert_eq!(edits[1].range.start, Position::new(4, 2));
+    assert_eq!(
+        apply_edits(content, &edits),
+        "# Heading\n\nShort paragraph.\n\n- item\n"
+    );
+}
+
+#[test]
+fn test_ranges_formatting_formats_only_the_selected_blocks() {
+    let mut server = TestLspServer::new();
+    let content = "#   One\n\nkeep   this\n\n#   Two\n";
+    server.open_document("file:///ranges.qmd", content, "quarto");
+
+    let edits = server
+        .format_ranges("file:///ranges.qmd", &[(4, 0, 4, 0), (0, 0, 0, 3)])
+        .unwrap();
+    assert_eq!(edits.len(), 2);
+    assert_eq!(
+        apply_edits(content, &edits),
+        "# One\n\nkeep   this\n\n# Two\n"
+    );
 }
 
 #[test]
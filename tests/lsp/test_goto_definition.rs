@@ -108,6 +108,21 @@ fn test_goto_reference_definition() {
     }
 }
 
+#[test]
+fn test_goto_shortcut_and_collapsed_reference_definition() {
+    let mut server = TestLspServer::new();
+    let content = "See [Docs] and [docs][].\n\n[docs]: https://example.com\n";
+    server.open_document("file:///test.md", content, "markdown");
+
+    for character in [6, 17] {
+        let result = server.goto_definition("file:///test.md", 0, character);
+        let Some(GotoDefinitionResponse::Scalar(location)) = result else {
+            panic!("Expected definition for reference at character {character}");
+        };
+        assert_eq!(location.range.start.line, 2);
+    }
+}
+
 #[test]
 fn test_goto_footnote_definition() {
     let mut server = TestLspServer::new();
@@ -416,18 +416,47 @@ fn test_hover_on_reference_link_definition_to_heading_shows_section_preview() {
 }
 
 #[test]
-fn test_hover_on_reference_link_definition_to_non_heading_returns_none() {
+fn test_hover_on_reference_link_definition_to_url_shows_url() {
     let mut server = TestLspServer::new();
     let content = "# Intro {#bar}\n\nSection body here.\n\nSee [foo][myref].\n\n[myref]: https://example.com\n";
     server.open_document("file:///test.md", content, "markdown");
 
     let hover = server.hover("file:///test.md", 4, 11);
+    let Some(h) = hover else {
+        panic!("Expected URL hover for reference link");
+    };
+    let content = match h.contents {
+        HoverContents::Markup(markup) => markup.value,
+        _ => panic!("Expected markdown hover content"),
+    };
+    assert_eq!(content, "`https://example.com`");
     assert!(
-        hover.is_none(),
+        !content.contains("Section body here."),
         "Non-heading reference definitions should not produce section preview hover"
     );
 }
 
+#[test]
+fn test_hover_on_reference_link_shows_url_and_title() {
+    let mut server = TestLspServer::new();
+    let content = "See [docs].\n\n[docs]: <https://example.com/docs> \"The docs\"\n";
+    server.open_document("file:///test.md", content, "markdown");
+
+    let hover = server
+        .hover("file:///test.md", 0, 6)
+        .expect("reference hover");
+    let content = match hover.contents {
+        HoverContents::Markup(markup) => markup.value,
+        _ => panic!("Expected markdown hover content"),
+    };
+    assert_eq!(content, "`https://example.com/docs`\n\nThe docs");
+
+    assert!(
+        server.hover("file:///test.md", 2, 2).is_none(),
+        "Hovering the definition itself should not repeat its URL"
+    );
+}
+
 #[test]
 fn test_hover_on_equation_reference_shows_equation_preview() {
     let mut server = TestLspServer::new();
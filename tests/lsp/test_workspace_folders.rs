@@ -11,12 +11,15 @@ use tempfile::TempDir;
 /// `line-width` config actually applied by inspecting the formatted line length.
 const LONG: &str = "alpha beta gamma delta epsilon zeta eta theta iota kappa lambda mu nu xi";
 
+/// Longest line of [`LONG`] once the formatting `edits` are applied. The
+/// edits only cover the changed hunks, so they are applied to the document
+/// rather than measured on their own.
 fn max_line_len(edits: &[lsp_types::TextEdit]) -> usize {
-    let new_text = edits
-        .iter()
-        .map(|e| e.new_text.as_str())
-        .collect::<String>();
-    new_text.lines().map(str::len).max().unwrap_or(0)
+    apply_edits(LONG, edits)
+        .lines()
+        .map(str::len)
+        .max()
+        .unwrap_or(0)
 }
 
 /// Build a git-anchored workspace folder with its own `panache.toml` and one
@@ -53,13 +56,12 @@ fn multi_root_resolves_config_per_folder() {
     server.open_document(doc_b.as_str(), LONG, "quarto");
 
     // Folder A: line-width = 80, so the paragraph fits on one line (no wrap).
-    let edits_a = server.format_document(doc_a.as_str());
-    if let Some(edits) = edits_a {
-        assert!(
-            max_line_len(&edits) > 40,
-            "folder A (line-width=80) should not wrap at 40"
-        );
-    }
+    let edits_a = server.format_document(doc_a.as_str()).unwrap_or_default();
+    assert_eq!(
+        apply_edits(LONG, &edits_a).trim_end(),
+        LONG,
+        "folder A (line-width=80) should not wrap at 40"
+    );
 
     // Folder B: line-width = 40, so the paragraph wraps.
     let edits_b = server
@@ -0,0 +1,170 @@
+//! Tests for workspace-wide `broken-link` and `broken-wikilink` diagnostics.
+
+use super::helpers::*;
+use lsp_types::*;
+use std::fs;
+use std::time::Duration;
+use tempfile::TempDir;
+
+fn messages_with_code(
+    publishes: &[PublishDiagnosticsParams],
+    uri: &Uri,
+    code: &str,
+) -> Vec<String> {
+    publishes
+        .iter()
+        .rfind(|publish| &publish.uri == uri)
+        .map(|publish| {
+            publish
+                .diagnostics
+                .iter()
+                .filter(|diag| diag.code == Some(NumberOrString::String(code.to_string())))
+                .map(|diag| diag.message.clone())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn broken_link_messages(publishes: &[PublishDiagnosticsParams], uri: &Uri) -> Vec<String> {
+    messages_with_code(publishes, uri, "broken-link")
+}
+
+fn start(root: &std::path::Path) -> TestLspServer {
+    let mut server = TestLspServer::new();
+    server.initialize(&Uri::from_file_path(root).unwrap().to_string());
+    server.initialized();
+    server.pump(Duration::from_secs(2));
+    server
+}
+
+#[test]
+fn test_broken_file_link_in_open_document() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().canonicalize().unwrap();
+    let doc_path = root.join("index.qmd");
+    let content = "See [ok](other.qmd) and [missing](nope.qmd).\n";
+    fs::write(&doc_path, content).unwrap();
+    fs::write(root.join("other.qmd"), "# Other\n").unwrap();
+
+    let mut server = start(&root);
+    let doc_uri = Uri::from_file_path(&doc_path).unwrap();
+    server.open_document(&doc_uri.to_string(), content, "quarto");
+    server.pump(Duration::from_secs(2));
+
+    let publishes = server.drain_all_publish_diagnostics();
+    assert_eq!(
+        broken_link_messages(&publishes, &doc_uri),
+        vec!["Link target 'nope.qmd' does not exist".to_string()]
+    );
+}
+
+#[test]
+fn test_missing_anchor_in_other_document() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().canonicalize().unwrap();
+    fs::create_dir(root.join("chapters")).unwrap();
+    fs::write(
+        root.join("chapters/intro.qmd"),
+        "# Introduction {#sec-intro}\n\n## Background\n",
+    )
+    .unwrap();
+    let doc_path = root.join("index.qmd");
+    let content = "[a](chapters/intro.qmd#sec-intro) [b](chapters/intro.qmd#background) \
+                   [c](chapters/intro.qmd#sec-missing)\n";
+    fs::write(&doc_path, content).unwrap();
+
+    let mut server = start(&root);
+    let doc_uri = Uri::from_file_path(&doc_path).unwrap();
+    server.open_document(&doc_uri.to_string(), content, "quarto");
+    server.pump(Duration::from_secs(2));
+
+    let publishes = server.drain_all_publish_diagnostics();
+    assert_eq!(
+        broken_link_messages(&publishes, &doc_uri),
+        vec!["Anchor '#sec-missing' not found in 'intro.qmd'".to_string()]
+    );
+}
+
+#[test]
+fn test_closed_document_reported_and_gitignored_skipped() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().canonicalize().unwrap();
+    // `ignore` only honors `.gitignore` inside a git repository.
+    fs::create_dir(root.join(".git")).unwrap();
+    fs::write(root.join(".gitignore"), "_build/\n").unwrap();
+    fs::create_dir(root.join("_build")).unwrap();
+    fs::write(root.join("_build/out.md"), "[x](gone.md)\n").unwrap();
+    let closed_path = root.join("closed.md");
+    fs::write(&closed_path, "[x](gone.md)\n").unwrap();
+
+    let mut server = start(&root);
+    let publishes = server.drain_all_publish_diagnostics();
+    let closed_uri = Uri::from_file_path(&closed_path).unwrap();
+    assert_eq!(
+        broken_link_messages(&publishes, &closed_uri),
+        vec!["Link target 'gone.md' does not exist".to_string()]
+    );
+    let ignored_uri = Uri::from_file_path(root.join("_build/out.md")).unwrap();
+    assert!(
+        !publishes.iter().any(|publish| publish.uri == ignored_uri),
+        "gitignored documents must not be scanned: {publishes:?}"
+    );
+
+    // Creating the target clears the diagnostic after the watcher rescan.
+    let target = root.join("gone.md");
+    fs::write(&target, "# Gone\n").unwrap();
+    server.did_change_watched_files(vec![FileEvent {
+        uri: Uri::from_file_path(&target).unwrap(),
+        typ: FileChangeType::CREATED,
+    }]);
+    server.pump(Duration::from_secs(2));
+    let publishes = server.drain_all_publish_diagnostics();
+    assert!(broken_link_messages(&publishes, &closed_uri).is_empty());
+}
+
+#[test]
+fn test_broken_link_rule_can_be_disabled() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().canonicalize().unwrap();
+    fs::write(
+        root.join("panache.toml"),
+        "[lint.rules]\nbroken-link = false\n",
+    )
+    .unwrap();
+    let doc_path = root.join("index.qmd");
+    let content = "[missing](nope.qmd)\n";
+    fs::write(&doc_path, content).unwrap();
+
+    let mut server = start(&root);
+    let doc_uri = Uri::from_file_path(&doc_path).unwrap();
+    server.open_document(&doc_uri.to_string(), content, "quarto");
+    server.pump(Duration::from_secs(2));
+
+    let publishes = server.drain_all_publish_diagnostics();
+    assert!(broken_link_messages(&publishes, &doc_uri).is_empty());
+}
+
+#[test]
+fn test_broken_wikilink_targets() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().canonicalize().unwrap();
+    fs::write(root.join("panache.toml"), "flavor = \"obsidian\"\n").unwrap();
+    fs::create_dir_all(root.join("notes/daily")).unwrap();
+    fs::write(root.join("notes/daily/Standup.md"), "# Standup\n").unwrap();
+    fs::write(root.join("diagram.png"), "").unwrap();
+    let doc_path = root.join("index.md");
+    let content = "[[standup]] [[daily/Standup#Notes|notes]] ![[diagram.png]] \
+                   [[#Local]] [[Missing page|gone]]\n";
+    fs::write(&doc_path, content).unwrap();
+
+    let mut server = start(&root);
+    let doc_uri = Uri::from_file_path(&doc_path).unwrap();
+    server.open_document(&doc_uri.to_string(), content, "markdown");
+    server.pump(Duration::from_secs(2));
+
+    let publishes = server.drain_all_publish_diagnostics();
+    assert_eq!(
+        messages_with_code(&publishes, &doc_uri, "broken-wikilink"),
+        vec!["Wikilink target 'Missing page' not found in workspace".to_string()]
+    );
+}